@@ -191,6 +191,15 @@ fn instantiate_address(code_id: u64, instance_id: u64) -> CanonicalAddr {
         .into()
 }
 
+/// Returns the address of the module account with specified name, like `distribution`.
+///
+/// Address is generated using the same algorithm as `NewModuleAddress` in Cosmos SDK,
+/// i.e. the first 20 bytes of the SHA-256 digest of the module name.
+pub(crate) fn module_address(api: &dyn Api, name: &str) -> AnyResult<Addr> {
+    let canonical_addr = CanonicalAddr::from(&Sha256::digest(name.as_bytes())[..20]);
+    Ok(api.addr_humanize(&canonical_addr)?)
+}
+
 /// Default contract address generator used in [WasmKeeper](crate::WasmKeeper).
 pub struct SimpleAddressGenerator;
 
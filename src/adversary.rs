@@ -0,0 +1,139 @@
+//! # Adversarial counterparty contract
+//!
+//! [Adversary] is a contract misbehaving on purpose, so protocols can test their resilience
+//! against malicious counterparties: a contract calling an adversary with a submessage
+//! must survive whatever ends up in its `reply` handler. Every message executed
+//! on the adversary (including `sudo` and `reply` calls) succeeds, but performs
+//! all configured attacks:
+//!
+//! - burns the simulated gas by writing junk values to the adversary's storage,
+//! - returns huge data in the response,
+//! - emits thousands of events.
+//!
+//! The adversary is stored and instantiated with a single call
+//! to [instantiate_adversary](crate::App::instantiate_adversary).
+
+use crate::contracts::Contract;
+use crate::error::AnyResult;
+use cosmwasm_std::{
+    to_json_binary, Binary, CustomMsg, CustomQuery, Deps, DepsMut, Empty, Env, Event, MessageInfo,
+    Reply, Response, Storage,
+};
+
+/// Prefix of the keys written to the adversary's storage to burn the simulated gas.
+const GAS_BURNING_PREFIX: &[u8] = b"__adversary_gas";
+
+/// Contract performing configured attacks on every executed message.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::Empty;
+/// use cw_multi_test::{Adversary, App, Executor};
+///
+/// let mut app = App::default();
+/// let adversary_addr = app
+///     .instantiate_adversary(Adversary::new().returning_data(1_000_000).emitting_events(5000))
+///     .unwrap();
+///
+/// let sender = app.api().addr_make("sender");
+/// let res = app
+///     .execute_contract(sender, adversary_addr, &Empty {}, &[])
+///     .unwrap();
+/// assert_eq!(1_000_000, res.data.unwrap().len());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Adversary {
+    gas_burning_writes: u32,
+    data_size: Option<usize>,
+    events: u32,
+}
+
+impl Adversary {
+    /// Creates an adversary performing no attacks, it just accepts every message.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Burns the simulated gas by writing the specified number of 1KiB values to storage
+    /// on every executed message.
+    pub fn burning_gas(mut self, writes: u32) -> Self {
+        self.gas_burning_writes = writes;
+        self
+    }
+
+    /// Returns data of the specified size (in bytes) in every response.
+    pub fn returning_data(mut self, size: usize) -> Self {
+        self.data_size = Some(size);
+        self
+    }
+
+    /// Emits the specified number of events in every response.
+    pub fn emitting_events(mut self, count: u32) -> Self {
+        self.events = count;
+        self
+    }
+
+    /// Performs all configured attacks, returns the malicious response.
+    fn attack<C>(&self, storage: &mut dyn Storage) -> Response<C>
+    where
+        C: CustomMsg,
+    {
+        let junk = vec![0xff; 1024];
+        for index in 0..self.gas_burning_writes {
+            let key = [GAS_BURNING_PREFIX, &index.to_be_bytes()].concat();
+            storage.set(&key, &junk);
+        }
+        let mut response =
+            Response::new()
+                .add_events((0..self.events).map(|index| {
+                    Event::new("adversary").add_attribute("index", index.to_string())
+                }));
+        if let Some(size) = self.data_size {
+            response = response.set_data(vec![0xff; size]);
+        }
+        response
+    }
+}
+
+impl<C, Q> Contract<C, Q> for Adversary
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    fn execute(
+        &self,
+        deps: DepsMut<Q>,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        Ok(self.attack(deps.storage))
+    }
+
+    fn instantiate(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        Ok(Response::default())
+    }
+
+    fn query(&self, _deps: Deps<Q>, _env: Env, _msg: Vec<u8>) -> AnyResult<Binary> {
+        Ok(to_json_binary(&Empty {})?)
+    }
+
+    fn sudo(&self, deps: DepsMut<Q>, _env: Env, _msg: Vec<u8>) -> AnyResult<Response<C>> {
+        Ok(self.attack(deps.storage))
+    }
+
+    fn reply(&self, deps: DepsMut<Q>, _env: Env, _msg: Reply) -> AnyResult<Response<C>> {
+        Ok(self.attack(deps.storage))
+    }
+
+    fn migrate(&self, _deps: DepsMut<Q>, _env: Env, _msg: Vec<u8>) -> AnyResult<Response<C>> {
+        Ok(Response::default())
+    }
+}
@@ -5,6 +5,7 @@ use cosmwasm_std::{
     Addr, Api, CanonicalAddr, RecoverPubkeyError, StdError, StdResult, VerificationError,
 };
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 
 pub struct MockApiBech<T> {
     api: MockApi,
@@ -117,3 +118,172 @@ pub type MockApiBech32 = MockApiBech<Bech32>;
 ///
 /// [Bech32m]: https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki
 pub type MockApiBech32m = MockApiBech<Bech32m>;
+
+/// Implementation of the `cosmwasm_std::Api` trait with address canonicalization
+/// and humanization specified by test vectors.
+///
+/// Address-handling edge cases reported from specific chains can be reproduced exactly,
+/// by declaring the canonical form of the human-readable address, or the error returned
+/// for malformed inputs. Inputs not covered by any test vector are handled by the wrapped
+/// `Api` implementation (`MockApi` by default), cryptographic functions are always delegated.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{Api, CanonicalAddr};
+/// use cw_multi_test::{no_init, AppBuilder, MockApiVectors};
+///
+/// let api = MockApiVectors::new()
+///     .with_address("Chain1ABC", CanonicalAddr::from(b"abc".as_slice()))
+///     .with_canonicalize_error("chain1abc", "address must be upper case");
+/// let app = AppBuilder::default().with_api(api).build(no_init);
+///
+/// assert_eq!(b"abc", app.api().addr_canonicalize("Chain1ABC").unwrap().as_slice());
+/// assert_eq!(
+///     "Generic error: address must be upper case",
+///     app.api().addr_validate("chain1abc").unwrap_err().to_string()
+/// );
+/// ```
+pub struct MockApiVectors<A = MockApi> {
+    api: A,
+    canonicalize: BTreeMap<String, Result<CanonicalAddr, String>>,
+    humanize: BTreeMap<Vec<u8>, Result<String, String>>,
+}
+
+impl MockApiVectors {
+    /// Returns `Api` implementation handling inputs not covered by test vectors with `MockApi`.
+    pub fn new() -> Self {
+        Self::with_fallback(MockApi::default())
+    }
+}
+
+impl Default for MockApiVectors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Api> MockApiVectors<A> {
+    /// Returns `Api` implementation handling inputs not covered by test vectors
+    /// with the specified `Api` implementation.
+    pub fn with_fallback(api: A) -> Self {
+        Self {
+            api,
+            canonicalize: BTreeMap::new(),
+            humanize: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the test vector converting the human-readable address to the canonical address
+    /// and vice versa.
+    pub fn with_address(mut self, human: impl Into<String>, canonical: CanonicalAddr) -> Self {
+        let human = human.into();
+        self.canonicalize
+            .insert(human.clone(), Ok(canonical.clone()));
+        self.humanize.insert(canonical.to_vec(), Ok(human));
+        self
+    }
+
+    /// Adds the test vector converting the human-readable address to the canonical address,
+    /// without the reverse conversion, like for addresses in non-normalized form.
+    pub fn with_canonicalized(
+        mut self,
+        human: impl Into<String>,
+        canonical: CanonicalAddr,
+    ) -> Self {
+        self.canonicalize.insert(human.into(), Ok(canonical));
+        self
+    }
+
+    /// Adds the test vector for the human-readable address that can not be canonicalized.
+    pub fn with_canonicalize_error(
+        mut self,
+        human: impl Into<String>,
+        error: impl Into<String>,
+    ) -> Self {
+        self.canonicalize.insert(human.into(), Err(error.into()));
+        self
+    }
+
+    /// Adds the test vector for the canonical address that can not be humanized.
+    pub fn with_humanize_error(
+        mut self,
+        canonical: CanonicalAddr,
+        error: impl Into<String>,
+    ) -> Self {
+        self.humanize.insert(canonical.to_vec(), Err(error.into()));
+        self
+    }
+}
+
+impl<A: Api> Api for MockApiVectors<A> {
+    fn addr_validate(&self, input: &str) -> StdResult<Addr> {
+        let canonical = self.addr_canonicalize(input)?;
+        let normalized = self.addr_humanize(&canonical)?;
+        if input != normalized.as_str() {
+            return Err(StdError::generic_err(
+                "Invalid input: address not normalized",
+            ));
+        }
+        Ok(normalized)
+    }
+
+    fn addr_canonicalize(&self, input: &str) -> StdResult<CanonicalAddr> {
+        match self.canonicalize.get(input) {
+            Some(Ok(canonical)) => Ok(canonical.clone()),
+            Some(Err(error)) => Err(StdError::generic_err(error)),
+            None => self.api.addr_canonicalize(input),
+        }
+    }
+
+    fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+        match self.humanize.get(canonical.as_slice()) {
+            Some(Ok(human)) => Ok(Addr::unchecked(human)),
+            Some(Err(error)) => Err(StdError::generic_err(error)),
+            None => self.api.addr_humanize(canonical),
+        }
+    }
+
+    fn secp256k1_verify(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.api
+            .secp256k1_verify(message_hash, signature, public_key)
+    }
+
+    fn secp256k1_recover_pubkey(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        recovery_param: u8,
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        self.api
+            .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+    }
+
+    fn ed25519_verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.api.ed25519_verify(message, signature, public_key)
+    }
+
+    fn ed25519_batch_verify(
+        &self,
+        messages: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        self.api
+            .ed25519_batch_verify(messages, signatures, public_keys)
+    }
+
+    fn debug(&self, message: &str) {
+        self.api.debug(message)
+    }
+}
@@ -1,23 +1,61 @@
-use crate::bank::{Bank, BankKeeper, BankSudo};
+use crate::addresses::module_address;
+use crate::adversary::Adversary;
+use crate::bank::{
+    ensure_msg_send_enabled, is_bank_grpc_query, query_bank_grpc, Bank, BankKeeper, BankSudo,
+    MultiSendIo,
+};
+use crate::budget::{measure_gas, BudgetMeter, ResourceBudget, ResourceUsage};
 use crate::contracts::Contract;
-use crate::error::{bail, AnyResult};
+use crate::cron::{
+    add_schedule, remove_schedule, reschedule, save_schedule, schedule, schedules, ExecutionStage,
+    FailurePolicy, Schedule, CRON_MODULE,
+};
+use crate::denom_traces::{denom_trace, register_denom_trace, DenomTrace};
+use crate::error::{anyhow, bail, AnyResult};
 use crate::executor::{AppResponse, Executor};
-use crate::gov::Gov;
+use crate::features::{is_feature_enabled, set_feature, ExperimentalFeature};
+use crate::gov::{is_gov_grpc_query, query_gov_grpc, Gov, GovSudo, SubmitProposal};
 use crate::ibc::Ibc;
+use crate::ibc_channels::{
+    channel_close_confirm, channel_close_init, channel_end, channel_open_ack, channel_open_confirm,
+    channel_open_init, channel_open_try, ChannelEnd,
+};
+use crate::ibc_localhost::{open_channel, relay_packet};
+use crate::invariants::InvariantChecker;
+use crate::matchers::{funds_to_string, FundsMatcher};
+use crate::mint::{is_mint_grpc_query, query_mint_grpc, Mint, MintKeeper, MintSudo};
 use crate::module::{FailingModule, Module};
+use crate::multisig::{
+    admin_msg_approvals, approve_admin_msg, register_multisig_admin, MultisigAdmin,
+};
+use crate::param_history::{param_history, record_param_change, ParamChange};
+use crate::pause::{ensure_msg_not_paused, pause_sudo, PauseSudo};
 use crate::prefixed_storage::{
     prefixed, prefixed_multilevel, prefixed_multilevel_read, prefixed_read,
 };
-use crate::staking::{Distribution, DistributionKeeper, StakeKeeper, Staking, StakingSudo};
+use crate::regression::to_rust_test;
+use crate::snapshots::BlockSnapshots;
+#[cfg(feature = "cosmwasm_2_0")]
+use crate::staking::query_distribution;
+use crate::staking::{
+    is_distribution_grpc_query, is_staking_grpc_query, query_distribution_grpc, query_staking_grpc,
+    Distribution, DistributionKeeper, DistributionSudo, LiquidStakingMsg, StakeKeeper, Staking,
+    StakingSudo, ValidatorDescription,
+};
+use crate::trace::{ExecutionTrace, Tracer};
 use crate::transactions::transactional;
+use crate::versions::{code_versions, register_code_version, MigrationMatrix};
 use crate::wasm::{ContractData, Wasm, WasmKeeper, WasmSudo};
 use crate::{AppBuilder, GovFailingModule, IbcFailingModule, Stargate, StargateFailing};
 use cosmwasm_std::testing::{MockApi, MockStorage};
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Api, Binary, BlockInfo, ContractResult, CosmosMsg, CustomMsg,
-    CustomQuery, Empty, Querier, QuerierResult, QuerierWrapper, QueryRequest, Record, Storage,
-    SystemError, SystemResult,
+    from_json, to_json_binary, Addr, Api, Binary, BlockInfo, Coin, ContractResult, CosmosMsg,
+    CustomMsg, CustomQuery, Decimal, Empty, IbcChannel, IbcEndpoint, IbcOrder, IbcTimeout, Querier,
+    QuerierResult, QuerierWrapper, QueryRequest, Record, StakingMsg, Storage, SystemError,
+    SystemResult, Timestamp, Uint128, Validator, WasmMsg,
 };
+use cw_utils::parse_instantiate_response_data;
+use itertools::Itertools;
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt::Debug;
 use std::marker::PhantomData;
@@ -29,6 +67,21 @@ pub fn next_block(block: &mut BlockInfo) {
     block.height += 1;
 }
 
+/// Returns the closure advancing the block to the first one at which the IBC timeout has passed,
+/// to be applied with [App::update_block]. Packets sent with this timeout are returned
+/// to the sending contract instead of being received, when relayed after the update.
+pub fn past_timeout(timeout: &IbcTimeout) -> impl Fn(&mut BlockInfo) {
+    let timeout = timeout.clone();
+    move |block| {
+        if let Some(timeout_block) = timeout.block() {
+            block.height = block.height.max(timeout_block.height);
+        }
+        if let Some(timestamp) = timeout.timestamp() {
+            block.time = block.time.max(timestamp);
+        }
+    }
+}
+
 /// A type alias for the default-built App. It simplifies storage and handling in typical scenarios,
 /// streamlining the use of the App structure in standard test setups.
 pub type BasicApp<ExecC = Empty, QueryC = Empty> = App<
@@ -42,12 +95,12 @@ pub type BasicApp<ExecC = Empty, QueryC = Empty> = App<
     IbcFailingModule,
     GovFailingModule,
     StargateFailing,
+    MintKeeper,
 >;
 
 /// # Blockchain application simulator
 ///
 /// This structure is the main component of the real-life blockchain simulator.
-#[derive(Clone)]
 pub struct App<
     Bank = BankKeeper,
     Api = MockApi,
@@ -59,16 +112,17 @@ pub struct App<
     Ibc = IbcFailingModule,
     Gov = GovFailingModule,
     Stargate = StargateFailing,
+    Mint = MintKeeper,
 > {
-    pub(crate) router: Router<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate>,
+    pub(crate) router: Router<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate, Mint>,
     pub(crate) api: Api,
     pub(crate) storage: Storage,
     pub(crate) block: BlockInfo,
 }
 
 /// No-op application initialization function.
-pub fn no_init<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>(
-    router: &mut Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>,
+pub fn no_init<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>(
+    router: &mut Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>,
     api: &dyn Api,
     storage: &mut dyn Storage,
 ) {
@@ -95,6 +149,7 @@ impl BasicApp {
                 IbcFailingModule,
                 GovFailingModule,
                 StargateFailing,
+                MintKeeper,
             >,
             &dyn Api,
             &mut dyn Storage,
@@ -120,6 +175,7 @@ where
             IbcFailingModule,
             GovFailingModule,
             StargateFailing,
+            MintKeeper,
         >,
         &dyn Api,
         &mut dyn Storage,
@@ -128,8 +184,37 @@ where
     AppBuilder::new_custom().build(init_fn)
 }
 
-impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT> Querier
-    for App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT> Clone
+    for App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
+where
+    BankT: Clone,
+    ApiT: Clone,
+    StorageT: Clone,
+    CustomT: Clone,
+    WasmT: Clone,
+    StakingT: Clone,
+    DistrT: Clone,
+    IbcT: Clone,
+    GovT: Clone,
+    StargateT: Clone,
+    MintT: Clone,
+{
+    /// Clones the application to branch test scenarios from a common setup.
+    ///
+    /// The storage is cloned, while the stored contract codes are shared
+    /// with the original application, so cloning is cheap.
+    fn clone(&self) -> Self {
+        Self {
+            router: self.router.clone(),
+            api: self.api.clone(),
+            storage: self.storage.clone(),
+            block: self.block.clone(),
+        }
+    }
+}
+
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT> Querier
+    for App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
 where
     CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
     CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
@@ -143,6 +228,7 @@ where
     IbcT: Ibc,
     GovT: Gov,
     StargateT: Stargate,
+    MintT: Mint,
 {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
         self.router
@@ -151,9 +237,9 @@ where
     }
 }
 
-impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
     Executor<CustomT::ExecT>
-    for App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+    for App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
 where
     CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
     CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
@@ -167,6 +253,7 @@ where
     IbcT: Ibc,
     GovT: Gov,
     StargateT: Stargate,
+    MintT: Mint,
 {
     fn execute(&mut self, sender: Addr, msg: CosmosMsg<CustomT::ExecT>) -> AnyResult<AppResponse> {
         let mut all = self.execute_multi(sender, vec![msg])?;
@@ -175,8 +262,8 @@ where
     }
 }
 
-impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
-    App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
+    App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
 where
     WasmT: Wasm<CustomT::ExecT, CustomT::QueryT>,
     BankT: Bank,
@@ -188,11 +275,12 @@ where
     IbcT: Ibc,
     GovT: Gov,
     StargateT: Stargate,
+    MintT: Mint,
 {
     /// Returns a shared reference to application's router.
     pub fn router(
         &self,
-    ) -> &Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT> {
+    ) -> &Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT> {
         &self.router
     }
 
@@ -215,7 +303,7 @@ where
     pub fn init_modules<F, T>(&mut self, init_fn: F) -> T
     where
         F: FnOnce(
-            &mut Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>,
+            &mut Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>,
             &dyn Api,
             &mut dyn Storage,
         ) -> T,
@@ -227,7 +315,7 @@ where
     pub fn read_module<F, T>(&self, query_fn: F) -> T
     where
         F: FnOnce(
-            &Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>,
+            &Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>,
             &dyn Api,
             &dyn Storage,
         ) -> T,
@@ -238,8 +326,8 @@ where
 
 // Helper functions to call some custom WasmKeeper logic.
 // They show how we can easily add such calls to other custom keepers (CustomT, StakingT, etc)
-impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
-    App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
+    App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
 where
     BankT: Bank,
     ApiT: Api,
@@ -251,6 +339,7 @@ where
     IbcT: Ibc,
     GovT: Gov,
     StargateT: Stargate,
+    MintT: Mint,
     CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
     CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
 {
@@ -283,6 +372,110 @@ where
         self.router.wasm.store_code_with_id(creator, code_id, code)
     }
 
+    /// Registers contract code (like [store_code](Self::store_code)) as the specified version
+    /// of the contract with the specified logical name. Returns the code identifier.
+    pub fn store_code_version(
+        &mut self,
+        name: &str,
+        version: &str,
+        code: Box<dyn Contract<CustomT::ExecT, CustomT::QueryT>>,
+    ) -> AnyResult<u64> {
+        if code_versions(&self.storage, name)?
+            .iter()
+            .any(|(registered, _)| registered == version)
+        {
+            bail!(
+                "version {} of contract {} is already registered",
+                version,
+                name
+            );
+        }
+        let code_id = self.store_code(code);
+        register_code_version(&mut self.storage, name, version, code_id)?;
+        Ok(code_id)
+    }
+
+    /// Returns registered versions of the contract with their code identifiers,
+    /// in the order of registration.
+    pub fn code_versions(&self, name: &str) -> AnyResult<Vec<(String, u64)>> {
+        code_versions(&self.storage, name)
+    }
+
+    /// Runs the migration matrix for every pair of registered versions of the contract,
+    /// each pair on a separate copy of this application, so the state of this application
+    /// is not changed. Returns the error describing the first failed pair of versions.
+    /// The application must be cloneable, like when built with [CloneableStorage](crate::CloneableStorage).
+    pub fn run_migration_matrix(&self, matrix: &MigrationMatrix<Self>) -> AnyResult<()>
+    where
+        Self: Clone,
+    {
+        let versions = self.code_versions(&matrix.name)?;
+        if versions.len() < 2 {
+            bail!(
+                "at least two versions of contract {} must be registered",
+                matrix.name
+            );
+        }
+        for (index, (from_version, from_code_id)) in versions.iter().enumerate() {
+            for (to_version, to_code_id) in &versions[index + 1..] {
+                self.clone()
+                    .run_migration(matrix, *from_code_id, *to_code_id)
+                    .map_err(|err| {
+                        anyhow!(
+                            "migration of contract {} from version {} to {} failed: {}",
+                            matrix.name,
+                            from_version,
+                            to_version,
+                            err
+                        )
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Instantiates the contract, runs the scenario and migrates the contract,
+    /// checking invariants before and after the migration.
+    fn run_migration(
+        &mut self,
+        matrix: &MigrationMatrix<Self>,
+        from_code_id: u64,
+        to_code_id: u64,
+    ) -> AnyResult<()> {
+        let response = self.execute(
+            matrix.admin.clone(),
+            WasmMsg::Instantiate {
+                admin: Some(matrix.admin.to_string()),
+                code_id: from_code_id,
+                msg: matrix.instantiate_msg.clone(),
+                funds: vec![],
+                label: matrix.name.clone(),
+            }
+            .into(),
+        )?;
+        let data = parse_instantiate_response_data(response.data.unwrap_or_default().as_slice())?;
+        let contract_addr = Addr::unchecked(data.contract_address);
+        if let Some(scenario) = &matrix.scenario {
+            scenario(self, &contract_addr)?;
+        }
+        for invariant in &matrix.invariants {
+            invariant(self, &contract_addr).map_err(|err| anyhow!("before migration: {}", err))?;
+        }
+        self.execute(
+            matrix.admin.clone(),
+            WasmMsg::Migrate {
+                contract_addr: contract_addr.to_string(),
+                new_code_id: to_code_id,
+                msg: matrix.migrate_msg.clone(),
+            }
+            .into(),
+        )?;
+        for invariant in &matrix.invariants {
+            invariant(self, &contract_addr).map_err(|err| anyhow!("after migration: {}", err))?;
+        }
+        Ok(())
+    }
+
     /// Duplicates the contract code identified by `code_id` and returns
     /// the identifier of the newly created copy of the contract code.
     ///
@@ -346,6 +539,112 @@ where
         self.router.wasm.dump_wasm_raw(&self.storage, address)
     }
 
+    /// Returns addresses and [ContractData] of all contract instances, ordered by address.
+    pub fn all_contracts(&self) -> AnyResult<Vec<(Addr, ContractData)>> {
+        self.router.wasm.all_contracts(&self.storage)
+    }
+
+    /// Returns addresses and [ContractData] of contract instances
+    /// with labels starting with specified prefix, ordered by address.
+    pub fn contracts_by_label_prefix(&self, prefix: &str) -> AnyResult<Vec<(Addr, ContractData)>> {
+        Ok(self
+            .all_contracts()?
+            .into_iter()
+            .filter(|(_, contract)| contract.label.starts_with(prefix))
+            .collect())
+    }
+
+    /// Registers a multi-member admin account under specified address.
+    /// Admin-gated wasm operations sent by this account must be approved
+    /// by at least `threshold` members using [approve_admin_msg](Self::approve_admin_msg).
+    pub fn register_multisig_admin(
+        &mut self,
+        address: &Addr,
+        members: Vec<Addr>,
+        threshold: u64,
+    ) -> AnyResult<()> {
+        register_multisig_admin(
+            &mut self.storage,
+            address,
+            MultisigAdmin { members, threshold },
+        )
+    }
+
+    /// Enables the experimental feature for this application.
+    pub fn enable_experimental_feature(&mut self, feature: ExperimentalFeature) -> AnyResult<()> {
+        set_feature(&mut self.storage, feature, true)?;
+        record_param_change(
+            &mut self.storage,
+            self.block.height,
+            "features",
+            feature.name(),
+            true,
+        )
+    }
+
+    /// Disables the experimental feature for this application.
+    pub fn disable_experimental_feature(&mut self, feature: ExperimentalFeature) -> AnyResult<()> {
+        set_feature(&mut self.storage, feature, false)?;
+        record_param_change(
+            &mut self.storage,
+            self.block.height,
+            "features",
+            feature.name(),
+            false,
+        )
+    }
+
+    /// Returns `true` when the experimental feature is enabled for this application.
+    pub fn is_experimental_feature_enabled(&self, feature: ExperimentalFeature) -> bool {
+        is_feature_enabled(&self.storage, feature)
+    }
+
+    /// Returns all changes of chain parameters recorded while the chain was running,
+    /// with heights of blocks in which they took effect, in the order they were made.
+    pub fn param_history(&self) -> AnyResult<Vec<ParamChange>> {
+        param_history(&self.storage)
+    }
+
+    /// Registers the ICS-20 denomination trace, returns the IBC denomination (`ibc/{hash}`)
+    /// representing the token on this chain. Registered traces are answered
+    /// by [StargateDenomTraces](crate::StargateDenomTraces).
+    pub fn register_denom_trace(&mut self, trace: &DenomTrace) -> AnyResult<String> {
+        register_denom_trace(&mut self.storage, trace)
+    }
+
+    /// Returns the denomination trace for specified hash, with or without `ibc/` prefix.
+    pub fn denom_trace(&self, hash: &str) -> AnyResult<Option<DenomTrace>> {
+        denom_trace(&self.storage, hash)
+    }
+
+    /// Approves the admin-gated wasm operation by the member of the multisig admin.
+    pub fn approve_admin_msg(
+        &mut self,
+        multisig_addr: &Addr,
+        member_addr: &Addr,
+        msg: &WasmMsg,
+    ) -> AnyResult<()> {
+        approve_admin_msg(&mut self.storage, multisig_addr, member_addr, msg)
+    }
+
+    /// Returns members of the multisig admin that approved the admin-gated wasm operation.
+    pub fn admin_msg_approvals(&self, multisig_addr: &Addr, msg: &WasmMsg) -> AnyResult<Vec<Addr>> {
+        admin_msg_approvals(&self.storage, multisig_addr, msg)
+    }
+
+    /// Returns the identifier of the IBC port bound to the contract with specified address,
+    /// or `None` when the contract does not expose IBC entry-points.
+    pub fn ibc_port_id(&self, address: &Addr) -> AnyResult<Option<String>> {
+        self.router.wasm.ibc_port_id(&self.storage, address)
+    }
+
+    /// Returns the address of the contract bound to the IBC port with specified identifier.
+    pub fn contract_by_ibc_port_id(&self, port_id: &str) -> AnyResult<Addr> {
+        self.router
+            .wasm
+            .contract_by_ibc_port_id(&self.storage, port_id)
+    }
+
     /// Returns **read-only** storage for a contract with specified address.
     pub fn contract_storage<'a>(&'a self, contract_addr: &Addr) -> Box<dyn Storage + 'a> {
         self.router
@@ -387,8 +686,8 @@ where
     }
 }
 
-impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
-    App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
+    App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
 where
     CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
     CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
@@ -402,23 +701,115 @@ where
     IbcT: Ibc,
     GovT: Gov,
     StargateT: Stargate,
+    MintT: Mint,
 {
     /// Sets the initial block properties.
     pub fn set_block(&mut self, block: BlockInfo) {
+        self.router.meter.count_block();
+        self.router.snapshots.take(&self.storage, &self.block);
+        self.run_schedules(ExecutionStage::EndBlocker);
+        self.router
+            .gov
+            .process_proposals(&self.api, &mut self.storage, &self.router, &self.block)
+            .unwrap();
+        self.router
+            .mint
+            .mint_block_provision(&self.api, &mut self.storage, &self.router, &self.block)
+            .unwrap();
         self.router
             .staking
             .process_queue(&self.api, &mut self.storage, &self.router, &self.block)
             .unwrap();
         self.block = block;
+        self.run_schedules(ExecutionStage::BeginBlocker);
     }
 
     /// Updates the current block applying the specified closure, usually [next_block].
     pub fn update_block<F: Fn(&mut BlockInfo)>(&mut self, action: F) {
+        self.router.meter.count_block();
+        self.router.snapshots.take(&self.storage, &self.block);
+        self.run_schedules(ExecutionStage::EndBlocker);
+        self.router
+            .gov
+            .process_proposals(&self.api, &mut self.storage, &self.router, &self.block)
+            .unwrap();
+        self.router
+            .mint
+            .mint_block_provision(&self.api, &mut self.storage, &self.router, &self.block)
+            .unwrap();
         self.router
             .staking
             .process_queue(&self.api, &mut self.storage, &self.router, &self.block)
             .unwrap();
         action(&mut self.block);
+        self.run_schedules(ExecutionStage::BeginBlocker);
+    }
+
+    /// Registers the schedule executing messages on contracts every `period` blocks,
+    /// counted from the current block.
+    pub fn add_schedule(&mut self, schedule: Schedule) -> AnyResult<()> {
+        add_schedule(&mut self.storage, schedule, self.block.height)
+    }
+
+    /// Cancels the schedule, its messages are no longer executed.
+    pub fn remove_schedule(&mut self, name: &str) -> AnyResult<()> {
+        remove_schedule(&mut self.storage, name)
+    }
+
+    /// Changes the period of the schedule, counted from the current block.
+    /// Schedules halted after a failed execution are resumed.
+    pub fn reschedule(&mut self, name: &str, period: u64) -> AnyResult<()> {
+        reschedule(&mut self.storage, name, period, self.block.height)
+    }
+
+    /// Returns the schedule with specified name, if registered.
+    pub fn schedule(&self, name: &str) -> AnyResult<Option<Schedule>> {
+        schedule(&self.storage, name)
+    }
+
+    /// Returns all registered schedules, ordered by name.
+    pub fn schedules(&self) -> AnyResult<Vec<Schedule>> {
+        schedules(&self.storage)
+    }
+
+    /// Executes all schedules due in the specified stage of the current block.
+    fn run_schedules(&mut self, stage: ExecutionStage) {
+        let sender = module_address(&self.api, CRON_MODULE).unwrap();
+        for mut schedule in schedules(&self.storage).unwrap() {
+            if !schedule.is_due(stage, self.block.height) {
+                continue;
+            }
+            let Self {
+                block,
+                router,
+                api,
+                storage,
+            } = self;
+            let result = transactional(&mut *storage, |write_cache, _| {
+                let (result, gas_used) = measure_gas(write_cache, |storage| {
+                    for scheduled in &schedule.msgs {
+                        let msg = WasmMsg::Execute {
+                            contract_addr: scheduled.contract.to_string(),
+                            msg: scheduled.msg.clone(),
+                            funds: vec![],
+                        };
+                        router.execute(&*api, storage, block, sender.clone(), msg.into())?;
+                    }
+                    Ok(())
+                });
+                if let Some(gas_limit) = schedule.gas_limit.filter(|limit| gas_used > *limit) {
+                    bail!("out of gas: used {}, limit {}", gas_used, gas_limit);
+                }
+                result
+            });
+            schedule.last_execute_height = self.block.height;
+            if let Err(err) = result {
+                schedule.failures += 1;
+                schedule.last_error = Some(err.root_cause().to_string());
+                schedule.halted = schedule.on_failure == FailurePolicy::Halt;
+            }
+            save_schedule(&mut self.storage, &schedule).unwrap();
+        }
     }
 
     /// Returns a copy of the current block_info
@@ -426,6 +817,139 @@ where
         self.block.clone()
     }
 
+    /// Starts recording snapshots of the chain state for at most `max_depth` recent blocks,
+    /// taken every time the block is advanced. Previously recorded snapshots are discarded.
+    pub fn enable_block_snapshots(&mut self, max_depth: usize) {
+        self.router.snapshots.enable(max_depth);
+    }
+
+    /// Stops recording snapshots of the chain state.
+    /// Previously recorded snapshots are discarded.
+    pub fn disable_block_snapshots(&mut self) {
+        self.router.snapshots.disable();
+    }
+
+    /// Returns the number of recent blocks that can be rolled back.
+    pub fn block_snapshots_count(&self) -> usize {
+        self.router.snapshots.len()
+    }
+
+    /// Rewinds the state and the height of the chain by `count` blocks,
+    /// like a short chain reorganization does. All changes made since then are discarded,
+    /// including changes made in the current block.
+    /// Requires block snapshots to be enabled with [enable_block_snapshots](Self::enable_block_snapshots).
+    pub fn rollback_blocks(&mut self, count: usize) -> AnyResult<()> {
+        self.block = self.router.snapshots.rollback(&mut self.storage, count)?;
+        Ok(())
+    }
+
+    /// Starts recording the execution trace of all processed messages.
+    /// Previously recorded trace is discarded.
+    pub fn enable_tracing(&mut self) {
+        self.router.tracer.enable();
+    }
+
+    /// Stops recording the execution trace.
+    /// Previously recorded trace is discarded.
+    pub fn disable_tracing(&mut self) {
+        self.router.tracer.disable();
+    }
+
+    /// Returns a copy of the execution trace recorded so far.
+    /// When tracing is not enabled, an empty trace is returned.
+    pub fn trace(&self) -> ExecutionTrace {
+        self.router.tracer.trace()
+    }
+
+    /// Returns the execution trace recorded so far and starts recording a new one.
+    pub fn take_trace(&mut self) -> ExecutionTrace {
+        self.router.tracer.take()
+    }
+
+    /// Exports the execution trace recorded so far as the Rust function replaying
+    /// all messages executed directly by this application, asserting their results
+    /// and final balances of all accounts taking part in the recorded scenario.
+    /// The generated function with the specified name takes the application prepared
+    /// the same way as this one, with the same contract codes stored and accounts funded.
+    pub fn export_regression_test(&self, name: &str) -> AnyResult<String> {
+        let trace = self.trace();
+        let mut balances = vec![];
+        for addr in trace
+            .entries()
+            .iter()
+            .flat_map(|entry| [entry.sender.as_str(), entry.receiver.as_str()])
+            .unique()
+        {
+            if let Ok(addr) = self.api.addr_validate(addr) {
+                let coins = self.wrap().query_all_balances(&addr)?;
+                balances.push((addr, coins));
+            }
+        }
+        Ok(to_rust_test(&trace, name, &balances))
+    }
+
+    /// Starts verifying that the sum of all balances equals the total supply
+    /// of every denomination after each processed message, including messages sent
+    /// by contracts and custom modules. A violated invariant aborts the test with a panic
+    /// reporting the differences.
+    pub fn enable_invariant_checks(&mut self) {
+        self.router.invariants.enable();
+    }
+
+    /// Stops verifying invariants after processed messages.
+    pub fn disable_invariant_checks(&mut self) {
+        self.router.invariants.disable();
+    }
+
+    /// Starts enforcing the budget of simulated resources, aborting the test with a panic
+    /// reporting the used resources when any limit is exceeded.
+    /// Resources used so far are not counted.
+    pub fn set_resource_budget(&mut self, budget: ResourceBudget) {
+        self.router.meter.set(budget);
+    }
+
+    /// Stops enforcing the budget of simulated resources.
+    pub fn clear_resource_budget(&mut self) {
+        self.router.meter.clear();
+    }
+
+    /// Returns resources used since the budget was set.
+    /// When no budget is set, no resources are counted.
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.router.meter.usage()
+    }
+
+    /// Exempts the contract from charging the simulated gas, like system contracts
+    /// whitelisted on some chains. Messages executed on gas-free contracts and all messages
+    /// they send are not charged, but are still counted in the budget of messages.
+    pub fn add_gas_free_contract(&mut self, contract_addr: Addr) {
+        self.router.meter.add_gas_free(contract_addr);
+    }
+
+    /// Removes the exemption from charging the simulated gas of the contract.
+    pub fn remove_gas_free_contract(&mut self, contract_addr: &Addr) {
+        self.router.meter.remove_gas_free(contract_addr);
+    }
+
+    /// Returns contracts exempted from charging the simulated gas.
+    pub fn gas_free_contracts(&self) -> Vec<Addr> {
+        self.router.meter.gas_free()
+    }
+
+    /// Asserts that all balances of the account match, panics otherwise.
+    #[track_caller]
+    pub fn assert_balances(&self, address: impl Into<String>, matcher: &FundsMatcher) {
+        let address = address.into();
+        let balances = self.wrap().query_all_balances(&address).unwrap();
+        assert!(
+            matcher.matches(&balances),
+            "expected balances of {} {}, but found {}",
+            address,
+            matcher,
+            funds_to_string(&balances)
+        );
+    }
+
     /// Simple helper so we get access to all the QuerierWrapper helpers,
     /// e.g. wrap().query_wasm_smart, query_all_balances, ...
     pub fn wrap(&self) -> QuerierWrapper<CustomT::QueryT> {
@@ -453,11 +977,243 @@ where
 
         transactional(&mut *storage, |write_cache, _| {
             msgs.into_iter()
-                .map(|msg| router.execute(&*api, write_cache, block, sender.clone(), msg))
+                .map(|msg| {
+                    ensure_user_msg_allowed(write_cache, &msg)?;
+                    router.execute(&*api, write_cache, block, sender.clone(), msg)
+                })
                 .collect()
         })
     }
 
+    /// Returns the address of the module account with specified name, like `distribution`,
+    /// generated the same way as in Cosmos SDK.
+    pub fn module_address(&self, module: &str) -> AnyResult<Addr> {
+        module_address(&self.api, module)
+    }
+
+    /// Runs multiple CosmosMsg in one atomic operation, sent by the account of the module
+    /// with specified name, like `distribution` or `gov`. This emulates messages originating
+    /// from module accounts (like the results of community pool spends),
+    /// without faking the sender address in tests.
+    pub fn execute_as_module(
+        &mut self,
+        module: &str,
+        msgs: Vec<CosmosMsg<CustomT::ExecT>>,
+    ) -> AnyResult<Vec<AppResponse>> {
+        let sender = self.module_address(module)?;
+        self.execute_multi(sender, msgs)
+    }
+
+    /// Stores the code of the [Adversary] contract and instantiates it,
+    /// returns the address of the adversary.
+    pub fn instantiate_adversary(&mut self, adversary: Adversary) -> AnyResult<Addr> {
+        let code_id = self.store_code(Box::new(adversary));
+        let sender = self.module_address("adversary")?;
+        self.instantiate_contract(code_id, sender, &Empty {}, &[], "adversary", None)
+    }
+
+    /// Opens the channel between contracts bound to specified IBC ports
+    /// over the simulated IBC localhost client, running the whole channel handshake.
+    /// Returns identifiers of both channel ends, the first one is bound to `port_id`.
+    /// Requires [IbcLocalhost](crate::IbcLocalhost) module to send packets over the channel.
+    pub fn open_localhost_channel(
+        &mut self,
+        port_id: &str,
+        counterparty_port_id: &str,
+        version: &str,
+        order: IbcOrder,
+    ) -> AnyResult<(String, String)> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            open_channel(
+                &*api,
+                write_cache,
+                router,
+                &router.wasm,
+                block,
+                port_id,
+                counterparty_port_id,
+                version,
+                order,
+            )
+        })
+    }
+
+    /// Initializes the channel end bound to specified IBC port, the first step
+    /// of the channel handshake run by a relayer. Calls contract's `ibc_channel_open`
+    /// entry-point with `OpenInit` and returns the initialized channel,
+    /// with the version proposed by the contract.
+    pub fn channel_open_init(
+        &mut self,
+        port_id: &str,
+        counterparty_port_id: &str,
+        connection_id: &str,
+        version: &str,
+        order: IbcOrder,
+    ) -> AnyResult<IbcChannel> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            channel_open_init(
+                &*api,
+                write_cache,
+                router,
+                &router.wasm,
+                block,
+                port_id,
+                counterparty_port_id,
+                connection_id,
+                version,
+                order,
+            )
+        })
+    }
+
+    /// Tries to open the channel end bound to specified IBC port, initialized by
+    /// the counterparty endpoint. Calls contract's `ibc_channel_open` entry-point with `OpenTry`
+    /// and returns the channel, with the version proposed by the contract.
+    pub fn channel_open_try(
+        &mut self,
+        port_id: &str,
+        counterparty: IbcEndpoint,
+        connection_id: &str,
+        counterparty_version: &str,
+        order: IbcOrder,
+    ) -> AnyResult<IbcChannel> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            channel_open_try(
+                &*api,
+                write_cache,
+                router,
+                &router.wasm,
+                block,
+                port_id,
+                counterparty,
+                connection_id,
+                counterparty_version,
+                order,
+            )
+        })
+    }
+
+    /// Opens the initialized channel end, when the counterparty agreed to open the channel
+    /// with specified version. Calls contract's `ibc_channel_connect` entry-point with `OpenAck`.
+    pub fn channel_open_ack(
+        &mut self,
+        channel_id: &str,
+        counterparty_channel_id: &str,
+        counterparty_version: &str,
+    ) -> AnyResult<()> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            channel_open_ack(
+                &*api,
+                write_cache,
+                router,
+                &router.wasm,
+                block,
+                channel_id,
+                counterparty_channel_id,
+                counterparty_version,
+            )
+        })
+    }
+
+    /// Opens the channel end in `TryOpen` state, when the counterparty acknowledged
+    /// the channel. Calls contract's `ibc_channel_connect` entry-point with `OpenConfirm`.
+    pub fn channel_open_confirm(&mut self, channel_id: &str) -> AnyResult<()> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            channel_open_confirm(&*api, write_cache, router, &router.wasm, block, channel_id)
+        })
+    }
+
+    /// Closes the channel end on request of the user, the counterparty end is closed
+    /// with [channel_close_confirm](Self::channel_close_confirm).
+    /// Calls contract's `ibc_channel_close` entry-point with `CloseInit`.
+    pub fn channel_close_init(&mut self, channel_id: &str) -> AnyResult<()> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            channel_close_init(&*api, write_cache, router, &router.wasm, block, channel_id)
+        })
+    }
+
+    /// Closes the channel end, when the counterparty channel end was closed.
+    /// Calls contract's `ibc_channel_close` entry-point with `CloseConfirm`.
+    pub fn channel_close_confirm(&mut self, channel_id: &str) -> AnyResult<()> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            channel_close_confirm(&*api, write_cache, router, &router.wasm, block, channel_id)
+        })
+    }
+
+    /// Returns the channel end with specified identifier, with the state of the handshake.
+    pub fn channel_end(&self, channel_id: &str) -> AnyResult<Option<ChannelEnd>> {
+        channel_end(&self.storage, channel_id)
+    }
+
+    /// Relays all packets sent over the IBC localhost client, including packets
+    /// sent while relaying, and returns responses of relayed packets in order.
+    /// Each packet is relayed in a separate transaction.
+    pub fn relay_localhost_packets(&mut self) -> AnyResult<Vec<AppResponse>> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        let mut responses = vec![];
+        while let Some(response) = transactional(&mut *storage, |write_cache, _| {
+            relay_packet(&*api, write_cache, router, &router.wasm, block)
+        })? {
+            responses.push(response);
+        }
+        Ok(responses)
+    }
+
     /// Call a smart contract in "sudo" mode.
     /// This will create a cache before the execution, so no state changes are persisted if this
     /// returns an error, but all are persisted on success.
@@ -501,11 +1257,111 @@ where
             router.sudo(&*api, write_cache, block, msg)
         })
     }
+
+    /// Mints tokens for the account, increasing the total supply.
+    /// Can be called at any point of the test, like [BankSudo::Mint].
+    pub fn mint_to(
+        &mut self,
+        address: impl Into<String>,
+        amount: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        self.sudo(
+            BankSudo::Mint {
+                to_address: address.into(),
+                amount,
+            }
+            .into(),
+        )
+    }
+
+    /// Slashes the fraction of the validator's stake, including all delegations
+    /// and pending unbondings, like [StakingSudo::Slash].
+    pub fn slash_validator(
+        &mut self,
+        validator: impl Into<String>,
+        fraction: Decimal,
+    ) -> AnyResult<AppResponse> {
+        self.sudo(
+            StakingSudo::Slash {
+                validator: validator.into(),
+                percentage: fraction,
+            }
+            .into(),
+        )
+    }
+
+    /// Jails the validator until the specified block time, like [StakingSudo::Jail].
+    pub fn jail_validator(
+        &mut self,
+        validator: impl Into<String>,
+        jailed_until: Timestamp,
+    ) -> AnyResult<AppResponse> {
+        self.sudo(
+            StakingSudo::Jail {
+                validator: validator.into(),
+                jailed_until,
+            }
+            .into(),
+        )
+    }
+
+    /// Adds the validator with the operator's self-delegation of newly minted tokens,
+    /// 1,000,000 tokens for every unit of the voting power, see [AppBuilder::with_validators].
+    pub(crate) fn create_genesis_validator(
+        &mut self,
+        operator: &Addr,
+        power: u64,
+        commission: Decimal,
+    ) -> AnyResult<()> {
+        let validator = Validator::new(
+            operator.to_string(),
+            commission,
+            Decimal::one(),
+            Decimal::percent(1),
+        );
+        self.sudo(StakingSudo::AddValidator { validator }.into())?;
+        if power > 0 {
+            let bonded_denom = self.wrap().query_bonded_denom()?;
+            let amount = Coin::new(u128::from(power) * 1_000_000, bonded_denom);
+            self.sudo(
+                BankSudo::Mint {
+                    to_address: operator.to_string(),
+                    amount: vec![amount.clone()],
+                }
+                .into(),
+            )?;
+            self.execute(
+                operator.clone(),
+                StakingMsg::Delegate {
+                    validator: operator.to_string(),
+                    amount,
+                }
+                .into(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Overwrites all balances of the account, adjusting the total supply.
+    /// Can be called at any point of the test, like [BankSudo::SetBalance].
+    pub fn set_balance(
+        &mut self,
+        address: impl Into<String>,
+        amount: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        self.sudo(
+            BankSudo::SetBalance {
+                address: address.into(),
+                amount,
+            }
+            .into(),
+        )
+    }
 }
 /// The Router plays a critical role in managing and directing
 /// transactions within the Cosmos blockchain.
 #[derive(Clone)]
-pub struct Router<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate> {
+pub struct Router<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate, Mint = MintKeeper> {
     /// Wasm module instance to be used in this [Router].
     pub(crate) wasm: Wasm,
     /// Bank module instance to be used in this [Router].
@@ -522,10 +1378,20 @@ pub struct Router<Bank, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate> {
     pub gov: Gov,
     /// Stargate handler instance to be used in this [Router].
     pub stargate: Stargate,
+    /// Mint module instance to be used in this [Router].
+    pub mint: Mint,
+    /// Execution trace recorder.
+    pub(crate) tracer: Tracer,
+    /// Enforcer of the budget of simulated resources.
+    pub(crate) meter: BudgetMeter,
+    /// Verifier of invariants of the chain state.
+    pub(crate) invariants: InvariantChecker,
+    /// Recorder of the chain state for committed blocks.
+    pub(crate) snapshots: BlockSnapshots,
 }
 
-impl<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
-    Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+impl<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
+    Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
 where
     CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
     CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
@@ -537,6 +1403,7 @@ where
     IbcT: Ibc,
     GovT: Gov,
     StargateT: Stargate,
+    MintT: Mint,
 {
     /// Returns a querier populated with the instance of this [Router].
     pub fn querier<'a>(
@@ -552,10 +1419,41 @@ where
             block_info,
         }
     }
+
+    /// Dispatches the message to the module responsible for processing it.
+    fn execute_msg(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: CosmosMsg<CustomT::ExecT>,
+    ) -> AnyResult<AppResponse> {
+        match msg {
+            CosmosMsg::Wasm(msg) => self.wasm.execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Bank(msg) => self.bank.execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Custom(msg) => self.custom.execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Staking(msg) => self.staking.execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Distribution(msg) => self
+                .distribution
+                .execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Ibc(msg) => self.ibc.execute(api, storage, self, block, sender, msg),
+            CosmosMsg::Gov(msg) => self.gov.execute(api, storage, self, block, sender, msg),
+            #[allow(deprecated)]
+            CosmosMsg::Stargate { type_url, value } => self
+                .stargate
+                .execute_stargate(api, storage, self, block, sender, type_url, value),
+            CosmosMsg::Any(msg) => self
+                .stargate
+                .execute_any(api, storage, self, block, sender, msg),
+            _ => bail!("Cannot execute {:?}", msg),
+        }
+    }
 }
 
 /// We use it to allow calling into modules from another module in sudo mode.
 /// Things like gov proposals belong here.
+#[derive(Clone, Debug)]
 pub enum SudoMsg {
     /// Bank privileged actions.
     Bank(BankSudo),
@@ -563,8 +1461,16 @@ pub enum SudoMsg {
     Custom(Empty),
     /// Staking privileged actions.
     Staking(StakingSudo),
+    /// Distribution privileged actions.
+    Distribution(DistributionSudo),
+    /// Governance privileged actions.
+    Gov(GovSudo),
+    /// Mint privileged actions.
+    Mint(MintSudo),
     /// Wasm privileged actions.
     Wasm(WasmSudo),
+    /// Privileged actions for pausing and resuming modules.
+    Pause(PauseSudo),
 }
 
 impl From<WasmSudo> for SudoMsg {
@@ -584,6 +1490,106 @@ impl From<StakingSudo> for SudoMsg {
         SudoMsg::Staking(staking)
     }
 }
+
+impl From<DistributionSudo> for SudoMsg {
+    fn from(distribution: DistributionSudo) -> Self {
+        SudoMsg::Distribution(distribution)
+    }
+}
+
+impl From<GovSudo> for SudoMsg {
+    fn from(gov: GovSudo) -> Self {
+        SudoMsg::Gov(gov)
+    }
+}
+
+impl From<MintSudo> for SudoMsg {
+    fn from(mint: MintSudo) -> Self {
+        SudoMsg::Mint(mint)
+    }
+}
+
+impl From<PauseSudo> for SudoMsg {
+    fn from(pause: PauseSudo) -> Self {
+        SudoMsg::Pause(pause)
+    }
+}
+
+/// Messages signed by the sender and executed by modules, that have no `CosmosMsg` equivalent.
+/// Protobuf-encoded Cosmos SDK messages sent as `CosmosMsg::Stargate` or `CosmosMsg::Any`
+/// are decoded into these messages by [CosmosSdkStargate](crate::CosmosSdkStargate).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CosmosSdkMsg {
+    /// Sends tokens from the sender's account to multiple outputs, like `MsgMultiSend`.
+    /// All inputs must belong to the sender.
+    MultiSend {
+        /// Accounts the tokens are sent from.
+        inputs: Vec<MultiSendIo>,
+        /// Accounts the tokens are sent to.
+        outputs: Vec<MultiSendIo>,
+    },
+    /// Submits the governance proposal, like `MsgSubmitProposal` signed by the proposer.
+    SubmitProposal(SubmitProposal),
+    /// Adds the sender's deposit to the proposal, like `MsgDeposit`.
+    Deposit {
+        /// Identifier of the proposal.
+        proposal_id: u64,
+        /// Deposited coins.
+        amount: Vec<Coin>,
+    },
+    /// Cancels the proposal submitted by the sender, like `MsgCancelProposal`.
+    CancelProposal {
+        /// Identifier of the proposal.
+        proposal_id: u64,
+    },
+    /// Withdraws the commission of the validator operated by the sender,
+    /// like `MsgWithdrawValidatorCommission`.
+    WithdrawValidatorCommission,
+    /// Moves coins from the sender's account to the community pool, like `MsgFundCommunityPool`.
+    FundCommunityPool {
+        /// Deposited coins.
+        amount: Vec<Coin>,
+    },
+    /// Creates the validator operated by the sender and delegates its self-delegation,
+    /// like `MsgCreateValidator`.
+    CreateValidator {
+        /// Initial commission rate.
+        commission: Decimal,
+        /// Maximum commission rate.
+        max_commission: Decimal,
+        /// Maximum daily change of the commission rate.
+        max_change_rate: Decimal,
+        /// Self-delegation of the validator's operator.
+        value: Coin,
+        /// Minimum number of tokens delegated by the validator's operator.
+        min_self_delegation: Uint128,
+    },
+    /// Edits the validator operated by the sender, like `MsgEditValidator`.
+    /// The description and the commission rate are kept when not specified.
+    EditValidator {
+        /// New description of the validator.
+        description: Option<ValidatorDescription>,
+        /// New commission rate.
+        commission: Option<Decimal>,
+    },
+    /// Unjails the validator operated by the sender, like `MsgUnjail`.
+    Unjail,
+    /// Liquid staking message signed by the sender, like `MsgTokenizeShares`.
+    LiquidStaking(LiquidStakingMsg),
+}
+
+/// Returns an error when the message sent by a user or a contract can not be processed,
+/// because the module is paused or the transferred coins are not allowed to be sent.
+/// Messages sent by the modules themselves (like unbonding payouts or deposit refunds)
+/// are not checked, just like transfers from module accounts on a real chain.
+pub(crate) fn ensure_user_msg_allowed<T>(
+    storage: &dyn Storage,
+    msg: &CosmosMsg<T>,
+) -> AnyResult<()> {
+    ensure_msg_not_paused(storage, msg)?;
+    ensure_msg_send_enabled(storage, msg)
+}
+
 /// A trait representing the Cosmos based chain's router.
 ///
 /// This trait is designed for routing messages within the Cosmos ecosystem.
@@ -622,10 +1628,22 @@ pub trait CosmosRouter {
         block: &BlockInfo,
         msg: SudoMsg,
     ) -> AnyResult<AppResponse>;
+
+    /// Executes messages signed by the sender, that have no `CosmosMsg` equivalent.
+    fn execute_sdk_msg(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _block: &BlockInfo,
+        sender: Addr,
+        msg: CosmosSdkMsg,
+    ) -> AnyResult<AppResponse> {
+        bail!("Cannot execute {:?} from {}", msg, sender)
+    }
 }
 
-impl<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT> CosmosRouter
-    for Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+impl<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT> CosmosRouter
+    for Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
 where
     CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
     CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
@@ -637,6 +1655,7 @@ where
     IbcT: Ibc,
     GovT: Gov,
     StargateT: Stargate,
+    MintT: Mint,
 {
     type ExecC = CustomT::ExecT;
     type QueryC = CustomT::QueryT;
@@ -649,25 +1668,18 @@ where
         sender: Addr,
         msg: CosmosMsg<Self::ExecC>,
     ) -> AnyResult<AppResponse> {
-        match msg {
-            CosmosMsg::Wasm(msg) => self.wasm.execute(api, storage, self, block, sender, msg),
-            CosmosMsg::Bank(msg) => self.bank.execute(api, storage, self, block, sender, msg),
-            CosmosMsg::Custom(msg) => self.custom.execute(api, storage, self, block, sender, msg),
-            CosmosMsg::Staking(msg) => self.staking.execute(api, storage, self, block, sender, msg),
-            CosmosMsg::Distribution(msg) => self
-                .distribution
-                .execute(api, storage, self, block, sender, msg),
-            CosmosMsg::Ibc(msg) => self.ibc.execute(api, storage, self, block, sender, msg),
-            CosmosMsg::Gov(msg) => self.gov.execute(api, storage, self, block, sender, msg),
-            #[allow(deprecated)]
-            CosmosMsg::Stargate { type_url, value } => self
-                .stargate
-                .execute_stargate(api, storage, self, block, sender, type_url, value),
-            CosmosMsg::Any(msg) => self
-                .stargate
-                .execute_any(api, storage, self, block, sender, msg),
-            _ => bail!("Cannot execute {:?}", msg),
+        let trace_index = self.tracer.enter(&sender, &msg);
+        let metered = self.meter.enter(&msg);
+        let exempt = self.meter.is_exempt(&msg);
+        let checked_msg = self.invariants.is_enabled().then(|| msg.clone());
+        let result = self.meter.metered(metered, exempt, storage, |storage| {
+            self.execute_msg(api, storage, block, sender, msg)
+        });
+        self.tracer.exit(trace_index, &result);
+        if let (Some(msg), Ok(_)) = (checked_msg, &result) {
+            self.invariants.check(storage, &msg);
         }
+        result
     }
 
     /// This is used by `RouterQuerier` to actual implement the `Querier` interface.
@@ -687,6 +1699,43 @@ where
             QueryRequest::Custom(req) => self.custom.query(api, storage, &querier, block, req),
             QueryRequest::Staking(req) => self.staking.query(api, storage, &querier, block, req),
             QueryRequest::Ibc(req) => self.ibc.query(api, storage, &querier, block, req),
+            #[cfg(feature = "cosmwasm_2_0")]
+            QueryRequest::Distribution(req) => query_distribution(api, storage, block, req),
+            #[allow(deprecated)]
+            QueryRequest::Stargate { path, data } if is_bank_grpc_query(&path) => {
+                query_bank_grpc(api, storage, block, &path, &data)
+            }
+            QueryRequest::Grpc(req) if is_bank_grpc_query(&req.path) => {
+                query_bank_grpc(api, storage, block, &req.path, &req.data)
+            }
+            #[allow(deprecated)]
+            QueryRequest::Stargate { path, data } if is_distribution_grpc_query(&path) => {
+                query_distribution_grpc(api, storage, block, &path, &data)
+            }
+            QueryRequest::Grpc(req) if is_distribution_grpc_query(&req.path) => {
+                query_distribution_grpc(api, storage, block, &req.path, &req.data)
+            }
+            #[allow(deprecated)]
+            QueryRequest::Stargate { path, data } if is_staking_grpc_query(&path) => {
+                query_staking_grpc(api, storage, block, &path, &data)
+            }
+            QueryRequest::Grpc(req) if is_staking_grpc_query(&req.path) => {
+                query_staking_grpc(api, storage, block, &req.path, &req.data)
+            }
+            #[allow(deprecated)]
+            QueryRequest::Stargate { path, data } if is_gov_grpc_query(&path) => {
+                query_gov_grpc(api, storage, block, &path, &data)
+            }
+            QueryRequest::Grpc(req) if is_gov_grpc_query(&req.path) => {
+                query_gov_grpc(api, storage, block, &req.path, &req.data)
+            }
+            #[allow(deprecated)]
+            QueryRequest::Stargate { path, .. } if is_mint_grpc_query(&path) => {
+                query_mint_grpc(&self.mint, api, storage, &querier, block, &path)
+            }
+            QueryRequest::Grpc(req) if is_mint_grpc_query(&req.path) => {
+                query_mint_grpc(&self.mint, api, storage, &querier, block, &req.path)
+            }
             #[allow(deprecated)]
             QueryRequest::Stargate { path, data } => self
                 .stargate
@@ -703,11 +1752,102 @@ where
         block: &BlockInfo,
         msg: SudoMsg,
     ) -> AnyResult<AppResponse> {
-        match msg {
+        let checked_msg = self.invariants.is_enabled().then(|| msg.clone());
+        let result = match msg {
             SudoMsg::Wasm(msg) => self.wasm.sudo(api, storage, self, block, msg),
             SudoMsg::Bank(msg) => self.bank.sudo(api, storage, self, block, msg),
             SudoMsg::Staking(msg) => self.staking.sudo(api, storage, self, block, msg),
+            SudoMsg::Distribution(DistributionSudo::CommunityPoolSpend { recipient, amount }) => {
+                let recipient = api.addr_validate(&recipient)?;
+                self.distribution
+                    .community_pool_spend(api, storage, self, block, recipient, amount)
+            }
+            SudoMsg::Gov(GovSudo::UpdateParams { params }) => {
+                self.gov.update_params(storage, block, params)
+            }
+            SudoMsg::Mint(msg) => self.mint.sudo(api, storage, self, block, msg),
             SudoMsg::Custom(_) => unimplemented!(),
+            SudoMsg::Pause(msg) => {
+                pause_sudo(storage, block.height, msg).map(|_| AppResponse::default())
+            }
+        };
+        if let (Some(msg), Ok(_)) = (checked_msg, &result) {
+            self.invariants.check(storage, &msg);
+        }
+        result
+    }
+
+    fn execute_sdk_msg(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: CosmosSdkMsg,
+    ) -> AnyResult<AppResponse> {
+        match msg {
+            CosmosSdkMsg::MultiSend { inputs, outputs } => self
+                .bank
+                .multi_send(api, storage, self, block, sender, inputs, outputs),
+            CosmosSdkMsg::SubmitProposal(proposal) => self
+                .gov
+                .submit_proposal(api, storage, self, block, sender, proposal),
+            CosmosSdkMsg::Deposit {
+                proposal_id,
+                amount,
+            } => self
+                .gov
+                .deposit(api, storage, self, block, sender, proposal_id, amount),
+            CosmosSdkMsg::CancelProposal { proposal_id } => {
+                self.gov
+                    .cancel_proposal(api, storage, self, block, sender, proposal_id)
+            }
+            CosmosSdkMsg::WithdrawValidatorCommission => self
+                .distribution
+                .withdraw_validator_commission(api, storage, self, block, sender),
+            CosmosSdkMsg::FundCommunityPool { amount } => self
+                .distribution
+                .fund_community_pool(api, storage, self, block, sender, amount),
+            CosmosSdkMsg::CreateValidator {
+                commission,
+                max_commission,
+                max_change_rate,
+                value,
+                min_self_delegation,
+            } => {
+                let validator = Validator::new(
+                    sender.to_string(),
+                    commission,
+                    max_commission,
+                    max_change_rate,
+                );
+                self.staking.create_validator(
+                    api,
+                    storage,
+                    self,
+                    block,
+                    sender,
+                    validator,
+                    value,
+                    min_self_delegation,
+                )
+            }
+            CosmosSdkMsg::EditValidator {
+                description,
+                commission,
+            } => self.staking.edit_validator(
+                api,
+                storage,
+                self,
+                block,
+                sender,
+                description,
+                commission,
+            ),
+            CosmosSdkMsg::Unjail => self.staking.unjail(api, storage, self, block, sender),
+            CosmosSdkMsg::LiquidStaking(msg) => self
+                .staking
+                .execute_liquid_staking(api, storage, self, block, sender, msg),
         }
     }
 }
@@ -2,11 +2,11 @@
 
 use crate::{
     App, Bank, BankKeeper, Distribution, DistributionKeeper, FailingModule, Gov, GovFailingModule,
-    Ibc, IbcFailingModule, Module, Router, StakeKeeper, Staking, Stargate, StargateFailing, Wasm,
-    WasmKeeper,
+    Ibc, IbcFailingModule, Mint, MintKeeper, Module, Router, StakeKeeper, Staking, Stargate,
+    StargateFailing, Wasm, WasmKeeper,
 };
 use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
-use cosmwasm_std::{Api, BlockInfo, CustomMsg, CustomQuery, Empty, Storage};
+use cosmwasm_std::{Addr, Api, BlockInfo, CustomMsg, CustomQuery, Decimal, Empty, Storage};
 use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 
@@ -39,11 +39,24 @@ pub type BasicAppBuilder<ExecC, QueryC> = AppBuilder<
     IbcFailingModule,
     GovFailingModule,
     StargateFailing,
+    MintKeeper,
 >;
 
 /// Utility to build [App] in stages.
 /// When particular properties are not explicitly set, then default values are used.
-pub struct AppBuilder<Bank, Api, Storage, Custom, Wasm, Staking, Distr, Ibc, Gov, Stargate> {
+pub struct AppBuilder<
+    Bank,
+    Api,
+    Storage,
+    Custom,
+    Wasm,
+    Staking,
+    Distr,
+    Ibc,
+    Gov,
+    Stargate,
+    Mint = MintKeeper,
+> {
     api: Api,
     block: BlockInfo,
     storage: Storage,
@@ -55,6 +68,8 @@ pub struct AppBuilder<Bank, Api, Storage, Custom, Wasm, Staking, Distr, Ibc, Gov
     ibc: Ibc,
     gov: Gov,
     stargate: Stargate,
+    mint: Mint,
+    validators: Vec<(Addr, u64, Decimal)>,
 }
 
 impl Default
@@ -69,6 +84,7 @@ impl Default
         IbcFailingModule,
         GovFailingModule,
         StargateFailing,
+        MintKeeper,
     >
 {
     fn default() -> Self {
@@ -88,6 +104,7 @@ impl
         IbcFailingModule,
         GovFailingModule,
         StargateFailing,
+        MintKeeper,
     >
 {
     /// Creates builder with default components working with empty exec and query messages.
@@ -104,6 +121,8 @@ impl
             ibc: IbcFailingModule::new(),
             gov: GovFailingModule::new(),
             stargate: StargateFailing,
+            mint: MintKeeper::new(),
+            validators: vec![],
         }
     }
 }
@@ -120,6 +139,7 @@ impl<ExecC, QueryC>
         IbcFailingModule,
         GovFailingModule,
         StargateFailing,
+        MintKeeper,
     >
 where
     ExecC: CustomMsg + DeserializeOwned + 'static,
@@ -140,12 +160,26 @@ where
             ibc: IbcFailingModule::new(),
             gov: GovFailingModule::new(),
             stargate: StargateFailing,
+            mint: MintKeeper::new(),
+            validators: vec![],
         }
     }
 }
 
-impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
-    AppBuilder<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
+    AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+        MintT,
+    >
 where
     CustomT: Module,
     WasmT: Wasm<CustomT::ExecT, CustomT::QueryT>,
@@ -158,8 +192,19 @@ where
     pub fn with_wasm<NewWasm: Wasm<CustomT::ExecT, CustomT::QueryT>>(
         self,
         wasm: NewWasm,
-    ) -> AppBuilder<BankT, ApiT, StorageT, CustomT, NewWasm, StakingT, DistrT, IbcT, GovT, StargateT>
-    {
+    ) -> AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        NewWasm,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+        MintT,
+    > {
         let AppBuilder {
             bank,
             api,
@@ -171,6 +216,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -186,6 +233,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
         }
     }
 
@@ -193,8 +242,19 @@ where
     pub fn with_bank<NewBank: Bank>(
         self,
         bank: NewBank,
-    ) -> AppBuilder<NewBank, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
-    {
+    ) -> AppBuilder<
+        NewBank,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+        MintT,
+    > {
         let AppBuilder {
             wasm,
             api,
@@ -206,6 +266,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -221,6 +283,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
         }
     }
 
@@ -228,8 +292,19 @@ where
     pub fn with_api<NewApi: Api>(
         self,
         api: NewApi,
-    ) -> AppBuilder<BankT, NewApi, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
-    {
+    ) -> AppBuilder<
+        BankT,
+        NewApi,
+        StorageT,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+        MintT,
+    > {
         let AppBuilder {
             wasm,
             bank,
@@ -241,6 +316,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -256,6 +333,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
         }
     }
 
@@ -263,8 +342,19 @@ where
     pub fn with_storage<NewStorage: Storage>(
         self,
         storage: NewStorage,
-    ) -> AppBuilder<BankT, ApiT, NewStorage, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
-    {
+    ) -> AppBuilder<
+        BankT,
+        ApiT,
+        NewStorage,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+        MintT,
+    > {
         let AppBuilder {
             wasm,
             api,
@@ -276,6 +366,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -291,6 +383,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
         }
     }
 
@@ -302,8 +396,19 @@ where
     pub fn with_custom<NewCustom: Module>(
         self,
         custom: NewCustom,
-    ) -> AppBuilder<BankT, ApiT, StorageT, NewCustom, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
-    {
+    ) -> AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        NewCustom,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+        MintT,
+    > {
         let AppBuilder {
             wasm,
             bank,
@@ -315,6 +420,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -330,6 +437,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
         }
     }
 
@@ -337,8 +446,19 @@ where
     pub fn with_staking<NewStaking: Staking>(
         self,
         staking: NewStaking,
-    ) -> AppBuilder<BankT, ApiT, StorageT, CustomT, WasmT, NewStaking, DistrT, IbcT, GovT, StargateT>
-    {
+    ) -> AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmT,
+        NewStaking,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+        MintT,
+    > {
         let AppBuilder {
             wasm,
             api,
@@ -350,6 +470,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -365,6 +487,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
         }
     }
 
@@ -383,6 +507,7 @@ where
         IbcT,
         GovT,
         StargateT,
+        MintT,
     > {
         let AppBuilder {
             wasm,
@@ -395,6 +520,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -410,6 +537,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
         }
     }
 
@@ -423,8 +552,19 @@ where
     pub fn with_ibc<NewIbc: Ibc>(
         self,
         ibc: NewIbc,
-    ) -> AppBuilder<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, NewIbc, GovT, StargateT>
-    {
+    ) -> AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        NewIbc,
+        GovT,
+        StargateT,
+        MintT,
+    > {
         let AppBuilder {
             wasm,
             api,
@@ -436,6 +576,8 @@ where
             distribution,
             gov,
             stargate,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -451,6 +593,8 @@ where
             distribution,
             ibc,
             gov,
+            mint,
+            validators,
         }
     }
 
@@ -458,8 +602,19 @@ where
     pub fn with_gov<NewGov: Gov>(
         self,
         gov: NewGov,
-    ) -> AppBuilder<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, NewGov, StargateT>
-    {
+    ) -> AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        NewGov,
+        StargateT,
+        MintT,
+    > {
         let AppBuilder {
             wasm,
             api,
@@ -471,6 +626,8 @@ where
             distribution,
             ibc,
             stargate,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -486,6 +643,8 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
         }
     }
 
@@ -493,8 +652,19 @@ where
     pub fn with_stargate<NewStargate: Stargate>(
         self,
         stargate: NewStargate,
-    ) -> AppBuilder<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, NewStargate>
-    {
+    ) -> AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        NewStargate,
+        MintT,
+    > {
         let AppBuilder {
             wasm,
             api,
@@ -506,6 +676,8 @@ where
             distribution,
             ibc,
             gov,
+            mint,
+            validators,
             ..
         } = self;
 
@@ -521,6 +693,58 @@ where
             ibc,
             gov,
             stargate,
+            mint,
+            validators,
+        }
+    }
+
+    /// Overwrites the default mint interface.
+    pub fn with_mint<NewMint: Mint>(
+        self,
+        mint: NewMint,
+    ) -> AppBuilder<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+        NewMint,
+    > {
+        let AppBuilder {
+            wasm,
+            api,
+            storage,
+            custom,
+            block,
+            staking,
+            bank,
+            distribution,
+            ibc,
+            gov,
+            stargate,
+            validators,
+            ..
+        } = self;
+
+        AppBuilder {
+            api,
+            block,
+            storage,
+            bank,
+            wasm,
+            custom,
+            staking,
+            distribution,
+            ibc,
+            gov,
+            stargate,
+            mint,
+            validators,
         }
     }
 
@@ -530,13 +754,34 @@ where
         self
     }
 
+    /// Adds validators to the initial validator set, every validator is given as the address
+    /// of its operator, its voting power and its commission rate. The maximum commission rate
+    /// of every validator is 100%, the maximum daily change of the commission rate is 1%.
+    ///
+    /// Validators are created after the initialization function passed to [build](Self::build)
+    /// is called, the operator of every validator self-delegates newly minted tokens
+    /// of the bonded denomination, 1,000,000 tokens for every unit of the voting power,
+    /// like the default power reduction in Cosmos SDK.
+    pub fn with_validators(
+        mut self,
+        validators: impl IntoIterator<Item = (Addr, u64, Decimal)>,
+    ) -> Self {
+        self.validators.extend(validators);
+        self
+    }
+
     /// Builds final `App`. At this point all components type have to be properly related to each
     /// other. If there are some generics related compilation errors, make sure that all components
     /// are properly relating to each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics when any validator added with [with_validators](Self::with_validators)
+    /// can not be created, e.g. when its commission rate is below the minimum commission rate.
     pub fn build<F>(
         self,
         init_fn: F,
-    ) -> App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>
+    ) -> App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
     where
         BankT: Bank,
         ApiT: Api,
@@ -548,8 +793,11 @@ where
         IbcT: Ibc,
         GovT: Gov,
         StargateT: Stargate,
+        MintT: Mint,
+        CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
+        CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
         F: FnOnce(
-            &mut Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT>,
+            &mut Router<BankT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>,
             &dyn Api,
             &mut dyn Storage,
         ),
@@ -563,6 +811,11 @@ where
             ibc: self.ibc,
             gov: self.gov,
             stargate: self.stargate,
+            mint: self.mint,
+            tracer: Default::default(),
+            meter: Default::default(),
+            invariants: Default::default(),
+            snapshots: Default::default(),
         };
 
         let mut app = App {
@@ -572,6 +825,10 @@ where
             storage: self.storage,
         };
         app.init_modules(init_fn);
+        for (operator, power, commission) in self.validators {
+            app.create_genesis_validator(&operator, power, commission)
+                .unwrap_or_else(|err| panic!("failed to create validator {operator}: {err:?}"));
+        }
         app
     }
 }
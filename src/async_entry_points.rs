@@ -0,0 +1,79 @@
+//! # Async entry-points
+//!
+//! Mocked contracts sometimes need to await external fixtures, like HTTP mocks or databases.
+//! [ContractWrapper::new_async](crate::ContractWrapper::new_async) accepts `async` entry-point
+//! functions and drives returned futures to completion on the calling thread,
+//! using the minimal executor provided in this module.
+//!
+//! The executor does not provide any reactor, so futures depending on a specific runtime
+//! (like `tokio` I/O or timers) must be spawned on that runtime and awaited through
+//! runtime-agnostic handles (like channels or join handles).
+
+use cosmwasm_std::{Binary, CustomQuery, Deps, DepsMut, Env, MessageInfo, Response};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Async function usable as contract's `execute` or `instantiate` entry-point,
+/// implemented for all `async fn(DepsMut<Q>, Env, MessageInfo, T) -> Result<Response<C>, E>`.
+pub trait AsyncContractFn<'a, T, C, E, Q>:
+    Fn(DepsMut<'a, Q>, Env, MessageInfo, T) -> Self::Future
+where
+    Q: CustomQuery + 'a,
+{
+    /// Future returned from the entry-point.
+    type Future: Future<Output = Result<Response<C>, E>> + 'a;
+}
+
+impl<'a, F, Fut, T, C, E, Q> AsyncContractFn<'a, T, C, E, Q> for F
+where
+    F: Fn(DepsMut<'a, Q>, Env, MessageInfo, T) -> Fut,
+    Fut: Future<Output = Result<Response<C>, E>> + 'a,
+    Q: CustomQuery + 'a,
+{
+    type Future = Fut;
+}
+
+/// Async function usable as contract's `query` entry-point,
+/// implemented for all `async fn(Deps<Q>, Env, T) -> Result<Binary, E>`.
+pub trait AsyncQueryFn<'a, T, E, Q>: Fn(Deps<'a, Q>, Env, T) -> Self::Future
+where
+    Q: CustomQuery + 'a,
+{
+    /// Future returned from the entry-point.
+    type Future: Future<Output = Result<Binary, E>> + 'a;
+}
+
+impl<'a, F, Fut, T, E, Q> AsyncQueryFn<'a, T, E, Q> for F
+where
+    F: Fn(Deps<'a, Q>, Env, T) -> Fut,
+    Fut: Future<Output = Result<Binary, E>> + 'a,
+    Q: CustomQuery + 'a,
+{
+    type Future = Fut;
+}
+
+/// Waker unparking the thread blocked on the future.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls the future on the current thread until it completes,
+/// parking the thread while the future is pending.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
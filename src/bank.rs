@@ -1,17 +1,23 @@
-use crate::app::CosmosRouter;
+use crate::app::{CosmosRouter, CosmosSdkMsg};
 use crate::error::{bail, AnyResult};
 use crate::executor::AppResponse;
 use crate::module::Module;
+use crate::param_history::record_param_change;
 use crate::prefixed_storage::{prefixed, prefixed_read};
+use crate::wasm::WasmSudo;
 use cosmwasm_std::{
     coin, to_json_binary, Addr, AllBalanceResponse, AllDenomMetadataResponse, Api, BalanceResponse,
-    BankMsg, BankQuery, Binary, BlockInfo, Coin, DenomMetadata, DenomMetadataResponse, Event,
-    Order, Querier, StdResult, Storage, SupplyResponse, Uint128,
+    BankMsg, BankQuery, Binary, BlockInfo, Coin, CosmosMsg, CustomMsg, CustomQuery, DenomMetadata,
+    DenomMetadataResponse, Empty, Event, Order, PageRequest, Querier, StdResult, Storage,
+    SupplyResponse, Timestamp, Uint128, WasmMsg,
 };
-use cw_storage_plus::Map;
+use cw_storage_plus::{Bound, Item, Map};
 use cw_utils::NativeBalance;
 use itertools::Itertools;
 use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 /// Collection of bank balances.
 const BALANCES: Map<&Addr, NativeBalance> = Map::new("balances");
@@ -19,6 +25,29 @@ const BALANCES: Map<&Addr, NativeBalance> = Map::new("balances");
 /// Collection of metadata for denomination.
 const DENOM_METADATA: Map<String, DenomMetadata> = Map::new("metadata");
 
+/// Collection of vesting accounts.
+const VESTING_ACCOUNTS: Map<&Addr, VestingAccount> = Map::new("vesting_accounts");
+
+/// Total supply of tokens, indexed by denomination.
+const SUPPLY: Map<&str, Uint128> = Map::new("supply");
+
+/// Send-enabled flags of denominations, transfers of denominations not listed
+/// are enabled according to the `default_send_enabled` parameter.
+const SEND_ENABLED: Map<&str, bool> = Map::new("send_enabled");
+
+/// Flag indicating whether transfers of denominations without a send-enabled flag are enabled,
+/// enabled when not set.
+const DEFAULT_SEND_ENABLED: Item<bool> = Item::new("default_send_enabled");
+
+/// Addresses not allowed to receive funds with `BankMsg::Send` and `MsgMultiSend`.
+const BLOCKED_ADDRESSES: Map<&Addr, Empty> = Map::new("blocked_addresses");
+
+/// Contracts notified about transfers, indexed by the watched address and the contract address.
+const TRANSFER_HOOKS: Map<(&Addr, &Addr), Empty> = Map::new("transfer_hooks");
+
+/// Collection of canonical denominations, indexed by their aliases.
+const DENOM_ALIASES: Map<&str, String> = Map::new("denom_aliases");
+
 /// Default storage namespace for bank module.
 const NAMESPACE_BANK: &[u8] = b"bank";
 
@@ -32,6 +61,582 @@ pub enum BankSudo {
         /// Amount of the minted tokens.
         amount: Vec<Coin>,
     },
+    /// Overwrites all balances of the account,
+    /// the total supply is adjusted by the difference between the old and new balance.
+    SetBalance {
+        /// Address of the account.
+        address: String,
+        /// New balances of the account, denominations not listed are cleared.
+        amount: Vec<Coin>,
+    },
+    /// Creates a continuous vesting account, the specified amount is minted
+    /// for the account and vests linearly between `start_time` and `end_time`.
+    CreateVestingAccount {
+        /// Address of the vesting account.
+        to_address: String,
+        /// Amount of the vesting tokens.
+        amount: Vec<Coin>,
+        /// Time when the vesting starts.
+        start_time: Timestamp,
+        /// Time when all tokens are vested.
+        end_time: Timestamp,
+    },
+    /// Creates a delayed vesting account, the specified amount is minted
+    /// for the account and vests at once at `end_time`.
+    CreateDelayedVestingAccount {
+        /// Address of the vesting account.
+        to_address: String,
+        /// Amount of the vesting tokens.
+        amount: Vec<Coin>,
+        /// Time when all tokens are vested.
+        end_time: Timestamp,
+    },
+    /// Creates a periodic vesting account, the sum of amounts of all periods is minted
+    /// for the account and vests at the end of each period.
+    CreatePeriodicVestingAccount {
+        /// Address of the vesting account.
+        to_address: String,
+        /// Time when the first period starts.
+        start_time: Timestamp,
+        /// Consecutive vesting periods.
+        periods: Vec<VestingPeriod>,
+    },
+    /// Tracks the delegation of tokens from the account.
+    /// Sent by the staking module before delegated tokens are transferred.
+    /// This is a no-op for accounts that are not vesting accounts.
+    TrackDelegation {
+        /// Address of the delegator.
+        delegator: String,
+        /// Delegated amount.
+        amount: Vec<Coin>,
+    },
+    /// Tracks the undelegation of tokens to the account.
+    /// Sent by the staking module after unbonded tokens are transferred back.
+    /// This is a no-op for accounts that are not vesting accounts.
+    TrackUndelegation {
+        /// Address of the delegator.
+        delegator: String,
+        /// Undelegated amount, after slashing.
+        amount: Vec<Coin>,
+    },
+    /// Sets the metadata of the denomination, like the `symbol`, `display` name
+    /// and exponents of denomination units, returned by `DenomMetadata` queries.
+    SetDenomMetadata {
+        /// Denomination the metadata is set for.
+        denom: String,
+        /// Metadata of the denomination.
+        metadata: DenomMetadata,
+    },
+    /// Enables or disables transfers of the denomination, like `SendEnabled` parameters
+    /// of Cosmos SDK bank module. Disabled denominations can still be minted and burned.
+    SetSendEnabled {
+        /// Denomination the flag is set for.
+        denom: String,
+        /// Flag indicating whether transfers of the denomination are enabled.
+        enabled: bool,
+    },
+    /// Replaces all parameters of the bank module, like `MsgUpdateParams`
+    /// of Cosmos SDK bank module. Send-enabled flags of denominations not listed
+    /// in the new parameters are cleared.
+    SetParams {
+        /// New parameters.
+        params: BankParams,
+    },
+}
+
+/// Input or output of the [CosmosSdkMsg::MultiSend] message.
+#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MultiSendIo {
+    /// Address of the account.
+    pub address: String,
+    /// Amount of the sent or received tokens.
+    pub coins: Vec<Coin>,
+}
+
+impl MultiSendIo {
+    /// Creates an input or output for specified account and amount.
+    pub fn new(address: impl Into<String>, coins: Vec<Coin>) -> Self {
+        Self {
+            address: address.into(),
+            coins,
+        }
+    }
+}
+
+/// Parameters of the bank module, like `Params` of Cosmos SDK bank module.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct BankParams {
+    /// Send-enabled flags of denominations, ordered by denomination.
+    pub send_enabled: Vec<SendEnabled>,
+    /// Flag indicating whether transfers of denominations not listed in `send_enabled`
+    /// are enabled.
+    pub default_send_enabled: bool,
+}
+
+impl Default for BankParams {
+    fn default() -> Self {
+        Self {
+            send_enabled: vec![],
+            default_send_enabled: true,
+        }
+    }
+}
+
+/// Send-enabled flag of the denomination.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SendEnabled {
+    /// Denomination the flag is set for.
+    pub denom: String,
+    /// Flag indicating whether transfers of the denomination are enabled.
+    pub enabled: bool,
+}
+
+/// Path of the gRPC query for parameters of the bank module.
+const QUERY_PARAMS: &str = "/cosmos.bank.v1beta1.Query/Params";
+
+/// Path of the gRPC query for spendable balances of the account.
+const QUERY_SPENDABLE_BALANCES: &str = "/cosmos.bank.v1beta1.Query/SpendableBalances";
+
+/// Path of the gRPC query for spendable balance of the account in single denomination.
+const QUERY_SPENDABLE_BALANCE_BY_DENOM: &str = "/cosmos.bank.v1beta1.Query/SpendableBalanceByDenom";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ProtoSendEnabled {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(bool, tag = "2")]
+    pub enabled: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ProtoParams {
+    #[prost(message, repeated, tag = "1")]
+    pub send_enabled: Vec<ProtoSendEnabled>,
+    #[prost(bool, tag = "2")]
+    pub default_send_enabled: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryParamsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub params: Option<ProtoParams>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQuerySpendableBalancesRequest {
+    #[prost(string, tag = "1")]
+    pub address: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQuerySpendableBalancesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub balances: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQuerySpendableBalanceByDenomRequest {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(string, tag = "2")]
+    pub denom: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQuerySpendableBalanceByDenomResponse {
+    #[prost(message, optional, tag = "1")]
+    pub balance: Option<ProtoCoin>,
+}
+
+impl From<Coin> for ProtoCoin {
+    fn from(c: Coin) -> Self {
+        Self {
+            denom: c.denom,
+            amount: c.amount.to_string(),
+        }
+    }
+}
+
+/// Returns `true` when the gRPC query with specified path is answered by the bank module.
+pub(crate) fn is_bank_grpc_query(path: &str) -> bool {
+    [
+        QUERY_PARAMS,
+        QUERY_SPENDABLE_BALANCES,
+        QUERY_SPENDABLE_BALANCE_BY_DENOM,
+    ]
+    .contains(&path)
+}
+
+/// Returns the protobuf-encoded response to the gRPC query of the bank module.
+///
+/// Spendable balances are not paginated, all balances are returned in a single page.
+pub(crate) fn query_bank_grpc(
+    api: &dyn Api,
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    path: &str,
+    data: &[u8],
+) -> AnyResult<Binary> {
+    let bank = BankKeeper::new();
+    let response = match path {
+        QUERY_PARAMS => {
+            let params = bank.params(storage)?;
+            prost::Message::encode_to_vec(&ProtoQueryParamsResponse {
+                params: Some(ProtoParams {
+                    send_enabled: params
+                        .send_enabled
+                        .into_iter()
+                        .map(|flag| ProtoSendEnabled {
+                            denom: flag.denom,
+                            enabled: flag.enabled,
+                        })
+                        .collect(),
+                    default_send_enabled: params.default_send_enabled,
+                }),
+            })
+        }
+        QUERY_SPENDABLE_BALANCES => {
+            let request = <ProtoQuerySpendableBalancesRequest as prost::Message>::decode(data)?;
+            let address = api.addr_validate(&request.address)?;
+            let balances = bank.spendable_balance(storage, block, &address)?;
+            prost::Message::encode_to_vec(&ProtoQuerySpendableBalancesResponse {
+                balances: balances.into_iter().map(Into::into).collect(),
+            })
+        }
+        QUERY_SPENDABLE_BALANCE_BY_DENOM => {
+            let request =
+                <ProtoQuerySpendableBalanceByDenomRequest as prost::Message>::decode(data)?;
+            let address = api.addr_validate(&request.address)?;
+            let balances = bank.spendable_balance(storage, block, &address)?;
+            let amount = amount_of(&balances, &request.denom);
+            prost::Message::encode_to_vec(&ProtoQuerySpendableBalanceByDenomResponse {
+                balance: Some(coin(amount.u128(), request.denom).into()),
+            })
+        }
+        _ => bail!("unsupported bank query: {}", path),
+    };
+    Ok(response.into())
+}
+
+/// Type URL of the `MsgMultiSend` message routed to the bank module.
+pub(crate) const MSG_MULTI_SEND: &str = "/cosmos.bank.v1beta1.MsgMultiSend";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMultiSendIo {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(message, repeated, tag = "2")]
+    pub coins: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgMultiSend {
+    #[prost(message, repeated, tag = "1")]
+    pub inputs: Vec<ProtoMultiSendIo>,
+    #[prost(message, repeated, tag = "2")]
+    pub outputs: Vec<ProtoMultiSendIo>,
+}
+
+impl TryFrom<ProtoMultiSendIo> for MultiSendIo {
+    type Error = anyhow::Error;
+
+    fn try_from(io: ProtoMultiSendIo) -> AnyResult<Self> {
+        let coins = io
+            .coins
+            .into_iter()
+            .map(|c| Ok(coin(c.amount.parse::<u128>()?, c.denom)))
+            .collect::<AnyResult<Vec<_>>>()?;
+        Ok(Self::new(io.address, coins))
+    }
+}
+
+/// Decodes the protobuf-encoded `MsgMultiSend`.
+pub(crate) fn decode_multi_send(value: &[u8]) -> AnyResult<CosmosSdkMsg> {
+    let msg = <ProtoMsgMultiSend as prost::Message>::decode(value)?;
+    let inputs = msg
+        .inputs
+        .into_iter()
+        .map(MultiSendIo::try_from)
+        .collect::<AnyResult<Vec<_>>>()?;
+    let outputs = msg
+        .outputs
+        .into_iter()
+        .map(MultiSendIo::try_from)
+        .collect::<AnyResult<Vec<_>>>()?;
+    Ok(CosmosSdkMsg::MultiSend { inputs, outputs })
+}
+
+/// Restriction of bank transfers, like send restrictions in Cosmos SDK v0.50.
+///
+/// Registered restrictions are applied to every transfer with `BankMsg::Send`
+/// (including funds attached to contract calls) and to every output of `MsgMultiSend`,
+/// before the tokens are moved. A restriction can reject the transfer by returning an error,
+/// or redirect it by returning a different recipient. Minting and burning are not restricted.
+///
+/// Any closure `Fn(&Addr, &Addr, &[Coin]) -> AnyResult<Addr>` is a send restriction.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{coins, Addr, Coin};
+/// use cw_multi_test::error::{bail, AnyResult};
+/// use cw_multi_test::{AppBuilder, BankKeeper, Executor};
+///
+/// let bank = BankKeeper::new().with_send_restriction(
+///     |_from: &Addr, to: &Addr, amount: &[Coin]| -> AnyResult<Addr> {
+///         if amount.iter().any(|c| c.amount.u128() > 100) {
+///             bail!("transfer limit exceeded");
+///         }
+///         Ok(to.clone())
+///     },
+/// );
+/// let mut app = AppBuilder::default().with_bank(bank).build(|_, _, _| {});
+/// let owner_addr = app.api().addr_make("owner");
+/// let recipient_addr = app.api().addr_make("recipient");
+/// app.mint_to(&owner_addr, coins(1000, "uatom")).unwrap();
+///
+/// app.send_tokens(owner_addr.clone(), recipient_addr.clone(), &coins(100, "uatom"))
+///     .unwrap();
+/// let err = app
+///     .send_tokens(owner_addr, recipient_addr, &coins(101, "uatom"))
+///     .unwrap_err();
+/// assert_eq!("transfer limit exceeded", err.root_cause().to_string());
+/// ```
+pub trait SendRestriction {
+    /// Checks the transfer of tokens from `from` to `to`,
+    /// returns the recipient the tokens are transferred to.
+    fn apply(&self, from: &Addr, to: &Addr, amount: &[Coin]) -> AnyResult<Addr>;
+}
+
+impl<F> SendRestriction for F
+where
+    F: Fn(&Addr, &Addr, &[Coin]) -> AnyResult<Addr>,
+{
+    fn apply(&self, from: &Addr, to: &Addr, amount: &[Coin]) -> AnyResult<Addr> {
+        self(from, to, amount)
+    }
+}
+
+/// Message sent to the `sudo` entry-point of the contract registered
+/// with [add_transfer_hook](BankKeeper::add_transfer_hook).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferHookMsg {
+    /// Tokens were transferred from one account to another.
+    BankTransfer {
+        /// Address of the sender.
+        from: String,
+        /// Address of the recipient.
+        to: String,
+        /// Amount of the transferred tokens.
+        amount: Vec<Coin>,
+    },
+}
+
+/// Holder of the denomination, returned by [denom_owners](BankKeeper::denom_owners).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DenomOwner {
+    /// Address of the holder.
+    pub address: Addr,
+    /// Balance of the holder in the denomination.
+    pub balance: Coin,
+}
+
+/// Single page of holders of the denomination.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DenomOwnersResponse {
+    /// Holders on the page, ordered by address.
+    pub denom_owners: Vec<DenomOwner>,
+    /// Key of the next page, `None` when this is the last page.
+    pub next_key: Option<Binary>,
+}
+
+/// Schedule of releasing tokens from the [VestingAccount].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub enum VestingSchedule {
+    /// Tokens vest linearly between start and end time.
+    #[default]
+    Continuous,
+    /// All tokens vest at the end time.
+    Delayed,
+    /// Tokens vest in consecutive periods, starting at the start time.
+    Periodic(Vec<VestingPeriod>),
+}
+
+/// Single period of the periodic vesting schedule.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct VestingPeriod {
+    /// Length of the period in seconds.
+    pub length: u64,
+    /// Tokens vested at the end of the period.
+    pub amount: Vec<Coin>,
+}
+
+/// Vesting account, like continuous, delayed and periodic vesting accounts in Cosmos SDK.
+///
+/// Tokens vest between start and end time, following the [VestingSchedule].
+/// Vesting tokens can not be transferred,
+/// but can be delegated. Like in Cosmos SDK, delegations are tracked as delegated vesting
+/// and delegated free tokens, undelegations reduce the delegated free tokens first.
+/// Slashed delegations are not tracked, so after undelegating slashed tokens
+/// the delegated vesting amount stays greater than the actual delegation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct VestingAccount {
+    /// Initial amount of vesting tokens.
+    pub original_vesting: Vec<Coin>,
+    /// Time when the vesting starts.
+    pub start_time: Timestamp,
+    /// Time when all tokens are vested.
+    pub end_time: Timestamp,
+    /// Delegated tokens that were vested at the time of delegation.
+    pub delegated_free: Vec<Coin>,
+    /// Delegated tokens that were vesting at the time of delegation.
+    pub delegated_vesting: Vec<Coin>,
+    /// Schedule of releasing the vesting tokens.
+    #[serde(default)]
+    pub schedule: VestingSchedule,
+}
+
+impl VestingAccount {
+    /// Returns tokens that are still vesting at the specified time.
+    pub fn vesting_coins(&self, time: Timestamp) -> Vec<Coin> {
+        if time <= self.start_time {
+            return self.original_vesting.clone();
+        }
+        if time >= self.end_time {
+            return vec![];
+        }
+        match &self.schedule {
+            VestingSchedule::Continuous => {
+                let elapsed = time.nanos() - self.start_time.nanos();
+                let duration = self.end_time.nanos() - self.start_time.nanos();
+                self.original_vesting
+                    .iter()
+                    .map(|c| {
+                        let vested = c.amount.multiply_ratio(elapsed, duration);
+                        coin((c.amount - vested).u128(), &c.denom)
+                    })
+                    .filter(|c| !c.amount.is_zero())
+                    .collect()
+            }
+            VestingSchedule::Delayed => self.original_vesting.clone(),
+            VestingSchedule::Periodic(periods) => {
+                let mut vesting = self.original_vesting.clone();
+                let mut period_end = self.start_time;
+                for period in periods {
+                    period_end = period_end.plus_seconds(period.length);
+                    if period_end > time {
+                        break;
+                    }
+                    for c in &period.amount {
+                        sub_amount(&mut vesting, &c.denom, c.amount);
+                    }
+                }
+                vesting
+            }
+        }
+    }
+
+    /// Returns tokens that can not be transferred at the specified time,
+    /// vesting tokens that were delegated are not locked.
+    pub fn locked_coins(&self, time: Timestamp) -> Vec<Coin> {
+        self.vesting_coins(time)
+            .into_iter()
+            .map(|c| {
+                let delegated = amount_of(&self.delegated_vesting, &c.denom);
+                coin(c.amount.saturating_sub(delegated).u128(), c.denom)
+            })
+            .filter(|c| !c.amount.is_zero())
+            .collect()
+    }
+
+    /// Tracks delegation of the specified amount, vesting tokens are delegated first.
+    fn track_delegation(&mut self, vesting: &[Coin], amount: &[Coin]) {
+        for c in amount {
+            let vesting_amount = amount_of(vesting, &c.denom);
+            let delegated_vesting = amount_of(&self.delegated_vesting, &c.denom);
+            let x = vesting_amount
+                .saturating_sub(delegated_vesting)
+                .min(c.amount);
+            let y = c.amount - x;
+            add_amount(&mut self.delegated_vesting, &c.denom, x);
+            add_amount(&mut self.delegated_free, &c.denom, y);
+        }
+    }
+
+    /// Tracks undelegation of the specified amount, delegated free tokens are undelegated first.
+    fn track_undelegation(&mut self, amount: &[Coin]) {
+        for c in amount {
+            let x = amount_of(&self.delegated_free, &c.denom).min(c.amount);
+            let y = amount_of(&self.delegated_vesting, &c.denom).min(c.amount - x);
+            sub_amount(&mut self.delegated_free, &c.denom, x);
+            sub_amount(&mut self.delegated_vesting, &c.denom, y);
+        }
+    }
+}
+
+/// Returns the amount of tokens in specified denomination.
+fn amount_of(coins: &[Coin], denom: &str) -> Uint128 {
+    coins
+        .iter()
+        .find(|c| c.denom == denom)
+        .map(|c| c.amount)
+        .unwrap_or_default()
+}
+
+/// Adds the amount of tokens in specified denomination.
+fn add_amount(coins: &mut Vec<Coin>, denom: &str, amount: Uint128) {
+    if amount.is_zero() {
+        return;
+    }
+    match coins.iter_mut().find(|c| c.denom == denom) {
+        Some(c) => c.amount += amount,
+        None => coins.push(coin(amount.u128(), denom)),
+    }
+}
+
+/// Subtracts the amount of tokens in specified denomination, removes empty entries.
+fn sub_amount(coins: &mut Vec<Coin>, denom: &str, amount: Uint128) {
+    if let Some(c) = coins.iter_mut().find(|c| c.denom == denom) {
+        c.amount = c.amount.saturating_sub(amount);
+    }
+    coins.retain(|c| !c.amount.is_zero());
+}
+
+/// Returns denominations for which the sum of all balances (including module accounts)
+/// differs from the total supply, with the sum of balances and the total supply.
+pub(crate) fn supply_mismatches(
+    storage: &dyn Storage,
+) -> AnyResult<Vec<(String, Uint128, Uint128)>> {
+    let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+    let mut balances = BTreeMap::<String, Uint128>::new();
+    for entry in BALANCES.range(&bank_storage, None, None, Order::Ascending) {
+        let (_, balance) = entry?;
+        for c in balance.into_vec() {
+            *balances.entry(c.denom).or_default() += c.amount;
+        }
+    }
+    let supply = SUPPLY
+        .range(&bank_storage, None, None, Order::Ascending)
+        .collect::<StdResult<BTreeMap<_, _>>>()?;
+    Ok(balances
+        .keys()
+        .chain(supply.keys())
+        .unique()
+        .filter_map(|denom| {
+            let total = balances.get(denom).copied().unwrap_or_default();
+            let supplied = supply.get(denom).copied().unwrap_or_default();
+            (total != supplied).then(|| (denom.clone(), total, supplied))
+        })
+        .collect())
 }
 
 /// This trait defines the interface for simulating banking operations.
@@ -39,15 +644,34 @@ pub enum BankSudo {
 /// In the test environment, it is essential for testing financial transactions,
 /// like transfers and balance checks, within your smart contracts.
 /// This trait implements all of these functionalities.
-pub trait Bank: Module<ExecT = BankMsg, QueryT = BankQuery, SudoT = BankSudo> {}
+pub trait Bank: Module<ExecT = BankMsg, QueryT = BankQuery, SudoT = BankSudo> {
+    /// This is called for `MsgMultiSend` signed by the sender, who must own all inputs.
+    /// If you're implementing a dummy bank module, this can fail.
+    #[allow(clippy::too_many_arguments)]
+    fn multi_send<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _sender: Addr,
+        _inputs: Vec<MultiSendIo>,
+        _outputs: Vec<MultiSendIo>,
+    ) -> AnyResult<AppResponse> {
+        bail!("multi-send is not supported")
+    }
+}
 
 /// A structure representing a default bank keeper.
 ///
 /// Manages financial interactions in CosmWasm tests, such as simulating token transactions
 /// and account balances. This is particularly important for contracts that deal with financial
 /// operations in the Cosmos ecosystem.
-#[derive(Default)]
-pub struct BankKeeper {}
+#[derive(Clone, Default)]
+pub struct BankKeeper {
+    /// Restrictions applied to transfers, in the order they are applied.
+    send_restrictions: Vec<Arc<dyn SendRestriction>>,
+}
 
 impl BankKeeper {
     /// Creates a new instance of a bank keeper with default settings.
@@ -55,41 +679,516 @@ impl BankKeeper {
         Self::default()
     }
 
-    /// Administration function for adjusting bank accounts in genesis.
-    pub fn init_balance(
+    /// Appends the restriction applied to transfers after all already registered restrictions.
+    pub fn with_send_restriction(mut self, restriction: impl SendRestriction + 'static) -> Self {
+        self.append_send_restriction(restriction);
+        self
+    }
+
+    /// Appends the restriction applied to transfers after all already registered restrictions,
+    /// like `AppendSendRestriction` in Cosmos SDK.
+    pub fn append_send_restriction(&mut self, restriction: impl SendRestriction + 'static) {
+        self.send_restrictions.push(Arc::new(restriction));
+    }
+
+    /// Prepends the restriction applied to transfers before all already registered restrictions,
+    /// like `PrependSendRestriction` in Cosmos SDK.
+    pub fn prepend_send_restriction(&mut self, restriction: impl SendRestriction + 'static) {
+        self.send_restrictions.insert(0, Arc::new(restriction));
+    }
+
+    /// Removes all registered send restrictions.
+    pub fn clear_send_restrictions(&mut self) {
+        self.send_restrictions.clear();
+    }
+
+    /// Applies all send restrictions to the transfer, returns the final recipient.
+    fn apply_send_restrictions(&self, from: &Addr, to: Addr, amount: &[Coin]) -> AnyResult<Addr> {
+        self.send_restrictions
+            .iter()
+            .try_fold(to, |to, restriction| restriction.apply(from, &to, amount))
+    }
+
+    /// Administration function for adjusting bank accounts in genesis.
+    pub fn init_balance(
+        &self,
+        storage: &mut dyn Storage,
+        account: &Addr,
+        amount: Vec<Coin>,
+    ) -> AnyResult<()> {
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        self.set_balance(&mut bank_storage, account, amount)
+    }
+
+    /// Administration function for initializing balances of many accounts at once,
+    /// like [init_balance](Self::init_balance) called for every account, but faster:
+    /// addresses are not validated and the total supply of every denomination
+    /// is written once, after all balances are saved.
+    pub fn init_balances<I>(&self, storage: &mut dyn Storage, balances: I) -> AnyResult<()>
+    where
+        I: IntoIterator<Item = (Addr, Vec<Coin>)>,
+    {
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        let mut increases = BTreeMap::<String, Uint128>::new();
+        let mut decreases = BTreeMap::<String, Uint128>::new();
+        for (account, amount) in balances {
+            let mut balance = NativeBalance(amount);
+            balance.normalize();
+            let old_balance = BALANCES
+                .may_load(&bank_storage, &account)?
+                .unwrap_or_default();
+            for c in &old_balance.0 {
+                *decreases.entry(c.denom.clone()).or_default() += c.amount;
+            }
+            for c in &balance.0 {
+                *increases.entry(c.denom.clone()).or_default() += c.amount;
+            }
+            BALANCES.save(&mut bank_storage, &account, &balance)?;
+        }
+        for denom in increases.keys().chain(decreases.keys()).unique() {
+            let increase = increases.get(denom).copied().unwrap_or_default();
+            let decrease = decreases.get(denom).copied().unwrap_or_default();
+            if increase > decrease {
+                self.increase_supply(&mut bank_storage, denom, increase - decrease)?;
+            } else if decrease > increase {
+                self.decrease_supply(&mut bank_storage, denom, decrease - increase)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Administration function for adjusting bank accounts,
+    /// the total supply is adjusted by the difference between the old and new balance.
+    fn set_balance(
+        &self,
+        bank_storage: &mut dyn Storage,
+        account: &Addr,
+        amount: Vec<Coin>,
+    ) -> AnyResult<()> {
+        let mut balance = NativeBalance(amount);
+        balance.normalize();
+        let old_balance = self.get_balance(bank_storage, account)?;
+        for denom in old_balance
+            .iter()
+            .chain(balance.0.iter())
+            .map(|c| c.denom.as_str())
+            .unique()
+        {
+            let old_amount = amount_of(&old_balance, denom);
+            let new_amount = amount_of(&balance.0, denom);
+            if new_amount > old_amount {
+                self.increase_supply(bank_storage, denom, new_amount - old_amount)?;
+            } else if old_amount > new_amount {
+                self.decrease_supply(bank_storage, denom, old_amount - new_amount)?;
+            }
+        }
+        self.save_balance(bank_storage, account, balance)
+    }
+
+    /// Saves the balance of the account, without adjusting the total supply.
+    fn save_balance(
+        &self,
+        bank_storage: &mut dyn Storage,
+        account: &Addr,
+        mut balance: NativeBalance,
+    ) -> AnyResult<()> {
+        balance.normalize();
+        BALANCES
+            .save(bank_storage, account, &balance)
+            .map_err(Into::into)
+    }
+
+    /// Increases the total supply of tokens in specified denomination.
+    fn increase_supply(
+        &self,
+        bank_storage: &mut dyn Storage,
+        denom: &str,
+        amount: Uint128,
+    ) -> AnyResult<()> {
+        let supply = SUPPLY.may_load(bank_storage, denom)?.unwrap_or_default();
+        SUPPLY.save(bank_storage, denom, &supply.checked_add(amount)?)?;
+        Ok(())
+    }
+
+    /// Decreases the total supply of tokens in specified denomination,
+    /// denominations with zero supply are removed.
+    fn decrease_supply(
+        &self,
+        bank_storage: &mut dyn Storage,
+        denom: &str,
+        amount: Uint128,
+    ) -> AnyResult<()> {
+        let supply = SUPPLY.may_load(bank_storage, denom)?.unwrap_or_default();
+        let supply = supply.checked_sub(amount)?;
+        if supply.is_zero() {
+            SUPPLY.remove(bank_storage, denom);
+        } else {
+            SUPPLY.save(bank_storage, denom, &supply)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the total supply of tokens in specified denomination.
+    pub fn supply(&self, storage: &dyn Storage, denom: &str) -> AnyResult<Coin> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        self.get_supply(&bank_storage, denom.to_string())
+    }
+
+    /// Returns the total supply of tokens in all denominations, ordered by denomination.
+    pub fn all_supply(&self, storage: &dyn Storage) -> AnyResult<Vec<Coin>> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        Ok(SUPPLY
+            .range(&bank_storage, None, None, Order::Ascending)
+            .map(|entry| entry.map(|(denom, amount)| Coin::new(amount.u128(), denom)))
+            .collect::<StdResult<Vec<_>>>()?)
+    }
+
+    /// Returns all holders of the denomination with their balances, ordered by address,
+    /// like the `DenomOwners` query in Cosmos SDK.
+    ///
+    /// The `key` of the page is the address of the first holder on that page,
+    /// the key of the next page is returned in `next_key`.
+    pub fn denom_owners(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        pagination: Option<PageRequest>,
+    ) -> AnyResult<DenomOwnersResponse> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        let page = pagination.unwrap_or(PageRequest {
+            key: None,
+            limit: 0,
+            reverse: false,
+        });
+        let start = page
+            .key
+            .map(|key| String::from_utf8(key.to_vec()))
+            .transpose()?
+            .map(Addr::unchecked);
+        let start = start.as_ref().map(Bound::inclusive);
+        let (min, max, order) = if page.reverse {
+            (None, start, Order::Descending)
+        } else {
+            (start, None, Order::Ascending)
+        };
+        let limit = if page.limit == 0 {
+            usize::MAX
+        } else {
+            page.limit as usize
+        };
+        let mut denom_owners = BALANCES
+            .range(&bank_storage, min, max, order)
+            .filter_map(|entry| match entry {
+                Ok((address, balance)) => balance
+                    .into_vec()
+                    .into_iter()
+                    .find(|c| c.denom == denom)
+                    .map(|balance| Ok(DenomOwner { address, balance })),
+                Err(err) => Some(Err(err)),
+            })
+            .take(limit.saturating_add(1))
+            .collect::<StdResult<Vec<_>>>()?;
+        let next_key = (denom_owners.len() > limit)
+            .then(|| denom_owners.pop())
+            .flatten()
+            .map(|owner| Binary::from(owner.address.as_bytes()));
+        Ok(DenomOwnersResponse {
+            denom_owners,
+            next_key,
+        })
+    }
+
+    /// Administration function for adjusting denomination metadata.
+    pub fn set_denom_metadata(
+        &self,
+        storage: &mut dyn Storage,
+        denom: String,
+        metadata: DenomMetadata,
+    ) -> AnyResult<()> {
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        DENOM_METADATA
+            .save(&mut bank_storage, denom, &metadata)
+            .map_err(Into::into)
+    }
+
+    /// Returns the metadata of specified denomination, if set.
+    pub fn denom_metadata(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+    ) -> AnyResult<Option<DenomMetadata>> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        Ok(DENOM_METADATA.may_load(&bank_storage, denom.to_string())?)
+    }
+
+    /// Blocks the address from receiving funds, like module accounts in Cosmos SDK
+    /// (e.g. `distribution` or `gov`) that reject direct transfers with `BankMsg::Send`.
+    /// Minted tokens are still received.
+    pub fn block_address(&self, storage: &mut dyn Storage, address: &Addr) -> AnyResult<()> {
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        BLOCKED_ADDRESSES.save(&mut bank_storage, address, &Empty {})?;
+        Ok(())
+    }
+
+    /// Allows the previously blocked address to receive funds again.
+    pub fn unblock_address(&self, storage: &mut dyn Storage, address: &Addr) -> AnyResult<()> {
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        BLOCKED_ADDRESSES.remove(&mut bank_storage, address);
+        Ok(())
+    }
+
+    /// Returns `true` when the address is blocked from receiving funds.
+    pub fn is_blocked_address(&self, storage: &dyn Storage, address: &Addr) -> AnyResult<bool> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        Ok(BLOCKED_ADDRESSES.has(&bank_storage, address))
+    }
+
+    /// Returns an error when the recipient is blocked from receiving funds.
+    fn ensure_not_blocked(&self, bank_storage: &dyn Storage, recipient: &Addr) -> AnyResult<()> {
+        if BLOCKED_ADDRESSES.has(bank_storage, recipient) {
+            bail!(
+                "{} is not allowed to receive funds: unauthorized",
+                recipient
+            );
+        }
+        Ok(())
+    }
+
+    /// Registers the contract notified about every transfer with `BankMsg::Send`
+    /// or `MsgMultiSend` from or to the watched address.
+    ///
+    /// The contract's `sudo` entry-point is called with [TransferHookMsg] after the transfer,
+    /// the transfer is reverted when the contract returns an error.
+    pub fn add_transfer_hook(
+        &self,
+        storage: &mut dyn Storage,
+        watched: &Addr,
+        contract: &Addr,
+    ) -> AnyResult<()> {
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        TRANSFER_HOOKS.save(&mut bank_storage, (watched, contract), &Empty {})?;
+        Ok(())
+    }
+
+    /// Unregisters the contract notified about transfers of the watched address.
+    pub fn remove_transfer_hook(
+        &self,
+        storage: &mut dyn Storage,
+        watched: &Addr,
+        contract: &Addr,
+    ) -> AnyResult<()> {
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        TRANSFER_HOOKS.remove(&mut bank_storage, (watched, contract));
+        Ok(())
+    }
+
+    /// Returns contracts notified about transfers of the watched address, ordered by address.
+    pub fn transfer_hooks(&self, storage: &dyn Storage, watched: &Addr) -> AnyResult<Vec<Addr>> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        Ok(TRANSFER_HOOKS
+            .prefix(watched)
+            .keys(&bank_storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?)
+    }
+
+    /// Notifies contracts watching the sender or the recipient about the transfer,
+    /// returns events emitted by the contracts.
+    fn call_transfer_hooks<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        transfers: Vec<(Addr, Addr, Vec<Coin>)>,
+    ) -> AnyResult<Vec<Event>> {
+        let mut events = vec![];
+        for (from, to, amount) in transfers {
+            let contracts = [&from, &to]
+                .into_iter()
+                .map(|watched| self.transfer_hooks(storage, watched))
+                .flatten_ok()
+                .collect::<AnyResult<Vec<_>>>()?;
+            let msg = TransferHookMsg::BankTransfer {
+                from: from.to_string(),
+                to: to.to_string(),
+                amount,
+            };
+            for contract in contracts.into_iter().unique() {
+                let sudo_msg = WasmSudo::new(&contract, &msg)?;
+                let res = router.sudo(api, storage, block, sudo_msg.into())?;
+                events.extend(res.events);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Returns the parameters of the bank module.
+    pub fn params(&self, storage: &dyn Storage) -> AnyResult<BankParams> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        self.load_params(&bank_storage)
+    }
+
+    fn load_params(&self, bank_storage: &dyn Storage) -> AnyResult<BankParams> {
+        let send_enabled = SEND_ENABLED
+            .range(bank_storage, None, None, Order::Ascending)
+            .map(|entry| entry.map(|(denom, enabled)| SendEnabled { denom, enabled }))
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(BankParams {
+            send_enabled,
+            default_send_enabled: self.default_send_enabled(bank_storage)?,
+        })
+    }
+
+    fn default_send_enabled(&self, bank_storage: &dyn Storage) -> AnyResult<bool> {
+        Ok(DEFAULT_SEND_ENABLED.may_load(bank_storage)?.unwrap_or(true))
+    }
+
+    /// Returns `true` when transfers of the denomination are enabled.
+    pub fn is_send_enabled(&self, storage: &dyn Storage, denom: &str) -> AnyResult<bool> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        match SEND_ENABLED.may_load(&bank_storage, denom)? {
+            Some(enabled) => Ok(enabled),
+            None => self.default_send_enabled(&bank_storage),
+        }
+    }
+
+    /// Mints tokens for the new vesting account.
+    fn create_vesting_account(
+        &self,
+        bank_storage: &mut dyn Storage,
+        to_address: Addr,
+        amount: Vec<Coin>,
+        start_time: Timestamp,
+        end_time: Timestamp,
+        schedule: VestingSchedule,
+    ) -> AnyResult<()> {
+        if end_time <= start_time {
+            bail!("invalid vesting account: end time must be after start time");
+        }
+        if VESTING_ACCOUNTS.has(bank_storage, &to_address) {
+            bail!("vesting account {} already exists", to_address);
+        }
+        let amount = self.normalize_amount(amount)?;
+        self.mint(bank_storage, to_address.clone(), amount.clone())?;
+        let vesting_account = VestingAccount {
+            original_vesting: NativeBalance(amount).into_vec(),
+            start_time,
+            end_time,
+            delegated_free: vec![],
+            delegated_vesting: vec![],
+            schedule,
+        };
+        VESTING_ACCOUNTS.save(bank_storage, &to_address, &vesting_account)?;
+        Ok(())
+    }
+
+    /// Returns tokens of the account that can be transferred at the time of the block,
+    /// i.e. the balance without tokens locked in vesting.
+    pub fn spendable_balance(
         &self,
-        storage: &mut dyn Storage,
+        storage: &dyn Storage,
+        block: &BlockInfo,
         account: &Addr,
-        amount: Vec<Coin>,
-    ) -> AnyResult<()> {
-        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
-        self.set_balance(&mut bank_storage, account, amount)
+    ) -> AnyResult<Vec<Coin>> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        let mut balance = self.get_balance(&bank_storage, account)?;
+        if let Some(vesting_account) = VESTING_ACCOUNTS.may_load(&bank_storage, account)? {
+            for locked in vesting_account.locked_coins(block.time) {
+                sub_amount(&mut balance, &locked.denom, locked.amount);
+            }
+        }
+        Ok(balance)
     }
 
-    /// Administration function for adjusting bank accounts.
-    fn set_balance(
+    /// Returns the vesting account for specified address, if such exists.
+    pub fn vesting_account(
         &self,
-        bank_storage: &mut dyn Storage,
+        storage: &dyn Storage,
         account: &Addr,
-        amount: Vec<Coin>,
-    ) -> AnyResult<()> {
-        let mut balance = NativeBalance(amount);
-        balance.normalize();
-        BALANCES
-            .save(bank_storage, account, &balance)
-            .map_err(Into::into)
+    ) -> AnyResult<Option<VestingAccount>> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        Ok(VESTING_ACCOUNTS.may_load(&bank_storage, account)?)
     }
 
-    /// Administration function for adjusting denomination metadata.
-    pub fn set_denom_metadata(
+    /// Registers the alias of the canonical denomination, like `ibc/{hash}` for `uatom`.
+    ///
+    /// Aliases are used only by helper conversions, like [canonical_coins](Self::canonical_coins)
+    /// and [canonical_balance](Self::canonical_balance); balances, transfers and queries
+    /// still use the original denominations.
+    pub fn register_denom_alias(
         &self,
-        bank_storage: &mut dyn Storage,
-        denom: String,
-        metadata: DenomMetadata,
+        storage: &mut dyn Storage,
+        alias: &str,
+        denom: &str,
     ) -> AnyResult<()> {
-        DENOM_METADATA
-            .save(bank_storage, denom, &metadata)
-            .map_err(Into::into)
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        if alias == denom {
+            bail!("denomination {} cannot be an alias of itself", denom);
+        }
+        if let Some(registered) = DENOM_ALIASES.may_load(&bank_storage, alias)? {
+            bail!(
+                "denomination {} is already an alias of {}",
+                alias,
+                registered
+            );
+        }
+        if let Some(registered) = DENOM_ALIASES.may_load(&bank_storage, denom)? {
+            bail!(
+                "denomination {} is an alias of {}, aliases cannot be chained",
+                denom,
+                registered
+            );
+        }
+        if DENOM_ALIASES
+            .range(&bank_storage, None, None, Order::Ascending)
+            .any(|entry| entry.is_ok_and(|(_, registered)| registered == alias))
+        {
+            bail!(
+                "denomination {} has aliases, aliases cannot be chained",
+                alias
+            );
+        }
+        DENOM_ALIASES.save(&mut bank_storage, alias, &denom.to_string())?;
+        Ok(())
+    }
+
+    /// Returns the canonical denomination of the alias,
+    /// denominations that are not aliases are returned unchanged.
+    pub fn canonical_denom(&self, storage: &dyn Storage, denom: &str) -> AnyResult<String> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        Ok(DENOM_ALIASES
+            .may_load(&bank_storage, denom)?
+            .unwrap_or_else(|| denom.to_string()))
+    }
+
+    /// Returns all registered aliases of the canonical denomination.
+    pub fn denom_aliases(&self, storage: &dyn Storage, denom: &str) -> AnyResult<Vec<String>> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        let mut aliases = vec![];
+        for entry in DENOM_ALIASES.range(&bank_storage, None, None, Order::Ascending) {
+            let (alias, registered) = entry?;
+            if registered == denom {
+                aliases.push(alias);
+            }
+        }
+        Ok(aliases)
+    }
+
+    /// Converts coins to canonical denominations, summing amounts of the same denomination.
+    pub fn canonical_coins(&self, storage: &dyn Storage, coins: &[Coin]) -> AnyResult<Vec<Coin>> {
+        let mut balance = NativeBalance::default();
+        for coin in coins {
+            balance += Coin::new(coin.amount, self.canonical_denom(storage, &coin.denom)?);
+        }
+        balance.normalize();
+        Ok(balance.into_vec())
+    }
+
+    /// Returns the balance of the account with all aliases converted to canonical denominations.
+    pub fn canonical_balance(&self, storage: &dyn Storage, account: &Addr) -> AnyResult<Vec<Coin>> {
+        let bank_storage = prefixed_read(storage, NAMESPACE_BANK);
+        let balance = self.get_balance(&bank_storage, account)?;
+        self.canonical_coins(storage, &balance)
     }
 
     /// Returns balance for specified address.
@@ -99,21 +1198,8 @@ impl BankKeeper {
     }
 
     fn get_supply(&self, bank_storage: &dyn Storage, denom: String) -> AnyResult<Coin> {
-        let supply: Uint128 = BALANCES
-            .range(bank_storage, None, None, Order::Ascending)
-            .collect::<StdResult<Vec<_>>>()?
-            .into_iter()
-            .map(|a| a.1)
-            .fold(Uint128::zero(), |accum, item| {
-                let mut subtotal = Uint128::zero();
-                for coin in item.into_vec() {
-                    if coin.denom == denom {
-                        subtotal += coin.amount;
-                    }
-                }
-                accum + subtotal
-            });
-        Ok(coin(supply.into(), denom))
+        let supply = SUPPLY.may_load(bank_storage, &denom)?.unwrap_or_default();
+        Ok(coin(supply.u128(), denom))
     }
 
     fn send(
@@ -123,8 +1209,34 @@ impl BankKeeper {
         to_address: Addr,
         amount: Vec<Coin>,
     ) -> AnyResult<()> {
-        self.burn(bank_storage, from_address, amount.clone())?;
-        self.mint(bank_storage, to_address, amount)
+        self.debit(bank_storage, &from_address, amount.clone())?;
+        self.credit(bank_storage, &to_address, amount)
+    }
+
+    /// Subtracts tokens from the balance of the account, without adjusting the total supply.
+    fn debit(
+        &self,
+        bank_storage: &mut dyn Storage,
+        account: &Addr,
+        amount: Vec<Coin>,
+    ) -> AnyResult<()> {
+        let amount = self.normalize_amount(amount)?;
+        let balance = self.get_balance(bank_storage, account)?;
+        let balance = (NativeBalance(balance) - amount)?;
+        self.save_balance(bank_storage, account, balance)
+    }
+
+    /// Adds tokens to the balance of the account, without adjusting the total supply.
+    fn credit(
+        &self,
+        bank_storage: &mut dyn Storage,
+        account: &Addr,
+        amount: Vec<Coin>,
+    ) -> AnyResult<()> {
+        let amount = self.normalize_amount(amount)?;
+        let balance = self.get_balance(bank_storage, account)?;
+        let balance = NativeBalance(balance) + NativeBalance(amount);
+        self.save_balance(bank_storage, account, balance)
     }
 
     fn mint(
@@ -134,21 +1246,130 @@ impl BankKeeper {
         amount: Vec<Coin>,
     ) -> AnyResult<()> {
         let amount = self.normalize_amount(amount)?;
-        let b = self.get_balance(bank_storage, &to_address)?;
-        let b = NativeBalance(b) + NativeBalance(amount);
-        self.set_balance(bank_storage, &to_address, b.into_vec())
+        for c in &amount {
+            self.increase_supply(bank_storage, &c.denom, c.amount)?;
+        }
+        self.credit(bank_storage, &to_address, amount)
+    }
+
+    /// Returns an error when the amount exceeds tokens that are not locked in vesting.
+    fn ensure_spendable(
+        &self,
+        bank_storage: &dyn Storage,
+        block: &BlockInfo,
+        account: &Addr,
+        amount: &[Coin],
+    ) -> AnyResult<()> {
+        let Some(vesting_account) = VESTING_ACCOUNTS.may_load(bank_storage, account)? else {
+            return Ok(());
+        };
+        let balance = self.get_balance(bank_storage, account)?;
+        for locked in vesting_account.locked_coins(block.time) {
+            let spendable = amount_of(&balance, &locked.denom).saturating_sub(locked.amount);
+            let requested = amount_of(amount, &locked.denom);
+            if requested > spendable {
+                bail!(
+                    "spendable balance {}{} is smaller than {}{}: insufficient funds",
+                    spendable,
+                    locked.denom,
+                    requested,
+                    locked.denom
+                );
+            }
+        }
+        Ok(())
     }
 
+    /// Burns tokens of the account and reduces the total supply,
+    /// returns events emitted like in Cosmos SDK.
     fn burn(
         &self,
         bank_storage: &mut dyn Storage,
         from_address: Addr,
         amount: Vec<Coin>,
-    ) -> AnyResult<()> {
+    ) -> AnyResult<Vec<Event>> {
         let amount = self.normalize_amount(amount)?;
-        let a = self.get_balance(bank_storage, &from_address)?;
-        let a = (NativeBalance(a) - amount)?;
-        self.set_balance(bank_storage, &from_address, a.into_vec())
+        self.debit(bank_storage, &from_address, amount.clone())?;
+        for c in &amount {
+            self.decrease_supply(bank_storage, &c.denom, c.amount)?;
+        }
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.50.6/x/bank/keeper/keeper.go#L377-L410
+        let mut burned = NativeBalance(amount);
+        burned.normalize();
+        let amount = coins_to_string(&burned.into_vec());
+        Ok(vec![
+            Event::new("coin_spent")
+                .add_attribute("spender", &from_address)
+                .add_attribute("amount", &amount),
+            Event::new("burn")
+                .add_attribute("burner", &from_address)
+                .add_attribute("amount", amount),
+        ])
+    }
+
+    /// Sends tokens from all inputs to all outputs, returns emitted events.
+    fn multi_send_coins(
+        &self,
+        api: &dyn Api,
+        bank_storage: &mut dyn Storage,
+        block: &BlockInfo,
+        inputs: Vec<MultiSendIo>,
+        outputs: Vec<MultiSendIo>,
+    ) -> AnyResult<Vec<Event>> {
+        if inputs.is_empty() {
+            bail!("no inputs to send transaction");
+        }
+        if outputs.is_empty() {
+            bail!("no outputs to send transaction");
+        }
+        let mut total_in = inputs.iter().fold(NativeBalance::default(), |acc, input| {
+            acc + NativeBalance(input.coins.clone())
+        });
+        let mut total_out = outputs
+            .iter()
+            .fold(NativeBalance::default(), |acc, output| {
+                acc + NativeBalance(output.coins.clone())
+            });
+        total_in.normalize();
+        total_out.normalize();
+        if total_in != total_out {
+            bail!(
+                "sum of inputs {} does not match sum of outputs {}",
+                coins_to_string(&total_in.into_vec()),
+                coins_to_string(&total_out.into_vec())
+            );
+        }
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/bank/keeper/send.go#L159-L215
+        ensure_send_enabled(bank_storage, &total_in.0)?;
+        let mut events = vec![];
+        let sender = (inputs.len() == 1).then(|| inputs[0].address.clone());
+        for input in inputs {
+            let address = api.addr_validate(&input.address)?;
+            self.ensure_spendable(bank_storage, block, &address, &input.coins)?;
+            self.debit(bank_storage, &address, input.coins.clone())?;
+            events.push(
+                Event::new("coin_spent")
+                    .add_attribute("spender", input.address)
+                    .add_attribute("amount", coins_to_string(&input.coins)),
+            );
+        }
+        for output in outputs {
+            let address = api.addr_validate(&output.address)?;
+            self.ensure_not_blocked(bank_storage, &address)?;
+            self.credit(bank_storage, &address, output.coins.clone())?;
+            let amount = coins_to_string(&output.coins);
+            events.push(
+                Event::new("coin_received")
+                    .add_attribute("receiver", &output.address)
+                    .add_attribute("amount", &amount),
+            );
+            let mut transfer = Event::new("transfer").add_attribute("recipient", output.address);
+            if let Some(sender) = &sender {
+                transfer = transfer.add_attribute("sender", sender);
+            }
+            events.push(transfer.add_attribute("amount", amount));
+        }
+        Ok(events)
     }
 
     /// Filters out all `0` value coins and returns an error if the resulting vector is empty.
@@ -169,42 +1390,125 @@ fn coins_to_string(coins: &[Coin]) -> String {
         .join(",")
 }
 
-impl Bank for BankKeeper {}
+/// Returns an error when transfers of any of the coins are disabled.
+fn ensure_send_enabled(bank_storage: &dyn Storage, amount: &[Coin]) -> AnyResult<()> {
+    let default_send_enabled = DEFAULT_SEND_ENABLED.may_load(bank_storage)?.unwrap_or(true);
+    for c in amount {
+        if !SEND_ENABLED
+            .may_load(bank_storage, &c.denom)?
+            .unwrap_or(default_send_enabled)
+        {
+            bail!(
+                "{} transfers are currently disabled: send transactions are disabled",
+                c.denom
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Returns an error when the message sent by a user or a contract transfers coins
+/// with disabled transfers, including funds attached to contract calls.
+/// Transfers made by the modules themselves, like unbonding payouts, are not checked.
+pub(crate) fn ensure_msg_send_enabled<T>(
+    storage: &dyn Storage,
+    msg: &CosmosMsg<T>,
+) -> AnyResult<()> {
+    let amount = match msg {
+        CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount,
+        CosmosMsg::Wasm(
+            WasmMsg::Execute { funds, .. }
+            | WasmMsg::Instantiate { funds, .. }
+            | WasmMsg::Instantiate2 { funds, .. },
+        ) => funds,
+        _ => return Ok(()),
+    };
+    ensure_send_enabled(&prefixed_read(storage, NAMESPACE_BANK), amount)
+}
+
+impl Bank for BankKeeper {
+    fn multi_send<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        inputs: Vec<MultiSendIo>,
+        mut outputs: Vec<MultiSendIo>,
+    ) -> AnyResult<AppResponse> {
+        // like in Cosmos SDK, the sender must be the owner of all inputs
+        if let Some(input) = inputs.iter().find(|input| input.address != sender.as_str()) {
+            bail!(
+                "input address {} does not match the signer {}",
+                input.address,
+                sender
+            );
+        }
+        for output in &mut outputs {
+            let recipient = Addr::unchecked(&output.address);
+            output.address = self
+                .apply_send_restrictions(&sender, recipient, &output.coins)?
+                .to_string();
+        }
+        let transfers = outputs
+            .iter()
+            .map(|output| {
+                let recipient = Addr::unchecked(&output.address);
+                (sender.clone(), recipient, output.coins.clone())
+            })
+            .collect();
+        let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
+        let mut events = self.multi_send_coins(api, &mut bank_storage, block, inputs, outputs)?;
+        events.extend(self.call_transfer_hooks(api, storage, router, block, transfers)?);
+        Ok(AppResponse { events, data: None })
+    }
+}
 
 impl Module for BankKeeper {
     type ExecT = BankMsg;
     type QueryT = BankQuery;
     type SudoT = BankSudo;
 
-    fn execute<ExecC, QueryC>(
+    fn execute<ExecC: CustomMsg, QueryC: CustomQuery>(
         &self,
-        _api: &dyn Api,
+        api: &dyn Api,
         storage: &mut dyn Storage,
-        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
-        _block: &BlockInfo,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
         sender: Addr,
         msg: BankMsg,
     ) -> AnyResult<AppResponse> {
         let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
         match msg {
             BankMsg::Send { to_address, amount } => {
+                let to_address =
+                    self.apply_send_restrictions(&sender, Addr::unchecked(to_address), &amount)?;
                 // see https://github.com/cosmos/cosmos-sdk/blob/v0.42.7/x/bank/keeper/send.go#L142-L147
                 let events = vec![Event::new("transfer")
                     .add_attribute("recipient", &to_address)
                     .add_attribute("sender", &sender)
                     .add_attribute("amount", coins_to_string(&amount))];
+                self.ensure_not_blocked(&bank_storage, &to_address)?;
+                self.ensure_spendable(&bank_storage, block, &sender, &amount)?;
                 self.send(
                     &mut bank_storage,
-                    sender,
-                    Addr::unchecked(to_address),
-                    amount,
+                    sender.clone(),
+                    to_address.clone(),
+                    amount.clone(),
                 )?;
-                Ok(AppResponse { events, data: None })
+                let transfers = vec![(sender, to_address, amount)];
+                let hook_events =
+                    self.call_transfer_hooks(api, storage, router, block, transfers)?;
+                Ok(AppResponse {
+                    events: events.into_iter().chain(hook_events).collect(),
+                    data: None,
+                })
             }
             BankMsg::Burn { amount } => {
-                // burn doesn't seem to emit any events
-                self.burn(&mut bank_storage, sender, amount)?;
-                Ok(AppResponse::default())
+                self.ensure_spendable(&bank_storage, block, &sender, &amount)?;
+                let events = self.burn(&mut bank_storage, sender, amount)?;
+                Ok(AppResponse { events, data: None })
             }
             other => unimplemented!("bank message: {other:?}"),
         }
@@ -242,28 +1546,63 @@ impl Module for BankKeeper {
                 to_json_binary(&res).map_err(Into::into)
             }
             BankQuery::DenomMetadata { denom } => {
-                let meta = DENOM_METADATA.may_load(storage, denom)?.unwrap_or_default();
+                let Some(meta) = DENOM_METADATA.may_load(&bank_storage, denom.clone())? else {
+                    bail!("client metadata for denom {}", denom);
+                };
                 let res = DenomMetadataResponse::new(meta);
                 to_json_binary(&res).map_err(Into::into)
             }
-            BankQuery::AllDenomMetadata { pagination: _ } => {
-                let mut metadata = vec![];
-                for key in DENOM_METADATA.keys(storage, None, None, Order::Ascending) {
-                    metadata.push(DENOM_METADATA.may_load(storage, key?)?.unwrap_or_default());
-                }
-                let res = AllDenomMetadataResponse::new(metadata, None);
+            BankQuery::AllDenomMetadata { pagination } => {
+                let (metadata, next_key) = match pagination {
+                    Some(page) => {
+                        // the key of the page is the first denomination on that page
+                        let start = page
+                            .key
+                            .map(|key| String::from_utf8(key.to_vec()))
+                            .transpose()?
+                            .map(Bound::inclusive);
+                        let (min, max, order) = if page.reverse {
+                            (None, start, Order::Descending)
+                        } else {
+                            (start, None, Order::Ascending)
+                        };
+                        let limit = if page.limit == 0 {
+                            usize::MAX
+                        } else {
+                            page.limit as usize
+                        };
+                        let mut entries = DENOM_METADATA
+                            .range(&bank_storage, min, max, order)
+                            .take(limit.saturating_add(1))
+                            .collect::<StdResult<Vec<_>>>()?;
+                        let next_key = (entries.len() > limit)
+                            .then(|| entries.pop())
+                            .flatten()
+                            .map(|(denom, _)| Binary::from(denom.into_bytes()));
+                        let metadata = entries.into_iter().map(|(_, meta)| meta).collect();
+                        (metadata, next_key)
+                    }
+                    None => {
+                        let metadata = DENOM_METADATA
+                            .range(&bank_storage, None, None, Order::Ascending)
+                            .map(|entry| entry.map(|(_, meta)| meta))
+                            .collect::<StdResult<Vec<_>>>()?;
+                        (metadata, None)
+                    }
+                };
+                let res = AllDenomMetadataResponse::new(metadata, next_key);
                 to_json_binary(&res).map_err(Into::into)
             }
             other => unimplemented!("bank query: {other:?}"),
         }
     }
 
-    fn sudo<ExecC, QueryC>(
+    fn sudo<ExecC: CustomMsg, QueryC: CustomQuery>(
         &self,
         api: &dyn Api,
         storage: &mut dyn Storage,
         _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
-        _block: &BlockInfo,
+        block: &BlockInfo,
         msg: BankSudo,
     ) -> AnyResult<AppResponse> {
         let mut bank_storage = prefixed(storage, NAMESPACE_BANK);
@@ -273,6 +1612,158 @@ impl Module for BankKeeper {
                 self.mint(&mut bank_storage, to_address, amount)?;
                 Ok(AppResponse::default())
             }
+            BankSudo::SetBalance { address, amount } => {
+                let address = api.addr_validate(&address)?;
+                self.set_balance(&mut bank_storage, &address, amount)?;
+                Ok(AppResponse::default())
+            }
+            BankSudo::CreateVestingAccount {
+                to_address,
+                amount,
+                start_time,
+                end_time,
+            } => {
+                let to_address = api.addr_validate(&to_address)?;
+                self.create_vesting_account(
+                    &mut bank_storage,
+                    to_address,
+                    amount,
+                    start_time,
+                    end_time,
+                    VestingSchedule::Continuous,
+                )?;
+                Ok(AppResponse::default())
+            }
+            BankSudo::CreateDelayedVestingAccount {
+                to_address,
+                amount,
+                end_time,
+            } => {
+                let to_address = api.addr_validate(&to_address)?;
+                self.create_vesting_account(
+                    &mut bank_storage,
+                    to_address,
+                    amount,
+                    block.time,
+                    end_time,
+                    VestingSchedule::Delayed,
+                )?;
+                Ok(AppResponse::default())
+            }
+            BankSudo::CreatePeriodicVestingAccount {
+                to_address,
+                start_time,
+                periods,
+            } => {
+                let to_address = api.addr_validate(&to_address)?;
+                let amount = periods
+                    .iter()
+                    .fold(NativeBalance::default(), |acc, period| {
+                        acc + NativeBalance(period.amount.clone())
+                    })
+                    .into_vec();
+                let end_time = start_time
+                    .plus_seconds(periods.iter().map(|period| period.length).sum::<u64>());
+                self.create_vesting_account(
+                    &mut bank_storage,
+                    to_address,
+                    amount,
+                    start_time,
+                    end_time,
+                    VestingSchedule::Periodic(periods),
+                )?;
+                Ok(AppResponse::default())
+            }
+            BankSudo::TrackDelegation { delegator, amount } => {
+                let delegator = api.addr_validate(&delegator)?;
+                if let Some(mut vesting_account) =
+                    VESTING_ACCOUNTS.may_load(&bank_storage, &delegator)?
+                {
+                    let vesting = vesting_account.vesting_coins(block.time);
+                    vesting_account.track_delegation(&vesting, &amount);
+                    VESTING_ACCOUNTS.save(&mut bank_storage, &delegator, &vesting_account)?;
+                }
+                Ok(AppResponse::default())
+            }
+            BankSudo::TrackUndelegation { delegator, amount } => {
+                let delegator = api.addr_validate(&delegator)?;
+                if let Some(mut vesting_account) =
+                    VESTING_ACCOUNTS.may_load(&bank_storage, &delegator)?
+                {
+                    vesting_account.track_undelegation(&amount);
+                    VESTING_ACCOUNTS.save(&mut bank_storage, &delegator, &vesting_account)?;
+                }
+                Ok(AppResponse::default())
+            }
+            BankSudo::SetDenomMetadata { denom, metadata } => {
+                if denom.is_empty() {
+                    bail!("denomination of the metadata must not be empty");
+                }
+                DENOM_METADATA.save(&mut bank_storage, denom, &metadata)?;
+                Ok(AppResponse::default())
+            }
+            BankSudo::SetSendEnabled { denom, enabled } => {
+                SEND_ENABLED.save(&mut bank_storage, &denom, &enabled)?;
+                let param = format!("send_enabled/{}", denom);
+                record_param_change(storage, block.height, "bank", &param, enabled)?;
+                Ok(AppResponse::default())
+            }
+            BankSudo::SetParams { params } => {
+                let old_params = self.load_params(&bank_storage)?;
+                let duplicate = params
+                    .send_enabled
+                    .iter()
+                    .map(|flag| &flag.denom)
+                    .duplicates()
+                    .next();
+                if let Some(denom) = duplicate {
+                    bail!("duplicate send-enabled flag for denom {}", denom);
+                }
+                for flag in &old_params.send_enabled {
+                    SEND_ENABLED.remove(&mut bank_storage, &flag.denom);
+                }
+                for flag in &params.send_enabled {
+                    SEND_ENABLED.save(&mut bank_storage, &flag.denom, &flag.enabled)?;
+                }
+                DEFAULT_SEND_ENABLED.save(&mut bank_storage, &params.default_send_enabled)?;
+                // record changes of effective flags only
+                let new_params = self.load_params(&bank_storage)?;
+                if new_params.default_send_enabled != old_params.default_send_enabled {
+                    let enabled = new_params.default_send_enabled;
+                    record_param_change(
+                        storage,
+                        block.height,
+                        "bank",
+                        "default_send_enabled",
+                        enabled,
+                    )?;
+                }
+                let old_flags = old_params
+                    .send_enabled
+                    .into_iter()
+                    .map(|flag| (flag.denom, flag.enabled))
+                    .collect::<BTreeMap<_, _>>();
+                let new_flags = new_params
+                    .send_enabled
+                    .into_iter()
+                    .map(|flag| (flag.denom, flag.enabled))
+                    .collect::<BTreeMap<_, _>>();
+                for denom in old_flags.keys().chain(new_flags.keys()).unique() {
+                    let old_enabled = old_flags
+                        .get(denom)
+                        .copied()
+                        .unwrap_or(old_params.default_send_enabled);
+                    let new_enabled = new_flags
+                        .get(denom)
+                        .copied()
+                        .unwrap_or(new_params.default_send_enabled);
+                    if old_enabled != new_enabled {
+                        let param = format!("send_enabled/{}", denom);
+                        record_param_change(storage, block.height, "bank", &param, new_enabled)?;
+                    }
+                }
+                Ok(AppResponse::default())
+            }
         }
     }
 }
@@ -283,7 +1774,7 @@ mod test {
 
     use crate::app::MockRouter;
     use cosmwasm_std::testing::{mock_env, MockApi, MockQuerier, MockStorage};
-    use cosmwasm_std::{coins, from_json, Empty, StdError};
+    use cosmwasm_std::{coins, from_json, DenomUnit, Empty, PageRequest, StdError};
 
     fn query_balance(
         bank: &BankKeeper,
@@ -468,10 +1959,23 @@ mod test {
         // burn both tokens
         let to_burn = vec![coin(30, "eth"), coin(5, "btc")];
         let msg = BankMsg::Burn { amount: to_burn };
-        bank.execute(&api, &mut store, &router, &block, owner.clone(), msg)
+        let res = bank
+            .execute(&api, &mut store, &router, &block, owner.clone(), msg)
             .unwrap();
         let rich = query_balance(&bank, &api, &store, &owner);
         assert_eq!(vec![coin(15, "btc"), coin(70, "eth")], rich);
+        assert_eq!(coin(15, "btc"), bank.supply(&store, "btc").unwrap());
+        assert_eq!(
+            vec![
+                Event::new("coin_spent")
+                    .add_attribute("spender", &owner)
+                    .add_attribute("amount", "5btc,30eth"),
+                Event::new("burn")
+                    .add_attribute("burner", &owner)
+                    .add_attribute("amount", "5btc,30eth"),
+            ],
+            res.events
+        );
 
         // cannot burn too much
         let msg = BankMsg::Burn {
@@ -495,6 +1999,71 @@ mod test {
         assert!(matches!(err.downcast().unwrap(), StdError::Overflow { .. }));
     }
 
+    #[test]
+    fn supply_should_be_tracked() {
+        let api = MockApi::default();
+        let mut store = MockStorage::new();
+        let block = mock_env().block;
+        let router = MockRouter::default();
+        let bank = BankKeeper::new();
+
+        let owner = api.addr_make("owner");
+        let rcpt = api.addr_make("recipient");
+
+        // genesis balances are counted
+        bank.init_balance(&mut store, &owner, vec![coin(20, "btc"), coin(100, "eth")])
+            .unwrap();
+        bank.init_balance(&mut store, &rcpt, coins(5, "btc"))
+            .unwrap();
+        assert_eq!(coin(25, "btc"), bank.supply(&store, "btc").unwrap());
+
+        // overwriting the genesis balance adjusts the supply
+        bank.init_balance(&mut store, &rcpt, coins(7, "atom"))
+            .unwrap();
+        assert_eq!(
+            vec![coin(7, "atom"), coin(20, "btc"), coin(100, "eth")],
+            bank.all_supply(&store).unwrap()
+        );
+
+        // minting and burning changes the supply, sending does not
+        let msg = BankSudo::Mint {
+            to_address: rcpt.to_string(),
+            amount: coins(10, "btc"),
+        };
+        bank.sudo(&api, &mut store, &router, &block, msg).unwrap();
+        let msg = BankMsg::Send {
+            to_address: rcpt.to_string(),
+            amount: coins(50, "eth"),
+        };
+        bank.execute(&api, &mut store, &router, &block, owner.clone(), msg)
+            .unwrap();
+        let msg = BankMsg::Burn {
+            amount: coins(100, "eth"),
+        };
+        bank.execute(&api, &mut store, &router, &block, owner.clone(), msg)
+            .unwrap_err();
+        let msg = BankMsg::Burn {
+            amount: coins(7, "atom"),
+        };
+        bank.execute(&api, &mut store, &router, &block, rcpt.clone(), msg)
+            .unwrap();
+        assert_eq!(
+            vec![coin(30, "btc"), coin(100, "eth")],
+            bank.all_supply(&store).unwrap()
+        );
+
+        // supply query returns the tracked supply, zero for unknown denominations
+        let req = BankQuery::Supply {
+            denom: "btc".to_string(),
+        };
+        let raw = bank
+            .query(&api, &store, &MockQuerier::default(), &block, req)
+            .unwrap();
+        let res: SupplyResponse = from_json(raw).unwrap();
+        assert_eq!(coin(30, "btc"), res.amount);
+        assert_eq!(coin(0, "atom"), bank.supply(&store, "atom").unwrap());
+    }
+
     #[test]
     fn set_get_denom_metadata_should_work() {
         let api = MockApi::default();
@@ -559,6 +2128,231 @@ mod test {
         assert_eq!(res.metadata[1].name, denom_eth_name);
     }
 
+    #[test]
+    fn denom_metadata_should_be_set_with_sudo_and_paginated() {
+        let api = MockApi::default();
+        let mut store = MockStorage::new();
+        let block = mock_env().block;
+        let router = MockRouter::default();
+        let querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        let bank = BankKeeper::new();
+        for denom in ["uatom", "ubtc", "ueth"] {
+            let msg = BankSudo::SetDenomMetadata {
+                denom: denom.to_string(),
+                metadata: DenomMetadata {
+                    base: denom.to_string(),
+                    display: denom[1..].to_string(),
+                    symbol: denom[1..].to_uppercase(),
+                    denom_units: vec![
+                        DenomUnit {
+                            denom: denom.to_string(),
+                            exponent: 0,
+                            aliases: vec![],
+                        },
+                        DenomUnit {
+                            denom: denom[1..].to_string(),
+                            exponent: 6,
+                            aliases: vec![],
+                        },
+                    ],
+                    ..Default::default()
+                },
+            };
+            bank.sudo(&api, &mut store, &router, &block, msg).unwrap();
+        }
+        assert_eq!(
+            "BTC",
+            bank.denom_metadata(&store, "ubtc").unwrap().unwrap().symbol
+        );
+
+        // unknown denomination is reported like in Cosmos SDK
+        let req = BankQuery::DenomMetadata {
+            denom: "uosmo".to_string(),
+        };
+        let err = bank.query(&api, &store, &querier, &block, req).unwrap_err();
+        assert_eq!("client metadata for denom uosmo", err.to_string());
+
+        // metadata is paginated
+        let query_page = |key: Option<Binary>, reverse: bool| {
+            let req = BankQuery::AllDenomMetadata {
+                pagination: Some(PageRequest {
+                    key,
+                    limit: 2,
+                    reverse,
+                }),
+            };
+            let raw = bank.query(&api, &store, &querier, &block, req).unwrap();
+            let res: AllDenomMetadataResponse = from_json(raw).unwrap();
+            let bases: Vec<_> = res.metadata.into_iter().map(|m| m.base).collect();
+            (bases, res.next_key)
+        };
+        let (bases, next_key) = query_page(None, false);
+        assert_eq!(vec!["uatom", "ubtc"], bases);
+        let (bases, next_key) = query_page(next_key, false);
+        assert_eq!(vec!["ueth"], bases);
+        assert_eq!(None, next_key);
+        let (bases, next_key) = query_page(None, true);
+        assert_eq!(vec!["ueth", "ubtc"], bases);
+        let (bases, _) = query_page(next_key, true);
+        assert_eq!(vec!["uatom"], bases);
+    }
+
+    #[test]
+    fn init_balances_should_adjust_supply_once() {
+        let mut store = MockStorage::new();
+        let bank = BankKeeper::new();
+        bank.init_balance(&mut store, &Addr::unchecked("account0"), coins(7, "uosmo"))
+            .unwrap();
+
+        // the first account is overwritten, the last account is listed twice
+        let balances = (0..10_000)
+            .map(|i| (Addr::unchecked(format!("account{}", i)), coins(10, "uatom")))
+            .chain([(Addr::unchecked("account9999"), coins(1, "ubtc"))]);
+        bank.init_balances(&mut store, balances).unwrap();
+
+        assert_eq!(
+            vec![coin(99_990, "uatom"), coin(1, "ubtc")],
+            bank.all_supply(&store).unwrap()
+        );
+        let balance = |name: &str| {
+            let bank_storage = prefixed_read(&store, NAMESPACE_BANK);
+            bank.get_balance(&bank_storage, &Addr::unchecked(name))
+                .unwrap()
+        };
+        assert_eq!(coins(10, "uatom"), balance("account0"));
+        assert_eq!(coins(10, "uatom"), balance("account5000"));
+        assert_eq!(coins(1, "ubtc"), balance("account9999"));
+    }
+
+    #[test]
+    fn denom_owners_should_be_paginated() {
+        let mut store = MockStorage::new();
+        let bank = BankKeeper::new();
+        for (name, amount) in [("a", 30), ("b", 0), ("c", 10), ("d", 20)] {
+            let mut balance = coins(5, "ubtc");
+            if amount > 0 {
+                balance.push(coin(amount, "uatom"));
+            }
+            bank.init_balance(&mut store, &Addr::unchecked(name), balance)
+                .unwrap();
+        }
+
+        // all holders are returned without pagination, accounts without the denom are skipped
+        let res = bank.denom_owners(&store, "uatom", None).unwrap();
+        assert_eq!(
+            vec![
+                (Addr::unchecked("a"), coin(30, "uatom")),
+                (Addr::unchecked("c"), coin(10, "uatom")),
+                (Addr::unchecked("d"), coin(20, "uatom")),
+            ],
+            res.denom_owners
+                .into_iter()
+                .map(|owner| (owner.address, owner.balance))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(None, res.next_key);
+
+        let query_page = |key: Option<Binary>, reverse: bool| {
+            let pagination = Some(PageRequest {
+                key,
+                limit: 2,
+                reverse,
+            });
+            let res = bank.denom_owners(&store, "uatom", pagination).unwrap();
+            let owners: Vec<_> = res
+                .denom_owners
+                .into_iter()
+                .map(|owner| owner.address.to_string())
+                .collect();
+            (owners, res.next_key)
+        };
+        let (owners, next_key) = query_page(None, false);
+        assert_eq!(vec!["a", "c"], owners);
+        let (owners, next_key) = query_page(next_key, false);
+        assert_eq!(vec!["d"], owners);
+        assert_eq!(None, next_key);
+        let (owners, next_key) = query_page(None, true);
+        assert_eq!(vec!["d", "c"], owners);
+        let (owners, _) = query_page(next_key, true);
+        assert_eq!(vec!["a"], owners);
+
+        // unknown denomination has no holders
+        let res = bank.denom_owners(&store, "ueth", None).unwrap();
+        assert!(res.denom_owners.is_empty());
+    }
+
+    #[test]
+    fn denom_aliases_should_work() {
+        let api = MockApi::default();
+        let mut store = MockStorage::new();
+        let bank = BankKeeper::new();
+        let owner = api.addr_make("owner");
+        let ibc_atom = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+        bank.init_balance(
+            &mut store,
+            &owner,
+            vec![coin(100, "uatom"), coin(20, ibc_atom), coin(5, "uosmo")],
+        )
+        .unwrap();
+
+        // without aliases, denominations are not converted
+        assert_eq!("uatom", bank.canonical_denom(&store, "uatom").unwrap());
+        assert_eq!(ibc_atom, bank.canonical_denom(&store, ibc_atom).unwrap());
+
+        // aliases are converted to canonical denominations
+        bank.register_denom_alias(&mut store, ibc_atom, "uatom")
+            .unwrap();
+        assert_eq!("uatom", bank.canonical_denom(&store, ibc_atom).unwrap());
+        assert_eq!(
+            vec![ibc_atom.to_string()],
+            bank.denom_aliases(&store, "uatom").unwrap()
+        );
+        assert_eq!(
+            vec![coin(120, "uatom"), coin(5, "uosmo")],
+            bank.canonical_balance(&store, &owner).unwrap()
+        );
+        assert_eq!(
+            vec![coin(3, "uatom")],
+            bank.canonical_coins(&store, &[coin(1, ibc_atom), coin(2, "uatom")])
+                .unwrap()
+        );
+
+        // balances keep original denominations
+        assert_eq!(
+            vec![coin(20, ibc_atom), coin(100, "uatom"), coin(5, "uosmo")],
+            query_balance(&bank, &api, &store, &owner)
+        );
+
+        // aliases cannot be redefined nor chained
+        assert_eq!(
+            format!("denomination {} is already an alias of uatom", ibc_atom),
+            bank.register_denom_alias(&mut store, ibc_atom, "uosmo")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            format!(
+                "denomination {} is an alias of uatom, aliases cannot be chained",
+                ibc_atom
+            ),
+            bank.register_denom_alias(&mut store, "ibc/ABC", ibc_atom)
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "denomination uatom has aliases, aliases cannot be chained",
+            bank.register_denom_alias(&mut store, "uatom", "uosmo")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "denomination uosmo cannot be an alias of itself",
+            bank.register_denom_alias(&mut store, "uosmo", "uosmo")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
     #[test]
     fn fail_on_zero_values() {
         let api = MockApi::default();
@@ -0,0 +1,322 @@
+//! # Resource budgets
+//!
+//! Large simulation tests may accidentally run quadratic (or worse) scenario loops,
+//! slowing down the whole CI. [ResourceBudget] limits the total resources simulated
+//! by the [App](crate::App): the number of processed messages, the simulated gas
+//! and the number of produced blocks. When any limit is exceeded, the test is aborted
+//! with a panic reporting the resources used so far.
+//!
+//! The gas is simulated by charging storage accesses made while processing messages,
+//! using the default key-value store gas costs of Cosmos SDK. The computation performed
+//! by contracts is not charged, so the simulated gas is lower than the gas used on a real chain.
+//!
+//! Like system contracts whitelisted on some chains, selected contracts can be made gas-free
+//! with [add_gas_free_contract](crate::App::add_gas_free_contract). Messages executed
+//! on gas-free contracts, including all messages sent while processing them, are not charged
+//! (messages and blocks are still counted), so infrastructure contracts do not distort
+//! the gas used by contracts under test.
+
+use cosmwasm_std::{Addr, CosmosMsg, Order, Record, Storage, WasmMsg};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Gas charged for reading a value from storage.
+const READ_COST_FLAT: u64 = 1000;
+/// Gas charged for every byte read from storage.
+const READ_COST_PER_BYTE: u64 = 3;
+/// Gas charged for writing a value to storage.
+const WRITE_COST_FLAT: u64 = 2000;
+/// Gas charged for every byte written to storage.
+const WRITE_COST_PER_BYTE: u64 = 30;
+/// Gas charged for deleting a value from storage.
+const DELETE_COST: u64 = 1000;
+/// Gas charged for moving the storage iterator to the next value.
+const ITER_NEXT_COST_FLAT: u64 = 30;
+
+/// Limits of resources simulated by the [App](crate::App).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceBudget {
+    max_gas: Option<u64>,
+    max_msgs: Option<u64>,
+    max_blocks: Option<u64>,
+}
+
+impl ResourceBudget {
+    /// Creates a budget without any limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the total simulated gas used while processing messages.
+    pub fn with_max_gas(mut self, max_gas: u64) -> Self {
+        self.max_gas = Some(max_gas);
+        self
+    }
+
+    /// Limits the total number of messages processed by the [Router](crate::Router),
+    /// including messages sent by contracts and modules.
+    pub fn with_max_msgs(mut self, max_msgs: u64) -> Self {
+        self.max_msgs = Some(max_msgs);
+        self
+    }
+
+    /// Limits the total number of blocks produced with [update_block](crate::App::update_block)
+    /// or [set_block](crate::App::set_block).
+    pub fn with_max_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_blocks = Some(max_blocks);
+        self
+    }
+}
+
+/// Resources used since the [ResourceBudget] was set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// Simulated gas used while processing messages.
+    pub gas: u64,
+    /// Number of processed messages.
+    pub msgs: u64,
+    /// Number of produced blocks.
+    pub blocks: u64,
+}
+
+/// State of the budget being enforced.
+#[derive(Clone, Default)]
+struct BudgetState {
+    budget: ResourceBudget,
+    usage: ResourceUsage,
+    /// Flag indicating that the storage is already metered by the outer message.
+    metering: bool,
+    /// Description of the last message processed by the router.
+    last_msg: String,
+}
+
+/// Report of the exceeded budget.
+struct BudgetReport<'a>(&'a BudgetState, &'static str);
+
+impl fmt::Display for BudgetReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let BudgetReport(state, exceeded) = self;
+        let limit = |limit: Option<u64>| limit.map_or("unlimited".to_string(), |l| l.to_string());
+        writeln!(f, "resource budget exceeded: {}", exceeded)?;
+        writeln!(
+            f,
+            "  gas:      {} / {}",
+            state.usage.gas,
+            limit(state.budget.max_gas)
+        )?;
+        writeln!(
+            f,
+            "  messages: {} / {}",
+            state.usage.msgs,
+            limit(state.budget.max_msgs)
+        )?;
+        writeln!(
+            f,
+            "  blocks:   {} / {}",
+            state.usage.blocks,
+            limit(state.budget.max_blocks)
+        )?;
+        write!(f, "  last message: {}", state.last_msg)
+    }
+}
+
+/// Enforces the [ResourceBudget] in the [Router](crate::Router), when set.
+#[derive(Clone, Default)]
+pub(crate) struct BudgetMeter {
+    /// State of the budget, when set.
+    state: RefCell<Option<BudgetState>>,
+    /// Contracts exempted from charging the gas.
+    gas_free: RefCell<BTreeSet<Addr>>,
+    /// Flag indicating that the currently processed message is not charged.
+    exempt: Cell<bool>,
+}
+
+impl BudgetMeter {
+    /// Starts enforcing the budget, resources used so far are forgotten.
+    pub fn set(&self, budget: ResourceBudget) {
+        *self.state.borrow_mut() = Some(BudgetState {
+            budget,
+            ..Default::default()
+        });
+    }
+
+    /// Stops enforcing the budget.
+    pub fn clear(&self) {
+        *self.state.borrow_mut() = None;
+    }
+
+    /// Exempts the contract from charging the gas.
+    pub fn add_gas_free(&self, contract_addr: Addr) {
+        self.gas_free.borrow_mut().insert(contract_addr);
+    }
+
+    /// Removes the gas exemption of the contract.
+    pub fn remove_gas_free(&self, contract_addr: &Addr) {
+        self.gas_free.borrow_mut().remove(contract_addr);
+    }
+
+    /// Returns gas-free contracts.
+    pub fn gas_free(&self) -> Vec<Addr> {
+        self.gas_free.borrow().iter().cloned().collect()
+    }
+
+    /// Returns resources used since the budget was set.
+    pub fn usage(&self) -> ResourceUsage {
+        self.state
+            .borrow()
+            .as_ref()
+            .map(|state| state.usage.clone())
+            .unwrap_or_default()
+    }
+
+    /// Counts the produced block, panics when the budget is exceeded.
+    pub fn count_block(&self) {
+        let mut state = self.state.borrow_mut();
+        let Some(state) = state.as_mut() else {
+            return;
+        };
+        state.usage.blocks += 1;
+        if state
+            .budget
+            .max_blocks
+            .is_some_and(|max| state.usage.blocks > max)
+        {
+            panic!("{}", BudgetReport(state, "too many blocks"));
+        }
+    }
+
+    /// Counts the message before it is processed, panics when the budget is exceeded.
+    /// Returns `true` when the storage should be metered while processing the message,
+    /// i.e. the budget is set and the message is not nested in another metered message.
+    pub fn enter(&self, msg: &impl fmt::Debug) -> bool {
+        let mut state = self.state.borrow_mut();
+        let Some(state) = state.as_mut() else {
+            return false;
+        };
+        state.usage.msgs += 1;
+        state.last_msg = format!("{:?}", msg);
+        if state
+            .budget
+            .max_msgs
+            .is_some_and(|max| state.usage.msgs > max)
+        {
+            panic!("{}", BudgetReport(state, "too many messages"));
+        }
+        !std::mem::replace(&mut state.metering, true)
+    }
+
+    /// Returns `true` when the message should not be charged, i.e. it is executed
+    /// on a gas-free contract or it is nested in a message that is not charged.
+    pub fn is_exempt<C>(&self, msg: &CosmosMsg<C>) -> bool {
+        self.exempt.get()
+            || match msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. })
+                | CosmosMsg::Wasm(WasmMsg::Migrate { contract_addr, .. }) => self
+                    .gas_free
+                    .borrow()
+                    .contains(&Addr::unchecked(contract_addr)),
+                _ => false,
+            }
+    }
+
+    /// Processes the message with the storage charging the gas when `metered` is `true`,
+    /// panics when the budget is exceeded. Gas is not charged while processing
+    /// the `exempt` message and messages nested in it.
+    pub fn metered<T>(
+        &self,
+        metered: bool,
+        exempt: bool,
+        storage: &mut dyn Storage,
+        action: impl FnOnce(&mut dyn Storage) -> T,
+    ) -> T {
+        let outer_exempt = self.exempt.replace(exempt);
+        if !metered {
+            let result = action(storage);
+            self.exempt.set(outer_exempt);
+            return result;
+        }
+        let result = action(&mut MeteredStorage {
+            storage,
+            charge: &|gas| self.charge(gas),
+        });
+        self.exempt.set(outer_exempt);
+        if let Some(state) = self.state.borrow_mut().as_mut() {
+            state.metering = false;
+            if state
+                .budget
+                .max_gas
+                .is_some_and(|max| state.usage.gas > max)
+            {
+                panic!("{}", BudgetReport(state, "out of gas"));
+            }
+        }
+        result
+    }
+
+    /// Charges the gas for accessing the storage, unless the processed message is gas-free.
+    fn charge(&self, gas: u64) {
+        if self.exempt.get() {
+            return;
+        }
+        if let Some(state) = self.state.borrow_mut().as_mut() {
+            state.usage.gas = state.usage.gas.saturating_add(gas);
+        }
+    }
+}
+
+/// Processes the action with the storage counting the gas for every access,
+/// returns the result of the action and the gas used, independently of any budget.
+pub(crate) fn measure_gas<T>(
+    storage: &mut dyn Storage,
+    action: impl FnOnce(&mut dyn Storage) -> T,
+) -> (T, u64) {
+    let used = Cell::new(0_u64);
+    let result = action(&mut MeteredStorage {
+        storage,
+        charge: &|gas| used.set(used.get().saturating_add(gas)),
+    });
+    (result, used.get())
+}
+
+/// Storage charging the gas for every access.
+struct MeteredStorage<'a> {
+    storage: &'a mut dyn Storage,
+    charge: &'a dyn Fn(u64),
+}
+
+impl Storage for MeteredStorage<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.storage.get(key);
+        let len = value.as_ref().map_or(0, Vec::len) as u64;
+        (self.charge)(READ_COST_FLAT + READ_COST_PER_BYTE * len);
+        value
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        let charge = self.charge;
+        Box::new(
+            self.storage
+                .range(start, end, order)
+                .inspect(move |(k, v)| {
+                    charge(ITER_NEXT_COST_FLAT + READ_COST_PER_BYTE * (k.len() + v.len()) as u64);
+                }),
+        )
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        let len = (key.len() + value.len()) as u64;
+        (self.charge)(WRITE_COST_FLAT + WRITE_COST_PER_BYTE * len);
+        self.storage.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        (self.charge)(DELETE_COST);
+        self.storage.remove(key);
+    }
+}
@@ -0,0 +1,65 @@
+use cosmwasm_std::{Order, Record, Storage};
+use std::collections::BTreeMap;
+use std::iter;
+use std::ops::Bound;
+
+/// In-memory storage that can be cloned.
+///
+/// `MockStorage` can not be cloned, so the [App](crate::App) built with this storage
+/// using [with_storage](crate::AppBuilder::with_storage) should be used
+/// to branch test scenarios from a common setup.
+///
+/// # Example
+///
+/// ```
+/// use cw_multi_test::{no_init, AppBuilder, CloneableStorage};
+///
+/// let app = AppBuilder::default()
+///     .with_storage(CloneableStorage::default())
+///     .build(no_init);
+/// let branch = app.clone();
+/// assert_eq!(app.block_info(), branch.block_info());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CloneableStorage {
+    data: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Storage for CloneableStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.data.get(key).cloned()
+    }
+
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Box::new(iter::empty());
+            }
+        }
+        let bounds = (
+            start.map_or(Bound::Unbounded, |start| Bound::Included(start.to_vec())),
+            end.map_or(Bound::Unbounded, |end| Bound::Excluded(end.to_vec())),
+        );
+        let records = self
+            .data
+            .range(bounds)
+            .map(|(key, value)| (key.clone(), value.clone()));
+        match order {
+            Order::Ascending => Box::new(records),
+            Order::Descending => Box::new(records.rev()),
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.data.insert(key.to_vec(), value.to_vec());
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.data.remove(key);
+    }
+}
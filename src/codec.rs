@@ -0,0 +1,25 @@
+//! # Codecs of messages passed to contract's entry-points
+//!
+//! [ContractWrapper](crate::ContractWrapper) decodes messages passed to entry-points from JSON,
+//! like contracts built with `cosmwasm-std`. Contracts using other encodings
+//! (MessagePack, protobuf, or binary inputs generated by fuzzers) can be wrapped
+//! by providing a [MessageCodec] that converts their messages into JSON.
+
+use crate::error::AnyResult;
+
+/// Interface of codecs converting raw messages passed to contract's entry-points
+/// into JSON, deserialized later into message types of the entry-points.
+pub trait MessageCodec {
+    /// Converts the raw message into JSON.
+    fn to_json(&self, msg: Vec<u8>) -> AnyResult<Vec<u8>>;
+}
+
+/// Default codec, passing JSON messages unchanged.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonCodec;
+
+impl MessageCodec for JsonCodec {
+    fn to_json(&self, msg: Vec<u8>) -> AnyResult<Vec<u8>> {
+        Ok(msg)
+    }
+}
@@ -1,13 +1,21 @@
 //! # Implementation of the contract trait and contract wrapper
 
+use crate::async_entry_points::{block_on, AsyncContractFn, AsyncQueryFn};
+use crate::codec::{JsonCodec, MessageCodec};
 use crate::error::{anyhow, bail, AnyError, AnyResult};
+use crate::ibc_callbacks::{IbcDestinationCallbackMsg, IbcSourceCallbackMsg};
+use crate::strict::from_json_strict;
 use cosmwasm_std::{
-    from_json, Binary, CosmosMsg, CustomMsg, CustomQuery, Deps, DepsMut, Empty, Env, MessageInfo,
-    QuerierWrapper, Reply, Response, SubMsg,
+    from_json, Binary, CosmosMsg, CustomMsg, CustomQuery, Deps, DepsMut, Empty, Env,
+    IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcChannelOpenResponse, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcReceiveResponse, MessageInfo, QuerierWrapper, Reply, Response, SubMsg,
 };
 use serde::de::DeserializeOwned;
+use std::cell::Cell;
 use std::fmt::{Debug, Display};
 use std::ops::Deref;
+use std::rc::Rc;
 
 /// This trait serves as a primary interface for interacting with contracts.
 #[rustfmt::skip]
@@ -23,6 +31,9 @@ where
     fn instantiate(&self, deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: Vec<u8>) -> AnyResult<Response<C>>;
 
     /// Evaluates contract's `query` entry-point.
+    ///
+    /// Queries get read-only access to the storage through [Deps],
+    /// so writes in query handlers are rejected at compile time.
     fn query(&self, deps: Deps<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Binary>;
 
     /// Evaluates contract's `sudo` entry-point.
@@ -33,6 +44,50 @@ where
 
     /// Evaluates contract's `migrate` entry-point.
     fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>>;
+
+    /// Returns `true` when the contract exposes IBC entry-points.
+    /// IBC-enabled contracts get an IBC port bound during instantiation.
+    fn ibc_enabled(&self) -> bool { false }
+
+    /// Evaluates contract's `ibc_channel_open` entry-point.
+    fn ibc_channel_open(&self, _deps: DepsMut<Q>, _env: Env, _msg: IbcChannelOpenMsg) -> AnyResult<IbcChannelOpenResponse> {
+        bail!("ibc_channel_open is not implemented for contract")
+    }
+
+    /// Evaluates contract's `ibc_channel_connect` entry-point.
+    fn ibc_channel_connect(&self, _deps: DepsMut<Q>, _env: Env, _msg: IbcChannelConnectMsg) -> AnyResult<IbcBasicResponse<C>> {
+        bail!("ibc_channel_connect is not implemented for contract")
+    }
+
+    /// Evaluates contract's `ibc_channel_close` entry-point.
+    fn ibc_channel_close(&self, _deps: DepsMut<Q>, _env: Env, _msg: IbcChannelCloseMsg) -> AnyResult<IbcBasicResponse<C>> {
+        bail!("ibc_channel_close is not implemented for contract")
+    }
+
+    /// Evaluates contract's `ibc_packet_receive` entry-point.
+    fn ibc_packet_receive(&self, _deps: DepsMut<Q>, _env: Env, _msg: IbcPacketReceiveMsg) -> AnyResult<IbcReceiveResponse<C>> {
+        bail!("ibc_packet_receive is not implemented for contract")
+    }
+
+    /// Evaluates contract's `ibc_packet_ack` entry-point.
+    fn ibc_packet_ack(&self, _deps: DepsMut<Q>, _env: Env, _msg: IbcPacketAckMsg) -> AnyResult<IbcBasicResponse<C>> {
+        bail!("ibc_packet_ack is not implemented for contract")
+    }
+
+    /// Evaluates contract's `ibc_packet_timeout` entry-point.
+    fn ibc_packet_timeout(&self, _deps: DepsMut<Q>, _env: Env, _msg: IbcPacketTimeoutMsg) -> AnyResult<IbcBasicResponse<C>> {
+        bail!("ibc_packet_timeout is not implemented for contract")
+    }
+
+    /// Evaluates contract's `ibc_source_callback` entry-point (ADR-8).
+    fn ibc_source_callback(&self, _deps: DepsMut<Q>, _env: Env, _msg: IbcSourceCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        bail!("ibc_source_callback is not implemented for contract")
+    }
+
+    /// Evaluates contract's `ibc_destination_callback` entry-point (ADR-8).
+    fn ibc_destination_callback(&self, _deps: DepsMut<Q>, _env: Env, _msg: IbcDestinationCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        bail!("ibc_destination_callback is not implemented for contract")
+    }
 }
 
 #[rustfmt::skip]
@@ -44,12 +99,34 @@ mod closures {
     pub type PermissionedFn<T, C, E, Q> = fn(deps: DepsMut<Q>, env: Env, msg: T) -> Result<Response<C>, E>;
     pub type ReplyFn<C, E, Q> = fn(deps: DepsMut<Q>, env: Env, msg: Reply) -> Result<Response<C>, E>;
     pub type QueryFn<T, E, Q> = fn(deps: Deps<Q>, env: Env, msg: T) -> Result<Binary, E>;
+    pub type IbcFn<T, R, E, Q> = fn(deps: DepsMut<Q>, env: Env, msg: T) -> Result<R, E>;
+    pub type MsgTranslator<C> = fn(msg: CosmosMsg<Empty>) -> CosmosMsg<C>;
 
     // closure types
     pub type ContractClosure<T, C, E, Q> = Box<dyn Fn(DepsMut<Q>, Env, MessageInfo, T) -> Result<Response<C>, E>>;
     pub type PermissionedClosure<T, C, E, Q> = Box<dyn Fn(DepsMut<Q>, Env, T) -> Result<Response<C>, E>>;
     pub type ReplyClosure<C, E, Q> = Box<dyn Fn(DepsMut<Q>, Env, Reply) -> Result<Response<C>, E>>;
     pub type QueryClosure<T, E, Q> = Box<dyn Fn(Deps<Q>, Env, T) -> Result<Binary, E>>;
+    pub type IbcClosure<T, R, Q> = Box<dyn Fn(DepsMut<Q>, Env, T) -> AnyResult<R>>;
+
+    /// Message translator shared by all entry-points returning `Empty` as a custom message.
+    pub type SharedMsgTranslator<C> = Rc<Cell<MsgTranslator<C>>>;
+
+    /// Closures evaluating contract's IBC entry-points.
+    pub struct IbcClosures<C: CustomMsg, Q: CustomQuery> {
+        pub channel_open_fn: IbcClosure<IbcChannelOpenMsg, IbcChannelOpenResponse, Q>,
+        pub channel_connect_fn: IbcClosure<IbcChannelConnectMsg, IbcBasicResponse<C>, Q>,
+        pub channel_close_fn: IbcClosure<IbcChannelCloseMsg, IbcBasicResponse<C>, Q>,
+        pub packet_receive_fn: IbcClosure<IbcPacketReceiveMsg, IbcReceiveResponse<C>, Q>,
+        pub packet_ack_fn: IbcClosure<IbcPacketAckMsg, IbcBasicResponse<C>, Q>,
+        pub packet_timeout_fn: IbcClosure<IbcPacketTimeoutMsg, IbcBasicResponse<C>, Q>,
+    }
+
+    /// Closures evaluating contract's IBC callbacks entry-points (ADR-8).
+    pub struct IbcCallbackClosures<C: CustomMsg, Q: CustomQuery> {
+        pub source_callback_fn: IbcClosure<IbcSourceCallbackMsg, IbcBasicResponse<C>, Q>,
+        pub destination_callback_fn: IbcClosure<IbcDestinationCallbackMsg, IbcBasicResponse<C>, Q>,
+    }
 }
 
 use closures::*;
@@ -152,10 +229,15 @@ pub struct ContractWrapper<
 {
     execute_fn: ContractClosure<T1, C, E1, Q>,
     instantiate_fn: ContractClosure<T2, C, E2, Q>,
-    query_fn: QueryClosure<T3, E3, Q>,
+    query_fn: Option<QueryClosure<T3, E3, Q>>,
     sudo_fn: Option<PermissionedClosure<T4, C, E4, Q>>,
     reply_fn: Option<ReplyClosure<C, E5, Q>>,
     migrate_fn: Option<PermissionedClosure<T6, C, E6, Q>>,
+    ibc_fns: Option<IbcClosures<C, Q>>,
+    ibc_callback_fns: Option<IbcCallbackClosures<C, Q>>,
+    msg_translator: SharedMsgTranslator<C>,
+    codec: Box<dyn MessageCodec>,
+    strict_decoding: bool,
 }
 
 impl<T1, T2, T3, E1, E2, E3, C, Q> ContractWrapper<T1, T2, T3, E1, E2, E3, C, Q>
@@ -178,10 +260,15 @@ where
         Self {
             execute_fn: Box::new(execute_fn),
             instantiate_fn: Box::new(instantiate_fn),
-            query_fn: Box::new(query_fn),
+            query_fn: Some(Box::new(query_fn)),
             sudo_fn: None,
             reply_fn: None,
             migrate_fn: None,
+            ibc_fns: None,
+            ibc_callback_fns: None,
+            msg_translator: Rc::new(Cell::new(default_msg_translator)),
+            codec: Box::new(JsonCodec),
+            strict_decoding: false,
         }
     }
 
@@ -191,14 +278,94 @@ where
         execute_fn: ContractFn<T1, Empty, E1, Empty>,
         instantiate_fn: ContractFn<T2, Empty, E2, Empty>,
         query_fn: QueryFn<T3, E3, Empty>,
+    ) -> Self {
+        let msg_translator: SharedMsgTranslator<C> = Rc::new(Cell::new(default_msg_translator));
+        Self {
+            execute_fn: customize_contract_fn(execute_fn, &msg_translator),
+            instantiate_fn: customize_contract_fn(instantiate_fn, &msg_translator),
+            query_fn: Some(customize_query_fn(query_fn)),
+            sudo_fn: None,
+            reply_fn: None,
+            migrate_fn: None,
+            ibc_fns: None,
+            ibc_callback_fns: None,
+            msg_translator,
+            codec: Box::new(JsonCodec),
+            strict_decoding: false,
+        }
+    }
+
+    /// Creates a new contract wrapper from closures.
+    ///
+    /// Unlike [new](Self::new), which accepts only function pointers, this constructor accepts
+    /// closures capturing their environment, so the state shared with the test
+    /// (counters, channels, configuration) can be injected into mocked contracts.
+    pub fn new_closure(
+        execute_fn: impl Fn(DepsMut<Q>, Env, MessageInfo, T1) -> Result<Response<C>, E1> + 'static,
+        instantiate_fn: impl Fn(DepsMut<Q>, Env, MessageInfo, T2) -> Result<Response<C>, E2> + 'static,
+        query_fn: impl Fn(Deps<Q>, Env, T3) -> Result<Binary, E3> + 'static,
     ) -> Self {
         Self {
-            execute_fn: customize_contract_fn(execute_fn),
-            instantiate_fn: customize_contract_fn(instantiate_fn),
-            query_fn: customize_query_fn(query_fn),
+            execute_fn: Box::new(execute_fn),
+            instantiate_fn: Box::new(instantiate_fn),
+            query_fn: Some(Box::new(query_fn)),
             sudo_fn: None,
             reply_fn: None,
             migrate_fn: None,
+            ibc_fns: None,
+            ibc_callback_fns: None,
+            msg_translator: Rc::new(Cell::new(default_msg_translator)),
+            codec: Box::new(JsonCodec),
+            strict_decoding: false,
+        }
+    }
+
+    /// Creates a new contract wrapper from `async` entry-point functions.
+    ///
+    /// Returned futures are driven to completion on the calling thread by a minimal executor,
+    /// so mocked contracts can await external fixtures, like HTTP mocks or databases.
+    /// See [AsyncContractFn] for details.
+    pub fn new_async<F1, F2, F3>(execute_fn: F1, instantiate_fn: F2, query_fn: F3) -> Self
+    where
+        F1: for<'a> AsyncContractFn<'a, T1, C, E1, Q> + 'static,
+        F2: for<'a> AsyncContractFn<'a, T2, C, E2, Q> + 'static,
+        F3: for<'a> AsyncQueryFn<'a, T3, E3, Q> + 'static,
+    {
+        Self::new_closure(
+            move |deps, env, info, msg| block_on(execute_fn(deps, env, info, msg)),
+            move |deps, env, info, msg| block_on(instantiate_fn(deps, env, info, msg)),
+            move |deps, env, msg| block_on(query_fn(deps, env, msg)),
+        )
+    }
+}
+
+impl<T1, T2, E1, E2, C, Q> ContractWrapper<T1, T2, Empty, E1, E2, AnyError, C, Q>
+where
+    T1: DeserializeOwned + 'static, // Type of message passed to `execute` entry-point.
+    T2: DeserializeOwned + 'static, // Type of message passed to `instantiate` entry-point.
+    E1: Display + Debug + Send + Sync + 'static, // Type of error returned from `execute` entry-point.
+    E2: Display + Debug + Send + Sync + 'static, // Type of error returned from `instantiate` entry-point.
+    C: CustomMsg + 'static, // Type of custom message returned from all entry-points except `query`.
+    Q: CustomQuery + DeserializeOwned + 'static, // Type of custom query in querier passed as deps/deps_mut to all entry-points.
+{
+    /// Creates a new contract wrapper for a contract without `query` entry-point.
+    /// Querying such a contract returns an error.
+    pub fn new_without_query(
+        execute_fn: ContractFn<T1, C, E1, Q>,
+        instantiate_fn: ContractFn<T2, C, E2, Q>,
+    ) -> Self {
+        Self {
+            execute_fn: Box::new(execute_fn),
+            instantiate_fn: Box::new(instantiate_fn),
+            query_fn: None,
+            sudo_fn: None,
+            reply_fn: None,
+            migrate_fn: None,
+            ibc_fns: None,
+            ibc_callback_fns: None,
+            msg_translator: Rc::new(Cell::new(default_msg_translator)),
+            codec: Box::new(JsonCodec),
+            strict_decoding: false,
         }
     }
 }
@@ -237,6 +404,11 @@ where
             sudo_fn: Some(Box::new(sudo_fn)),
             reply_fn: self.reply_fn,
             migrate_fn: self.migrate_fn,
+            ibc_fns: self.ibc_fns,
+            ibc_callback_fns: self.ibc_callback_fns,
+            msg_translator: self.msg_translator,
+            codec: self.codec,
+            strict_decoding: self.strict_decoding,
         }
     }
 
@@ -253,9 +425,14 @@ where
             execute_fn: self.execute_fn,
             instantiate_fn: self.instantiate_fn,
             query_fn: self.query_fn,
-            sudo_fn: Some(customize_permissioned_fn(sudo_fn)),
+            sudo_fn: Some(customize_permissioned_fn(sudo_fn, &self.msg_translator)),
             reply_fn: self.reply_fn,
             migrate_fn: self.migrate_fn,
+            ibc_fns: self.ibc_fns,
+            ibc_callback_fns: self.ibc_callback_fns,
+            msg_translator: self.msg_translator,
+            codec: self.codec,
+            strict_decoding: self.strict_decoding,
         }
     }
 
@@ -274,6 +451,11 @@ where
             sudo_fn: self.sudo_fn,
             reply_fn: Some(Box::new(reply_fn)),
             migrate_fn: self.migrate_fn,
+            ibc_fns: self.ibc_fns,
+            ibc_callback_fns: self.ibc_callback_fns,
+            msg_translator: self.msg_translator,
+            codec: self.codec,
+            strict_decoding: self.strict_decoding,
         }
     }
 
@@ -290,8 +472,13 @@ where
             instantiate_fn: self.instantiate_fn,
             query_fn: self.query_fn,
             sudo_fn: self.sudo_fn,
-            reply_fn: Some(customize_permissioned_fn(reply_fn)),
+            reply_fn: Some(customize_permissioned_fn(reply_fn, &self.msg_translator)),
             migrate_fn: self.migrate_fn,
+            ibc_fns: self.ibc_fns,
+            ibc_callback_fns: self.ibc_callback_fns,
+            msg_translator: self.msg_translator,
+            codec: self.codec,
+            strict_decoding: self.strict_decoding,
         }
     }
 
@@ -311,6 +498,11 @@ where
             sudo_fn: self.sudo_fn,
             reply_fn: self.reply_fn,
             migrate_fn: Some(Box::new(migrate_fn)),
+            ibc_fns: self.ibc_fns,
+            ibc_callback_fns: self.ibc_callback_fns,
+            msg_translator: self.msg_translator,
+            codec: self.codec,
+            strict_decoding: self.strict_decoding,
         }
     }
 
@@ -329,20 +521,236 @@ where
             query_fn: self.query_fn,
             sudo_fn: self.sudo_fn,
             reply_fn: self.reply_fn,
-            migrate_fn: Some(customize_permissioned_fn(migrate_fn)),
+            migrate_fn: Some(customize_permissioned_fn(migrate_fn, &self.msg_translator)),
+            ibc_fns: self.ibc_fns,
+            ibc_callback_fns: self.ibc_callback_fns,
+            msg_translator: self.msg_translator,
+            codec: self.codec,
+            strict_decoding: self.strict_decoding,
+        }
+    }
+
+    /// Populates [ContractWrapper] with contract's `sudo` entry-point provided as a closure.
+    pub fn with_sudo_closure<T4A, E4A>(
+        self,
+        sudo_fn: impl Fn(DepsMut<Q>, Env, T4A) -> Result<Response<C>, E4A> + 'static,
+    ) -> ContractWrapper<T1, T2, T3, E1, E2, E3, C, Q, T4A, E4A, E5, T6, E6>
+    where
+        T4A: DeserializeOwned + 'static,
+        E4A: Display + Debug + Send + Sync + 'static,
+    {
+        ContractWrapper {
+            execute_fn: self.execute_fn,
+            instantiate_fn: self.instantiate_fn,
+            query_fn: self.query_fn,
+            sudo_fn: Some(Box::new(sudo_fn)),
+            reply_fn: self.reply_fn,
+            migrate_fn: self.migrate_fn,
+            ibc_fns: self.ibc_fns,
+            ibc_callback_fns: self.ibc_callback_fns,
+            msg_translator: self.msg_translator,
+            codec: self.codec,
+            strict_decoding: self.strict_decoding,
+        }
+    }
+
+    /// Populates [ContractWrapper] with contract's `reply` entry-point provided as a closure.
+    pub fn with_reply_closure<E5A>(
+        self,
+        reply_fn: impl Fn(DepsMut<Q>, Env, Reply) -> Result<Response<C>, E5A> + 'static,
+    ) -> ContractWrapper<T1, T2, T3, E1, E2, E3, C, Q, T4, E4, E5A, T6, E6>
+    where
+        E5A: Display + Debug + Send + Sync + 'static,
+    {
+        ContractWrapper {
+            execute_fn: self.execute_fn,
+            instantiate_fn: self.instantiate_fn,
+            query_fn: self.query_fn,
+            sudo_fn: self.sudo_fn,
+            reply_fn: Some(Box::new(reply_fn)),
+            migrate_fn: self.migrate_fn,
+            ibc_fns: self.ibc_fns,
+            ibc_callback_fns: self.ibc_callback_fns,
+            msg_translator: self.msg_translator,
+            codec: self.codec,
+            strict_decoding: self.strict_decoding,
+        }
+    }
+
+    /// Populates [ContractWrapper] with contract's `migrate` entry-point provided as a closure.
+    pub fn with_migrate_closure<T6A, E6A>(
+        self,
+        migrate_fn: impl Fn(DepsMut<Q>, Env, T6A) -> Result<Response<C>, E6A> + 'static,
+    ) -> ContractWrapper<T1, T2, T3, E1, E2, E3, C, Q, T4, E4, E5, T6A, E6A>
+    where
+        T6A: DeserializeOwned + 'static,
+        E6A: Display + Debug + Send + Sync + 'static,
+    {
+        ContractWrapper {
+            execute_fn: self.execute_fn,
+            instantiate_fn: self.instantiate_fn,
+            query_fn: self.query_fn,
+            sudo_fn: self.sudo_fn,
+            reply_fn: self.reply_fn,
+            migrate_fn: Some(Box::new(migrate_fn)),
+            ibc_fns: self.ibc_fns,
+            ibc_callback_fns: self.ibc_callback_fns,
+            msg_translator: self.msg_translator,
+            codec: self.codec,
+            strict_decoding: self.strict_decoding,
         }
     }
+
+    /// Populates [ContractWrapper] with contract's IBC entry-points and custom message type.
+    pub fn with_ibc<E7A>(
+        mut self,
+        channel_open_fn: IbcFn<IbcChannelOpenMsg, IbcChannelOpenResponse, E7A, Q>,
+        channel_connect_fn: IbcFn<IbcChannelConnectMsg, IbcBasicResponse<C>, E7A, Q>,
+        channel_close_fn: IbcFn<IbcChannelCloseMsg, IbcBasicResponse<C>, E7A, Q>,
+        packet_receive_fn: IbcFn<IbcPacketReceiveMsg, IbcReceiveResponse<C>, E7A, Q>,
+        packet_ack_fn: IbcFn<IbcPacketAckMsg, IbcBasicResponse<C>, E7A, Q>,
+        packet_timeout_fn: IbcFn<IbcPacketTimeoutMsg, IbcBasicResponse<C>, E7A, Q>,
+    ) -> Self
+    where
+        E7A: Display + Debug + Send + Sync + 'static,
+    {
+        self.ibc_fns = Some(IbcClosures {
+            channel_open_fn: box_ibc_fn(channel_open_fn),
+            channel_connect_fn: box_ibc_fn(channel_connect_fn),
+            channel_close_fn: box_ibc_fn(channel_close_fn),
+            packet_receive_fn: box_ibc_fn(packet_receive_fn),
+            packet_ack_fn: box_ibc_fn(packet_ack_fn),
+            packet_timeout_fn: box_ibc_fn(packet_timeout_fn),
+        });
+        self
+    }
+
+    /// Populates [ContractWrapper] with contract's IBC entry-points and `Empty` as a custom message.
+    pub fn with_ibc_empty<E7A>(
+        mut self,
+        channel_open_fn: IbcFn<IbcChannelOpenMsg, IbcChannelOpenResponse, E7A, Empty>,
+        channel_connect_fn: IbcFn<IbcChannelConnectMsg, IbcBasicResponse, E7A, Empty>,
+        channel_close_fn: IbcFn<IbcChannelCloseMsg, IbcBasicResponse, E7A, Empty>,
+        packet_receive_fn: IbcFn<IbcPacketReceiveMsg, IbcReceiveResponse, E7A, Empty>,
+        packet_ack_fn: IbcFn<IbcPacketAckMsg, IbcBasicResponse, E7A, Empty>,
+        packet_timeout_fn: IbcFn<IbcPacketTimeoutMsg, IbcBasicResponse, E7A, Empty>,
+    ) -> Self
+    where
+        E7A: Display + Debug + Send + Sync + 'static,
+    {
+        self.ibc_fns = Some(IbcClosures {
+            channel_open_fn: customize_ibc_fn(channel_open_fn, &self.msg_translator, |resp, _| {
+                resp
+            }),
+            channel_connect_fn: customize_ibc_fn(
+                channel_connect_fn,
+                &self.msg_translator,
+                customize_basic_response,
+            ),
+            channel_close_fn: customize_ibc_fn(
+                channel_close_fn,
+                &self.msg_translator,
+                customize_basic_response,
+            ),
+            packet_receive_fn: customize_ibc_fn(
+                packet_receive_fn,
+                &self.msg_translator,
+                customize_receive_response,
+            ),
+            packet_ack_fn: customize_ibc_fn(
+                packet_ack_fn,
+                &self.msg_translator,
+                customize_basic_response,
+            ),
+            packet_timeout_fn: customize_ibc_fn(
+                packet_timeout_fn,
+                &self.msg_translator,
+                customize_basic_response,
+            ),
+        });
+        self
+    }
+
+    /// Populates [ContractWrapper] with contract's IBC callbacks entry-points (ADR-8)
+    /// and custom message type.
+    pub fn with_ibc_callbacks<E8A>(
+        mut self,
+        source_callback_fn: IbcFn<IbcSourceCallbackMsg, IbcBasicResponse<C>, E8A, Q>,
+        destination_callback_fn: IbcFn<IbcDestinationCallbackMsg, IbcBasicResponse<C>, E8A, Q>,
+    ) -> Self
+    where
+        E8A: Display + Debug + Send + Sync + 'static,
+    {
+        self.ibc_callback_fns = Some(IbcCallbackClosures {
+            source_callback_fn: box_ibc_fn(source_callback_fn),
+            destination_callback_fn: box_ibc_fn(destination_callback_fn),
+        });
+        self
+    }
+
+    /// Populates [ContractWrapper] with contract's IBC callbacks entry-points (ADR-8)
+    /// and `Empty` as a custom message.
+    pub fn with_ibc_callbacks_empty<E8A>(
+        mut self,
+        source_callback_fn: IbcFn<IbcSourceCallbackMsg, IbcBasicResponse, E8A, Empty>,
+        destination_callback_fn: IbcFn<IbcDestinationCallbackMsg, IbcBasicResponse, E8A, Empty>,
+    ) -> Self
+    where
+        E8A: Display + Debug + Send + Sync + 'static,
+    {
+        self.ibc_callback_fns = Some(IbcCallbackClosures {
+            source_callback_fn: customize_ibc_fn(
+                source_callback_fn,
+                &self.msg_translator,
+                customize_basic_response,
+            ),
+            destination_callback_fn: customize_ibc_fn(
+                destination_callback_fn,
+                &self.msg_translator,
+                customize_basic_response,
+            ),
+        });
+        self
+    }
+
+    /// Sets the translator of messages returned from entry-points populated with `Empty`
+    /// as a custom message, like in [new_with_empty](ContractWrapper::new_with_empty).
+    /// The translator is applied to all such entry-points, no matter in which order
+    /// they were populated. By default, [default_msg_translator] is used.
+    pub fn with_msg_translator(self, msg_translator: MsgTranslator<C>) -> Self {
+        self.msg_translator.set(msg_translator);
+        self
+    }
+
+    /// Enables strict decoding of messages passed to `execute`, `instantiate`, `query`,
+    /// `sudo` and `migrate` entry-points. In strict mode, messages containing fields
+    /// unknown to the message type are rejected, like in contracts whose messages
+    /// are annotated with `#[serde(deny_unknown_fields)]`.
+    pub fn with_strict_decoding(mut self) -> Self {
+        self.strict_decoding = true;
+        self
+    }
+
+    /// Sets the codec converting messages passed to `execute`, `instantiate`, `query`,
+    /// `sudo` and `migrate` entry-points into JSON. By default, [JsonCodec] is used,
+    /// so messages are expected to be JSON-encoded.
+    pub fn with_codec(mut self, codec: impl MessageCodec + 'static) -> Self {
+        self.codec = Box::new(codec);
+        self
+    }
 }
 
 fn customize_contract_fn<T, C, E, Q>(
     raw_fn: ContractFn<T, Empty, E, Empty>,
+    msg_translator: &SharedMsgTranslator<C>,
 ) -> ContractClosure<T, C, E, Q>
 where
     T: DeserializeOwned + 'static,
     E: Display + Debug + Send + Sync + 'static,
-    C: CustomMsg,
+    C: CustomMsg + 'static,
     Q: CustomQuery + DeserializeOwned,
 {
+    let msg_translator = msg_translator.clone();
     Box::new(
         move |mut deps: DepsMut<Q>,
               env: Env,
@@ -350,7 +758,7 @@ where
               msg: T|
               -> Result<Response<C>, E> {
             let deps = decustomize_deps_mut(&mut deps);
-            raw_fn(deps, env, info, msg).map(customize_response::<C>)
+            raw_fn(deps, env, info, msg).map(|resp| customize_response(resp, msg_translator.get()))
         },
     )
 }
@@ -371,17 +779,55 @@ where
 
 fn customize_permissioned_fn<T, C, E, Q>(
     raw_fn: PermissionedFn<T, Empty, E, Empty>,
+    msg_translator: &SharedMsgTranslator<C>,
 ) -> PermissionedClosure<T, C, E, Q>
 where
     T: DeserializeOwned + 'static,
     E: Display + Debug + Send + Sync + 'static,
-    C: CustomMsg,
+    C: CustomMsg + 'static,
     Q: CustomQuery + DeserializeOwned,
 {
+    let msg_translator = msg_translator.clone();
     Box::new(
         move |mut deps: DepsMut<Q>, env: Env, msg: T| -> Result<Response<C>, E> {
             let deps = decustomize_deps_mut(&mut deps);
-            raw_fn(deps, env, msg).map(customize_response::<C>)
+            raw_fn(deps, env, msg).map(|resp| customize_response(resp, msg_translator.get()))
+        },
+    )
+}
+
+fn box_ibc_fn<T, R, E, Q>(raw_fn: IbcFn<T, R, E, Q>) -> IbcClosure<T, R, Q>
+where
+    T: 'static,
+    R: 'static,
+    E: Display + Debug + Send + Sync + 'static,
+    Q: CustomQuery + 'static,
+{
+    Box::new(move |deps: DepsMut<Q>, env: Env, msg: T| -> AnyResult<R> {
+        raw_fn(deps, env, msg).map_err(|err: E| anyhow!(err))
+    })
+}
+
+fn customize_ibc_fn<T, R, RC, C, E, Q>(
+    raw_fn: IbcFn<T, R, E, Empty>,
+    msg_translator: &SharedMsgTranslator<C>,
+    customize: fn(R, MsgTranslator<C>) -> RC,
+) -> IbcClosure<T, RC, Q>
+where
+    T: 'static,
+    R: 'static,
+    RC: 'static,
+    C: CustomMsg + 'static,
+    E: Display + Debug + Send + Sync + 'static,
+    Q: CustomQuery + DeserializeOwned,
+{
+    let msg_translator = msg_translator.clone();
+    Box::new(
+        move |mut deps: DepsMut<Q>, env: Env, msg: T| -> AnyResult<RC> {
+            let deps = decustomize_deps_mut(&mut deps);
+            raw_fn(deps, env, msg)
+                .map(|resp| customize(resp, msg_translator.get()))
+                .map_err(|err: E| anyhow!(err))
         },
     )
 }
@@ -408,40 +854,127 @@ where
     }
 }
 
-fn customize_response<C>(resp: Response<Empty>) -> Response<C>
+fn customize_response<C>(resp: Response<Empty>, msg_translator: MsgTranslator<C>) -> Response<C>
 where
     C: CustomMsg,
 {
     let mut customized_resp = Response::<C>::new()
-        .add_submessages(resp.messages.into_iter().map(customize_msg::<C>))
+        .add_submessages(
+            resp.messages
+                .into_iter()
+                .map(|msg| customize_msg(msg, msg_translator)),
+        )
         .add_events(resp.events)
         .add_attributes(resp.attributes);
     customized_resp.data = resp.data;
     customized_resp
 }
 
-fn customize_msg<C>(msg: SubMsg<Empty>) -> SubMsg<C>
+fn customize_basic_response<C>(
+    resp: IbcBasicResponse<Empty>,
+    msg_translator: MsgTranslator<C>,
+) -> IbcBasicResponse<C>
+where
+    C: CustomMsg,
+{
+    IbcBasicResponse::<C>::new()
+        .add_submessages(
+            resp.messages
+                .into_iter()
+                .map(|msg| customize_msg(msg, msg_translator)),
+        )
+        .add_events(resp.events)
+        .add_attributes(resp.attributes)
+}
+
+fn customize_receive_response<C>(
+    resp: IbcReceiveResponse<Empty>,
+    msg_translator: MsgTranslator<C>,
+) -> IbcReceiveResponse<C>
+where
+    C: CustomMsg,
+{
+    let customized_resp = match resp.acknowledgement {
+        Some(ack) => IbcReceiveResponse::<C>::new(ack),
+        None => IbcReceiveResponse::<C>::without_ack(),
+    };
+    customized_resp
+        .add_submessages(
+            resp.messages
+                .into_iter()
+                .map(|msg| customize_msg(msg, msg_translator)),
+        )
+        .add_events(resp.events)
+        .add_attributes(resp.attributes)
+}
+
+fn customize_msg<C>(msg: SubMsg<Empty>, msg_translator: MsgTranslator<C>) -> SubMsg<C>
 where
     C: CustomMsg,
 {
     SubMsg {
         id: msg.id,
         payload: Binary::default(),
-        msg: match msg.msg {
-            CosmosMsg::Wasm(wasm) => CosmosMsg::Wasm(wasm),
-            CosmosMsg::Bank(bank) => CosmosMsg::Bank(bank),
-            CosmosMsg::Staking(staking) => CosmosMsg::Staking(staking),
-            CosmosMsg::Distribution(distribution) => CosmosMsg::Distribution(distribution),
-            CosmosMsg::Custom(_) => unreachable!(),
-            CosmosMsg::Ibc(ibc) => CosmosMsg::Ibc(ibc),
-            CosmosMsg::Any(any) => CosmosMsg::Any(any),
-            _ => panic!("unknown message variant {:?}", msg),
-        },
+        msg: msg_translator(msg.msg),
         gas_limit: msg.gas_limit,
         reply_on: msg.reply_on,
     }
 }
 
+/// Default translator of messages returned from contracts using `Empty` as a custom message.
+///
+/// All standard message variants are passed through unchanged. `CosmosMsg::Custom(Empty)`
+/// can not be translated into chain-specific custom message, so this translator panics
+/// when such message is encountered. Use [ContractWrapper::with_msg_translator]
+/// to provide a translator handling custom messages.
+pub fn default_msg_translator<C>(msg: CosmosMsg<Empty>) -> CosmosMsg<C>
+where
+    C: CustomMsg,
+{
+    #[allow(deprecated)]
+    match msg {
+        CosmosMsg::Wasm(wasm) => CosmosMsg::Wasm(wasm),
+        CosmosMsg::Bank(bank) => CosmosMsg::Bank(bank),
+        CosmosMsg::Staking(staking) => CosmosMsg::Staking(staking),
+        CosmosMsg::Distribution(distribution) => CosmosMsg::Distribution(distribution),
+        CosmosMsg::Ibc(ibc) => CosmosMsg::Ibc(ibc),
+        CosmosMsg::Gov(gov) => CosmosMsg::Gov(gov),
+        CosmosMsg::Any(any) => CosmosMsg::Any(any),
+        CosmosMsg::Stargate { type_url, value } => CosmosMsg::Stargate { type_url, value },
+        CosmosMsg::Custom(_) => panic!("no translator for custom message {:?}", msg),
+        _ => panic!("unknown message variant {:?}", msg),
+    }
+}
+
+impl<T1, T2, T3, E1, E2, E3, C, Q, T4, E4, E5, T6, E6>
+    ContractWrapper<T1, T2, T3, E1, E2, E3, C, Q, T4, E4, E5, T6, E6>
+where
+    T1: DeserializeOwned, // Type of message passed to `execute` entry-point.
+    T2: DeserializeOwned, // Type of message passed to `instantiate` entry-point.
+    T3: DeserializeOwned, // Type of message passed to `query` entry-point.
+    T4: DeserializeOwned, // Type of message passed to `sudo` entry-point.
+    T6: DeserializeOwned, // Type of message passed to `migrate` entry-point.
+    E1: Display + Debug + Send + Sync, // Type of error returned from `execute` entry-point.
+    E2: Display + Debug + Send + Sync, // Type of error returned from `instantiate` entry-point.
+    E3: Display + Debug + Send + Sync, // Type of error returned from `query` entry-point.
+    E4: Display + Debug + Send + Sync, // Type of error returned from `sudo` entry-point.
+    E5: Display + Debug + Send + Sync, // Type of error returned from `reply` entry-point.
+    E6: Display + Debug + Send + Sync, // Type of error returned from `migrate` entry-point.
+    C: CustomMsg,         // Type of custom message returned from all entry-points except `query`.
+    Q: CustomQuery + DeserializeOwned, // Type of custom query in querier passed as deps/deps_mut to all entry-points.
+{
+    /// Decodes the message passed to an entry-point using the codec,
+    /// rejecting unknown fields in strict mode.
+    fn decode<T: DeserializeOwned>(&self, msg: Vec<u8>) -> AnyResult<T> {
+        let msg = self.codec.to_json(msg)?;
+        if self.strict_decoding {
+            from_json_strict(msg)
+        } else {
+            Ok(from_json(msg)?)
+        }
+    }
+}
+
 impl<T1, T2, T3, E1, E2, E3, C, T4, E4, E5, T6, E6, Q> Contract<C, Q>
     for ContractWrapper<T1, T2, T3, E1, E2, E3, C, Q, T4, E4, E5, T6, E6>
 where
@@ -469,7 +1002,7 @@ where
         info: MessageInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<C>> {
-        let msg: T1 = from_json(msg)?;
+        let msg: T1 = self.decode(msg)?;
         (self.execute_fn)(deps, env, info, msg).map_err(|err: E1| anyhow!(err))
     }
 
@@ -483,16 +1016,20 @@ where
         info: MessageInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<C>> {
-        let msg: T2 = from_json(msg)?;
+        let msg: T2 = self.decode(msg)?;
         (self.instantiate_fn)(deps, env, info, msg).map_err(|err: E2| anyhow!(err))
     }
 
     /// Calls [query] on wrapped [Contract] trait implementor.
+    /// Returns an error when the contract does not implement [query].
     ///
     /// [query]: Contract::query
     fn query(&self, deps: Deps<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
-        let msg: T3 = from_json(msg)?;
-        (self.query_fn)(deps, env, msg).map_err(|err: E3| anyhow!(err))
+        let Some(query_fn) = &self.query_fn else {
+            bail!("query is not implemented for contract");
+        };
+        let msg: T3 = self.decode(msg)?;
+        query_fn(deps, env, msg).map_err(|err: E3| anyhow!(err))
     }
 
     /// Calls [sudo] on wrapped [Contract] trait implementor.
@@ -500,7 +1037,7 @@ where
     ///
     /// [sudo]: Contract::sudo
     fn sudo(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
-        let msg: T4 = from_json(msg)?;
+        let msg: T4 = self.decode(msg)?;
         match &self.sudo_fn {
             Some(sudo) => sudo(deps, env, msg).map_err(|err: E4| anyhow!(err)),
             None => bail!("sudo is not implemented for contract"),
@@ -524,10 +1061,143 @@ where
     ///
     /// [migrate]: Contract::migrate
     fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
-        let msg: T6 = from_json(msg)?;
+        let msg: T6 = self.decode(msg)?;
         match &self.migrate_fn {
             Some(migrate) => migrate(deps, env, msg).map_err(|err: E6| anyhow!(err)),
             None => bail!("migrate is not implemented for contract"),
         }
     }
+
+    /// Returns `true` when the [ContractWrapper] was populated with IBC entry-points.
+    fn ibc_enabled(&self) -> bool {
+        self.ibc_fns.is_some()
+    }
+
+    /// Calls [ibc_channel_open] on wrapped [Contract] trait implementor.
+    /// Returns an error when the contract does not implement IBC entry-points.
+    ///
+    /// [ibc_channel_open]: Contract::ibc_channel_open
+    fn ibc_channel_open(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        msg: IbcChannelOpenMsg,
+    ) -> AnyResult<IbcChannelOpenResponse> {
+        match &self.ibc_fns {
+            Some(ibc_fns) => (ibc_fns.channel_open_fn)(deps, env, msg),
+            None => bail!("ibc_channel_open is not implemented for contract"),
+        }
+    }
+
+    /// Calls [ibc_channel_connect] on wrapped [Contract] trait implementor.
+    /// Returns an error when the contract does not implement IBC entry-points.
+    ///
+    /// [ibc_channel_connect]: Contract::ibc_channel_connect
+    fn ibc_channel_connect(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        msg: IbcChannelConnectMsg,
+    ) -> AnyResult<IbcBasicResponse<C>> {
+        match &self.ibc_fns {
+            Some(ibc_fns) => (ibc_fns.channel_connect_fn)(deps, env, msg),
+            None => bail!("ibc_channel_connect is not implemented for contract"),
+        }
+    }
+
+    /// Calls [ibc_channel_close] on wrapped [Contract] trait implementor.
+    /// Returns an error when the contract does not implement IBC entry-points.
+    ///
+    /// [ibc_channel_close]: Contract::ibc_channel_close
+    fn ibc_channel_close(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        msg: IbcChannelCloseMsg,
+    ) -> AnyResult<IbcBasicResponse<C>> {
+        match &self.ibc_fns {
+            Some(ibc_fns) => (ibc_fns.channel_close_fn)(deps, env, msg),
+            None => bail!("ibc_channel_close is not implemented for contract"),
+        }
+    }
+
+    /// Calls [ibc_packet_receive] on wrapped [Contract] trait implementor.
+    /// Returns an error when the contract does not implement IBC entry-points.
+    ///
+    /// [ibc_packet_receive]: Contract::ibc_packet_receive
+    fn ibc_packet_receive(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        msg: IbcPacketReceiveMsg,
+    ) -> AnyResult<IbcReceiveResponse<C>> {
+        match &self.ibc_fns {
+            Some(ibc_fns) => (ibc_fns.packet_receive_fn)(deps, env, msg),
+            None => bail!("ibc_packet_receive is not implemented for contract"),
+        }
+    }
+
+    /// Calls [ibc_packet_ack] on wrapped [Contract] trait implementor.
+    /// Returns an error when the contract does not implement IBC entry-points.
+    ///
+    /// [ibc_packet_ack]: Contract::ibc_packet_ack
+    fn ibc_packet_ack(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        msg: IbcPacketAckMsg,
+    ) -> AnyResult<IbcBasicResponse<C>> {
+        match &self.ibc_fns {
+            Some(ibc_fns) => (ibc_fns.packet_ack_fn)(deps, env, msg),
+            None => bail!("ibc_packet_ack is not implemented for contract"),
+        }
+    }
+
+    /// Calls [ibc_packet_timeout] on wrapped [Contract] trait implementor.
+    /// Returns an error when the contract does not implement IBC entry-points.
+    ///
+    /// [ibc_packet_timeout]: Contract::ibc_packet_timeout
+    fn ibc_packet_timeout(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        msg: IbcPacketTimeoutMsg,
+    ) -> AnyResult<IbcBasicResponse<C>> {
+        match &self.ibc_fns {
+            Some(ibc_fns) => (ibc_fns.packet_timeout_fn)(deps, env, msg),
+            None => bail!("ibc_packet_timeout is not implemented for contract"),
+        }
+    }
+
+    /// Calls [ibc_source_callback] on wrapped [Contract] trait implementor.
+    /// Returns an error when the contract does not implement IBC callbacks entry-points.
+    ///
+    /// [ibc_source_callback]: Contract::ibc_source_callback
+    fn ibc_source_callback(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        msg: IbcSourceCallbackMsg,
+    ) -> AnyResult<IbcBasicResponse<C>> {
+        match &self.ibc_callback_fns {
+            Some(ibc_callback_fns) => (ibc_callback_fns.source_callback_fn)(deps, env, msg),
+            None => bail!("ibc_source_callback is not implemented for contract"),
+        }
+    }
+
+    /// Calls [ibc_destination_callback] on wrapped [Contract] trait implementor.
+    /// Returns an error when the contract does not implement IBC callbacks entry-points.
+    ///
+    /// [ibc_destination_callback]: Contract::ibc_destination_callback
+    fn ibc_destination_callback(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        msg: IbcDestinationCallbackMsg,
+    ) -> AnyResult<IbcBasicResponse<C>> {
+        match &self.ibc_callback_fns {
+            Some(ibc_callback_fns) => (ibc_callback_fns.destination_callback_fn)(deps, env, msg),
+            None => bail!("ibc_destination_callback is not implemented for contract"),
+        }
+    }
 }
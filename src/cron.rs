@@ -0,0 +1,210 @@
+//! # Scheduled execution of contract messages
+//!
+//! Modeled after the Neutron `cron` module: a [Schedule] executes its messages on contracts
+//! every `period` blocks, when the block is advanced with [update_block](crate::App::update_block)
+//! or [set_block](crate::App::set_block). Schedules are executed at the end of the finished
+//! block or at the beginning of the new block (see [ExecutionStage]), in the order of names.
+//!
+//! Messages of a schedule are executed atomically, sent by the `cron` module account.
+//! When any message fails, or the messages use more simulated gas than the schedule's limit,
+//! all changes made by the schedule are reverted and the [FailurePolicy] decides
+//! whether the schedule is executed again in the next period or halted until rescheduled.
+//!
+//! Schedules are kept in the application's storage, they can be cancelled with
+//! [remove_schedule](crate::App::remove_schedule) and rescheduled with
+//! [reschedule](crate::App::reschedule).
+
+use crate::error::{bail, AnyResult};
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use cosmwasm_std::{to_json_binary, Addr, Binary, Order, StdResult, Storage};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Registered schedules, indexed by name.
+const SCHEDULES: Map<&str, Schedule> = Map::new("schedules");
+
+/// Default storage namespace for schedules.
+const NAMESPACE_CRON: &[u8] = b"cron";
+
+/// Name of the module account sending scheduled messages.
+pub(crate) const CRON_MODULE: &str = "cron";
+
+/// Stage of the block processing in which the schedule is executed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ExecutionStage {
+    /// Executed at the beginning of the new block.
+    #[default]
+    BeginBlocker,
+    /// Executed at the end of the finished block.
+    EndBlocker,
+}
+
+/// Behavior of the schedule after a failed execution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum FailurePolicy {
+    /// Failed execution is skipped, the schedule is executed again in the next period.
+    #[default]
+    Skip,
+    /// The schedule is halted and not executed until it is rescheduled.
+    Halt,
+}
+
+/// Message executed on the contract by the [Schedule].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ScheduledMsg {
+    /// Address of the executed contract.
+    pub contract: Addr,
+    /// JSON-encoded message passed to the contract's `execute` entry-point.
+    pub msg: Binary,
+}
+
+/// Messages executed on contracts every `period` blocks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Schedule {
+    /// Unique name of the schedule.
+    pub name: String,
+    /// Number of blocks between executions.
+    pub period: u64,
+    /// Messages executed in the order they were added.
+    pub msgs: Vec<ScheduledMsg>,
+    /// Stage of the block processing in which the schedule is executed.
+    pub stage: ExecutionStage,
+    /// Maximum simulated gas used by all messages of the schedule in a single execution.
+    pub gas_limit: Option<u64>,
+    /// Behavior after a failed execution.
+    pub on_failure: FailurePolicy,
+    /// Height of the block in which the schedule was last executed, added or rescheduled.
+    pub last_execute_height: u64,
+    /// Flag indicating that the schedule was halted after a failed execution.
+    pub halted: bool,
+    /// Number of failed executions.
+    pub failures: u64,
+    /// Error of the last failed execution.
+    pub last_error: Option<String>,
+}
+
+impl Schedule {
+    /// Creates a schedule executed every `period` blocks, without any messages.
+    pub fn new(name: impl Into<String>, period: u64) -> Self {
+        Self {
+            name: name.into(),
+            period,
+            msgs: vec![],
+            stage: ExecutionStage::default(),
+            gas_limit: None,
+            on_failure: FailurePolicy::default(),
+            last_execute_height: 0,
+            halted: false,
+            failures: 0,
+            last_error: None,
+        }
+    }
+
+    /// Adds the message executed on the contract.
+    pub fn with_msg(mut self, contract: Addr, msg: &impl Serialize) -> StdResult<Self> {
+        self.msgs.push(ScheduledMsg {
+            contract,
+            msg: to_json_binary(msg)?,
+        });
+        Ok(self)
+    }
+
+    /// Sets the stage of the block processing in which the schedule is executed.
+    pub fn with_stage(mut self, stage: ExecutionStage) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    /// Limits the simulated gas used by all messages of the schedule in a single execution.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sets the behavior after a failed execution.
+    pub fn with_failure_policy(mut self, on_failure: FailurePolicy) -> Self {
+        self.on_failure = on_failure;
+        self
+    }
+
+    /// Returns `true` when the schedule should be executed in the stage of the block
+    /// with specified height.
+    pub(crate) fn is_due(&self, stage: ExecutionStage, height: u64) -> bool {
+        !self.halted
+            && self.stage == stage
+            && height >= self.last_execute_height.saturating_add(self.period)
+    }
+}
+
+/// Registers a new schedule, counting periods from the specified height.
+pub(crate) fn add_schedule(
+    storage: &mut dyn Storage,
+    mut schedule: Schedule,
+    height: u64,
+) -> AnyResult<()> {
+    let mut cron_storage = prefixed(storage, NAMESPACE_CRON);
+    if schedule.period == 0 {
+        bail!("period of schedule {} must be positive", schedule.name);
+    }
+    if SCHEDULES.has(&cron_storage, &schedule.name) {
+        bail!("schedule {} already exists", schedule.name);
+    }
+    schedule.last_execute_height = height;
+    SCHEDULES.save(&mut cron_storage, &schedule.name, &schedule)?;
+    Ok(())
+}
+
+/// Removes the schedule, returns an error when the schedule does not exist.
+pub(crate) fn remove_schedule(storage: &mut dyn Storage, name: &str) -> AnyResult<()> {
+    let mut cron_storage = prefixed(storage, NAMESPACE_CRON);
+    if !SCHEDULES.has(&cron_storage, name) {
+        bail!("schedule {} does not exist", name);
+    }
+    SCHEDULES.remove(&mut cron_storage, name);
+    Ok(())
+}
+
+/// Changes the period of the schedule, counting it from the specified height,
+/// halted schedule is resumed.
+pub(crate) fn reschedule(
+    storage: &mut dyn Storage,
+    name: &str,
+    period: u64,
+    height: u64,
+) -> AnyResult<()> {
+    let mut cron_storage = prefixed(storage, NAMESPACE_CRON);
+    if period == 0 {
+        bail!("period of schedule {} must be positive", name);
+    }
+    let Some(mut schedule) = SCHEDULES.may_load(&cron_storage, name)? else {
+        bail!("schedule {} does not exist", name);
+    };
+    schedule.period = period;
+    schedule.last_execute_height = height;
+    schedule.halted = false;
+    SCHEDULES.save(&mut cron_storage, name, &schedule)?;
+    Ok(())
+}
+
+/// Returns the schedule with specified name, if registered.
+pub(crate) fn schedule(storage: &dyn Storage, name: &str) -> AnyResult<Option<Schedule>> {
+    let cron_storage = prefixed_read(storage, NAMESPACE_CRON);
+    Ok(SCHEDULES.may_load(&cron_storage, name)?)
+}
+
+/// Returns all registered schedules, ordered by name.
+pub(crate) fn schedules(storage: &dyn Storage) -> AnyResult<Vec<Schedule>> {
+    let cron_storage = prefixed_read(storage, NAMESPACE_CRON);
+    Ok(SCHEDULES
+        .range(&cron_storage, None, None, Order::Ascending)
+        .map(|entry| entry.map(|(_, schedule)| schedule))
+        .collect::<StdResult<Vec<_>>>()?)
+}
+
+/// Saves the state of the existing schedule.
+pub(crate) fn save_schedule(storage: &mut dyn Storage, schedule: &Schedule) -> AnyResult<()> {
+    let mut cron_storage = prefixed(storage, NAMESPACE_CRON);
+    SCHEDULES.save(&mut cron_storage, &schedule.name, schedule)?;
+    Ok(())
+}
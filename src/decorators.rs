@@ -0,0 +1,315 @@
+//! # Contract decorators
+//!
+//! Tests often need a real contract behaving slightly differently: failing a specific message,
+//! answering a query with a fixed value or processing messages later than requested.
+//! [ContractDecorators] provides combinators wrapping any boxed [Contract],
+//! so such tweaks do not require reimplementing the contract.
+//!
+//! Message patterns are matched like in [MockContract](crate::MockContract): a message matches
+//! the pattern when all fields present in the pattern are present in the message
+//! with matching values.
+
+use crate::contracts::Contract;
+use crate::error::AnyResult;
+use crate::ibc_callbacks::{IbcDestinationCallbackMsg, IbcSourceCallbackMsg};
+use crate::mock_contract::{matches, to_pattern};
+use cosmwasm_std::{
+    from_json, to_json_vec, Addr, Binary, Coin, CustomMsg, CustomQuery, Deps, DepsMut, Env,
+    IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcChannelOpenResponse, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+    IbcReceiveResponse, MessageInfo, Reply, Response,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Key of the queue of delayed messages, kept in the storage of the delaying contract.
+const DELAYED_MSGS_KEY: &[u8] = b"__delayed_msgs";
+
+/// Handler overriding the `execute` entry-point, called with the decorated contract.
+type ExecuteHandler<C, Q> = Box<
+    dyn Fn(&dyn Contract<C, Q>, DepsMut<Q>, Env, MessageInfo, Vec<u8>) -> AnyResult<Response<C>>,
+>;
+
+/// Handler overriding the `query` entry-point, called with the decorated contract.
+type QueryHandler<C, Q> =
+    Box<dyn Fn(&dyn Contract<C, Q>, Deps<Q>, Env, Vec<u8>) -> AnyResult<Binary>>;
+
+/// Behavior added to the decorated contract.
+enum Decoration<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    ExecuteOverride(Value, ExecuteHandler<C, Q>),
+    QueryOverride(Value, QueryHandler<C, Q>),
+    Delaying(u64),
+}
+
+/// Message delayed by the delaying contract.
+#[derive(Serialize, Deserialize)]
+struct DelayedMsg {
+    /// Height of the block, starting from which the message is processed.
+    height: u64,
+    sender: Addr,
+    funds: Vec<Coin>,
+    msg: Binary,
+}
+
+/// Combinators wrapping a boxed [Contract] to override or augment its behavior.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{Empty, Response};
+/// use cw_multi_test::error::bail;
+/// use cw_multi_test::{App, Contract, ContractDecorators, Executor, MockContract};
+/// use serde_json::json;
+///
+/// let contract: Box<dyn Contract<Empty>> = Box::new(
+///     MockContract::new().on_execute(&json!({ "withdraw": {} }), Response::default()),
+/// );
+/// let contract = contract.with_execute_override(
+///     &json!({ "withdraw": { "amount": "0" } }),
+///     |_, _, _, _, _| bail!("nothing to withdraw"),
+/// );
+///
+/// let mut app = App::default();
+/// let code_id = app.store_code(contract);
+/// let owner = app.api().addr_make("owner");
+/// let contract_addr = app
+///     .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "vault", None)
+///     .unwrap();
+///
+/// let msg = json!({ "withdraw": { "amount": "10" } });
+/// app.execute_contract(owner.clone(), contract_addr.clone(), &msg, &[]).unwrap();
+/// let msg = json!({ "withdraw": { "amount": "0" } });
+/// let err = app.execute_contract(owner, contract_addr, &msg, &[]).unwrap_err();
+/// assert_eq!("nothing to withdraw", err.root_cause().to_string());
+/// ```
+pub trait ContractDecorators<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    /// Calls the handler instead of the contract's `execute` entry-point for messages
+    /// matching the pattern. The handler receives the decorated contract,
+    /// so it can augment the original behavior instead of replacing it.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the pattern can not be serialized to JSON.
+    fn with_execute_override(
+        self,
+        pattern: &impl Serialize,
+        handler: impl Fn(&dyn Contract<C, Q>, DepsMut<Q>, Env, MessageInfo, Vec<u8>) -> AnyResult<Response<C>>
+            + 'static,
+    ) -> Box<dyn Contract<C, Q>>;
+
+    /// Calls the handler instead of the contract's `query` entry-point for messages
+    /// matching the pattern. The handler receives the decorated contract,
+    /// so it can augment the original behavior instead of replacing it.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the pattern can not be serialized to JSON.
+    fn with_query_override(
+        self,
+        pattern: &impl Serialize,
+        handler: impl Fn(&dyn Contract<C, Q>, Deps<Q>, Env, Vec<u8>) -> AnyResult<Binary> + 'static,
+    ) -> Box<dyn Contract<C, Q>>;
+
+    /// Delays processing of `execute` messages by the specified number of blocks.
+    ///
+    /// Delayed messages are stored in the contract's storage and are processed,
+    /// in the order they were received, when the contract is executed again
+    /// at least `n_blocks` blocks later, just before the new message is delayed.
+    /// Funds sent with delayed messages are transferred immediately.
+    fn delaying(self, n_blocks: u64) -> Box<dyn Contract<C, Q>>;
+}
+
+impl<C, Q> ContractDecorators<C, Q> for Box<dyn Contract<C, Q>>
+where
+    C: CustomMsg + 'static,
+    Q: CustomQuery + 'static,
+{
+    fn with_execute_override(
+        self,
+        pattern: &impl Serialize,
+        handler: impl Fn(&dyn Contract<C, Q>, DepsMut<Q>, Env, MessageInfo, Vec<u8>) -> AnyResult<Response<C>>
+            + 'static,
+    ) -> Box<dyn Contract<C, Q>> {
+        Decorated::boxed(
+            self,
+            Decoration::ExecuteOverride(to_pattern(pattern), Box::new(handler)),
+        )
+    }
+
+    fn with_query_override(
+        self,
+        pattern: &impl Serialize,
+        handler: impl Fn(&dyn Contract<C, Q>, Deps<Q>, Env, Vec<u8>) -> AnyResult<Binary> + 'static,
+    ) -> Box<dyn Contract<C, Q>> {
+        Decorated::boxed(
+            self,
+            Decoration::QueryOverride(to_pattern(pattern), Box::new(handler)),
+        )
+    }
+
+    fn delaying(self, n_blocks: u64) -> Box<dyn Contract<C, Q>> {
+        Decorated::boxed(self, Decoration::Delaying(n_blocks))
+    }
+}
+
+/// Contract decorated with additional behavior.
+struct Decorated<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    contract: Box<dyn Contract<C, Q>>,
+    decoration: Decoration<C, Q>,
+}
+
+impl<C, Q> Decorated<C, Q>
+where
+    C: CustomMsg + 'static,
+    Q: CustomQuery + 'static,
+{
+    fn boxed(
+        contract: Box<dyn Contract<C, Q>>,
+        decoration: Decoration<C, Q>,
+    ) -> Box<dyn Contract<C, Q>> {
+        Box::new(Self {
+            contract,
+            decoration,
+        })
+    }
+}
+
+/// Returns `true` when the message matches the pattern.
+fn is_matching(msg: &[u8], pattern: &Value) -> bool {
+    serde_json::from_slice::<Value>(msg).is_ok_and(|value| matches(&value, pattern))
+}
+
+/// Processes delayed messages that are due and delays the new message.
+fn execute_delayed<C, Q>(
+    contract: &dyn Contract<C, Q>,
+    n_blocks: u64,
+    mut deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    msg: Vec<u8>,
+) -> AnyResult<Response<C>>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    let delayed: Vec<DelayedMsg> = match deps.storage.get(DELAYED_MSGS_KEY) {
+        Some(value) => from_json(value)?,
+        None => vec![],
+    };
+    let (due, mut pending): (Vec<_>, Vec<_>) = delayed
+        .into_iter()
+        .partition(|delayed| delayed.height <= env.block.height);
+    let mut response = Response::new();
+    for delayed in due {
+        let info = MessageInfo {
+            sender: delayed.sender,
+            funds: delayed.funds,
+        };
+        let processed = contract.execute(deps.branch(), env.clone(), info, delayed.msg.into())?;
+        response = response
+            .add_submessages(processed.messages)
+            .add_attributes(processed.attributes)
+            .add_events(processed.events);
+    }
+    let height = env.block.height + n_blocks;
+    pending.push(DelayedMsg {
+        height,
+        sender: info.sender,
+        funds: info.funds,
+        msg: msg.into(),
+    });
+    deps.storage.set(DELAYED_MSGS_KEY, &to_json_vec(&pending)?);
+    Ok(response.add_attribute("delayed_until_height", height.to_string()))
+}
+
+#[rustfmt::skip]
+impl<C, Q> Contract<C, Q> for Decorated<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    fn execute(&self, deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        match &self.decoration {
+            Decoration::ExecuteOverride(pattern, handler) if is_matching(&msg, pattern) => {
+                handler(self.contract.as_ref(), deps, env, info, msg)
+            }
+            Decoration::Delaying(n_blocks) => {
+                execute_delayed(self.contract.as_ref(), *n_blocks, deps, env, info, msg)
+            }
+            _ => self.contract.execute(deps, env, info, msg),
+        }
+    }
+
+    fn instantiate(&self, deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.contract.instantiate(deps, env, info, msg)
+    }
+
+    fn query(&self, deps: Deps<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        match &self.decoration {
+            Decoration::QueryOverride(pattern, handler) if is_matching(&msg, pattern) => {
+                handler(self.contract.as_ref(), deps, env, msg)
+            }
+            _ => self.contract.query(deps, env, msg),
+        }
+    }
+
+    fn sudo(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.contract.sudo(deps, env, msg)
+    }
+
+    fn reply(&self, deps: DepsMut<Q>, env: Env, msg: Reply) -> AnyResult<Response<C>> {
+        self.contract.reply(deps, env, msg)
+    }
+
+    fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.contract.migrate(deps, env, msg)
+    }
+
+    fn ibc_enabled(&self) -> bool {
+        self.contract.ibc_enabled()
+    }
+
+    fn ibc_channel_open(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelOpenMsg) -> AnyResult<IbcChannelOpenResponse> {
+        self.contract.ibc_channel_open(deps, env, msg)
+    }
+
+    fn ibc_channel_connect(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelConnectMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_channel_connect(deps, env, msg)
+    }
+
+    fn ibc_channel_close(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelCloseMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_channel_close(deps, env, msg)
+    }
+
+    fn ibc_packet_receive(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketReceiveMsg) -> AnyResult<IbcReceiveResponse<C>> {
+        self.contract.ibc_packet_receive(deps, env, msg)
+    }
+
+    fn ibc_packet_ack(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketAckMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_packet_ack(deps, env, msg)
+    }
+
+    fn ibc_packet_timeout(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketTimeoutMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_packet_timeout(deps, env, msg)
+    }
+
+    fn ibc_source_callback(&self, deps: DepsMut<Q>, env: Env, msg: IbcSourceCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_source_callback(deps, env, msg)
+    }
+
+    fn ibc_destination_callback(&self, deps: DepsMut<Q>, env: Env, msg: IbcDestinationCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_destination_callback(deps, env, msg)
+    }
+}
@@ -0,0 +1,116 @@
+//! # Registry of ICS-20 denomination traces
+//!
+//! Tokens transferred over IBC are represented on the receiving chain by a denomination
+//! in the format `ibc/{hash}`, where the hash is the SHA-256 digest of the full denomination
+//! path, like `transfer/channel-0/uatom`. The registry maps such hashes back to
+//! the path and the base denomination, like the ICS-20 transfer module does.
+//! Registered traces are kept in the application's storage.
+
+use crate::error::{bail, AnyResult};
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use cosmwasm_std::{Order, StdResult, Storage};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Registered denomination traces, indexed by hash.
+const DENOM_TRACES: Map<&str, DenomTrace> = Map::new("denom_traces");
+
+/// Default storage namespace for denomination traces.
+const NAMESPACE_DENOM_TRACES: &[u8] = b"denom-traces";
+
+/// Prefix of denominations representing tokens transferred over IBC.
+const IBC_DENOM_PREFIX: &str = "ibc/";
+
+/// Trace of the token transferred over IBC.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct DenomTrace {
+    /// Chain of port/channel identifiers the token was transferred through,
+    /// like `transfer/channel-0`.
+    pub path: String,
+    /// Base denomination of the token on the source chain, like `uatom`.
+    pub base_denom: String,
+}
+
+impl DenomTrace {
+    /// Creates a denomination trace with specified path and base denomination.
+    pub fn new(path: impl Into<String>, base_denom: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            base_denom: base_denom.into(),
+        }
+    }
+
+    /// Returns the full denomination path, like `transfer/channel-0/uatom`.
+    pub fn full_path(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}/{}", self.path, self.base_denom)
+        }
+    }
+
+    /// Returns the hash of the full denomination path, as uppercase hex.
+    pub fn hash(&self) -> String {
+        hash_full_path(&self.full_path())
+    }
+
+    /// Returns the IBC denomination in the format `ibc/{hash}`.
+    /// Native tokens (with empty path) are returned as the base denomination.
+    pub fn ibc_denom(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}{}", IBC_DENOM_PREFIX, self.hash())
+        }
+    }
+}
+
+/// Returns the hash of the full denomination path, as uppercase hex.
+fn hash_full_path(full_path: &str) -> String {
+    Sha256::digest(full_path.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect()
+}
+
+/// Registers the denomination trace, returns the IBC denomination.
+pub(crate) fn register_denom_trace(
+    storage: &mut dyn Storage,
+    trace: &DenomTrace,
+) -> AnyResult<String> {
+    if trace.base_denom.is_empty() {
+        bail!("base denomination cannot be empty");
+    }
+    // path must consist of port/channel identifier pairs
+    let segments = trace.path.split('/').collect::<Vec<_>>();
+    if segments.len() % 2 != 0 || segments.iter().any(|segment| segment.is_empty()) {
+        bail!("invalid denomination trace path: {}", trace.path);
+    }
+    let mut traces_storage = prefixed(storage, NAMESPACE_DENOM_TRACES);
+    DENOM_TRACES.save(&mut traces_storage, &trace.hash(), trace)?;
+    Ok(trace.ibc_denom())
+}
+
+/// Returns the denomination trace for specified hash, with or without `ibc/` prefix.
+pub(crate) fn denom_trace(storage: &dyn Storage, hash: &str) -> AnyResult<Option<DenomTrace>> {
+    let hash = hash.strip_prefix(IBC_DENOM_PREFIX).unwrap_or(hash);
+    let traces_storage = prefixed_read(storage, NAMESPACE_DENOM_TRACES);
+    Ok(DENOM_TRACES.may_load(&traces_storage, &hash.to_uppercase())?)
+}
+
+/// Returns the hash of the registered denomination trace with specified full path.
+pub(crate) fn denom_hash(storage: &dyn Storage, full_path: &str) -> AnyResult<Option<String>> {
+    let hash = hash_full_path(full_path);
+    Ok(denom_trace(storage, &hash)?.map(|_| hash))
+}
+
+/// Returns all registered denomination traces.
+pub(crate) fn denom_traces(storage: &dyn Storage) -> AnyResult<Vec<DenomTrace>> {
+    let traces_storage = prefixed_read(storage, NAMESPACE_DENOM_TRACES);
+    Ok(DENOM_TRACES
+        .range(&traces_storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, trace)| trace))
+        .collect::<StdResult<Vec<_>>>()?)
+}
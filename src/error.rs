@@ -1,7 +1,10 @@
 //! # Error definitions
 
 pub use anyhow::{anyhow, bail, Context as AnyContext, Error as AnyError, Result as AnyResult};
-use cosmwasm_std::{WasmMsg, WasmQuery};
+use cosmwasm_std::{from_json, to_json_binary, Binary, WasmMsg, WasmQuery};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
 use thiserror::Error;
 
 /// An enumeration of errors reported across the **CosmWasm MultiTest** library.
@@ -50,6 +53,43 @@ pub enum Error {
     /// Error variant for reporting duplicated contract addresses.
     #[error("Contract with this address already exists: {0}")]
     DuplicatedContractAddress(String),
+
+    /// Error variant for reporting that token transfers are disabled.
+    #[error("send transactions are disabled")]
+    SendDisabled,
+
+    /// Error variant for reporting that IBC fungible token transfers are disabled.
+    #[error("fungible token transfers from this chain are disabled")]
+    IbcTransfersDisabled,
+
+    /// Error variant for reporting that a module is paused.
+    #[error("tx type not allowed: {0} module is paused")]
+    ModulePaused(String),
+
+    /// Error variant for reporting an IBC port not bound to any contract.
+    #[error("port {0}: no contract bound to IBC port")]
+    UnknownIbcPort(String),
+
+    /// Error variant for reporting invalid threshold of the multisig admin.
+    #[error("invalid threshold {0} for {1} multisig members")]
+    InvalidMultisigThreshold(u64, usize),
+
+    /// Error variant for reporting that an account is not a multisig admin.
+    #[error("account {0} is not a multisig admin")]
+    NotMultisigAdmin(String),
+
+    /// Error variant for reporting that an account is not a member of the multisig admin.
+    #[error("account {0} is not a member of the multisig admin")]
+    NotMultisigMember(String),
+
+    /// Error variant for reporting that an admin operation was not approved by enough members.
+    #[error("admin operation approved by {0} of {1} required members")]
+    InsufficientApprovals(usize, u64),
+
+    /// Error variant for reporting a panic in contract's entry-point,
+    /// with the backtrace captured at the panic.
+    #[error("contract {0} panicked: {1}\nbacktrace:\n{2}")]
+    ContractPanicked(String, String, String),
 }
 
 impl Error {
@@ -107,4 +147,121 @@ impl Error {
     pub fn duplicated_contract_address(address: impl Into<String>) -> Self {
         Self::DuplicatedContractAddress(address.into())
     }
+
+    /// Creates an instance of the [Error](Self) for paused modules.
+    pub fn module_paused(module: impl Into<String>) -> Self {
+        Self::ModulePaused(module.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for IBC ports not bound to any contract.
+    pub fn unknown_ibc_port(port_id: impl Into<String>) -> Self {
+        Self::UnknownIbcPort(port_id.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for invalid threshold of the multisig admin.
+    pub fn invalid_multisig_threshold(threshold: u64, members: usize) -> Self {
+        Self::InvalidMultisigThreshold(threshold, members)
+    }
+
+    /// Creates an instance of the [Error](Self) for accounts that are not multisig admins.
+    pub fn not_multisig_admin(address: impl Into<String>) -> Self {
+        Self::NotMultisigAdmin(address.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for accounts that are not multisig members.
+    pub fn not_multisig_member(address: impl Into<String>) -> Self {
+        Self::NotMultisigMember(address.into())
+    }
+
+    /// Creates an instance of the [Error](Self) for insufficiently approved admin operations.
+    pub fn insufficient_approvals(approvals: usize, threshold: u64) -> Self {
+        Self::InsufficientApprovals(approvals, threshold)
+    }
+
+    /// Creates an instance of the [Error](Self) for panics in contract's entry-points.
+    pub fn contract_panicked(
+        address: impl Into<String>,
+        message: impl Into<String>,
+        backtrace: impl Into<String>,
+    ) -> Self {
+        Self::ContractPanicked(address.into(), message.into(), backtrace.into())
+    }
+}
+
+/// Error returned from contract's entry-points, carrying a structured JSON payload.
+///
+/// The payload survives routing through the [App](crate::App), even when the error
+/// is returned from a contract called by another contract, and can be retrieved
+/// from the returned error using [json](JsonErrorExt::json).
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+/// use cw_multi_test::error::JsonError;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Shortfall {
+///     missing: u128,
+/// }
+///
+/// fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: ()) -> Result<Response, JsonError> {
+///     Err(JsonError::new("insufficient funds", &Shortfall { missing: 10 }))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    /// Error message.
+    message: String,
+    /// JSON-serialized payload.
+    payload: Binary,
+}
+
+impl JsonError {
+    /// Creates an error with specified message and payload.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the payload can not be serialized to JSON.
+    pub fn new(message: impl Into<String>, payload: &impl Serialize) -> Self {
+        Self {
+            message: message.into(),
+            payload: to_json_binary(payload).expect("error payload should be serializable"),
+        }
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the JSON-serialized payload.
+    pub fn payload(&self) -> &Binary {
+        &self.payload
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// Extension for retrieving the payload of the [JsonError] from returned errors.
+pub trait JsonErrorExt {
+    /// Deserializes the payload of the [JsonError] that caused this error.
+    /// Returns an error when there is no payload, or it can not be deserialized to `T`.
+    fn json<T: DeserializeOwned>(&self) -> AnyResult<T>;
+}
+
+impl JsonErrorExt for AnyError {
+    fn json<T: DeserializeOwned>(&self) -> AnyResult<T> {
+        match self.downcast_ref::<JsonError>() {
+            Some(error) => Ok(from_json(&error.payload)?),
+            None => bail!("error has no JSON payload: {}", self.root_cause()),
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use crate::error::AnyResult;
+use crate::matchers::IntoFunds;
 use cosmwasm_std::{
     to_json_binary, Addr, Attribute, BankMsg, Binary, Coin, CosmosMsg, CustomMsg, Event,
     SubMsgResponse, WasmMsg,
@@ -41,6 +42,32 @@ impl AppResponse {
         })
     }
 
+    /// Returns all coins transferred to the recipient, summed from `transfer` events.
+    pub fn transferred_to(&self, recipient: &str) -> Vec<Coin> {
+        self.transferred("recipient", recipient)
+    }
+
+    /// Returns all coins transferred from the sender, summed from `transfer` events.
+    pub fn transferred_from(&self, sender: &str) -> Vec<Coin> {
+        self.transferred("sender", sender)
+    }
+
+    fn transferred(&self, key: &str, address: &str) -> Vec<Coin> {
+        self.events
+            .iter()
+            .filter(|event| event.ty == "transfer")
+            .filter(|event| {
+                event
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.key == key && attr.value == address)
+            })
+            .flat_map(|event| &event.attributes)
+            .filter(|attr| attr.key == "amount")
+            .flat_map(|attr| attr.value.as_str().into_funds())
+            .collect()
+    }
+
     /// Like [has_event](Self::has_event) but panics if there is no match.
     #[track_caller]
     pub fn assert_event(&self, expected: &Event) {
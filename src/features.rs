@@ -0,0 +1,57 @@
+//! # Experimental features
+//!
+//! Semantics that are not yet enabled on real-life chains can be switched on per [App](crate::App)
+//! instance, so library authors can test their contracts against the upcoming behavior
+//! before it lands. Enabled features are kept in the application's storage,
+//! so they are preserved when the application is cloned. All features are disabled by default.
+
+use crate::error::AnyResult;
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use cosmwasm_std::{Empty, Storage};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+
+/// Collection of enabled experimental features.
+const ENABLED_FEATURES: Map<&str, Empty> = Map::new("enabled_features");
+
+/// Default storage namespace for experimental features.
+const NAMESPACE_FEATURES: &[u8] = b"features";
+
+/// Experimental features that can be enabled per application.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum ExperimentalFeature {
+    /// Queries sent by the contract during execution observe the state written so far
+    /// in the same call, including the contract's own state (contract-to-self queries).
+    /// When disabled, queries observe the state from before the call.
+    QueryUncommittedState,
+}
+
+impl ExperimentalFeature {
+    /// Returns the name of the feature.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::QueryUncommittedState => "query_uncommitted_state",
+        }
+    }
+}
+
+/// Enables or disables the specified experimental feature.
+pub(crate) fn set_feature(
+    storage: &mut dyn Storage,
+    feature: ExperimentalFeature,
+    enabled: bool,
+) -> AnyResult<()> {
+    let mut features_storage = prefixed(storage, NAMESPACE_FEATURES);
+    if enabled {
+        ENABLED_FEATURES.save(&mut features_storage, feature.name(), &Empty {})?;
+    } else {
+        ENABLED_FEATURES.remove(&mut features_storage, feature.name());
+    }
+    Ok(())
+}
+
+/// Returns `true` when the specified experimental feature is enabled.
+pub(crate) fn is_feature_enabled(storage: &dyn Storage, feature: ExperimentalFeature) -> bool {
+    let features_storage = prefixed_read(storage, NAMESPACE_FEATURES);
+    ENABLED_FEATURES.has(&features_storage, feature.name())
+}
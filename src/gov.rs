@@ -1,9 +1,101 @@
+use crate::addresses::module_address;
+use crate::app::{CosmosRouter, CosmosSdkMsg, SudoMsg};
+use crate::bank::{BankKeeper, BankParams, BankSudo, ProtoCoin, ProtoParams as ProtoBankParams};
+use crate::error::{anyhow, bail, AnyResult};
+use crate::executor::AppResponse;
+use crate::param_history::record_param_change;
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use crate::staking::{
+    ProtoDuration, ProtoStakingParams, ProtoTimestamp, StakeKeeper, StakingInfo, StakingSudo,
+};
+use crate::transactions::transactional;
+use crate::wasm::WasmSudo;
 use crate::{AcceptingModule, FailingModule, Module};
-use cosmwasm_std::{Empty, GovMsg};
+use cosmwasm_std::{
+    Addr, AnyMsg, Api, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, CustomMsg, CustomQuery,
+    Decimal, Empty, Event, GovMsg, Order, Querier, StdResult, Storage, Timestamp, Uint128,
+    VoteOption, WasmMsg, WeightedVoteOption,
+};
+use cw_storage_plus::{Item, Map};
+use cw_utils::NativeBalance;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
 /// Handles governance-related operations within the test environment.
 /// This trait is essential for testing contracts that interact with governance mechanisms,
 /// simulating proposals, voting, and other governance activities.
-pub trait Gov: Module<ExecT = GovMsg, QueryT = Empty, SudoT = Empty> {}
+pub trait Gov: Module<ExecT = GovMsg, QueryT = Empty, SudoT = Empty> {
+    /// This is called for `MsgSubmitProposal` signed by the proposer.
+    /// If you're implementing a dummy governance module, this can fail.
+    fn submit_proposal<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _proposer: Addr,
+        _proposal: SubmitProposal,
+    ) -> AnyResult<AppResponse> {
+        bail!("submitting proposals is not supported")
+    }
+
+    /// This is called for `MsgDeposit` signed by the depositor.
+    /// If you're implementing a dummy governance module, this can fail.
+    #[allow(clippy::too_many_arguments)]
+    fn deposit<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _depositor: Addr,
+        _proposal_id: u64,
+        _amount: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        bail!("depositing is not supported")
+    }
+
+    /// This is called for `MsgCancelProposal` signed by the proposer.
+    /// If you're implementing a dummy governance module, this can fail.
+    fn cancel_proposal<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _proposer: Addr,
+        _proposal_id: u64,
+    ) -> AnyResult<AppResponse> {
+        bail!("cancelling proposals is not supported")
+    }
+
+    /// This is called for [GovSudo::UpdateParams], to change parameters of the governance module.
+    /// If you're implementing a dummy governance module, this can fail.
+    fn update_params(
+        &self,
+        _storage: &mut dyn Storage,
+        _block: &BlockInfo,
+        _params: GovParams,
+    ) -> AnyResult<AppResponse> {
+        bail!("updating params is not supported")
+    }
+
+    /// This is called from the end blocker (`update_block` / `set_block`) to tally votes
+    /// of proposals with the voting period ended and to drop proposals with the deposit
+    /// period ended.
+    /// If you're implementing a dummy governance module, this can be a no-op.
+    fn process_proposals<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+    ) -> AnyResult<AppResponse> {
+        Ok(AppResponse::default())
+    }
+}
 /// A type alias for a module that accepts governance-related interactions.
 /// It's used in scenarios where you need to test how your contract interacts
 /// with governance processes and messages.
@@ -15,3 +107,1833 @@ impl Gov for GovAcceptingModule {}
 pub type GovFailingModule = FailingModule<GovMsg, Empty, Empty>;
 
 impl Gov for GovFailingModule {}
+
+/// Parameters of the governance module, like `Params` of Cosmos SDK gov module.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct GovParams {
+    /// Minimum deposit for the proposal to enter the voting period.
+    pub min_deposit: Vec<Coin>,
+    /// Minimum deposit for the expedited proposal to enter the voting period.
+    pub expedited_min_deposit: Vec<Coin>,
+    /// Maximum duration of the deposit period in seconds.
+    pub max_deposit_period: u64,
+    /// Duration of the voting period in seconds.
+    pub voting_period: u64,
+    /// Minimum fraction of the bonded voting power that must vote for the result to be valid.
+    pub quorum: Decimal,
+    /// Minimum fraction of `yes` votes, not counting abstaining ones, for the proposal to pass.
+    pub threshold: Decimal,
+    /// Fraction of `no_with_veto` votes, not counting abstaining ones, vetoing the proposal.
+    pub veto_threshold: Decimal,
+    /// Duration of the voting period of expedited proposals in seconds.
+    pub expedited_voting_period: u64,
+    /// Minimum fraction of `yes` votes, not counting abstaining ones,
+    /// for the expedited proposal to pass.
+    pub expedited_threshold: Decimal,
+    /// Fraction of deposits charged when the proposal is cancelled, the rest is refunded.
+    pub proposal_cancel_ratio: Decimal,
+    /// Address receiving deposits charged when the proposal is cancelled,
+    /// charged deposits are burned when `None`.
+    pub proposal_cancel_dest: Option<Addr>,
+}
+
+impl Default for GovParams {
+    /// Creates governance parameters with the default values of Cosmos SDK,
+    /// except that no deposit is required for proposals to enter the voting period.
+    fn default() -> Self {
+        Self {
+            min_deposit: vec![],
+            expedited_min_deposit: vec![],
+            max_deposit_period: 60 * 60 * 24 * 2,
+            voting_period: 60 * 60 * 24 * 2,
+            quorum: Decimal::permille(334),
+            threshold: Decimal::percent(50),
+            veto_threshold: Decimal::permille(334),
+            expedited_voting_period: 60 * 60 * 24,
+            expedited_threshold: Decimal::permille(667),
+            proposal_cancel_ratio: Decimal::percent(50),
+            proposal_cancel_dest: None,
+        }
+    }
+}
+
+/// Privileged actions of the governance module.
+#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub enum GovSudo {
+    /// Replaces parameters of the governance module, like `MsgUpdateParams`.
+    /// Proposals already in the voting period keep their voting end time.
+    UpdateParams {
+        /// New governance parameters.
+        params: GovParams,
+    },
+}
+
+/// Content of the submitted proposal, decoded from `MsgSubmitProposal`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubmitProposal {
+    /// Title of the proposal.
+    pub title: String,
+    /// Summary of the proposal.
+    pub summary: String,
+    /// Whether the proposal is expedited.
+    pub expedited: bool,
+    /// Deposit sent by the proposer with the proposal.
+    pub initial_deposit: Vec<Coin>,
+    /// Messages executed by the governance module when the proposal passes.
+    pub messages: Vec<AnyMsg>,
+}
+
+/// Status of the proposal, like `ProposalStatus` in Cosmos SDK.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    /// The proposal awaits deposits reaching the minimum deposit.
+    DepositPeriod,
+    /// The proposal is open for voting.
+    VotingPeriod,
+    /// The proposal passed.
+    Passed,
+    /// The proposal was rejected.
+    Rejected,
+    /// The proposal passed, but executing its messages failed.
+    Failed,
+}
+
+/// Votes counted at the end of the voting period, like `TallyResult` in Cosmos SDK.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct TallyResult {
+    /// Voting power of `yes` votes.
+    pub yes: Uint128,
+    /// Voting power of `abstain` votes.
+    pub abstain: Uint128,
+    /// Voting power of `no` votes.
+    pub no: Uint128,
+    /// Voting power of `no_with_veto` votes.
+    pub no_with_veto: Uint128,
+}
+
+/// Governance proposal, like `Proposal` in Cosmos SDK.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Proposal {
+    /// Identifier of the proposal.
+    pub id: u64,
+    /// Address of the account which submitted the proposal.
+    pub proposer: Addr,
+    /// Title of the proposal.
+    pub title: String,
+    /// Summary of the proposal.
+    pub summary: String,
+    /// Messages executed by the governance module when the proposal passes.
+    pub messages: Vec<AnyMsg>,
+    /// Current status of the proposal.
+    pub status: ProposalStatus,
+    /// Whether the proposal is expedited, expedited proposals which did not pass
+    /// are converted to regular ones.
+    pub expedited: bool,
+    /// Block time when the proposal was submitted.
+    pub submit_time: Timestamp,
+    /// Block time when the deposit period ends.
+    pub deposit_end_time: Timestamp,
+    /// Sum of all deposits on the proposal.
+    pub total_deposit: Vec<Coin>,
+    /// Block time when the voting period started, set when the minimum deposit is reached.
+    pub voting_start_time: Option<Timestamp>,
+    /// Block time when the voting period ends, set when the minimum deposit is reached.
+    pub voting_end_time: Option<Timestamp>,
+    /// Result of tallying votes, set when the voting period ends.
+    pub final_tally_result: Option<TallyResult>,
+    /// Error of the failed proposal message, set when the proposal status is
+    /// [Failed](ProposalStatus::Failed).
+    pub failed_reason: Option<String>,
+}
+
+const PARAMS: Item<GovParams> = Item::new("params");
+/// (proposal id) -> proposal
+const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+/// Identifier of the most recently submitted proposal.
+const LAST_PROPOSAL_ID: Item<u64> = Item::new("last_proposal_id");
+/// (proposal id, voter) -> weighted options of the vote, removed when votes are tallied.
+const VOTES: Map<(u64, &Addr), Vec<WeightedVoteOption>> = Map::new("votes");
+/// (proposal id, depositor) -> deposited coins, removed when deposits are refunded or burned.
+const DEPOSITS: Map<(u64, &Addr), Vec<Coin>> = Map::new("deposits");
+
+pub const NAMESPACE_GOV: &[u8] = b"gov";
+
+/// Type URL of the message submitting a governance proposal.
+pub(crate) const MSG_SUBMIT_PROPOSAL: &str = "/cosmos.gov.v1.MsgSubmitProposal";
+/// Type URL of the message depositing tokens on a governance proposal.
+pub(crate) const MSG_DEPOSIT: &str = "/cosmos.gov.v1.MsgDeposit";
+/// Type URL of the message cancelling a governance proposal.
+pub(crate) const MSG_CANCEL_PROPOSAL: &str = "/cosmos.gov.v1.MsgCancelProposal";
+
+/// Name of the governance module account holding deposits.
+const GOV_MODULE: &str = "gov";
+
+/// Type URL of the message calling `sudo` entry point of the contract.
+const MSG_SUDO_CONTRACT: &str = "/cosmwasm.wasm.v1.MsgSudoContract";
+/// Type URL of the message executing the contract.
+const MSG_EXECUTE_CONTRACT: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+/// Type URL of the message migrating the contract.
+const MSG_MIGRATE_CONTRACT: &str = "/cosmwasm.wasm.v1.MsgMigrateContract";
+
+/// Type URL of the message updating parameters of the bank module.
+const MSG_UPDATE_BANK_PARAMS: &str = "/cosmos.bank.v1beta1.MsgUpdateParams";
+/// Type URL of the message updating parameters of the staking module.
+const MSG_UPDATE_STAKING_PARAMS: &str = "/cosmos.staking.v1beta1.MsgUpdateParams";
+/// Type URL of the message updating parameters of the governance module.
+const MSG_UPDATE_GOV_PARAMS: &str = "/cosmos.gov.v1.MsgUpdateParams";
+
+/// Path of the gRPC query for governance parameters.
+const QUERY_GOV_PARAMS: &str = "/cosmos.gov.v1.Query/Params";
+
+/// Path of the gRPC query for the proposal.
+const QUERY_PROPOSAL: &str = "/cosmos.gov.v1.Query/Proposal";
+
+/// Path of the gRPC query for proposals.
+const QUERY_PROPOSALS: &str = "/cosmos.gov.v1.Query/Proposals";
+
+/// Path of the gRPC query for the vote of the voter.
+const QUERY_VOTE: &str = "/cosmos.gov.v1.Query/Vote";
+
+/// Path of the gRPC query for votes on the proposal.
+const QUERY_VOTES: &str = "/cosmos.gov.v1.Query/Votes";
+
+/// Path of the gRPC query for the deposit of the depositor.
+const QUERY_DEPOSIT: &str = "/cosmos.gov.v1.Query/Deposit";
+
+/// Path of the gRPC query for deposits on the proposal.
+const QUERY_DEPOSITS: &str = "/cosmos.gov.v1.Query/Deposits";
+
+/// Path of the gRPC query for the tally result of the proposal.
+const QUERY_TALLY_RESULT: &str = "/cosmos.gov.v1.Query/TallyResult";
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoAny {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgSubmitProposal {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<ProtoAny>,
+    #[prost(message, repeated, tag = "2")]
+    pub initial_deposit: Vec<ProtoCoin>,
+    #[prost(string, tag = "3")]
+    pub proposer: String,
+    #[prost(string, tag = "5")]
+    pub title: String,
+    #[prost(string, tag = "6")]
+    pub summary: String,
+    #[prost(bool, tag = "7")]
+    pub expedited: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgDeposit {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+    #[prost(message, repeated, tag = "3")]
+    pub amount: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgSudoContract {
+    #[prost(string, tag = "1")]
+    pub authority: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub msg: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgExecuteContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub msg: Vec<u8>,
+    #[prost(message, repeated, tag = "5")]
+    pub funds: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgMigrateContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(uint64, tag = "3")]
+    pub code_id: u64,
+    #[prost(bytes = "vec", tag = "4")]
+    pub msg: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgUpdateBankParams {
+    #[prost(string, tag = "1")]
+    pub authority: String,
+    #[prost(message, optional, tag = "2")]
+    pub params: Option<ProtoBankParams>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgUpdateStakingParams {
+    #[prost(string, tag = "1")]
+    pub authority: String,
+    #[prost(message, optional, tag = "2")]
+    pub params: Option<ProtoStakingParams>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgUpdateGovParams {
+    #[prost(string, tag = "1")]
+    pub authority: String,
+    #[prost(message, optional, tag = "2")]
+    pub params: Option<ProtoGovParams>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoGovParams {
+    #[prost(message, repeated, tag = "1")]
+    pub min_deposit: Vec<ProtoCoin>,
+    #[prost(message, optional, tag = "2")]
+    pub max_deposit_period: Option<ProtoDuration>,
+    #[prost(message, optional, tag = "3")]
+    pub voting_period: Option<ProtoDuration>,
+    #[prost(string, tag = "4")]
+    pub quorum: String,
+    #[prost(string, tag = "5")]
+    pub threshold: String,
+    #[prost(string, tag = "6")]
+    pub veto_threshold: String,
+    #[prost(string, tag = "8")]
+    pub proposal_cancel_ratio: String,
+    #[prost(string, tag = "9")]
+    pub proposal_cancel_dest: String,
+    #[prost(message, optional, tag = "10")]
+    pub expedited_voting_period: Option<ProtoDuration>,
+    #[prost(string, tag = "11")]
+    pub expedited_threshold: String,
+    #[prost(message, repeated, tag = "12")]
+    pub expedited_min_deposit: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryGovParamsResponse {
+    #[prost(message, optional, tag = "4")]
+    pub params: Option<ProtoGovParams>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoTallyResult {
+    #[prost(string, tag = "1")]
+    pub yes_count: String,
+    #[prost(string, tag = "2")]
+    pub abstain_count: String,
+    #[prost(string, tag = "3")]
+    pub no_count: String,
+    #[prost(string, tag = "4")]
+    pub no_with_veto_count: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoProposal {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(message, repeated, tag = "2")]
+    pub messages: Vec<ProtoAny>,
+    #[prost(int32, tag = "3")]
+    pub status: i32,
+    #[prost(message, optional, tag = "4")]
+    pub final_tally_result: Option<ProtoTallyResult>,
+    #[prost(message, optional, tag = "5")]
+    pub submit_time: Option<ProtoTimestamp>,
+    #[prost(message, optional, tag = "6")]
+    pub deposit_end_time: Option<ProtoTimestamp>,
+    #[prost(message, repeated, tag = "7")]
+    pub total_deposit: Vec<ProtoCoin>,
+    #[prost(message, optional, tag = "8")]
+    pub voting_start_time: Option<ProtoTimestamp>,
+    #[prost(message, optional, tag = "9")]
+    pub voting_end_time: Option<ProtoTimestamp>,
+    #[prost(string, tag = "11")]
+    pub title: String,
+    #[prost(string, tag = "12")]
+    pub summary: String,
+    #[prost(string, tag = "13")]
+    pub proposer: String,
+    #[prost(bool, tag = "14")]
+    pub expedited: bool,
+    #[prost(string, tag = "15")]
+    pub failed_reason: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoWeightedVoteOption {
+    #[prost(int32, tag = "1")]
+    pub option: i32,
+    #[prost(string, tag = "2")]
+    pub weight: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoVote {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub voter: String,
+    #[prost(message, repeated, tag = "4")]
+    pub options: Vec<ProtoWeightedVoteOption>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoDeposit {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+    #[prost(message, repeated, tag = "3")]
+    pub amount: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryProposalRequest {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryProposalResponse {
+    #[prost(message, optional, tag = "1")]
+    pub proposal: Option<ProtoProposal>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryProposalsRequest {
+    #[prost(int32, tag = "1")]
+    pub proposal_status: i32,
+    #[prost(string, tag = "2")]
+    pub voter: String,
+    #[prost(string, tag = "3")]
+    pub depositor: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryProposalsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub proposals: Vec<ProtoProposal>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryVoteRequest {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub voter: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryVoteResponse {
+    #[prost(message, optional, tag = "1")]
+    pub vote: Option<ProtoVote>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryVotesRequest {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryVotesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub votes: Vec<ProtoVote>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDepositRequest {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDepositResponse {
+    #[prost(message, optional, tag = "1")]
+    pub deposit: Option<ProtoDeposit>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDepositsRequest {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDepositsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub deposits: Vec<ProtoDeposit>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryTallyResultRequest {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryTallyResultResponse {
+    #[prost(message, optional, tag = "1")]
+    pub tally: Option<ProtoTallyResult>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgCancelProposal {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub proposer: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgCancelProposalResponse {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(message, optional, tag = "2")]
+    pub canceled_time: Option<ProtoTimestamp>,
+    #[prost(uint64, tag = "3")]
+    pub canceled_height: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgSubmitProposalResponse {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+}
+
+/// Decodes the protobuf-encoded `MsgSubmitProposal` signed by the sender.
+pub(crate) fn decode_submit_proposal(sender: &Addr, value: &[u8]) -> AnyResult<CosmosSdkMsg> {
+    let msg = <ProtoMsgSubmitProposal as prost::Message>::decode(value)?;
+    if msg.proposer != sender.as_str() {
+        bail!(
+            "proposer address {} does not match the signer {}",
+            msg.proposer,
+            sender
+        );
+    }
+    Ok(CosmosSdkMsg::SubmitProposal(SubmitProposal {
+        title: msg.title,
+        summary: msg.summary,
+        expedited: msg.expedited,
+        initial_deposit: decode_coins(msg.initial_deposit)?,
+        messages: msg
+            .messages
+            .into_iter()
+            .map(|msg| AnyMsg {
+                type_url: msg.type_url,
+                value: msg.value.into(),
+            })
+            .collect(),
+    }))
+}
+
+/// Message of the proposal, executed by the governance module when the proposal passes.
+enum ProposalMsg {
+    /// Privileged action of the module, like `MsgSudoContract` or `MsgUpdateParams`.
+    Sudo(SudoMsg),
+    /// Message executed by the wasm module with the governance module as the sender.
+    Wasm(WasmMsg),
+    /// Any other message, routed like `CosmosMsg::Any` with the governance module as the sender,
+    /// so custom modules registered as [Stargate](crate::Stargate) handlers can check the authority.
+    Any(AnyMsg),
+}
+
+/// Decodes the message of the proposal, which must be signed by the governance module.
+/// Parameters not present in updated module parameters are kept unchanged.
+/// Signers of messages not known to the governance module are not checked.
+fn decode_proposal_msg(
+    api: &dyn Api,
+    storage: &dyn Storage,
+    gov_module: &Addr,
+    msg: &AnyMsg,
+) -> AnyResult<ProposalMsg> {
+    let value = msg.value.as_slice();
+    let (signer, proposal_msg) = match msg.type_url.as_str() {
+        MSG_SUDO_CONTRACT => {
+            let msg = <ProtoMsgSudoContract as prost::Message>::decode(value)?;
+            let sudo = WasmSudo {
+                contract_addr: api.addr_validate(&msg.contract)?,
+                message: msg.msg.into(),
+            };
+            (msg.authority, ProposalMsg::Sudo(sudo.into()))
+        }
+        MSG_EXECUTE_CONTRACT => {
+            let msg = <ProtoMsgExecuteContract as prost::Message>::decode(value)?;
+            let execute = WasmMsg::Execute {
+                contract_addr: msg.contract,
+                msg: msg.msg.into(),
+                funds: decode_coins(msg.funds)?,
+            };
+            (msg.sender, ProposalMsg::Wasm(execute))
+        }
+        MSG_MIGRATE_CONTRACT => {
+            let msg = <ProtoMsgMigrateContract as prost::Message>::decode(value)?;
+            let migrate = WasmMsg::Migrate {
+                contract_addr: msg.contract,
+                new_code_id: msg.code_id,
+                msg: msg.msg.into(),
+            };
+            (msg.sender, ProposalMsg::Wasm(migrate))
+        }
+        MSG_UPDATE_BANK_PARAMS => {
+            let msg = <ProtoMsgUpdateBankParams as prost::Message>::decode(value)?;
+            let proto = msg.params.unwrap_or_default();
+            // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/bank/types/params.go#L35-L45
+            if !proto.send_enabled.is_empty() {
+                bail!("use of send_enabled in params is no longer supported");
+            }
+            let params = BankParams {
+                default_send_enabled: proto.default_send_enabled,
+                ..BankKeeper::new().params(storage)?
+            };
+            (
+                msg.authority,
+                ProposalMsg::Sudo(BankSudo::SetParams { params }.into()),
+            )
+        }
+        MSG_UPDATE_STAKING_PARAMS => {
+            let msg = <ProtoMsgUpdateStakingParams as prost::Message>::decode(value)?;
+            let proto = msg.params.unwrap_or_default();
+            let params = StakingInfo {
+                bonded_denom: proto.bond_denom,
+                unbonding_time: decode_duration(proto.unbonding_time),
+                max_validators: proto.max_validators,
+                max_entries: proto.max_entries,
+                historical_entries: proto.historical_entries,
+                // encoded like `LegacyDec`, with 18 decimal places
+                min_commission_rate: Decimal::from_atomics(
+                    proto.min_commission_rate.parse::<Uint128>()?,
+                    Decimal::DECIMAL_PLACES,
+                )?,
+                ..StakeKeeper::new().staking_info(storage)?
+            };
+            let sudo = StakingSudo::UpdateParams { params };
+            (msg.authority, ProposalMsg::Sudo(sudo.into()))
+        }
+        MSG_UPDATE_GOV_PARAMS => {
+            let msg = <ProtoMsgUpdateGovParams as prost::Message>::decode(value)?;
+            let proto = msg.params.unwrap_or_default();
+            let params = GovParams {
+                min_deposit: decode_coins(proto.min_deposit)?,
+                expedited_min_deposit: decode_coins(proto.expedited_min_deposit)?,
+                max_deposit_period: decode_duration(proto.max_deposit_period),
+                voting_period: decode_duration(proto.voting_period),
+                quorum: proto.quorum.parse()?,
+                threshold: proto.threshold.parse()?,
+                veto_threshold: proto.veto_threshold.parse()?,
+                expedited_voting_period: decode_duration(proto.expedited_voting_period),
+                expedited_threshold: proto.expedited_threshold.parse()?,
+                proposal_cancel_ratio: proto.proposal_cancel_ratio.parse()?,
+                proposal_cancel_dest: match proto.proposal_cancel_dest.as_str() {
+                    "" => None,
+                    dest => Some(api.addr_validate(dest)?),
+                },
+            };
+            (
+                msg.authority,
+                ProposalMsg::Sudo(GovSudo::UpdateParams { params }.into()),
+            )
+        }
+        _ => return Ok(ProposalMsg::Any(msg.clone())),
+    };
+    // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/keeper/proposal.go#L46-L51
+    if signer != gov_module.as_str() {
+        bail!("expected gov account as only signer for proposal message");
+    }
+    Ok(proposal_msg)
+}
+
+/// Decodes the protobuf-encoded `MsgDeposit` signed by the sender.
+pub(crate) fn decode_deposit(sender: &Addr, value: &[u8]) -> AnyResult<CosmosSdkMsg> {
+    let msg = <ProtoMsgDeposit as prost::Message>::decode(value)?;
+    if msg.depositor != sender.as_str() {
+        bail!(
+            "depositor address {} does not match the signer {}",
+            msg.depositor,
+            sender
+        );
+    }
+    Ok(CosmosSdkMsg::Deposit {
+        proposal_id: msg.proposal_id,
+        amount: decode_coins(msg.amount)?,
+    })
+}
+
+/// Decodes the protobuf-encoded `MsgCancelProposal` signed by the sender.
+pub(crate) fn decode_cancel_proposal(sender: &Addr, value: &[u8]) -> AnyResult<CosmosSdkMsg> {
+    let msg = <ProtoMsgCancelProposal as prost::Message>::decode(value)?;
+    if msg.proposer != sender.as_str() {
+        bail!(
+            "proposer address {} does not match the signer {}",
+            msg.proposer,
+            sender
+        );
+    }
+    Ok(CosmosSdkMsg::CancelProposal {
+        proposal_id: msg.proposal_id,
+    })
+}
+
+fn decode_coins(coins: Vec<ProtoCoin>) -> AnyResult<Vec<Coin>> {
+    coins
+        .into_iter()
+        .map(|c| Ok(Coin::new(c.amount.parse::<Uint128>()?, c.denom)))
+        .collect()
+}
+
+fn encode_coins(coins: &[Coin]) -> Vec<ProtoCoin> {
+    coins
+        .iter()
+        .map(|c| ProtoCoin {
+            denom: c.denom.clone(),
+            amount: c.amount.to_string(),
+        })
+        .collect()
+}
+
+fn decode_duration(duration: Option<ProtoDuration>) -> u64 {
+    duration.map_or(0, |d| d.seconds as u64)
+}
+
+fn encode_duration(seconds: u64) -> Option<ProtoDuration> {
+    Some(ProtoDuration {
+        seconds: seconds as i64,
+        nanos: 0,
+    })
+}
+
+fn encode_timestamp(timestamp: Timestamp) -> ProtoTimestamp {
+    ProtoTimestamp {
+        seconds: timestamp.seconds() as i64,
+        nanos: timestamp.subsec_nanos() as i32,
+    }
+}
+
+fn encode_tally(tally: TallyResult) -> ProtoTallyResult {
+    ProtoTallyResult {
+        yes_count: tally.yes.to_string(),
+        abstain_count: tally.abstain.to_string(),
+        no_count: tally.no.to_string(),
+        no_with_veto_count: tally.no_with_veto.to_string(),
+    }
+}
+
+/// Returns the value of the status like `ProposalStatus` enumeration in Cosmos SDK.
+fn encode_status(status: ProposalStatus) -> i32 {
+    match status {
+        ProposalStatus::DepositPeriod => 1,
+        ProposalStatus::VotingPeriod => 2,
+        ProposalStatus::Passed => 3,
+        ProposalStatus::Rejected => 4,
+        ProposalStatus::Failed => 5,
+    }
+}
+
+fn encode_proposal(proposal: Proposal) -> ProtoProposal {
+    ProtoProposal {
+        id: proposal.id,
+        messages: proposal
+            .messages
+            .into_iter()
+            .map(|msg| ProtoAny {
+                type_url: msg.type_url,
+                value: msg.value.to_vec(),
+            })
+            .collect(),
+        status: encode_status(proposal.status),
+        final_tally_result: proposal.final_tally_result.map(encode_tally),
+        submit_time: Some(encode_timestamp(proposal.submit_time)),
+        deposit_end_time: Some(encode_timestamp(proposal.deposit_end_time)),
+        total_deposit: encode_coins(&proposal.total_deposit),
+        voting_start_time: proposal.voting_start_time.map(encode_timestamp),
+        voting_end_time: proposal.voting_end_time.map(encode_timestamp),
+        title: proposal.title,
+        summary: proposal.summary,
+        proposer: proposal.proposer.into_string(),
+        expedited: proposal.expedited,
+        failed_reason: proposal.failed_reason.unwrap_or_default(),
+    }
+}
+
+fn encode_vote(proposal_id: u64, voter: Addr, options: Vec<WeightedVoteOption>) -> ProtoVote {
+    ProtoVote {
+        proposal_id,
+        voter: voter.into_string(),
+        options: options
+            .into_iter()
+            .map(|o| ProtoWeightedVoteOption {
+                // values of `VoteOption` enumeration in Cosmos SDK start with 1
+                option: option_index(&o.option) as i32 + 1,
+                weight: encode_dec(o.weight),
+            })
+            .collect(),
+    }
+}
+
+fn encode_deposit(proposal_id: u64, depositor: Addr, amount: Vec<Coin>) -> ProtoDeposit {
+    ProtoDeposit {
+        proposal_id,
+        depositor: depositor.into_string(),
+        amount: encode_coins(&amount),
+    }
+}
+
+/// Formats the decimal like `LegacyDec.String` in Cosmos SDK, with 18 decimal places.
+fn encode_dec(value: Decimal) -> String {
+    let atomics = value.atomics().u128();
+    let units = 10u128.pow(Decimal::DECIMAL_PLACES);
+    format!("{}.{:018}", atomics / units, atomics % units)
+}
+
+/// Returns `true` when the path is the gRPC query of the governance module.
+pub(crate) fn is_gov_grpc_query(path: &str) -> bool {
+    [
+        QUERY_GOV_PARAMS,
+        QUERY_PROPOSAL,
+        QUERY_PROPOSALS,
+        QUERY_VOTE,
+        QUERY_VOTES,
+        QUERY_DEPOSIT,
+        QUERY_DEPOSITS,
+        QUERY_TALLY_RESULT,
+    ]
+    .contains(&path)
+}
+
+/// Returns the protobuf-encoded response to the gRPC query of the governance module.
+///
+/// Like on chain, votes are available only until the proposal is tallied and deposits
+/// until they are refunded or burned. Lists are not paginated, all entries are returned
+/// in a single page.
+pub(crate) fn query_gov_grpc(
+    api: &dyn Api,
+    storage: &dyn Storage,
+    _block: &BlockInfo,
+    path: &str,
+    data: &[u8],
+) -> AnyResult<Binary> {
+    let gov = GovKeeper::new();
+    let response = match path {
+        QUERY_GOV_PARAMS => {
+            let params = gov.params(storage)?;
+            prost::Message::encode_to_vec(&ProtoQueryGovParamsResponse {
+                params: Some(ProtoGovParams {
+                    min_deposit: encode_coins(&params.min_deposit),
+                    max_deposit_period: encode_duration(params.max_deposit_period),
+                    voting_period: encode_duration(params.voting_period),
+                    quorum: encode_dec(params.quorum),
+                    threshold: encode_dec(params.threshold),
+                    veto_threshold: encode_dec(params.veto_threshold),
+                    proposal_cancel_ratio: encode_dec(params.proposal_cancel_ratio),
+                    proposal_cancel_dest: params
+                        .proposal_cancel_dest
+                        .map(Addr::into_string)
+                        .unwrap_or_default(),
+                    expedited_voting_period: encode_duration(params.expedited_voting_period),
+                    expedited_threshold: encode_dec(params.expedited_threshold),
+                    expedited_min_deposit: encode_coins(&params.expedited_min_deposit),
+                }),
+            })
+        }
+        QUERY_PROPOSAL => {
+            let request = <ProtoQueryProposalRequest as prost::Message>::decode(data)?;
+            let proposal = gov.proposal(storage, request.proposal_id)?;
+            prost::Message::encode_to_vec(&ProtoQueryProposalResponse {
+                proposal: Some(encode_proposal(proposal)),
+            })
+        }
+        QUERY_PROPOSALS => {
+            let request = <ProtoQueryProposalsRequest as prost::Message>::decode(data)?;
+            let validate = |addr: &str| -> AnyResult<Option<Addr>> {
+                Ok(if addr.is_empty() {
+                    None
+                } else {
+                    Some(api.addr_validate(addr)?)
+                })
+            };
+            let voter = validate(&request.voter)?;
+            let depositor = validate(&request.depositor)?;
+            let mut proposals = vec![];
+            for proposal in gov.proposals(storage)? {
+                if request.proposal_status != 0
+                    && request.proposal_status != encode_status(proposal.status)
+                {
+                    continue;
+                }
+                if let Some(voter) = &voter {
+                    if gov.vote(storage, proposal.id, voter)?.is_none() {
+                        continue;
+                    }
+                }
+                if let Some(depositor) = &depositor {
+                    if gov.deposit(storage, proposal.id, depositor)?.is_none() {
+                        continue;
+                    }
+                }
+                proposals.push(encode_proposal(proposal));
+            }
+            prost::Message::encode_to_vec(&ProtoQueryProposalsResponse { proposals })
+        }
+        QUERY_VOTE => {
+            let request = <ProtoQueryVoteRequest as prost::Message>::decode(data)?;
+            let voter = api.addr_validate(&request.voter)?;
+            let options = gov
+                .vote(storage, request.proposal_id, &voter)?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "voter: {} not found for proposal: {}",
+                        voter,
+                        request.proposal_id
+                    )
+                })?;
+            prost::Message::encode_to_vec(&ProtoQueryVoteResponse {
+                vote: Some(encode_vote(request.proposal_id, voter, options)),
+            })
+        }
+        QUERY_VOTES => {
+            let request = <ProtoQueryVotesRequest as prost::Message>::decode(data)?;
+            let votes = gov
+                .votes(storage, request.proposal_id)?
+                .into_iter()
+                .map(|(voter, options)| encode_vote(request.proposal_id, voter, options))
+                .collect();
+            prost::Message::encode_to_vec(&ProtoQueryVotesResponse { votes })
+        }
+        QUERY_DEPOSIT => {
+            let request = <ProtoQueryDepositRequest as prost::Message>::decode(data)?;
+            let depositor = api.addr_validate(&request.depositor)?;
+            let amount = gov
+                .deposit(storage, request.proposal_id, &depositor)?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "depositer: {} not found for proposal: {}",
+                        depositor,
+                        request.proposal_id
+                    )
+                })?;
+            prost::Message::encode_to_vec(&ProtoQueryDepositResponse {
+                deposit: Some(encode_deposit(request.proposal_id, depositor, amount)),
+            })
+        }
+        QUERY_DEPOSITS => {
+            let request = <ProtoQueryDepositsRequest as prost::Message>::decode(data)?;
+            let deposits = gov
+                .deposits(storage, request.proposal_id)?
+                .into_iter()
+                .map(|(depositor, amount)| encode_deposit(request.proposal_id, depositor, amount))
+                .collect();
+            prost::Message::encode_to_vec(&ProtoQueryDepositsResponse { deposits })
+        }
+        QUERY_TALLY_RESULT => {
+            let request = <ProtoQueryTallyResultRequest as prost::Message>::decode(data)?;
+            let tally = gov.tally_result(storage, request.proposal_id)?;
+            prost::Message::encode_to_vec(&ProtoQueryTallyResultResponse {
+                tally: Some(encode_tally(tally)),
+            })
+        }
+        _ => bail!("unsupported gov query: {}", path),
+    };
+    Ok(response.into())
+}
+
+/// Governance module with proposals voted on by stakers, using the voting power
+/// of delegations tracked by [StakeKeeper].
+#[derive(Clone, Default)]
+pub struct GovKeeper {}
+
+impl GovKeeper {
+    /// Creates a new governance keeper with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets parameters of the governance module.
+    pub fn setup(&self, storage: &mut dyn Storage, params: GovParams) -> AnyResult<()> {
+        Self::validate_params(&params)?;
+        let mut storage = prefixed(storage, NAMESPACE_GOV);
+        PARAMS.save(&mut storage, &params)?;
+        Ok(())
+    }
+
+    /// Returns parameters of the governance module, set with [setup](Self::setup)
+    /// or changed with [GovSudo::UpdateParams].
+    pub fn params(&self, storage: &dyn Storage) -> AnyResult<GovParams> {
+        let storage = prefixed_read(storage, NAMESPACE_GOV);
+        Self::get_params(&storage)
+    }
+
+    fn validate_params(params: &GovParams) -> AnyResult<()> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.50.1/x/gov/types/v1/params.go#L164-L230
+        if params.max_deposit_period == 0 {
+            bail!("maximum deposit period must be positive");
+        }
+        if params.voting_period == 0 {
+            bail!("voting period must be positive");
+        }
+        if params.quorum > Decimal::one() {
+            bail!("quorum too large: {}", params.quorum);
+        }
+        if params.threshold.is_zero() || params.threshold > Decimal::one() {
+            bail!("vote threshold must be positive and at most one");
+        }
+        if params.veto_threshold.is_zero() || params.veto_threshold > Decimal::one() {
+            bail!("veto threshold must be positive and at most one");
+        }
+        if params.expedited_voting_period >= params.voting_period {
+            bail!("expedited voting period must be strictly less than the regular voting period");
+        }
+        if params.expedited_threshold <= params.threshold
+            || params.expedited_threshold > Decimal::one()
+        {
+            bail!("expedited threshold must be greater than the regular threshold and at most one");
+        }
+        if params.proposal_cancel_ratio > Decimal::one() {
+            bail!("proposal cancel ratio must be at most one");
+        }
+        Ok(())
+    }
+
+    /// Returns the proposal with specified identifier.
+    pub fn proposal(&self, storage: &dyn Storage, proposal_id: u64) -> AnyResult<Proposal> {
+        let storage = prefixed_read(storage, NAMESPACE_GOV);
+        Self::get_proposal(&storage, proposal_id)
+    }
+
+    /// Returns all proposals, ordered by identifiers. Proposals dropped at the end
+    /// of the deposit period and cancelled ones are not returned.
+    pub fn proposals(&self, storage: &dyn Storage) -> AnyResult<Vec<Proposal>> {
+        let storage = prefixed_read(storage, NAMESPACE_GOV);
+        Ok(PROPOSALS
+            .range(&storage, None, None, Order::Ascending)
+            .map(|entry| entry.map(|(_, proposal)| proposal))
+            .collect::<StdResult<Vec<_>>>()?)
+    }
+
+    /// Returns the tally result of the proposal: the final one when the voting period ended,
+    /// votes counted so far when the proposal is in the voting period
+    /// and an empty one when the proposal is in the deposit period.
+    pub fn tally_result(&self, storage: &dyn Storage, proposal_id: u64) -> AnyResult<TallyResult> {
+        let gov_storage = prefixed_read(storage, NAMESPACE_GOV);
+        let proposal = Self::get_proposal(&gov_storage, proposal_id)?;
+        Ok(match proposal.status {
+            ProposalStatus::DepositPeriod => TallyResult::default(),
+            ProposalStatus::VotingPeriod => {
+                let params = Self::get_params(&gov_storage)?;
+                Self::tally(storage, &params, &proposal)?.2
+            }
+            _ => proposal.final_tally_result.unwrap_or_default(),
+        })
+    }
+
+    /// Returns all votes on the proposal, ordered by voter addresses.
+    /// Votes are removed when the proposal is tallied.
+    pub fn votes(
+        &self,
+        storage: &dyn Storage,
+        proposal_id: u64,
+    ) -> AnyResult<Vec<(Addr, Vec<WeightedVoteOption>)>> {
+        let storage = prefixed_read(storage, NAMESPACE_GOV);
+        Ok(VOTES
+            .prefix(proposal_id)
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?)
+    }
+
+    /// Returns weighted options of the voter's vote on the proposal, `None` when the voter
+    /// did not vote or the votes were already tallied.
+    pub fn vote(
+        &self,
+        storage: &dyn Storage,
+        proposal_id: u64,
+        voter: &Addr,
+    ) -> AnyResult<Option<Vec<WeightedVoteOption>>> {
+        let storage = prefixed_read(storage, NAMESPACE_GOV);
+        Ok(VOTES.may_load(&storage, (proposal_id, voter))?)
+    }
+
+    /// Returns coins deposited by the depositor on the proposal, `None` when the depositor
+    /// did not deposit or the deposit was already refunded or burned.
+    pub fn deposit(
+        &self,
+        storage: &dyn Storage,
+        proposal_id: u64,
+        depositor: &Addr,
+    ) -> AnyResult<Option<Vec<Coin>>> {
+        let storage = prefixed_read(storage, NAMESPACE_GOV);
+        Ok(DEPOSITS.may_load(&storage, (proposal_id, depositor))?)
+    }
+
+    /// Returns all deposits on the proposal, which were not yet refunded or burned.
+    pub fn deposits(
+        &self,
+        storage: &dyn Storage,
+        proposal_id: u64,
+    ) -> AnyResult<Vec<(Addr, Vec<Coin>)>> {
+        let storage = prefixed_read(storage, NAMESPACE_GOV);
+        Ok(DEPOSITS
+            .prefix(proposal_id)
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?)
+    }
+
+    fn get_params(gov_storage: &dyn Storage) -> AnyResult<GovParams> {
+        Ok(PARAMS.may_load(gov_storage)?.unwrap_or_default())
+    }
+
+    fn get_proposal(gov_storage: &dyn Storage, proposal_id: u64) -> AnyResult<Proposal> {
+        PROPOSALS
+            .may_load(gov_storage, proposal_id)?
+            .ok_or_else(|| anyhow!("proposal {} doesn't exist", proposal_id))
+    }
+
+    /// Saves the vote of the voter, replacing the previous one.
+    fn add_vote(
+        &self,
+        storage: &mut dyn Storage,
+        voter: Addr,
+        proposal_id: u64,
+        options: Vec<WeightedVoteOption>,
+    ) -> AnyResult<AppResponse> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/types/v1/vote.go#L118-L145
+        if options.is_empty() {
+            bail!("invalid vote option: no options");
+        }
+        let mut total_weight = Decimal::zero();
+        for (index, option) in options.iter().enumerate() {
+            if option.weight.is_zero() || option.weight > Decimal::one() {
+                bail!("invalid vote option: weight must be positive and at most one");
+            }
+            if options[..index].iter().any(|o| o.option == option.option) {
+                bail!("invalid vote option: duplicated option");
+            }
+            total_weight += option.weight;
+        }
+        if total_weight != Decimal::one() {
+            bail!("invalid vote option: total weight must be one");
+        }
+
+        let mut gov_storage = prefixed(storage, NAMESPACE_GOV);
+        let proposal = Self::get_proposal(&gov_storage, proposal_id)?;
+        if proposal.status != ProposalStatus::VotingPeriod {
+            bail!("inactive proposal {}", proposal_id);
+        }
+        VOTES.save(&mut gov_storage, (proposal_id, &voter), &options)?;
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/keeper/vote.go#L38-L44
+        let events = vec![Event::new("proposal_vote")
+            .add_attribute("option", options_to_string(&options))
+            .add_attribute("proposal_id", proposal_id.to_string())
+            .add_attribute("voter", voter)];
+        Ok(AppResponse { events, data: None })
+    }
+
+    /// Moves deposited coins to the governance module account and starts the voting period
+    /// when the minimum deposit is reached. Returns whether the voting period started
+    /// and events of the deposit.
+    #[allow(clippy::too_many_arguments)]
+    fn add_deposit<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        depositor: Addr,
+        proposal_id: u64,
+        amount: NativeBalance,
+    ) -> AnyResult<(bool, Vec<Event>)> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/keeper/deposit.go#L108-L162
+        let gov_storage = prefixed_read(storage, NAMESPACE_GOV);
+        let params = Self::get_params(&gov_storage)?;
+        let mut proposal = Self::get_proposal(&gov_storage, proposal_id)?;
+        if !matches!(
+            proposal.status,
+            ProposalStatus::DepositPeriod | ProposalStatus::VotingPeriod
+        ) {
+            bail!("inactive proposal {}", proposal_id);
+        }
+        let min_deposit = if proposal.expedited {
+            &params.expedited_min_deposit
+        } else {
+            &params.min_deposit
+        };
+        if !params.min_deposit.is_empty() {
+            if let Some(coin) = amount
+                .0
+                .iter()
+                .find(|c| params.min_deposit.iter().all(|m| m.denom != c.denom))
+            {
+                bail!(
+                    "deposited {}, but gov accepts only the following denom(s): {}",
+                    coin,
+                    params
+                        .min_deposit
+                        .iter()
+                        .map(|c| c.denom.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+        }
+
+        let mut events = vec![];
+        if !amount.is_empty() {
+            router.execute(
+                api,
+                storage,
+                block,
+                depositor.clone(),
+                BankMsg::Send {
+                    to_address: module_address(api, GOV_MODULE)?.to_string(),
+                    amount: amount.clone().into_vec(),
+                }
+                .into(),
+            )?;
+            let mut gov_storage = prefixed(storage, NAMESPACE_GOV);
+            let deposit = DEPOSITS
+                .may_load(&gov_storage, (proposal_id, &depositor))?
+                .unwrap_or_default();
+            let deposit = NativeBalance(deposit) + amount.clone();
+            DEPOSITS.save(
+                &mut gov_storage,
+                (proposal_id, &depositor),
+                &deposit.into_vec(),
+            )?;
+            proposal.total_deposit =
+                (NativeBalance(proposal.total_deposit) + amount.clone()).into_vec();
+            events.push(
+                Event::new("proposal_deposit")
+                    .add_attribute("amount", amount.to_string())
+                    .add_attribute("proposal_id", proposal_id.to_string()),
+            );
+        }
+
+        let total_deposit = NativeBalance(proposal.total_deposit.clone());
+        let activated = proposal.status == ProposalStatus::DepositPeriod
+            && min_deposit.iter().all(|c| total_deposit.has(c));
+        if activated {
+            let voting_period = if proposal.expedited {
+                params.expedited_voting_period
+            } else {
+                params.voting_period
+            };
+            proposal.status = ProposalStatus::VotingPeriod;
+            proposal.voting_start_time = Some(block.time);
+            proposal.voting_end_time = Some(block.time.plus_seconds(voting_period));
+        }
+        PROPOSALS.save(
+            &mut prefixed(storage, NAMESPACE_GOV),
+            proposal_id,
+            &proposal,
+        )?;
+        Ok((activated, events))
+    }
+
+    /// Charges the fraction of every deposit on the proposal, burning charged coins or sending
+    /// them to the destination, refunds the rest to depositors and removes deposit records.
+    #[allow(clippy::too_many_arguments)]
+    fn charge_deposits<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        proposal_id: u64,
+        ratio: Decimal,
+        destination: Option<&Addr>,
+    ) -> AnyResult<()> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.50.1/x/gov/keeper/deposit.go#L208-L270
+        let deposits = self.deposits(storage, proposal_id)?;
+        let gov_module = module_address(api, GOV_MODULE)?;
+        for (depositor, amount) in deposits {
+            DEPOSITS.remove(
+                &mut prefixed(storage, NAMESPACE_GOV),
+                (proposal_id, &depositor),
+            );
+            let mut charged = vec![];
+            let mut refunded = vec![];
+            for coin in amount {
+                let charged_amount = coin.amount.mul_floor(ratio);
+                let refunded_amount = coin.amount - charged_amount;
+                if !charged_amount.is_zero() {
+                    charged.push(Coin::new(charged_amount, &coin.denom));
+                }
+                if !refunded_amount.is_zero() {
+                    refunded.push(Coin::new(refunded_amount, coin.denom));
+                }
+            }
+            let mut msgs = vec![];
+            if !charged.is_empty() {
+                msgs.push(match destination {
+                    Some(destination) => BankMsg::Send {
+                        to_address: destination.to_string(),
+                        amount: charged,
+                    },
+                    None => BankMsg::Burn { amount: charged },
+                });
+            }
+            if !refunded.is_empty() {
+                msgs.push(BankMsg::Send {
+                    to_address: depositor.into_string(),
+                    amount: refunded,
+                });
+            }
+            for msg in msgs {
+                router.execute(api, storage, block, gov_module.clone(), msg.into())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes all votes on the proposal.
+    fn delete_votes(gov_storage: &mut dyn Storage, proposal_id: u64) -> AnyResult<()> {
+        let voters = VOTES
+            .prefix(proposal_id)
+            .keys(gov_storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for voter in voters {
+            VOTES.remove(gov_storage, (proposal_id, &voter));
+        }
+        Ok(())
+    }
+
+    /// Executes messages of the passed proposal as the governance module,
+    /// changes made by all messages are reverted when any of them fails.
+    fn execute_proposal_msgs<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        proposal: &Proposal,
+    ) -> AnyResult<Vec<Event>> {
+        let gov_module = module_address(api, GOV_MODULE)?;
+        transactional(storage, |write_cache, _| {
+            let mut events = vec![];
+            for msg in &proposal.messages {
+                let res = match decode_proposal_msg(api, write_cache, &gov_module, msg)? {
+                    ProposalMsg::Sudo(msg) => router.sudo(api, write_cache, block, msg)?,
+                    ProposalMsg::Wasm(msg) => {
+                        router.execute(api, write_cache, block, gov_module.clone(), msg.into())?
+                    }
+                    ProposalMsg::Any(msg) => router.execute(
+                        api,
+                        write_cache,
+                        block,
+                        gov_module.clone(),
+                        CosmosMsg::Any(msg),
+                    )?,
+                };
+                events.extend(res.events);
+            }
+            Ok(events)
+        })
+    }
+
+    /// Counts votes weighted by the voting power, validators' votes are inherited by delegators
+    /// who did not vote. Returns whether the proposal passed, whether deposits on the proposal
+    /// should be burned and the tally result.
+    fn tally(
+        storage: &dyn Storage,
+        params: &GovParams,
+        proposal: &Proposal,
+    ) -> AnyResult<(bool, bool, TallyResult)> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/keeper/tally.go
+        let mut validators = StakeKeeper::validator_set(storage)?
+            .into_iter()
+            .map(|stake| {
+                let tokens = Decimal::from_atomics(stake.tokens, 0)?;
+                Ok((
+                    Addr::unchecked(stake.address),
+                    (tokens, Decimal::zero(), None),
+                ))
+            })
+            .collect::<AnyResult<BTreeMap<_, _>>>()?;
+        let total_bonded = validators
+            .values()
+            .fold(Decimal::zero(), |total, (tokens, ..)| total + tokens);
+
+        let gov_storage = prefixed_read(storage, NAMESPACE_GOV);
+        let votes = VOTES
+            .prefix(proposal.id)
+            .range(&gov_storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let mut results = [Decimal::zero(); 4];
+        let mut total_voting_power = Decimal::zero();
+        let mut count = |options: &[WeightedVoteOption], power: Decimal| {
+            for option in options {
+                results[option_index(&option.option)] += power * option.weight;
+            }
+            total_voting_power += power;
+        };
+        for (voter, options) in votes {
+            if let Some((_, _, vote)) = validators.get_mut(&voter) {
+                *vote = Some(options.clone());
+            }
+            for (validator, stake) in StakeKeeper::delegator_stakes(storage, &voter)? {
+                if let Some((_, deductions, _)) = validators.get_mut(&validator) {
+                    *deductions += stake;
+                    count(&options, stake);
+                }
+            }
+        }
+        // validators vote with the stake of delegators who did not vote
+        for (tokens, deductions, vote) in validators.values() {
+            if let Some(options) = vote {
+                count(options, tokens.saturating_sub(*deductions));
+            }
+        }
+
+        let [yes, abstain, no, no_with_veto] = results;
+        let tally = TallyResult {
+            yes: Uint128::new(1).mul_floor(yes),
+            abstain: Uint128::new(1).mul_floor(abstain),
+            no: Uint128::new(1).mul_floor(no),
+            no_with_veto: Uint128::new(1).mul_floor(no_with_veto),
+        };
+        if total_bonded.is_zero() {
+            return Ok((false, false, tally));
+        }
+        // deposits are burned when the quorum is not reached, like in Cosmos SDK v0.46
+        if total_voting_power / total_bonded < params.quorum {
+            return Ok((false, true, tally));
+        }
+        let non_abstaining = total_voting_power - abstain;
+        if non_abstaining.is_zero() {
+            return Ok((false, false, tally));
+        }
+        if no_with_veto / non_abstaining > params.veto_threshold {
+            return Ok((false, true, tally));
+        }
+        let threshold = if proposal.expedited {
+            params.expedited_threshold
+        } else {
+            params.threshold
+        };
+        Ok((yes / non_abstaining > threshold, false, tally))
+    }
+}
+
+/// Returns the index of the option in the tally results.
+fn option_index(option: &VoteOption) -> usize {
+    match option {
+        VoteOption::Yes => 0,
+        VoteOption::Abstain => 1,
+        VoteOption::No => 2,
+        VoteOption::NoWithVeto => 3,
+    }
+}
+
+/// Formats weighted options like `WeightedVoteOptions.String` in Cosmos SDK.
+fn options_to_string(options: &[WeightedVoteOption]) -> String {
+    options
+        .iter()
+        .map(|o| {
+            let option = match o.option {
+                VoteOption::Yes => "VOTE_OPTION_YES",
+                VoteOption::Abstain => "VOTE_OPTION_ABSTAIN",
+                VoteOption::No => "VOTE_OPTION_NO",
+                VoteOption::NoWithVeto => "VOTE_OPTION_NO_WITH_VETO",
+            };
+            format!("option:{} weight:\"{}\"", option, o.weight)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Gov for GovKeeper {
+    fn submit_proposal<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        proposer: Addr,
+        proposal: SubmitProposal,
+    ) -> AnyResult<AppResponse> {
+        let gov_module = module_address(api, GOV_MODULE)?;
+        for msg in &proposal.messages {
+            decode_proposal_msg(api, storage, &gov_module, msg)?;
+        }
+        let mut gov_storage = prefixed(storage, NAMESPACE_GOV);
+        let params = Self::get_params(&gov_storage)?;
+        let id = LAST_PROPOSAL_ID.may_load(&gov_storage)?.unwrap_or_default() + 1;
+        LAST_PROPOSAL_ID.save(&mut gov_storage, &id)?;
+        PROPOSALS.save(
+            &mut gov_storage,
+            id,
+            &Proposal {
+                id,
+                proposer: proposer.clone(),
+                title: proposal.title,
+                summary: proposal.summary,
+                messages: proposal.messages,
+                status: ProposalStatus::DepositPeriod,
+                expedited: proposal.expedited,
+                submit_time: block.time,
+                deposit_end_time: block.time.plus_seconds(params.max_deposit_period),
+                total_deposit: vec![],
+                voting_start_time: None,
+                voting_end_time: None,
+                final_tally_result: None,
+                failed_reason: None,
+            },
+        )?;
+        let mut initial_deposit = NativeBalance(proposal.initial_deposit);
+        initial_deposit.normalize();
+        let (activated, mut events) =
+            self.add_deposit(api, storage, router, block, proposer, id, initial_deposit)?;
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/keeper/msg_server.go#L70-L89
+        if activated {
+            events.push(
+                Event::new("submit_proposal").add_attribute("voting_period_start", id.to_string()),
+            );
+        }
+        events.push(Event::new("submit_proposal").add_attribute("proposal_id", id.to_string()));
+        let data = ProtoMsgSubmitProposalResponse { proposal_id: id };
+        Ok(AppResponse {
+            events,
+            data: Some(prost::Message::encode_to_vec(&data).into()),
+        })
+    }
+
+    fn cancel_proposal<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        proposer: Addr,
+        proposal_id: u64,
+    ) -> AnyResult<AppResponse> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.50.1/x/gov/keeper/proposal.go#L140-L185
+        let gov_storage = prefixed_read(storage, NAMESPACE_GOV);
+        let params = Self::get_params(&gov_storage)?;
+        let proposal = Self::get_proposal(&gov_storage, proposal_id)?;
+        if proposal.proposer != proposer {
+            bail!("invalid proposer {}", proposer);
+        }
+        if !matches!(
+            proposal.status,
+            ProposalStatus::DepositPeriod | ProposalStatus::VotingPeriod
+        ) {
+            bail!("proposal should be in the deposit or voting period");
+        }
+        if proposal
+            .voting_end_time
+            .is_some_and(|end_time| end_time < block.time)
+        {
+            bail!(
+                "voting period is already ended for this proposal {}",
+                proposal_id
+            );
+        }
+        self.charge_deposits(
+            api,
+            storage,
+            router,
+            block,
+            proposal_id,
+            params.proposal_cancel_ratio,
+            params.proposal_cancel_dest.as_ref(),
+        )?;
+        let mut gov_storage = prefixed(storage, NAMESPACE_GOV);
+        Self::delete_votes(&mut gov_storage, proposal_id)?;
+        PROPOSALS.remove(&mut gov_storage, proposal_id);
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.50.1/x/gov/keeper/msg_server.go#L146-L152
+        let events = vec![Event::new("cancel_proposal")
+            .add_attribute("sender", proposer)
+            .add_attribute("proposal_id", proposal_id.to_string())];
+        let data = ProtoMsgCancelProposalResponse {
+            proposal_id,
+            canceled_time: Some(ProtoTimestamp {
+                seconds: block.time.seconds() as i64,
+                nanos: block.time.subsec_nanos() as i32,
+            }),
+            canceled_height: block.height,
+        };
+        Ok(AppResponse {
+            events,
+            data: Some(prost::Message::encode_to_vec(&data).into()),
+        })
+    }
+
+    fn update_params(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        params: GovParams,
+    ) -> AnyResult<AppResponse> {
+        Self::validate_params(&params)?;
+        let mut gov_storage = prefixed(storage, NAMESPACE_GOV);
+        let old_params = Self::get_params(&gov_storage)?;
+        PARAMS.save(&mut gov_storage, &params)?;
+        let coins_to_string = |coins: &[Coin]| NativeBalance(coins.to_vec()).to_string();
+        let changes = [
+            (
+                "min_deposit",
+                old_params.min_deposit != params.min_deposit,
+                coins_to_string(&params.min_deposit),
+            ),
+            (
+                "expedited_min_deposit",
+                old_params.expedited_min_deposit != params.expedited_min_deposit,
+                coins_to_string(&params.expedited_min_deposit),
+            ),
+            (
+                "max_deposit_period",
+                old_params.max_deposit_period != params.max_deposit_period,
+                params.max_deposit_period.to_string(),
+            ),
+            (
+                "voting_period",
+                old_params.voting_period != params.voting_period,
+                params.voting_period.to_string(),
+            ),
+            (
+                "quorum",
+                old_params.quorum != params.quorum,
+                params.quorum.to_string(),
+            ),
+            (
+                "threshold",
+                old_params.threshold != params.threshold,
+                params.threshold.to_string(),
+            ),
+            (
+                "veto_threshold",
+                old_params.veto_threshold != params.veto_threshold,
+                params.veto_threshold.to_string(),
+            ),
+            (
+                "expedited_voting_period",
+                old_params.expedited_voting_period != params.expedited_voting_period,
+                params.expedited_voting_period.to_string(),
+            ),
+            (
+                "expedited_threshold",
+                old_params.expedited_threshold != params.expedited_threshold,
+                params.expedited_threshold.to_string(),
+            ),
+            (
+                "proposal_cancel_ratio",
+                old_params.proposal_cancel_ratio != params.proposal_cancel_ratio,
+                params.proposal_cancel_ratio.to_string(),
+            ),
+            (
+                "proposal_cancel_dest",
+                old_params.proposal_cancel_dest != params.proposal_cancel_dest,
+                params
+                    .proposal_cancel_dest
+                    .as_ref()
+                    .map(Addr::to_string)
+                    .unwrap_or_default(),
+            ),
+        ];
+        for (param, changed, value) in changes {
+            if changed {
+                record_param_change(storage, block.height, "gov", param, value)?;
+            }
+        }
+        Ok(AppResponse::default())
+    }
+
+    fn deposit<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        depositor: Addr,
+        proposal_id: u64,
+        amount: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        let mut amount = NativeBalance(amount);
+        amount.normalize();
+        if amount.is_empty() {
+            bail!("invalid deposit amount: deposit must be positive");
+        }
+        let (activated, mut events) =
+            self.add_deposit(api, storage, router, block, depositor, proposal_id, amount)?;
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/keeper/msg_server.go#L164-L171
+        if activated {
+            events.push(
+                Event::new("proposal_deposit")
+                    .add_attribute("voting_period_start", proposal_id.to_string()),
+            );
+        }
+        Ok(AppResponse { events, data: None })
+    }
+
+    fn process_proposals<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+    ) -> AnyResult<AppResponse> {
+        let gov_storage = prefixed_read(storage, NAMESPACE_GOV);
+        let params = Self::get_params(&gov_storage)?;
+        let proposals = PROPOSALS
+            .range(&gov_storage, None, None, Order::Ascending)
+            .map(|entry| entry.map(|(_, proposal)| proposal))
+            .collect::<StdResult<Vec<_>>>()?;
+        let mut events = vec![];
+        for mut proposal in proposals {
+            match proposal.status {
+                ProposalStatus::DepositPeriod if proposal.deposit_end_time <= block.time => {
+                    // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/abci.go#L20-L46
+                    // proposals which did not reach the minimum deposit are dropped
+                    let ratio = Decimal::zero();
+                    self.charge_deposits(api, storage, router, block, proposal.id, ratio, None)?;
+                    PROPOSALS.remove(&mut prefixed(storage, NAMESPACE_GOV), proposal.id);
+                    events.push(
+                        Event::new("inactive_proposal")
+                            .add_attribute("proposal_id", proposal.id.to_string())
+                            .add_attribute("proposal_result", "proposal_dropped"),
+                    );
+                    continue;
+                }
+                ProposalStatus::VotingPeriod
+                    if proposal
+                        .voting_end_time
+                        .is_some_and(|end_time| end_time <= block.time) => {}
+                _ => continue,
+            }
+            let (passed, burn, tally) = Self::tally(storage, &params, &proposal)?;
+            if proposal.expedited && !passed {
+                // see https://github.com/cosmos/cosmos-sdk/blob/v0.50.1/x/gov/abci.go#L269-L284
+                // the proposal continues on the regular track, keeping its votes and deposits
+                proposal.expedited = false;
+                proposal.voting_end_time = proposal
+                    .voting_start_time
+                    .map(|start_time| start_time.plus_seconds(params.voting_period));
+                PROPOSALS.save(
+                    &mut prefixed(storage, NAMESPACE_GOV),
+                    proposal.id,
+                    &proposal,
+                )?;
+                events.push(
+                    Event::new("active_proposal")
+                        .add_attribute("proposal_id", proposal.id.to_string())
+                        .add_attribute("proposal_result", "expedited_proposal_rejected"),
+                );
+                continue;
+            }
+            // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/abci.go#L64-L120
+            let ratio = if burn {
+                Decimal::one()
+            } else {
+                Decimal::zero()
+            };
+            self.charge_deposits(api, storage, router, block, proposal.id, ratio, None)?;
+            proposal.status = if passed {
+                match self.execute_proposal_msgs(api, storage, router, block, &proposal) {
+                    Ok(msg_events) => {
+                        events.extend(msg_events);
+                        ProposalStatus::Passed
+                    }
+                    Err(err) => {
+                        proposal.failed_reason = Some(err.root_cause().to_string());
+                        ProposalStatus::Failed
+                    }
+                }
+            } else {
+                ProposalStatus::Rejected
+            };
+            let mut gov_storage = prefixed(storage, NAMESPACE_GOV);
+            Self::delete_votes(&mut gov_storage, proposal.id)?;
+            proposal.final_tally_result = Some(tally);
+            PROPOSALS.save(&mut gov_storage, proposal.id, &proposal)?;
+            // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/gov/abci.go#L136-L141
+            let result = match proposal.status {
+                ProposalStatus::Passed => "proposal_passed",
+                ProposalStatus::Failed => "proposal_failed",
+                _ => "proposal_rejected",
+            };
+            events.push(
+                Event::new("active_proposal")
+                    .add_attribute("proposal_id", proposal.id.to_string())
+                    .add_attribute("proposal_result", result),
+            );
+        }
+        Ok(AppResponse { events, data: None })
+    }
+}
+
+impl Module for GovKeeper {
+    type ExecT = GovMsg;
+    type QueryT = Empty;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        sender: Addr,
+        msg: GovMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match msg {
+            GovMsg::Vote {
+                proposal_id,
+                option,
+            } => self.add_vote(
+                storage,
+                sender,
+                proposal_id,
+                vec![WeightedVoteOption {
+                    option,
+                    weight: Decimal::one(),
+                }],
+            ),
+            GovMsg::VoteWeighted {
+                proposal_id,
+                options,
+            } => self.add_vote(storage, sender, proposal_id, options),
+        }
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        _request: Empty,
+    ) -> AnyResult<Binary> {
+        bail!("Something went wrong - Gov doesn't have query messages")
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Empty,
+    ) -> AnyResult<AppResponse> {
+        bail!("Something went wrong - Gov doesn't have sudo messages")
+    }
+}
@@ -0,0 +1,92 @@
+//! # IBC callbacks (ADR-8)
+//!
+//! Packets sent by IBC applications supporting callbacks (like ICS-20 transfers) may carry
+//! callback metadata in the memo, notifying contracts about the packet lifecycle:
+//!
+//! ```json
+//! {"src_callback":{"address":"..."},"dest_callback":{"address":"..."}}
+//! ```
+//!
+//! The source callback contract is notified about the acknowledgement or the timeout
+//! of the sent packet, and the destination callback contract is notified after the packet
+//! is received. Message types mirror those introduced in `cosmwasm-std` 2.1.
+
+use cosmwasm_std::{Addr, IbcAcknowledgement, IbcPacket};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Message passed to contract's `ibc_source_callback` entry-point.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcSourceCallbackMsg {
+    /// The packet sent by the contract was acknowledged.
+    Acknowledgement(IbcAckCallbackMsg),
+    /// The packet sent by the contract timed out.
+    Timeout(IbcTimeoutCallbackMsg),
+}
+
+/// Acknowledgement of the packet, passed to the source callback.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IbcAckCallbackMsg {
+    /// Acknowledgement written by the destination.
+    pub acknowledgement: IbcAcknowledgement,
+    /// Acknowledged packet.
+    pub original_packet: IbcPacket,
+    /// Address of the relayer.
+    pub relayer: Addr,
+}
+
+/// Timeout of the packet, passed to the source callback.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IbcTimeoutCallbackMsg {
+    /// Packet that timed out.
+    pub packet: IbcPacket,
+    /// Address of the relayer.
+    pub relayer: Addr,
+}
+
+/// Message passed to contract's `ibc_destination_callback` entry-point.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct IbcDestinationCallbackMsg {
+    /// Received packet.
+    pub packet: IbcPacket,
+    /// Acknowledgement written for the received packet.
+    pub ack: IbcAcknowledgement,
+}
+
+/// Addresses of contracts to be called back, read from the memo of the packet.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct CallbackAddresses {
+    /// Address of the contract notified about the acknowledgement or the timeout.
+    pub source: Option<String>,
+    /// Address of the contract notified about the received packet.
+    pub destination: Option<String>,
+}
+
+impl CallbackAddresses {
+    /// Reads the callback addresses from the memo of the packet data, like ICS-20 packet data.
+    /// Packets without memo or with memo not containing callbacks have no callback addresses.
+    pub fn from_packet_data(data: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct PacketData {
+            memo: String,
+        }
+        #[derive(Deserialize)]
+        struct Callback {
+            address: String,
+        }
+        #[derive(Deserialize)]
+        struct Memo {
+            src_callback: Option<Callback>,
+            dest_callback: Option<Callback>,
+        }
+        serde_json::from_slice::<PacketData>(data)
+            .ok()
+            .and_then(|data| serde_json::from_str::<Memo>(&data.memo).ok())
+            .map(|memo| Self {
+                source: memo.src_callback.map(|callback| callback.address),
+                destination: memo.dest_callback.map(|callback| callback.address),
+            })
+            .unwrap_or_default()
+    }
+}
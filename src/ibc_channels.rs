@@ -0,0 +1,391 @@
+//! # IBC channels
+//!
+//! Channel ends opened on the chain simulated by [App](crate::App), with the channel handshake
+//! run step by step against contracts bound to IBC ports, like a relayer would do:
+//!
+//! - [channel_open_init](crate::App::channel_open_init) calls `ibc_channel_open` with `OpenInit`,
+//! - [channel_open_try](crate::App::channel_open_try) calls `ibc_channel_open` with `OpenTry`,
+//! - [channel_open_ack](crate::App::channel_open_ack) calls `ibc_channel_connect` with `OpenAck`,
+//! - [channel_open_confirm](crate::App::channel_open_confirm) calls `ibc_channel_connect`
+//!   with `OpenConfirm`.
+//!
+//! Channel ends are closed with [channel_close_init](crate::App::channel_close_init),
+//! which calls `ibc_channel_close` with `CloseInit`, or by contracts with `IbcMsg::CloseChannel`.
+//! The counterparty end is closed with [channel_close_confirm](crate::App::channel_close_confirm),
+//! which calls `ibc_channel_close` with `CloseConfirm`. Ordered channels are closed
+//! when a packet sent over them times out.
+//!
+//! Contracts may propose another version in response to `ibc_channel_open`,
+//! the channel is opened with the version agreed by both ends. Channel ends bound to
+//! the `transfer` port are handled by the ICS-20 transfer module instead of contracts.
+
+use crate::app::CosmosRouter;
+use crate::error::{bail, AnyResult};
+use crate::ibc_transfer::{channel_callback as transfer_channel_callback, TRANSFER_PORT};
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use crate::wasm::IbcCallback;
+use crate::{AppResponse, Wasm};
+use cosmwasm_std::{
+    Api, BlockInfo, IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcEndpoint, IbcOrder, Order, StdResult, Storage,
+};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Default storage namespace for IBC channels.
+const NAMESPACE_IBC_CHANNELS: &[u8] = b"ibc-channels";
+
+/// (channel identifier) -> channel end.
+const CHANNEL_ENDS: Map<&str, ChannelEnd> = Map::new("channel_ends");
+
+/// Number of channels created so far.
+const CHANNEL_COUNT: Item<u64> = Item::new("channel_count");
+
+/// Number of connections created so far.
+const CONNECTION_COUNT: Item<u64> = Item::new("connection_count");
+
+/// State of the channel end, like in `ibc-go`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ChannelState {
+    /// The channel end was initialized, waiting for the acknowledgement of the counterparty.
+    Init,
+    /// The counterparty tried to open the channel, waiting for the confirmation.
+    TryOpen,
+    /// The channel is open and ready to send and receive packets.
+    Open,
+    /// The channel is closed, packets can not be sent or received anymore.
+    Closed,
+}
+
+/// Channel end with the state of the handshake.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelEnd {
+    /// State of the channel end.
+    pub state: ChannelState,
+    /// Channel as seen by the contract bound to the port of this end.
+    /// Identifier of the counterparty channel is empty until it is known.
+    pub channel: IbcChannel,
+}
+
+/// Initializes the channel end bound to specified port, calls `ibc_channel_open`
+/// on the bound contract. Returns the initialized channel with the version proposed by the contract.
+pub(crate) fn channel_open_init<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    port_id: &str,
+    counterparty_port_id: &str,
+    connection_id: &str,
+    version: &str,
+    order: IbcOrder,
+) -> AnyResult<IbcChannel>
+where
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    let channel_id = next_channel_id(storage)?;
+    let mut channel = IbcChannel::new(
+        IbcEndpoint {
+            port_id: port_id.to_string(),
+            channel_id: channel_id.clone(),
+        },
+        IbcEndpoint {
+            port_id: counterparty_port_id.to_string(),
+            channel_id: String::new(),
+        },
+        order,
+        version,
+        connection_id,
+    );
+    // the contract may propose another version when initializing the channel
+    let msg = IbcChannelOpenMsg::new_init(channel.clone());
+    let callback = IbcCallback::ChannelOpen(msg);
+    let response = deliver_callback(api, storage, router, wasm, block, port_id, callback)?;
+    channel.version = negotiated_version(response, version)?;
+    save_channel_end(storage, ChannelState::Init, channel.clone())?;
+    Ok(channel)
+}
+
+/// Tries to open the channel end bound to specified port, proposed by the counterparty,
+/// calls `ibc_channel_open` on the bound contract. Returns the channel with the version
+/// proposed by the contract.
+pub(crate) fn channel_open_try<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    port_id: &str,
+    counterparty: IbcEndpoint,
+    connection_id: &str,
+    counterparty_version: &str,
+    order: IbcOrder,
+) -> AnyResult<IbcChannel>
+where
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    let channel_id = next_channel_id(storage)?;
+    let mut channel = IbcChannel::new(
+        IbcEndpoint {
+            port_id: port_id.to_string(),
+            channel_id: channel_id.clone(),
+        },
+        counterparty,
+        order,
+        counterparty_version,
+        connection_id,
+    );
+    // the contract may propose another version when trying to open the channel
+    let msg = IbcChannelOpenMsg::new_try(channel.clone(), counterparty_version);
+    let callback = IbcCallback::ChannelOpen(msg);
+    let response = deliver_callback(api, storage, router, wasm, block, port_id, callback)?;
+    channel.version = negotiated_version(response, counterparty_version)?;
+    save_channel_end(storage, ChannelState::TryOpen, channel.clone())?;
+    Ok(channel)
+}
+
+/// Opens the initialized channel end after the counterparty tried to open the channel,
+/// calls `ibc_channel_connect` on the bound contract.
+pub(crate) fn channel_open_ack<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    channel_id: &str,
+    counterparty_channel_id: &str,
+    counterparty_version: &str,
+) -> AnyResult<()>
+where
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    let mut channel = channel_end_in_state(storage, channel_id, ChannelState::Init)?;
+    channel.counterparty_endpoint.channel_id = counterparty_channel_id.to_string();
+    channel.version = counterparty_version.to_string();
+    let port_id = channel.endpoint.port_id.clone();
+    let msg = IbcChannelConnectMsg::new_ack(channel.clone(), counterparty_version);
+    let callback = IbcCallback::ChannelConnect(msg);
+    deliver_callback(api, storage, router, wasm, block, &port_id, callback)?;
+    save_channel_end(storage, ChannelState::Open, channel)
+}
+
+/// Opens the channel end after the counterparty acknowledged the channel,
+/// calls `ibc_channel_connect` on the bound contract.
+pub(crate) fn channel_open_confirm<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    channel_id: &str,
+) -> AnyResult<()>
+where
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    let channel = channel_end_in_state(storage, channel_id, ChannelState::TryOpen)?;
+    let port_id = channel.endpoint.port_id.clone();
+    let msg = IbcChannelConnectMsg::new_confirm(channel.clone());
+    let callback = IbcCallback::ChannelConnect(msg);
+    deliver_callback(api, storage, router, wasm, block, &port_id, callback)?;
+    save_channel_end(storage, ChannelState::Open, channel)
+}
+
+/// Closes the channel end on request of the user, calls `ibc_channel_close`
+/// on the bound contract with `CloseInit`.
+pub(crate) fn channel_close_init<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    channel_id: &str,
+) -> AnyResult<()>
+where
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    let channel = channel_end_not_closed(storage, channel_id)?;
+    let port_id = channel.endpoint.port_id.clone();
+    let msg = IbcChannelCloseMsg::new_init(channel.clone());
+    let callback = IbcCallback::ChannelClose(msg);
+    deliver_callback(api, storage, router, wasm, block, &port_id, callback)?;
+    save_channel_end(storage, ChannelState::Closed, channel)
+}
+
+/// Closes the channel end, calls `ibc_channel_close` on the bound contract with `CloseConfirm`.
+/// Used when the closing was not requested on this end, after a packet timed out
+/// on the ordered channel, or when the counterparty channel end was closed.
+pub(crate) fn channel_close_confirm<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    channel_id: &str,
+) -> AnyResult<()>
+where
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    let channel = channel_end_not_closed(storage, channel_id)?;
+    let port_id = channel.endpoint.port_id.clone();
+    let msg = IbcChannelCloseMsg::new_confirm(channel.clone());
+    let callback = IbcCallback::ChannelClose(msg);
+    deliver_callback(api, storage, router, wasm, block, &port_id, callback)?;
+    save_channel_end(storage, ChannelState::Closed, channel)
+}
+
+/// Closes the channel end on request of the bound contract, returns the closed channel.
+/// Like in `wasmd`, the contract is not called back, the counterparty end
+/// is closed when the closing is relayed.
+pub(crate) fn close_channel_end(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+) -> AnyResult<IbcChannel> {
+    let channel = channel_end_not_closed(storage, channel_id)?;
+    save_channel_end(storage, ChannelState::Closed, channel.clone())?;
+    Ok(channel)
+}
+
+/// Returns closed channel ends using the connection with specified identifier.
+pub(crate) fn closed_channels(
+    storage: &dyn Storage,
+    connection_id: &str,
+) -> AnyResult<Vec<IbcChannel>> {
+    Ok(channel_ends(storage)?
+        .into_iter()
+        .filter(|channel_end| channel_end.state == ChannelState::Closed)
+        .map(|channel_end| channel_end.channel)
+        .filter(|channel| channel.connection_id == connection_id)
+        .collect())
+}
+
+/// Returns `true` when the channel end with specified identifier is closed.
+pub(crate) fn is_channel_closed(storage: &dyn Storage, channel_id: &str) -> AnyResult<bool> {
+    Ok(channel_end(storage, channel_id)?
+        .is_some_and(|channel_end| channel_end.state == ChannelState::Closed))
+}
+
+/// Delivers the channel callback to the transfer module, when the port is bound to it,
+/// or to the contract bound to the port.
+fn deliver_callback<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    port_id: &str,
+    callback: IbcCallback,
+) -> AnyResult<AppResponse>
+where
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    if port_id == TRANSFER_PORT {
+        return transfer_channel_callback(&callback);
+    }
+    let contract_addr = wasm.contract_by_ibc_port_id(storage, port_id)?;
+    wasm.ibc_callback(api, storage, router, block, contract_addr, callback)
+}
+
+/// Returns the channel end with specified identifier.
+pub(crate) fn channel_end(
+    storage: &dyn Storage,
+    channel_id: &str,
+) -> AnyResult<Option<ChannelEnd>> {
+    let ibc_storage = prefixed_read(storage, NAMESPACE_IBC_CHANNELS);
+    Ok(CHANNEL_ENDS.may_load(&ibc_storage, channel_id)?)
+}
+
+/// Returns all channel ends, ordered by channel identifier.
+pub(crate) fn channel_ends(storage: &dyn Storage) -> AnyResult<Vec<ChannelEnd>> {
+    let ibc_storage = prefixed_read(storage, NAMESPACE_IBC_CHANNELS);
+    Ok(CHANNEL_ENDS
+        .range(&ibc_storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, channel_end)| channel_end))
+        .collect::<StdResult<Vec<_>>>()?)
+}
+
+/// Returns the open channel with specified identifier, fails when the channel is not open.
+pub(crate) fn opened_channel(storage: &dyn Storage, channel_id: &str) -> AnyResult<IbcChannel> {
+    channel_end_in_state(storage, channel_id, ChannelState::Open)
+}
+
+/// Returns the channel with specified identifier, fails when the channel end
+/// is not in the expected state.
+fn channel_end_in_state(
+    storage: &dyn Storage,
+    channel_id: &str,
+    expected: ChannelState,
+) -> AnyResult<IbcChannel> {
+    let Some(channel_end) = channel_end(storage, channel_id)? else {
+        bail!("channel {} not found", channel_id);
+    };
+    if channel_end.state != expected {
+        bail!(
+            "invalid channel state: channel {} is {:?}, expected {:?}",
+            channel_id,
+            channel_end.state,
+            expected
+        );
+    }
+    Ok(channel_end.channel)
+}
+
+/// Returns the channel with specified identifier, fails when the channel end is closed.
+fn channel_end_not_closed(storage: &dyn Storage, channel_id: &str) -> AnyResult<IbcChannel> {
+    let Some(channel_end) = channel_end(storage, channel_id)? else {
+        bail!("channel {} not found", channel_id);
+    };
+    if channel_end.state == ChannelState::Closed {
+        bail!(
+            "invalid channel state: channel {} is already closed",
+            channel_id
+        );
+    }
+    Ok(channel_end.channel)
+}
+
+/// Saves the channel end in specified state.
+fn save_channel_end(
+    storage: &mut dyn Storage,
+    state: ChannelState,
+    channel: IbcChannel,
+) -> AnyResult<()> {
+    let mut ibc_storage = prefixed(storage, NAMESPACE_IBC_CHANNELS);
+    let channel_id = channel.endpoint.channel_id.clone();
+    CHANNEL_ENDS.save(
+        &mut ibc_storage,
+        &channel_id,
+        &ChannelEnd { state, channel },
+    )?;
+    Ok(())
+}
+
+/// Creates the client tracking the counterparty chain and the connection using it,
+/// returns identifiers of the client and the connection.
+pub(crate) fn create_connection(storage: &mut dyn Storage) -> AnyResult<(String, String)> {
+    let mut ibc_storage = prefixed(storage, NAMESPACE_IBC_CHANNELS);
+    let count = CONNECTION_COUNT.may_load(&ibc_storage)?.unwrap_or_default();
+    CONNECTION_COUNT.save(&mut ibc_storage, &(count + 1))?;
+    Ok((
+        format!("07-tendermint-{}", count),
+        format!("connection-{}", count),
+    ))
+}
+
+/// Returns the next channel identifier.
+fn next_channel_id(storage: &mut dyn Storage) -> AnyResult<String> {
+    let mut ibc_storage = prefixed(storage, NAMESPACE_IBC_CHANNELS);
+    let count = CHANNEL_COUNT.may_load(&ibc_storage)?.unwrap_or_default();
+    CHANNEL_COUNT.save(&mut ibc_storage, &(count + 1))?;
+    Ok(format!("channel-{}", count))
+}
+
+/// Returns the version from the response to the channel open callback,
+/// or the proposed version when the contract accepted it.
+fn negotiated_version(response: AppResponse, proposed: &str) -> AnyResult<String> {
+    match response.data {
+        Some(version) => Ok(String::from_utf8(version.to_vec())?),
+        None => Ok(proposed.to_string()),
+    }
+}
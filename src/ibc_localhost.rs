@@ -0,0 +1,597 @@
+//! # IBC localhost client
+//!
+//! [IbcLocalhost] simulates the IBC localhost (loopback) client, so contracts instantiated
+//! in the same [App](crate::App) can talk to each other over IBC channels, without setting up
+//! a second chain and a relayer. Channels are opened between ports bound to contracts using
+//! [open_localhost_channel](crate::App::open_localhost_channel), which runs all steps
+//! of the channel handshake at once. Packets sent by contracts with `IbcMsg::SendPacket`
+//! are queued, and delivered when [relay_localhost_packets](crate::App::relay_localhost_packets)
+//! is called, like a relayer would do. Acknowledgements are relayed back to the sending contract
+//! immediately, packets that timed out at the current block are returned to the sending contract.
+//! When the receiving contract fails, its changes are discarded and the error is acknowledged.
+//!
+//! Packets carrying ADR-8 callback metadata in the memo of their data (see [crate::IbcSourceCallbackMsg])
+//! additionally trigger `ibc_destination_callback` on the destination callback contract
+//! when the packet is acknowledged by the receiver, and `ibc_source_callback` on the sending
+//! contract when it is the source callback contract, after acknowledgement or timeout.
+
+use crate::app::CosmosRouter;
+use crate::error::{bail, AnyResult};
+use crate::ibc_callbacks::{
+    CallbackAddresses, IbcAckCallbackMsg, IbcDestinationCallbackMsg, IbcSourceCallbackMsg,
+    IbcTimeoutCallbackMsg,
+};
+use crate::ibc_channels::{
+    channel_close_confirm, channel_end, channel_ends, channel_open_ack, channel_open_confirm,
+    channel_open_init, channel_open_try, close_channel_end, closed_channels, is_channel_closed,
+    opened_channel, ChannelState,
+};
+use crate::ibc_transfer::{
+    acknowledge_transfer, receive_transfer, send_transfer, timeout_transfer, transfer_sender,
+    TRANSFER_PORT,
+};
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use crate::transactions::transactional;
+use crate::wasm::{IbcCallback, IBC_PORT_PREFIX};
+use crate::{AppResponse, Ibc, Module, Wasm};
+use cosmwasm_std::{
+    to_json_binary, Addr, Api, Binary, BlockInfo, ChannelResponse, CustomMsg, CustomQuery, Empty,
+    Event, IbcAcknowledgement, IbcMsg, IbcOrder, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg,
+    IbcPacketTimeoutMsg, IbcQuery, IbcTimeout, ListChannelsResponse, Querier, Storage,
+};
+use cw_storage_plus::Map;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Identifier of the connection used by all localhost channels, the same as in `ibc-go`.
+const LOCALHOST_CONNECTION_ID: &str = "connection-localhost";
+
+/// Address of the simulated relayer, passed to packet callbacks.
+const RELAYER: &str = "relayer";
+
+/// Default storage namespace for the IBC localhost client.
+const NAMESPACE_IBC_LOCALHOST: &[u8] = b"ibc-localhost";
+
+/// (channel identifier) -> sequence of the next packet sent over the channel.
+const NEXT_SEQUENCES: Map<&str, u64> = Map::new("next_sequences");
+
+/// (channel identifier) -> sequence of the next packet received over the ordered channel.
+const NEXT_SEQUENCES_RECV: Map<&str, u64> = Map::new("next_sequences_recv");
+
+/// (connection identifier) -> packets sent by contracts over the connection,
+/// waiting to be relayed.
+const PENDING_PACKETS: Map<&str, Vec<IbcPacket>> = Map::new("pending_packets");
+
+/// Acknowledgement of a packet the receiving contract failed to process,
+/// encoded as JSON like the error `Acknowledgement` in `ibc-go`.
+#[derive(Serialize)]
+struct ErrorAcknowledgement {
+    error: String,
+}
+
+/// IBC module simulating the localhost (loopback) client.
+///
+/// Contracts can send packets over channels opened with
+/// [open_localhost_channel](crate::App::open_localhost_channel), or with the
+/// [Relayer](crate::Relayer) connecting to another chain, and query the opened channels,
+/// of their own port when the port is omitted.
+/// Tokens are transferred with `IbcMsg::Transfer` over channels opened between `transfer` ports,
+/// handled by the ICS-20 transfer module. Packets sent over ordered channels are received
+/// in sequence, and the ordered channel is closed when a packet sent over it times out.
+/// Contracts close channels with `IbcMsg::CloseChannel`, the counterparty end is closed
+/// when relaying.
+#[derive(Clone, Default)]
+pub struct IbcLocalhost;
+
+impl IbcLocalhost {
+    /// Creates a new IBC localhost module.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Module for IbcLocalhost {
+    type ExecT = IbcMsg;
+    type QueryT = IbcQuery;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: IbcMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match msg {
+            IbcMsg::SendPacket {
+                channel_id,
+                data,
+                timeout,
+            } => {
+                let port_id = format!("{}{}", IBC_PORT_PREFIX, sender);
+                let (packet, connection_id) =
+                    send_packet(storage, &port_id, &channel_id, data, timeout)?;
+                Ok(AppResponse {
+                    events: vec![send_packet_event(&packet, connection_id)],
+                    data: None,
+                })
+            }
+            IbcMsg::Transfer {
+                channel_id,
+                to_address,
+                amount,
+                timeout,
+                memo,
+            } => {
+                let (data, transfer_event) = send_transfer(
+                    api,
+                    storage,
+                    router,
+                    block,
+                    &sender,
+                    &channel_id,
+                    to_address,
+                    amount,
+                    memo,
+                )?;
+                let (packet, connection_id) =
+                    send_packet(storage, TRANSFER_PORT, &channel_id, data, timeout)?;
+                Ok(AppResponse {
+                    events: vec![transfer_event, send_packet_event(&packet, connection_id)],
+                    data: None,
+                })
+            }
+            IbcMsg::CloseChannel { channel_id } => {
+                let port_id = format!("{}{}", IBC_PORT_PREFIX, sender);
+                if opened_channel(storage, &channel_id)?.endpoint.port_id != port_id {
+                    bail!("channel {} is not bound to port {}", channel_id, port_id);
+                }
+                let channel = close_channel_end(storage, &channel_id)?;
+                let event = Event::new("channel_close_init")
+                    .add_attribute("port_id", &channel.endpoint.port_id)
+                    .add_attribute("channel_id", &channel.endpoint.channel_id)
+                    .add_attribute(
+                        "counterparty_port_id",
+                        &channel.counterparty_endpoint.port_id,
+                    )
+                    .add_attribute(
+                        "counterparty_channel_id",
+                        &channel.counterparty_endpoint.channel_id,
+                    )
+                    .add_attribute("connection_id", &channel.connection_id);
+                Ok(AppResponse {
+                    events: vec![event],
+                    data: None,
+                })
+            }
+            msg => bail!("Unexpected exec msg {:?} from {:?}", msg, sender),
+        }
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: IbcQuery,
+    ) -> AnyResult<Binary> {
+        match request {
+            // the port is omitted only when the querying contract is not bound to any port
+            IbcQuery::ListChannels { port_id } => {
+                let channels = channel_ends(storage)?
+                    .into_iter()
+                    .filter(|channel_end| channel_end.state == ChannelState::Open)
+                    .map(|channel_end| channel_end.channel)
+                    .filter(|channel| Some(&channel.endpoint.port_id) == port_id.as_ref())
+                    .collect();
+                Ok(to_json_binary(&ListChannelsResponse::new(channels))?)
+            }
+            IbcQuery::Channel {
+                channel_id,
+                port_id,
+            } => {
+                let channel = channel_end(storage, &channel_id)?
+                    .filter(|channel_end| channel_end.state == ChannelState::Open)
+                    .map(|channel_end| channel_end.channel)
+                    .filter(|channel| Some(&channel.endpoint.port_id) == port_id.as_ref());
+                Ok(to_json_binary(&ChannelResponse::new(channel))?)
+            }
+            request => bail!("Unexpected custom query {:?}", request),
+        }
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("Unexpected sudo msg {:?}", msg)
+    }
+}
+
+impl Ibc for IbcLocalhost {}
+
+/// Opens the channel between contracts bound to specified ports, running the whole handshake.
+/// Returns identifiers of the channel ends, the first one is bound to `port_id`.
+pub(crate) fn open_channel<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    port_id: &str,
+    counterparty_port_id: &str,
+    version: &str,
+    order: IbcOrder,
+) -> AnyResult<(String, String)>
+where
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    let channel = channel_open_init(
+        api,
+        storage,
+        router,
+        wasm,
+        block,
+        port_id,
+        counterparty_port_id,
+        LOCALHOST_CONNECTION_ID,
+        version,
+        order.clone(),
+    )?;
+    let counterparty_channel = channel_open_try(
+        api,
+        storage,
+        router,
+        wasm,
+        block,
+        counterparty_port_id,
+        channel.endpoint.clone(),
+        LOCALHOST_CONNECTION_ID,
+        &channel.version,
+        order,
+    )?;
+    let channel_id = channel.endpoint.channel_id;
+    let counterparty_channel_id = counterparty_channel.endpoint.channel_id;
+    channel_open_ack(
+        api,
+        storage,
+        router,
+        wasm,
+        block,
+        &channel_id,
+        &counterparty_channel_id,
+        &counterparty_channel.version,
+    )?;
+    channel_open_confirm(api, storage, router, wasm, block, &counterparty_channel_id)?;
+    Ok((channel_id, counterparty_channel_id))
+}
+
+/// Relays the oldest packet pending on the localhost connection,
+/// returns `None` when there are no pending packets. Counterparty ends of closed channels
+/// are closed first.
+///
+/// The packet is received by the destination contract and the acknowledgement
+/// is relayed back to the sending contract. When the packet timed out at the current block,
+/// or its channel was closed, it is returned to the sending contract instead.
+pub(crate) fn relay_packet<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+) -> AnyResult<Option<AppResponse>>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    // both channel ends are on this chain, closing is relayed before packets
+    for channel in closed_channels(storage, LOCALHOST_CONNECTION_ID)? {
+        let counterparty_channel_id = &channel.counterparty_endpoint.channel_id;
+        if !is_channel_closed(storage, counterparty_channel_id)? {
+            channel_close_confirm(api, storage, router, wasm, block, counterparty_channel_id)?;
+        }
+    }
+    let Some(packet) = pending_packet(storage, LOCALHOST_CONNECTION_ID)? else {
+        return Ok(None);
+    };
+    if is_channel_closed(storage, &packet.src.channel_id)? || is_timed_out(&packet.timeout, block) {
+        let response = timeout_packet(api, storage, router, wasm, block, &packet)?;
+        return Ok(Some(response));
+    }
+    let (mut response, acknowledgement) =
+        receive_packet(api, storage, router, wasm, block, &packet)?;
+    let ack_response =
+        acknowledge_packet(api, storage, router, wasm, block, &packet, acknowledgement)?;
+    response.events.extend(ack_response.events);
+    Ok(Some(response))
+}
+
+/// Returns the oldest packet sent over the connection with specified identifier,
+/// waiting to be relayed.
+pub(crate) fn pending_packet(
+    storage: &dyn Storage,
+    connection_id: &str,
+) -> AnyResult<Option<IbcPacket>> {
+    let ibc_storage = prefixed_read(storage, NAMESPACE_IBC_LOCALHOST);
+    let packets = PENDING_PACKETS
+        .may_load(&ibc_storage, connection_id)?
+        .unwrap_or_default();
+    Ok(packets.into_iter().next())
+}
+
+/// Delivers the packet to the contract bound to the destination port,
+/// or to the transfer module for the `transfer` port, calling
+/// the destination callback contract when the packet is acknowledged synchronously.
+/// Returns the response of the contract and the acknowledgement, if written.
+/// An error returned by the contract is written as an error acknowledgement.
+pub(crate) fn receive_packet<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    packet: &IbcPacket,
+) -> AnyResult<(AppResponse, Option<IbcAcknowledgement>)>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    let channel = opened_channel(storage, &packet.dest.channel_id)?;
+    if channel.order == IbcOrder::Ordered {
+        receive_in_sequence(storage, packet)?;
+    }
+    let (mut response, acknowledgement) = if packet.dest.port_id == TRANSFER_PORT {
+        receive_transfer(api, storage, router, block, packet)?
+    } else {
+        let relayer = Addr::unchecked(RELAYER);
+        let dest_addr = wasm.contract_by_ibc_port_id(storage, &packet.dest.port_id)?;
+        let msg = IbcPacketReceiveMsg::new(packet.clone(), relayer);
+        let callback = IbcCallback::PacketReceive(msg);
+        let result = transactional(storage, |write_cache, _| {
+            wasm.ibc_callback(api, write_cache, router, block, dest_addr, callback)
+        });
+        // like in wasmd, changes of the failed contract are discarded and the error
+        // is acknowledged, the destination callback is not called
+        let response = match result {
+            Ok(response) => response,
+            Err(err) => return Ok((AppResponse::default(), Some(error_acknowledgement(&err)?))),
+        };
+        // no acknowledgement means it will be written asynchronously
+        let Some(acknowledgement) = response.data.clone() else {
+            return Ok((response, None));
+        };
+        (response, IbcAcknowledgement::new(acknowledgement))
+    };
+    let callbacks = CallbackAddresses::from_packet_data(packet.data.as_slice());
+    if let Some(address) = callbacks.destination {
+        let msg = IbcDestinationCallbackMsg {
+            packet: packet.clone(),
+            ack: acknowledgement.clone(),
+        };
+        let callback = IbcCallback::DestinationCallback(msg);
+        let callback_addr = api.addr_validate(&address)?;
+        let callback_response =
+            wasm.ibc_callback(api, storage, router, block, callback_addr, callback)?;
+        response.events.extend(callback_response.events);
+    }
+    Ok((response, Some(acknowledgement)))
+}
+
+/// Returns the acknowledgement reporting the error of receiving a packet.
+pub(crate) fn error_acknowledgement(err: &anyhow::Error) -> AnyResult<IbcAcknowledgement> {
+    Ok(IbcAcknowledgement::encode_json(&ErrorAcknowledgement {
+        error: err.to_string(),
+    })?)
+}
+
+/// Relays the acknowledgement of the pending packet back to the sending contract,
+/// calling it also as the source callback contract when requested in the packet.
+/// The packet is no longer pending. Contracts are not called when the acknowledgement
+/// is `None`, as it will be written asynchronously.
+pub(crate) fn acknowledge_packet<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    packet: &IbcPacket,
+    acknowledgement: Option<IbcAcknowledgement>,
+) -> AnyResult<AppResponse>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    remove_pending_packet(storage, packet)?;
+    let Some(acknowledgement) = acknowledgement else {
+        return Ok(AppResponse::default());
+    };
+    let relayer = Addr::unchecked(RELAYER);
+    let (mut response, src_addr) = if packet.src.port_id == TRANSFER_PORT {
+        let response = acknowledge_transfer(api, storage, router, block, packet, &acknowledgement)?;
+        (response, transfer_sender(api, packet)?)
+    } else {
+        let src_addr = wasm.contract_by_ibc_port_id(storage, &packet.src.port_id)?;
+        let msg = IbcPacketAckMsg::new(acknowledgement.clone(), packet.clone(), relayer.clone());
+        let callback = IbcCallback::PacketAck(msg);
+        let response =
+            wasm.ibc_callback(api, storage, router, block, src_addr.clone(), callback)?;
+        (response, src_addr)
+    };
+    if is_source_callback(packet, &src_addr) {
+        let msg = IbcSourceCallbackMsg::Acknowledgement(IbcAckCallbackMsg {
+            acknowledgement,
+            original_packet: packet.clone(),
+            relayer,
+        });
+        let callback = IbcCallback::SourceCallback(msg);
+        let callback_response =
+            wasm.ibc_callback(api, storage, router, block, src_addr, callback)?;
+        response.events.extend(callback_response.events);
+    }
+    Ok(response)
+}
+
+/// Returns the pending packet that timed out to the sending contract, or refunds tokens
+/// transferred over the `transfer` port, calling the sender also as the source callback contract
+/// when requested in the packet. The packet is no longer pending.
+/// The ordered channel of the packet is closed, calling `ibc_channel_close` on the sending contract.
+pub(crate) fn timeout_packet<ExecC, QueryC, WasmT>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    wasm: &WasmT,
+    block: &BlockInfo,
+    packet: &IbcPacket,
+) -> AnyResult<AppResponse>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+    WasmT: Wasm<ExecC, QueryC>,
+{
+    remove_pending_packet(storage, packet)?;
+    let relayer = Addr::unchecked(RELAYER);
+    let (mut response, src_addr) = if packet.src.port_id == TRANSFER_PORT {
+        let response = timeout_transfer(api, storage, router, block, packet)?;
+        (response, transfer_sender(api, packet)?)
+    } else {
+        let src_addr = wasm.contract_by_ibc_port_id(storage, &packet.src.port_id)?;
+        let msg = IbcPacketTimeoutMsg::new(packet.clone(), relayer.clone());
+        let callback = IbcCallback::PacketTimeout(msg);
+        let response =
+            wasm.ibc_callback(api, storage, router, block, src_addr.clone(), callback)?;
+        (response, src_addr)
+    };
+    if is_source_callback(packet, &src_addr) {
+        let msg = IbcSourceCallbackMsg::Timeout(IbcTimeoutCallbackMsg {
+            packet: packet.clone(),
+            relayer,
+        });
+        let callback = IbcCallback::SourceCallback(msg);
+        let callback_response =
+            wasm.ibc_callback(api, storage, router, block, src_addr, callback)?;
+        response.events.extend(callback_response.events);
+    }
+    // timeout closes the ordered channel, it can not deliver packets in order anymore
+    if let Some(channel_end) = channel_end(storage, &packet.src.channel_id)? {
+        if channel_end.channel.order == IbcOrder::Ordered
+            && channel_end.state != ChannelState::Closed
+        {
+            channel_close_confirm(api, storage, router, wasm, block, &packet.src.channel_id)?;
+        }
+    }
+    Ok(response)
+}
+
+/// Ensures packets are received over the ordered channel in the order they were sent.
+fn receive_in_sequence(storage: &mut dyn Storage, packet: &IbcPacket) -> AnyResult<()> {
+    // see https://github.com/cosmos/ibc-go/blob/v8.0.0/modules/core/04-channel/keeper/packet.go#L240-L260
+    let mut ibc_storage = prefixed(storage, NAMESPACE_IBC_LOCALHOST);
+    let channel_id = packet.dest.channel_id.as_str();
+    let next_sequence = NEXT_SEQUENCES_RECV
+        .may_load(&ibc_storage, channel_id)?
+        .unwrap_or(1);
+    if packet.sequence != next_sequence {
+        bail!(
+            "packet sequence is out of order: packet sequence ≠ next receive sequence ({} ≠ {})",
+            packet.sequence,
+            next_sequence
+        );
+    }
+    NEXT_SEQUENCES_RECV.save(&mut ibc_storage, channel_id, &(next_sequence + 1))?;
+    Ok(())
+}
+
+/// Returns `true` when the sending contract requested to be notified about
+/// the lifecycle of the packet, only the sending contract may be the source callback contract.
+fn is_source_callback(packet: &IbcPacket, src_addr: &Addr) -> bool {
+    CallbackAddresses::from_packet_data(packet.data.as_slice())
+        .source
+        .is_some_and(|address| address == src_addr.as_str())
+}
+
+/// Removes the packet from packets pending on the connection of its source channel.
+fn remove_pending_packet(storage: &mut dyn Storage, packet: &IbcPacket) -> AnyResult<()> {
+    let Some(channel_end) = channel_end(storage, &packet.src.channel_id)? else {
+        bail!("channel {} not found", packet.src.channel_id);
+    };
+    let connection_id = channel_end.channel.connection_id;
+    let mut ibc_storage = prefixed(storage, NAMESPACE_IBC_LOCALHOST);
+    let mut packets = PENDING_PACKETS
+        .may_load(&ibc_storage, &connection_id)?
+        .unwrap_or_default();
+    packets.retain(|pending| pending.src != packet.src || pending.sequence != packet.sequence);
+    PENDING_PACKETS.save(&mut ibc_storage, &connection_id, &packets)?;
+    Ok(())
+}
+
+/// Queues the packet sent from specified port over the channel,
+/// returns the packet and the identifier of the connection of the channel.
+fn send_packet(
+    storage: &mut dyn Storage,
+    port_id: &str,
+    channel_id: &str,
+    data: Binary,
+    timeout: IbcTimeout,
+) -> AnyResult<(IbcPacket, String)> {
+    let channel = opened_channel(storage, channel_id)?;
+    if channel.endpoint.port_id != port_id {
+        bail!("channel {} is not bound to port {}", channel_id, port_id);
+    }
+    let mut ibc_storage = prefixed(storage, NAMESPACE_IBC_LOCALHOST);
+    let sequence = NEXT_SEQUENCES
+        .may_load(&ibc_storage, channel_id)?
+        .unwrap_or(1);
+    NEXT_SEQUENCES.save(&mut ibc_storage, channel_id, &(sequence + 1))?;
+    let packet = IbcPacket::new(
+        data,
+        channel.endpoint.clone(),
+        channel.counterparty_endpoint.clone(),
+        sequence,
+        timeout,
+    );
+    let mut packets = PENDING_PACKETS
+        .may_load(&ibc_storage, &channel.connection_id)?
+        .unwrap_or_default();
+    packets.push(packet.clone());
+    PENDING_PACKETS.save(&mut ibc_storage, &channel.connection_id, &packets)?;
+    Ok((packet, channel.connection_id))
+}
+
+/// Returns the event emitted when the packet is sent.
+fn send_packet_event(packet: &IbcPacket, connection_id: String) -> Event {
+    Event::new("send_packet")
+        .add_attribute("packet_src_port", &packet.src.port_id)
+        .add_attribute("packet_src_channel", &packet.src.channel_id)
+        .add_attribute("packet_dst_port", &packet.dest.port_id)
+        .add_attribute("packet_dst_channel", &packet.dest.channel_id)
+        .add_attribute("packet_sequence", packet.sequence.to_string())
+        .add_attribute("packet_connection", connection_id)
+}
+
+/// Returns `true` when the timeout has passed at the specified block.
+pub(crate) fn is_timed_out(timeout: &IbcTimeout, block: &BlockInfo) -> bool {
+    timeout
+        .block()
+        .is_some_and(|timeout| block.height >= timeout.height)
+        || timeout
+            .timestamp()
+            .is_some_and(|timestamp| block.time >= timestamp)
+}
@@ -0,0 +1,394 @@
+//! # ICS-20 fungible token transfers
+//!
+//! Tokens are transferred with `IbcMsg::Transfer` over channels opened between `transfer` ports,
+//! bound to the transfer module instead of a contract. When the sending chain is the source
+//! of the tokens, they are escrowed in the escrow account of the channel, and the receiving chain
+//! mints vouchers with the denomination `ibc/{hash}`, registering the denomination trace
+//! (see [DenomTrace](crate::DenomTrace)). When vouchers are sent back over the channel
+//! they came from, they are burned, and the original tokens are released from the escrow.
+//! Tokens are refunded to the sender when the receiving chain acknowledges the packet
+//! with an error, or when the packet times out.
+
+use crate::app::CosmosRouter;
+use crate::bank::BankSudo;
+use crate::denom_traces::{denom_trace, register_denom_trace, DenomTrace};
+use crate::error::{anyhow, bail, AnyResult};
+use crate::ibc_channels::opened_channel;
+use crate::transactions::transactional;
+use crate::wasm::IbcCallback;
+use crate::AppResponse;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Api, BankMsg, Binary, BlockInfo, CanonicalAddr, Coin,
+    CustomMsg, CustomQuery, Event, IbcAcknowledgement, IbcChannel, IbcChannelCloseMsg,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcOrder, IbcPacket, Storage, Uint128,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Identifier of the port bound to the transfer module.
+pub(crate) const TRANSFER_PORT: &str = "transfer";
+
+/// Version of the ICS-20 protocol.
+const ICS20_VERSION: &str = "ics20-1";
+
+/// Packet data of the fungible token transfer, encoded as JSON like in `ibc-go`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FungibleTokenPacketData {
+    /// Full denomination path of the transferred token, like `transfer/channel-0/uatom`.
+    pub denom: String,
+    /// Amount of the transferred token.
+    pub amount: String,
+    /// Address of the sender on the sending chain.
+    pub sender: String,
+    /// Address of the receiver on the receiving chain.
+    pub receiver: String,
+    /// Optional memo, may carry callback metadata.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub memo: String,
+}
+
+/// Acknowledgement of the fungible token transfer, encoded as JSON like in `ibc-go`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Ics20Ack {
+    /// Tokens were received.
+    Result(Binary),
+    /// Tokens were not received, the error is reported to the sending chain.
+    Error(String),
+}
+
+/// Handles the channel handshake callback for the transfer port,
+/// only unordered channels with ICS-20 version are accepted.
+/// Response data holds the proposed version for `ChannelOpen` callbacks.
+pub(crate) fn channel_callback(callback: &IbcCallback) -> AnyResult<AppResponse> {
+    // see https://github.com/cosmos/ibc-go/blob/v8.0.0/modules/apps/transfer/ibc_module.go#L70-L170
+    match callback {
+        IbcCallback::ChannelOpen(IbcChannelOpenMsg::OpenInit { channel }) => {
+            ensure_unordered(channel)?;
+            if !channel.version.is_empty() && channel.version != ICS20_VERSION {
+                bail!(
+                    "invalid ICS20 version: expected {}, got {}",
+                    ICS20_VERSION,
+                    channel.version
+                );
+            }
+            Ok(AppResponse {
+                events: vec![],
+                data: Some(Binary::from(ICS20_VERSION.as_bytes())),
+            })
+        }
+        IbcCallback::ChannelOpen(IbcChannelOpenMsg::OpenTry {
+            channel,
+            counterparty_version,
+        }) => {
+            ensure_unordered(channel)?;
+            ensure_counterparty_version(counterparty_version)?;
+            Ok(AppResponse::default())
+        }
+        IbcCallback::ChannelConnect(IbcChannelConnectMsg::OpenAck {
+            counterparty_version,
+            ..
+        }) => {
+            ensure_counterparty_version(counterparty_version)?;
+            Ok(AppResponse::default())
+        }
+        IbcCallback::ChannelConnect(IbcChannelConnectMsg::OpenConfirm { .. })
+        | IbcCallback::ChannelClose(IbcChannelCloseMsg::CloseConfirm { .. }) => {
+            Ok(AppResponse::default())
+        }
+        IbcCallback::ChannelClose(IbcChannelCloseMsg::CloseInit { .. }) => {
+            bail!("user cannot close channel: invalid request")
+        }
+        callback => bail!("Unexpected IBC callback {:?} for transfer module", callback),
+    }
+}
+
+/// Escrows or burns the transferred tokens, returns the packet data sent over the channel
+/// and the event of the transfer.
+pub(crate) fn send_transfer<ExecC, QueryC>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    sender: &Addr,
+    channel_id: &str,
+    receiver: String,
+    amount: Coin,
+    memo: Option<String>,
+) -> AnyResult<(Binary, Event)>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+{
+    if amount.amount.is_zero() {
+        bail!("invalid token amount: amount must be positive");
+    }
+    let channel = opened_channel(storage, channel_id)?;
+    let full_denom_path = match amount.denom.strip_prefix("ibc/") {
+        Some(hash) => denom_trace(storage, hash)?
+            .ok_or_else(|| anyhow!("denomination trace not found: {}", hash))?
+            .full_path(),
+        None => amount.denom.clone(),
+    };
+    // see https://github.com/cosmos/ibc-go/blob/v8.0.0/modules/apps/transfer/keeper/relay.go#L116-L160
+    if is_sender_chain_source(&channel.endpoint.port_id, channel_id, &full_denom_path) {
+        let escrow_addr = escrow_address(api, &channel.endpoint.port_id, channel_id)?;
+        let msg = BankMsg::Send {
+            to_address: escrow_addr.to_string(),
+            amount: vec![amount.clone()],
+        };
+        router.execute(api, storage, block, sender.clone(), msg.into())?;
+    } else {
+        let msg = BankMsg::Burn {
+            amount: vec![amount.clone()],
+        };
+        router.execute(api, storage, block, sender.clone(), msg.into())?;
+    }
+    let data = FungibleTokenPacketData {
+        denom: full_denom_path,
+        amount: amount.amount.to_string(),
+        sender: sender.to_string(),
+        receiver,
+        memo: memo.unwrap_or_default(),
+    };
+    let event = Event::new("ibc_transfer")
+        .add_attribute("sender", &data.sender)
+        .add_attribute("receiver", &data.receiver)
+        .add_attribute("amount", &data.amount)
+        .add_attribute("denom", &data.denom)
+        .add_attribute("memo", &data.memo);
+    Ok((to_json_binary(&data)?, event))
+}
+
+/// Receives tokens transferred in the packet, releasing them from the escrow or minting
+/// vouchers for the receiver. Changes are reverted when tokens can not be received,
+/// and the error is written to the returned acknowledgement.
+pub(crate) fn receive_transfer<ExecC, QueryC>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    packet: &IbcPacket,
+) -> AnyResult<(AppResponse, IbcAcknowledgement)>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+{
+    let result = transactional(storage, |write_cache, _| {
+        on_recv_packet(api, write_cache, router, block, packet)
+    });
+    let (data, ack) = match result {
+        Ok(data) => (Some(data), Ics20Ack::Result(Binary::from(vec![1]))),
+        Err(err) => (None, Ics20Ack::Error(err.to_string())),
+    };
+    let mut event = Event::new("fungible_token_packet").add_attribute("module", TRANSFER_PORT);
+    if let Some(data) = data {
+        event = event
+            .add_attribute("sender", data.sender)
+            .add_attribute("receiver", data.receiver)
+            .add_attribute("denom", data.denom)
+            .add_attribute("amount", data.amount)
+            .add_attribute("memo", data.memo);
+    }
+    let event = event.add_attribute("success", matches!(ack, Ics20Ack::Result(_)).to_string());
+    let response = AppResponse {
+        events: vec![event],
+        data: None,
+    };
+    Ok((response, IbcAcknowledgement::encode_json(&ack)?))
+}
+
+/// Handles the acknowledgement of the transfer, tokens are refunded to the sender
+/// when the receiving chain reported an error.
+pub(crate) fn acknowledge_transfer<ExecC, QueryC>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    packet: &IbcPacket,
+    acknowledgement: &IbcAcknowledgement,
+) -> AnyResult<AppResponse>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+{
+    let event = Event::new("fungible_token_packet").add_attribute("module", TRANSFER_PORT);
+    let event = match from_json(&acknowledgement.data)? {
+        Ics20Ack::Result(_) => event.add_attribute("success", "true"),
+        Ics20Ack::Error(error) => {
+            refund_transfer(api, storage, router, block, packet)?;
+            event.add_attribute("error", error)
+        }
+    };
+    Ok(AppResponse {
+        events: vec![event],
+        data: None,
+    })
+}
+
+/// Handles the timeout of the transfer, tokens are refunded to the sender.
+pub(crate) fn timeout_transfer<ExecC, QueryC>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    packet: &IbcPacket,
+) -> AnyResult<AppResponse>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+{
+    // see https://github.com/cosmos/ibc-go/blob/v8.0.0/modules/apps/transfer/ibc_module.go#L270-L300
+    let data = refund_transfer(api, storage, router, block, packet)?;
+    let event = Event::new("timeout")
+        .add_attribute("module", TRANSFER_PORT)
+        .add_attribute("refund_receiver", data.sender)
+        .add_attribute("refund_denom", data.denom)
+        .add_attribute("refund_amount", data.amount)
+        .add_attribute("memo", data.memo);
+    Ok(AppResponse {
+        events: vec![event],
+        data: None,
+    })
+}
+
+/// Returns the address of the sender of tokens transferred in the packet.
+pub(crate) fn transfer_sender(api: &dyn Api, packet: &IbcPacket) -> AnyResult<Addr> {
+    let data: FungibleTokenPacketData = from_json(&packet.data)?;
+    Ok(api.addr_validate(&data.sender)?)
+}
+
+/// Returns the address of the account escrowing tokens sent over the channel,
+/// generated the same way as in `ibc-go`.
+pub(crate) fn escrow_address(api: &dyn Api, port_id: &str, channel_id: &str) -> AnyResult<Addr> {
+    // see https://github.com/cosmos/ibc-go/blob/v8.0.0/modules/apps/transfer/types/keys.go#L48-L60
+    let mut pre_image = ICS20_VERSION.as_bytes().to_vec();
+    pre_image.push(0);
+    pre_image.extend_from_slice(format!("{}/{}", port_id, channel_id).as_bytes());
+    let hash = Sha256::digest(&pre_image);
+    Ok(api.addr_humanize(&CanonicalAddr::from(&hash[..20]))?)
+}
+
+/// Returns the tokens sent in the packet to the sender, releasing them from the escrow
+/// or minting back the burned vouchers. Returns the packet data of the refunded transfer.
+fn refund_transfer<ExecC, QueryC>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    packet: &IbcPacket,
+) -> AnyResult<FungibleTokenPacketData>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+{
+    // see https://github.com/cosmos/ibc-go/blob/v8.0.0/modules/apps/transfer/keeper/relay.go#L345-L390
+    let data: FungibleTokenPacketData = from_json(&packet.data)?;
+    let denom = parse_denom_trace(&data.denom).ibc_denom();
+    let amount = Coin::new(data.amount.parse::<Uint128>()?.u128(), denom);
+    let sender = api.addr_validate(&data.sender)?;
+    if is_sender_chain_source(&packet.src.port_id, &packet.src.channel_id, &data.denom) {
+        let escrow_addr = escrow_address(api, &packet.src.port_id, &packet.src.channel_id)?;
+        let msg = BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: vec![amount],
+        };
+        router.execute(api, storage, block, escrow_addr, msg.into())?;
+    } else {
+        let msg = BankSudo::Mint {
+            to_address: sender.to_string(),
+            amount: vec![amount],
+        };
+        router.sudo(api, storage, block, msg.into())?;
+    }
+    Ok(data)
+}
+
+/// Releases tokens from the escrow or mints vouchers for the receiver,
+/// returns the packet data of the transfer.
+fn on_recv_packet<ExecC, QueryC>(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    packet: &IbcPacket,
+) -> AnyResult<FungibleTokenPacketData>
+where
+    ExecC: CustomMsg,
+    QueryC: CustomQuery,
+{
+    // see https://github.com/cosmos/ibc-go/blob/v8.0.0/modules/apps/transfer/keeper/relay.go#L190-L330
+    let data: FungibleTokenPacketData = from_json(&packet.data)?;
+    let amount = data.amount.parse::<Uint128>()?;
+    if amount.is_zero() {
+        bail!("invalid token amount: amount must be positive");
+    }
+    let receiver = api.addr_validate(&data.receiver)?;
+    let voucher_prefix = format!("{}/{}/", packet.src.port_id, packet.src.channel_id);
+    if let Some(unprefixed_denom) = data.denom.strip_prefix(&voucher_prefix) {
+        // tokens are returning to the chain they came from
+        let denom = parse_denom_trace(unprefixed_denom).ibc_denom();
+        let escrow_addr = escrow_address(api, &packet.dest.port_id, &packet.dest.channel_id)?;
+        let msg = BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: vec![Coin::new(amount.u128(), denom)],
+        };
+        router.execute(api, storage, block, escrow_addr, msg.into())?;
+    } else {
+        let prefixed_denom = format!(
+            "{}/{}/{}",
+            packet.dest.port_id, packet.dest.channel_id, data.denom
+        );
+        let denom = register_denom_trace(storage, &parse_denom_trace(&prefixed_denom))?;
+        let msg = BankSudo::Mint {
+            to_address: receiver.to_string(),
+            amount: vec![Coin::new(amount.u128(), denom)],
+        };
+        router.sudo(api, storage, block, msg.into())?;
+    }
+    Ok(data)
+}
+
+/// Returns `true` when the chain sending tokens over the channel is their source,
+/// so the denomination was not received over the same channel before.
+fn is_sender_chain_source(port_id: &str, channel_id: &str, full_denom_path: &str) -> bool {
+    !full_denom_path.starts_with(&format!("{}/{}/", port_id, channel_id))
+}
+
+/// Parses the full denomination path, like `transfer/channel-0/uatom`, into the trace
+/// of port/channel identifier pairs and the base denomination.
+fn parse_denom_trace(full_denom_path: &str) -> DenomTrace {
+    let segments = full_denom_path.split('/').collect::<Vec<_>>();
+    let mut path_len = 0;
+    while path_len + 1 < segments.len() && segments[path_len + 1].starts_with("channel-") {
+        path_len += 2;
+    }
+    if path_len == segments.len() {
+        // full path consisting only of identifiers is the base denomination
+        return DenomTrace::new("", full_denom_path);
+    }
+    DenomTrace::new(
+        segments[..path_len].join("/"),
+        segments[path_len..].join("/"),
+    )
+}
+
+/// Returns an error when the channel is not unordered.
+fn ensure_unordered(channel: &IbcChannel) -> AnyResult<()> {
+    if channel.order != IbcOrder::Unordered {
+        bail!("invalid channel ordering: expected UNORDERED channel, got ORDERED");
+    }
+    Ok(())
+}
+
+/// Returns an error when the counterparty does not use ICS-20 version.
+fn ensure_counterparty_version(counterparty_version: &str) -> AnyResult<()> {
+    if counterparty_version != ICS20_VERSION {
+        bail!(
+            "invalid ICS20 version: invalid counterparty version: expected {}, got {}",
+            ICS20_VERSION,
+            counterparty_version
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,170 @@
+//! # Idempotency assertions
+//!
+//! Messages like claiming rewards, processing a withdrawal or settling an auction must not
+//! have any effect when delivered again. [assert_idempotent] executes the message twice
+//! on a copy of the application and verifies the repeated execution according
+//! to the [IdempotencyPolicy], so this common safety test takes a single line.
+
+use crate::{App, Bank, Distribution, Executor, Gov, Ibc, Mint, Module, Staking, Stargate, Wasm};
+use cosmwasm_std::{Addr, Api, CustomMsg, CustomQuery, Order, Record, Storage};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// Expected outcome of the repeated execution of the message checked by [assert_idempotent].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdempotencyPolicy {
+    /// Repeated execution must fail.
+    MustFail,
+    /// Repeated execution must succeed without changing the state.
+    MustBeNoOp,
+    /// Repeated execution must either fail or succeed without changing the state.
+    FailOrNoOp,
+}
+
+/// Executes the message on the contract twice, on a copy of the application,
+/// and asserts that the repeated execution meets the policy. Panics when the first
+/// execution fails or the repeated execution violates the policy.
+///
+/// The state of the application passed to this function is not changed.
+/// The application is cloned, so its storage must be cloneable, like [CloneableStorage](crate::CloneableStorage).
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult};
+/// use cw_multi_test::{
+///     assert_idempotent, no_init, AppBuilder, CloneableStorage, ContractWrapper, Executor,
+///     IdempotencyPolicy,
+/// };
+///
+/// fn execute(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+///     if deps.storage.get(b"claimed").is_some() {
+///         return Err(StdError::generic_err("already claimed"));
+///     }
+///     deps.storage.set(b"claimed", b"yes");
+///     Ok(Response::default())
+/// }
+///
+/// fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+///     Ok(Response::default())
+/// }
+///
+/// fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+///     Ok(Binary::default())
+/// }
+///
+/// let mut app = AppBuilder::default()
+///     .with_storage(CloneableStorage::default())
+///     .build(no_init);
+/// let sender_addr = app.api().addr_make("sender");
+/// let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+/// let contract_addr = app
+///     .instantiate_contract(code_id, sender_addr.clone(), &Empty {}, &[], "claim", None)
+///     .unwrap();
+///
+/// assert_idempotent(&app, sender_addr, contract_addr, &Empty {}, IdempotencyPolicy::MustFail);
+/// ```
+#[track_caller]
+pub fn assert_idempotent<
+    BankT,
+    ApiT,
+    StorageT,
+    CustomT,
+    WasmT,
+    StakingT,
+    DistrT,
+    IbcT,
+    GovT,
+    StargateT,
+    MintT,
+    T,
+>(
+    app: &App<
+        BankT,
+        ApiT,
+        StorageT,
+        CustomT,
+        WasmT,
+        StakingT,
+        DistrT,
+        IbcT,
+        GovT,
+        StargateT,
+        MintT,
+    >,
+    sender: Addr,
+    contract: Addr,
+    msg: &T,
+    policy: IdempotencyPolicy,
+) where
+    CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
+    CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
+    WasmT: Wasm<CustomT::ExecT, CustomT::QueryT> + Clone,
+    BankT: Bank + Clone,
+    ApiT: Api + Clone,
+    StorageT: Storage + Clone,
+    CustomT: Module + Clone,
+    StakingT: Staking + Clone,
+    DistrT: Distribution + Clone,
+    IbcT: Ibc + Clone,
+    GovT: Gov + Clone,
+    StargateT: Stargate + Clone,
+    MintT: Mint + Clone,
+    T: Serialize + Debug,
+{
+    let mut app = app.clone();
+    if let Err(err) = app.execute_contract(sender.clone(), contract.clone(), msg, &[]) {
+        panic!("first execution of {:?} failed: {}", msg, err);
+    }
+    let state = dump_state(app.storage());
+    match app.execute_contract(sender, contract, msg, &[]) {
+        Ok(_) if policy == IdempotencyPolicy::MustFail => {
+            panic!(
+                "repeated execution of {:?} succeeded, but was expected to fail",
+                msg
+            );
+        }
+        Ok(_) => {
+            if let Some(key) = first_changed_key(&state, &dump_state(app.storage())) {
+                panic!(
+                    "repeated execution of {:?} changed the state at key {}",
+                    msg,
+                    String::from_utf8_lossy(&key)
+                );
+            }
+        }
+        Err(err) if policy == IdempotencyPolicy::MustBeNoOp => {
+            panic!(
+                "repeated execution of {:?} failed, but was expected to be a no-op: {}",
+                msg, err
+            );
+        }
+        Err(_) => {}
+    }
+}
+
+/// Returns all key-values of the storage.
+fn dump_state(storage: &dyn Storage) -> Vec<Record> {
+    storage.range(None, None, Order::Ascending).collect()
+}
+
+/// Returns the first key with a different value (or missing) in one of the states.
+fn first_changed_key(before: &[Record], after: &[Record]) -> Option<Vec<u8>> {
+    let mut before = before.iter();
+    let mut after = after.iter();
+    loop {
+        match (before.next(), after.next()) {
+            (None, None) => return None,
+            (Some((key, _)), None) | (None, Some((key, _))) => return Some(key.clone()),
+            (Some((before_key, before_value)), Some((after_key, after_value))) => {
+                if before_key != after_key {
+                    return Some(before_key.min(after_key).clone());
+                }
+                if before_value != after_value {
+                    return Some(before_key.clone());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! # Per-instance contract context
+//!
+//! A single code stored with [ContractWrapper::new_closure](crate::ContractWrapper::new_closure)
+//! is often instantiated many times, while every instance should behave differently,
+//! like oracles returning different prices. [InstanceContext] associates test fixtures
+//! with contract instances (by address), so closures of one wrapper can look up
+//! the fixture of the instance being called, using the address passed in [Env].
+
+use cosmwasm_std::{Addr, Env, StdError, StdResult};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+/// Fixtures of contract instances, shared between the test and contract closures.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{to_json_binary, Addr, Empty, Response, StdError};
+/// use cw_multi_test::{App, ContractWrapper, Executor, InstanceContext};
+///
+/// let prices = InstanceContext::<u128>::new();
+/// let query_prices = prices.clone();
+/// let contract = ContractWrapper::new_closure(
+///     |_, _, _, _: Empty| Ok::<_, StdError>(Response::default()),
+///     |_, _, _, _: Empty| Ok::<_, StdError>(Response::default()),
+///     move |_, env, _: Empty| to_json_binary(&query_prices.of(&env)?),
+/// );
+///
+/// let mut app = App::default();
+/// let code_id = app.store_code(Box::new(contract));
+/// let owner = app.api().addr_make("owner");
+/// let mut oracle = |label: &str| -> Addr {
+///     app.instantiate_contract(code_id, owner.clone(), &Empty {}, &[], label, None)
+///         .unwrap()
+/// };
+/// let (oracle_a, oracle_b) = (oracle("a"), oracle("b"));
+/// prices.set(oracle_a.clone(), 10);
+/// prices.set(oracle_b.clone(), 20);
+///
+/// let price: u128 = app.wrap().query_wasm_smart(oracle_a, &Empty {}).unwrap();
+/// assert_eq!(10, price);
+/// let price: u128 = app.wrap().query_wasm_smart(oracle_b, &Empty {}).unwrap();
+/// assert_eq!(20, price);
+/// ```
+#[derive(Debug)]
+pub struct InstanceContext<T>(Rc<RefCell<BTreeMap<Addr, T>>>);
+
+impl<T> Clone for InstanceContext<T> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<T> Default for InstanceContext<T> {
+    fn default() -> Self {
+        Self(Rc::default())
+    }
+}
+
+impl<T: Clone> InstanceContext<T> {
+    /// Creates an empty context, shared between all its clones.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the fixture of the contract instance, replacing the previous one.
+    pub fn set(&self, contract_addr: Addr, value: T) {
+        self.0.borrow_mut().insert(contract_addr, value);
+    }
+
+    /// Removes the fixture of the contract instance, returns the removed fixture.
+    pub fn remove(&self, contract_addr: &Addr) -> Option<T> {
+        self.0.borrow_mut().remove(contract_addr)
+    }
+
+    /// Returns the fixture of the contract instance, if set.
+    pub fn get(&self, contract_addr: &Addr) -> Option<T> {
+        self.0.borrow().get(contract_addr).cloned()
+    }
+
+    /// Updates the fixture of the contract instance in place, when set.
+    /// Returns `true` when the fixture was updated.
+    pub fn update(&self, contract_addr: &Addr, action: impl FnOnce(&mut T)) -> bool {
+        match self.0.borrow_mut().get_mut(contract_addr) {
+            Some(value) => {
+                action(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the fixture of the contract instance being called,
+    /// or an error when no fixture was set for this instance.
+    pub fn of(&self, env: &Env) -> StdResult<T> {
+        self.get(&env.contract.address).ok_or_else(|| {
+            StdError::not_found(format!("context of contract {}", env.contract.address))
+        })
+    }
+}
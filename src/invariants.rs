@@ -0,0 +1,68 @@
+//! # Invariant checks
+//!
+//! When enabled with [enable_invariant_checks](crate::App::enable_invariant_checks),
+//! the state of the bank module is verified after every message processed by the
+//! [Router](crate::Router), including messages sent by contracts and custom modules:
+//! for every denomination, the sum of balances of all accounts (including module accounts)
+//! must be equal to the total supply. A violated invariant aborts the test with a panic
+//! reporting the differences, catching accounting bugs in modules that change balances
+//! without adjusting the supply (or vice versa).
+
+use crate::bank::supply_mismatches;
+use cosmwasm_std::Storage;
+use std::cell::Cell;
+use std::fmt::Debug;
+
+/// Verifies invariants of the chain state after every processed message, when enabled.
+#[derive(Clone, Default)]
+pub(crate) struct InvariantChecker {
+    enabled: Cell<bool>,
+}
+
+impl InvariantChecker {
+    /// Starts verifying invariants.
+    pub fn enable(&self) {
+        self.enabled.set(true);
+    }
+
+    /// Stops verifying invariants.
+    pub fn disable(&self) {
+        self.enabled.set(false);
+    }
+
+    /// Returns `true` when invariants are verified.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Verifies invariants after the message was successfully processed,
+    /// panics with the report of differences when any invariant is violated.
+    pub fn check(&self, storage: &dyn Storage, msg: &impl Debug) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mismatches = supply_mismatches(storage).expect("failed to read bank state");
+        if mismatches.is_empty() {
+            return;
+        }
+        let diff = mismatches
+            .iter()
+            .map(|(denom, balances, supply)| {
+                let (sign, delta) = if balances > supply {
+                    ('+', *balances - *supply)
+                } else {
+                    ('-', *supply - *balances)
+                };
+                format!(
+                    "  {}: sum of balances {} != total supply {} ({}{})",
+                    denom, balances, supply, sign, delta
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        panic!(
+            "bank invariant violated after processing {:?}\n{}",
+            msg, diff
+        );
+    }
+}
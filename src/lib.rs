@@ -125,43 +125,127 @@
 #![deny(rustdoc::missing_crate_level_docs)]
 
 mod addresses;
+mod adversary;
 mod api;
 mod app;
 mod app_builder;
+mod async_entry_points;
 mod bank;
+mod budget;
 mod checksums;
+mod cloneable_storage;
+mod codec;
 mod contracts;
+mod cron;
 pub mod custom_handler;
+mod decorators;
+mod denom_traces;
 pub mod error;
 mod executor;
+mod features;
 mod gov;
 mod ibc;
+mod ibc_callbacks;
+mod ibc_channels;
+mod ibc_localhost;
+mod ibc_transfer;
+mod idempotency;
+mod instance_context;
+mod invariants;
+mod macros;
+mod matchers;
+mod mint;
+mod mock_contract;
 mod module;
+mod multisig;
+mod param_history;
+mod pause;
 mod prefixed_storage;
+mod regression;
+mod relayer;
+#[cfg(feature = "rpc")]
+mod rpc;
+mod schema_validation;
+mod shadow;
+mod snapshots;
+mod spans;
+mod spying;
 mod staking;
 mod stargate;
+mod strict;
 mod test_helpers;
 mod tests;
+mod trace;
 mod transactions;
+mod versions;
 mod wasm;
+#[cfg(feature = "wasm_vm")]
+mod wasm_contract;
 
 pub use crate::addresses::{
     AddressGenerator, IntoAddr, IntoBech32, IntoBech32m, SimpleAddressGenerator,
 };
-pub use crate::api::{MockApiBech32, MockApiBech32m};
+pub use crate::adversary::Adversary;
+pub use crate::api::{MockApiBech32, MockApiBech32m, MockApiVectors};
 pub use crate::app::{
-    custom_app, next_block, no_init, App, BasicApp, CosmosRouter, Router, SudoMsg,
+    custom_app, next_block, no_init, past_timeout, App, BasicApp, CosmosRouter, CosmosSdkMsg,
+    Router, SudoMsg,
 };
 pub use crate::app_builder::{AppBuilder, BasicAppBuilder};
-pub use crate::bank::{Bank, BankKeeper, BankSudo};
+pub use crate::async_entry_points::{AsyncContractFn, AsyncQueryFn};
+pub use crate::bank::{
+    Bank, BankKeeper, BankParams, BankSudo, DenomOwner, DenomOwnersResponse, MultiSendIo,
+    SendEnabled, SendRestriction, TransferHookMsg, VestingAccount, VestingPeriod, VestingSchedule,
+};
+pub use crate::budget::{ResourceBudget, ResourceUsage};
 pub use crate::checksums::ChecksumGenerator;
-pub use crate::contracts::{Contract, ContractWrapper};
+pub use crate::cloneable_storage::CloneableStorage;
+pub use crate::codec::{JsonCodec, MessageCodec};
+pub use crate::contracts::{default_msg_translator, Contract, ContractWrapper};
+pub use crate::cron::{ExecutionStage, FailurePolicy, Schedule, ScheduledMsg};
+pub use crate::decorators::ContractDecorators;
+pub use crate::denom_traces::DenomTrace;
 pub use crate::executor::{AppResponse, Executor};
-pub use crate::gov::{Gov, GovAcceptingModule, GovFailingModule};
+pub use crate::features::ExperimentalFeature;
+pub use crate::gov::{
+    Gov, GovAcceptingModule, GovFailingModule, GovKeeper, GovParams, GovSudo, Proposal,
+    ProposalStatus, SubmitProposal, TallyResult,
+};
 pub use crate::ibc::{Ibc, IbcAcceptingModule, IbcFailingModule};
+pub use crate::ibc_callbacks::{
+    IbcAckCallbackMsg, IbcDestinationCallbackMsg, IbcSourceCallbackMsg, IbcTimeoutCallbackMsg,
+};
+pub use crate::ibc_channels::{ChannelEnd, ChannelState};
+pub use crate::ibc_localhost::IbcLocalhost;
+pub use crate::idempotency::{assert_idempotent, IdempotencyPolicy};
+pub use crate::instance_context::InstanceContext;
+pub use crate::matchers::{FundsMatcher, IntoFunds};
+pub use crate::mint::{
+    AnnualProvisionsResponse, InflationResponse, Mint, MintFailingModule, MintKeeper, MintParams,
+    MintParamsResponse, MintQuery, MintSudo, Minter,
+};
+pub use crate::mock_contract::MockContract;
 pub use crate::module::{AcceptingModule, FailingModule, Module};
+pub use crate::multisig::MultisigAdmin;
+pub use crate::param_history::ParamChange;
+pub use crate::pause::{PausableModule, PauseSudo};
+pub use crate::relayer::{IbcChain, Relayer};
+#[cfg(feature = "rpc")]
+pub use crate::rpc::{RpcFacade, RpcLimits, RpcResponse};
+pub use crate::schema_validation::SchemaValidated;
+pub use crate::shadow::{Divergence, DivergenceLog, Shadowed};
+pub use crate::spying::{SpiedCall, SpyLog, Spying};
 pub use crate::staking::{
-    Distribution, DistributionKeeper, StakeKeeper, Staking, StakingInfo, StakingSudo,
+    Distribution, DistributionKeeper, DistributionSudo, LiquidStakingMsg, RedelegationEntry,
+    RewardAccrual, RewardDistribution, SlashingParams, StakeKeeper, Staking, StakingHookMsg,
+    StakingInfo, StakingSudo, TokenizeShareRecord, UnbondingEntry, ValidatorDescription,
+};
+pub use crate::stargate::{
+    CosmosSdkStargate, Stargate, StargateAccepting, StargateDenomTraces, StargateFailing,
+    StargateTendermint,
 };
-pub use crate::stargate::{Stargate, StargateAccepting, StargateFailing};
-pub use crate::wasm::{ContractData, Wasm, WasmKeeper, WasmSudo};
+pub use crate::trace::{ExecutionTrace, TraceEntry};
+pub use crate::versions::MigrationMatrix;
+pub use crate::wasm::{ContractData, IbcCallback, Wasm, WasmKeeper, WasmSudo};
+#[cfg(feature = "wasm_vm")]
+pub use crate::wasm_contract::WasmContract;
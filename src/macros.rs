@@ -0,0 +1,87 @@
+//! # Macros
+
+/// Builds a [ContractWrapper](crate::ContractWrapper) from a contract dispatching its messages,
+/// like contracts written with the [Sylvia](https://github.com/CosmWasm/sylvia) framework.
+///
+/// The first argument is an expression creating the contract instance, it is evaluated
+/// on each call to the entry-point. The second argument is a path to the module with messages
+/// (for Sylvia contracts it is the generated `sv` module), which must define:
+///
+/// - `InstantiateMsg` with `dispatch(self, &contract, (DepsMut, Env, MessageInfo))`,
+/// - `ContractExecMsg` with `dispatch(self, &contract, (DepsMut, Env, MessageInfo))`,
+/// - `ContractQueryMsg` with `dispatch(self, &contract, (Deps, Env))` returning [Binary](cosmwasm_std::Binary).
+///
+/// Optional `sudo` and `migrate` entry-points are wired up when listed after the `with` keyword,
+/// they dispatch `ContractSudoMsg` and `MigrateMsg` respectively with `(DepsMut, Env)` context.
+///
+/// # Example
+///
+/// ```ignore
+/// use cw_multi_test::{dispatch_contract, App};
+///
+/// let mut app = App::default();
+/// let code_id = app.store_code(Box::new(dispatch_contract!(
+///     counter::CounterContract::new(),
+///     counter::sv with migrate
+/// )));
+/// ```
+#[macro_export]
+macro_rules! dispatch_contract {
+    (@build $wrapper:expr; $contract:expr; $($sv:ident)::+;) => {
+        $wrapper
+    };
+    (@build $wrapper:expr; $contract:expr; $($sv:ident)::+; sudo $(, $rest:ident)*) => {
+        $crate::dispatch_contract!(
+            @build $wrapper.with_sudo_closure(|deps, env, msg: $($sv)::+::ContractSudoMsg| {
+                msg.dispatch(&$contract, (deps, env))
+            });
+            $contract; $($sv)::+; $($rest),*
+        )
+    };
+    (@build $wrapper:expr; $contract:expr; $($sv:ident)::+; migrate $(, $rest:ident)*) => {
+        $crate::dispatch_contract!(
+            @build $wrapper.with_migrate_closure(|deps, env, msg: $($sv)::+::MigrateMsg| {
+                msg.dispatch(&$contract, (deps, env))
+            });
+            $contract; $($sv)::+; $($rest),*
+        )
+    };
+    ($contract:expr, $($sv:ident)::+) => {
+        $crate::ContractWrapper::new_closure(
+            |deps, env, info, msg: $($sv)::+::ContractExecMsg| {
+                msg.dispatch(&$contract, (deps, env, info))
+            },
+            |deps, env, info, msg: $($sv)::+::InstantiateMsg| {
+                msg.dispatch(&$contract, (deps, env, info))
+            },
+            |deps, env, msg: $($sv)::+::ContractQueryMsg| msg.dispatch(&$contract, (deps, env)),
+        )
+    };
+    ($contract:expr, $($sv:ident)::+ with $($entry_point:ident),+ $(,)?) => {
+        $crate::dispatch_contract!(
+            @build $crate::dispatch_contract!($contract, $($sv)::+);
+            $contract; $($sv)::+; $($entry_point),+
+        )
+    };
+}
+
+/// Asserts that two lists of funds are equal, ignoring order of coins and zero amounts.
+///
+/// Both arguments may be given as coins or in the Cosmos SDK notation, like `"100uatom,5uosmo"`
+/// (see [IntoFunds](crate::IntoFunds)). Panics with both normalized lists when they differ.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::coin;
+/// use cw_multi_test::coins_eq;
+///
+/// coins_eq!(vec![coin(5, "uosmo"), coin(100, "uatom")], "100uatom,5uosmo");
+/// ```
+#[macro_export]
+macro_rules! coins_eq {
+    ($actual:expr, $expected:expr $(,)?) => {
+        $crate::FundsMatcher::exactly($expected)
+            .assert_matches(&$crate::IntoFunds::into_funds($actual))
+    };
+}
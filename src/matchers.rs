@@ -0,0 +1,173 @@
+//! # Funds matchers
+//!
+//! Comparing coins returned from balance queries or transferred in responses is error-prone:
+//! the order of coins, duplicated denominations and zero amounts make equal funds look different.
+//! [FundsMatcher] and the [coins_eq](crate::coins_eq) macro compare normalized funds,
+//! which can be given as coins or in the Cosmos SDK notation, like `"100uatom,5uosmo"`.
+
+use cosmwasm_std::{Coin, Uint128};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Conversion into funds compared by [FundsMatcher].
+pub trait IntoFunds {
+    /// Converts into a list of coins.
+    fn into_funds(self) -> Vec<Coin>;
+}
+
+/// Parses funds in the Cosmos SDK notation, like `"100uatom,5uosmo"`.
+/// Panics when the notation is invalid, since funds are given in tests as literals.
+impl IntoFunds for &str {
+    fn into_funds(self) -> Vec<Coin> {
+        self.split(',')
+            .map(str::trim)
+            .filter(|coin| !coin.is_empty())
+            .map(|coin| {
+                Coin::from_str(coin)
+                    .unwrap_or_else(|err| panic!("invalid coin '{}': {}", coin, err))
+            })
+            .collect()
+    }
+}
+
+impl IntoFunds for Coin {
+    fn into_funds(self) -> Vec<Coin> {
+        vec![self]
+    }
+}
+
+impl IntoFunds for Vec<Coin> {
+    fn into_funds(self) -> Vec<Coin> {
+        self
+    }
+}
+
+impl IntoFunds for &Vec<Coin> {
+    fn into_funds(self) -> Vec<Coin> {
+        self.clone()
+    }
+}
+
+impl IntoFunds for &[Coin] {
+    fn into_funds(self) -> Vec<Coin> {
+        self.to_vec()
+    }
+}
+
+/// Sums amounts of the same denomination and skips zero amounts.
+fn normalize(funds: &[Coin]) -> BTreeMap<&str, Uint128> {
+    let mut normalized = BTreeMap::<&str, Uint128>::new();
+    for coin in funds {
+        *normalized.entry(coin.denom.as_str()).or_default() += coin.amount;
+    }
+    normalized.retain(|_, amount| !amount.is_zero());
+    normalized
+}
+
+/// Formats funds in the Cosmos SDK notation, `"(none)"` for empty funds.
+pub(crate) fn funds_to_string(funds: &[Coin]) -> String {
+    let normalized = normalize(funds);
+    if normalized.is_empty() {
+        return "(none)".to_string();
+    }
+    normalized
+        .iter()
+        .map(|(denom, amount)| format!("{}{}", amount, denom))
+        .join(",")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Matching {
+    Exactly,
+    AtLeast,
+    AtMost,
+}
+
+/// Matcher of funds, like account balances or coins transferred in a response.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::coin;
+/// use cw_multi_test::FundsMatcher;
+///
+/// let balance = vec![coin(5, "uatom"), coin(150, "uosmo")];
+/// assert!(FundsMatcher::at_least("100uosmo").matches(&balance));
+/// assert!(FundsMatcher::at_most("10uatom,200uosmo").matches(&balance));
+/// assert!(!FundsMatcher::exactly("150uosmo").matches(&balance));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FundsMatcher {
+    matching: Matching,
+    expected: Vec<Coin>,
+}
+
+impl FundsMatcher {
+    /// Matches funds equal to expected ones, ignoring order of coins and zero amounts.
+    pub fn exactly(expected: impl IntoFunds) -> Self {
+        Self::new(Matching::Exactly, expected)
+    }
+
+    /// Matches funds containing at least the expected amount of every expected denomination.
+    /// Other denominations are ignored.
+    pub fn at_least(expected: impl IntoFunds) -> Self {
+        Self::new(Matching::AtLeast, expected)
+    }
+
+    /// Matches funds containing at most the expected amount of every denomination.
+    /// Denominations not listed in expected funds must not be present.
+    pub fn at_most(expected: impl IntoFunds) -> Self {
+        Self::new(Matching::AtMost, expected)
+    }
+
+    /// Matches empty funds.
+    pub fn none() -> Self {
+        Self::new(Matching::Exactly, Vec::<Coin>::new())
+    }
+
+    fn new(matching: Matching, expected: impl IntoFunds) -> Self {
+        Self {
+            matching,
+            expected: expected.into_funds(),
+        }
+    }
+
+    /// Returns `true` when actual funds match.
+    pub fn matches(&self, actual: &[Coin]) -> bool {
+        let expected = normalize(&self.expected);
+        let actual = normalize(actual);
+        match self.matching {
+            Matching::Exactly => expected == actual,
+            Matching::AtLeast => expected
+                .iter()
+                .all(|(denom, amount)| actual.get(denom).is_some_and(|actual| actual >= amount)),
+            Matching::AtMost => actual
+                .iter()
+                .all(|(denom, amount)| expected.get(denom).is_some_and(|max| amount <= max)),
+        }
+    }
+
+    /// Like [matches](Self::matches) but panics when actual funds do not match.
+    #[track_caller]
+    pub fn assert_matches(&self, actual: &[Coin]) {
+        assert!(
+            self.matches(actual),
+            "expected funds {}, but found {}",
+            self,
+            funds_to_string(actual)
+        );
+    }
+}
+
+impl fmt::Display for FundsMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let matching = match self.matching {
+            Matching::Exactly => "exactly",
+            Matching::AtLeast => "at least",
+            Matching::AtMost => "at most",
+        };
+        write!(f, "{} {}", matching, funds_to_string(&self.expected))
+    }
+}
@@ -0,0 +1,613 @@
+//! # Minting block provisions
+//!
+//! Tokens of the bonded denomination are minted at the end of every block by the [Mint] module
+//! of the [App](crate::App), like `BeginBlocker` of the mint module in Cosmos SDK.
+//! The default [MintKeeper] mints tokens after its parameters are set
+//! with [setup](MintKeeper::setup) or privileged [MintSudo] actions.
+//! Minted tokens, together with fees collected by the fee collector, are allocated
+//! to the community pool, the block proposer and bonded validators.
+//! The state of the mint module can be queried with [MintQuery]
+//! or the `/cosmos.mint.v1beta1.Query/*` gRPC queries.
+
+use crate::addresses::module_address;
+use crate::app::CosmosRouter;
+use crate::error::{bail, AnyResult};
+use crate::executor::AppResponse;
+use crate::param_history::record_param_change;
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use crate::staking::{ValidatorStake, DISTRIBUTION_MODULE};
+use crate::{BankSudo, DistributionKeeper, FailingModule, Module, StakeKeeper};
+use cosmwasm_std::{
+    coin, from_json, to_json_binary, Addr, Api, BalanceResponse, BankMsg, BankQuery, Binary,
+    BlockInfo, Coin, CustomMsg, CustomQuery, Decimal, Empty, Event, Querier, QuerierWrapper,
+    Storage, SupplyResponse, Uint128,
+};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Parameters of the mint module, no tokens are minted until they are set.
+const MINT_PARAMS: Item<MintParams> = Item::new("mint_params");
+
+/// State of the mint module, updated at the end of every block.
+const MINTER: Item<Minter> = Item::new("minter");
+
+/// Name of the module account collecting fees and minted tokens.
+const FEE_COLLECTOR: &str = "fee_collector";
+
+/// Default storage namespace for the mint module.
+const NAMESPACE_MINT: &[u8] = b"mint";
+
+/// Path of the gRPC query for parameters of the mint module.
+const QUERY_MINT_PARAMS: &str = "/cosmos.mint.v1beta1.Query/Params";
+
+/// Path of the gRPC query for the current inflation rate.
+const QUERY_INFLATION: &str = "/cosmos.mint.v1beta1.Query/Inflation";
+
+/// Path of the gRPC query for the current annual provisions.
+const QUERY_ANNUAL_PROVISIONS: &str = "/cosmos.mint.v1beta1.Query/AnnualProvisions";
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMintParams {
+    #[prost(string, tag = "1")]
+    pub mint_denom: String,
+    #[prost(string, tag = "2")]
+    pub inflation_rate_change: String,
+    #[prost(string, tag = "3")]
+    pub inflation_max: String,
+    #[prost(string, tag = "4")]
+    pub inflation_min: String,
+    #[prost(string, tag = "5")]
+    pub goal_bonded: String,
+    #[prost(uint64, tag = "6")]
+    pub blocks_per_year: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryMintParamsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub params: Option<ProtoMintParams>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryInflationResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub inflation: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryAnnualProvisionsResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub annual_provisions: Vec<u8>,
+}
+
+/// Handles minting of block provisions within the test environment.
+/// Mint parameters are changed with privileged [MintSudo] actions
+/// and the state of the module is queried with [MintQuery].
+pub trait Mint: Module<ExecT = Empty, QueryT = MintQuery, SudoT = MintSudo> {
+    /// This is called from the end blocker (`update_block` / `set_block`) to mint
+    /// the block provision and allocate it together with fees collected in the block.
+    /// If you're implementing a dummy mint module, this can be a no-op.
+    fn mint_block_provision<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+    ) -> AnyResult<AppResponse> {
+        Ok(AppResponse::default())
+    }
+}
+
+/// This type alias represents a mint module that never mints any tokens
+/// and fails in response to mint queries and privileged actions.
+pub type MintFailingModule = FailingModule<Empty, MintQuery, MintSudo>;
+
+impl Mint for MintFailingModule {}
+
+/// Parameters of the mint module, like `Params` of Cosmos SDK mint module,
+/// together with the parameters of the distribution module allocating minted tokens.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MintParams {
+    /// Maximum annual change of the inflation rate.
+    pub inflation_rate_change: Decimal,
+    /// Maximum inflation rate.
+    pub inflation_max: Decimal,
+    /// Minimum inflation rate.
+    pub inflation_min: Decimal,
+    /// Goal of the fraction of bonded tokens, the inflation rises below it and falls above it.
+    pub goal_bonded: Decimal,
+    /// Expected number of blocks per year.
+    pub blocks_per_year: u64,
+    /// Fraction of allocated tokens sent to the community pool.
+    pub community_tax: Decimal,
+    /// Fraction of allocated tokens rewarded to the proposer of the block,
+    /// like `base_proposer_reward` of the distribution module before Cosmos SDK v0.47.
+    pub proposer_reward: Decimal,
+}
+
+impl Default for MintParams {
+    /// Creates mint parameters with the default values of Cosmos SDK.
+    fn default() -> Self {
+        Self {
+            inflation_rate_change: Decimal::percent(13),
+            inflation_max: Decimal::percent(20),
+            inflation_min: Decimal::percent(7),
+            goal_bonded: Decimal::percent(67),
+            blocks_per_year: 60 * 60 * 8766 / 5,
+            community_tax: Decimal::percent(2),
+            proposer_reward: Decimal::zero(),
+        }
+    }
+}
+
+impl MintParams {
+    /// Returns the inflation rate for the next block, see `NextInflationRate` in Cosmos SDK.
+    fn next_inflation_rate(&self, inflation: Decimal, bonded_ratio: Decimal) -> Decimal {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/mint/types/minter.go#L44-L68
+        let blocks_per_year = Decimal::from_ratio(self.blocks_per_year, 1u128);
+        let inflation = if bonded_ratio < self.goal_bonded {
+            let change = (Decimal::one() - bonded_ratio / self.goal_bonded)
+                * self.inflation_rate_change
+                / blocks_per_year;
+            inflation.saturating_add(change)
+        } else {
+            let change = (bonded_ratio / self.goal_bonded - Decimal::one())
+                * self.inflation_rate_change
+                / blocks_per_year;
+            inflation.saturating_sub(change)
+        };
+        inflation.clamp(self.inflation_min, self.inflation_max)
+    }
+}
+
+/// State of the mint module, like `Minter` in Cosmos SDK.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Minter {
+    /// Current annual inflation rate.
+    pub inflation: Decimal,
+    /// Current annual expected provisions.
+    pub annual_provisions: Decimal,
+}
+
+impl Default for Minter {
+    /// Creates the initial minter with the default inflation rate of Cosmos SDK.
+    fn default() -> Self {
+        Self {
+            inflation: Decimal::percent(13),
+            annual_provisions: Decimal::zero(),
+        }
+    }
+}
+
+/// Privileged actions of the mint module.
+#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub enum MintSudo {
+    /// Replaces parameters of the mint module, like `MsgUpdateParams`.
+    /// Minting starts at the end of the next block when the parameters were not set before.
+    UpdateParams {
+        /// New mint parameters.
+        params: MintParams,
+    },
+}
+
+/// Queries of the mint module, like the `Query` service of Cosmos SDK mint module.
+#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub enum MintQuery {
+    /// Returns [MintParamsResponse].
+    Params,
+    /// Returns [InflationResponse].
+    Inflation,
+    /// Returns [AnnualProvisionsResponse].
+    AnnualProvisions,
+}
+
+/// Response to [MintQuery::Params].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MintParamsResponse {
+    /// Parameters of the mint module, `None` when no tokens are minted.
+    pub params: Option<MintParams>,
+}
+
+/// Response to [MintQuery::Inflation].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct InflationResponse {
+    /// Current annual inflation rate.
+    pub inflation: Decimal,
+}
+
+/// Response to [MintQuery::AnnualProvisions].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AnnualProvisionsResponse {
+    /// Current annual expected provisions.
+    pub annual_provisions: Decimal,
+}
+
+/// A structure representing a default mint keeper.
+#[derive(Clone, Default)]
+pub struct MintKeeper {}
+
+impl MintKeeper {
+    /// Creates a new mint keeper with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets parameters of the mint module. Tokens of the bonded denomination are minted
+    /// at the end of every block only after the parameters are set.
+    pub fn setup(&self, storage: &mut dyn Storage, params: MintParams) -> AnyResult<()> {
+        Self::validate_params(&params)?;
+        let mut mint_storage = prefixed(storage, NAMESPACE_MINT);
+        MINT_PARAMS.save(&mut mint_storage, &params)?;
+        if !MINTER.exists(&mint_storage) {
+            MINTER.save(&mut mint_storage, &Minter::default())?;
+        }
+        Ok(())
+    }
+
+    /// Returns parameters of the mint module, `None` when no tokens are minted.
+    pub fn params(&self, storage: &dyn Storage) -> AnyResult<Option<MintParams>> {
+        let mint_storage = prefixed_read(storage, NAMESPACE_MINT);
+        Ok(MINT_PARAMS.may_load(&mint_storage)?)
+    }
+
+    /// Returns the state of the mint module, with the inflation rate and annual provisions
+    /// calculated at the end of the last block.
+    pub fn minter(&self, storage: &dyn Storage) -> AnyResult<Minter> {
+        let mint_storage = prefixed_read(storage, NAMESPACE_MINT);
+        Ok(MINTER.may_load(&mint_storage)?.unwrap_or_default())
+    }
+
+    fn validate_params(params: &MintParams) -> AnyResult<()> {
+        if params.goal_bonded.is_zero() {
+            bail!("goal bonded must be positive");
+        }
+        if params.blocks_per_year == 0 {
+            bail!("blocks per year must be positive");
+        }
+        if params.inflation_min > params.inflation_max {
+            bail!("max inflation must be greater than or equal to min inflation");
+        }
+        if params.community_tax + params.proposer_reward > Decimal::one() {
+            bail!("community tax and proposer reward cannot exceed one");
+        }
+        Ok(())
+    }
+
+    /// Moves tokens of the bonded denomination collected by the fee collector to the distribution
+    /// module and allocates them to the community pool, the block proposer and bonded validators
+    /// proportionally to their stake.
+    #[allow(clippy::too_many_arguments)]
+    fn allocate_tokens<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        params: &MintParams,
+        fee_collector: Addr,
+        validators: &[ValidatorStake],
+        bonded_denom: &str,
+    ) -> AnyResult<Vec<Event>> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.46.15/x/distribution/keeper/allocation.go#L15-L107
+        let collected: BalanceResponse = from_json(
+            router.query(
+                api,
+                storage,
+                block,
+                BankQuery::Balance {
+                    address: fee_collector.to_string(),
+                    denom: bonded_denom.to_string(),
+                }
+                .into(),
+            )?,
+        )?;
+        let collected = collected.amount.amount;
+        if collected.is_zero() {
+            return Ok(vec![]);
+        }
+        router.execute(
+            api,
+            storage,
+            block,
+            fee_collector,
+            BankMsg::Send {
+                to_address: module_address(api, DISTRIBUTION_MODULE)?.to_string(),
+                amount: vec![coin(collected.u128(), bonded_denom)],
+            }
+            .into(),
+        )?;
+
+        let mut events = vec![];
+        let mut allocated = Decimal::zero();
+        let bonded = validators.iter().map(|v| v.tokens).sum::<Uint128>();
+        if !bonded.is_zero() {
+            let total = Decimal::from_atomics(collected, 0)?;
+            let proposer = StakeKeeper::next_proposer(storage, validators)?;
+            let proposer_reward = total * params.proposer_reward;
+            let mut rewards = vec![(proposer.clone(), proposer_reward)];
+            events.push(
+                Event::new("proposer_reward")
+                    .add_attribute("amount", format!("{}{}", proposer_reward, bonded_denom))
+                    .add_attribute("validator", &proposer),
+            );
+            let remaining =
+                total * (Decimal::one() - params.community_tax - params.proposer_reward);
+            rewards.extend(validators.iter().map(|stake| {
+                (
+                    Addr::unchecked(&stake.address),
+                    remaining * Decimal::from_ratio(stake.tokens, bonded),
+                )
+            }));
+            for (validator, reward) in rewards {
+                let commission = StakeKeeper::allocate_to_validator(storage, &validator, reward)?;
+                allocated += reward;
+                events.push(
+                    Event::new("commission")
+                        .add_attribute("amount", format!("{}{}", commission, bonded_denom))
+                        .add_attribute("validator", &validator),
+                );
+                events.push(
+                    Event::new("rewards")
+                        .add_attribute("amount", format!("{}{}", reward, bonded_denom))
+                        .add_attribute("validator", &validator),
+                );
+            }
+        }
+
+        // the remainder, including the community tax, is sent to the community pool
+        let provisioned = Uint128::new(1).mul_ceil(allocated).min(collected);
+        DistributionKeeper::add_allocated_tokens(
+            storage,
+            Coin::new(collected, bonded_denom),
+            provisioned,
+        )?;
+        Ok(events)
+    }
+}
+
+impl Mint for MintKeeper {
+    /// Mints the block provision to the fee collector and allocates the collected tokens,
+    /// like `BeginBlocker` of the mint and distribution modules in Cosmos SDK.
+    fn mint_block_provision<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+    ) -> AnyResult<AppResponse> {
+        let Some(params) = self.params(storage)? else {
+            return Ok(AppResponse::default());
+        };
+        let mut minter = self.minter(storage)?;
+        let bonded_denom = StakeKeeper::new().staking_info(storage)?.bonded_denom;
+
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/mint/abci.go#L13-L60
+        let supply: SupplyResponse = from_json(
+            router.query(
+                api,
+                storage,
+                block,
+                BankQuery::Supply {
+                    denom: bonded_denom.clone(),
+                }
+                .into(),
+            )?,
+        )?;
+        let supply = supply.amount.amount;
+        let validators = StakeKeeper::validator_set(storage)?;
+        let bonded = validators.iter().map(|v| v.tokens).sum::<Uint128>();
+        let bonded_ratio = if supply.is_zero() {
+            Decimal::zero()
+        } else {
+            Decimal::from_ratio(bonded, supply)
+        };
+        minter.inflation = params.next_inflation_rate(minter.inflation, bonded_ratio);
+        minter.annual_provisions = minter.inflation * Decimal::from_atomics(supply, 0)?;
+        let provision = Uint128::new(1).mul_floor(
+            minter.annual_provisions / Decimal::from_ratio(params.blocks_per_year, 1u128),
+        );
+        MINTER.save(&mut prefixed(storage, NAMESPACE_MINT), &minter)?;
+
+        let fee_collector = module_address(api, FEE_COLLECTOR)?;
+        if !provision.is_zero() {
+            router.sudo(
+                api,
+                storage,
+                block,
+                BankSudo::Mint {
+                    to_address: fee_collector.to_string(),
+                    amount: vec![coin(provision.u128(), &bonded_denom)],
+                }
+                .into(),
+            )?;
+        }
+        let mut events = vec![Event::new("mint")
+            .add_attribute("bonded_ratio", bonded_ratio.to_string())
+            .add_attribute("inflation", minter.inflation.to_string())
+            .add_attribute("annual_provisions", minter.annual_provisions.to_string())
+            .add_attribute("amount", provision.to_string())];
+        events.extend(self.allocate_tokens(
+            api,
+            storage,
+            router,
+            block,
+            &params,
+            fee_collector,
+            &validators,
+            &bonded_denom,
+        )?);
+        Ok(AppResponse { events, data: None })
+    }
+}
+
+impl Module for MintKeeper {
+    type ExecT = Empty;
+    type QueryT = MintQuery;
+    type SudoT = MintSudo;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _sender: Addr,
+        _msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("Something went wrong - Mint doesn't have execute messages")
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: MintQuery,
+    ) -> AnyResult<Binary> {
+        match request {
+            MintQuery::Params => Ok(to_json_binary(&MintParamsResponse {
+                params: self.params(storage)?,
+            })?),
+            MintQuery::Inflation => Ok(to_json_binary(&InflationResponse {
+                inflation: self.minter(storage)?.inflation,
+            })?),
+            MintQuery::AnnualProvisions => Ok(to_json_binary(&AnnualProvisionsResponse {
+                annual_provisions: self.minter(storage)?.annual_provisions,
+            })?),
+        }
+    }
+
+    /// Processes privileged actions of the mint module,
+    /// changed parameters are recorded in the changelog of parameters.
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: MintSudo,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        match msg {
+            MintSudo::UpdateParams { params } => {
+                let old_params = self.params(storage)?;
+                self.setup(storage, params.clone())?;
+                let changes = [
+                    (
+                        "inflation_rate_change",
+                        params.inflation_rate_change.to_string(),
+                        old_params
+                            .as_ref()
+                            .map(|p| p.inflation_rate_change.to_string()),
+                    ),
+                    (
+                        "inflation_max",
+                        params.inflation_max.to_string(),
+                        old_params.as_ref().map(|p| p.inflation_max.to_string()),
+                    ),
+                    (
+                        "inflation_min",
+                        params.inflation_min.to_string(),
+                        old_params.as_ref().map(|p| p.inflation_min.to_string()),
+                    ),
+                    (
+                        "goal_bonded",
+                        params.goal_bonded.to_string(),
+                        old_params.as_ref().map(|p| p.goal_bonded.to_string()),
+                    ),
+                    (
+                        "blocks_per_year",
+                        params.blocks_per_year.to_string(),
+                        old_params.as_ref().map(|p| p.blocks_per_year.to_string()),
+                    ),
+                    (
+                        "community_tax",
+                        params.community_tax.to_string(),
+                        old_params.as_ref().map(|p| p.community_tax.to_string()),
+                    ),
+                    (
+                        "proposer_reward",
+                        params.proposer_reward.to_string(),
+                        old_params.as_ref().map(|p| p.proposer_reward.to_string()),
+                    ),
+                ];
+                for (param, value, old_value) in changes {
+                    if old_value.as_ref() != Some(&value) {
+                        record_param_change(storage, block.height, "mint", param, value)?;
+                    }
+                }
+                Ok(AppResponse::default())
+            }
+        }
+    }
+}
+
+/// Returns `true` when the path is the gRPC query of the mint module.
+pub(crate) fn is_mint_grpc_query(path: &str) -> bool {
+    [QUERY_MINT_PARAMS, QUERY_INFLATION, QUERY_ANNUAL_PROVISIONS].contains(&path)
+}
+
+/// Processes the gRPC query of the mint module with the [MintQuery] of the mint module
+/// used in the application, encoding its response in protobuf.
+pub(crate) fn query_mint_grpc<MintT: Mint>(
+    mint: &MintT,
+    api: &dyn Api,
+    storage: &dyn Storage,
+    querier: &dyn Querier,
+    block: &BlockInfo,
+    path: &str,
+) -> AnyResult<Binary> {
+    let response = match path {
+        QUERY_MINT_PARAMS => {
+            let response: MintParamsResponse =
+                from_json(mint.query(api, storage, querier, block, MintQuery::Params)?)?;
+            let params = match response.params {
+                Some(params) => Some(ProtoMintParams {
+                    mint_denom: QuerierWrapper::<Empty>::new(querier).query_bonded_denom()?,
+                    inflation_rate_change: params.inflation_rate_change.atomics().to_string(),
+                    inflation_max: params.inflation_max.atomics().to_string(),
+                    inflation_min: params.inflation_min.atomics().to_string(),
+                    goal_bonded: params.goal_bonded.atomics().to_string(),
+                    blocks_per_year: params.blocks_per_year,
+                }),
+                None => None,
+            };
+            prost::Message::encode_to_vec(&ProtoQueryMintParamsResponse { params })
+        }
+        QUERY_INFLATION => {
+            let response: InflationResponse =
+                from_json(mint.query(api, storage, querier, block, MintQuery::Inflation)?)?;
+            prost::Message::encode_to_vec(&ProtoQueryInflationResponse {
+                inflation: response.inflation.atomics().to_string().into_bytes(),
+            })
+        }
+        QUERY_ANNUAL_PROVISIONS => {
+            let response: AnnualProvisionsResponse = from_json(mint.query(
+                api,
+                storage,
+                querier,
+                block,
+                MintQuery::AnnualProvisions,
+            )?)?;
+            prost::Message::encode_to_vec(&ProtoQueryAnnualProvisionsResponse {
+                annual_provisions: response
+                    .annual_provisions
+                    .atomics()
+                    .to_string()
+                    .into_bytes(),
+            })
+        }
+        _ => bail!("unsupported mint query: {}", path),
+    };
+    Ok(response.into())
+}
@@ -0,0 +1,208 @@
+//! # Declarative mock contract
+//!
+//! [MockContract] answers incoming messages with canned responses declared in tests,
+//! so dependencies of the tested contract can be stubbed without writing entry-points.
+
+use crate::contracts::Contract;
+use crate::error::{bail, AnyResult};
+use cosmwasm_std::{
+    to_json_binary, Binary, CustomMsg, CustomQuery, Deps, DepsMut, Empty, Env, MessageInfo, Reply,
+    Response,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Canned results paired with patterns of messages they are returned for.
+type Rules<T> = Vec<(Value, Result<T, String>)>;
+
+/// Contract returning canned responses for incoming messages matching declared JSON patterns.
+///
+/// A message matches the pattern when all fields present in the pattern are present in the message
+/// with matching values, so the pattern `{"price":{}}` matches any `price` query, no matter
+/// what arguments it carries. Rules are checked in the order they were declared,
+/// the first matching rule wins. Messages not matching any rule are rejected with an error.
+/// When no `instantiate` rules are declared, every instantiation succeeds with an empty response.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{Empty, Event, Response};
+/// use cw_multi_test::{App, Executor, MockContract};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// #[serde(rename_all = "snake_case")]
+/// enum OracleMsg {
+///     Price {},
+/// }
+///
+/// let oracle = MockContract::new()
+///     .on_query(&OracleMsg::Price {}, &"1.25")
+///     .on_execute(&OracleMsg::Price {}, Response::new().add_event(Event::new("price")));
+///
+/// let mut app = App::default();
+/// let code_id = app.store_code(Box::new(oracle));
+/// let owner = app.api().addr_make("owner");
+/// let oracle_addr = app
+///     .instantiate_contract(code_id, owner, &Empty {}, &[], "oracle", None)
+///     .unwrap();
+///
+/// let price: String = app.wrap().query_wasm_smart(oracle_addr, &OracleMsg::Price {}).unwrap();
+/// assert_eq!("1.25", price);
+/// ```
+pub struct MockContract<C = Empty>
+where
+    C: CustomMsg,
+{
+    instantiate: Rules<Response<C>>,
+    execute: Rules<Response<C>>,
+    query: Rules<Binary>,
+    sudo: Rules<Response<C>>,
+    migrate: Rules<Response<C>>,
+}
+
+impl<C> Default for MockContract<C>
+where
+    C: CustomMsg,
+{
+    fn default() -> Self {
+        Self {
+            instantiate: vec![],
+            execute: vec![],
+            query: vec![],
+            sudo: vec![],
+            migrate: vec![],
+        }
+    }
+}
+
+impl<C> MockContract<C>
+where
+    C: CustomMsg,
+{
+    /// Creates a mock contract without any declared responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the response from `instantiate` entry-point for messages matching the pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the pattern can not be serialized to JSON, this applies to all `on_*` methods.
+    pub fn on_instantiate(mut self, pattern: &impl Serialize, response: Response<C>) -> Self {
+        self.instantiate.push((to_pattern(pattern), Ok(response)));
+        self
+    }
+
+    /// Returns the response from `execute` entry-point for messages matching the pattern.
+    pub fn on_execute(mut self, pattern: &impl Serialize, response: Response<C>) -> Self {
+        self.execute.push((to_pattern(pattern), Ok(response)));
+        self
+    }
+
+    /// Returns the error from `execute` entry-point for messages matching the pattern.
+    pub fn on_execute_error(mut self, pattern: &impl Serialize, error: impl Into<String>) -> Self {
+        self.execute.push((to_pattern(pattern), Err(error.into())));
+        self
+    }
+
+    /// Returns the JSON-serialized response from `query` entry-point for messages matching the pattern.
+    pub fn on_query(mut self, pattern: &impl Serialize, response: &impl Serialize) -> Self {
+        let response = to_json_binary(response).expect("query response should be serializable");
+        self.query.push((to_pattern(pattern), Ok(response)));
+        self
+    }
+
+    /// Returns the error from `query` entry-point for messages matching the pattern.
+    pub fn on_query_error(mut self, pattern: &impl Serialize, error: impl Into<String>) -> Self {
+        self.query.push((to_pattern(pattern), Err(error.into())));
+        self
+    }
+
+    /// Returns the response from `sudo` entry-point for messages matching the pattern.
+    pub fn on_sudo(mut self, pattern: &impl Serialize, response: Response<C>) -> Self {
+        self.sudo.push((to_pattern(pattern), Ok(response)));
+        self
+    }
+
+    /// Returns the response from `migrate` entry-point for messages matching the pattern.
+    pub fn on_migrate(mut self, pattern: &impl Serialize, response: Response<C>) -> Self {
+        self.migrate.push((to_pattern(pattern), Ok(response)));
+        self
+    }
+}
+
+impl<C, Q> Contract<C, Q> for MockContract<C>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    fn execute(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: Env,
+        _info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        respond(&self.execute, "execute", &msg)
+    }
+
+    fn instantiate(
+        &self,
+        _deps: DepsMut<Q>,
+        _env: Env,
+        _info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        if self.instantiate.is_empty() {
+            return Ok(Response::default());
+        }
+        respond(&self.instantiate, "instantiate", &msg)
+    }
+
+    fn query(&self, _deps: Deps<Q>, _env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        respond(&self.query, "query", &msg)
+    }
+
+    fn sudo(&self, _deps: DepsMut<Q>, _env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        respond(&self.sudo, "sudo", &msg)
+    }
+
+    fn reply(&self, _deps: DepsMut<Q>, _env: Env, _msg: Reply) -> AnyResult<Response<C>> {
+        Ok(Response::default())
+    }
+
+    fn migrate(&self, _deps: DepsMut<Q>, _env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        respond(&self.migrate, "migrate", &msg)
+    }
+}
+
+/// Converts the pattern into JSON value.
+pub(crate) fn to_pattern(pattern: &impl Serialize) -> Value {
+    serde_json::to_value(pattern).expect("message pattern should be serializable")
+}
+
+/// Returns the result of the first rule matching the message.
+fn respond<T: Clone>(rules: &Rules<T>, entry_point: &str, msg: &[u8]) -> AnyResult<T> {
+    let value: Value = serde_json::from_slice(msg)?;
+    match rules.iter().find(|(pattern, _)| matches(&value, pattern)) {
+        Some((_, Ok(response))) => Ok(response.clone()),
+        Some((_, Err(error))) => bail!("{}", error),
+        None => bail!(
+            "no mock response for {} message: {}",
+            entry_point,
+            String::from_utf8_lossy(msg)
+        ),
+    }
+}
+
+/// Returns `true` when the value contains all fields of the pattern with matching values.
+pub(crate) fn matches(value: &Value, pattern: &Value) -> bool {
+    match (value, pattern) {
+        (Value::Object(fields), Value::Object(pattern_fields)) => pattern_fields
+            .iter()
+            .all(|(key, pattern)| fields.get(key).is_some_and(|value| matches(value, pattern))),
+        _ => value == pattern,
+    }
+}
@@ -2,6 +2,7 @@ use crate::app::CosmosRouter;
 use crate::error::{bail, AnyResult};
 use crate::AppResponse;
 use cosmwasm_std::{Addr, Api, Binary, BlockInfo, CustomMsg, CustomQuery, Querier, Storage};
+use derivative::Derivative;
 use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 use std::marker::PhantomData;
@@ -67,6 +68,8 @@ pub trait Module {
 ///
 /// This could be a diagnostic or testing tool within the Cosmos ecosystem,
 /// designed to intentionally fail during processing any message, query or privileged action.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
 pub struct FailingModule<ExecT, QueryT, SudoT>(PhantomData<(ExecT, QueryT, SudoT)>);
 
 impl<ExecT, QueryT, SudoT> FailingModule<ExecT, QueryT, SudoT> {
@@ -134,6 +137,8 @@ where
 ///
 /// This struct represents a module in the Cosmos ecosystem designed to
 /// always accept all processed messages, queries and privileged actions.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
 pub struct AcceptingModule<ExecT, QueryT, SudoT>(PhantomData<(ExecT, QueryT, SudoT)>);
 
 impl<ExecT, QueryT, SudoT> AcceptingModule<ExecT, QueryT, SudoT> {
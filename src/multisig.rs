@@ -0,0 +1,127 @@
+//! # Multisig admins
+//!
+//! Admin-gated wasm operations (migrating a contract, updating or clearing its admin) can be
+//! required to be approved by members of a multi-member admin account, like in operational
+//! security setups used on real-life chains. Multisig admin is registered with its members
+//! and approval threshold, approvals are collected using [App](crate::App) helpers before
+//! the operation is executed with the multisig admin as a sender.
+//! Executed operation consumes all collected approvals.
+
+use crate::error::{bail, AnyResult, Error};
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use cosmwasm_std::{to_json_vec, Addr, Storage, WasmMsg};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Registered multisig admins.
+const MULTISIG_ADMINS: Map<&Addr, MultisigAdmin> = Map::new("multisig_admins");
+
+/// Approvals collected for admin operations, indexed by multisig admin and message hash.
+const APPROVALS: Map<(&Addr, &[u8]), Vec<Addr>> = Map::new("multisig_approvals");
+
+/// Default storage namespace for multisig admins.
+const NAMESPACE_MULTISIG: &[u8] = b"multisig";
+
+/// Multi-member admin account.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct MultisigAdmin {
+    /// Addresses of members allowed to approve admin operations.
+    pub members: Vec<Addr>,
+    /// Number of approvals required to execute an admin operation.
+    pub threshold: u64,
+}
+
+/// Registers the multisig admin under specified address.
+pub(crate) fn register_multisig_admin(
+    storage: &mut dyn Storage,
+    address: &Addr,
+    admin: MultisigAdmin,
+) -> AnyResult<()> {
+    let mut members = admin.members.clone();
+    members.sort();
+    members.dedup();
+    if admin.threshold == 0 || admin.threshold > members.len() as u64 {
+        bail!(Error::invalid_multisig_threshold(
+            admin.threshold,
+            members.len()
+        ));
+    }
+    let mut multisig_storage = prefixed(storage, NAMESPACE_MULTISIG);
+    MULTISIG_ADMINS.save(&mut multisig_storage, address, &admin)?;
+    Ok(())
+}
+
+/// Returns the multisig admin registered under specified address.
+pub(crate) fn multisig_admin(
+    storage: &dyn Storage,
+    address: &Addr,
+) -> AnyResult<Option<MultisigAdmin>> {
+    let multisig_storage = prefixed_read(storage, NAMESPACE_MULTISIG);
+    Ok(MULTISIG_ADMINS.may_load(&multisig_storage, address)?)
+}
+
+/// Records the approval of the admin operation by the member of the multisig admin.
+pub(crate) fn approve_admin_msg(
+    storage: &mut dyn Storage,
+    address: &Addr,
+    member: &Addr,
+    msg: &WasmMsg,
+) -> AnyResult<()> {
+    let Some(admin) = multisig_admin(storage, address)? else {
+        bail!(Error::not_multisig_admin(address));
+    };
+    if !admin.members.contains(member) {
+        bail!(Error::not_multisig_member(member));
+    }
+    let key = msg_key(msg)?;
+    let mut multisig_storage = prefixed(storage, NAMESPACE_MULTISIG);
+    let mut approvals = APPROVALS
+        .may_load(&multisig_storage, (address, &key))?
+        .unwrap_or_default();
+    if !approvals.contains(member) {
+        approvals.push(member.clone());
+    }
+    APPROVALS.save(&mut multisig_storage, (address, &key), &approvals)?;
+    Ok(())
+}
+
+/// Returns members of the multisig admin that approved the admin operation.
+pub(crate) fn admin_msg_approvals(
+    storage: &dyn Storage,
+    address: &Addr,
+    msg: &WasmMsg,
+) -> AnyResult<Vec<Addr>> {
+    let key = msg_key(msg)?;
+    let multisig_storage = prefixed_read(storage, NAMESPACE_MULTISIG);
+    Ok(APPROVALS
+        .may_load(&multisig_storage, (address, &key))?
+        .unwrap_or_default())
+}
+
+/// Returns an error when the sender is a multisig admin and the admin operation
+/// was not approved by the required number of members. Consumes collected approvals.
+pub(crate) fn ensure_admin_msg_approved(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    msg: &WasmMsg,
+) -> AnyResult<()> {
+    if let Some(admin) = multisig_admin(storage, sender)? {
+        let approvals = admin_msg_approvals(storage, sender, msg)?;
+        if (approvals.len() as u64) < admin.threshold {
+            bail!(Error::insufficient_approvals(
+                approvals.len(),
+                admin.threshold
+            ));
+        }
+        let key = msg_key(msg)?;
+        APPROVALS.remove(&mut prefixed(storage, NAMESPACE_MULTISIG), (sender, &key));
+    }
+    Ok(())
+}
+
+/// Returns the key identifying the admin operation.
+fn msg_key(msg: &WasmMsg) -> AnyResult<Vec<u8>> {
+    Ok(Sha256::digest(to_json_vec(msg)?).to_vec())
+}
@@ -0,0 +1,76 @@
+//! # Changelog of chain parameters
+//!
+//! Every change of the simulated chain configuration made while the chain is running
+//! is recorded together with the height of the block in which it took effect:
+//!
+//! - staking parameters updated with [StakingSudo::UpdateParams](crate::StakingSudo::UpdateParams),
+//! - transfers of denominations enabled and disabled with
+//!   [BankSudo::SetSendEnabled](crate::BankSudo::SetSendEnabled)
+//!   and [BankSudo::SetParams](crate::BankSudo::SetParams),
+//! - governance parameters updated with [GovSudo::UpdateParams](crate::GovSudo::UpdateParams),
+//! - mint parameters updated with [MintSudo::UpdateParams](crate::MintSudo::UpdateParams),
+//! - modules paused and resumed with [PauseSudo](crate::PauseSudo),
+//! - experimental features enabled and disabled in [App](crate::App).
+//!
+//! The changelog is kept in the application's storage, so changes reverted
+//! with the failed transaction are not recorded. Recorded changes are returned
+//! by [param_history](crate::App::param_history), in the order they were made.
+
+use crate::error::AnyResult;
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use cosmwasm_std::{Order, StdResult, Storage};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Recorded changes, indexed by sequence number.
+const PARAM_CHANGES: Map<u64, ParamChange> = Map::new("param_changes");
+
+/// Default storage namespace for the changelog of parameters.
+const NAMESPACE_PARAM_HISTORY: &[u8] = b"param_history";
+
+/// Single change of the chain parameter.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ParamChange {
+    /// Height of the block in which the change took effect.
+    pub height: u64,
+    /// Name of the module the parameter belongs to, like `staking`.
+    pub module: String,
+    /// Name of the parameter, like `unbonding_time`.
+    pub param: String,
+    /// New value of the parameter, formatted as a string.
+    pub value: String,
+}
+
+/// Records the change of the parameter in the block with specified height.
+pub(crate) fn record_param_change(
+    storage: &mut dyn Storage,
+    height: u64,
+    module: &str,
+    param: &str,
+    value: impl ToString,
+) -> AnyResult<()> {
+    let mut history_storage = prefixed(storage, NAMESPACE_PARAM_HISTORY);
+    let next_seq = PARAM_CHANGES
+        .keys(&history_storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .map_or(0, |seq| seq + 1);
+    let change = ParamChange {
+        height,
+        module: module.to_string(),
+        param: param.to_string(),
+        value: value.to_string(),
+    };
+    PARAM_CHANGES.save(&mut history_storage, next_seq, &change)?;
+    Ok(())
+}
+
+/// Returns all recorded changes, in the order they were made.
+pub(crate) fn param_history(storage: &dyn Storage) -> AnyResult<Vec<ParamChange>> {
+    let history_storage = prefixed_read(storage, NAMESPACE_PARAM_HISTORY);
+    Ok(PARAM_CHANGES
+        .range(&history_storage, None, None, Order::Ascending)
+        .map(|entry| entry.map(|(_, change)| change))
+        .collect::<StdResult<Vec<_>>>()?)
+}
@@ -0,0 +1,170 @@
+//! # Pausing modules
+//!
+//! Modules can be paused (and resumed) using privileged [PauseSudo] actions.
+//! Paused module rejects all messages with an error resembling the one returned
+//! by a real-life chain, while queries and privileged actions are still processed.
+//! Only messages sent by users and contracts are rejected, transfers made by
+//! the modules themselves (like unbonding payouts or deposit refunds) still succeed.
+//! This allows rehearsing the behavior of contracts during chain emergencies,
+//! like halted token transfers.
+
+use crate::bank::MSG_MULTI_SEND;
+use crate::error::{bail, AnyResult, Error};
+use crate::gov::{MSG_CANCEL_PROPOSAL, MSG_DEPOSIT, MSG_SUBMIT_PROPOSAL};
+use crate::param_history::record_param_change;
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use crate::staking::{
+    is_liquid_staking_msg, MSG_BEGIN_REDELEGATE, MSG_CREATE_VALIDATOR, MSG_EDIT_VALIDATOR,
+    MSG_FUND_COMMUNITY_POOL, MSG_UNJAIL, MSG_WITHDRAW_VALIDATOR_COMMISSION,
+};
+use cosmwasm_std::{BankMsg, CosmosMsg, Empty, IbcMsg, Storage};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+
+/// Collection of paused modules.
+const PAUSED_MODULES: Map<&str, Empty> = Map::new("paused_modules");
+
+/// Default storage namespace for paused modules.
+const NAMESPACE_PAUSE: &[u8] = b"pause";
+
+/// Modules that can be paused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub enum PausableModule {
+    /// Bank module, when paused, token transfers are disabled.
+    Bank,
+    /// Wasm module.
+    Wasm,
+    /// Staking module.
+    Staking,
+    /// Distribution module.
+    Distribution,
+    /// IBC module, when paused, fungible token transfers are disabled.
+    Ibc,
+    /// Governance module.
+    Gov,
+    /// Custom module.
+    Custom,
+    /// Stargate handler.
+    Stargate,
+}
+
+impl PausableModule {
+    /// Returns the name of the module.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bank => "bank",
+            Self::Wasm => "wasm",
+            Self::Staking => "staking",
+            Self::Distribution => "distribution",
+            Self::Ibc => "ibc",
+            Self::Gov => "gov",
+            Self::Custom => "custom",
+            Self::Stargate => "stargate",
+        }
+    }
+}
+
+/// A message representing privileged actions for pausing modules.
+#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub enum PauseSudo {
+    /// Pauses the specified module, all messages processed by this module will fail.
+    Pause(PausableModule),
+    /// Resumes processing messages by the specified module.
+    Resume(PausableModule),
+}
+
+/// Processes privileged actions for pausing modules, the change is recorded
+/// in the changelog of parameters at the specified height.
+pub(crate) fn pause_sudo(storage: &mut dyn Storage, height: u64, msg: PauseSudo) -> AnyResult<()> {
+    let mut pause_storage = prefixed(storage, NAMESPACE_PAUSE);
+    let (module, paused) = match msg {
+        PauseSudo::Pause(module) => {
+            PAUSED_MODULES.save(&mut pause_storage, module.name(), &Empty {})?;
+            (module, true)
+        }
+        PauseSudo::Resume(module) => {
+            PAUSED_MODULES.remove(&mut pause_storage, module.name());
+            (module, false)
+        }
+    };
+    record_param_change(storage, height, module.name(), "paused", paused)
+}
+
+/// Returns `true` when the specified module is paused.
+pub(crate) fn is_paused(storage: &dyn Storage, module: PausableModule) -> AnyResult<bool> {
+    let pause_storage = prefixed_read(storage, NAMESPACE_PAUSE);
+    Ok(PAUSED_MODULES.has(&pause_storage, module.name()))
+}
+
+/// Returns an error when the specified module is paused.
+pub(crate) fn ensure_not_paused(storage: &dyn Storage, module: PausableModule) -> AnyResult<()> {
+    if is_paused(storage, module)? {
+        bail!(Error::module_paused(module.name()));
+    }
+    Ok(())
+}
+
+/// Returns an error when the module processing the message sent
+/// by a user or a contract is paused.
+pub(crate) fn ensure_msg_not_paused<T>(storage: &dyn Storage, msg: &CosmosMsg<T>) -> AnyResult<()> {
+    match msg {
+        CosmosMsg::Wasm(_) => ensure_not_paused(storage, PausableModule::Wasm),
+        CosmosMsg::Bank(msg) => ensure_bank_not_paused(storage, msg),
+        CosmosMsg::Custom(_) => ensure_not_paused(storage, PausableModule::Custom),
+        CosmosMsg::Staking(_) => ensure_not_paused(storage, PausableModule::Staking),
+        CosmosMsg::Distribution(_) => ensure_not_paused(storage, PausableModule::Distribution),
+        CosmosMsg::Ibc(msg) => ensure_ibc_not_paused(storage, msg),
+        CosmosMsg::Gov(_) => ensure_not_paused(storage, PausableModule::Gov),
+        #[allow(deprecated)]
+        CosmosMsg::Stargate { type_url, .. } => ensure_type_url_not_paused(storage, type_url),
+        CosmosMsg::Any(msg) => ensure_type_url_not_paused(storage, &msg.type_url),
+        _ => Ok(()),
+    }
+}
+
+/// Returns an error when the module processing the message with specified type URL is paused.
+fn ensure_type_url_not_paused(storage: &dyn Storage, type_url: &str) -> AnyResult<()> {
+    let module = match type_url {
+        MSG_MULTI_SEND => return ensure_multi_send_not_paused(storage),
+        MSG_SUBMIT_PROPOSAL | MSG_DEPOSIT | MSG_CANCEL_PROPOSAL => PausableModule::Gov,
+        MSG_WITHDRAW_VALIDATOR_COMMISSION | MSG_FUND_COMMUNITY_POOL => PausableModule::Distribution,
+        MSG_CREATE_VALIDATOR | MSG_EDIT_VALIDATOR | MSG_UNJAIL | MSG_BEGIN_REDELEGATE => {
+            PausableModule::Staking
+        }
+        _ if is_liquid_staking_msg(type_url) => PausableModule::Staking,
+        _ => PausableModule::Stargate,
+    };
+    ensure_not_paused(storage, module)
+}
+
+/// Returns an error when the bank module is paused.
+/// Token transfers report the same error as the bank module on a real chain.
+fn ensure_bank_not_paused(storage: &dyn Storage, msg: &BankMsg) -> AnyResult<()> {
+    if is_paused(storage, PausableModule::Bank)? {
+        if matches!(msg, BankMsg::Send { .. }) {
+            bail!(Error::SendDisabled);
+        }
+        bail!(Error::module_paused(PausableModule::Bank.name()));
+    }
+    Ok(())
+}
+
+/// Returns an error when the bank module is paused and `MsgMultiSend` can not be processed.
+fn ensure_multi_send_not_paused(storage: &dyn Storage) -> AnyResult<()> {
+    if is_paused(storage, PausableModule::Bank)? {
+        bail!(Error::SendDisabled);
+    }
+    Ok(())
+}
+
+/// Returns an error when the IBC module is paused.
+/// Fungible token transfers report the same error as the ICS-20 module on a real chain.
+fn ensure_ibc_not_paused(storage: &dyn Storage, msg: &IbcMsg) -> AnyResult<()> {
+    if is_paused(storage, PausableModule::Ibc)? {
+        if matches!(msg, IbcMsg::Transfer { .. }) {
+            bail!(Error::IbcTransfersDisabled);
+        }
+        bail!(Error::module_paused(PausableModule::Ibc.name()));
+    }
+    Ok(())
+}
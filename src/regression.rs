@@ -0,0 +1,126 @@
+//! # Regression tests generated from recorded scenarios
+//!
+//! Exploratory simulations can be turned into committed regression tests:
+//! with tracing enabled, [export_regression_test](crate::App::export_regression_test)
+//! converts messages executed directly by the [App](crate::App) (top-level entries
+//! of the [ExecutionTrace]) into Rust code replaying them with the crate's APIs.
+//! Every replayed message is asserted to succeed or to fail with the recorded error,
+//! and the final balances of all accounts taking part in the scenario are asserted at the end.
+//!
+//! The generated function takes the application prepared the same way as the recorded one,
+//! with the same contract codes stored and the same accounts funded, so that instantiated
+//! contracts get the same addresses.
+
+use crate::trace::ExecutionTrace;
+use cosmwasm_std::{Addr, Coin};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+/// Returns the Rust code of the function replaying top-level messages of the trace
+/// and asserting final balances of accounts.
+pub(crate) fn to_rust_test(
+    trace: &ExecutionTrace,
+    name: &str,
+    balances: &[(Addr, Vec<Coin>)],
+) -> String {
+    let mut names = BTreeMap::new();
+    let steps = trace
+        .entries()
+        .iter()
+        .filter(|entry| entry.depth == 0)
+        .collect::<Vec<_>>();
+    let has_coins = balances.iter().any(|(_, coins)| !coins.is_empty());
+    let std_imports = [
+        (has_coins, "coin"),
+        (!steps.is_empty(), "from_json"),
+        (true, "Addr"),
+        (!steps.is_empty(), "CosmosMsg"),
+    ]
+    .into_iter()
+    .filter_map(|(used, import)| used.then_some(import))
+    .join(", ");
+    let mut lines = vec![
+        format!("use cosmwasm_std::{{{}}};", std_imports),
+        if steps.is_empty() {
+            "use cw_multi_test::App;".to_string()
+        } else {
+            "use cw_multi_test::{App, Executor};".to_string()
+        },
+        String::new(),
+        "/// Replays the recorded scenario, generated by `App::export_regression_test`."
+            .to_string(),
+        "/// The application must be prepared the same way as the recorded one.".to_string(),
+        "#[allow(unused_variables)]".to_string(),
+        format!("pub fn {}(app: &mut App) {{", name),
+    ];
+    let actors = steps
+        .iter()
+        .map(|entry| entry.sender.as_str())
+        .chain(balances.iter().map(|(addr, _)| addr.as_str()))
+        .unique()
+        .collect::<Vec<_>>();
+    for (index, actor) in actors.into_iter().enumerate() {
+        let variable = format!("addr_{}", index);
+        lines.push(format!(
+            "    let {} = Addr::unchecked({:?});",
+            variable, actor
+        ));
+        names.insert(actor, variable);
+    }
+    for (step, entry) in steps.iter().enumerate() {
+        lines.push(String::new());
+        lines.push(format!(
+            "    // step {}: {}",
+            step + 1,
+            entry.details.replace('\n', " ")
+        ));
+        lines.push(format!(
+            "    let msg: CosmosMsg = from_json({}).unwrap();",
+            raw_string_literal(&entry.msg)
+        ));
+        let sender = &names[entry.sender.as_str()];
+        match &entry.error {
+            None => lines.push(format!(
+                "    app.execute({}.clone(), msg).unwrap();",
+                sender
+            )),
+            Some(error) => {
+                lines.push(format!(
+                    "    let err = app.execute({}.clone(), msg).unwrap_err();",
+                    sender
+                ));
+                lines.push(format!(
+                    "    assert_eq!({:?}, err.root_cause().to_string());",
+                    error
+                ));
+            }
+        }
+    }
+    if !balances.is_empty() {
+        lines.push(String::new());
+        lines.push("    // final balances".to_string());
+    }
+    for (addr, coins) in balances {
+        let coins = coins
+            .iter()
+            .map(|c| format!("coin({}, {:?})", c.amount, c.denom))
+            .join(", ");
+        lines.push(format!(
+            "    assert_eq!(app.wrap().query_all_balances(&{}).unwrap(), vec![{}]);",
+            names[addr.as_str()],
+            coins
+        ));
+    }
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// Returns the raw string literal with the text, using as many `#` delimiters as needed,
+/// so JSON messages remain readable.
+fn raw_string_literal(text: &str) -> String {
+    let mut hashes = String::from("#");
+    while text.contains(&format!("\"{}", hashes)) {
+        hashes.push('#');
+    }
+    format!("r{hashes}\"{text}\"{hashes}")
+}
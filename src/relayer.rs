@@ -0,0 +1,426 @@
+//! # Relayer between two chains
+//!
+//! [Relayer] connects two chains simulated by separate [App] instances, like a relayer process
+//! (Hermes, Go relayer) connects real chains. Creating the relayer creates clients and
+//! the connection between both chains, channels between contracts bound to IBC ports are opened
+//! with [open_channel](Relayer::open_channel), which runs the channel handshake on both chains.
+//! Packets sent by contracts over channels of the connection are queued by the
+//! [IbcLocalhost](crate::IbcLocalhost) module of the sending chain, and delivered to
+//! the counterparty chain when [relay_packets](Relayer::relay_packets) is called,
+//! which also closes counterparty ends of closed channels.
+//!
+//! Both chains must be passed to the relayer's methods in the same order,
+//! the chain passed first is called _the chain_, the other one is _the counterparty chain_.
+
+use crate::error::AnyResult;
+use crate::ibc_channels::{closed_channels, create_connection};
+use crate::ibc_localhost::{
+    acknowledge_packet, error_acknowledgement, is_timed_out, pending_packet, receive_packet,
+    timeout_packet,
+};
+use crate::transactions::transactional;
+use crate::{
+    App, AppResponse, Bank, ChannelEnd, ChannelState, Distribution, Gov, Ibc, Mint, Module,
+    Staking, Stargate, Wasm,
+};
+use cosmwasm_std::{
+    Api, BlockInfo, CustomMsg, CustomQuery, IbcAcknowledgement, IbcChannel, IbcEndpoint, IbcOrder,
+    IbcPacket, Storage,
+};
+use serde::de::DeserializeOwned;
+
+/// Chain connected by the [Relayer], implemented by [App].
+///
+/// Each method is executed in a separate transaction on the chain.
+pub trait IbcChain {
+    /// Returns the information about the current block of the chain.
+    fn block_info(&self) -> BlockInfo;
+
+    /// Creates the client tracking the counterparty chain and the connection using it,
+    /// returns identifiers of the client and the connection.
+    fn create_connection(&mut self) -> AnyResult<(String, String)>;
+
+    /// Initializes the channel end bound to specified port, see [App::channel_open_init].
+    fn channel_open_init(
+        &mut self,
+        port_id: &str,
+        counterparty_port_id: &str,
+        connection_id: &str,
+        version: &str,
+        order: IbcOrder,
+    ) -> AnyResult<IbcChannel>;
+
+    /// Tries to open the channel end bound to specified port, see [App::channel_open_try].
+    fn channel_open_try(
+        &mut self,
+        port_id: &str,
+        counterparty: IbcEndpoint,
+        connection_id: &str,
+        counterparty_version: &str,
+        order: IbcOrder,
+    ) -> AnyResult<IbcChannel>;
+
+    /// Opens the initialized channel end, see [App::channel_open_ack].
+    fn channel_open_ack(
+        &mut self,
+        channel_id: &str,
+        counterparty_channel_id: &str,
+        counterparty_version: &str,
+    ) -> AnyResult<()>;
+
+    /// Opens the channel end in `TryOpen` state, see [App::channel_open_confirm].
+    fn channel_open_confirm(&mut self, channel_id: &str) -> AnyResult<()>;
+
+    /// Closes the channel end after the counterparty end was closed,
+    /// see [App::channel_close_confirm].
+    fn channel_close_confirm(&mut self, channel_id: &str) -> AnyResult<()>;
+
+    /// Returns the channel end with specified identifier, see [App::channel_end].
+    fn channel_end(&self, channel_id: &str) -> AnyResult<Option<ChannelEnd>>;
+
+    /// Returns channels closed on the chain over the connection with specified identifier.
+    fn closed_channels(&self, connection_id: &str) -> AnyResult<Vec<IbcChannel>>;
+
+    /// Returns the oldest packet sent over the connection with specified identifier,
+    /// waiting to be relayed.
+    fn pending_packet(&self, connection_id: &str) -> AnyResult<Option<IbcPacket>>;
+
+    /// Delivers the packet sent by the counterparty chain to the contract bound to
+    /// the destination port. Returns the response of the contract and the acknowledgement,
+    /// which is `None` when it will be written asynchronously.
+    fn receive_packet(
+        &mut self,
+        packet: &IbcPacket,
+    ) -> AnyResult<(AppResponse, Option<IbcAcknowledgement>)>;
+
+    /// Delivers the acknowledgement of the pending packet to the sending contract,
+    /// the packet is no longer pending.
+    fn acknowledge_packet(
+        &mut self,
+        packet: &IbcPacket,
+        acknowledgement: Option<IbcAcknowledgement>,
+    ) -> AnyResult<AppResponse>;
+
+    /// Returns the pending packet that timed out on the counterparty chain
+    /// to the sending contract, the packet is no longer pending.
+    /// The ordered channel of the packet is closed.
+    fn timeout_packet(&mut self, packet: &IbcPacket) -> AnyResult<AppResponse>;
+}
+
+impl<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT> IbcChain
+    for App<BankT, ApiT, StorageT, CustomT, WasmT, StakingT, DistrT, IbcT, GovT, StargateT, MintT>
+where
+    BankT: Bank,
+    ApiT: Api,
+    StorageT: Storage,
+    CustomT: Module,
+    WasmT: Wasm<CustomT::ExecT, CustomT::QueryT>,
+    StakingT: Staking,
+    DistrT: Distribution,
+    IbcT: Ibc,
+    GovT: Gov,
+    StargateT: Stargate,
+    MintT: Mint,
+    CustomT::ExecT: CustomMsg + DeserializeOwned + 'static,
+    CustomT::QueryT: CustomQuery + DeserializeOwned + 'static,
+{
+    fn block_info(&self) -> BlockInfo {
+        self.block.clone()
+    }
+
+    fn create_connection(&mut self) -> AnyResult<(String, String)> {
+        transactional(&mut self.storage, |write_cache, _| {
+            create_connection(write_cache)
+        })
+    }
+
+    fn channel_open_init(
+        &mut self,
+        port_id: &str,
+        counterparty_port_id: &str,
+        connection_id: &str,
+        version: &str,
+        order: IbcOrder,
+    ) -> AnyResult<IbcChannel> {
+        App::channel_open_init(
+            self,
+            port_id,
+            counterparty_port_id,
+            connection_id,
+            version,
+            order,
+        )
+    }
+
+    fn channel_open_try(
+        &mut self,
+        port_id: &str,
+        counterparty: IbcEndpoint,
+        connection_id: &str,
+        counterparty_version: &str,
+        order: IbcOrder,
+    ) -> AnyResult<IbcChannel> {
+        App::channel_open_try(
+            self,
+            port_id,
+            counterparty,
+            connection_id,
+            counterparty_version,
+            order,
+        )
+    }
+
+    fn channel_open_ack(
+        &mut self,
+        channel_id: &str,
+        counterparty_channel_id: &str,
+        counterparty_version: &str,
+    ) -> AnyResult<()> {
+        App::channel_open_ack(
+            self,
+            channel_id,
+            counterparty_channel_id,
+            counterparty_version,
+        )
+    }
+
+    fn channel_open_confirm(&mut self, channel_id: &str) -> AnyResult<()> {
+        App::channel_open_confirm(self, channel_id)
+    }
+
+    fn channel_close_confirm(&mut self, channel_id: &str) -> AnyResult<()> {
+        App::channel_close_confirm(self, channel_id)
+    }
+
+    fn channel_end(&self, channel_id: &str) -> AnyResult<Option<ChannelEnd>> {
+        App::channel_end(self, channel_id)
+    }
+
+    fn closed_channels(&self, connection_id: &str) -> AnyResult<Vec<IbcChannel>> {
+        closed_channels(&self.storage, connection_id)
+    }
+
+    fn pending_packet(&self, connection_id: &str) -> AnyResult<Option<IbcPacket>> {
+        pending_packet(&self.storage, connection_id)
+    }
+
+    fn receive_packet(
+        &mut self,
+        packet: &IbcPacket,
+    ) -> AnyResult<(AppResponse, Option<IbcAcknowledgement>)> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            receive_packet(&*api, write_cache, router, &router.wasm, block, packet)
+        })
+    }
+
+    fn acknowledge_packet(
+        &mut self,
+        packet: &IbcPacket,
+        acknowledgement: Option<IbcAcknowledgement>,
+    ) -> AnyResult<AppResponse> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            acknowledge_packet(
+                &*api,
+                write_cache,
+                router,
+                &router.wasm,
+                block,
+                packet,
+                acknowledgement,
+            )
+        })
+    }
+
+    fn timeout_packet(&mut self, packet: &IbcPacket) -> AnyResult<AppResponse> {
+        let Self {
+            block,
+            router,
+            api,
+            storage,
+        } = self;
+
+        transactional(&mut *storage, |write_cache, _| {
+            timeout_packet(&*api, write_cache, router, &router.wasm, block, packet)
+        })
+    }
+}
+
+/// Relayer connecting two chains simulated by separate [App] instances.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Relayer {
+    /// Identifier of the client tracking the counterparty chain on the chain.
+    client_id: String,
+    /// Identifier of the connection on the chain.
+    connection_id: String,
+    /// Identifier of the client tracking the chain on the counterparty chain.
+    counterparty_client_id: String,
+    /// Identifier of the connection on the counterparty chain.
+    counterparty_connection_id: String,
+}
+
+impl Relayer {
+    /// Creates clients and the connection between the chain and the counterparty chain.
+    pub fn new(chain: &mut impl IbcChain, counterparty: &mut impl IbcChain) -> AnyResult<Self> {
+        let (client_id, connection_id) = chain.create_connection()?;
+        let (counterparty_client_id, counterparty_connection_id) =
+            counterparty.create_connection()?;
+        Ok(Self {
+            client_id,
+            connection_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        })
+    }
+
+    /// Returns the identifier of the client tracking the counterparty chain on the chain.
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Returns the identifier of the connection on the chain.
+    pub fn connection_id(&self) -> &str {
+        &self.connection_id
+    }
+
+    /// Returns the identifier of the client tracking the chain on the counterparty chain.
+    pub fn counterparty_client_id(&self) -> &str {
+        &self.counterparty_client_id
+    }
+
+    /// Returns the identifier of the connection on the counterparty chain.
+    pub fn counterparty_connection_id(&self) -> &str {
+        &self.counterparty_connection_id
+    }
+
+    /// Opens the channel between the contract bound to `port_id` on the chain
+    /// and the contract bound to `counterparty_port_id` on the counterparty chain,
+    /// running the whole channel handshake initialized by the chain.
+    /// Returns identifiers of the channel ends on the chain and on the counterparty chain.
+    pub fn open_channel(
+        &self,
+        chain: &mut impl IbcChain,
+        counterparty: &mut impl IbcChain,
+        port_id: &str,
+        counterparty_port_id: &str,
+        version: &str,
+        order: IbcOrder,
+    ) -> AnyResult<(String, String)> {
+        let channel = chain.channel_open_init(
+            port_id,
+            counterparty_port_id,
+            &self.connection_id,
+            version,
+            order.clone(),
+        )?;
+        let counterparty_channel = counterparty.channel_open_try(
+            counterparty_port_id,
+            channel.endpoint.clone(),
+            &self.counterparty_connection_id,
+            &channel.version,
+            order,
+        )?;
+        let channel_id = channel.endpoint.channel_id;
+        let counterparty_channel_id = counterparty_channel.endpoint.channel_id;
+        chain.channel_open_ack(
+            &channel_id,
+            &counterparty_channel_id,
+            &counterparty_channel.version,
+        )?;
+        counterparty.channel_open_confirm(&counterparty_channel_id)?;
+        Ok((channel_id, counterparty_channel_id))
+    }
+
+    /// Relays all packets sent over the connection in both directions, including packets
+    /// sent while relaying, and returns responses of relayed packets in order.
+    ///
+    /// Packets are received by the destination contract and acknowledgements are relayed back
+    /// to the sending contract. Packets that timed out at the current block of the destination
+    /// chain are returned to the sending contract instead. Counterparty ends of closed channels
+    /// are closed before relaying packets, the remaining packets sent over closed channels
+    /// are returned to the sending contract. When receiving a packet fails, changes on
+    /// the destination chain are discarded and the error is acknowledged to the sending contract.
+    pub fn relay_packets(
+        &self,
+        chain: &mut impl IbcChain,
+        counterparty: &mut impl IbcChain,
+    ) -> AnyResult<Vec<AppResponse>> {
+        let mut responses = vec![];
+        loop {
+            relay_channel_closes(chain, counterparty, &self.connection_id)?;
+            relay_channel_closes(counterparty, chain, &self.counterparty_connection_id)?;
+            if let Some(response) = relay_packet(chain, counterparty, &self.connection_id)? {
+                responses.push(response);
+            } else if let Some(response) =
+                relay_packet(counterparty, chain, &self.counterparty_connection_id)?
+            {
+                responses.push(response);
+            } else {
+                return Ok(responses);
+            }
+        }
+    }
+}
+
+/// Relays the oldest packet sent from the source chain over the connection,
+/// returns `None` when there are no pending packets. Packets that timed out, or were sent
+/// over the channel closed in the meantime, are returned to the source chain.
+/// Packets the destination chain fails to receive are acknowledged with the error.
+fn relay_packet(
+    source: &mut impl IbcChain,
+    destination: &mut impl IbcChain,
+    connection_id: &str,
+) -> AnyResult<Option<AppResponse>> {
+    let Some(packet) = source.pending_packet(connection_id)? else {
+        return Ok(None);
+    };
+    if is_channel_closed(source, &packet.src.channel_id)?
+        || is_timed_out(&packet.timeout, &destination.block_info())
+    {
+        return source.timeout_packet(&packet).map(Some);
+    }
+    // a packet failed on the destination chain is acknowledged with the error,
+    // so it does not block packets sent after it
+    let (mut response, acknowledgement) = match destination.receive_packet(&packet) {
+        Ok(received) => received,
+        Err(err) => (AppResponse::default(), Some(error_acknowledgement(&err)?)),
+    };
+    let ack_response = source.acknowledge_packet(&packet, acknowledgement)?;
+    response.events.extend(ack_response.events);
+    Ok(Some(response))
+}
+
+/// Closes counterparty ends of channels closed on the source chain over the connection.
+fn relay_channel_closes(
+    source: &mut impl IbcChain,
+    destination: &mut impl IbcChain,
+    connection_id: &str,
+) -> AnyResult<()> {
+    for channel in source.closed_channels(connection_id)? {
+        let counterparty_channel_id = &channel.counterparty_endpoint.channel_id;
+        if !is_channel_closed(destination, counterparty_channel_id)? {
+            destination.channel_close_confirm(counterparty_channel_id)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns `true` when the channel end with specified identifier is closed on the chain.
+fn is_channel_closed(chain: &impl IbcChain, channel_id: &str) -> AnyResult<bool> {
+    Ok(chain
+        .channel_end(channel_id)?
+        .is_some_and(|channel_end| channel_end.state == ChannelState::Closed))
+}
@@ -0,0 +1,313 @@
+//! # Query-only RPC facade
+//!
+//! [RpcFacade] exposes a small subset of the REST endpoints served by a real-life node,
+//! so frontend end-to-end tests can be run against the [App](crate::App) instead of a devnet.
+//! Only queries are supported, the served endpoints are:
+//!
+//! - `GET /cosmwasm/wasm/v1/contract/{address}/smart/{query_data}`,
+//!   where `query_data` is a base64-encoded JSON query message,
+//! - `GET /cosmos/bank/v1beta1/balances/{address}`,
+//! - `GET /cosmos/bank/v1beta1/balances/{address}/by_denom?denom={denom}`.
+//!
+//! The facade is resource-limited by [RpcLimits], oversized requests and responses are rejected.
+//! Available only when the `rpc` feature is enabled.
+
+use crate::error::AnyResult;
+use cosmwasm_std::{
+    to_json_string, to_json_vec, Binary, Coin, ContractResult, Empty, Querier, QuerierWrapper,
+    QueryRequest, SystemResult, WasmQuery,
+};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+/// Limits of resources used by the [RpcFacade].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcLimits {
+    /// Maximum size of the request head (request line and headers) in bytes.
+    pub max_request_size: usize,
+    /// Maximum size of the response body in bytes.
+    pub max_response_size: usize,
+}
+
+impl Default for RpcLimits {
+    /// Creates default limits, 8 KiB for requests and 1 MiB for responses.
+    fn default() -> Self {
+        Self {
+            max_request_size: 8 * 1024,
+            max_response_size: 1024 * 1024,
+        }
+    }
+}
+
+/// Response returned by the [RpcFacade].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// JSON body.
+    pub body: String,
+}
+
+impl RpcResponse {
+    /// Creates a successful response with specified JSON body.
+    fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// Creates an error response in the format used by the gRPC gateway.
+    fn error(status: u16, code: u32, message: impl Into<String>) -> Self {
+        #[derive(Serialize)]
+        struct ErrorBody {
+            code: u32,
+            message: String,
+            details: Vec<Empty>,
+        }
+        let body = ErrorBody {
+            code,
+            message: message.into(),
+            details: vec![],
+        };
+        Self {
+            status,
+            body: to_json_string(&body).unwrap_or_default(),
+        }
+    }
+
+    /// Creates a response for invalid requests.
+    fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::error(400, 3, message)
+    }
+
+    /// Creates a response for failed queries.
+    fn unknown(message: impl Into<String>) -> Self {
+        Self::error(500, 2, message)
+    }
+
+    /// Creates a response for unsupported endpoints.
+    fn not_found() -> Self {
+        Self::error(404, 5, "Not Found")
+    }
+}
+
+/// Query-only facade over the blockchain [Querier], like [App](crate::App).
+///
+/// # Example
+///
+/// ```ignore
+/// use cw_multi_test::{App, RpcFacade};
+/// use std::net::TcpListener;
+///
+/// let app = App::default();
+/// let listener = TcpListener::bind("127.0.0.1:1317").unwrap();
+/// // serve 10 requests sent by the frontend
+/// RpcFacade::new(&app).serve(&listener, 10).unwrap();
+/// ```
+pub struct RpcFacade<'a> {
+    querier: &'a dyn Querier,
+    limits: RpcLimits,
+}
+
+impl<'a> RpcFacade<'a> {
+    /// Creates a facade over specified querier with default limits.
+    pub fn new(querier: &'a dyn Querier) -> Self {
+        Self {
+            querier,
+            limits: RpcLimits::default(),
+        }
+    }
+
+    /// Sets the resource limits.
+    pub fn with_limits(mut self, limits: RpcLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Handles a single request with specified HTTP method and target (path with query string).
+    pub fn handle(&self, method: &str, target: &str) -> RpcResponse {
+        if method != "GET" {
+            return RpcResponse::error(405, 12, "Method Not Allowed");
+        }
+        let (path, query_string) = target.split_once('?').unwrap_or((target, ""));
+        let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+        let response = match segments.as_slice() {
+            ["cosmwasm", "wasm", "v1", "contract", address, "smart", query_data] => {
+                self.smart_query(address, query_data)
+            }
+            ["cosmos", "bank", "v1beta1", "balances", address] => self.all_balances(address),
+            ["cosmos", "bank", "v1beta1", "balances", address, "by_denom"] => {
+                match query_param(query_string, "denom") {
+                    Some(denom) => self.balance(address, &denom),
+                    None => RpcResponse::invalid_argument("invalid denom"),
+                }
+            }
+            _ => RpcResponse::not_found(),
+        };
+        if response.body.len() > self.limits.max_response_size {
+            return RpcResponse::unknown(format!(
+                "response size exceeds the limit of {} bytes",
+                self.limits.max_response_size
+            ));
+        }
+        response
+    }
+
+    /// Serves specified number of HTTP requests received by the listener.
+    /// Each request is processed in a separate connection.
+    pub fn serve(&self, listener: &TcpListener, max_requests: usize) -> AnyResult<()> {
+        for stream in listener.incoming().take(max_requests) {
+            self.serve_connection(stream?)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a single HTTP request from the stream and writes back the response.
+    fn serve_connection(&self, stream: TcpStream) -> AnyResult<()> {
+        let mut reader =
+            BufReader::new(stream.try_clone()?).take(self.limits.max_request_size as u64);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // skip headers, the request must fit within the limit
+        let mut complete = false;
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            if line == "\r\n" || line == "\n" {
+                complete = true;
+                break;
+            }
+            line.clear();
+        }
+        let response = match request_line
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .as_slice()
+        {
+            _ if !complete => RpcResponse::error(
+                431,
+                8,
+                format!(
+                    "request size exceeds the limit of {} bytes",
+                    self.limits.max_request_size
+                ),
+            ),
+            [method, target, _] => self.handle(method, target),
+            _ => RpcResponse::invalid_argument("malformed request"),
+        };
+        write_response(stream, &response)
+    }
+
+    /// Processes the smart query of the contract.
+    fn smart_query(&self, address: &str, query_data: &str) -> RpcResponse {
+        let Ok(msg) = Binary::from_base64(&percent_decode(query_data)) else {
+            return RpcResponse::invalid_argument("invalid query data");
+        };
+        let request = QueryRequest::<Empty>::Wasm(WasmQuery::Smart {
+            contract_addr: address.to_string(),
+            msg,
+        });
+        let Ok(raw_request) = to_json_vec(&request) else {
+            return RpcResponse::invalid_argument("invalid query request");
+        };
+        match self.querier.raw_query(&raw_request) {
+            SystemResult::Ok(ContractResult::Ok(data)) => match String::from_utf8(data.to_vec()) {
+                Ok(data) => RpcResponse::ok(format!("{{\"data\":{}}}", data)),
+                Err(_) => RpcResponse::unknown("query response is not valid JSON"),
+            },
+            SystemResult::Ok(ContractResult::Err(error)) => RpcResponse::unknown(error),
+            SystemResult::Err(error) => RpcResponse::invalid_argument(error.to_string()),
+        }
+    }
+
+    /// Processes the query for all balances of the account.
+    fn all_balances(&self, address: &str) -> RpcResponse {
+        #[derive(Serialize)]
+        struct Pagination {
+            next_key: Option<String>,
+            total: String,
+        }
+        #[derive(Serialize)]
+        struct AllBalances {
+            balances: Vec<Coin>,
+            pagination: Pagination,
+        }
+        match QuerierWrapper::<Empty>::new(self.querier).query_all_balances(address) {
+            Ok(balances) => RpcResponse::ok(
+                to_json_string(&AllBalances {
+                    pagination: Pagination {
+                        next_key: None,
+                        total: balances.len().to_string(),
+                    },
+                    balances,
+                })
+                .unwrap_or_default(),
+            ),
+            Err(error) => RpcResponse::invalid_argument(error.to_string()),
+        }
+    }
+
+    /// Processes the query for the balance of the account in specified denomination.
+    fn balance(&self, address: &str, denom: &str) -> RpcResponse {
+        #[derive(Serialize)]
+        struct Balance {
+            balance: Coin,
+        }
+        match QuerierWrapper::<Empty>::new(self.querier).query_balance(address, denom) {
+            Ok(balance) => {
+                RpcResponse::ok(to_json_string(&Balance { balance }).unwrap_or_default())
+            }
+            Err(error) => RpcResponse::invalid_argument(error.to_string()),
+        }
+    }
+}
+
+/// Writes the HTTP response to the stream.
+fn write_response(mut stream: TcpStream, response: &RpcResponse) -> AnyResult<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Returns the decoded value of the parameter from the query string.
+fn query_param(query_string: &str, name: &str) -> Option<String> {
+    query_string
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(value))
+        .filter(|value| !value.is_empty())
+}
+
+/// Decodes percent-encoded characters.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
@@ -0,0 +1,524 @@
+//! # Schema-validating contracts
+//!
+//! When a test sends a malformed message, contracts fail with generic `serde` parse errors,
+//! like `Error parsing into type my_contract::msg::ExecuteMsg: Invalid type`, which do not tell
+//! which field is wrong. [SchemaValidated] wraps any [Contract] and validates incoming
+//! messages against the contract's JSON schemas (generated with `schemars`) before they
+//! are dispatched, reporting errors like ``field `transfer.amount` expected string, got number``.
+//!
+//! Supported is the subset of JSON Schema generated by `schemars` for contract messages:
+//! types, properties, required and additional properties, enumerations, references
+//! to definitions, array items, numeric minimums and `oneOf`, `anyOf` and `allOf` subschemas.
+
+use crate::contracts::Contract;
+use crate::error::{anyhow, AnyResult};
+use crate::ibc_callbacks::{IbcDestinationCallbackMsg, IbcSourceCallbackMsg};
+use cosmwasm_std::{
+    Binary, CustomMsg, CustomQuery, Deps, DepsMut, Empty, Env, IbcBasicResponse,
+    IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, MessageInfo,
+    Reply, Response,
+};
+use itertools::Itertools;
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+use std::fmt;
+
+/// Violation of the schema found in the validated message.
+#[derive(Debug)]
+enum Violation {
+    /// Value has an unexpected type.
+    Type(Vec<&'static str>, &'static str),
+    /// Required field is missing.
+    Missing(String),
+    /// Field is not allowed.
+    Unknown(String),
+    /// Value is not one of allowed values.
+    NotAllowed(Vec<String>, String),
+    /// Number is lower than the allowed minimum.
+    Minimum(f64, String),
+    /// Value does not match any of alternative subschemas.
+    NoVariant(Vec<String>),
+    /// Referenced definition does not exist in the schema.
+    Reference(String),
+}
+
+/// Error of the message validation, with the path to the invalid field.
+#[derive(Debug)]
+struct ValidationError {
+    path: Vec<String>,
+    violation: Violation,
+}
+
+impl ValidationError {
+    fn new(path: &[String], violation: Violation) -> Self {
+        Self {
+            path: path.to_vec(),
+            violation,
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path.join(".");
+        let subject = if path.is_empty() {
+            "message".to_string()
+        } else {
+            format!("field `{}`", path)
+        };
+        let field = |name: &str| {
+            if path.is_empty() {
+                format!("`{}`", name)
+            } else {
+                format!("`{}.{}`", path, name)
+            }
+        };
+        match &self.violation {
+            Violation::Type(expected, actual) => {
+                write!(
+                    f,
+                    "{} expected {}, got {}",
+                    subject,
+                    expected.join(" or "),
+                    actual
+                )
+            }
+            Violation::Missing(name) => write!(f, "missing field {}", field(name)),
+            Violation::Unknown(name) => write!(f, "unknown field {}", field(name)),
+            Violation::NotAllowed(allowed, actual) => write!(
+                f,
+                "{} expected one of {}, got {}",
+                subject,
+                allowed.join(", "),
+                actual
+            ),
+            Violation::Minimum(minimum, actual) => {
+                write!(
+                    f,
+                    "{} expected at least {}, got {}",
+                    subject, minimum, actual
+                )
+            }
+            Violation::NoVariant(variants) if variants.is_empty() => {
+                write!(f, "{} does not match any variant", subject)
+            }
+            Violation::NoVariant(variants) => write!(
+                f,
+                "{} does not match any variant, expected one of {}",
+                subject,
+                variants.iter().map(|v| format!("`{}`", v)).join(", ")
+            ),
+            Violation::Reference(reference) => {
+                write!(f, "{} refers to unknown definition {}", subject, reference)
+            }
+        }
+    }
+}
+
+/// Returns the name of the JSON type of the value.
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Returns the name of the JSON Schema instance type.
+fn instance_type_name(instance_type: &InstanceType) -> &'static str {
+    match instance_type {
+        InstanceType::Null => "null",
+        InstanceType::Boolean => "boolean",
+        InstanceType::Object => "object",
+        InstanceType::Array => "array",
+        InstanceType::Number => "number",
+        InstanceType::String => "string",
+        InstanceType::Integer => "integer",
+    }
+}
+
+/// Returns `true` when the value is an instance of the type.
+fn is_instance_of(value: &Value, instance_type: &InstanceType) -> bool {
+    match (instance_type, value) {
+        (InstanceType::Null, Value::Null)
+        | (InstanceType::Boolean, Value::Bool(_))
+        | (InstanceType::Object, Value::Object(_))
+        | (InstanceType::Array, Value::Array(_))
+        | (InstanceType::Number, Value::Number(_))
+        | (InstanceType::String, Value::String(_)) => true,
+        (InstanceType::Integer, Value::Number(number)) => !number.is_f64(),
+        _ => false,
+    }
+}
+
+/// Validator of JSON values against the root schema and its definitions.
+struct Validator<'a> {
+    root: &'a RootSchema,
+}
+
+impl Validator<'_> {
+    fn validate(
+        &self,
+        schema: &Schema,
+        value: &Value,
+        path: &mut Vec<String>,
+    ) -> Result<(), ValidationError> {
+        match schema {
+            Schema::Bool(true) => Ok(()),
+            Schema::Bool(false) => Err(ValidationError::new(path, Violation::NoVariant(vec![]))),
+            Schema::Object(object) => self.validate_object(object, value, path),
+        }
+    }
+
+    fn validate_object(
+        &self,
+        schema: &SchemaObject,
+        value: &Value,
+        path: &mut Vec<String>,
+    ) -> Result<(), ValidationError> {
+        if let Some(reference) = &schema.reference {
+            let definition = reference
+                .strip_prefix("#/definitions/")
+                .and_then(|name| self.root.definitions.get(name))
+                .ok_or_else(|| {
+                    ValidationError::new(path, Violation::Reference(reference.clone()))
+                })?;
+            self.validate(definition, value, path)?;
+        }
+        if let Some(instance_type) = &schema.instance_type {
+            let expected = match instance_type {
+                SingleOrVec::Single(instance_type) => vec![instance_type.as_ref()],
+                SingleOrVec::Vec(instance_types) => instance_types.iter().collect(),
+            };
+            if !expected.iter().any(|t| is_instance_of(value, t)) {
+                let expected = expected.into_iter().map(instance_type_name).collect();
+                return Err(ValidationError::new(
+                    path,
+                    Violation::Type(expected, type_name(value)),
+                ));
+            }
+        }
+        if let Some(allowed) = &schema.enum_values {
+            if !allowed.contains(value) {
+                let allowed = allowed.iter().map(Value::to_string).collect();
+                return Err(ValidationError::new(
+                    path,
+                    Violation::NotAllowed(allowed, value.to_string()),
+                ));
+            }
+        }
+        if let (Some(number), Some(actual)) = (&schema.number, value.as_f64()) {
+            if let Some(minimum) = number.minimum {
+                if actual < minimum {
+                    return Err(ValidationError::new(
+                        path,
+                        Violation::Minimum(minimum, value.to_string()),
+                    ));
+                }
+            }
+        }
+        if let (Some(object), Value::Object(fields)) = (&schema.object, value) {
+            if let Some(name) = object
+                .required
+                .iter()
+                .find(|name| !fields.contains_key(*name))
+            {
+                return Err(ValidationError::new(path, Violation::Missing(name.clone())));
+            }
+            for (name, field) in fields {
+                let field_schema = match object.properties.get(name) {
+                    Some(field_schema) => field_schema,
+                    None => match object.additional_properties.as_deref() {
+                        Some(Schema::Bool(false)) => {
+                            return Err(ValidationError::new(
+                                path,
+                                Violation::Unknown(name.clone()),
+                            ));
+                        }
+                        Some(field_schema) => field_schema,
+                        None => continue,
+                    },
+                };
+                path.push(name.clone());
+                self.validate(field_schema, field, path)?;
+                path.pop();
+            }
+        }
+        if let (Some(array), Value::Array(items)) = (&schema.array, value) {
+            for (index, item) in items.iter().enumerate() {
+                let item_schema = match &array.items {
+                    Some(SingleOrVec::Single(item_schema)) => Some(item_schema.as_ref()),
+                    Some(SingleOrVec::Vec(item_schemas)) => item_schemas.get(index),
+                    None => None,
+                };
+                if let Some(item_schema) = item_schema {
+                    path.push(index.to_string());
+                    self.validate(item_schema, item, path)?;
+                    path.pop();
+                }
+            }
+        }
+        if let Some(subschemas) = &schema.subschemas {
+            for subschema in subschemas.all_of.iter().flatten() {
+                self.validate(subschema, value, path)?;
+            }
+            for alternatives in [&subschemas.one_of, &subschemas.any_of]
+                .into_iter()
+                .flatten()
+            {
+                self.validate_alternatives(alternatives, value, path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the value against alternative subschemas, like variants of an enumeration.
+    /// When no alternative matches, reports the error of the variant matching the value
+    /// most closely, i.e. the one failing at the deepest field.
+    fn validate_alternatives(
+        &self,
+        alternatives: &[Schema],
+        value: &Value,
+        path: &[String],
+    ) -> Result<(), ValidationError> {
+        let mut errors = vec![];
+        for alternative in alternatives {
+            match self.validate(alternative, value, &mut path.to_vec()) {
+                Ok(()) => return Ok(()),
+                Err(err) => errors.push(err),
+            }
+        }
+        let deepest = errors
+            .into_iter()
+            .filter(|err| err.path.len() > path.len())
+            .max_by_key(|err| err.path.len());
+        match deepest {
+            Some(err) => Err(err),
+            None => {
+                let variants = alternatives
+                    .iter()
+                    .flat_map(|alternative| self.variant_names(alternative))
+                    .collect();
+                Err(ValidationError::new(path, Violation::NoVariant(variants)))
+            }
+        }
+    }
+
+    /// Returns names of enumeration variants described by the subschema: required fields
+    /// of externally tagged variants and allowed values of unit variants.
+    fn variant_names(&self, schema: &Schema) -> Vec<String> {
+        let Schema::Object(object) = schema else {
+            return vec![];
+        };
+        let mut names = vec![];
+        if let Some(object) = &object.object {
+            if object.required.len() == 1 {
+                names.extend(object.required.iter().cloned());
+            }
+        }
+        for value in object.enum_values.iter().flatten() {
+            if let Value::String(name) = value {
+                names.push(name.clone());
+            }
+        }
+        names
+    }
+}
+
+/// Validates the message against the schema, returns the error describing
+/// the first violation.
+fn validate_msg(entry_point: &str, schema: &RootSchema, msg: &[u8]) -> AnyResult<()> {
+    let value = serde_json::from_slice::<Value>(msg)
+        .map_err(|err| anyhow!("invalid {} message: {}", entry_point, err))?;
+    let validator = Validator { root: schema };
+    validator
+        .validate_object(&schema.schema, &value, &mut vec![])
+        .map_err(|err| anyhow!("invalid {} message: {}", entry_point, err))
+}
+
+/// Contract validating messages received by the wrapped contract against JSON schemas,
+/// before they are passed to the wrapped contract. Messages of entry-points without
+/// a schema are not validated.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{Empty, Response, Uint128};
+/// use cw_multi_test::{App, Executor, MockContract, SchemaValidated};
+/// use schemars::{schema_for, JsonSchema};
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, JsonSchema)]
+/// #[serde(rename_all = "snake_case")]
+/// enum ExecuteMsg {
+///     Transfer { amount: Uint128 },
+/// }
+///
+/// let contract = SchemaValidated::new(Box::new(
+///     MockContract::new().on_execute(&Empty {}, Response::default()),
+/// ))
+/// .with_execute_schema(schema_for!(ExecuteMsg));
+///
+/// let mut app = App::default();
+/// let code_id = app.store_code(Box::new(contract));
+/// let owner = app.api().addr_make("owner");
+/// let contract_addr = app
+///     .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "validated", None)
+///     .unwrap();
+///
+/// let msg = serde_json::json!({ "transfer": { "amount": 100 } });
+/// let err = app.execute_contract(owner, contract_addr, &msg, &[]).unwrap_err();
+/// assert_eq!(
+///     "invalid execute message: field `transfer.amount` expected string, got number",
+///     err.root_cause().to_string()
+/// );
+/// ```
+pub struct SchemaValidated<C = Empty, Q = Empty>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    contract: Box<dyn Contract<C, Q>>,
+    instantiate_schema: Option<RootSchema>,
+    execute_schema: Option<RootSchema>,
+    query_schema: Option<RootSchema>,
+    sudo_schema: Option<RootSchema>,
+    migrate_schema: Option<RootSchema>,
+}
+
+impl<C, Q> SchemaValidated<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    /// Creates a contract wrapping the specified contract, without any schemas.
+    pub fn new(contract: Box<dyn Contract<C, Q>>) -> Self {
+        Self {
+            contract,
+            instantiate_schema: None,
+            execute_schema: None,
+            query_schema: None,
+            sudo_schema: None,
+            migrate_schema: None,
+        }
+    }
+
+    /// Validates messages passed to `instantiate` entry-point against the schema.
+    pub fn with_instantiate_schema(mut self, schema: RootSchema) -> Self {
+        self.instantiate_schema = Some(schema);
+        self
+    }
+
+    /// Validates messages passed to `execute` entry-point against the schema.
+    pub fn with_execute_schema(mut self, schema: RootSchema) -> Self {
+        self.execute_schema = Some(schema);
+        self
+    }
+
+    /// Validates messages passed to `query` entry-point against the schema.
+    pub fn with_query_schema(mut self, schema: RootSchema) -> Self {
+        self.query_schema = Some(schema);
+        self
+    }
+
+    /// Validates messages passed to `sudo` entry-point against the schema.
+    pub fn with_sudo_schema(mut self, schema: RootSchema) -> Self {
+        self.sudo_schema = Some(schema);
+        self
+    }
+
+    /// Validates messages passed to `migrate` entry-point against the schema.
+    pub fn with_migrate_schema(mut self, schema: RootSchema) -> Self {
+        self.migrate_schema = Some(schema);
+        self
+    }
+
+    /// Validates the message when the schema of the entry-point is set.
+    fn validate(
+        &self,
+        entry_point: &str,
+        schema: &Option<RootSchema>,
+        msg: &[u8],
+    ) -> AnyResult<()> {
+        match schema {
+            Some(schema) => validate_msg(entry_point, schema, msg),
+            None => Ok(()),
+        }
+    }
+}
+
+#[rustfmt::skip]
+impl<C, Q> Contract<C, Q> for SchemaValidated<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    fn execute(&self, deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.validate("execute", &self.execute_schema, &msg)?;
+        self.contract.execute(deps, env, info, msg)
+    }
+
+    fn instantiate(&self, deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.validate("instantiate", &self.instantiate_schema, &msg)?;
+        self.contract.instantiate(deps, env, info, msg)
+    }
+
+    fn query(&self, deps: Deps<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        self.validate("query", &self.query_schema, &msg)?;
+        self.contract.query(deps, env, msg)
+    }
+
+    fn sudo(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.validate("sudo", &self.sudo_schema, &msg)?;
+        self.contract.sudo(deps, env, msg)
+    }
+
+    fn reply(&self, deps: DepsMut<Q>, env: Env, msg: Reply) -> AnyResult<Response<C>> {
+        self.contract.reply(deps, env, msg)
+    }
+
+    fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.validate("migrate", &self.migrate_schema, &msg)?;
+        self.contract.migrate(deps, env, msg)
+    }
+
+    fn ibc_enabled(&self) -> bool {
+        self.contract.ibc_enabled()
+    }
+
+    fn ibc_channel_open(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelOpenMsg) -> AnyResult<IbcChannelOpenResponse> {
+        self.contract.ibc_channel_open(deps, env, msg)
+    }
+
+    fn ibc_channel_connect(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelConnectMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_channel_connect(deps, env, msg)
+    }
+
+    fn ibc_channel_close(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelCloseMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_channel_close(deps, env, msg)
+    }
+
+    fn ibc_packet_receive(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketReceiveMsg) -> AnyResult<IbcReceiveResponse<C>> {
+        self.contract.ibc_packet_receive(deps, env, msg)
+    }
+
+    fn ibc_packet_ack(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketAckMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_packet_ack(deps, env, msg)
+    }
+
+    fn ibc_packet_timeout(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketTimeoutMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_packet_timeout(deps, env, msg)
+    }
+
+    fn ibc_source_callback(&self, deps: DepsMut<Q>, env: Env, msg: IbcSourceCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_source_callback(deps, env, msg)
+    }
+
+    fn ibc_destination_callback(&self, deps: DepsMut<Q>, env: Env, msg: IbcDestinationCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_destination_callback(deps, env, msg)
+    }
+}
@@ -0,0 +1,314 @@
+//! # Shadow execution
+//!
+//! [Shadowed] runs two implementations of the same logical contract side by side,
+//! e.g. the original code and its refactored version. The primary contract is called
+//! as usual and its results are returned to the [App](crate::App), while the shadow contract
+//! receives every message against a copy of the contract state taken before the call.
+//! Divergences in results, responses (messages, attributes, events, data) and storage
+//! are recorded in a [DivergenceLog], turning any test suite into a differential test.
+
+use crate::contracts::Contract;
+use crate::error::AnyResult;
+use crate::ibc_callbacks::{IbcDestinationCallbackMsg, IbcSourceCallbackMsg};
+use cosmwasm_std::{
+    Addr, Binary, CustomMsg, CustomQuery, Deps, DepsMut, Empty, Env, IbcBasicResponse,
+    IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, MemoryStorage,
+    MessageInfo, Order, Reply, Response, Storage,
+};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// Divergence between the primary and the shadow contract, detected in a single call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Name of the called entry-point, like `execute` or `query`.
+    pub entry_point: &'static str,
+    /// Address of the called contract.
+    pub contract_addr: Addr,
+    /// Human-readable descriptions of all detected differences.
+    pub differences: Vec<String>,
+}
+
+/// Log of divergences detected by the [Shadowed] contract, shared with the test.
+#[derive(Clone, Debug, Default)]
+pub struct DivergenceLog(Rc<RefCell<Vec<Divergence>>>);
+
+impl DivergenceLog {
+    /// Returns all divergences detected so far, in the order of calls.
+    pub fn divergences(&self) -> Vec<Divergence> {
+        self.0.borrow().clone()
+    }
+
+    /// Returns `true` when no divergences were detected.
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// Removes all recorded divergences.
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+
+    /// Asserts that no divergences were detected, panics with all detected differences otherwise.
+    pub fn assert_no_divergences(&self) {
+        let divergences = self.0.borrow();
+        if divergences.is_empty() {
+            return;
+        }
+        let report = divergences
+            .iter()
+            .map(|divergence| {
+                format!(
+                    "{} of {}:\n  {}",
+                    divergence.entry_point,
+                    divergence.contract_addr,
+                    divergence.differences.join("\n  ")
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        panic!(
+            "shadow contract diverged from the primary contract:\n{}",
+            report
+        );
+    }
+
+    fn record(&self, entry_point: &'static str, env: &Env, differences: Vec<String>) {
+        if !differences.is_empty() {
+            self.0.borrow_mut().push(Divergence {
+                entry_point,
+                contract_addr: env.contract.address.clone(),
+                differences,
+            });
+        }
+    }
+}
+
+/// Contract calling the primary contract and comparing its behaviour
+/// with the shadow contract, for messages passed to `instantiate`, `execute`,
+/// `query`, `sudo`, `reply` and `migrate` entry-points.
+/// IBC entry-points are called only on the primary contract.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{Empty, Response};
+/// use cw_multi_test::{App, Executor, MockContract, Shadowed};
+///
+/// let shadowed = Shadowed::new(
+///     Box::new(MockContract::new().on_execute(&Empty {}, Response::new().add_attribute("v", "1"))),
+///     Box::new(MockContract::new().on_execute(&Empty {}, Response::new().add_attribute("v", "2"))),
+/// );
+/// let log = shadowed.log();
+///
+/// let mut app = App::default();
+/// let code_id = app.store_code(Box::new(shadowed));
+/// let owner = app.api().addr_make("owner");
+/// let contract_addr = app
+///     .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "shadowed", None)
+///     .unwrap();
+/// app.execute_contract(owner, contract_addr, &Empty {}, &[])
+///     .unwrap();
+///
+/// let divergences = log.divergences();
+/// assert_eq!(1, divergences.len());
+/// assert_eq!("execute", divergences[0].entry_point);
+/// ```
+pub struct Shadowed<C = Empty, Q = Empty>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    primary: Box<dyn Contract<C, Q>>,
+    shadow: Box<dyn Contract<C, Q>>,
+    log: DivergenceLog,
+}
+
+impl<C, Q> Shadowed<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    /// Creates a contract calling the primary contract and comparing it with the shadow contract.
+    pub fn new(primary: Box<dyn Contract<C, Q>>, shadow: Box<dyn Contract<C, Q>>) -> Self {
+        Self {
+            primary,
+            shadow,
+            log: DivergenceLog::default(),
+        }
+    }
+
+    /// Returns the handle to the log of detected divergences.
+    pub fn log(&self) -> DivergenceLog {
+        self.log.clone()
+    }
+
+    /// Calls both contracts with mutable access to the storage, the shadow contract
+    /// gets the copy of the storage, and records the divergences.
+    fn compare_mut<T: PartialEq + Debug>(
+        &self,
+        entry_point: &'static str,
+        deps: DepsMut<Q>,
+        env: &Env,
+        call: impl Fn(&dyn Contract<C, Q>, DepsMut<Q>) -> AnyResult<T>,
+    ) -> AnyResult<T> {
+        let mut shadow_storage = snapshot(deps.storage);
+        let shadow_deps = DepsMut {
+            storage: &mut shadow_storage,
+            api: deps.api,
+            querier: deps.querier,
+        };
+        let shadow_result = call(self.shadow.as_ref(), shadow_deps);
+        let DepsMut {
+            storage,
+            api,
+            querier,
+        } = deps;
+        let primary_result = call(
+            self.primary.as_ref(),
+            DepsMut {
+                storage: &mut *storage,
+                api,
+                querier,
+            },
+        );
+        let mut differences = compare_results(&primary_result, &shadow_result);
+        if primary_result.is_ok() && shadow_result.is_ok() {
+            differences.extend(compare_storages(storage, &shadow_storage));
+        }
+        self.log.record(entry_point, env, differences);
+        primary_result
+    }
+}
+
+/// Copies all records from the contract storage.
+fn snapshot(storage: &dyn Storage) -> MemoryStorage {
+    let mut snapshot = MemoryStorage::new();
+    for (key, value) in storage.range(None, None, Order::Ascending) {
+        snapshot.set(&key, &value);
+    }
+    snapshot
+}
+
+/// Describes differences between results returned from the primary and the shadow contract.
+fn compare_results<T: PartialEq + Debug>(
+    primary: &AnyResult<T>,
+    shadow: &AnyResult<T>,
+) -> Vec<String> {
+    match (primary, shadow) {
+        (Ok(primary), Ok(shadow)) if primary != shadow => vec![format!(
+            "responses differ: primary {:?}, shadow {:?}",
+            primary, shadow
+        )],
+        (Ok(_), Err(err)) => vec![format!("only shadow failed: {}", err)],
+        (Err(err), Ok(_)) => vec![format!("only primary failed: {}", err)],
+        (Err(primary), Err(shadow)) if primary.to_string() != shadow.to_string() => {
+            vec![format!(
+                "errors differ: primary {}, shadow {}",
+                primary, shadow
+            )]
+        }
+        _ => vec![],
+    }
+}
+
+/// Describes differences between storages modified by the primary and the shadow contract.
+fn compare_storages(primary: &dyn Storage, shadow: &dyn Storage) -> Vec<String> {
+    let records = |storage: &dyn Storage| -> BTreeMap<Vec<u8>, Vec<u8>> {
+        storage.range(None, None, Order::Ascending).collect()
+    };
+    let escape = |value: Option<&Vec<u8>>| {
+        value.map_or("(none)".to_string(), |value| {
+            value.escape_ascii().to_string()
+        })
+    };
+    let primary = records(primary);
+    let shadow = records(shadow);
+    let mut keys = primary.keys().chain(shadow.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| primary.get(*key) != shadow.get(*key))
+        .map(|key| {
+            format!(
+                "storage differs at key `{}`: primary {}, shadow {}",
+                key.escape_ascii(),
+                escape(primary.get(key)),
+                escape(shadow.get(key))
+            )
+        })
+        .collect()
+}
+
+#[rustfmt::skip]
+impl<C, Q> Contract<C, Q> for Shadowed<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    fn execute(&self, deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.compare_mut("execute", deps, &env, |contract, deps| contract.execute(deps, env.clone(), info.clone(), msg.clone()))
+    }
+
+    fn instantiate(&self, deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.compare_mut("instantiate", deps, &env, |contract, deps| contract.instantiate(deps, env.clone(), info.clone(), msg.clone()))
+    }
+
+    fn query(&self, deps: Deps<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        let shadow_result = self.shadow.query(deps, env.clone(), msg.clone());
+        let primary_result = self.primary.query(deps, env.clone(), msg);
+        self.log.record("query", &env, compare_results(&primary_result, &shadow_result));
+        primary_result
+    }
+
+    fn sudo(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.compare_mut("sudo", deps, &env, |contract, deps| contract.sudo(deps, env.clone(), msg.clone()))
+    }
+
+    fn reply(&self, deps: DepsMut<Q>, env: Env, msg: Reply) -> AnyResult<Response<C>> {
+        self.compare_mut("reply", deps, &env, |contract, deps| contract.reply(deps, env.clone(), msg.clone()))
+    }
+
+    fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.compare_mut("migrate", deps, &env, |contract, deps| contract.migrate(deps, env.clone(), msg.clone()))
+    }
+
+    fn ibc_enabled(&self) -> bool {
+        self.primary.ibc_enabled()
+    }
+
+    fn ibc_channel_open(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelOpenMsg) -> AnyResult<IbcChannelOpenResponse> {
+        self.primary.ibc_channel_open(deps, env, msg)
+    }
+
+    fn ibc_channel_connect(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelConnectMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.primary.ibc_channel_connect(deps, env, msg)
+    }
+
+    fn ibc_channel_close(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelCloseMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.primary.ibc_channel_close(deps, env, msg)
+    }
+
+    fn ibc_packet_receive(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketReceiveMsg) -> AnyResult<IbcReceiveResponse<C>> {
+        self.primary.ibc_packet_receive(deps, env, msg)
+    }
+
+    fn ibc_packet_ack(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketAckMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.primary.ibc_packet_ack(deps, env, msg)
+    }
+
+    fn ibc_packet_timeout(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketTimeoutMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.primary.ibc_packet_timeout(deps, env, msg)
+    }
+
+    fn ibc_source_callback(&self, deps: DepsMut<Q>, env: Env, msg: IbcSourceCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.primary.ibc_source_callback(deps, env, msg)
+    }
+
+    fn ibc_destination_callback(&self, deps: DepsMut<Q>, env: Env, msg: IbcDestinationCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.primary.ibc_destination_callback(deps, env, msg)
+    }
+}
@@ -0,0 +1,108 @@
+//! # Block snapshots
+//!
+//! When enabled in [App](crate::App), the state of the chain is recorded every time
+//! the block is advanced with [update_block](crate::App::update_block)
+//! or [set_block](crate::App::set_block). Recorded snapshots allow to rewind the state
+//! and the height with [rollback_blocks](crate::App::rollback_blocks), simulating short
+//! chain reorganizations observed by off-chain components on optimistic-execution chains.
+//!
+//! Only the state kept in the storage is rewound, contract codes stored in the meantime
+//! remain available.
+
+use crate::error::{bail, AnyResult};
+use cosmwasm_std::{BlockInfo, Order, Record, Storage};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+/// Snapshot of the chain state, taken when the block was committed.
+#[derive(Clone)]
+struct Snapshot {
+    block: BlockInfo,
+    records: Vec<Record>,
+}
+
+/// Recorded snapshots, the most recent last.
+#[derive(Clone, Default)]
+struct SnapshotsState {
+    max_depth: usize,
+    snapshots: VecDeque<Snapshot>,
+}
+
+/// Records snapshots of the chain state for every committed block, when enabled.
+#[derive(Clone, Default)]
+pub(crate) struct BlockSnapshots(RefCell<Option<SnapshotsState>>);
+
+impl BlockSnapshots {
+    /// Starts recording snapshots of at most `max_depth` recent blocks,
+    /// previously recorded snapshots are discarded.
+    pub fn enable(&self, max_depth: usize) {
+        *self.0.borrow_mut() = Some(SnapshotsState {
+            max_depth,
+            snapshots: VecDeque::new(),
+        });
+    }
+
+    /// Stops recording snapshots, previously recorded snapshots are discarded.
+    pub fn disable(&self) {
+        *self.0.borrow_mut() = None;
+    }
+
+    /// Returns the number of recorded snapshots.
+    pub fn len(&self) -> usize {
+        self.0
+            .borrow()
+            .as_ref()
+            .map_or(0, |state| state.snapshots.len())
+    }
+
+    /// Records the state of the committed block, the oldest snapshot is discarded
+    /// when the maximum depth is reached.
+    pub fn take(&self, storage: &dyn Storage, block: &BlockInfo) {
+        let mut state = self.0.borrow_mut();
+        let Some(state) = state.as_mut() else {
+            return;
+        };
+        if state.max_depth == 0 {
+            return;
+        }
+        if state.snapshots.len() == state.max_depth {
+            state.snapshots.pop_front();
+        }
+        state.snapshots.push_back(Snapshot {
+            block: block.clone(),
+            records: storage.range(None, None, Order::Ascending).collect(),
+        });
+    }
+
+    /// Restores the storage to the state of the block committed `count` blocks ago,
+    /// returns the restored block.
+    pub fn rollback(&self, storage: &mut dyn Storage, count: usize) -> AnyResult<BlockInfo> {
+        let mut state = self.0.borrow_mut();
+        let Some(state) = state.as_mut() else {
+            bail!("block snapshots are not enabled");
+        };
+        if count == 0 || count > state.snapshots.len() {
+            bail!(
+                "cannot rollback {} blocks, {} block snapshots available",
+                count,
+                state.snapshots.len()
+            );
+        }
+        let snapshot = state
+            .snapshots
+            .drain(state.snapshots.len() - count..)
+            .next()
+            .unwrap();
+        let keys = storage
+            .range(None, None, Order::Ascending)
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        for key in keys {
+            storage.remove(&key);
+        }
+        for (key, value) in snapshot.records {
+            storage.set(&key, &value);
+        }
+        Ok(snapshot.block)
+    }
+}
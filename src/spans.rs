@@ -0,0 +1,63 @@
+//! # Entry-point tracing spans
+//!
+//! With the `tracing` feature enabled, every call to contract's `instantiate`, `execute`,
+//! `query`, `sudo`, `reply` and `migrate` entry-point is wrapped in a [tracing](https://docs.rs/tracing)
+//! span named `entry_point`, with the contract address, the entry-point name, the size
+//! of the message and the result of the call. Failing tests can then be debugged
+//! by installing any `tracing` subscriber (like `tracing-subscriber` filtered with `RUST_LOG`),
+//! instead of adding `println!` calls to contracts.
+//!
+//! Without the `tracing` feature, spans compile to nothing.
+
+use crate::error::AnyResult;
+use cosmwasm_std::Addr;
+
+/// Span covering a single call to contract's entry-point.
+pub(crate) struct EntryPointSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+}
+
+impl EntryPointSpan {
+    /// Enters the span of the call to the contract's entry-point.
+    /// The size of the message is not known for `reply` entry-point.
+    pub fn enter(entry_point: &'static str, contract: &Addr, msg_size: Option<usize>) -> Self {
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::debug_span!(
+                "entry_point",
+                entry_point,
+                contract = %contract,
+                msg_size = tracing::field::Empty,
+                result = tracing::field::Empty,
+            );
+            if let Some(msg_size) = msg_size {
+                span.record("msg_size", msg_size);
+            }
+            Self {
+                span: span.entered(),
+            }
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = (entry_point, contract, msg_size);
+            Self {}
+        }
+    }
+
+    /// Records the result of the call and exits the span.
+    pub fn exit<T>(self, result: AnyResult<T>) -> AnyResult<T> {
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(_) => {
+                self.span.record("result", "ok");
+                tracing::debug!(result = "ok", "entry-point call succeeded");
+            }
+            Err(err) => {
+                self.span.record("result", tracing::field::display(err));
+                tracing::debug!(error = %err, "entry-point call failed");
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,196 @@
+//! # Spying contracts
+//!
+//! [Spying] wraps any [Contract] and records all calls it receives into a [SpyLog],
+//! so tests can assert which messages were sent to the contract, by whom and with what funds.
+
+use crate::contracts::Contract;
+use crate::error::AnyResult;
+use crate::ibc_callbacks::{IbcDestinationCallbackMsg, IbcSourceCallbackMsg};
+use cosmwasm_std::{
+    Addr, Binary, Coin, CustomMsg, CustomQuery, Deps, DepsMut, Empty, Env, IbcBasicResponse,
+    IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, MessageInfo,
+    Reply, Response,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Call received by the spied contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpiedCall {
+    /// Name of the called entry-point, like `execute` or `query`.
+    pub entry_point: &'static str,
+    /// Address of the called contract.
+    pub contract_addr: Addr,
+    /// Sender of the message, available for `instantiate` and `execute` entry-points.
+    pub sender: Option<Addr>,
+    /// Funds sent with the message, available for `instantiate` and `execute` entry-points.
+    pub funds: Vec<Coin>,
+    /// Raw message passed to the entry-point.
+    pub msg: Binary,
+}
+
+/// Log of calls received by the spied contract, shared between the test and the [Spying] contract.
+#[derive(Clone, Debug, Default)]
+pub struct SpyLog(Rc<RefCell<Vec<SpiedCall>>>);
+
+impl SpyLog {
+    /// Returns all calls recorded so far, in the order they were received.
+    pub fn calls(&self) -> Vec<SpiedCall> {
+        self.0.borrow().clone()
+    }
+
+    /// Returns recorded calls of the specified entry-point.
+    pub fn calls_to(&self, entry_point: &str) -> Vec<SpiedCall> {
+        self.0
+            .borrow()
+            .iter()
+            .filter(|call| call.entry_point == entry_point)
+            .cloned()
+            .collect()
+    }
+
+    /// Removes all recorded calls.
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+
+    fn record(&self, entry_point: &'static str, env: &Env, info: Option<&MessageInfo>, msg: &[u8]) {
+        self.0.borrow_mut().push(SpiedCall {
+            entry_point,
+            contract_addr: env.contract.address.clone(),
+            sender: info.map(|info| info.sender.clone()),
+            funds: info.map(|info| info.funds.clone()).unwrap_or_default(),
+            msg: msg.into(),
+        });
+    }
+}
+
+/// Contract recording all calls with messages (`instantiate`, `execute`, `query`, `sudo`
+/// and `migrate`) received by the wrapped contract into the [SpyLog].
+/// Calls are recorded before they are passed to the wrapped contract, so failed calls
+/// are recorded too, even when their state changes are reverted.
+///
+/// # Example
+///
+/// ```
+/// use cosmwasm_std::{Empty, Response};
+/// use cw_multi_test::{App, Executor, MockContract, Spying};
+///
+/// let spying = Spying::new(Box::new(
+///     MockContract::new().on_execute(&Empty {}, Response::default()),
+/// ));
+/// let log = spying.log();
+///
+/// let mut app = App::default();
+/// let code_id = app.store_code(Box::new(spying));
+/// let owner = app.api().addr_make("owner");
+/// let contract_addr = app
+///     .instantiate_contract(code_id, owner.clone(), &Empty {}, &[], "spied", None)
+///     .unwrap();
+/// app.execute_contract(owner.clone(), contract_addr, &Empty {}, &[])
+///     .unwrap();
+///
+/// let calls = log.calls_to("execute");
+/// assert_eq!(1, calls.len());
+/// assert_eq!(Some(owner), calls[0].sender);
+/// ```
+pub struct Spying<C = Empty, Q = Empty>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    contract: Box<dyn Contract<C, Q>>,
+    log: SpyLog,
+}
+
+impl<C, Q> Spying<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    /// Creates a spying contract wrapping the specified contract.
+    pub fn new(contract: Box<dyn Contract<C, Q>>) -> Self {
+        Self {
+            contract,
+            log: SpyLog::default(),
+        }
+    }
+
+    /// Returns the handle to the log of recorded calls.
+    pub fn log(&self) -> SpyLog {
+        self.log.clone()
+    }
+}
+
+#[rustfmt::skip]
+impl<C, Q> Contract<C, Q> for Spying<C, Q>
+where
+    C: CustomMsg,
+    Q: CustomQuery,
+{
+    fn execute(&self, deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.log.record("execute", &env, Some(&info), &msg);
+        self.contract.execute(deps, env, info, msg)
+    }
+
+    fn instantiate(&self, deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.log.record("instantiate", &env, Some(&info), &msg);
+        self.contract.instantiate(deps, env, info, msg)
+    }
+
+    fn query(&self, deps: Deps<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        self.log.record("query", &env, None, &msg);
+        self.contract.query(deps, env, msg)
+    }
+
+    fn sudo(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.log.record("sudo", &env, None, &msg);
+        self.contract.sudo(deps, env, msg)
+    }
+
+    fn reply(&self, deps: DepsMut<Q>, env: Env, msg: Reply) -> AnyResult<Response<C>> {
+        self.contract.reply(deps, env, msg)
+    }
+
+    fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.log.record("migrate", &env, None, &msg);
+        self.contract.migrate(deps, env, msg)
+    }
+
+    fn ibc_enabled(&self) -> bool {
+        self.contract.ibc_enabled()
+    }
+
+    fn ibc_channel_open(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelOpenMsg) -> AnyResult<IbcChannelOpenResponse> {
+        self.contract.ibc_channel_open(deps, env, msg)
+    }
+
+    fn ibc_channel_connect(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelConnectMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_channel_connect(deps, env, msg)
+    }
+
+    fn ibc_channel_close(&self, deps: DepsMut<Q>, env: Env, msg: IbcChannelCloseMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_channel_close(deps, env, msg)
+    }
+
+    fn ibc_packet_receive(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketReceiveMsg) -> AnyResult<IbcReceiveResponse<C>> {
+        self.contract.ibc_packet_receive(deps, env, msg)
+    }
+
+    fn ibc_packet_ack(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketAckMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_packet_ack(deps, env, msg)
+    }
+
+    fn ibc_packet_timeout(&self, deps: DepsMut<Q>, env: Env, msg: IbcPacketTimeoutMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_packet_timeout(deps, env, msg)
+    }
+
+    fn ibc_source_callback(&self, deps: DepsMut<Q>, env: Env, msg: IbcSourceCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_source_callback(deps, env, msg)
+    }
+
+    fn ibc_destination_callback(&self, deps: DepsMut<Q>, env: Env, msg: IbcDestinationCallbackMsg) -> AnyResult<IbcBasicResponse<C>> {
+        self.contract.ibc_destination_callback(deps, env, msg)
+    }
+}
@@ -1,15 +1,27 @@
-use crate::app::CosmosRouter;
+use crate::addresses::module_address;
+use crate::app::{CosmosRouter, CosmosSdkMsg};
+use crate::bank::ProtoCoin;
 use crate::error::{anyhow, bail, AnyResult};
 use crate::executor::AppResponse;
+use crate::param_history::record_param_change;
 use crate::prefixed_storage::{prefixed, prefixed_read};
-use crate::{BankSudo, Module};
+use crate::stargate::query_historical_info;
+use crate::{BankSudo, Module, WasmSudo};
 use cosmwasm_std::{
-    coin, ensure, ensure_eq, to_json_binary, Addr, AllDelegationsResponse, AllValidatorsResponse,
-    Api, BankMsg, Binary, BlockInfo, BondedDenomResponse, Coin, CustomMsg, CustomQuery, Decimal,
-    Delegation, DelegationResponse, DistributionMsg, Empty, Event, FullDelegation, Querier,
-    StakingMsg, StakingQuery, Storage, Timestamp, Uint128, Validator, ValidatorResponse,
+    coin, ensure, ensure_eq, from_json, to_json_binary, Addr, AllDelegationsResponse,
+    AllValidatorsResponse, Api, BankMsg, BankQuery, Binary, BlockInfo, BondedDenomResponse, Coin,
+    CustomMsg, CustomQuery, DecCoin, Decimal, Delegation, DelegationResponse, DistributionMsg,
+    Empty, Event, FullDelegation, Order, Querier, StakingMsg, StakingQuery, StdResult, Storage,
+    SupplyResponse, Timestamp, Uint128, Validator, ValidatorResponse,
 };
-use cw_storage_plus::{Deque, Item, Map};
+#[cfg(feature = "cosmwasm_2_0")]
+use cosmwasm_std::{
+    DelegationRewardsResponse, DelegationTotalRewardsResponse, DelegatorReward,
+    DelegatorValidatorsResponse, DelegatorWithdrawAddressResponse, DistributionQuery,
+};
+use cw_storage_plus::{Bound, Deque, Item, Map};
+use cw_utils::NativeBalance;
+use itertools::Itertools;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeSet, VecDeque};
@@ -23,6 +35,19 @@ pub struct StakingInfo {
     pub unbonding_time: u64,
     /// Interest rate per year (60 * 60 * 24 * 365 seconds)
     pub apr: Decimal,
+    /// Maximum number of bonded validators, the ones with the highest stake
+    #[serde(default = "default_max_validators")]
+    pub max_validators: u32,
+    /// Maximum number of pending unbondings for each (delegator, validator) pair
+    /// and pending redelegations for each (delegator, source validator, destination validator)
+    #[serde(default = "default_max_entries")]
+    pub max_entries: u32,
+    /// Minimum commission rate of every validator
+    #[serde(default)]
+    pub min_commission_rate: Decimal,
+    /// Number of recent blocks for which the historical info is kept
+    #[serde(default = "default_historical_entries")]
+    pub historical_entries: u32,
 }
 
 impl Default for StakingInfo {
@@ -32,24 +57,125 @@ impl Default for StakingInfo {
             bonded_denom: "TOKEN".to_string(),
             unbonding_time: 60,
             apr: Decimal::percent(10),
+            max_validators: default_max_validators(),
+            max_entries: default_max_entries(),
+            min_commission_rate: Decimal::zero(),
+            historical_entries: default_historical_entries(),
         }
     }
 }
 
+/// The default maximum number of bonded validators, the same as in Cosmos SDK.
+fn default_max_validators() -> u32 {
+    100
+}
+
+/// The default maximum number of unbonding and redelegation entries, the same as in Cosmos SDK.
+fn default_max_entries() -> u32 {
+    7
+}
+
+/// The default number of historical info entries to keep, the same as in Cosmos SDK.
+fn default_historical_entries() -> u32 {
+    10_000
+}
+
+/// The way the validator accrues rewards, overriding the interest rate
+/// from [StakingInfo] used by default.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardAccrual {
+    /// Interest rate per year (60 * 60 * 24 * 365 seconds) of the validator's bonded stake.
+    Apr(Decimal),
+    /// Fixed number of tokens provisioned to the validator in every block,
+    /// regardless of the size of the stake. Nothing is provisioned when no tokens are bonded.
+    PerBlock(Uint128),
+}
+
+/// The algorithm distributing rewards of the validator among its delegators.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardDistribution {
+    /// Rewards are added to every delegation whenever the validator's rewards are calculated,
+    /// proportionally to the delegated stake. Decimal remainders of withdrawn rewards are kept.
+    #[default]
+    Linear,
+    /// Rewards are distributed with the F1 fee distribution algorithm of Cosmos SDK, using
+    /// cumulative reward ratios of the validator's periods, so the rewards are rounded like on chain.
+    /// Rewards of a delegation are truncated whenever the delegation is modified or rewards are withdrawn.
+    F1,
+}
+
 /// The number of stake and rewards of this validator the staker has. These can be fractional in case of slashing.
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 struct Shares {
     stake: Decimal,
     rewards: Decimal,
+    /// The validator's period preceding the delegation's starting period, used by [RewardDistribution::F1].
+    #[serde(default)]
+    period: u64,
 }
 
 impl Shares {
-    /// Calculates the share of validator rewards that should be given to this staker.
-    pub fn share_of_rewards(&self, validator: &ValidatorInfo, rewards: Decimal) -> Decimal {
-        if validator.stake.is_zero() {
+    /// Calculates the share of validator rewards that should be given to this staker,
+    /// proportionally to the stake delegated to the validator by all stakers.
+    pub fn share_of_rewards(&self, delegated: Decimal, rewards: Decimal) -> Decimal {
+        if delegated.is_zero() {
             return Decimal::zero();
         }
-        rewards * self.stake / validator.stake
+        rewards * self.stake / delegated
+    }
+}
+
+/// Description of the validator, like `Description` of Cosmos SDK staking module.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct ValidatorDescription {
+    /// Name of the validator.
+    pub moniker: String,
+    /// Optional identity signature, like UPort or Keybase.
+    pub identity: String,
+    /// Optional website link.
+    pub website: String,
+    /// Optional security contact information.
+    pub security_contact: String,
+    /// Optional details about the validator.
+    pub details: String,
+}
+
+impl ValidatorDescription {
+    /// Returns the description with fields replaced by the edited ones, fields
+    /// of the edited description set to `[do-not-modify]` keep their current value.
+    fn updated(&self, edited: ValidatorDescription) -> AnyResult<Self> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/staking/types/validator.go#L236-L297
+        const DO_NOT_MODIFY: &str = "[do-not-modify]";
+        let update = |current: &str, edited: String, name: &str, max: usize| {
+            let value = if edited == DO_NOT_MODIFY {
+                current.to_string()
+            } else {
+                edited
+            };
+            if value.len() > max {
+                bail!(
+                    "invalid {} length; got: {}, max: {}",
+                    name,
+                    value.len(),
+                    max
+                );
+            }
+            Ok(value)
+        };
+        Ok(Self {
+            moniker: update(&self.moniker, edited.moniker, "moniker", 70)?,
+            identity: update(&self.identity, edited.identity, "identity", 3000)?,
+            website: update(&self.website, edited.website, "website", 140)?,
+            security_contact: update(
+                &self.security_contact,
+                edited.security_contact,
+                "security contact",
+                140,
+            )?,
+            details: update(&self.details, edited.details, "details", 280)?,
+        })
     }
 }
 
@@ -63,27 +189,210 @@ struct ValidatorInfo {
     stake: Uint128,
     /// The block time when this validator's rewards were last update. This is needed for rewards calculation.
     last_rewards_calculation: Timestamp,
+    /// The block height when this validator's rewards were last update, needed for per-block provisions.
+    #[serde(default)]
+    last_rewards_height: u64,
+    /// Reward accrual specific to this validator, `None` when the interest rate from [StakingInfo] is used.
+    #[serde(default)]
+    reward_accrual: Option<RewardAccrual>,
+    /// Commission accrued until the last rewards calculation and not withdrawn yet.
+    #[serde(default)]
+    commission: Decimal,
+    /// Block time until which the validator is jailed, `None` when the validator is not jailed.
+    #[serde(default)]
+    jailed_until: Option<Timestamp>,
+    /// Flag indicating that the validator was jailed forever for double signing.
+    #[serde(default)]
+    tombstoned: bool,
+    /// Minimum self-delegation of the validator's operator, the validator is jailed
+    /// when its self-delegation drops below it.
+    #[serde(default)]
+    min_self_delegation: Uint128,
+    /// Description of the validator.
+    #[serde(default)]
+    description: ValidatorDescription,
+    /// The block time of the last commission change, the commission
+    /// can be changed only once in 24 hours.
+    #[serde(default)]
+    commission_update_time: Timestamp,
+    /// The current rewards period, used by [RewardDistribution::F1].
+    #[serde(default = "default_period")]
+    period: u64,
+    /// Rewards of delegators accrued in the current period, used by [RewardDistribution::F1].
+    #[serde(default)]
+    current_rewards: Decimal,
+}
+
+/// The first rewards period of the validator, the same as in Cosmos SDK.
+fn default_period() -> u64 {
+    1
 }
 
 impl ValidatorInfo {
-    pub fn new(block_time: Timestamp) -> Self {
+    pub fn new(block: &BlockInfo) -> Self {
         Self {
             stakers: BTreeSet::new(),
             stake: Uint128::zero(),
-            last_rewards_calculation: block_time,
+            last_rewards_calculation: block.time,
+            last_rewards_height: block.height,
+            reward_accrual: None,
+            commission: Decimal::zero(),
+            jailed_until: None,
+            tombstoned: false,
+            min_self_delegation: Uint128::zero(),
+            description: ValidatorDescription::default(),
+            commission_update_time: block.time,
+            period: default_period(),
+            current_rewards: Decimal::zero(),
+        }
+    }
+
+    /// Returns the stake earning rewards, jailed validators are not bonded and earn nothing.
+    pub fn bonded_stake(&self) -> Uint128 {
+        if self.jailed_until.is_some() {
+            Uint128::zero()
+        } else {
+            self.stake
+        }
+    }
+
+    /// Returns `true` when rewards were already calculated in the block.
+    pub fn is_rewarded(&self, block: &BlockInfo) -> bool {
+        self.last_rewards_calculation >= block.time && self.last_rewards_height >= block.height
+    }
+}
+
+/// Parameters of the slashing module, like `Params` of Cosmos SDK slashing module.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SlashingParams {
+    /// Time in seconds the validator is jailed for after the downtime.
+    pub downtime_jail_duration: u64,
+    /// Fraction of the stake slashed for double signing.
+    pub slash_fraction_double_sign: Decimal,
+    /// Fraction of the stake slashed for the downtime.
+    pub slash_fraction_downtime: Decimal,
+}
+
+impl Default for SlashingParams {
+    /// Creates slashing parameters with the default values of Cosmos SDK.
+    fn default() -> Self {
+        Self {
+            downtime_jail_duration: 600,
+            slash_fraction_double_sign: Decimal::percent(5),
+            slash_fraction_downtime: Decimal::percent(1),
         }
     }
 }
 
+/// Bonded tokens of a validator.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub(crate) struct ValidatorStake {
+    /// Validator's address.
+    pub address: String,
+    /// Tokens bonded to the validator.
+    pub tokens: Uint128,
+}
+
+/// Block and validator set at given height, tracked like `HistoricalInfo` in Cosmos SDK.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub(crate) struct HistoricalInfo {
+    /// Block at given height.
+    pub block: BlockInfo,
+    /// Validators with bonded tokens.
+    pub validators: Vec<ValidatorStake>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 struct Unbonding {
     pub delegator: Addr,
     pub validator: Addr,
     pub amount: Uint128,
     pub payout_at: Timestamp,
+    /// Height of the block in which the tokens were undelegated.
+    #[serde(default)]
+    pub creation_height: u64,
+    /// Undelegated amount, before slashing.
+    #[serde(default)]
+    pub initial_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct Redelegation {
+    pub delegator: Addr,
+    pub src_validator: Addr,
+    pub dst_validator: Addr,
+    pub amount: Uint128,
+    pub creation_height: u64,
+    pub completion_time: Timestamp,
+}
+
+/// Tokens redelegated between validators, like `RedelegationEntry` in Cosmos SDK.
+///
+/// Until the completion time, the redelegated tokens can not be redelegated again
+/// from the destination validator. Unlike in Cosmos SDK, redelegated tokens
+/// are not slashed when the source validator is slashed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RedelegationEntry {
+    /// Address of the delegator.
+    pub delegator: Addr,
+    /// Address of the validator the tokens were redelegated from.
+    pub src_validator: Addr,
+    /// Address of the validator the tokens were redelegated to.
+    pub dst_validator: Addr,
+    /// Height of the block in which the tokens were redelegated.
+    pub creation_height: u64,
+    /// Block time at which the redelegation completes.
+    pub completion_time: Timestamp,
+    /// Redelegated tokens.
+    pub balance: Coin,
+}
+
+/// Tokens undelegated from the validator, waiting for the end of the unbonding period,
+/// like `UnbondingDelegationEntry` in Cosmos SDK.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct UnbondingEntry {
+    /// Address of the delegator.
+    pub delegator: Addr,
+    /// Address of the validator the tokens were undelegated from.
+    pub validator: Addr,
+    /// Height of the block in which the tokens were undelegated.
+    pub creation_height: u64,
+    /// Block time at which the tokens are released to the delegator.
+    pub completion_time: Timestamp,
+    /// Undelegated tokens.
+    pub initial_balance: Coin,
+    /// Tokens released to the delegator, less than `initial_balance` when the validator was slashed.
+    pub balance: Coin,
+}
+
+/// Delegation converted into transferable share tokens, like `TokenizeShareRecord`
+/// in the liquid staking module.
+///
+/// Tokenized stake is delegated by the record's module account, share tokens
+/// with [share_denom](Self::share_denom) are minted to the owner of the record.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TokenizeShareRecord {
+    /// Identifier of the record.
+    pub id: u64,
+    /// Owner of the record.
+    pub owner: Addr,
+    /// Address of the module account holding the tokenized delegation.
+    pub module_account: Addr,
+    /// Address of the validator the tokenized stake is delegated to.
+    pub validator: Addr,
+}
+
+impl TokenizeShareRecord {
+    /// Returns the denomination of share tokens, like `{validator}/{id}`.
+    pub fn share_denom(&self) -> String {
+        format!("{}/{}", self.validator, self.id)
+    }
 }
 
 const STAKING_INFO: Item<StakingInfo> = Item::new("staking_info");
+const SLASHING_PARAMS: Item<SlashingParams> = Item::new("slashing_params");
+/// (validator) -> proposer priority, used to select block proposers like in CometBFT.
+const PROPOSER_PRIORITY: Map<&Addr, i128> = Map::new("proposer_priority");
 /// (staker_addr, validator_addr) -> shares
 const STAKES: Map<(&Addr, &Addr), Shares> = Map::new("stakes");
 const VALIDATOR_MAP: Map<&Addr, Validator> = Map::new("validator_map");
@@ -93,189 +402,2384 @@ const VALIDATORS: Deque<Validator> = Deque::new("validators");
 const VALIDATOR_INFO: Map<&Addr, ValidatorInfo> = Map::new("validator_info");
 /// The queue of unbonding operations. This is needed because unbonding has a waiting time. See [`StakeKeeper`]
 const UNBONDING_QUEUE: Item<VecDeque<Unbonding>> = Item::new("unbonding_queue");
+/// The queue of redelegations, removed when completed.
+const REDELEGATION_QUEUE: Item<VecDeque<Redelegation>> = Item::new("redelegation_queue");
 /// (addr) -> addr. Maps addresses to the address they have delegated
 /// to receive their staking rewards. A missing key => no delegation
 /// has been set.
 const WITHDRAW_ADDRESS: Map<&Addr, Addr> = Map::new("withdraw_address");
+/// Coins in the community pool, held by the distribution module's account.
+const COMMUNITY_POOL: Item<Vec<Coin>> = Item::new("community_pool");
+/// Name of the distribution module, its account holds the community pool.
+pub(crate) const DISTRIBUTION_MODULE: &str = "distribution";
+/// Minted tokens allocated as rewards, held by the distribution module's account until withdrawn.
+const PROVISIONED_REWARDS: Item<Uint128> = Item::new("provisioned_rewards");
+/// (height) -> historical info. Block and validator set at the end of each block.
+const HISTORICAL_INFO: Map<u64, HistoricalInfo> = Map::new("historical_info");
+/// (id) -> tokenize share record. Records of delegations converted into share tokens.
+const TOKENIZE_SHARE_RECORDS: Map<u64, TokenizeShareRecord> = Map::new("tokenize_share_records");
+/// Identifier of the most recently created tokenize share record.
+const LAST_TOKENIZE_SHARE_RECORD_ID: Item<u64> = Item::new("last_tokenize_share_record_id");
+/// (contract_addr) -> empty. Contracts notified about staking events.
+const STAKING_HOOKS: Map<&Addr, Empty> = Map::new("staking_hooks");
+
+const REWARD_DISTRIBUTION: Item<RewardDistribution> = Item::new("reward_distribution");
+/// (validator, period) -> cumulative reward ratio at the end of the validator's period
+const VALIDATOR_HISTORICAL_REWARDS: Map<(&Addr, u64), Decimal> =
+    Map::new("validator_historical_rewards");
 
 pub const NAMESPACE_STAKING: &[u8] = b"staking";
 // https://github.com/cosmos/cosmos-sdk/blob/4f6f6c00021f4b5ee486bbb71ae2071a8ceb47c9/x/distribution/types/keys.go#L16
 pub const NAMESPACE_DISTRIBUTION: &[u8] = b"distribution";
 
-/// Staking privileged action definition.
-///
-/// We need to expand on this, but we will need this to properly test out staking
-#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
-pub enum StakingSudo {
-    /// Slashes the given percentage of the validator's stake.
-    /// For now, you cannot slash retrospectively in tests.
-    Slash {
-        /// Validator's address.
-        validator: String,
-        /// Percentage of the validator's stake.
-        percentage: Decimal,
-    },
+/// Path of the gRPC query for pending unbondings of the delegator.
+const QUERY_DELEGATOR_UNBONDING_DELEGATIONS: &str =
+    "/cosmos.staking.v1beta1.Query/DelegatorUnbondingDelegations";
+
+/// Path of the gRPC query for pending unbondings of the delegator from single validator.
+const QUERY_UNBONDING_DELEGATION: &str = "/cosmos.staking.v1beta1.Query/UnbondingDelegation";
+
+/// Path of the gRPC query for delegations of the delegator.
+const QUERY_DELEGATOR_DELEGATIONS: &str = "/cosmos.staking.v1beta1.Query/DelegatorDelegations";
+
+/// Path of the gRPC query for delegations to the validator.
+const QUERY_VALIDATOR_DELEGATIONS: &str = "/cosmos.staking.v1beta1.Query/ValidatorDelegations";
+
+/// Path of the gRPC query for staking parameters.
+const QUERY_STAKING_PARAMS: &str = "/cosmos.staking.v1beta1.Query/Params";
+
+/// Path of the gRPC query for the validator.
+const QUERY_VALIDATOR: &str = "/cosmos.staking.v1beta1.Query/Validator";
+
+/// Path of the gRPC query for all validators.
+const QUERY_VALIDATORS: &str = "/cosmos.staking.v1beta1.Query/Validators";
+
+/// Path of the gRPC query for the historical info.
+const QUERY_HISTORICAL_INFO: &str = "/cosmos.staking.v1beta1.Query/HistoricalInfo";
+
+/// Path of the gRPC query for redelegations.
+const QUERY_REDELEGATIONS: &str = "/cosmos.staking.v1beta1.Query/Redelegations";
+
+/// Path of the gRPC query for the commission accrued by the validator.
+const QUERY_VALIDATOR_COMMISSION: &str = "/cosmos.distribution.v1beta1.Query/ValidatorCommission";
+
+/// Path of the gRPC query for rewards of the validator not withdrawn yet.
+const QUERY_VALIDATOR_OUTSTANDING_REWARDS: &str =
+    "/cosmos.distribution.v1beta1.Query/ValidatorOutstandingRewards";
+
+/// Path of the gRPC query for the withdraw address of the delegator.
+const QUERY_DELEGATOR_WITHDRAW_ADDRESS: &str =
+    "/cosmos.distribution.v1beta1.Query/DelegatorWithdrawAddress";
+
+/// Path of the gRPC query for the community pool.
+const QUERY_COMMUNITY_POOL: &str = "/cosmos.distribution.v1beta1.Query/CommunityPool";
+
+/// Type URL of the `MsgFundCommunityPool` message routed to the distribution module.
+pub(crate) const MSG_FUND_COMMUNITY_POOL: &str =
+    "/cosmos.distribution.v1beta1.MsgFundCommunityPool";
+
+/// Type URL of the `MsgWithdrawValidatorCommission` message routed to the distribution module.
+pub(crate) const MSG_WITHDRAW_VALIDATOR_COMMISSION: &str =
+    "/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission";
+
+/// Type URL of the `MsgCreateValidator` message routed to the staking module.
+pub(crate) const MSG_CREATE_VALIDATOR: &str = "/cosmos.staking.v1beta1.MsgCreateValidator";
+
+/// Type URL of the `MsgEditValidator` message routed to the staking module.
+pub(crate) const MSG_EDIT_VALIDATOR: &str = "/cosmos.staking.v1beta1.MsgEditValidator";
+
+/// Type URL of the `MsgUnjail` message routed to the staking module.
+pub(crate) const MSG_UNJAIL: &str = "/cosmos.slashing.v1beta1.MsgUnjail";
+
+/// Type URL of the `MsgBeginRedelegate` message routed to the staking module.
+pub(crate) const MSG_BEGIN_REDELEGATE: &str = "/cosmos.staking.v1beta1.MsgBeginRedelegate";
+
+/// Type URLs of the `MsgTokenizeShares` message routed to the staking module,
+/// as defined in the liquid staking module of Cosmos SDK fork and Gaia.
+const MSG_TOKENIZE_SHARES: [&str; 2] = [
+    "/cosmos.staking.v1beta1.MsgTokenizeShares",
+    "/gaia.liquid.v1beta1.MsgTokenizeShares",
+];
+
+/// Type URLs of the `MsgRedeemTokensForShares` message routed to the staking module.
+const MSG_REDEEM_TOKENS_FOR_SHARES: [&str; 2] = [
+    "/cosmos.staking.v1beta1.MsgRedeemTokensForShares",
+    "/gaia.liquid.v1beta1.MsgRedeemTokensForShares",
+];
+
+/// Type URLs of the `MsgTransferTokenizeShareRecord` message routed to the staking module.
+const MSG_TRANSFER_TOKENIZE_SHARE_RECORD: [&str; 2] = [
+    "/cosmos.staking.v1beta1.MsgTransferTokenizeShareRecord",
+    "/gaia.liquid.v1beta1.MsgTransferTokenizeShareRecord",
+];
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ProtoDuration {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
 }
 
-/// A trait defining a behavior of the stake keeper.
-///
-/// Manages staking operations, vital for testing contracts in proof-of-stake (PoS) blockchain environments.
-/// This trait simulates staking behaviors, including delegation, validator operations, and reward mechanisms.
-pub trait Staking: Module<ExecT = StakingMsg, QueryT = StakingQuery, SudoT = StakingSudo> {
-    /// This is called from the end blocker (`update_block` / `set_block`) to process the
-    /// staking queue. Needed because unbonding has a waiting time.
-    /// If you're implementing a dummy staking module, this can be a no-op.
-    fn process_queue<ExecC: CustomMsg, QueryC: CustomQuery>(
-        &self,
-        _api: &dyn Api,
-        _storage: &mut dyn Storage,
-        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
-        _block: &BlockInfo,
-    ) -> AnyResult<AppResponse> {
-        Ok(AppResponse::default())
-    }
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ProtoStakingParams {
+    #[prost(message, optional, tag = "1")]
+    pub unbonding_time: Option<ProtoDuration>,
+    #[prost(uint32, tag = "2")]
+    pub max_validators: u32,
+    #[prost(uint32, tag = "3")]
+    pub max_entries: u32,
+    #[prost(uint32, tag = "4")]
+    pub historical_entries: u32,
+    #[prost(string, tag = "5")]
+    pub bond_denom: String,
+    #[prost(string, tag = "6")]
+    pub min_commission_rate: String,
 }
 
-/// A trait defining a behavior of the distribution keeper.
-pub trait Distribution: Module<ExecT = DistributionMsg, QueryT = Empty, SudoT = Empty> {}
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryStakingParamsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub params: Option<ProtoStakingParams>,
+}
 
-/// A structure representing a default stake keeper.
-pub struct StakeKeeper {
-    /// Module address of a default stake keeper.
-    module_addr: Addr,
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ProtoTimestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
 }
 
-impl Default for StakeKeeper {
-    /// Creates a new stake keeper with default settings.
-    fn default() -> Self {
-        Self::new()
+impl From<Timestamp> for ProtoTimestamp {
+    fn from(time: Timestamp) -> Self {
+        Self {
+            seconds: time.seconds() as i64,
+            nanos: time.subsec_nanos() as i32,
+        }
     }
 }
 
-impl StakeKeeper {
-    /// Creates a new stake keeper with default module address.
-    pub fn new() -> Self {
-        StakeKeeper {
-            // The address of the staking module. This holds all staked tokens.
-            module_addr: Addr::unchecked("staking_module"),
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgBeginRedelegate {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_src_address: String,
+    #[prost(string, tag = "3")]
+    pub validator_dst_address: String,
+    #[prost(message, optional, tag = "4")]
+    pub amount: Option<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoCommissionRates {
+    #[prost(string, tag = "1")]
+    pub rate: String,
+    #[prost(string, tag = "2")]
+    pub max_rate: String,
+    #[prost(string, tag = "3")]
+    pub max_change_rate: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgCreateValidator {
+    #[prost(message, optional, tag = "2")]
+    pub commission: Option<ProtoCommissionRates>,
+    #[prost(string, tag = "3")]
+    pub min_self_delegation: String,
+    #[prost(string, tag = "5")]
+    pub validator_address: String,
+    #[prost(message, optional, tag = "7")]
+    pub value: Option<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoDescription {
+    #[prost(string, tag = "1")]
+    pub moniker: String,
+    #[prost(string, tag = "2")]
+    pub identity: String,
+    #[prost(string, tag = "3")]
+    pub website: String,
+    #[prost(string, tag = "4")]
+    pub security_contact: String,
+    #[prost(string, tag = "5")]
+    pub details: String,
+}
+
+impl From<ValidatorDescription> for ProtoDescription {
+    fn from(description: ValidatorDescription) -> Self {
+        Self {
+            moniker: description.moniker,
+            identity: description.identity,
+            website: description.website,
+            security_contact: description.security_contact,
+            details: description.details,
         }
     }
+}
 
-    /// Provides some general parameters to the stake keeper
-    pub fn setup(&self, storage: &mut dyn Storage, staking_info: StakingInfo) -> AnyResult<()> {
-        let mut storage = prefixed(storage, NAMESPACE_STAKING);
-        STAKING_INFO.save(&mut storage, &staking_info)?;
-        Ok(())
+impl From<ProtoDescription> for ValidatorDescription {
+    fn from(description: ProtoDescription) -> Self {
+        Self {
+            moniker: description.moniker,
+            identity: description.identity,
+            website: description.website,
+            security_contact: description.security_contact,
+            details: description.details,
+        }
     }
+}
 
-    /// Add a new validator available for staking
-    pub fn add_validator(
-        &self,
-        api: &dyn Api,
-        storage: &mut dyn Storage,
-        block: &BlockInfo,
-        validator: Validator,
-    ) -> AnyResult<()> {
-        let mut storage = prefixed(storage, NAMESPACE_STAKING);
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoCommission {
+    #[prost(message, optional, tag = "1")]
+    pub commission_rates: Option<ProtoCommissionRates>,
+    #[prost(message, optional, tag = "2")]
+    pub update_time: Option<ProtoTimestamp>,
+}
 
-        let val_addr = api.addr_validate(&validator.address)?;
-        if VALIDATOR_MAP.may_load(&storage, &val_addr)?.is_some() {
-            bail!(
-                "Cannot add validator {}, since a validator with that address already exists",
-                val_addr
-            );
-        }
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoValidator {
+    #[prost(string, tag = "1")]
+    pub operator_address: String,
+    #[prost(bool, tag = "3")]
+    pub jailed: bool,
+    #[prost(int32, tag = "4")]
+    pub status: i32,
+    #[prost(string, tag = "5")]
+    pub tokens: String,
+    #[prost(string, tag = "6")]
+    pub delegator_shares: String,
+    #[prost(message, optional, tag = "7")]
+    pub description: Option<ProtoDescription>,
+    #[prost(message, optional, tag = "10")]
+    pub commission: Option<ProtoCommission>,
+    #[prost(string, tag = "11")]
+    pub min_self_delegation: String,
+}
 
-        VALIDATOR_MAP.save(&mut storage, &val_addr, &validator)?;
-        VALIDATORS.push_back(&mut storage, &validator)?;
-        VALIDATOR_INFO.save(&mut storage, &val_addr, &ValidatorInfo::new(block.time))?;
-        Ok(())
-    }
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryValidatorRequest {
+    #[prost(string, tag = "1")]
+    pub validator_addr: String,
+}
 
-    fn get_staking_info(staking_storage: &dyn Storage) -> AnyResult<StakingInfo> {
-        Ok(STAKING_INFO.may_load(staking_storage)?.unwrap_or_default())
-    }
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryValidatorResponse {
+    #[prost(message, optional, tag = "1")]
+    pub validator: Option<ProtoValidator>,
+}
 
-    /// Returns the rewards of the given delegator at the given validator
-    pub fn get_rewards(
-        &self,
-        storage: &dyn Storage,
-        block: &BlockInfo,
-        delegator: &Addr,
-        validator: &Addr,
-    ) -> AnyResult<Option<Coin>> {
-        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryValidatorsRequest {
+    #[prost(string, tag = "1")]
+    pub status: String,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<ProtoPageRequest>,
+}
 
-        let validator_obj = match self.get_validator(&staking_storage, validator)? {
-            Some(validator) => validator,
-            None => bail!("validator {} not found", validator),
-        };
-        // calculate rewards using fixed ratio
-        let shares = match STAKES.load(&staking_storage, (delegator, validator)) {
-            Ok(stakes) => stakes,
-            Err(_) => {
-                return Ok(None);
-            }
-        };
-        let validator_info = VALIDATOR_INFO.load(&staking_storage, validator)?;
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryValidatorsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub validators: Vec<ProtoValidator>,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<ProtoPageResponse>,
+}
 
-        Self::get_rewards_internal(
-            &staking_storage,
-            block,
-            &shares,
-            &validator_obj,
-            &validator_info,
-        )
-        .map(Some)
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgEditValidator {
+    #[prost(message, optional, tag = "1")]
+    pub description: Option<ProtoDescription>,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(string, tag = "3")]
+    pub commission_rate: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgUnjail {
+    #[prost(string, tag = "1")]
+    pub validator_addr: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgTokenizeShares {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(message, optional, tag = "3")]
+    pub amount: Option<ProtoCoin>,
+    #[prost(string, tag = "4")]
+    pub tokenized_share_owner: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgRedeemTokensForShares {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(message, optional, tag = "2")]
+    pub amount: Option<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgTransferTokenizeShareRecord {
+    #[prost(uint64, tag = "1")]
+    pub tokenize_share_record_id: u64,
+    #[prost(string, tag = "2")]
+    pub sender: String,
+    #[prost(string, tag = "3")]
+    pub new_owner: String,
+}
+
+/// Response to `MsgTokenizeShares` and `MsgRedeemTokensForShares`.
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgSharesResponse {
+    #[prost(message, optional, tag = "1")]
+    pub amount: Option<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgWithdrawValidatorCommission {
+    #[prost(string, tag = "1")]
+    pub validator_address: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoDecCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoValidatorAccumulatedCommission {
+    #[prost(message, repeated, tag = "1")]
+    pub commission: Vec<ProtoDecCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryValidatorOutstandingRewardsRequest {
+    #[prost(string, tag = "1")]
+    pub validator_address: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoValidatorOutstandingRewards {
+    #[prost(message, repeated, tag = "1")]
+    pub rewards: Vec<ProtoDecCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryValidatorOutstandingRewardsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub rewards: Option<ProtoValidatorOutstandingRewards>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDelegatorWithdrawAddressRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDelegatorWithdrawAddressResponse {
+    #[prost(string, tag = "1")]
+    pub withdraw_address: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryCommunityPoolResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub pool: Vec<ProtoDecCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoMsgFundCommunityPool {
+    #[prost(message, repeated, tag = "1")]
+    pub amount: Vec<ProtoCoin>,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryValidatorCommissionRequest {
+    #[prost(string, tag = "1")]
+    pub validator_address: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryValidatorCommissionResponse {
+    #[prost(message, optional, tag = "1")]
+    pub commission: Option<ProtoValidatorAccumulatedCommission>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoRedelegationEntry {
+    #[prost(int64, tag = "1")]
+    pub creation_height: i64,
+    #[prost(message, optional, tag = "2")]
+    pub completion_time: Option<ProtoTimestamp>,
+    #[prost(string, tag = "3")]
+    pub initial_balance: String,
+    #[prost(string, tag = "4")]
+    pub shares_dst: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoRedelegation {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_src_address: String,
+    #[prost(string, tag = "3")]
+    pub validator_dst_address: String,
+    #[prost(message, repeated, tag = "4")]
+    pub entries: Vec<ProtoRedelegationEntry>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoRedelegationEntryResponse {
+    #[prost(message, optional, tag = "1")]
+    pub redelegation_entry: Option<ProtoRedelegationEntry>,
+    #[prost(string, tag = "4")]
+    pub balance: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoRedelegationResponse {
+    #[prost(message, optional, tag = "1")]
+    pub redelegation: Option<ProtoRedelegation>,
+    #[prost(message, repeated, tag = "2")]
+    pub entries: Vec<ProtoRedelegationEntryResponse>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryRedelegationsRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_addr: String,
+    #[prost(string, tag = "2")]
+    pub src_validator_addr: String,
+    #[prost(string, tag = "3")]
+    pub dst_validator_addr: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryRedelegationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub redelegation_responses: Vec<ProtoRedelegationResponse>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoUnbondingDelegationEntry {
+    #[prost(int64, tag = "1")]
+    pub creation_height: i64,
+    #[prost(message, optional, tag = "2")]
+    pub completion_time: Option<ProtoTimestamp>,
+    #[prost(string, tag = "3")]
+    pub initial_balance: String,
+    #[prost(string, tag = "4")]
+    pub balance: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoUnbondingDelegation {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(message, repeated, tag = "3")]
+    pub entries: Vec<ProtoUnbondingDelegationEntry>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoPageRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub offset: u64,
+    #[prost(uint64, tag = "3")]
+    pub limit: u64,
+    #[prost(bool, tag = "4")]
+    pub count_total: bool,
+    #[prost(bool, tag = "5")]
+    pub reverse: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoPageResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub next_key: Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub total: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDelegatorUnbondingDelegationsRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_addr: String,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<ProtoPageRequest>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDelegatorUnbondingDelegationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub unbonding_responses: Vec<ProtoUnbondingDelegation>,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<ProtoPageResponse>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoDelegation {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(string, tag = "3")]
+    pub shares: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoDelegationResponse {
+    #[prost(message, optional, tag = "1")]
+    pub delegation: Option<ProtoDelegation>,
+    #[prost(message, optional, tag = "2")]
+    pub balance: Option<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDelegatorDelegationsRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_addr: String,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<ProtoPageRequest>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryValidatorDelegationsRequest {
+    #[prost(string, tag = "1")]
+    pub validator_addr: String,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<ProtoPageRequest>,
+}
+
+/// Response to both `DelegatorDelegations` and `ValidatorDelegations` queries.
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryDelegationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub delegation_responses: Vec<ProtoDelegationResponse>,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<ProtoPageResponse>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryUnbondingDelegationRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_addr: String,
+    #[prost(string, tag = "2")]
+    pub validator_addr: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProtoQueryUnbondingDelegationResponse {
+    #[prost(message, optional, tag = "1")]
+    pub unbond: Option<ProtoUnbondingDelegation>,
+}
+
+/// Groups unbonding entries by validator, like `UnbondingDelegation` in Cosmos SDK.
+fn to_proto_unbonding_delegations(entries: Vec<UnbondingEntry>) -> Vec<ProtoUnbondingDelegation> {
+    entries
+        .into_iter()
+        .into_group_map_by(|entry| entry.validator.clone())
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(validator, entries)| ProtoUnbondingDelegation {
+            delegator_address: entries[0].delegator.to_string(),
+            validator_address: validator.to_string(),
+            entries: entries
+                .into_iter()
+                .map(|entry| ProtoUnbondingDelegationEntry {
+                    creation_height: entry.creation_height as i64,
+                    completion_time: Some(entry.completion_time.into()),
+                    initial_balance: entry.initial_balance.amount.to_string(),
+                    balance: entry.balance.amount.to_string(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Returns the page of items ordered by their keys, like key-based pagination in Cosmos SDK.
+///
+/// The `key` of the page is the key of the first item on that page, the key of the next page
+/// is returned in the page response. The `offset` is used only when no key is specified,
+/// like in Cosmos SDK, the page has 100 items when no limit is specified.
+fn paginate<T>(
+    items: Vec<(Vec<u8>, T)>,
+    page: Option<ProtoPageRequest>,
+) -> (Vec<T>, ProtoPageResponse) {
+    let page = page.unwrap_or_default();
+    let limit = if page.limit == 0 {
+        100
+    } else {
+        page.limit as usize
+    };
+    let total = if page.count_total && page.key.is_empty() {
+        items.len() as u64
+    } else {
+        0
+    };
+    let mut items = items
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| if page.reverse { b.cmp(a) } else { a.cmp(b) })
+        .filter(|(key, _)| match (page.key.is_empty(), page.reverse) {
+            (true, _) => true,
+            (false, false) => *key >= page.key,
+            (false, true) => *key <= page.key,
+        })
+        .skip(if page.key.is_empty() {
+            page.offset as usize
+        } else {
+            0
+        })
+        .take(limit.saturating_add(1))
+        .collect::<Vec<_>>();
+    let next_key = if items.len() > limit {
+        items.pop().map(|(key, _)| key).unwrap_or_default()
+    } else {
+        vec![]
+    };
+    let items = items.into_iter().map(|(_, item)| item).collect();
+    (items, ProtoPageResponse { next_key, total })
+}
+
+/// Returns the delegation like `DelegationResponse` in Cosmos SDK, shares are equal to tokens.
+fn to_proto_delegation(
+    delegator: &Addr,
+    validator: &Addr,
+    shares: &Shares,
+    bonded_denom: &str,
+) -> ProtoDelegationResponse {
+    ProtoDelegationResponse {
+        delegation: Some(ProtoDelegation {
+            delegator_address: delegator.to_string(),
+            validator_address: validator.to_string(),
+            // encoded like `LegacyDec`, with 18 decimal places
+            shares: shares.stake.atomics().to_string(),
+        }),
+        balance: Some(coin(Uint128::new(1).mul_floor(shares.stake).u128(), bonded_denom).into()),
+    }
+}
+
+/// Groups redelegation entries by source and destination validator,
+/// like `RedelegationResponse` in Cosmos SDK.
+/// Names of validator bond statuses, indexed by the protobuf-encoded status.
+const BOND_STATUSES: [&str; 4] = [
+    "BOND_STATUS_UNSPECIFIED",
+    "BOND_STATUS_UNBONDED",
+    "BOND_STATUS_UNBONDING",
+    "BOND_STATUS_BONDED",
+];
+
+/// Converts the validator to protobuf-encoded validator, validators that are not bonded
+/// are reported as unbonded, even when their delegations are still unbonding.
+fn to_proto_validator(
+    staking_storage: &dyn Storage,
+    validator: Validator,
+    bonded: bool,
+) -> AnyResult<ProtoValidator> {
+    let validator_addr = Addr::unchecked(&validator.address);
+    let validator_info = VALIDATOR_INFO.load(staking_storage, &validator_addr)?;
+    let delegated =
+        StakeKeeper::delegated_stake(staking_storage, &validator_addr, &validator_info)?;
+    Ok(ProtoValidator {
+        operator_address: validator.address,
+        jailed: validator_info.jailed_until.is_some(),
+        status: if bonded { 3 } else { 1 },
+        tokens: validator_info.stake.to_string(),
+        // encoded like `LegacyDec`, with 18 decimal places
+        delegator_shares: delegated.atomics().to_string(),
+        description: Some(validator_info.description.into()),
+        commission: Some(ProtoCommission {
+            commission_rates: Some(ProtoCommissionRates {
+                rate: validator.commission.atomics().to_string(),
+                max_rate: validator.max_commission.atomics().to_string(),
+                max_change_rate: validator.max_change_rate.atomics().to_string(),
+            }),
+            update_time: Some(validator_info.commission_update_time.into()),
+        }),
+        min_self_delegation: validator_info.min_self_delegation.to_string(),
+    })
+}
+
+fn to_proto_redelegations(entries: Vec<RedelegationEntry>) -> Vec<ProtoRedelegationResponse> {
+    entries
+        .into_iter()
+        .into_group_map_by(|entry| {
+            (
+                entry.delegator.clone(),
+                entry.src_validator.clone(),
+                entry.dst_validator.clone(),
+            )
+        })
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|((delegator, src_validator, dst_validator), entries)| {
+            let entries = entries
+                .into_iter()
+                .map(|entry| ProtoRedelegationEntryResponse {
+                    redelegation_entry: Some(ProtoRedelegationEntry {
+                        creation_height: entry.creation_height as i64,
+                        completion_time: Some(entry.completion_time.into()),
+                        initial_balance: entry.balance.amount.to_string(),
+                        // shares are encoded like `LegacyDec`, with 18 decimal places
+                        shares_dst: Decimal::from_ratio(entry.balance.amount, 1u128)
+                            .atomics()
+                            .to_string(),
+                    }),
+                    balance: entry.balance.amount.to_string(),
+                })
+                .collect::<Vec<_>>();
+            ProtoRedelegationResponse {
+                redelegation: Some(ProtoRedelegation {
+                    delegator_address: delegator.to_string(),
+                    validator_src_address: src_validator.to_string(),
+                    validator_dst_address: dst_validator.to_string(),
+                    entries: entries
+                        .iter()
+                        .filter_map(|entry| entry.redelegation_entry.clone())
+                        .collect(),
+                }),
+                entries,
+            }
+        })
+        .collect()
+}
+
+/// Decodes the protobuf-encoded `MsgBeginRedelegate` signed by the sender.
+pub(crate) fn decode_begin_redelegate(sender: &Addr, value: &[u8]) -> AnyResult<StakingMsg> {
+    let msg = <ProtoMsgBeginRedelegate as prost::Message>::decode(value)?;
+    if msg.delegator_address != sender.as_str() {
+        bail!(
+            "delegator address {} does not match the signer {}",
+            msg.delegator_address,
+            sender
+        );
+    }
+    let Some(amount) = msg.amount else {
+        bail!("invalid shares amount");
+    };
+    Ok(StakingMsg::Redelegate {
+        src_validator: msg.validator_src_address,
+        dst_validator: msg.validator_dst_address,
+        amount: coin(amount.amount.parse::<u128>()?, amount.denom),
+    })
+}
+
+/// Decodes the protobuf-encoded `MsgWithdrawValidatorCommission` signed by the sender.
+/// Like in Cosmos SDK, the sender must be the validator's operator.
+pub(crate) fn decode_withdraw_validator_commission(
+    sender: &Addr,
+    value: &[u8],
+) -> AnyResult<CosmosSdkMsg> {
+    let msg = <ProtoMsgWithdrawValidatorCommission as prost::Message>::decode(value)?;
+    if msg.validator_address != sender.as_str() {
+        bail!(
+            "validator address {} does not match the signer {}",
+            msg.validator_address,
+            sender
+        );
+    }
+    Ok(CosmosSdkMsg::WithdrawValidatorCommission)
+}
+
+/// Decodes the protobuf-encoded `MsgFundCommunityPool` signed by the sender.
+pub(crate) fn decode_fund_community_pool(sender: &Addr, value: &[u8]) -> AnyResult<CosmosSdkMsg> {
+    let msg = <ProtoMsgFundCommunityPool as prost::Message>::decode(value)?;
+    if msg.depositor != sender.as_str() {
+        bail!(
+            "depositor address {} does not match the signer {}",
+            msg.depositor,
+            sender
+        );
+    }
+    let amount = msg
+        .amount
+        .into_iter()
+        .map(|c| Ok(coin(c.amount.parse::<u128>()?, c.denom)))
+        .collect::<AnyResult<_>>()?;
+    Ok(CosmosSdkMsg::FundCommunityPool { amount })
+}
+
+/// Parses the decimal encoded like `LegacyDec`, with 18 decimal places.
+fn parse_legacy_dec(value: &str) -> AnyResult<Decimal> {
+    Ok(Decimal::from_atomics(value.parse::<Uint128>()?, 18)?)
+}
+
+/// Decodes the protobuf-encoded `MsgCreateValidator` signed by the sender.
+/// The description and public key are ignored.
+pub(crate) fn decode_create_validator(sender: &Addr, value: &[u8]) -> AnyResult<CosmosSdkMsg> {
+    let msg = <ProtoMsgCreateValidator as prost::Message>::decode(value)?;
+    if msg.validator_address != sender.as_str() {
+        bail!(
+            "validator address {} does not match the signer {}",
+            msg.validator_address,
+            sender
+        );
+    }
+    let Some(commission) = msg.commission else {
+        bail!("empty commission rates");
+    };
+    let Some(value) = msg.value else {
+        bail!("invalid delegation amount");
+    };
+    let min_self_delegation = if msg.min_self_delegation.is_empty() {
+        Uint128::zero()
+    } else {
+        msg.min_self_delegation.parse::<Uint128>()?
+    };
+    let value = coin(value.amount.parse::<u128>()?, value.denom);
+    // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/staking/keeper/msg_server.go#L53-L55
+    if value.amount < min_self_delegation {
+        bail!("validator's self delegation must be greater than their minimum self delegation");
+    }
+    Ok(CosmosSdkMsg::CreateValidator {
+        commission: parse_legacy_dec(&commission.rate)?,
+        max_commission: parse_legacy_dec(&commission.max_rate)?,
+        max_change_rate: parse_legacy_dec(&commission.max_change_rate)?,
+        value,
+        min_self_delegation,
+    })
+}
+
+/// Decodes the protobuf-encoded `MsgEditValidator` signed by the sender.
+/// The description and the commission rate can be edited, each of them is kept when not specified.
+pub(crate) fn decode_edit_validator(sender: &Addr, value: &[u8]) -> AnyResult<CosmosSdkMsg> {
+    let msg = <ProtoMsgEditValidator as prost::Message>::decode(value)?;
+    if msg.validator_address != sender.as_str() {
+        bail!(
+            "validator address {} does not match the signer {}",
+            msg.validator_address,
+            sender
+        );
+    }
+    let commission = if msg.commission_rate.is_empty() {
+        None
+    } else {
+        Some(parse_legacy_dec(&msg.commission_rate)?)
+    };
+    Ok(CosmosSdkMsg::EditValidator {
+        description: msg.description.map(Into::into),
+        commission,
+    })
+}
+
+/// Decodes the protobuf-encoded `MsgUnjail` signed by the sender.
+/// Like in Cosmos SDK, the sender must be the validator's operator.
+pub(crate) fn decode_unjail(sender: &Addr, value: &[u8]) -> AnyResult<CosmosSdkMsg> {
+    let msg = <ProtoMsgUnjail as prost::Message>::decode(value)?;
+    if msg.validator_addr != sender.as_str() {
+        bail!(
+            "validator address {} does not match the signer {}",
+            msg.validator_addr,
+            sender
+        );
+    }
+    Ok(CosmosSdkMsg::Unjail)
+}
+
+/// Returns `true` when the message with specified type URL is one of the liquid staking messages.
+pub(crate) fn is_liquid_staking_msg(type_url: &str) -> bool {
+    MSG_TOKENIZE_SHARES.contains(&type_url)
+        || MSG_REDEEM_TOKENS_FOR_SHARES.contains(&type_url)
+        || MSG_TRANSFER_TOKENIZE_SHARE_RECORD.contains(&type_url)
+}
+
+/// Decodes the protobuf-encoded liquid staking message signed by the sender.
+pub(crate) fn decode_liquid_staking(
+    sender: &Addr,
+    type_url: &str,
+    value: &[u8],
+) -> AnyResult<LiquidStakingMsg> {
+    let ensure_signer = |role: &str, addr: &str| -> AnyResult<()> {
+        if addr != sender.as_str() {
+            bail!(
+                "{} address {} does not match the signer {}",
+                role,
+                addr,
+                sender
+            );
+        }
+        Ok(())
+    };
+    let to_coin = |amount: Option<ProtoCoin>| -> AnyResult<Coin> {
+        let Some(amount) = amount else {
+            bail!("invalid shares amount");
+        };
+        Ok(coin(amount.amount.parse::<u128>()?, amount.denom))
+    };
+    if MSG_TOKENIZE_SHARES.contains(&type_url) {
+        let msg = <ProtoMsgTokenizeShares as prost::Message>::decode(value)?;
+        ensure_signer("delegator", &msg.delegator_address)?;
+        Ok(LiquidStakingMsg::TokenizeShares {
+            validator: msg.validator_address,
+            amount: to_coin(msg.amount)?,
+            owner: msg.tokenized_share_owner,
+        })
+    } else if MSG_REDEEM_TOKENS_FOR_SHARES.contains(&type_url) {
+        let msg = <ProtoMsgRedeemTokensForShares as prost::Message>::decode(value)?;
+        ensure_signer("delegator", &msg.delegator_address)?;
+        Ok(LiquidStakingMsg::RedeemTokensForShares {
+            amount: to_coin(msg.amount)?,
+        })
+    } else if MSG_TRANSFER_TOKENIZE_SHARE_RECORD.contains(&type_url) {
+        let msg = <ProtoMsgTransferTokenizeShareRecord as prost::Message>::decode(value)?;
+        ensure_signer("sender", &msg.sender)?;
+        Ok(LiquidStakingMsg::TransferTokenizeShareRecord {
+            record_id: msg.tokenize_share_record_id,
+            new_owner: msg.new_owner,
+        })
+    } else {
+        bail!("unsupported liquid staking message: {}", type_url)
+    }
+}
+
+/// Returns `true` when the gRPC query with specified path is answered by the distribution module.
+pub(crate) fn is_distribution_grpc_query(path: &str) -> bool {
+    [
+        QUERY_VALIDATOR_COMMISSION,
+        QUERY_VALIDATOR_OUTSTANDING_REWARDS,
+        QUERY_DELEGATOR_WITHDRAW_ADDRESS,
+        QUERY_COMMUNITY_POOL,
+    ]
+    .contains(&path)
+}
+
+/// Returns the protobuf-encoded response to the gRPC query of the distribution module.
+pub(crate) fn query_distribution_grpc(
+    api: &dyn Api,
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    path: &str,
+    data: &[u8],
+) -> AnyResult<Binary> {
+    let distribution = DistributionKeeper::new();
+    let response = match path {
+        QUERY_VALIDATOR_COMMISSION => {
+            let request = <ProtoQueryValidatorCommissionRequest as prost::Message>::decode(data)?;
+            let validator = api.addr_validate(&request.validator_address)?;
+            let commission = distribution.validator_commission(storage, block, &validator)?;
+            prost::Message::encode_to_vec(&ProtoQueryValidatorCommissionResponse {
+                commission: Some(ProtoValidatorAccumulatedCommission {
+                    commission: commission
+                        .into_iter()
+                        .map(|c| ProtoDecCoin {
+                            denom: c.denom,
+                            // encoded like `LegacyDec`, with 18 decimal places
+                            amount: c.amount.atomics().to_string(),
+                        })
+                        .collect(),
+                }),
+            })
+        }
+        QUERY_VALIDATOR_OUTSTANDING_REWARDS => {
+            let request =
+                <ProtoQueryValidatorOutstandingRewardsRequest as prost::Message>::decode(data)?;
+            let validator = api.addr_validate(&request.validator_address)?;
+            let rewards = distribution.validator_outstanding_rewards(storage, block, &validator)?;
+            prost::Message::encode_to_vec(&ProtoQueryValidatorOutstandingRewardsResponse {
+                rewards: Some(ProtoValidatorOutstandingRewards {
+                    rewards: rewards
+                        .into_iter()
+                        .map(|c| ProtoDecCoin {
+                            denom: c.denom,
+                            // encoded like `LegacyDec`, with 18 decimal places
+                            amount: c.amount.atomics().to_string(),
+                        })
+                        .collect(),
+                }),
+            })
+        }
+        QUERY_DELEGATOR_WITHDRAW_ADDRESS => {
+            let request =
+                <ProtoQueryDelegatorWithdrawAddressRequest as prost::Message>::decode(data)?;
+            let delegator = api.addr_validate(&request.delegator_address)?;
+            let withdraw_address = distribution.delegator_withdraw_address(storage, &delegator)?;
+            prost::Message::encode_to_vec(&ProtoQueryDelegatorWithdrawAddressResponse {
+                withdraw_address: withdraw_address.into_string(),
+            })
+        }
+        QUERY_COMMUNITY_POOL => {
+            let pool = distribution.community_pool(storage)?;
+            prost::Message::encode_to_vec(&ProtoQueryCommunityPoolResponse {
+                pool: pool
+                    .into_iter()
+                    .map(|c| ProtoDecCoin {
+                        denom: c.denom,
+                        // encoded like `LegacyDec`, with 18 decimal places
+                        amount: Decimal::from_ratio(c.amount, 1u128).atomics().to_string(),
+                    })
+                    .collect(),
+            })
+        }
+        _ => bail!("unsupported distribution query: {}", path),
+    };
+    Ok(response.into())
+}
+
+/// Returns the response to the query of the distribution module.
+#[cfg(feature = "cosmwasm_2_0")]
+pub(crate) fn query_distribution(
+    api: &dyn Api,
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    request: DistributionQuery,
+) -> AnyResult<Binary> {
+    let distribution = DistributionKeeper::new();
+    let to_dec_coins = |reward: Coin| {
+        if reward.amount.is_zero() {
+            vec![]
+        } else {
+            vec![DecCoin::new(
+                Decimal::from_ratio(reward.amount, 1u128),
+                reward.denom,
+            )]
+        }
+    };
+    match request {
+        DistributionQuery::DelegatorWithdrawAddress { delegator_address } => {
+            let delegator = api.addr_validate(&delegator_address)?;
+            let withdraw_address = distribution.delegator_withdraw_address(storage, &delegator)?;
+            Ok(to_json_binary(&DelegatorWithdrawAddressResponse::new(
+                withdraw_address,
+            ))?)
+        }
+        DistributionQuery::DelegationRewards {
+            delegator_address,
+            validator_address,
+        } => {
+            let delegator = api.addr_validate(&delegator_address)?;
+            let validator = api.addr_validate(&validator_address)?;
+            let Some(reward) =
+                StakeKeeper::new().get_rewards(storage, block, &delegator, &validator)?
+            else {
+                // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/distribution/keeper/grpc_query.go#L196-L199
+                bail!("delegation does not exist");
+            };
+            Ok(to_json_binary(&DelegationRewardsResponse::new(
+                to_dec_coins(reward),
+            ))?)
+        }
+        DistributionQuery::DelegationTotalRewards { delegator_address } => {
+            let delegator = api.addr_validate(&delegator_address)?;
+            let rewards = distribution.delegation_rewards(storage, block, &delegator)?;
+            let total = rewards
+                .iter()
+                .fold(NativeBalance::default(), |total, (_, reward)| {
+                    total + NativeBalance(vec![reward.clone()])
+                });
+            Ok(to_json_binary(&DelegationTotalRewardsResponse::new(
+                rewards
+                    .into_iter()
+                    .map(|(validator, reward)| {
+                        DelegatorReward::new(validator.into_string(), to_dec_coins(reward))
+                    })
+                    .collect(),
+                total
+                    .into_vec()
+                    .into_iter()
+                    .flat_map(to_dec_coins)
+                    .collect(),
+            ))?)
+        }
+        DistributionQuery::DelegatorValidators { delegator_address } => {
+            let delegator = api.addr_validate(&delegator_address)?;
+            let validators = distribution
+                .delegation_rewards(storage, block, &delegator)?
+                .into_iter()
+                .map(|(validator, _)| validator.into_string())
+                .collect();
+            Ok(to_json_binary(&DelegatorValidatorsResponse::new(
+                validators,
+            ))?)
+        }
+        other => bail!("unsupported distribution query: {:?}", other),
+    }
+}
+
+/// Returns `true` when the gRPC query with specified path is answered by the staking module.
+pub(crate) fn is_staking_grpc_query(path: &str) -> bool {
+    [
+        QUERY_DELEGATOR_UNBONDING_DELEGATIONS,
+        QUERY_UNBONDING_DELEGATION,
+        QUERY_DELEGATOR_DELEGATIONS,
+        QUERY_VALIDATOR_DELEGATIONS,
+        QUERY_REDELEGATIONS,
+        QUERY_STAKING_PARAMS,
+        QUERY_HISTORICAL_INFO,
+        QUERY_VALIDATOR,
+        QUERY_VALIDATORS,
+    ]
+    .contains(&path)
+}
+
+/// Returns the protobuf-encoded response to the gRPC query of the staking module.
+///
+/// Delegations and unbonding delegations are paginated by validator or delegator address,
+/// redelegations are not paginated, all of them are returned in a single page.
+pub(crate) fn query_staking_grpc(
+    api: &dyn Api,
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    path: &str,
+    data: &[u8],
+) -> AnyResult<Binary> {
+    let staking = StakeKeeper::new();
+    let response = match path {
+        QUERY_DELEGATOR_UNBONDING_DELEGATIONS => {
+            let request =
+                <ProtoQueryDelegatorUnbondingDelegationsRequest as prost::Message>::decode(data)?;
+            let delegator = api.addr_validate(&request.delegator_addr)?;
+            let entries = staking.unbonding_entries(storage, &delegator)?;
+            let (unbonding_responses, page) = paginate(
+                to_proto_unbonding_delegations(entries)
+                    .into_iter()
+                    .map(|unbonding| (unbonding.validator_address.clone().into_bytes(), unbonding))
+                    .collect(),
+                request.pagination,
+            );
+            prost::Message::encode_to_vec(&ProtoQueryDelegatorUnbondingDelegationsResponse {
+                unbonding_responses,
+                pagination: Some(page),
+            })
+        }
+        QUERY_DELEGATOR_DELEGATIONS => {
+            let request = <ProtoQueryDelegatorDelegationsRequest as prost::Message>::decode(data)?;
+            let delegator = api.addr_validate(&request.delegator_addr)?;
+            let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+            let bonded_denom = StakeKeeper::get_staking_info(&staking_storage)?.bonded_denom;
+            let delegations = STAKES
+                .prefix(&delegator)
+                .range(&staking_storage, None, None, Order::Ascending)
+                .map(|entry| {
+                    entry.map(|(validator, shares)| {
+                        let delegation =
+                            to_proto_delegation(&delegator, &validator, &shares, &bonded_denom);
+                        (validator.as_bytes().to_vec(), delegation)
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            let (delegation_responses, page) = paginate(delegations, request.pagination);
+            prost::Message::encode_to_vec(&ProtoQueryDelegationsResponse {
+                delegation_responses,
+                pagination: Some(page),
+            })
+        }
+        QUERY_VALIDATOR_DELEGATIONS => {
+            let request = <ProtoQueryValidatorDelegationsRequest as prost::Message>::decode(data)?;
+            let validator = api.addr_validate(&request.validator_addr)?;
+            let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+            let bonded_denom = StakeKeeper::get_staking_info(&staking_storage)?.bonded_denom;
+            let stakers = VALIDATOR_INFO
+                .may_load(&staking_storage, &validator)?
+                .map(|validator_info| validator_info.stakers)
+                .unwrap_or_default();
+            let delegations = stakers
+                .into_iter()
+                .map(|delegator| {
+                    let shares = STAKES.load(&staking_storage, (&delegator, &validator))?;
+                    let delegation =
+                        to_proto_delegation(&delegator, &validator, &shares, &bonded_denom);
+                    Ok((delegator.as_bytes().to_vec(), delegation))
+                })
+                .collect::<AnyResult<Vec<_>>>()?;
+            let (delegation_responses, page) = paginate(delegations, request.pagination);
+            prost::Message::encode_to_vec(&ProtoQueryDelegationsResponse {
+                delegation_responses,
+                pagination: Some(page),
+            })
+        }
+        QUERY_UNBONDING_DELEGATION => {
+            let request = <ProtoQueryUnbondingDelegationRequest as prost::Message>::decode(data)?;
+            let delegator = api.addr_validate(&request.delegator_addr)?;
+            let validator = api.addr_validate(&request.validator_addr)?;
+            let entries = staking
+                .unbonding_entries(storage, &delegator)?
+                .into_iter()
+                .filter(|entry| entry.validator == validator)
+                .collect::<Vec<_>>();
+            if entries.is_empty() {
+                bail!(
+                    "unbonding delegation with delegator {} not found for validator {}",
+                    delegator,
+                    validator
+                );
+            }
+            prost::Message::encode_to_vec(&ProtoQueryUnbondingDelegationResponse {
+                unbond: to_proto_unbonding_delegations(entries).pop(),
+            })
+        }
+        QUERY_HISTORICAL_INFO => return query_historical_info(storage, block, data),
+        QUERY_VALIDATOR => {
+            let request = <ProtoQueryValidatorRequest as prost::Message>::decode(data)?;
+            let validator = api.addr_validate(&request.validator_addr)?;
+            let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+            let Some(validator_obj) = VALIDATOR_MAP.may_load(&staking_storage, &validator)? else {
+                bail!("validator {} not found", validator);
+            };
+            let bonded = StakeKeeper::bonded_validators(&staking_storage)?
+                .iter()
+                .any(|(v, _)| v.address == validator_obj.address);
+            prost::Message::encode_to_vec(&ProtoQueryValidatorResponse {
+                validator: Some(to_proto_validator(&staking_storage, validator_obj, bonded)?),
+            })
+        }
+        QUERY_VALIDATORS => {
+            let request = <ProtoQueryValidatorsRequest as prost::Message>::decode(data)?;
+            let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+            let bonded = StakeKeeper::bonded_validators(&staking_storage)?
+                .into_iter()
+                .map(|(validator, _)| validator.address)
+                .collect::<BTreeSet<_>>();
+            let validators = VALIDATOR_MAP
+                .range(&staking_storage, None, None, Order::Ascending)
+                .map(|entry| {
+                    let (address, validator_obj) = entry?;
+                    let is_bonded = bonded.contains(address.as_str());
+                    let validator = to_proto_validator(&staking_storage, validator_obj, is_bonded)?;
+                    Ok((address.as_bytes().to_vec(), validator))
+                })
+                .collect::<AnyResult<Vec<_>>>()?
+                .into_iter()
+                .filter(|(_, validator)| {
+                    request.status.is_empty()
+                        || BOND_STATUSES[validator.status as usize] == request.status
+                })
+                .collect();
+            let (validators, page) = paginate(validators, request.pagination);
+            prost::Message::encode_to_vec(&ProtoQueryValidatorsResponse {
+                validators,
+                pagination: Some(page),
+            })
+        }
+        QUERY_STAKING_PARAMS => {
+            let params = StakeKeeper::new().staking_info(storage)?;
+            prost::Message::encode_to_vec(&ProtoQueryStakingParamsResponse {
+                params: Some(ProtoStakingParams {
+                    unbonding_time: Some(ProtoDuration {
+                        seconds: params.unbonding_time as i64,
+                        nanos: 0,
+                    }),
+                    max_validators: params.max_validators,
+                    max_entries: params.max_entries,
+                    historical_entries: params.historical_entries,
+                    bond_denom: params.bonded_denom,
+                    // encoded like `LegacyDec`, with 18 decimal places
+                    min_commission_rate: params.min_commission_rate.atomics().to_string(),
+                }),
+            })
+        }
+        QUERY_REDELEGATIONS => {
+            let request = <ProtoQueryRedelegationsRequest as prost::Message>::decode(data)?;
+            let validate = |addr: &str| -> AnyResult<Option<Addr>> {
+                Ok(if addr.is_empty() {
+                    None
+                } else {
+                    Some(api.addr_validate(addr)?)
+                })
+            };
+            let delegator = validate(&request.delegator_addr)?;
+            let src_validator = validate(&request.src_validator_addr)?;
+            let dst_validator = validate(&request.dst_validator_addr)?;
+            if delegator.is_none() && src_validator.is_none() {
+                bail!("delegator or source validator address must be specified");
+            }
+            let entries = staking
+                .redelegations(storage)?
+                .into_iter()
+                .filter(|entry| delegator.as_ref().is_none_or(|a| entry.delegator == a))
+                .filter(|entry| {
+                    src_validator
+                        .as_ref()
+                        .is_none_or(|a| entry.src_validator == a)
+                })
+                .filter(|entry| {
+                    dst_validator
+                        .as_ref()
+                        .is_none_or(|a| entry.dst_validator == a)
+                })
+                .collect::<Vec<_>>();
+            if let (Some(delegator), Some(src_validator), Some(_), true) = (
+                &delegator,
+                &src_validator,
+                &dst_validator,
+                entries.is_empty(),
+            ) {
+                bail!(
+                    "redelegation not found for delegator address {} from validator address {}",
+                    delegator,
+                    src_validator
+                );
+            }
+            prost::Message::encode_to_vec(&ProtoQueryRedelegationsResponse {
+                redelegation_responses: to_proto_redelegations(entries),
+            })
+        }
+        _ => bail!("unsupported staking query: {}", path),
+    };
+    Ok(response.into())
+}
+
+/// Formats the block time like `time.RFC3339` in Go, used in events emitted by Cosmos SDK.
+fn format_rfc3339(time: Timestamp) -> String {
+    // see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let seconds = time.seconds();
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds % 86_400 / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Staking privileged action definition.
+///
+/// We need to expand on this, but we will need this to properly test out staking
+#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub enum StakingSudo {
+    /// Slashes the given percentage of the validator's stake.
+    /// For now, you cannot slash retrospectively in tests.
+    Slash {
+        /// Validator's address.
+        validator: String,
+        /// Percentage of the validator's stake.
+        percentage: Decimal,
+    },
+    /// Updates staking parameters, like a parameter change proposal.
+    /// Rewards accrued so far are calculated with the previous parameters.
+    UpdateParams {
+        /// New staking parameters.
+        params: StakingInfo,
+    },
+    /// Adds the validator, like `MsgCreateValidator` without the self-delegation.
+    AddValidator {
+        /// Added validator.
+        validator: Validator,
+    },
+    /// Changes the commission rate of the validator, like `MsgEditValidator`.
+    /// Rewards accrued so far are calculated with the previous commission rate.
+    EditValidator {
+        /// Validator's address.
+        validator: String,
+        /// New commission rate, limited by the validator's maximum commission rate
+        /// and maximum change rate.
+        commission: Decimal,
+    },
+    /// Changes the description of the validator, like `MsgEditValidator`.
+    /// Fields set to `[do-not-modify]` keep their current value.
+    EditDescription {
+        /// Validator's address.
+        validator: String,
+        /// Edited description.
+        description: ValidatorDescription,
+    },
+    /// Sets the way the validator accrues rewards, `None` restores the interest rate
+    /// from [StakingInfo]. Rewards accrued so far are calculated the previous way.
+    SetRewardAccrual {
+        /// Validator's address.
+        validator: String,
+        /// Reward accrual specific to the validator.
+        accrual: Option<RewardAccrual>,
+    },
+    /// Removes the validator from the validator set.
+    ///
+    /// Like when the validator is unbonded and removed on chain, all delegations
+    /// to the validator are forcibly undelegated and released after the unbonding time.
+    /// Rewards accrued by delegators and the validator's commission are withdrawn.
+    RemoveValidator {
+        /// Validator's address.
+        validator: String,
+    },
+    /// Sets the minimum self-delegation of the validator, like `min_self_delegation`
+    /// of `MsgCreateValidator`. The validator is jailed immediately when its operator
+    /// delegated less tokens.
+    SetMinSelfDelegation {
+        /// Validator's address.
+        validator: String,
+        /// Minimum number of tokens delegated by the validator's operator.
+        min_self_delegation: Uint128,
+    },
+    /// Jails the validator until the specified block time.
+    ///
+    /// Jailed validator is not bonded: it earns no rewards and is not returned
+    /// by the query for all validators. Delegations to the jailed validator are kept,
+    /// the validator is unjailed with `MsgUnjail` signed by its operator or [StakingSudo::Unjail].
+    Jail {
+        /// Validator's address.
+        validator: String,
+        /// Block time until which the validator can not be unjailed.
+        jailed_until: Timestamp,
+    },
+    /// Unjails the validator, like `MsgUnjail` signed by the validator's operator.
+    /// Fails when the validator is not jailed, the jail time did not pass yet
+    /// or the validator was jailed forever for double signing.
+    Unjail {
+        /// Validator's address.
+        validator: String,
+    },
+    /// Slashes the validator for the downtime and jails it, using [SlashingParams].
+    SlashForDowntime {
+        /// Validator's address.
+        validator: String,
+    },
+    /// Slashes the validator for double signing and jails it forever, using [SlashingParams].
+    SlashForDoubleSign {
+        /// Validator's address.
+        validator: String,
+    },
+}
+
+/// Messages of the liquid staking module, converting delegations into transferable share tokens.
+#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub enum LiquidStakingMsg {
+    /// Converts the sender's delegation into share tokens minted to the owner,
+    /// like `MsgTokenizeShares`.
+    TokenizeShares {
+        /// Validator's address.
+        validator: String,
+        /// Tokenized amount of the delegation.
+        amount: Coin,
+        /// Address of the owner of the tokenize share record and share tokens.
+        owner: String,
+    },
+    /// Burns the sender's share tokens and delegates the corresponding stake
+    /// back to the sender, like `MsgRedeemTokensForShares`.
+    RedeemTokensForShares {
+        /// Redeemed share tokens.
+        amount: Coin,
+    },
+    /// Transfers the ownership of the tokenize share record, like `MsgTransferTokenizeShareRecord`.
+    TransferTokenizeShareRecord {
+        /// Identifier of the record.
+        record_id: u64,
+        /// Address of the new owner.
+        new_owner: String,
+    },
+}
+
+/// Message sent to the `sudo` entry-point of the contract registered
+/// with [add_staking_hook](StakeKeeper::add_staking_hook), like staking hooks in Cosmos SDK.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingHookMsg {
+    /// The validator was created.
+    AfterValidatorCreated {
+        /// Validator's address.
+        validator: String,
+    },
+    /// The validator was removed.
+    AfterValidatorRemoved {
+        /// Validator's address.
+        validator: String,
+    },
+    /// The delegation was created or changed.
+    AfterDelegationModified {
+        /// Delegator's address.
+        delegator: String,
+        /// Validator's address.
+        validator: String,
+    },
+    /// The whole delegation is about to be removed.
+    BeforeDelegationRemoved {
+        /// Delegator's address.
+        delegator: String,
+        /// Validator's address.
+        validator: String,
+    },
+    /// The validator is about to be slashed.
+    BeforeValidatorSlashed {
+        /// Validator's address.
+        validator: String,
+        /// Slashed fraction of the validator's stake.
+        fraction: Decimal,
+    },
+}
+
+/// A trait defining a behavior of the stake keeper.
+///
+/// Manages staking operations, vital for testing contracts in proof-of-stake (PoS) blockchain environments.
+/// This trait simulates staking behaviors, including delegation, validator operations, and reward mechanisms.
+pub trait Staking: Module<ExecT = StakingMsg, QueryT = StakingQuery, SudoT = StakingSudo> {
+    /// This is called from the end blocker (`update_block` / `set_block`) to process the
+    /// staking queue. Needed because unbonding has a waiting time.
+    /// If you're implementing a dummy staking module, this can be a no-op.
+    fn process_queue<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+    ) -> AnyResult<AppResponse> {
+        Ok(AppResponse::default())
+    }
+
+    /// This is called for the liquid staking messages, like `MsgTokenizeShares`,
+    /// signed by the sender.
+    /// If you're implementing a dummy staking module, this can fail.
+    fn execute_liquid_staking<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _sender: Addr,
+        _msg: LiquidStakingMsg,
+    ) -> AnyResult<AppResponse> {
+        bail!("liquid staking is not supported")
+    }
+
+    /// This is called for `MsgCreateValidator` signed by the validator's operator,
+    /// to add the validator and delegate its self-delegation.
+    /// If you're implementing a dummy staking module, this can fail.
+    #[allow(clippy::too_many_arguments)]
+    fn create_validator<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _operator: Addr,
+        _validator: Validator,
+        _value: Coin,
+        _min_self_delegation: Uint128,
+    ) -> AnyResult<AppResponse> {
+        bail!("creating validators is not supported")
+    }
+
+    /// This is called for `MsgEditValidator` signed by the validator's operator,
+    /// to edit the validator's description and commission rate.
+    /// Each of them is kept when not specified.
+    /// If you're implementing a dummy staking module, this can fail.
+    #[allow(clippy::too_many_arguments)]
+    fn edit_validator<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _operator: Addr,
+        _description: Option<ValidatorDescription>,
+        _commission: Option<Decimal>,
+    ) -> AnyResult<AppResponse> {
+        bail!("editing validators is not supported")
+    }
+
+    /// This is called for `MsgUnjail` signed by the validator's operator.
+    /// If you're implementing a dummy staking module, this can fail.
+    fn unjail<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _operator: Addr,
+    ) -> AnyResult<AppResponse> {
+        bail!("unjailing validators is not supported")
+    }
+}
+
+/// A trait defining a behavior of the distribution keeper.
+pub trait Distribution: Module<ExecT = DistributionMsg, QueryT = Empty, SudoT = Empty> {
+    /// This is called for `MsgWithdrawValidatorCommission` signed by the validator's operator,
+    /// to send the commission accrued by the validator to the operator's account.
+    /// If you're implementing a dummy distribution module, this can fail.
+    fn withdraw_validator_commission<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _validator: Addr,
+    ) -> AnyResult<AppResponse> {
+        bail!("withdrawing validator commission is not supported")
+    }
+
+    /// This is called for `MsgFundCommunityPool` and [DistributionMsg::FundCommunityPool],
+    /// to move coins from the depositor's account to the community pool.
+    /// If you're implementing a dummy distribution module, this can fail.
+    fn fund_community_pool<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _depositor: Addr,
+        _amount: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        bail!("funding the community pool is not supported")
+    }
+
+    /// This is called for [DistributionSudo::CommunityPoolSpend],
+    /// to send coins from the community pool to the recipient.
+    /// If you're implementing a dummy distribution module, this can fail.
+    fn community_pool_spend<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _recipient: Addr,
+        _amount: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        bail!("spending from the community pool is not supported")
+    }
+}
+
+/// Privileged actions of the distribution module.
+#[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub enum DistributionSudo {
+    /// Sends coins from the community pool to the recipient,
+    /// like `MsgCommunityPoolSpend` executed by the governance module.
+    CommunityPoolSpend {
+        /// Recipient's address.
+        recipient: String,
+        /// Coins sent from the community pool.
+        amount: Vec<Coin>,
+    },
+}
+
+/// A structure representing a default stake keeper.
+#[derive(Clone)]
+pub struct StakeKeeper {
+    /// Module address of a default stake keeper.
+    module_addr: Addr,
+}
+
+impl Default for StakeKeeper {
+    /// Creates a new stake keeper with default settings.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StakeKeeper {
+    /// Creates a new stake keeper with default module address.
+    pub fn new() -> Self {
+        StakeKeeper {
+            // The address of the staking module. This holds all staked tokens.
+            module_addr: Addr::unchecked("staking_module"),
+        }
+    }
+
+    /// Provides some general parameters to the stake keeper
+    pub fn setup(&self, storage: &mut dyn Storage, staking_info: StakingInfo) -> AnyResult<()> {
+        let mut storage = prefixed(storage, NAMESPACE_STAKING);
+        STAKING_INFO.save(&mut storage, &staking_info)?;
+        Ok(())
+    }
+
+    /// Returns general staking parameters, provided with [setup](Self::setup)
+    /// or changed with [StakingSudo::UpdateParams].
+    pub fn staking_info(&self, storage: &dyn Storage) -> AnyResult<StakingInfo> {
+        let storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Self::get_staking_info(&storage)
+    }
+
+    /// Sets parameters of the slashing module.
+    pub fn setup_slashing(
+        &self,
+        storage: &mut dyn Storage,
+        params: SlashingParams,
+    ) -> AnyResult<()> {
+        let mut storage = prefixed(storage, NAMESPACE_STAKING);
+        SLASHING_PARAMS.save(&mut storage, &params)?;
+        Ok(())
+    }
+
+    /// Returns parameters of the slashing module.
+    pub fn slashing_params(&self, storage: &dyn Storage) -> AnyResult<SlashingParams> {
+        let storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Ok(SLASHING_PARAMS.may_load(&storage)?.unwrap_or_default())
+    }
+
+    /// Sets the algorithm distributing rewards among delegators,
+    /// it can be set only before any validator is added.
+    pub fn setup_reward_distribution(
+        &self,
+        storage: &mut dyn Storage,
+        distribution: RewardDistribution,
+    ) -> AnyResult<()> {
+        let mut storage = prefixed(storage, NAMESPACE_STAKING);
+        if VALIDATOR_INFO
+            .keys(&storage, None, None, Order::Ascending)
+            .next()
+            .is_some()
+        {
+            bail!("reward distribution can be set only before any validator is added");
+        }
+        REWARD_DISTRIBUTION.save(&mut storage, &distribution)?;
+        Ok(())
+    }
+
+    /// Returns the algorithm distributing rewards among delegators,
+    /// [RewardDistribution::Linear] by default.
+    pub fn reward_distribution(&self, storage: &dyn Storage) -> AnyResult<RewardDistribution> {
+        let storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Self::get_reward_distribution(&storage)
+    }
+
+    /// Returns the block time until which the validator is jailed,
+    /// `None` when the validator is not jailed.
+    pub fn jailed_until(
+        &self,
+        storage: &dyn Storage,
+        validator: &Addr,
+    ) -> AnyResult<Option<Timestamp>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let validator_info = VALIDATOR_INFO
+            .may_load(&staking_storage, validator)?
+            .ok_or_else(|| anyhow!("validator does not exist"))?;
+        Ok(validator_info.jailed_until)
+    }
+
+    /// Returns the description of the validator,
+    /// set with [StakingSudo::EditDescription] or `MsgEditValidator`.
+    pub fn validator_description(
+        &self,
+        storage: &dyn Storage,
+        validator: &Addr,
+    ) -> AnyResult<ValidatorDescription> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let validator_info = VALIDATOR_INFO
+            .may_load(&staking_storage, validator)?
+            .ok_or_else(|| anyhow!("validator does not exist"))?;
+        Ok(validator_info.description)
+    }
+
+    /// Returns the minimum self-delegation of the validator,
+    /// set with [StakingSudo::SetMinSelfDelegation] or `MsgCreateValidator`.
+    pub fn min_self_delegation(
+        &self,
+        storage: &dyn Storage,
+        validator: &Addr,
+    ) -> AnyResult<Uint128> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let validator_info = VALIDATOR_INFO
+            .may_load(&staking_storage, validator)?
+            .ok_or_else(|| anyhow!("validator does not exist"))?;
+        Ok(validator_info.min_self_delegation)
+    }
+
+    /// Returns pending unbondings of the delegator, ordered by completion time.
+    ///
+    /// Tokens are released to the delegator at the end of the first block
+    /// with time not earlier than the completion time.
+    pub fn unbonding_entries(
+        &self,
+        storage: &dyn Storage,
+        delegator: &Addr,
+    ) -> AnyResult<Vec<UnbondingEntry>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let bonded_denom = Self::get_staking_info(&staking_storage)?.bonded_denom;
+        let unbonding_queue = UNBONDING_QUEUE
+            .may_load(&staking_storage)?
+            .unwrap_or_default();
+        Ok(unbonding_queue
+            .into_iter()
+            .filter(|unbonding| unbonding.delegator == delegator)
+            .map(|unbonding| UnbondingEntry {
+                delegator: unbonding.delegator,
+                validator: unbonding.validator,
+                creation_height: unbonding.creation_height,
+                completion_time: unbonding.payout_at,
+                initial_balance: coin(unbonding.initial_amount.u128(), &bonded_denom),
+                balance: coin(unbonding.amount.u128(), &bonded_denom),
+            })
+            .sorted_by_key(|entry| entry.completion_time)
+            .collect())
+    }
+
+    /// Returns pending redelegations of the delegator, ordered by completion time.
+    pub fn redelegation_entries(
+        &self,
+        storage: &dyn Storage,
+        delegator: &Addr,
+    ) -> AnyResult<Vec<RedelegationEntry>> {
+        Ok(self
+            .redelegations(storage)?
+            .into_iter()
+            .filter(|entry| entry.delegator == delegator)
+            .collect())
+    }
+
+    /// Returns all tokenize share records, ordered by identifier.
+    pub fn tokenize_share_records(
+        &self,
+        storage: &dyn Storage,
+    ) -> AnyResult<Vec<TokenizeShareRecord>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Ok(TOKENIZE_SHARE_RECORDS
+            .range(&staking_storage, None, None, Order::Ascending)
+            .map(|entry| entry.map(|(_, record)| record))
+            .collect::<StdResult<Vec<_>>>()?)
+    }
+
+    /// Registers the contract notified about staking events, like delegation changes
+    /// and slashing of validators.
+    ///
+    /// The contract's `sudo` entry-point is called with [StakingHookMsg],
+    /// the staking operation is reverted when the contract returns an error.
+    pub fn add_staking_hook(&self, storage: &mut dyn Storage, contract: &Addr) -> AnyResult<()> {
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        STAKING_HOOKS.save(&mut staking_storage, contract, &Empty {})?;
+        Ok(())
+    }
+
+    /// Unregisters the contract notified about staking events.
+    pub fn remove_staking_hook(&self, storage: &mut dyn Storage, contract: &Addr) -> AnyResult<()> {
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        STAKING_HOOKS.remove(&mut staking_storage, contract);
+        Ok(())
+    }
+
+    /// Returns contracts notified about staking events, ordered by address.
+    pub fn staking_hooks(&self, storage: &dyn Storage) -> AnyResult<Vec<Addr>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Ok(STAKING_HOOKS
+            .keys(&staking_storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?)
+    }
+
+    /// Notifies contracts registered for staking hooks, returns events emitted by the contracts.
+    fn call_staking_hooks<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msgs: Vec<StakingHookMsg>,
+    ) -> AnyResult<Vec<Event>> {
+        let mut events = vec![];
+        let contracts = self.staking_hooks(storage)?;
+        for msg in msgs {
+            for contract in &contracts {
+                let sudo_msg = WasmSudo::new(contract, &msg)?;
+                let res = router.sudo(api, storage, block, sudo_msg.into())?;
+                events.extend(res.events);
+            }
+        }
+        Ok(events)
+    }
+
+    /// Notifies contracts registered for staking hooks about the validator being slashed,
+    /// returns events emitted by the contracts.
+    fn call_slashing_hooks<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        validator: &Addr,
+        fraction: Decimal,
+    ) -> AnyResult<Vec<Event>> {
+        let hook_msg = StakingHookMsg::BeforeValidatorSlashed {
+            validator: validator.to_string(),
+            fraction,
+        };
+        self.call_staking_hooks(api, storage, router, block, vec![hook_msg])
+    }
+
+    /// Increases the delegation by the amount and notifies contracts registered
+    /// for staking hooks, returns events emitted by the contracts.
+    fn add_stake_with_hooks<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        delegator: &Addr,
+        validator: &Addr,
+        amount: Coin,
+    ) -> AnyResult<Vec<Event>> {
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        self.add_stake(
+            api,
+            &mut staking_storage,
+            block,
+            delegator,
+            validator,
+            amount,
+        )?;
+        let hook_msg = StakingHookMsg::AfterDelegationModified {
+            delegator: delegator.to_string(),
+            validator: validator.to_string(),
+        };
+        self.call_staking_hooks(api, storage, router, block, vec![hook_msg])
+    }
+
+    /// Decreases the delegation by the amount and notifies contracts registered
+    /// for staking hooks, returns events emitted by the contracts and by jailing the validator.
+    ///
+    /// Like in Cosmos SDK, contracts are notified before the whole delegation is removed,
+    /// or after the delegation was modified otherwise. The validator is jailed
+    /// when its self-delegation drops below the minimum or no delegations are left.
+    fn remove_stake_with_hooks<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        delegator: &Addr,
+        validator: &Addr,
+        amount: Coin,
+    ) -> AnyResult<Vec<Event>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let removed = STAKES
+            .may_load(&staking_storage, (delegator, validator))?
+            .is_some_and(|shares| shares.stake <= Decimal::from_ratio(amount.amount, 1u128));
+        let mut events = vec![];
+        if removed {
+            let hook_msg = StakingHookMsg::BeforeDelegationRemoved {
+                delegator: delegator.to_string(),
+                validator: validator.to_string(),
+            };
+            events = self.call_staking_hooks(api, storage, router, block, vec![hook_msg])?;
+        }
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        self.remove_stake(
+            api,
+            &mut staking_storage,
+            block,
+            delegator,
+            validator,
+            amount,
+        )?;
+        let jail_events = self.jail_if_undelegated(api, &mut staking_storage, block, validator)?;
+        if !removed {
+            let hook_msg = StakingHookMsg::AfterDelegationModified {
+                delegator: delegator.to_string(),
+                validator: validator.to_string(),
+            };
+            events = self.call_staking_hooks(api, storage, router, block, vec![hook_msg])?;
+        }
+        events.extend(jail_events);
+        Ok(events)
+    }
+
+    /// Returns all pending redelegations, ordered by completion time.
+    fn redelegations(&self, storage: &dyn Storage) -> AnyResult<Vec<RedelegationEntry>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let bonded_denom = Self::get_staking_info(&staking_storage)?.bonded_denom;
+        let redelegation_queue = REDELEGATION_QUEUE
+            .may_load(&staking_storage)?
+            .unwrap_or_default();
+        Ok(redelegation_queue
+            .into_iter()
+            .map(|redelegation| RedelegationEntry {
+                delegator: redelegation.delegator,
+                src_validator: redelegation.src_validator,
+                dst_validator: redelegation.dst_validator,
+                creation_height: redelegation.creation_height,
+                completion_time: redelegation.completion_time,
+                balance: coin(redelegation.amount.u128(), &bonded_denom),
+            })
+            .sorted_by_key(|entry| entry.completion_time)
+            .collect())
+    }
+
+    /// Add a new validator available for staking
+    pub fn add_validator(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        validator: Validator,
+    ) -> AnyResult<()> {
+        let mut storage = prefixed(storage, NAMESPACE_STAKING);
+
+        let val_addr = api.addr_validate(&validator.address)?;
+        if VALIDATOR_MAP.may_load(&storage, &val_addr)?.is_some() {
+            bail!(
+                "Cannot add validator {}, since a validator with that address already exists",
+                val_addr
+            );
+        }
+        Self::validate_min_commission(&storage, validator.commission)?;
+
+        VALIDATOR_MAP.save(&mut storage, &val_addr, &validator)?;
+        VALIDATORS.push_back(&mut storage, &validator)?;
+        VALIDATOR_INFO.save(&mut storage, &val_addr, &ValidatorInfo::new(block))?;
+        Ok(())
+    }
+
+    /// Returns all validators with bonded tokens.
+    pub(crate) fn validator_set(storage: &dyn Storage) -> AnyResult<Vec<ValidatorStake>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Ok(Self::bonded_validators(&staking_storage)?
+            .into_iter()
+            .filter(|(_, tokens)| !tokens.is_zero())
+            .map(|(validator, tokens)| ValidatorStake {
+                address: validator.address,
+                tokens,
+            })
+            .collect())
+    }
+
+    /// Returns the delegator's stake at every validator.
+    pub(crate) fn delegator_stakes(
+        storage: &dyn Storage,
+        delegator: &Addr,
+    ) -> AnyResult<Vec<(Addr, Decimal)>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Ok(STAKES
+            .prefix(delegator)
+            .range(&staking_storage, None, None, Order::Ascending)
+            .map(|entry| entry.map(|(validator, shares)| (validator, shares.stake)))
+            .collect::<StdResult<_>>()?)
+    }
+
+    /// Returns validators which are not jailed, limited to `max_validators` with the highest
+    /// stake, in the order they were added, together with their bonded tokens.
+    fn bonded_validators(staking_storage: &dyn Storage) -> AnyResult<Vec<(Validator, Uint128)>> {
+        let max_validators = Self::get_staking_info(staking_storage)?.max_validators as usize;
+        let mut validators = vec![];
+        for validator in VALIDATORS.iter(staking_storage)? {
+            let validator = validator?;
+            let validator_info =
+                VALIDATOR_INFO.load(staking_storage, &Addr::unchecked(&validator.address))?;
+            if validator_info.jailed_until.is_none() {
+                validators.push((validator, validator_info.bonded_stake()));
+            }
+        }
+        if validators.len() > max_validators {
+            // the stable sort keeps validators with the same stake in the order they were added
+            let bonded = validators
+                .iter()
+                .enumerate()
+                .sorted_by(|(_, (_, a)), (_, (_, b))| b.cmp(a))
+                .take(max_validators)
+                .map(|(index, _)| index)
+                .collect::<BTreeSet<_>>();
+            validators = validators
+                .into_iter()
+                .enumerate()
+                .filter(|(index, _)| bonded.contains(index))
+                .map(|(_, validator)| validator)
+                .collect();
+        }
+        Ok(validators)
+    }
+
+    /// Checks that the commission is not lower than the minimum commission rate.
+    fn validate_min_commission(
+        staking_storage: &dyn Storage,
+        commission: Decimal,
+    ) -> AnyResult<()> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/staking/keeper/msg_server.go#L54-L56
+        if commission < Self::get_staking_info(staking_storage)?.min_commission_rate {
+            bail!("commission cannot be less than min rate");
+        }
+        Ok(())
+    }
+
+    /// Returns the block and validator set at the end of the block with specified height.
+    pub(crate) fn historical_info(
+        storage: &dyn Storage,
+        height: u64,
+    ) -> AnyResult<Option<HistoricalInfo>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        Ok(HISTORICAL_INFO.may_load(&staking_storage, height)?)
+    }
+
+    /// Saves the block and the current validator set, prunes the oldest entries.
+    fn track_historical_info(&self, storage: &mut dyn Storage, block: &BlockInfo) -> AnyResult<()> {
+        let info = HistoricalInfo {
+            block: block.clone(),
+            validators: Self::validator_set(storage)?,
+        };
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        let historical_entries = Self::get_staking_info(&staking_storage)?.historical_entries;
+        if historical_entries > 0 {
+            HISTORICAL_INFO.save(&mut staking_storage, block.height, &info)?;
+        }
+        // the number of entries may have been lowered, so all outdated entries are pruned
+        let oldest = (block.height + 1).saturating_sub(historical_entries as u64);
+        let outdated = HISTORICAL_INFO
+            .keys(
+                &staking_storage,
+                None,
+                Some(Bound::exclusive(oldest)),
+                Order::Ascending,
+            )
+            .collect::<StdResult<Vec<_>>>()?;
+        for height in outdated {
+            HISTORICAL_INFO.remove(&mut staking_storage, height);
+        }
+        Ok(())
+    }
+
+    fn get_staking_info(staking_storage: &dyn Storage) -> AnyResult<StakingInfo> {
+        Ok(STAKING_INFO.may_load(staking_storage)?.unwrap_or_default())
+    }
+
+    fn get_reward_distribution(staking_storage: &dyn Storage) -> AnyResult<RewardDistribution> {
+        Ok(REWARD_DISTRIBUTION
+            .may_load(staking_storage)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the rewards of the given delegator at the given validator
+    pub fn get_rewards(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        delegator: &Addr,
+        validator: &Addr,
+    ) -> AnyResult<Option<Coin>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+
+        let validator_obj = match self.get_validator(&staking_storage, validator)? {
+            Some(validator) => validator,
+            None => bail!("validator {} not found", validator),
+        };
+        // calculate rewards using fixed ratio
+        let shares = match STAKES.load(&staking_storage, (delegator, validator)) {
+            Ok(stakes) => stakes,
+            Err(_) => {
+                return Ok(None);
+            }
+        };
+        let validator_info = VALIDATOR_INFO.load(&staking_storage, validator)?;
+
+        Self::get_rewards_internal(
+            &staking_storage,
+            block,
+            &shares,
+            &validator_obj,
+            &validator_info,
+        )
+        .map(Some)
+    }
+
+    fn get_rewards_internal(
+        staking_storage: &dyn Storage,
+        block: &BlockInfo,
+        shares: &Shares,
+        validator: &Validator,
+        validator_info: &ValidatorInfo,
+    ) -> AnyResult<Coin> {
+        let staking_info = Self::get_staking_info(staking_storage)?;
+
+        // calculate missing rewards without updating the validator to reduce rounding errors
+        let (new_validator_rewards, _) = Self::calculate_rewards(
+            block,
+            validator_info,
+            staking_info.apr,
+            validator.commission,
+        );
+
+        // calculate the delegator's share of those
+        let delegator_rewards = Self::delegation_rewards(
+            staking_storage,
+            &Addr::unchecked(&validator.address),
+            validator_info,
+            shares,
+            new_validator_rewards,
+        )?;
+
+        Ok(Coin {
+            denom: staking_info.bonded_denom,
+            amount: Uint128::new(1).mul_floor(delegator_rewards), // multiplying by 1 to convert Decimal to Uint128
+        })
+    }
+
+    /// Calculates the rewards that are due since the last calculation,
+    /// returns rewards of delegators and the validator's commission.
+    fn calculate_rewards(
+        block: &BlockInfo,
+        validator_info: &ValidatorInfo,
+        interest_rate: Decimal,
+        validator_commission: Decimal,
+    ) -> (Decimal, Decimal) {
+        let stake = validator_info.bonded_stake();
+        let interest_rate = match validator_info.reward_accrual {
+            Some(RewardAccrual::PerBlock(provision)) => {
+                let blocks = block
+                    .height
+                    .saturating_sub(validator_info.last_rewards_height);
+                let reward = if stake.is_zero() {
+                    Decimal::zero()
+                } else {
+                    Decimal::from_ratio(provision, 1u128) * Decimal::from_ratio(blocks, 1u128)
+                };
+                let commission = reward * validator_commission;
+                return (reward - commission, commission);
+            }
+            Some(RewardAccrual::Apr(apr)) => apr,
+            None => interest_rate,
+        };
+
+        // calculate time since last update (in seconds)
+        let time_diff = block
+            .time
+            .seconds()
+            .saturating_sub(validator_info.last_rewards_calculation.seconds());
+
+        // using decimal here to reduce rounding error when calling this function a lot
+        let reward = Decimal::from_ratio(stake, 1u128)
+            * interest_rate
+            * Decimal::from_ratio(time_diff, 1u128)
+            / Decimal::from_ratio(60u128 * 60 * 24 * 365, 1u128);
+        let commission = reward * validator_commission;
+
+        (reward - commission, commission)
+    }
+
+    /// Returns the stake delegated to the validator by all stakers. After slashing, the sum
+    /// of fractional stakes can differ from the validator's stake, so rewards are shared
+    /// proportionally to this sum, never distributing more than the validator earned.
+    fn delegated_stake(
+        staking_storage: &dyn Storage,
+        validator: &Addr,
+        validator_info: &ValidatorInfo,
+    ) -> AnyResult<Decimal> {
+        validator_info
+            .stakers
+            .iter()
+            .try_fold(Decimal::zero(), |delegated, staker| {
+                Ok(delegated + STAKES.load(staking_storage, (staker, validator))?.stake)
+            })
+    }
+
+    /// Returns rewards of the delegation including the validator's rewards
+    /// not calculated yet, without truncating them.
+    fn delegation_rewards(
+        staking_storage: &dyn Storage,
+        validator: &Addr,
+        validator_info: &ValidatorInfo,
+        shares: &Shares,
+        new_validator_rewards: Decimal,
+    ) -> AnyResult<Decimal> {
+        Ok(match Self::get_reward_distribution(staking_storage)? {
+            RewardDistribution::Linear => {
+                let delegated = Self::delegated_stake(staking_storage, validator, validator_info)?;
+                shares.rewards + shares.share_of_rewards(delegated, new_validator_rewards)
+            }
+            RewardDistribution::F1 => {
+                let ending_ratio = Self::current_reward_ratio(
+                    staking_storage,
+                    validator,
+                    validator_info,
+                    validator_info.current_rewards + new_validator_rewards,
+                )?;
+                let starting_ratio =
+                    Self::historical_reward_ratio(staking_storage, validator, shares.period)?;
+                shares.rewards + shares.stake * (ending_ratio - starting_ratio)
+            }
+        })
+    }
+
+    /// Returns the cumulative reward ratio at the end of the validator's period,
+    /// zero for periods that never ended.
+    fn historical_reward_ratio(
+        staking_storage: &dyn Storage,
+        validator: &Addr,
+        period: u64,
+    ) -> AnyResult<Decimal> {
+        Ok(VALIDATOR_HISTORICAL_REWARDS
+            .may_load(staking_storage, (validator, period))?
+            .unwrap_or_default())
     }
 
-    fn get_rewards_internal(
+    /// Returns the cumulative reward ratio of the validator as if its current period ended
+    /// with the given rewards; like on chain, the ratio of the period is truncated.
+    fn current_reward_ratio(
         staking_storage: &dyn Storage,
-        block: &BlockInfo,
-        shares: &Shares,
-        validator: &Validator,
+        validator: &Addr,
         validator_info: &ValidatorInfo,
-    ) -> AnyResult<Coin> {
-        let staking_info = Self::get_staking_info(staking_storage)?;
-
-        // calculate missing rewards without updating the validator to reduce rounding errors
-        let new_validator_rewards = Self::calculate_rewards(
-            block.time,
-            validator_info.last_rewards_calculation,
-            staking_info.apr,
-            validator.commission,
-            validator_info.stake,
-        );
-
-        // calculate the delegator's share of those
-        let delegator_rewards =
-            shares.rewards + shares.share_of_rewards(validator_info, new_validator_rewards);
-
-        Ok(Coin {
-            denom: staking_info.bonded_denom,
-            amount: Uint128::new(1).mul_floor(delegator_rewards), // multiplying by 1 to convert Decimal to Uint128
-        })
+        current_rewards: Decimal,
+    ) -> AnyResult<Decimal> {
+        let previous_ratio =
+            Self::historical_reward_ratio(staking_storage, validator, validator_info.period - 1)?;
+        let delegated = Self::delegated_stake(staking_storage, validator, validator_info)?;
+        if delegated.is_zero() {
+            return Ok(previous_ratio);
+        }
+        Ok(previous_ratio + current_rewards / delegated)
     }
 
-    /// Calculates the rewards that are due since the last calculation.
-    fn calculate_rewards(
-        current_time: Timestamp,
-        since: Timestamp,
-        interest_rate: Decimal,
-        validator_commission: Decimal,
-        stake: Uint128,
-    ) -> Decimal {
-        // calculate time since last update (in seconds)
-        let time_diff = current_time.minus_seconds(since.seconds()).seconds();
+    /// Ends the current rewards period of the validator and returns it,
+    /// like `IncrementValidatorPeriod` in Cosmos SDK. Validator info is not saved.
+    fn increment_period(
+        staking_storage: &mut dyn Storage,
+        validator: &Addr,
+        validator_info: &mut ValidatorInfo,
+    ) -> AnyResult<u64> {
+        let ratio = Self::current_reward_ratio(
+            staking_storage,
+            validator,
+            validator_info,
+            validator_info.current_rewards,
+        )?;
+        let period = validator_info.period;
+        VALIDATOR_HISTORICAL_REWARDS.save(staking_storage, (validator, period), &ratio)?;
+        validator_info.period += 1;
+        validator_info.current_rewards = Decimal::zero();
+        Ok(period)
+    }
 
-        // using decimal here to reduce rounding error when calling this function a lot
-        let reward = Decimal::from_ratio(stake, 1u128)
-            * interest_rate
-            * Decimal::from_ratio(time_diff, 1u128)
-            / Decimal::from_ratio(60u128 * 60 * 24 * 365, 1u128);
-        let commission = reward * validator_commission;
+    /// Moves rewards accrued by the delegation since its starting period to its rewards
+    /// and starts a new period of the delegation. Rewards are truncated, like on chain,
+    /// where the remainder is sent to the community pool.
+    fn settle_rewards(
+        staking_storage: &dyn Storage,
+        validator: &Addr,
+        shares: &mut Shares,
+        ending_period: u64,
+    ) -> AnyResult<()> {
+        let starting_ratio =
+            Self::historical_reward_ratio(staking_storage, validator, shares.period)?;
+        let ending_ratio =
+            Self::historical_reward_ratio(staking_storage, validator, ending_period)?;
+        let rewards = shares.stake * (ending_ratio - starting_ratio);
+        shares.rewards += Decimal::from_ratio(Uint128::new(1).mul_floor(rewards), 1u128);
+        shares.period = ending_period;
+        Ok(())
+    }
 
-        reward - commission
+    /// Ends the current rewards period of the validator and settles rewards of all its delegators,
+    /// does nothing unless rewards are distributed with [RewardDistribution::F1].
+    fn settle_all_rewards(staking_storage: &mut dyn Storage, validator: &Addr) -> AnyResult<()> {
+        if Self::get_reward_distribution(staking_storage)? != RewardDistribution::F1 {
+            return Ok(());
+        }
+        let mut validator_info = VALIDATOR_INFO.load(staking_storage, validator)?;
+        let ending_period =
+            Self::increment_period(staking_storage, validator, &mut validator_info)?;
+        VALIDATOR_INFO.save(staking_storage, validator, &validator_info)?;
+        for staker in validator_info.stakers.iter() {
+            let mut shares = STAKES.load(staking_storage, (staker, validator))?;
+            Self::settle_rewards(staking_storage, validator, &mut shares, ending_period)?;
+            STAKES.save(staking_storage, (staker, validator), &shares)?;
+        }
+        Ok(())
     }
 
     /// Updates the staking reward for the given validator and their stakers
@@ -296,25 +2800,33 @@ impl StakeKeeper {
 
         let validator_obj = VALIDATOR_MAP.load(staking_storage, validator)?;
 
-        if validator_info.last_rewards_calculation >= block.time {
+        if validator_info.is_rewarded(block) {
             return Ok(());
         }
 
-        let new_rewards = Self::calculate_rewards(
-            block.time,
-            validator_info.last_rewards_calculation,
+        let (new_rewards, new_commission) = Self::calculate_rewards(
+            block,
+            &validator_info,
             staking_info.apr,
             validator_obj.commission,
-            validator_info.stake,
         );
 
         // update validator info
         validator_info.last_rewards_calculation = block.time;
+        validator_info.last_rewards_height = block.height;
+        validator_info.commission += new_commission;
+        let distribution = Self::get_reward_distribution(staking_storage)?;
+        if distribution == RewardDistribution::F1 {
+            // delegators' rewards are calculated from the validator's periods
+            validator_info.current_rewards += new_rewards;
+        }
         VALIDATOR_INFO.save(staking_storage, validator, &validator_info)?;
 
         // update delegators
-        if !new_rewards.is_zero() {
+        if distribution == RewardDistribution::Linear && !new_rewards.is_zero() {
             let validator_addr = api.addr_validate(&validator_obj.address)?;
+            let delegated =
+                Self::delegated_stake(staking_storage, &validator_addr, &validator_info)?;
             // update all delegators
             for staker in validator_info.stakers.iter() {
                 STAKES.update(
@@ -323,7 +2835,7 @@ impl StakeKeeper {
                     |shares| -> AnyResult<_> {
                         let mut shares =
                             shares.expect("all stakers in validator_info should exist");
-                        shares.rewards += shares.share_of_rewards(&validator_info, new_rewards);
+                        shares.rewards += shares.share_of_rewards(delegated, new_rewards);
                         Ok(shares)
                     },
                 )?;
@@ -424,7 +2936,7 @@ impl StakeKeeper {
         // now, we can update the stake of the delegator and validator
         let mut validator_info = VALIDATOR_INFO
             .may_load(staking_storage, validator)?
-            .unwrap_or_else(|| ValidatorInfo::new(block.time));
+            .unwrap_or_else(|| ValidatorInfo::new(block));
         let shares = STAKES.may_load(staking_storage, (delegator, validator))?;
         let mut shares = if sub {
             // see https://github.com/cosmos/cosmos-sdk/blob/3c5387048f75d7e78b40c5b8d2421fdb8f5d973a/x/staking/keeper/delegation.go#L1005-L1007
@@ -433,6 +2945,11 @@ impl StakeKeeper {
         } else {
             shares.unwrap_or_default()
         };
+        if Self::get_reward_distribution(staking_storage)? == RewardDistribution::F1 {
+            let ending_period =
+                Self::increment_period(staking_storage, validator, &mut validator_info)?;
+            Self::settle_rewards(staking_storage, validator, &mut shares, ending_period)?;
+        }
 
         let amount_dec = Decimal::from_ratio(amount, 1u128);
         if sub {
@@ -472,6 +2989,7 @@ impl StakeKeeper {
     ) -> AnyResult<()> {
         // calculate rewards before slashing
         Self::update_rewards(api, staking_storage, block, validator)?;
+        Self::settle_all_rewards(staking_storage, validator)?;
 
         // update stake of validator and stakers
         let mut validator_info = VALIDATOR_INFO
@@ -520,6 +3038,376 @@ impl StakeKeeper {
         Ok(())
     }
 
+    /// Replaces the list of validators.
+    fn save_validators(
+        staking_storage: &mut dyn Storage,
+        validators: impl IntoIterator<Item = Validator>,
+    ) -> AnyResult<()> {
+        while VALIDATORS.pop_back(staking_storage)?.is_some() {}
+        for validator in validators {
+            VALIDATORS.push_back(staking_storage, &validator)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the validator, all delegations to the validator are moved to the unbonding queue,
+    /// accrued rewards and commission are withdrawn.
+    fn remove_validator<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        validator: &Addr,
+    ) -> AnyResult<AppResponse> {
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        Self::update_rewards(api, &mut staking_storage, block, validator)?;
+        Self::settle_all_rewards(&mut staking_storage, validator)?;
+        let validator_info = VALIDATOR_INFO.load(&staking_storage, validator)?;
+        let staking_info = Self::get_staking_info(&staking_storage)?;
+        let payout_at = block.time.plus_seconds(staking_info.unbonding_time);
+        let mut unbonding_queue = UNBONDING_QUEUE
+            .may_load(&staking_storage)?
+            .unwrap_or_default();
+        let mut events = vec![];
+        let mut payouts = vec![];
+        for delegator in &validator_info.stakers {
+            let shares = STAKES.load(&staking_storage, (delegator, validator))?;
+            STAKES.remove(&mut staking_storage, (delegator, validator));
+            let amount = Uint128::new(1).mul_floor(shares.stake);
+            if !amount.is_zero() {
+                unbonding_queue.push_back(Unbonding {
+                    delegator: delegator.clone(),
+                    validator: validator.clone(),
+                    amount,
+                    payout_at,
+                    creation_height: block.height,
+                    initial_amount: amount,
+                });
+                events.push(
+                    Event::new("unbond")
+                        .add_attribute("validator", validator)
+                        .add_attribute("delegator", delegator)
+                        .add_attribute("amount", format!("{}{}", amount, staking_info.bonded_denom))
+                        .add_attribute("completion_time", format_rfc3339(payout_at)),
+                );
+            }
+            payouts.push((delegator.clone(), Uint128::new(1).mul_floor(shares.rewards)));
+        }
+        payouts.push((
+            validator.clone(),
+            Uint128::new(1).mul_floor(validator_info.commission),
+        ));
+        UNBONDING_QUEUE.save(&mut staking_storage, &unbonding_queue)?;
+        VALIDATOR_INFO.remove(&mut staking_storage, validator);
+        PROPOSER_PRIORITY.remove(&mut staking_storage, validator);
+        let periods = VALIDATOR_HISTORICAL_REWARDS
+            .prefix(validator)
+            .keys(&staking_storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for period in periods {
+            VALIDATOR_HISTORICAL_REWARDS.remove(&mut staking_storage, (validator, period));
+        }
+        VALIDATOR_MAP.remove(&mut staking_storage, validator);
+        let validators = self.get_validators(&staking_storage)?;
+        Self::save_validators(
+            &mut staking_storage,
+            validators
+                .into_iter()
+                .filter(|v| v.address != validator.as_str()),
+        )?;
+
+        // withdraw rewards, like when the delegation is removed on chain
+        for (account, amount) in payouts {
+            if amount.is_zero() {
+                continue;
+            }
+            let distribution_storage = prefixed_read(storage, NAMESPACE_DISTRIBUTION);
+            let receiver =
+                DistributionKeeper::get_withdraw_address(&distribution_storage, &account)?;
+            DistributionKeeper::pay_rewards(
+                api,
+                storage,
+                router,
+                block,
+                receiver,
+                coin(amount.u128(), &staking_info.bonded_denom),
+            )?;
+        }
+        Ok(AppResponse { events, data: None })
+    }
+
+    /// Jails the validator until the specified block time, rewards accrued so far are kept.
+    fn jail(
+        &self,
+        api: &dyn Api,
+        staking_storage: &mut dyn Storage,
+        block: &BlockInfo,
+        validator: &Addr,
+        jailed_until: Timestamp,
+    ) -> AnyResult<AppResponse> {
+        Self::update_rewards(api, staking_storage, block, validator)?;
+        let mut validator_info = VALIDATOR_INFO.load(staking_storage, validator)?;
+        validator_info.jailed_until = Some(jailed_until);
+        VALIDATOR_INFO.save(staking_storage, validator, &validator_info)?;
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/slashing/keeper/keeper.go#L104-L108
+        Ok(AppResponse {
+            events: vec![Event::new("slash")
+                .add_attribute("address", validator)
+                .add_attribute("jailed", validator)],
+            data: None,
+        })
+    }
+
+    /// Returns the number of tokens delegated to the validator by its operator.
+    fn self_delegation(staking_storage: &dyn Storage, validator: &Addr) -> AnyResult<Uint128> {
+        Ok(STAKES
+            .may_load(staking_storage, (validator, validator))?
+            .map(|shares| Uint128::new(1).mul_floor(shares.stake))
+            .unwrap_or_default())
+    }
+
+    /// Jails the validator whose self-delegation dropped below the minimum,
+    /// or with no delegations left, like Cosmos SDK does when the delegations are undelegated.
+    /// Such validator can be unjailed immediately, after its self-delegation is restored.
+    fn jail_if_undelegated(
+        &self,
+        api: &dyn Api,
+        staking_storage: &mut dyn Storage,
+        block: &BlockInfo,
+        validator: &Addr,
+    ) -> AnyResult<Vec<Event>> {
+        let Some(validator_info) = VALIDATOR_INFO.may_load(staking_storage, validator)? else {
+            return Ok(vec![]);
+        };
+        if validator_info.jailed_until.is_some() {
+            return Ok(vec![]);
+        }
+        let reason = if validator_info.stake.is_zero() {
+            "no_delegations"
+        } else if Self::self_delegation(staking_storage, validator)?
+            < validator_info.min_self_delegation
+        {
+            "min_self_delegation"
+        } else {
+            return Ok(vec![]);
+        };
+        self.jail(api, staking_storage, block, validator, block.time)?;
+        Ok(vec![Event::new("jail")
+            .add_attribute("validator", validator)
+            .add_attribute("reason", reason)])
+    }
+
+    /// Moves the sender's delegation to the module account of the new tokenize share record
+    /// and mints the same amount of share tokens to the owner of the record.
+    fn tokenize_shares<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        validator: String,
+        amount: Coin,
+        owner: String,
+    ) -> AnyResult<AppResponse> {
+        let validator = api.addr_validate(&validator)?;
+        let owner = api.addr_validate(&owner)?;
+        if amount.amount.is_zero() {
+            bail!("invalid shares amount");
+        }
+        let id = LAST_TOKENIZE_SHARE_RECORD_ID
+            .may_load(&prefixed_read(storage, NAMESPACE_STAKING))?
+            .unwrap_or_default()
+            + 1;
+        let record = TokenizeShareRecord {
+            id,
+            owner: owner.clone(),
+            module_account: module_address(api, &format!("tokenizeshare_{}", id))?,
+            validator: validator.clone(),
+        };
+        let mut hook_events = self.remove_stake_with_hooks(
+            api,
+            storage,
+            router,
+            block,
+            &sender,
+            &validator,
+            amount.clone(),
+        )?;
+        hook_events.extend(self.add_stake_with_hooks(
+            api,
+            storage,
+            router,
+            block,
+            &record.module_account,
+            &validator,
+            amount.clone(),
+        )?);
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        LAST_TOKENIZE_SHARE_RECORD_ID.save(&mut staking_storage, &id)?;
+        TOKENIZE_SHARE_RECORDS.save(&mut staking_storage, id, &record)?;
+
+        let shares = coin(amount.amount.u128(), record.share_denom());
+        router.sudo(
+            api,
+            storage,
+            block,
+            BankSudo::Mint {
+                to_address: owner.to_string(),
+                amount: vec![shares.clone()],
+            }
+            .into(),
+        )?;
+        let events = vec![Event::new("tokenize_shares")
+            .add_attribute("delegator", &sender)
+            .add_attribute("validator", &validator)
+            .add_attribute("share_owner", &owner)
+            .add_attribute("share_record_id", id.to_string())
+            .add_attribute("amount", format!("{}{}", amount.amount, amount.denom))
+            .add_attribute(
+                "tokenized_shares",
+                format!("{}{}", shares.amount, shares.denom),
+            )];
+        let data = ProtoMsgSharesResponse {
+            amount: Some(shares.into()),
+        };
+        Ok(AppResponse {
+            events: events.into_iter().chain(hook_events).collect(),
+            data: Some(prost::Message::encode_to_vec(&data).into()),
+        })
+    }
+
+    /// Burns the sender's share tokens and moves the corresponding part of the tokenized
+    /// delegation back to the sender. The record is removed when the whole delegation is redeemed.
+    fn redeem_tokens_for_shares<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        amount: Coin,
+    ) -> AnyResult<AppResponse> {
+        if amount.amount.is_zero() {
+            bail!("invalid shares amount");
+        }
+        let record = self
+            .tokenize_share_records(storage)?
+            .into_iter()
+            .find(|record| record.share_denom() == amount.denom)
+            .ok_or_else(|| anyhow!("tokenize share record not exists"))?;
+
+        // share tokens are converted to the stake proportionally, so slashing of the validator
+        // affects all holders of share tokens equally
+        let supply: SupplyResponse = from_json(
+            router.query(
+                api,
+                storage,
+                block,
+                BankQuery::Supply {
+                    denom: amount.denom.clone(),
+                }
+                .into(),
+            )?,
+        )?;
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let bonded_denom = Self::get_staking_info(&staking_storage)?.bonded_denom;
+        let stake = STAKES
+            .may_load(
+                &staking_storage,
+                (&record.module_account, &record.validator),
+            )?
+            .map(|shares| shares.stake)
+            .unwrap_or_default();
+        if amount.amount > supply.amount.amount {
+            bail!("insufficient shares to redeem");
+        }
+        let tokens = Uint128::new(1).mul_floor(
+            stake.checked_mul(Decimal::from_ratio(amount.amount, supply.amount.amount))?,
+        );
+
+        router.execute(
+            api,
+            storage,
+            block,
+            sender.clone(),
+            BankMsg::Burn {
+                amount: vec![amount.clone()],
+            }
+            .into(),
+        )?;
+        let mut hook_events = vec![];
+        if !tokens.is_zero() {
+            let tokens = coin(tokens.u128(), &bonded_denom);
+            hook_events = self.remove_stake_with_hooks(
+                api,
+                storage,
+                router,
+                block,
+                &record.module_account,
+                &record.validator,
+                tokens.clone(),
+            )?;
+            hook_events.extend(self.add_stake_with_hooks(
+                api,
+                storage,
+                router,
+                block,
+                &sender,
+                &record.validator,
+                tokens,
+            )?);
+        }
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        if amount.amount == supply.amount.amount
+            || !STAKES.has(
+                &staking_storage,
+                (&record.module_account, &record.validator),
+            )
+        {
+            TOKENIZE_SHARE_RECORDS.remove(&mut staking_storage, record.id);
+        }
+
+        let events = vec![Event::new("redeem_shares")
+            .add_attribute("delegator", &sender)
+            .add_attribute("validator", &record.validator)
+            .add_attribute("amount", format!("{}{}", tokens, bonded_denom))];
+        let data = ProtoMsgSharesResponse {
+            amount: Some(coin(tokens.u128(), bonded_denom).into()),
+        };
+        Ok(AppResponse {
+            events: events.into_iter().chain(hook_events).collect(),
+            data: Some(prost::Message::encode_to_vec(&data).into()),
+        })
+    }
+
+    /// Transfers the ownership of the tokenize share record owned by the sender.
+    fn transfer_tokenize_share_record(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        sender: Addr,
+        record_id: u64,
+        new_owner: String,
+    ) -> AnyResult<AppResponse> {
+        let new_owner = api.addr_validate(&new_owner)?;
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        let mut record = TOKENIZE_SHARE_RECORDS
+            .may_load(&staking_storage, record_id)?
+            .ok_or_else(|| anyhow!("tokenize share record not exists"))?;
+        if record.owner != sender {
+            bail!("not tokenize share record owner");
+        }
+        record.owner = new_owner.clone();
+        TOKENIZE_SHARE_RECORDS.save(&mut staking_storage, record_id, &record)?;
+        let events = vec![Event::new("transfer_tokenize_share_record")
+            .add_attribute("share_record_id", record_id.to_string())
+            .add_attribute("sender", &sender)
+            .add_attribute("share_owner", &new_owner)];
+        Ok(AppResponse { events, data: None })
+    }
+
     // Asserts that the given coin has the proper denominator
     fn validate_denom(&self, staking_storage: &dyn Storage, amount: &Coin) -> AnyResult<()> {
         let staking_info = Self::get_staking_info(staking_storage)?;
@@ -541,6 +3429,63 @@ impl StakeKeeper {
         Ok(())
     }
 
+    /// Allocates tokens to the validator, the commission is kept by the validator
+    /// and the rest is shared by delegators. Returns the validator's commission.
+    pub(crate) fn allocate_to_validator(
+        storage: &mut dyn Storage,
+        validator: &Addr,
+        tokens: Decimal,
+    ) -> AnyResult<Decimal> {
+        let staking_storage = &mut prefixed(storage, NAMESPACE_STAKING);
+        let validator_obj = VALIDATOR_MAP.load(staking_storage, validator)?;
+        let mut validator_info = VALIDATOR_INFO.load(staking_storage, validator)?;
+        let commission = tokens * validator_obj.commission;
+        let shared = tokens - commission;
+        validator_info.commission += commission;
+        match Self::get_reward_distribution(staking_storage)? {
+            RewardDistribution::F1 => validator_info.current_rewards += shared,
+            RewardDistribution::Linear => {
+                let delegated = Self::delegated_stake(staking_storage, validator, &validator_info)?;
+                for staker in validator_info.stakers.iter() {
+                    let mut shares = STAKES.load(staking_storage, (staker, validator))?;
+                    shares.rewards += shares.share_of_rewards(delegated, shared);
+                    STAKES.save(staking_storage, (staker, validator), &shares)?;
+                }
+            }
+        }
+        VALIDATOR_INFO.save(staking_storage, validator, &validator_info)?;
+        Ok(commission)
+    }
+
+    /// Selects the proposer of the block with the weighted round-robin of CometBFT,
+    /// so every validator proposes blocks proportionally to its stake.
+    pub(crate) fn next_proposer(
+        storage: &mut dyn Storage,
+        validators: &[ValidatorStake],
+    ) -> AnyResult<Addr> {
+        let staking_storage = &mut prefixed(storage, NAMESPACE_STAKING);
+        // see https://github.com/cometbft/cometbft/blob/v0.37.2/types/validator_set.go#L98-L131
+        let mut proposer: Option<(Addr, i128)> = None;
+        for stake in validators {
+            let validator = Addr::unchecked(&stake.address);
+            let priority = PROPOSER_PRIORITY
+                .may_load(staking_storage, &validator)?
+                .unwrap_or_default()
+                + stake.tokens.u128() as i128;
+            PROPOSER_PRIORITY.save(staking_storage, &validator, &priority)?;
+            if proposer.as_ref().is_none_or(|(_, max)| priority > *max) {
+                proposer = Some((validator, priority));
+            }
+        }
+        let (proposer, priority) = proposer.ok_or_else(|| anyhow!("no bonded validators"))?;
+        let total = validators
+            .iter()
+            .map(|v| v.tokens.u128() as i128)
+            .sum::<i128>();
+        PROPOSER_PRIORITY.save(staking_storage, &proposer, &(priority - total))?;
+        Ok(proposer)
+    }
+
     fn process_queue<ExecC: CustomMsg, QueryC: CustomQuery>(
         &self,
         api: &dyn Api,
@@ -548,22 +3493,27 @@ impl StakeKeeper {
         router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
         block: &BlockInfo,
     ) -> AnyResult<AppResponse> {
+        self.track_historical_info(storage, block)?;
         let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
         let mut unbonding_queue = UNBONDING_QUEUE
             .may_load(&staking_storage)?
             .unwrap_or_default();
+        let mut events = vec![];
         loop {
             let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
-            match unbonding_queue.front() {
-                // assuming the queue is sorted by payout_at
-                Some(Unbonding { payout_at, .. }) if payout_at <= &block.time => {
+            // the queue is not sorted by payout time when the unbonding time was changed
+            match unbonding_queue
+                .iter()
+                .position(|unbonding| unbonding.payout_at <= block.time)
+            {
+                Some(index) => {
                     // remove from queue
                     let Unbonding {
                         delegator,
                         validator,
                         amount,
                         ..
-                    } = unbonding_queue.pop_front().unwrap();
+                    } = unbonding_queue.remove(index).unwrap();
 
                     // remove staking entry if it is empty
                     let delegation = self
@@ -586,26 +3536,103 @@ impl StakeKeeper {
                     }
 
                     let staking_info = Self::get_staking_info(&staking_storage)?;
+                    // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/staking/keeper/msg_server.go#L571-L578
+                    events.push(
+                        Event::new("complete_unbonding")
+                            .add_attribute(
+                                "amount",
+                                format!("{}{}", amount, staking_info.bonded_denom),
+                            )
+                            .add_attribute("validator", &validator)
+                            .add_attribute("delegator", &delegator),
+                    );
                     if !amount.is_zero() {
+                        let amount = vec![coin(amount.u128(), &staking_info.bonded_denom)];
                         router.execute(
                             api,
                             storage,
                             block,
                             self.module_addr.clone(),
                             BankMsg::Send {
-                                to_address: delegator.into_string(),
-                                amount: vec![coin(amount.u128(), &staking_info.bonded_denom)],
+                                to_address: delegator.to_string(),
+                                amount: amount.clone(),
+                            }
+                            .into(),
+                        )?;
+                        // track undelegations to vesting accounts
+                        router.sudo(
+                            api,
+                            storage,
+                            block,
+                            BankSudo::TrackUndelegation {
+                                delegator: delegator.into_string(),
+                                amount,
                             }
                             .into(),
                         )?;
                     }
                 }
-                _ => break,
+                None => break,
             }
         }
         let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
         UNBONDING_QUEUE.save(&mut staking_storage, &unbonding_queue)?;
-        Ok(AppResponse::default())
+
+        // remove completed redelegations
+        let staking_info = Self::get_staking_info(&staking_storage)?;
+        let (completed, pending): (VecDeque<_>, VecDeque<_>) = REDELEGATION_QUEUE
+            .may_load(&staking_storage)?
+            .unwrap_or_default()
+            .into_iter()
+            .partition(|redelegation| redelegation.completion_time <= block.time);
+        REDELEGATION_QUEUE.save(&mut staking_storage, &pending)?;
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/staking/keeper/msg_server.go#L592-L600
+        events.extend(completed.into_iter().map(|redelegation| {
+            Event::new("complete_redelegation")
+                .add_attribute(
+                    "amount",
+                    format!("{}{}", redelegation.amount, staking_info.bonded_denom),
+                )
+                .add_attribute("delegator", redelegation.delegator)
+                .add_attribute("source_validator", redelegation.src_validator)
+                .add_attribute("destination_validator", redelegation.dst_validator)
+        }));
+        Ok(AppResponse { events, data: None })
+    }
+
+    /// Unjails the validator, fails when the validator can not be unjailed yet.
+    fn unjail_validator(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        validator: &Addr,
+    ) -> AnyResult<AppResponse> {
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        let mut validator_info = VALIDATOR_INFO
+            .may_load(&staking_storage, validator)?
+            .ok_or_else(|| anyhow!("validator does not exist"))?;
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/slashing/keeper/unjail.go
+        match validator_info.jailed_until {
+            None => bail!("validator not jailed; cannot be unjailed"),
+            Some(jailed_until) if validator_info.tombstoned || block.time < jailed_until => {
+                bail!("validator still jailed; cannot be unjailed")
+            }
+            Some(_) => {}
+        }
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/slashing/keeper/unjail.go#L28-L35
+        if Self::self_delegation(&staking_storage, validator)? < validator_info.min_self_delegation
+        {
+            bail!("validator's self delegation less than minimum; cannot be unjailed");
+        }
+        // rewards are not accrued for the jailed period
+        validator_info.last_rewards_calculation = block.time;
+        validator_info.last_rewards_height = block.height;
+        validator_info.jailed_until = None;
+        VALIDATOR_INFO.save(&mut staking_storage, validator, &validator_info)?;
+        Ok(AppResponse {
+            events: vec![Event::new("unjail").add_attribute("validator", validator)],
+            data: None,
+        })
     }
 }
 
@@ -614,10 +3641,136 @@ impl Staking for StakeKeeper {
         &self,
         api: &dyn Api,
         storage: &mut dyn Storage,
-        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+    ) -> AnyResult<AppResponse> {
+        self.process_queue(api, storage, router, block)
+    }
+
+    fn execute_liquid_staking<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: LiquidStakingMsg,
+    ) -> AnyResult<AppResponse> {
+        match msg {
+            LiquidStakingMsg::TokenizeShares {
+                validator,
+                amount,
+                owner,
+            } => self.tokenize_shares(
+                api, storage, router, block, sender, validator, amount, owner,
+            ),
+            LiquidStakingMsg::RedeemTokensForShares { amount } => {
+                self.redeem_tokens_for_shares(api, storage, router, block, sender, amount)
+            }
+            LiquidStakingMsg::TransferTokenizeShareRecord {
+                record_id,
+                new_owner,
+            } => self.transfer_tokenize_share_record(api, storage, sender, record_id, new_owner),
+        }
+    }
+
+    fn create_validator<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        operator: Addr,
+        validator: Validator,
+        value: Coin,
+        min_self_delegation: Uint128,
+    ) -> AnyResult<AppResponse> {
+        if validator.address != operator.as_str() {
+            bail!(
+                "validator address {} does not match the operator {}",
+                validator.address,
+                operator
+            );
+        }
+        let validator_addr = validator.address.clone();
+        let created = router.sudo(
+            api,
+            storage,
+            block,
+            StakingSudo::AddValidator { validator }.into(),
+        )?;
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/staking/keeper/msg_server.go#L118-L124
+        let mut res = AppResponse {
+            events: vec![Event::new("create_validator")
+                .add_attribute("validator", &validator_addr)
+                .add_attribute("amount", format!("{}{}", value.amount, value.denom))],
+            data: None,
+        };
+        res.events.extend(created.events);
+        let delegation = router.execute(
+            api,
+            storage,
+            block,
+            operator,
+            StakingMsg::Delegate {
+                validator: validator_addr.clone(),
+                amount: value,
+            }
+            .into(),
+        )?;
+        res.events.extend(delegation.events);
+        router.sudo(
+            api,
+            storage,
+            block,
+            StakingSudo::SetMinSelfDelegation {
+                validator: validator_addr,
+                min_self_delegation,
+            }
+            .into(),
+        )?;
+        Ok(res)
+    }
+
+    fn edit_validator<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        operator: Addr,
+        description: Option<ValidatorDescription>,
+        commission: Option<Decimal>,
+    ) -> AnyResult<AppResponse> {
+        let mut res = AppResponse::default();
+        if let Some(description) = description {
+            let msg = StakingSudo::EditDescription {
+                validator: operator.to_string(),
+                description,
+            };
+            res.events
+                .extend(router.sudo(api, storage, block, msg.into())?.events);
+        }
+        if let Some(commission) = commission {
+            let msg = StakingSudo::EditValidator {
+                validator: operator.to_string(),
+                commission,
+            };
+            res.events
+                .extend(router.sudo(api, storage, block, msg.into())?.events);
+        }
+        Ok(res)
+    }
+
+    fn unjail<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        _api: &dyn Api,
+        storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
         block: &BlockInfo,
+        operator: Addr,
     ) -> AnyResult<AppResponse> {
-        self.process_queue(api, storage, router, block)
+        self.unjail_validator(storage, block, &operator)
     }
 }
 
@@ -658,19 +3811,39 @@ impl Module for StakeKeeper {
                     &validator,
                     amount.clone(),
                 )?;
+                // track delegations from vesting accounts before moving the money
+                router.sudo(
+                    api,
+                    storage,
+                    block,
+                    BankSudo::TrackDelegation {
+                        delegator: sender.to_string(),
+                        amount: vec![amount.clone()],
+                    }
+                    .into(),
+                )?;
                 // move money from sender account to this module (note we can control sender here)
                 router.execute(
                     api,
                     storage,
                     block,
-                    sender,
+                    sender.clone(),
                     BankMsg::Send {
                         to_address: self.module_addr.to_string(),
                         amount: vec![amount],
                     }
                     .into(),
                 )?;
-                Ok(AppResponse { events, data: None })
+                let hook_msg = StakingHookMsg::AfterDelegationModified {
+                    delegator: sender.to_string(),
+                    validator: validator.to_string(),
+                };
+                let hook_events =
+                    self.call_staking_hooks(api, storage, router, block, vec![hook_msg])?;
+                Ok(AppResponse {
+                    events: events.into_iter().chain(hook_events).collect(),
+                    data: None,
+                })
             }
             StakingMsg::Undelegate { validator, amount } => {
                 let validator = api.addr_validate(&validator)?;
@@ -682,31 +3855,47 @@ impl Module for StakeKeeper {
                 }
 
                 // see https://github.com/cosmos/cosmos-sdk/blob/v0.46.1/x/staking/keeper/msg_server.go#L378-L383
+                let staking_info = Self::get_staking_info(&staking_storage)?;
+                let payout_at = block.time.plus_seconds(staking_info.unbonding_time);
                 let events = vec![Event::new("unbond")
                     .add_attribute("validator", &validator)
                     .add_attribute("amount", format!("{}{}", amount.amount, amount.denom))
-                    .add_attribute("completion_time", "2022-09-27T14:00:00+00:00")]; // TODO: actual date?
-                self.remove_stake(
+                    .add_attribute("completion_time", format_rfc3339(payout_at))];
+                let hook_events = self.remove_stake_with_hooks(
                     api,
-                    &mut staking_storage,
+                    storage,
+                    router,
                     block,
                     &sender,
                     &validator,
                     amount.clone(),
                 )?;
                 // add tokens to unbonding queue
-                let staking_info = Self::get_staking_info(&staking_storage)?;
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
                 let mut unbonding_queue = UNBONDING_QUEUE
                     .may_load(&staking_storage)?
                     .unwrap_or_default();
+                let entries = unbonding_queue
+                    .iter()
+                    .filter(|u| u.delegator == sender && u.validator == validator)
+                    .count();
+                // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/staking/keeper/delegation.go#L880-L882
+                if entries >= staking_info.max_entries as usize {
+                    bail!("too many unbonding delegation entries for (delegator, validator) tuple");
+                }
                 unbonding_queue.push_back(Unbonding {
                     delegator: sender.clone(),
                     validator,
                     amount: amount.amount,
-                    payout_at: block.time.plus_seconds(staking_info.unbonding_time),
+                    payout_at,
+                    creation_height: block.height,
+                    initial_amount: amount.amount,
                 });
                 UNBONDING_QUEUE.save(&mut staking_storage, &unbonding_queue)?;
-                Ok(AppResponse { events, data: None })
+                Ok(AppResponse {
+                    events: events.into_iter().chain(hook_events).collect(),
+                    data: None,
+                })
             }
             StakingMsg::Redelegate {
                 src_validator,
@@ -715,28 +3904,67 @@ impl Module for StakeKeeper {
             } => {
                 let src_validator = api.addr_validate(&src_validator)?;
                 let dst_validator = api.addr_validate(&dst_validator)?;
+                // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/staking/keeper/delegation.go#L1017-L1047
+                if src_validator == dst_validator {
+                    bail!("cannot redelegate to the same validator");
+                }
+                let staking_info = Self::get_staking_info(&staking_storage)?;
+                let mut redelegation_queue = REDELEGATION_QUEUE
+                    .may_load(&staking_storage)?
+                    .unwrap_or_default();
+                if redelegation_queue
+                    .iter()
+                    .any(|r| r.delegator == sender && r.dst_validator == src_validator)
+                {
+                    bail!("redelegation to this validator already in progress; first redelegation to this validator must complete before next redelegation");
+                }
+                let entries = redelegation_queue
+                    .iter()
+                    .filter(|r| {
+                        r.delegator == sender
+                            && r.src_validator == src_validator
+                            && r.dst_validator == dst_validator
+                    })
+                    .count();
+                if entries >= staking_info.max_entries as usize {
+                    bail!("too many redelegation entries for (delegator, src-validator, dst-validator) tuple");
+                }
+                let completion_time = block.time.plus_seconds(staking_info.unbonding_time);
                 // see https://github.com/cosmos/cosmos-sdk/blob/v0.46.1/x/staking/keeper/msg_server.go#L316-L322
-                let events = vec![Event::new("redelegate")
+                let mut events = vec![Event::new("redelegate")
                     .add_attribute("source_validator", &src_validator)
                     .add_attribute("destination_validator", &dst_validator)
-                    .add_attribute("amount", format!("{}{}", amount.amount, amount.denom))];
+                    .add_attribute("amount", format!("{}{}", amount.amount, amount.denom))
+                    .add_attribute("completion_time", format_rfc3339(completion_time))];
 
-                self.remove_stake(
+                events.extend(self.remove_stake_with_hooks(
                     api,
-                    &mut staking_storage,
+                    storage,
+                    router,
                     block,
                     &sender,
                     &src_validator,
                     amount.clone(),
-                )?;
-                self.add_stake(
+                )?);
+                events.extend(self.add_stake_with_hooks(
                     api,
-                    &mut staking_storage,
+                    storage,
+                    router,
                     block,
                     &sender,
                     &dst_validator,
-                    amount,
-                )?;
+                    amount.clone(),
+                )?);
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                redelegation_queue.push_back(Redelegation {
+                    delegator: sender,
+                    src_validator,
+                    dst_validator,
+                    amount: amount.amount,
+                    creation_height: block.height,
+                    completion_time,
+                });
+                REDELEGATION_QUEUE.save(&mut staking_storage, &redelegation_queue)?;
 
                 Ok(AppResponse { events, data: None })
             }
@@ -832,9 +4060,14 @@ impl Module for StakeKeeper {
                 let res = to_json_binary(&full_delegation_response)?;
                 Ok(res)
             }
-            StakingQuery::AllValidators {} => Ok(to_json_binary(&AllValidatorsResponse::new(
-                self.get_validators(&staking_storage)?,
-            ))?),
+            StakingQuery::AllValidators {} => {
+                // like in wasmd, only bonded validators are returned
+                let validators = Self::bonded_validators(&staking_storage)?
+                    .into_iter()
+                    .map(|(validator, _)| validator)
+                    .collect();
+                Ok(to_json_binary(&AllValidatorsResponse::new(validators))?)
+            }
             StakingQuery::Validator { address } => Ok(to_json_binary(&ValidatorResponse::new(
                 self.get_validator(&staking_storage, &Addr::unchecked(address))?,
             ))?),
@@ -846,7 +4079,7 @@ impl Module for StakeKeeper {
         &self,
         api: &dyn Api,
         storage: &mut dyn Storage,
-        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
         block: &BlockInfo,
         msg: StakingSudo,
     ) -> AnyResult<AppResponse> {
@@ -855,12 +4088,234 @@ impl Module for StakeKeeper {
                 validator,
                 percentage,
             } => {
-                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
                 let validator = api.addr_validate(&validator)?;
                 self.validate_percentage(percentage)?;
+                let events =
+                    self.call_slashing_hooks(api, storage, router, block, &validator, percentage)?;
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
                 self.slash(api, &mut staking_storage, block, &validator, percentage)?;
+                Ok(AppResponse { events, data: None })
+            }
+            StakingSudo::UpdateParams { params } => {
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                for validator in self.get_validators(&staking_storage)? {
+                    let validator = api.addr_validate(&validator.address)?;
+                    Self::update_rewards(api, &mut staking_storage, block, &validator)?;
+                }
+                let old_params = Self::get_staking_info(&staking_storage)?;
+                STAKING_INFO.save(&mut staking_storage, &params)?;
+                let changes = [
+                    (
+                        "bonded_denom",
+                        old_params.bonded_denom != params.bonded_denom,
+                        params.bonded_denom,
+                    ),
+                    (
+                        "unbonding_time",
+                        old_params.unbonding_time != params.unbonding_time,
+                        params.unbonding_time.to_string(),
+                    ),
+                    ("apr", old_params.apr != params.apr, params.apr.to_string()),
+                    (
+                        "max_validators",
+                        old_params.max_validators != params.max_validators,
+                        params.max_validators.to_string(),
+                    ),
+                    (
+                        "max_entries",
+                        old_params.max_entries != params.max_entries,
+                        params.max_entries.to_string(),
+                    ),
+                    (
+                        "min_commission_rate",
+                        old_params.min_commission_rate != params.min_commission_rate,
+                        params.min_commission_rate.to_string(),
+                    ),
+                    (
+                        "historical_entries",
+                        old_params.historical_entries != params.historical_entries,
+                        params.historical_entries.to_string(),
+                    ),
+                ];
+                for (param, changed, value) in changes {
+                    if changed {
+                        record_param_change(storage, block.height, "staking", param, value)?;
+                    }
+                }
+                Ok(AppResponse::default())
+            }
+            StakingSudo::AddValidator { validator } => {
+                let hook_msg = StakingHookMsg::AfterValidatorCreated {
+                    validator: validator.address.clone(),
+                };
+                self.add_validator(api, storage, block, validator)?;
+                let events =
+                    self.call_staking_hooks(api, storage, router, block, vec![hook_msg])?;
+                Ok(AppResponse { events, data: None })
+            }
+            StakingSudo::EditValidator {
+                validator,
+                commission,
+            } => {
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                let validator = api.addr_validate(&validator)?;
+                Self::update_rewards(api, &mut staking_storage, block, &validator)?;
+                let mut validator_obj = VALIDATOR_MAP.load(&staking_storage, &validator)?;
+                let mut validator_info = VALIDATOR_INFO.load(&staking_storage, &validator)?;
+                // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/staking/types/commission.go#L77-L97
+                if block.time < validator_info.commission_update_time.plus_hours(24) {
+                    bail!("commission cannot be changed more than once in 24h");
+                }
+                if commission > validator_obj.max_commission {
+                    bail!("commission cannot be more than the max rate");
+                }
+                Self::validate_min_commission(&staking_storage, commission)?;
+                if commission.abs_diff(validator_obj.commission) > validator_obj.max_change_rate {
+                    bail!("commission cannot be changed more than max change rate");
+                }
+                validator_obj.commission = commission;
+                VALIDATOR_MAP.save(&mut staking_storage, &validator, &validator_obj)?;
+                validator_info.commission_update_time = block.time;
+                VALIDATOR_INFO.save(&mut staking_storage, &validator, &validator_info)?;
+                let validators = self.get_validators(&staking_storage)?;
+                Self::save_validators(
+                    &mut staking_storage,
+                    validators.into_iter().map(|v| {
+                        if v.address == validator_obj.address {
+                            validator_obj.clone()
+                        } else {
+                            v
+                        }
+                    }),
+                )?;
+                Ok(AppResponse {
+                    events: vec![Event::new("edit_validator")
+                        .add_attribute("commission_rate", commission.to_string())],
+                    data: None,
+                })
+            }
+            StakingSudo::EditDescription {
+                validator,
+                description,
+            } => {
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                let validator = api.addr_validate(&validator)?;
+                let mut validator_info = VALIDATOR_INFO
+                    .may_load(&staking_storage, &validator)?
+                    .ok_or_else(|| anyhow!("validator does not exist"))?;
+                validator_info.description = validator_info.description.updated(description)?;
+                VALIDATOR_INFO.save(&mut staking_storage, &validator, &validator_info)?;
                 Ok(AppResponse::default())
             }
+            StakingSudo::SetRewardAccrual { validator, accrual } => {
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                let validator = api.addr_validate(&validator)?;
+                Self::update_rewards(api, &mut staking_storage, block, &validator)?;
+                let mut validator_info = VALIDATOR_INFO.load(&staking_storage, &validator)?;
+                validator_info.reward_accrual = accrual;
+                VALIDATOR_INFO.save(&mut staking_storage, &validator, &validator_info)?;
+                Ok(AppResponse::default())
+            }
+            StakingSudo::RemoveValidator { validator } => {
+                let validator = api.addr_validate(&validator)?;
+                let mut res = self.remove_validator(api, storage, router, block, &validator)?;
+                let hook_msg = StakingHookMsg::AfterValidatorRemoved {
+                    validator: validator.to_string(),
+                };
+                res.events.extend(self.call_staking_hooks(
+                    api,
+                    storage,
+                    router,
+                    block,
+                    vec![hook_msg],
+                )?);
+                Ok(res)
+            }
+            StakingSudo::SetMinSelfDelegation {
+                validator,
+                min_self_delegation,
+            } => {
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                let validator = api.addr_validate(&validator)?;
+                let mut validator_info = VALIDATOR_INFO
+                    .may_load(&staking_storage, &validator)?
+                    .ok_or_else(|| anyhow!("validator does not exist"))?;
+                validator_info.min_self_delegation = min_self_delegation;
+                VALIDATOR_INFO.save(&mut staking_storage, &validator, &validator_info)?;
+                let events =
+                    if Self::self_delegation(&staking_storage, &validator)? < min_self_delegation {
+                        self.jail_if_undelegated(api, &mut staking_storage, block, &validator)?
+                    } else {
+                        vec![]
+                    };
+                Ok(AppResponse { events, data: None })
+            }
+            StakingSudo::Jail {
+                validator,
+                jailed_until,
+            } => {
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                let validator = api.addr_validate(&validator)?;
+                self.jail(api, &mut staking_storage, block, &validator, jailed_until)
+            }
+            StakingSudo::Unjail { validator } => {
+                let validator = api.addr_validate(&validator)?;
+                self.unjail_validator(storage, block, &validator)
+            }
+            StakingSudo::SlashForDowntime { validator } => {
+                let params = self.slashing_params(storage)?;
+                let validator = api.addr_validate(&validator)?;
+                let events = self.call_slashing_hooks(
+                    api,
+                    storage,
+                    router,
+                    block,
+                    &validator,
+                    params.slash_fraction_downtime,
+                )?;
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                self.slash(
+                    api,
+                    &mut staking_storage,
+                    block,
+                    &validator,
+                    params.slash_fraction_downtime,
+                )?;
+                let jailed_until = block.time.plus_seconds(params.downtime_jail_duration);
+                let mut res =
+                    self.jail(api, &mut staking_storage, block, &validator, jailed_until)?;
+                res.events.splice(0..0, events);
+                Ok(res)
+            }
+            StakingSudo::SlashForDoubleSign { validator } => {
+                let params = self.slashing_params(storage)?;
+                let validator = api.addr_validate(&validator)?;
+                let events = self.call_slashing_hooks(
+                    api,
+                    storage,
+                    router,
+                    block,
+                    &validator,
+                    params.slash_fraction_double_sign,
+                )?;
+                let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+                self.slash(
+                    api,
+                    &mut staking_storage,
+                    block,
+                    &validator,
+                    params.slash_fraction_double_sign,
+                )?;
+                // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/evidence/keeper/infraction.go#L113-L121
+                let jailed_until = Timestamp::from_nanos(u64::MAX);
+                let mut res =
+                    self.jail(api, &mut staking_storage, block, &validator, jailed_until)?;
+                let mut validator_info = VALIDATOR_INFO.load(&staking_storage, &validator)?;
+                validator_info.tombstoned = true;
+                VALIDATOR_INFO.save(&mut staking_storage, &validator, &validator_info)?;
+                res.events.splice(0..0, events);
+                Ok(res)
+            }
         }
     }
 }
@@ -870,7 +4325,7 @@ impl Module for StakeKeeper {
 /// This module likely manages the distribution of rewards and fees within the blockchain network.
 /// It could handle tasks like distributing block rewards to validators and delegators,
 /// and managing community funding mechanisms.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct DistributionKeeper {}
 
 impl DistributionKeeper {
@@ -894,15 +4349,220 @@ impl DistributionKeeper {
 
         // load updated rewards for delegator
         let mut shares = STAKES.load(&staking_storage, (delegator, validator))?;
+        if StakeKeeper::get_reward_distribution(&staking_storage)? == RewardDistribution::F1 {
+            let mut validator_info = VALIDATOR_INFO.load(&staking_storage, validator)?;
+            let ending_period = StakeKeeper::increment_period(
+                &mut staking_storage,
+                validator,
+                &mut validator_info,
+            )?;
+            VALIDATOR_INFO.save(&mut staking_storage, validator, &validator_info)?;
+            StakeKeeper::settle_rewards(&staking_storage, validator, &mut shares, ending_period)?;
+        }
         let rewards = Uint128::new(1).mul_floor(shares.rewards); // convert to Uint128
 
-        // remove rewards from delegator
-        shares.rewards = Decimal::zero();
+        // remove withdrawn rewards from delegator, the decimal remainder is kept,
+        // so the rewards remain consistent with validator's outstanding rewards
+        shares.rewards -= Decimal::from_ratio(rewards, 1u128);
         STAKES.save(&mut staking_storage, (delegator, validator), &shares)?;
 
         Ok(rewards)
     }
 
+    /// Returns the commission accrued by the validator until the block and not withdrawn yet.
+    pub fn validator_commission(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        validator: &Addr,
+    ) -> AnyResult<Vec<DecCoin>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let validator_info = VALIDATOR_INFO
+            .may_load(&staking_storage, validator)?
+            .ok_or_else(|| anyhow!("validator does not exist"))?;
+        let validator_obj = VALIDATOR_MAP.load(&staking_storage, validator)?;
+        let staking_info = StakeKeeper::get_staking_info(&staking_storage)?;
+        let mut commission = validator_info.commission;
+        if !validator_info.is_rewarded(block) {
+            let (_, new_commission) = StakeKeeper::calculate_rewards(
+                block,
+                &validator_info,
+                staking_info.apr,
+                validator_obj.commission,
+            );
+            commission += new_commission;
+        }
+        Ok(if commission.is_zero() {
+            vec![]
+        } else {
+            vec![DecCoin::new(commission, staking_info.bonded_denom)]
+        })
+    }
+
+    /// Returns rewards of the validator accrued until the block and not withdrawn yet,
+    /// the sum of rewards of all its delegators and the validator's commission.
+    pub fn validator_outstanding_rewards(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        validator: &Addr,
+    ) -> AnyResult<Vec<DecCoin>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        let validator_info = VALIDATOR_INFO
+            .may_load(&staking_storage, validator)?
+            .ok_or_else(|| anyhow!("validator does not exist"))?;
+        let validator_obj = VALIDATOR_MAP.load(&staking_storage, validator)?;
+        let staking_info = StakeKeeper::get_staking_info(&staking_storage)?;
+        let distribution = StakeKeeper::get_reward_distribution(&staking_storage)?;
+        let mut rewards = validator_info.commission;
+        for staker in &validator_info.stakers {
+            let shares = STAKES.load(&staking_storage, (staker, validator))?;
+            rewards += match distribution {
+                RewardDistribution::Linear => shares.rewards,
+                // including rewards of the periods not settled yet
+                RewardDistribution::F1 => StakeKeeper::delegation_rewards(
+                    &staking_storage,
+                    validator,
+                    &validator_info,
+                    &shares,
+                    Decimal::zero(),
+                )?,
+            };
+        }
+        if !validator_info.is_rewarded(block) {
+            // new rewards of delegators are accrued only when there are any delegators
+            let (new_rewards, new_commission) = StakeKeeper::calculate_rewards(
+                block,
+                &validator_info,
+                staking_info.apr,
+                validator_obj.commission,
+            );
+            if !validator_info.stakers.is_empty() {
+                rewards += new_rewards;
+            }
+            rewards += new_commission;
+        }
+        Ok(if rewards.is_zero() {
+            vec![]
+        } else {
+            vec![DecCoin::new(rewards, staking_info.bonded_denom)]
+        })
+    }
+
+    /// Returns rewards accrued by the delegator until the block,
+    /// for every validator the delegator has delegated to.
+    pub fn delegation_rewards(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+        delegator: &Addr,
+    ) -> AnyResult<Vec<(Addr, Coin)>> {
+        let staking_storage = prefixed_read(storage, NAMESPACE_STAKING);
+        STAKES
+            .prefix(delegator)
+            .range(&staking_storage, None, None, Order::Ascending)
+            .map(|entry| {
+                let (validator, shares) = entry?;
+                let validator_obj = VALIDATOR_MAP.load(&staking_storage, &validator)?;
+                let validator_info = VALIDATOR_INFO.load(&staking_storage, &validator)?;
+                let reward = StakeKeeper::get_rewards_internal(
+                    &staking_storage,
+                    block,
+                    &shares,
+                    &validator_obj,
+                    &validator_info,
+                )?;
+                Ok((validator, reward))
+            })
+            .collect()
+    }
+
+    /// Returns coins in the community pool.
+    pub fn community_pool(&self, storage: &dyn Storage) -> AnyResult<Vec<Coin>> {
+        let distribution_storage = prefixed_read(storage, NAMESPACE_DISTRIBUTION);
+        Ok(COMMUNITY_POOL
+            .may_load(&distribution_storage)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the address receiving rewards withdrawn by the delegator,
+    /// set with [DistributionMsg::SetWithdrawAddress]; the delegator's own address by default.
+    pub fn delegator_withdraw_address(
+        &self,
+        storage: &dyn Storage,
+        delegator: &Addr,
+    ) -> AnyResult<Addr> {
+        let distribution_storage = prefixed_read(storage, NAMESPACE_DISTRIBUTION);
+        Self::get_withdraw_address(&distribution_storage, delegator)
+    }
+
+    /// Records tokens moved to the distribution module's account, the provisioned part
+    /// is held for rewards allocated to validators, the rest is added to the community pool.
+    pub(crate) fn add_allocated_tokens(
+        storage: &mut dyn Storage,
+        collected: Coin,
+        provisioned: Uint128,
+    ) -> AnyResult<()> {
+        let pool = NativeBalance(Self::new().community_pool(storage)?)
+            + NativeBalance(vec![coin(
+                (collected.amount - provisioned).u128(),
+                collected.denom,
+            )]);
+        let mut distribution_storage = prefixed(storage, NAMESPACE_DISTRIBUTION);
+        COMMUNITY_POOL.save(&mut distribution_storage, &pool.into_vec())?;
+        let provisioned = provisioned
+            + PROVISIONED_REWARDS
+                .may_load(&distribution_storage)?
+                .unwrap_or_default();
+        PROVISIONED_REWARDS.save(&mut distribution_storage, &provisioned)?;
+        Ok(())
+    }
+
+    /// Pays out withdrawn rewards. Minted tokens allocated as rewards and held by the distribution
+    /// module's account are sent first, the rest of rewards is minted directly to the receiver.
+    fn pay_rewards<ExecC: CustomMsg, QueryC: CustomQuery>(
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        receiver: Addr,
+        rewards: Coin,
+    ) -> AnyResult<()> {
+        let mut distribution_storage = prefixed(storage, NAMESPACE_DISTRIBUTION);
+        let provisioned = PROVISIONED_REWARDS
+            .may_load(&distribution_storage)?
+            .unwrap_or_default();
+        let sent = provisioned.min(rewards.amount);
+        let minted = rewards.amount - sent;
+        if !sent.is_zero() {
+            PROVISIONED_REWARDS.save(&mut distribution_storage, &(provisioned - sent))?;
+            router.execute(
+                api,
+                storage,
+                block,
+                module_address(api, DISTRIBUTION_MODULE)?,
+                BankMsg::Send {
+                    to_address: receiver.to_string(),
+                    amount: vec![coin(sent.u128(), &rewards.denom)],
+                }
+                .into(),
+            )?;
+        }
+        if sent.is_zero() || !minted.is_zero() {
+            router.sudo(
+                api,
+                storage,
+                block,
+                BankSudo::Mint {
+                    to_address: receiver.into_string(),
+                    amount: vec![coin(minted.u128(), &rewards.denom)],
+                }
+                .into(),
+            )?;
+        }
+        Ok(())
+    }
+
     /// Returns the withdrawal address for specified delegator.
     pub fn get_withdraw_address(storage: &dyn Storage, delegator: &Addr) -> AnyResult<Addr> {
         Ok(match WITHDRAW_ADDRESS.may_load(storage, delegator)? {
@@ -932,7 +4592,94 @@ impl DistributionKeeper {
     }
 }
 
-impl Distribution for DistributionKeeper {}
+impl Distribution for DistributionKeeper {
+    fn withdraw_validator_commission<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        validator: Addr,
+    ) -> AnyResult<AppResponse> {
+        let mut staking_storage = prefixed(storage, NAMESPACE_STAKING);
+        StakeKeeper::update_rewards(api, &mut staking_storage, block, &validator)?;
+        let mut validator_info = VALIDATOR_INFO.load(&staking_storage, &validator)?;
+        // the decimal remainder stays with the validator, like in Cosmos SDK
+        let amount = Uint128::new(1).mul_floor(validator_info.commission);
+        if amount.is_zero() {
+            // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/distribution/types/errors.go#L20
+            bail!("no validator commission to withdraw");
+        }
+        validator_info.commission -= Decimal::from_ratio(amount, 1u128);
+        VALIDATOR_INFO.save(&mut staking_storage, &validator, &validator_info)?;
+
+        let bonded_denom = StakeKeeper::get_staking_info(&staking_storage)?.bonded_denom;
+        Self::pay_rewards(
+            api,
+            storage,
+            router,
+            block,
+            validator,
+            coin(amount.u128(), &bonded_denom),
+        )?;
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/distribution/keeper/keeper.go#L147-L153
+        let events = vec![Event::new("withdraw_commission")
+            .add_attribute("amount", format!("{}{}", amount, bonded_denom))];
+        Ok(AppResponse { events, data: None })
+    }
+
+    fn fund_community_pool<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        depositor: Addr,
+        amount: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        let pool = NativeBalance(self.community_pool(storage)?) + NativeBalance(amount.clone());
+        let mut distribution_storage = prefixed(storage, NAMESPACE_DISTRIBUTION);
+        COMMUNITY_POOL.save(&mut distribution_storage, &pool.into_vec())?;
+        router.execute(
+            api,
+            storage,
+            block,
+            depositor,
+            BankMsg::Send {
+                to_address: module_address(api, DISTRIBUTION_MODULE)?.to_string(),
+                amount,
+            }
+            .into(),
+        )
+    }
+
+    fn community_pool_spend<ExecC: CustomMsg, QueryC: CustomQuery>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        recipient: Addr,
+        amount: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        // see https://github.com/cosmos/cosmos-sdk/blob/v0.47.5/x/distribution/keeper/keeper.go#L170-L186
+        let pool = (NativeBalance(self.community_pool(storage)?) - amount.clone())
+            .map_err(|_| anyhow!("community pool does not have sufficient coins to distribute"))?;
+        let mut distribution_storage = prefixed(storage, NAMESPACE_DISTRIBUTION);
+        COMMUNITY_POOL.save(&mut distribution_storage, &pool.into_vec())?;
+        router.execute(
+            api,
+            storage,
+            block,
+            module_address(api, DISTRIBUTION_MODULE)?,
+            BankMsg::Send {
+                to_address: recipient.into_string(),
+                amount,
+            }
+            .into(),
+        )
+    }
+}
 
 impl Module for DistributionKeeper {
     type ExecT = DistributionMsg;
@@ -958,19 +4705,13 @@ impl Module for DistributionKeeper {
                 let distribution_storage = prefixed_read(storage, NAMESPACE_DISTRIBUTION);
                 let staking_info = StakeKeeper::get_staking_info(&staking_storage)?;
                 let receiver = Self::get_withdraw_address(&distribution_storage, &sender)?;
-                // directly mint rewards to delegator
-                router.sudo(
+                Self::pay_rewards(
                     api,
                     storage,
+                    router,
                     block,
-                    BankSudo::Mint {
-                        to_address: receiver.into_string(),
-                        amount: vec![Coin {
-                            amount: rewards,
-                            denom: staking_info.bonded_denom.clone(),
-                        }],
-                    }
-                    .into(),
+                    receiver,
+                    coin(rewards.u128(), &staking_info.bonded_denom),
                 )?;
 
                 let events = vec![Event::new("withdraw_delegator_reward")
@@ -994,6 +4735,10 @@ impl Module for DistributionKeeper {
                         .add_attribute("withdraw_address", address)],
                 })
             }
+            #[cfg(feature = "cosmwasm_2_0")]
+            DistributionMsg::FundCommunityPool { amount } => {
+                self.fund_community_pool(api, storage, router, block, sender, amount)
+            }
             m => bail!("Unsupported distribution message: {:?}", m),
         }
     }
@@ -1025,8 +4770,8 @@ impl Module for DistributionKeeper {
 mod test {
     use super::*;
     use crate::{
-        app::MockRouter, BankKeeper, FailingModule, GovFailingModule, IbcFailingModule, Router,
-        StargateFailing, WasmKeeper,
+        app::MockRouter, BankKeeper, FailingModule, GovFailingModule, IbcFailingModule, MintKeeper,
+        Router, StargateFailing, WasmKeeper,
     };
     use cosmwasm_std::{
         from_json,
@@ -1044,6 +4789,7 @@ mod test {
         IbcFailingModule,
         GovFailingModule,
         StargateFailing,
+        MintKeeper,
     >;
 
     fn mock_router() -> BasicRouter {
@@ -1056,6 +4802,11 @@ mod test {
             ibc: IbcFailingModule::new(),
             gov: GovFailingModule::new(),
             stargate: StargateFailing,
+            mint: MintKeeper::new(),
+            tracer: Default::default(),
+            meter: Default::default(),
+            invariants: Default::default(),
+            snapshots: Default::default(),
         }
     }
 
@@ -1078,6 +4829,7 @@ mod test {
                     bonded_denom: "TOKEN".to_string(),
                     unbonding_time: 60,
                     apr,
+                    ..StakingInfo::default()
                 },
             )
             .unwrap();
@@ -1,12 +1,28 @@
 //! # Handler for `CosmosMsg::Stargate`, `CosmosMsg::Any`, `QueryRequest::Stargate` and `QueryRequest::Grpc` messages
 
+use crate::app::CosmosSdkMsg;
+use crate::bank::{decode_multi_send, MSG_MULTI_SEND};
+use crate::denom_traces::{denom_hash, denom_trace, denom_traces, DenomTrace};
 use crate::error::AnyResult;
-use crate::{AppResponse, CosmosRouter};
+use crate::gov::{
+    decode_cancel_proposal, decode_deposit, decode_submit_proposal, MSG_CANCEL_PROPOSAL,
+    MSG_DEPOSIT, MSG_SUBMIT_PROPOSAL,
+};
+use crate::staking::{
+    decode_begin_redelegate, decode_create_validator, decode_edit_validator,
+    decode_fund_community_pool, decode_liquid_staking, decode_unjail,
+    decode_withdraw_validator_commission, is_liquid_staking_msg,
+    HistoricalInfo as StakingHistoricalInfo, ValidatorStake, MSG_BEGIN_REDELEGATE,
+    MSG_CREATE_VALIDATOR, MSG_EDIT_VALIDATOR, MSG_FUND_COMMUNITY_POOL, MSG_UNJAIL,
+    MSG_WITHDRAW_VALIDATOR_COMMISSION,
+};
+use crate::{AppResponse, CosmosRouter, StakeKeeper};
 use anyhow::bail;
 use cosmwasm_std::{
-    to_json_binary, Addr, AnyMsg, Api, Binary, BlockInfo, CustomMsg, CustomQuery, Empty, GrpcQuery,
-    Querier, Storage,
+    to_json_binary, Addr, AnyMsg, Api, Binary, BlockInfo, CosmosMsg, CustomMsg, CustomQuery,
+    Decimal, Empty, GrpcQuery, Querier, Storage,
 };
+use prost::Message;
 use serde::de::DeserializeOwned;
 
 /// Interface of handlers for processing `Stargate`/`Any` message variants
@@ -79,11 +95,13 @@ pub trait Stargate {
 }
 
 /// Always failing handler for `Stargate`/`Any` message variants and `Stargate`/`Grpc` queries.
+#[derive(Clone)]
 pub struct StargateFailing;
 
 impl Stargate for StargateFailing {}
 
 /// Always accepting handler for `Stargate`/`Any` message variants and `Stargate`/`Grpc` queries.
+#[derive(Clone)]
 pub struct StargateAccepting;
 
 impl Stargate for StargateAccepting {
@@ -143,3 +161,410 @@ impl Stargate for StargateAccepting {
         Ok(Binary::default())
     }
 }
+
+/// Handler executing protobuf-encoded Cosmos SDK messages in the modules of the [App](crate::App),
+/// like the message service router of a Cosmos SDK chain.
+///
+/// The following `Stargate`/`Any` messages are supported, signed by the sender:
+///
+/// - `/cosmos.bank.v1beta1.MsgMultiSend`,
+/// - `/cosmos.gov.v1.MsgSubmitProposal`, `/cosmos.gov.v1.MsgDeposit`,
+///   `/cosmos.gov.v1.MsgCancelProposal`,
+/// - `/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission`,
+///   `/cosmos.distribution.v1beta1.MsgFundCommunityPool`,
+/// - `/cosmos.staking.v1beta1.MsgCreateValidator`, `/cosmos.staking.v1beta1.MsgEditValidator`,
+///   `/cosmos.staking.v1beta1.MsgBeginRedelegate`,
+/// - `/cosmos.slashing.v1beta1.MsgUnjail`,
+/// - liquid staking messages: `MsgTokenizeShares`, `MsgRedeemTokensForShares`
+///   and `MsgTransferTokenizeShareRecord`.
+///
+/// Messages are decoded into [CosmosSdkMsg] (or [StakingMsg] for `MsgBeginRedelegate`)
+/// and executed by the router. All other messages and queries are rejected.
+#[derive(Clone)]
+pub struct CosmosSdkStargate;
+
+impl Stargate for CosmosSdkStargate {
+    fn execute_stargate<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        type_url: String,
+        value: Binary,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let msg = AnyMsg { type_url, value };
+        self.execute_any(api, storage, router, block, sender, msg)
+    }
+
+    fn execute_any<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: AnyMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        let sdk_msg = match msg.type_url.as_str() {
+            MSG_MULTI_SEND => decode_multi_send(&msg.value)?,
+            MSG_SUBMIT_PROPOSAL => decode_submit_proposal(&sender, &msg.value)?,
+            MSG_DEPOSIT => decode_deposit(&sender, &msg.value)?,
+            MSG_CANCEL_PROPOSAL => decode_cancel_proposal(&sender, &msg.value)?,
+            MSG_WITHDRAW_VALIDATOR_COMMISSION => {
+                decode_withdraw_validator_commission(&sender, &msg.value)?
+            }
+            MSG_FUND_COMMUNITY_POOL => decode_fund_community_pool(&sender, &msg.value)?,
+            MSG_CREATE_VALIDATOR => decode_create_validator(&sender, &msg.value)?,
+            MSG_EDIT_VALIDATOR => decode_edit_validator(&sender, &msg.value)?,
+            MSG_UNJAIL => decode_unjail(&sender, &msg.value)?,
+            MSG_BEGIN_REDELEGATE => {
+                let msg = decode_begin_redelegate(&sender, &msg.value)?;
+                return router.execute(api, storage, block, sender, CosmosMsg::Staking(msg));
+            }
+            type_url if is_liquid_staking_msg(type_url) => {
+                CosmosSdkMsg::LiquidStaking(decode_liquid_staking(&sender, type_url, &msg.value)?)
+            }
+            _ => bail!("Unexpected any execute: msg={:?} from {}", msg, sender),
+        };
+        router.execute_sdk_msg(api, storage, block, sender, sdk_msg)
+    }
+}
+
+/// Path of the query for the latest block.
+const GET_LATEST_BLOCK: &str = "/cosmos.base.tendermint.v1beta1.Service/GetLatestBlock";
+/// Path of the query for the latest validator set.
+const GET_LATEST_VALIDATOR_SET: &str =
+    "/cosmos.base.tendermint.v1beta1.Service/GetLatestValidatorSet";
+/// Path of the query for the historical info.
+const HISTORICAL_INFO: &str = "/cosmos.staking.v1beta1.Query/HistoricalInfo";
+
+/// Handler answering queries for the latest block header, the latest validator set
+/// and the historical info, using the block and validators simulated by the [App](crate::App).
+///
+/// The following `Stargate`/`Grpc` queries are supported, responses are encoded in protobuf:
+///
+/// - `/cosmos.base.tendermint.v1beta1.Service/GetLatestBlock`,
+/// - `/cosmos.base.tendermint.v1beta1.Service/GetLatestValidatorSet`,
+/// - `/cosmos.staking.v1beta1.Query/HistoricalInfo`.
+///
+/// Validators are read from the storage of the [StakeKeeper], which records the historical info
+/// of each block when the block is left, like the `BeginBlocker` of the Cosmos SDK staking module.
+/// The voting power is the amount of bonded tokens divided by 10^6.
+/// All other messages and queries are rejected.
+///
+/// The historical info query is also answered without this handler, by the staking module.
+#[derive(Clone)]
+pub struct StargateTendermint;
+
+impl Stargate for StargateTendermint {
+    fn query_stargate(
+        &self,
+        _api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        block: &BlockInfo,
+        path: String,
+        data: Binary,
+    ) -> AnyResult<Binary> {
+        match path.as_str() {
+            GET_LATEST_BLOCK => Ok(encode(&GetLatestBlockResponse {
+                block: Some(TmBlock {
+                    header: Some(Header::new(block)),
+                }),
+            })),
+            GET_LATEST_VALIDATOR_SET => {
+                let validators = StakeKeeper::validator_set(storage)?;
+                Ok(encode(&GetLatestValidatorSetResponse {
+                    block_height: block.height as i64,
+                    validators: validators.iter().map(TmValidator::new).collect(),
+                }))
+            }
+            HISTORICAL_INFO => query_historical_info(storage, block, &data),
+            _ => bail!("Unexpected stargate query: path={}, data={}", path, data),
+        }
+    }
+
+    fn query_grpc(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        block: &BlockInfo,
+        request: GrpcQuery,
+    ) -> AnyResult<Binary> {
+        self.query_stargate(api, storage, querier, block, request.path, request.data)
+    }
+}
+
+/// Returns the protobuf-encoded response to the historical info query,
+/// the validator set of the current block is returned for its height.
+pub(crate) fn query_historical_info(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    data: &[u8],
+) -> AnyResult<Binary> {
+    let request = QueryHistoricalInfoRequest::decode(data)?;
+    let height = u64::try_from(request.height)?;
+    let info = if height == block.height {
+        StakingHistoricalInfo {
+            block: block.clone(),
+            validators: StakeKeeper::validator_set(storage)?,
+        }
+    } else {
+        match StakeKeeper::historical_info(storage, height)? {
+            Some(info) => info,
+            None => bail!("historical info for height {} not found", height),
+        }
+    };
+    Ok(encode(&QueryHistoricalInfoResponse {
+        hist: Some(HistoricalInfo {
+            header: Some(Header::new(&info.block)),
+            valset: info.validators.iter().map(Validator::new).collect(),
+        }),
+    }))
+}
+
+/// Path of the query for the denomination trace.
+const DENOM_TRACE: &str = "/ibc.applications.transfer.v1.Query/DenomTrace";
+/// Path of the query for all denomination traces.
+const DENOM_TRACES: &str = "/ibc.applications.transfer.v1.Query/DenomTraces";
+/// Path of the query for the denomination hash.
+const DENOM_HASH: &str = "/ibc.applications.transfer.v1.Query/DenomHash";
+
+/// Handler answering ICS-20 queries for denomination traces registered
+/// in the [App](crate::App) using [register_denom_trace](crate::App::register_denom_trace).
+///
+/// The following `Stargate`/`Grpc` queries are supported, responses are encoded in protobuf:
+///
+/// - `/ibc.applications.transfer.v1.Query/DenomTrace`,
+/// - `/ibc.applications.transfer.v1.Query/DenomTraces`,
+/// - `/ibc.applications.transfer.v1.Query/DenomHash`.
+///
+/// All other messages and queries are rejected.
+#[derive(Clone)]
+pub struct StargateDenomTraces;
+
+impl Stargate for StargateDenomTraces {
+    fn query_stargate(
+        &self,
+        _api: &dyn Api,
+        storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        path: String,
+        data: Binary,
+    ) -> AnyResult<Binary> {
+        match path.as_str() {
+            DENOM_TRACE => {
+                let request = QueryDenomTraceRequest::decode(data.as_slice())?;
+                match denom_trace(storage, &request.hash)? {
+                    Some(trace) => Ok(encode(&QueryDenomTraceResponse {
+                        denom_trace: Some(trace.into()),
+                    })),
+                    None => bail!("denomination trace not found: {}", request.hash),
+                }
+            }
+            DENOM_TRACES => Ok(encode(&QueryDenomTracesResponse {
+                denom_traces: denom_traces(storage)?.into_iter().map(Into::into).collect(),
+            })),
+            DENOM_HASH => {
+                let request = QueryDenomHashRequest::decode(data.as_slice())?;
+                match denom_hash(storage, &request.trace)? {
+                    Some(hash) => Ok(encode(&QueryDenomHashResponse { hash })),
+                    None => bail!("denomination trace not found: {}", request.trace),
+                }
+            }
+            _ => bail!("Unexpected stargate query: path={}, data={}", path, data),
+        }
+    }
+
+    fn query_grpc(
+        &self,
+        api: &dyn Api,
+        storage: &dyn Storage,
+        querier: &dyn Querier,
+        block: &BlockInfo,
+        request: GrpcQuery,
+    ) -> AnyResult<Binary> {
+        self.query_stargate(api, storage, querier, block, request.path, request.data)
+    }
+}
+
+/// Encodes the protobuf message.
+fn encode(message: &impl Message) -> Binary {
+    message.encode_to_vec().into()
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Timestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Header {
+    #[prost(string, tag = "2")]
+    pub chain_id: String,
+    #[prost(int64, tag = "3")]
+    pub height: i64,
+    #[prost(message, optional, tag = "4")]
+    pub time: Option<Timestamp>,
+}
+
+impl Header {
+    fn new(block: &BlockInfo) -> Self {
+        Self {
+            chain_id: block.chain_id.clone(),
+            height: block.height as i64,
+            time: Some(Timestamp {
+                seconds: block.time.seconds() as i64,
+                nanos: block.time.subsec_nanos() as i32,
+            }),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct TmBlock {
+    #[prost(message, optional, tag = "1")]
+    pub header: Option<Header>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct GetLatestBlockResponse {
+    #[prost(message, optional, tag = "2")]
+    pub block: Option<TmBlock>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct TmValidator {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(int64, tag = "3")]
+    pub voting_power: i64,
+}
+
+impl TmValidator {
+    fn new(validator: &ValidatorStake) -> Self {
+        Self {
+            address: validator.address.clone(),
+            voting_power: (validator.tokens.u128() / 1_000_000) as i64,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct GetLatestValidatorSetResponse {
+    #[prost(int64, tag = "1")]
+    pub block_height: i64,
+    #[prost(message, repeated, tag = "2")]
+    pub validators: Vec<TmValidator>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryHistoricalInfoRequest {
+    #[prost(int64, tag = "1")]
+    pub height: i64,
+}
+
+/// Bonded status of the validator in Cosmos SDK.
+const BOND_STATUS_BONDED: i32 = 3;
+
+#[derive(Clone, PartialEq, Message)]
+struct Validator {
+    #[prost(string, tag = "1")]
+    pub operator_address: String,
+    #[prost(int32, tag = "4")]
+    pub status: i32,
+    #[prost(string, tag = "5")]
+    pub tokens: String,
+    #[prost(string, tag = "6")]
+    pub delegator_shares: String,
+}
+
+impl Validator {
+    fn new(validator: &ValidatorStake) -> Self {
+        Self {
+            operator_address: validator.address.clone(),
+            status: BOND_STATUS_BONDED,
+            tokens: validator.tokens.to_string(),
+            // one share per token, encoded like `LegacyDec`, with 18 decimal places
+            delegator_shares: Decimal::from_ratio(validator.tokens, 1u128)
+                .atomics()
+                .to_string(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct HistoricalInfo {
+    #[prost(message, optional, tag = "1")]
+    pub header: Option<Header>,
+    #[prost(message, repeated, tag = "2")]
+    pub valset: Vec<Validator>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryHistoricalInfoResponse {
+    #[prost(message, optional, tag = "1")]
+    pub hist: Option<HistoricalInfo>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoDenomTrace {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(string, tag = "2")]
+    pub base_denom: String,
+}
+
+impl From<DenomTrace> for ProtoDenomTrace {
+    fn from(trace: DenomTrace) -> Self {
+        Self {
+            path: trace.path,
+            base_denom: trace.base_denom,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomTraceRequest {
+    #[prost(string, tag = "1")]
+    pub hash: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomTraceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub denom_trace: Option<ProtoDenomTrace>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomTracesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub denom_traces: Vec<ProtoDenomTrace>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomHashRequest {
+    #[prost(string, tag = "1")]
+    pub trace: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomHashResponse {
+    #[prost(string, tag = "1")]
+    pub hash: String,
+}
@@ -0,0 +1,420 @@
+//! # Strict decoding of contract messages
+//!
+//! Contracts compiled with `#[serde(deny_unknown_fields)]` reject messages containing
+//! fields not defined in the message type. Message types used in tests usually do not
+//! carry this attribute, so [from_json_strict] detects such fields by wrapping the deserializer
+//! and tracking every value that was skipped by the deserialized type.
+
+use crate::error::AnyResult;
+use cosmwasm_std::{from_json, StdError};
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::Deserialize;
+use std::any::type_name;
+use std::cell::RefCell;
+use std::fmt;
+
+/// Deserializes the message from JSON, rejecting fields unknown to the message type.
+pub(crate) fn from_json_strict<T: DeserializeOwned>(msg: impl AsRef<[u8]>) -> AnyResult<T> {
+    let strict = from_json::<Strict<T>>(msg).map_err(|err| match err {
+        StdError::ParseErr { msg, .. } => StdError::parse_err(type_name::<T>(), msg),
+        err => err,
+    })?;
+    match strict.unknown_fields.first() {
+        Some(field) => {
+            Err(StdError::parse_err(type_name::<T>(), format!("unknown field `{}`", field)).into())
+        }
+        None => Ok(strict.value),
+    }
+}
+
+/// Deserialized value with the names of fields skipped during deserialization.
+struct Strict<T> {
+    value: T,
+    unknown_fields: Vec<String>,
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Strict<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tracker = Tracker::default();
+        let value = T::deserialize(StrictDeserializer {
+            inner: deserializer,
+            tracker: &tracker,
+        })?;
+        Ok(Self {
+            value,
+            unknown_fields: tracker.unknown_fields.into_inner(),
+        })
+    }
+}
+
+/// Tracks the most recently visited string (field name) and the names of skipped fields.
+#[derive(Default)]
+struct Tracker {
+    last_key: RefCell<String>,
+    unknown_fields: RefCell<Vec<String>>,
+}
+
+impl Tracker {
+    fn visit_key(&self, key: &str) {
+        *self.last_key.borrow_mut() = key.to_string();
+    }
+
+    fn skip_value(&self) {
+        let key = self.last_key.borrow().clone();
+        self.unknown_fields.borrow_mut().push(key);
+    }
+}
+
+struct StrictDeserializer<'t, D> {
+    inner: D,
+    tracker: &'t Tracker,
+}
+
+impl<'t, D> StrictDeserializer<'t, D> {
+    fn visitor<V>(&self, visitor: V) -> StrictVisitor<'t, V> {
+        StrictVisitor {
+            inner: visitor,
+            tracker: self.tracker,
+        }
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                let visitor = self.visitor(visitor);
+                self.inner.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 't, D: Deserializer<'de>> Deserializer<'de> for StrictDeserializer<'t, D> {
+    type Error = D::Error;
+
+    forward_deserialize!(
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_seq,
+        deserialize_map,
+        deserialize_identifier
+    );
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let visitor = self.visitor(visitor);
+        self.inner.deserialize_enum(name, variants, visitor)
+    }
+
+    /// Values of unknown fields are skipped by deserializing them as `IgnoredAny`.
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.tracker.skip_value();
+        self.inner.deserialize_ignored_any(visitor)
+    }
+}
+
+struct StrictVisitor<'t, V> {
+    inner: V,
+    tracker: &'t Tracker,
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty)),*) => {
+        $(
+            fn $method<E: serde::de::Error>(self, v: $ty) -> Result<Self::Value, E> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, 't, V: Visitor<'de>> Visitor<'de> for StrictVisitor<'t, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    forward_visit!(
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>)
+    );
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        self.tracker.visit_key(v);
+        self.inner.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E: serde::de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        self.tracker.visit_key(v);
+        self.inner.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+        self.tracker.visit_key(&v);
+        self.inner.visit_string(v)
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.inner.visit_some(StrictDeserializer {
+            inner: deserializer,
+            tracker: self.tracker,
+        })
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        self.inner.visit_newtype_struct(StrictDeserializer {
+            inner: deserializer,
+            tracker: self.tracker,
+        })
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.inner.visit_seq(StrictAccess {
+            inner: seq,
+            tracker: self.tracker,
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.inner.visit_map(StrictAccess {
+            inner: map,
+            tracker: self.tracker,
+        })
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.inner.visit_enum(StrictAccess {
+            inner: data,
+            tracker: self.tracker,
+        })
+    }
+}
+
+/// Wrapper passing the tracker down to nested seeds, sequences, maps and enums.
+struct StrictAccess<'t, A> {
+    inner: A,
+    tracker: &'t Tracker,
+}
+
+impl<'t, A> StrictAccess<'t, A> {
+    fn wrap<B>(&self, inner: B) -> StrictAccess<'t, B> {
+        StrictAccess {
+            inner,
+            tracker: self.tracker,
+        }
+    }
+}
+
+impl<'de, 't, S: DeserializeSeed<'de>> DeserializeSeed<'de> for StrictAccess<'t, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.inner.deserialize(StrictDeserializer {
+            inner: deserializer,
+            tracker: self.tracker,
+        })
+    }
+}
+
+impl<'de, 't, A: SeqAccess<'de>> SeqAccess<'de> for StrictAccess<'t, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, Self::Error> {
+        let seed = self.wrap(seed);
+        self.inner.next_element_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, 't, A: MapAccess<'de>> MapAccess<'de> for StrictAccess<'t, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let seed = self.wrap(seed);
+        self.inner.next_key_seed(seed)
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<S::Value, Self::Error> {
+        let seed = self.wrap(seed);
+        self.inner.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, 't, A: EnumAccess<'de>> EnumAccess<'de> for StrictAccess<'t, A> {
+    type Error = A::Error;
+    type Variant = StrictAccess<'t, A::Variant>;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, Self::Variant), Self::Error> {
+        let seed = self.wrap(seed);
+        let tracker = self.tracker;
+        self.inner.variant_seed(seed).map(|(value, variant)| {
+            (
+                value,
+                StrictAccess {
+                    inner: variant,
+                    tracker,
+                },
+            )
+        })
+    }
+}
+
+impl<'de, 't, A: VariantAccess<'de>> VariantAccess<'de> for StrictAccess<'t, A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<S::Value, Self::Error> {
+        let seed = self.wrap(seed);
+        self.inner.newtype_variant_seed(seed)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let visitor = StrictVisitor {
+            inner: visitor,
+            tracker: self.tracker,
+        };
+        self.inner.tuple_variant(len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let visitor = StrictVisitor {
+            inner: visitor,
+            tracker: self.tracker,
+        };
+        self.inner.struct_variant(fields, visitor)
+    }
+}
@@ -1,8 +1,15 @@
+use crate::test_helpers::COUNT;
 use crate::{Contract, ContractWrapper};
 use cosmwasm_std::{
-    Binary, CosmosMsg, Deps, DepsMut, Empty, Env, IbcMsg, MessageInfo, Response, StdResult,
+    to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, IbcBasicResponse,
+    IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcMsg,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, MessageInfo,
+    Response, StdError, StdResult,
 };
 
+/// Version of the IBC channel accepted by the IBC-enabled contract.
+pub const IBC_VERSION: &str = "counter-1";
+
 fn instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
     Ok(Response::new())
 }
@@ -24,3 +31,65 @@ pub fn contract() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(execute, instantiate, query);
     Box::new(contract)
 }
+
+fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> StdResult<IbcChannelOpenResponse> {
+    if msg.channel().version != IBC_VERSION {
+        return Err(StdError::generic_err("invalid channel version"));
+    }
+    Ok(None)
+}
+
+fn ibc_channel_connect(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> StdResult<IbcBasicResponse> {
+    Ok(IbcBasicResponse::new().add_attribute("channel", &msg.channel().endpoint.channel_id))
+}
+
+fn ibc_channel_close(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcChannelCloseMsg,
+) -> StdResult<IbcBasicResponse> {
+    Ok(IbcBasicResponse::new())
+}
+
+fn ibc_packet_receive(
+    deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketReceiveMsg,
+) -> StdResult<IbcReceiveResponse> {
+    let count = COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    COUNT.save(deps.storage, &count)?;
+    Ok(IbcReceiveResponse::new(to_json_binary(&count)?))
+}
+
+fn ibc_packet_ack(_deps: DepsMut, _env: Env, _msg: IbcPacketAckMsg) -> StdResult<IbcBasicResponse> {
+    Ok(IbcBasicResponse::new())
+}
+
+fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketTimeoutMsg,
+) -> StdResult<IbcBasicResponse> {
+    Err(StdError::generic_err("packet timed out"))
+}
+
+/// Contract exposing IBC entry-points, counts received packets.
+pub fn ibc_enabled_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(execute, instantiate, query).with_ibc(
+        ibc_channel_open,
+        ibc_channel_connect,
+        ibc_channel_close,
+        ibc_packet_receive,
+        ibc_packet_ack,
+        ibc_packet_timeout,
+    );
+    Box::new(contract)
+}
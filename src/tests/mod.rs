@@ -6,3 +6,4 @@ mod test_error;
 mod test_gov;
 mod test_ibc;
 mod test_stargate;
+mod test_trace;
@@ -1,6 +1,10 @@
 use crate::test_helpers::ibc;
 use crate::{no_init, App, AppBuilder, Executor, IbcAcceptingModule};
-use cosmwasm_std::Empty;
+use cosmwasm_std::{
+    to_json_binary, Binary, Empty, IbcAcknowledgement, IbcChannel, IbcChannelCloseMsg,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcEndpoint, IbcOrder, IbcPacket, IbcPacketAckMsg,
+    IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcTimeout,
+};
 
 #[test]
 fn default_ibc() {
@@ -35,3 +39,128 @@ fn accepting_ibc() {
     app.execute_contract(owner_addr, contract, &Empty {}, &[])
         .unwrap();
 }
+
+#[test]
+fn ibc_entry_points() {
+    let mut app = App::default();
+
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(ibc::ibc_enabled_contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr, &Empty {}, &[], "counter", None)
+        .unwrap();
+    let port_id = app.ibc_port_id(&contract_addr).unwrap().unwrap();
+
+    let channel = |version: &str| {
+        IbcChannel::new(
+            IbcEndpoint {
+                port_id: port_id.clone(),
+                channel_id: "channel-0".to_string(),
+            },
+            IbcEndpoint {
+                port_id: "wasm.counterparty".to_string(),
+                channel_id: "channel-7".to_string(),
+            },
+            IbcOrder::Unordered,
+            version,
+            "connection-0",
+        )
+    };
+    let packet = IbcPacket::new(
+        Binary::default(),
+        channel(ibc::IBC_VERSION).counterparty_endpoint,
+        channel(ibc::IBC_VERSION).endpoint,
+        1,
+        IbcTimeout::with_timestamp(app.block_info().time.plus_seconds(60)),
+    );
+    let relayer_addr = app.api().addr_make("relayer");
+    let block = app.block_info();
+
+    app.init_modules(|router, api, storage| {
+        let wasm = &router.wasm;
+
+        // channel with unsupported version is rejected
+        let msg = IbcChannelOpenMsg::new_init(channel("unknown-1"));
+        wasm.call_ibc_channel_open(contract_addr.clone(), api, storage, router, &block, msg)
+            .unwrap_err();
+
+        // channel handshake
+        let msg = IbcChannelOpenMsg::new_init(channel(ibc::IBC_VERSION));
+        let res = wasm
+            .call_ibc_channel_open(contract_addr.clone(), api, storage, router, &block, msg)
+            .unwrap();
+        assert_eq!(None, res);
+        let msg = IbcChannelConnectMsg::new_ack(channel(ibc::IBC_VERSION), ibc::IBC_VERSION);
+        let res = wasm
+            .call_ibc_channel_connect(contract_addr.clone(), api, storage, router, &block, msg)
+            .unwrap();
+        assert_eq!("channel-0", res.attributes[0].value);
+
+        // received packets are counted
+        for expected in 1..=2 {
+            let msg = IbcPacketReceiveMsg::new(packet.clone(), relayer_addr.clone());
+            let res = wasm
+                .call_ibc_packet_receive(contract_addr.clone(), api, storage, router, &block, msg)
+                .unwrap();
+            assert_eq!(
+                Some(to_json_binary(&expected).unwrap()),
+                res.acknowledgement
+            );
+        }
+
+        // acknowledgements and timeouts
+        let msg = IbcPacketAckMsg::new(
+            IbcAcknowledgement::new(Binary::default()),
+            packet.clone(),
+            relayer_addr.clone(),
+        );
+        wasm.call_ibc_packet_ack(contract_addr.clone(), api, storage, router, &block, msg)
+            .unwrap();
+        let msg = IbcPacketTimeoutMsg::new(packet.clone(), relayer_addr.clone());
+        wasm.call_ibc_packet_timeout(contract_addr.clone(), api, storage, router, &block, msg)
+            .unwrap_err();
+
+        // channel closing
+        let msg = IbcChannelCloseMsg::new_init(channel(ibc::IBC_VERSION));
+        wasm.call_ibc_channel_close(contract_addr.clone(), api, storage, router, &block, msg)
+            .unwrap();
+    });
+}
+
+#[test]
+fn ibc_entry_points_not_implemented() {
+    let mut app = App::default();
+
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(ibc::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr, &Empty {}, &[], "ibanera", None)
+        .unwrap();
+    assert_eq!(None, app.ibc_port_id(&contract_addr).unwrap());
+
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        let channel = IbcChannel::new(
+            IbcEndpoint {
+                port_id: "wasm.contract".to_string(),
+                channel_id: "channel-0".to_string(),
+            },
+            IbcEndpoint {
+                port_id: "wasm.counterparty".to_string(),
+                channel_id: "channel-7".to_string(),
+            },
+            IbcOrder::Unordered,
+            ibc::IBC_VERSION,
+            "connection-0",
+        );
+        let msg = IbcChannelOpenMsg::new_init(channel);
+        let err = router
+            .wasm
+            .call_ibc_channel_open(contract_addr, api, storage, router, &block, msg)
+            .unwrap_err();
+        assert_eq!(
+            "ibc_channel_open is not implemented for contract",
+            err.to_string()
+        );
+    });
+}
@@ -0,0 +1,282 @@
+use crate::test_helpers::{caller, error, payout};
+use crate::{App, BankSudo, Executor};
+use cosmwasm_std::{coin, coins, to_json_binary, Addr, Empty, WasmMsg};
+
+/// Prepares the application with `caller` contract calling `payout` contract.
+fn setup() -> (App, Addr, Addr, Addr) {
+    let mut app = App::default();
+
+    let owner_addr = app.api().addr_make("owner");
+    let caller_code_id = app.store_code(caller::contract());
+    let payout_code_id = app.store_code(payout::contract());
+
+    let caller_addr = app
+        .instantiate_contract(
+            caller_code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "caller",
+            None,
+        )
+        .unwrap();
+    let payout_addr = app
+        .instantiate_contract(
+            payout_code_id,
+            owner_addr.clone(),
+            &payout::InstantiateMessage {
+                payout: coin(5, "gold"),
+            },
+            &[],
+            "payout",
+            None,
+        )
+        .unwrap();
+    app.sudo(
+        BankSudo::Mint {
+            to_address: payout_addr.to_string(),
+            amount: coins(100, "gold"),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    (app, owner_addr, caller_addr, payout_addr)
+}
+
+#[test]
+fn tracing_is_disabled_by_default() {
+    let (mut app, owner_addr, caller_addr, payout_addr) = setup();
+
+    let msg = WasmMsg::Execute {
+        contract_addr: payout_addr.to_string(),
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+    };
+    app.execute_contract(owner_addr.clone(), caller_addr.clone(), &msg, &[])
+        .unwrap();
+
+    assert!(app.trace().is_empty());
+}
+
+#[test]
+fn nested_messages_are_traced() {
+    let (mut app, owner_addr, caller_addr, payout_addr) = setup();
+    app.enable_tracing();
+
+    let msg = WasmMsg::Execute {
+        contract_addr: payout_addr.to_string(),
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+    };
+    app.execute_contract(owner_addr.clone(), caller_addr.clone(), &msg, &[])
+        .unwrap();
+
+    let trace = app.take_trace();
+    let entries = trace.entries();
+    assert_eq!(3, entries.len());
+
+    // user calls the caller contract
+    assert_eq!(0, entries[0].depth);
+    assert_eq!(owner_addr, entries[0].sender);
+    assert_eq!(caller_addr.as_str(), entries[0].receiver);
+    assert_eq!("execute", entries[0].action);
+
+    // caller contract calls the payout contract
+    assert_eq!(1, entries[1].depth);
+    assert_eq!(caller_addr, entries[1].sender);
+    assert_eq!(payout_addr.as_str(), entries[1].receiver);
+    assert_eq!("execute {}", entries[1].details);
+
+    // payout contract sends tokens back to the caller
+    assert_eq!(2, entries[2].depth);
+    assert_eq!(payout_addr, entries[2].sender);
+    assert_eq!(caller_addr.as_str(), entries[2].receiver);
+    assert_eq!("send 5gold", entries[2].details);
+    assert!(entries.iter().all(|entry| entry.is_ok()));
+
+    // taking the trace starts a new one
+    assert!(app.trace().is_empty());
+}
+
+#[test]
+fn failed_messages_are_traced() {
+    let (mut app, owner_addr, _, _) = setup();
+    app.enable_tracing();
+
+    let recipient_addr = app.api().addr_make("recipient");
+    app.send_tokens(owner_addr, recipient_addr.clone(), &coins(1, "gold"))
+        .unwrap_err();
+
+    let trace = app.trace();
+    assert_eq!(1, trace.entries().len());
+    assert!(!trace.entries()[0].is_ok());
+    assert!(trace.entries()[0]
+        .error
+        .as_ref()
+        .unwrap()
+        .starts_with("Cannot Sub"));
+    assert_eq!(recipient_addr.as_str(), trace.entries()[0].receiver);
+}
+
+#[test]
+fn instantiated_contract_becomes_receiver() {
+    let mut app = App::default();
+    app.enable_tracing();
+
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(caller::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr, &Empty {}, &[], "caller", None)
+        .unwrap();
+
+    let trace = app.trace();
+    assert_eq!(contract_addr.as_str(), trace.entries()[0].receiver);
+    assert_eq!("instantiate", trace.entries()[0].action);
+    assert_eq!("instantiate code id 1: {}", trace.entries()[0].details);
+}
+
+#[test]
+fn trace_exports_to_sequence_diagrams() {
+    let (mut app, owner_addr, caller_addr, payout_addr) = setup();
+    app.enable_tracing();
+
+    let msg = WasmMsg::Execute {
+        contract_addr: payout_addr.to_string(),
+        msg: to_json_binary(&Empty {}).unwrap(),
+        funds: vec![],
+    };
+    app.execute_contract(owner_addr.clone(), caller_addr.clone(), &msg, &[])
+        .unwrap();
+    let trace = app.trace();
+
+    let mermaid = [
+        "sequenceDiagram".to_string(),
+        format!("    participant P0 as {}", owner_addr),
+        format!("    participant P1 as {}", caller_addr),
+        format!("    participant P2 as {}", payout_addr),
+        format!(
+            "    P0->>P1: execute {{\"execute\":{{\"contract_addr\":\"{}\",\"msg\":\"e30=\",\"funds\":[]}}}}",
+            payout_addr
+        ),
+        "    P1->>P2: execute {}".to_string(),
+        "    P2->>P1: send 5gold".to_string(),
+        "    P1-->>P2: ok".to_string(),
+        "    P2-->>P1: ok".to_string(),
+        "    P1-->>P0: ok".to_string(),
+    ]
+    .join("\n");
+    assert_eq!(mermaid, trace.to_mermaid());
+
+    let plantuml = trace.to_plantuml();
+    assert!(plantuml.starts_with("@startuml\n"));
+    assert!(plantuml.contains(&format!("participant \"{}\" as P2", payout_addr)));
+    assert!(plantuml.contains("P1 -> P2 : execute {}"));
+    assert!(plantuml.contains("P2 -> P1 : send 5gold"));
+    assert!(plantuml.ends_with("P1 --> P0 : ok\n@enduml"));
+}
+
+#[test]
+fn refunds_of_failed_instantiation_are_traced() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    app.sudo(
+        BankSudo::Mint {
+            to_address: owner_addr.to_string(),
+            amount: coins(100, "gold"),
+        }
+        .into(),
+    )
+    .unwrap();
+    let code_id = app.store_code(error::contract(false));
+    app.enable_tracing();
+
+    app.instantiate_contract(
+        code_id,
+        owner_addr.clone(),
+        &Empty {},
+        &coins(30, "gold"),
+        "failing",
+        None,
+    )
+    .unwrap_err();
+
+    // escrowed funds are given back to the sender
+    assert_eq!(
+        coin(100, "gold"),
+        app.wrap().query_balance(&owner_addr, "gold").unwrap()
+    );
+
+    let trace = app.trace();
+    let entries = trace.entries();
+    assert_eq!(3, entries.len());
+    assert_eq!("instantiate", entries[0].action);
+    assert!(!entries[0].is_ok());
+    assert_eq!("send 30gold", entries[1].details);
+    assert_eq!("refund 30gold", entries[2].details);
+    assert_eq!(1, entries[2].depth);
+    assert_eq!(entries[1].receiver, entries[2].sender.as_str());
+    assert_eq!(owner_addr.as_str(), entries[2].receiver);
+    assert_eq!(1, trace.refunds().len());
+    trace.assert_refunded(&owner_addr, &coins(30, "gold"));
+}
+
+#[test]
+fn refunds_of_failed_execution_are_traced() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    app.sudo(
+        BankSudo::Mint {
+            to_address: owner_addr.to_string(),
+            amount: coins(100, "gold"),
+        }
+        .into(),
+    )
+    .unwrap();
+    let code_id = app.store_code(error::contract(true));
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "failing", None)
+        .unwrap();
+    app.enable_tracing();
+
+    app.execute_contract(
+        owner_addr.clone(),
+        contract_addr.clone(),
+        &Empty {},
+        &coins(30, "gold"),
+    )
+    .unwrap_err();
+
+    let trace = app.trace();
+    trace.assert_refunded(&owner_addr, &coins(30, "gold"));
+    assert_eq!(contract_addr, trace.refunds()[0].sender);
+    assert!(trace.to_mermaid().contains("P1->>P0: refund 30gold"));
+}
+
+#[test]
+fn successful_messages_are_not_refunded() {
+    let (mut app, owner_addr, _, payout_addr) = setup();
+    app.sudo(
+        BankSudo::Mint {
+            to_address: owner_addr.to_string(),
+            amount: coins(10, "gold"),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.enable_tracing();
+
+    app.execute_contract(
+        owner_addr.clone(),
+        payout_addr,
+        &Empty {},
+        &coins(10, "gold"),
+    )
+    .unwrap();
+
+    let trace = app.trace();
+    assert!(trace.refunds().is_empty());
+    let result =
+        std::panic::catch_unwind(|| trace.assert_refunded(&owner_addr, &coins(10, "gold")));
+    assert!(result.is_err());
+}
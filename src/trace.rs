@@ -0,0 +1,477 @@
+//! # Execution traces
+//!
+//! When tracing is enabled in [App](crate::App), every message processed by the [Router](crate::Router)
+//! is recorded in an [ExecutionTrace], including messages sent by contracts and modules.
+//! Recorded trace can be exported as a sequence diagram in [Mermaid](https://mermaid.js.org)
+//! or [PlantUML](https://plantuml.com) format, ready to be embedded in design documents.
+//!
+//! Funds attached to instantiate and execute messages are escrowed, i.e. sent to the contract
+//! before it is called. When the call fails, all state changes are reverted, so the escrowed
+//! funds are given back to the sender. This refund is recorded in the trace as an additional
+//! entry with `refund` action, following all entries nested in the failed message.
+
+use crate::error::AnyResult;
+use crate::AppResponse;
+use cosmwasm_std::{
+    to_json_string, Addr, BankMsg, Coin, CosmosMsg, CustomMsg, DistributionMsg, Empty, GovMsg,
+    IbcMsg, StakingMsg, WasmMsg,
+};
+use cw_utils::parse_instantiate_response_data;
+use itertools::Itertools;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+/// Single message recorded in the execution trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// Nesting level of the message, messages executed directly by the [App](crate::App) have depth `0`.
+    pub depth: usize,
+    /// Address of the message sender.
+    pub sender: Addr,
+    /// Receiver of the message, contract or account address, or the name of the module.
+    pub receiver: String,
+    /// Short name of the performed action, like `execute` or `send`.
+    pub action: String,
+    /// Human-readable details of the action, like the message passed to the contract or sent coins.
+    pub details: String,
+    /// Full message serialized to JSON.
+    pub msg: String,
+    /// Error message when processing the message has failed.
+    pub error: Option<String>,
+}
+
+impl TraceEntry {
+    /// Returns `true` when the message was processed successfully.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Recorded sequence of messages processed by the [Router](crate::Router).
+///
+/// Entries are stored in the order the messages were dispatched,
+/// nested messages follow the message that caused them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionTrace {
+    entries: Vec<TraceEntry>,
+}
+
+impl ExecutionTrace {
+    /// Returns all recorded entries.
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.entries
+    }
+
+    /// Returns `true` when no messages were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns all refunds of escrowed funds, recorded for failed messages.
+    pub fn refunds(&self) -> Vec<&TraceEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.action == ACTION_REFUND)
+            .collect()
+    }
+
+    /// Asserts that funds sent by the specified sender were escrowed by the contract
+    /// and refunded back to the sender after the message failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics when no such escrow followed by the refund was recorded.
+    pub fn assert_refunded(&self, sender: &Addr, funds: &[Coin]) {
+        let funds = format_coins(funds);
+        let escrow = self.entries.iter().position(|entry| {
+            entry.action == ACTION_SEND
+                && entry.is_ok()
+                && entry.sender == *sender
+                && entry.details == format!("{} {}", ACTION_SEND, funds)
+        });
+        let refund = escrow.and_then(|escrow| {
+            self.entries[escrow..].iter().find(|entry| {
+                entry.action == ACTION_REFUND
+                    && entry.receiver == sender.as_str()
+                    && entry.sender.as_str() == self.entries[escrow].receiver
+                    && entry.details == format!("{} {}", ACTION_REFUND, funds)
+            })
+        });
+        match (escrow, refund) {
+            (None, _) => panic!("no funds {} escrowed from {}", funds, sender),
+            (Some(_), None) => panic!("escrowed funds {} not refunded to {}", funds, sender),
+            _ => {}
+        }
+    }
+
+    /// Exports the trace as a [Mermaid](https://mermaid.js.org) sequence diagram.
+    pub fn to_mermaid(&self) -> String {
+        let mut diagram = vec!["sequenceDiagram".to_string()];
+        let participants = self.participants();
+        for (alias, name) in participants.values().sorted() {
+            diagram.push(format!("    participant P{} as {}", alias, name));
+        }
+        self.walk(|step| {
+            let (from, to) = (participants[step.from].0, participants[step.to].0);
+            match step.kind {
+                StepKind::Call(label) => diagram.push(format!(
+                    "    P{}->>P{}: {}",
+                    from,
+                    to,
+                    escape_mermaid(label)
+                )),
+                StepKind::Return(None) => diagram.push(format!("    P{}-->>P{}: ok", from, to)),
+                StepKind::Return(Some(error)) => diagram.push(format!(
+                    "    P{}--xP{}: error: {}",
+                    from,
+                    to,
+                    escape_mermaid(error)
+                )),
+            }
+        });
+        diagram.join("\n")
+    }
+
+    /// Exports the trace as a [PlantUML](https://plantuml.com) sequence diagram.
+    pub fn to_plantuml(&self) -> String {
+        let mut diagram = vec!["@startuml".to_string()];
+        let participants = self.participants();
+        for (alias, name) in participants.values().sorted() {
+            diagram.push(format!("participant \"{}\" as P{}", name, alias));
+        }
+        self.walk(|step| {
+            let (from, to) = (participants[step.from].0, participants[step.to].0);
+            match step.kind {
+                StepKind::Call(label) => {
+                    diagram.push(format!("P{} -> P{} : {}", from, to, escape_plantuml(label)))
+                }
+                StepKind::Return(None) => diagram.push(format!("P{} --> P{} : ok", from, to)),
+                StepKind::Return(Some(error)) => diagram.push(format!(
+                    "P{} -->x P{} : error: {}",
+                    from,
+                    to,
+                    escape_plantuml(error)
+                )),
+            }
+        });
+        diagram.push("@enduml".to_string());
+        diagram.join("\n")
+    }
+
+    /// Returns all participants of the trace, numbered in the order of their first appearance.
+    fn participants(&self) -> BTreeMap<&str, (usize, &str)> {
+        let mut participants = BTreeMap::new();
+        for entry in &self.entries {
+            for name in [entry.sender.as_str(), entry.receiver.as_str()] {
+                let next = participants.len();
+                participants.entry(name).or_insert((next, name));
+            }
+        }
+        participants
+    }
+
+    /// Walks through the trace, reporting calls and returns in chronological order.
+    fn walk<'a>(&'a self, mut f: impl FnMut(Step<'a>)) {
+        let mut stack: Vec<&TraceEntry> = vec![];
+        for entry in &self.entries {
+            while stack.last().is_some_and(|top| top.depth >= entry.depth) {
+                f(Step::ret(stack.pop().unwrap()));
+            }
+            f(Step::call(entry));
+            stack.push(entry);
+        }
+        while let Some(top) = stack.pop() {
+            f(Step::ret(top));
+        }
+    }
+}
+
+/// Single step of the sequence diagram.
+struct Step<'a> {
+    from: &'a str,
+    to: &'a str,
+    kind: StepKind<'a>,
+}
+
+enum StepKind<'a> {
+    Call(&'a str),
+    Return(Option<&'a str>),
+}
+
+impl<'a> Step<'a> {
+    fn call(entry: &'a TraceEntry) -> Self {
+        Self {
+            from: entry.sender.as_str(),
+            to: entry.receiver.as_str(),
+            kind: StepKind::Call(&entry.details),
+        }
+    }
+
+    fn ret(entry: &'a TraceEntry) -> Self {
+        Self {
+            from: entry.receiver.as_str(),
+            to: entry.sender.as_str(),
+            kind: StepKind::Return(entry.error.as_deref()),
+        }
+    }
+}
+
+/// Escapes characters having special meaning in Mermaid message labels.
+fn escape_mermaid(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| match c {
+            '#' => "#35;".to_string(),
+            ';' => "#59;".to_string(),
+            '\n' => " ".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Escapes characters having special meaning in PlantUML message labels.
+fn escape_plantuml(label: &str) -> String {
+    label.replace('\n', "\\n")
+}
+
+/// Action name of the escrow of funds.
+const ACTION_SEND: &str = "send";
+
+/// Action name of the refund of escrowed funds.
+const ACTION_REFUND: &str = "refund";
+
+/// State of the trace being recorded.
+#[derive(Clone, Default)]
+struct TraceState {
+    trace: ExecutionTrace,
+    depth: usize,
+    /// Funds attached to messages being processed, indexed by trace entry.
+    escrows: BTreeMap<usize, Vec<Coin>>,
+}
+
+/// Records messages processed by the [Router](crate::Router), when enabled.
+#[derive(Clone, Default)]
+pub(crate) struct Tracer(RefCell<Option<TraceState>>);
+
+impl Tracer {
+    /// Starts recording a new trace, previously recorded trace is discarded.
+    pub fn enable(&self) {
+        *self.0.borrow_mut() = Some(TraceState::default());
+    }
+
+    /// Stops recording the trace, previously recorded trace is discarded.
+    pub fn disable(&self) {
+        *self.0.borrow_mut() = None;
+    }
+
+    /// Returns a copy of the trace recorded so far.
+    pub fn trace(&self) -> ExecutionTrace {
+        self.0
+            .borrow()
+            .as_ref()
+            .map(|state| state.trace.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the trace recorded so far and starts recording a new one.
+    pub fn take(&self) -> ExecutionTrace {
+        self.0
+            .borrow_mut()
+            .as_mut()
+            .map(|state| std::mem::take(&mut state.trace))
+            .unwrap_or_default()
+    }
+
+    /// Records the message before it is processed, returns the index of the new trace entry.
+    pub fn enter<ExecC>(&self, sender: &Addr, msg: &CosmosMsg<ExecC>) -> Option<usize>
+    where
+        ExecC: CustomMsg,
+    {
+        let mut state = self.0.borrow_mut();
+        let state = state.as_mut()?;
+        let (receiver, action, details) = describe(msg);
+        if let Some(funds) = escrowed_funds(msg) {
+            state.escrows.insert(state.trace.entries.len(), funds);
+        }
+        state.trace.entries.push(TraceEntry {
+            depth: state.depth,
+            sender: sender.clone(),
+            receiver,
+            action,
+            details,
+            msg: to_json_string(msg).unwrap_or_else(|_| format!("{:?}", msg)),
+            error: None,
+        });
+        state.depth += 1;
+        Some(state.trace.entries.len() - 1)
+    }
+
+    /// Records the result of processing the message.
+    pub fn exit(&self, index: Option<usize>, result: &AnyResult<AppResponse>) {
+        let mut state = self.0.borrow_mut();
+        let (Some(state), Some(index)) = (state.as_mut(), index) else {
+            return;
+        };
+        state.depth = state.depth.saturating_sub(1);
+        let funds = state.escrows.remove(&index);
+        if let (Err(_), Some(funds)) = (result, funds) {
+            state.record_refund(index, funds);
+        }
+        let Some(entry) = state.trace.entries.get_mut(index) else {
+            return;
+        };
+        match result {
+            Ok(response) => {
+                // newly instantiated contract becomes the receiver of the message
+                if entry.action.starts_with("instantiate") {
+                    if let Some(data) = &response.data {
+                        if let Ok(res) = parse_instantiate_response_data(data.as_slice()) {
+                            entry.receiver = res.contract_address;
+                        }
+                    }
+                }
+            }
+            Err(e) => entry.error = Some(e.root_cause().to_string()),
+        }
+    }
+}
+
+impl TraceState {
+    /// Records the refund of funds escrowed while processing the failed message.
+    fn record_refund(&mut self, index: usize, funds: Vec<Coin>) {
+        let Some(entry) = self.trace.entries.get(index) else {
+            return;
+        };
+        // the escrow is the first message nested in the failed one
+        let Some(escrow) = self.trace.entries.get(index + 1).filter(|escrow| {
+            escrow.depth == entry.depth + 1
+                && escrow.action == ACTION_SEND
+                && escrow.sender == entry.sender
+                && escrow.is_ok()
+        }) else {
+            return;
+        };
+        let msg: CosmosMsg<Empty> = BankMsg::Send {
+            to_address: entry.sender.to_string(),
+            amount: funds.clone(),
+        }
+        .into();
+        let refund = TraceEntry {
+            depth: entry.depth + 1,
+            sender: Addr::unchecked(&escrow.receiver),
+            receiver: entry.sender.to_string(),
+            action: ACTION_REFUND.to_string(),
+            details: format!("{} {}", ACTION_REFUND, format_coins(&funds)),
+            msg: to_json_string(&msg).unwrap_or_else(|_| format!("{:?}", msg)),
+            error: None,
+        };
+        self.trace.entries.push(refund);
+    }
+}
+
+/// Returns non-empty funds attached to instantiate and execute messages.
+fn escrowed_funds<ExecC>(msg: &CosmosMsg<ExecC>) -> Option<Vec<Coin>> {
+    match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { funds, .. })
+        | CosmosMsg::Wasm(WasmMsg::Instantiate { funds, .. })
+        | CosmosMsg::Wasm(WasmMsg::Instantiate2 { funds, .. })
+            if !funds.is_empty() =>
+        {
+            Some(funds.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Formats coins as a comma-separated list.
+fn format_coins(coins: &[Coin]) -> String {
+    coins.iter().map(|c| c.to_string()).join(",")
+}
+
+/// Returns the receiver, the action name and human-readable details of the message.
+fn describe<ExecC>(msg: &CosmosMsg<ExecC>) -> (String, String, String)
+where
+    ExecC: CustomMsg,
+{
+    let text = |msg: &[u8]| String::from_utf8_lossy(msg).to_string();
+    let (receiver, action, details) = match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) => (contract_addr.clone(), "execute", text(msg)),
+        CosmosMsg::Wasm(WasmMsg::Instantiate { code_id, msg, .. })
+        | CosmosMsg::Wasm(WasmMsg::Instantiate2 { code_id, msg, .. }) => (
+            "wasm".to_string(),
+            "instantiate",
+            format!("code id {}: {}", code_id, text(msg)),
+        ),
+        CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr,
+            new_code_id,
+            msg,
+        }) => (
+            contract_addr.clone(),
+            "migrate",
+            format!("code id {}: {}", new_code_id, text(msg)),
+        ),
+        CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+            contract_addr,
+            admin,
+        }) => (contract_addr.clone(), "update_admin", admin.clone()),
+        CosmosMsg::Wasm(WasmMsg::ClearAdmin { contract_addr }) => {
+            (contract_addr.clone(), "clear_admin", String::new())
+        }
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+            (to_address.clone(), ACTION_SEND, format_coins(amount))
+        }
+        CosmosMsg::Bank(BankMsg::Burn { amount }) => {
+            ("bank".to_string(), "burn", format_coins(amount))
+        }
+        CosmosMsg::Custom(_) => ("custom".to_string(), "custom", String::new()),
+        CosmosMsg::Staking(msg) => {
+            let action = match msg {
+                StakingMsg::Delegate { .. } => "delegate",
+                StakingMsg::Undelegate { .. } => "undelegate",
+                StakingMsg::Redelegate { .. } => "redelegate",
+                _ => "staking",
+            };
+            ("staking".to_string(), action, String::new())
+        }
+        CosmosMsg::Distribution(msg) => {
+            let action = match msg {
+                DistributionMsg::SetWithdrawAddress { .. } => "set_withdraw_address",
+                DistributionMsg::WithdrawDelegatorReward { .. } => "withdraw_delegator_reward",
+                DistributionMsg::FundCommunityPool { .. } => "fund_community_pool",
+                _ => "distribution",
+            };
+            ("distribution".to_string(), action, String::new())
+        }
+        CosmosMsg::Ibc(msg) => {
+            let action = match msg {
+                IbcMsg::Transfer { .. } => "transfer",
+                IbcMsg::SendPacket { .. } => "send_packet",
+                IbcMsg::CloseChannel { .. } => "close_channel",
+                _ => "ibc",
+            };
+            ("ibc".to_string(), action, String::new())
+        }
+        CosmosMsg::Gov(msg) => {
+            let action = match msg {
+                GovMsg::Vote { .. } => "vote",
+                GovMsg::VoteWeighted { .. } => "vote_weighted",
+            };
+            ("gov".to_string(), action, String::new())
+        }
+        #[allow(deprecated)]
+        CosmosMsg::Stargate { type_url, .. } => {
+            ("stargate".to_string(), "stargate", type_url.clone())
+        }
+        CosmosMsg::Any(msg) => ("stargate".to_string(), "any", msg.type_url.clone()),
+        _ => ("unknown".to_string(), "unknown", String::new()),
+    };
+    let details = if details.is_empty() {
+        action.to_string()
+    } else {
+        format!("{} {}", action, details)
+    };
+    (receiver, action.to_string(), details)
+}
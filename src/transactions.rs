@@ -1,6 +1,7 @@
 use crate::error::AnyResult;
 use cosmwasm_std::Storage;
 use cosmwasm_std::{Order, Record};
+use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::iter;
@@ -104,6 +105,41 @@ impl<'a> Storage for StorageTransaction<'a> {
     }
 }
 
+/// Storage shared by multiple readers and writers, used when queries sent by the contract
+/// should observe the state written so far by the same contract.
+/// Ranges are collected eagerly, so no borrow outlives a single storage operation.
+pub struct SharedStorage<'a>(RefCell<&'a mut dyn Storage>);
+
+impl<'a> SharedStorage<'a> {
+    pub fn new(storage: &'a mut dyn Storage) -> Self {
+        Self(RefCell::new(storage))
+    }
+}
+
+impl<'a, 'b> Storage for &'b SharedStorage<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.0.borrow().get(key)
+    }
+
+    fn range<'c>(
+        &'c self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'c> {
+        let records: Vec<Record> = self.0.borrow().range(start, end, order).collect();
+        Box::new(records.into_iter())
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.0.borrow_mut().set(key, value)
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.0.borrow_mut().remove(key)
+    }
+}
+
 pub struct RepLog {
     /// this is a list of changes to be written to backing storage upon commit
     ops_log: Vec<Op>,
@@ -0,0 +1,104 @@
+//! # Registry of contract versions
+//!
+//! Several code versions of the same contract can be registered under one logical name
+//! using [store_code_version](crate::App::store_code_version). Registered versions are used
+//! by [run_migration_matrix](crate::App::run_migration_matrix) to test upgrade compatibility:
+//! for every pair of versions, the older version is instantiated, the scenario is run,
+//! the contract is migrated to the newer version and the invariants are checked again.
+
+use crate::error::AnyResult;
+use crate::prefixed_storage::{prefixed, prefixed_read};
+use cosmwasm_std::{to_json_binary, Addr, Binary, StdResult, Storage};
+use cw_storage_plus::Map;
+
+/// Registered code versions in the order of registration, indexed by contract name.
+const CODE_VERSIONS: Map<&str, Vec<(String, u64)>> = Map::new("code_versions");
+
+/// Default storage namespace for contract versions.
+const NAMESPACE_CODE_VERSIONS: &[u8] = b"code-versions";
+
+/// Registers the code identifier of the specified version of the contract.
+pub(crate) fn register_code_version(
+    storage: &mut dyn Storage,
+    name: &str,
+    version: &str,
+    code_id: u64,
+) -> AnyResult<()> {
+    let mut versions_storage = prefixed(storage, NAMESPACE_CODE_VERSIONS);
+    let mut versions = CODE_VERSIONS
+        .may_load(&versions_storage, name)?
+        .unwrap_or_default();
+    versions.push((version.to_string(), code_id));
+    CODE_VERSIONS.save(&mut versions_storage, name, &versions)?;
+    Ok(())
+}
+
+/// Returns registered versions of the contract with their code identifiers,
+/// in the order of registration.
+pub(crate) fn code_versions(storage: &dyn Storage, name: &str) -> AnyResult<Vec<(String, u64)>> {
+    let versions_storage = prefixed_read(storage, NAMESPACE_CODE_VERSIONS);
+    Ok(CODE_VERSIONS
+        .may_load(&versions_storage, name)?
+        .unwrap_or_default())
+}
+
+/// Scenario run by the [MigrationMatrix] on the instantiated contract.
+type Scenario<A> = Box<dyn Fn(&mut A, &Addr) -> AnyResult<()>>;
+
+/// Invariant checked by the [MigrationMatrix] before and after the migration.
+type Invariant<A> = Box<dyn Fn(&A, &Addr) -> AnyResult<()>>;
+
+/// Upgrade-compatibility test of all registered versions of the contract,
+/// run with [run_migration_matrix](crate::App::run_migration_matrix).
+///
+/// For every pair of registered versions (the older one registered first), on a copy
+/// of the application:
+/// - the older version is instantiated with the admin set to the matrix admin,
+/// - the scenario is run and the invariants are checked,
+/// - the contract is migrated to the newer version and the invariants are checked again.
+pub struct MigrationMatrix<A> {
+    pub(crate) name: String,
+    pub(crate) admin: Addr,
+    pub(crate) instantiate_msg: Binary,
+    pub(crate) migrate_msg: Binary,
+    pub(crate) scenario: Option<Scenario<A>>,
+    pub(crate) invariants: Vec<Invariant<A>>,
+}
+
+impl<A> MigrationMatrix<A> {
+    /// Creates a migration matrix of the contract with the specified name,
+    /// instantiated and migrated by the admin with specified messages.
+    pub fn new(
+        name: impl Into<String>,
+        admin: Addr,
+        instantiate_msg: &impl serde::Serialize,
+        migrate_msg: &impl serde::Serialize,
+    ) -> StdResult<Self> {
+        Ok(Self {
+            name: name.into(),
+            admin,
+            instantiate_msg: to_json_binary(instantiate_msg)?,
+            migrate_msg: to_json_binary(migrate_msg)?,
+            scenario: None,
+            invariants: vec![],
+        })
+    }
+
+    /// Sets the scenario run on the contract instance before the migration.
+    pub fn with_scenario(
+        mut self,
+        scenario: impl Fn(&mut A, &Addr) -> AnyResult<()> + 'static,
+    ) -> Self {
+        self.scenario = Some(Box::new(scenario));
+        self
+    }
+
+    /// Adds the invariant checked after the scenario and after the migration.
+    pub fn with_invariant(
+        mut self,
+        invariant: impl Fn(&A, &Addr) -> AnyResult<()> + 'static,
+    ) -> Self {
+        self.invariants.push(Box::new(invariant));
+        self
+    }
+}
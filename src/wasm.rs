@@ -1,26 +1,38 @@
 use crate::addresses::{AddressGenerator, SimpleAddressGenerator};
-use crate::app::{CosmosRouter, RouterQuerier};
+use crate::app::{ensure_user_msg_allowed, CosmosRouter, RouterQuerier};
 use crate::checksums::{ChecksumGenerator, SimpleChecksumGenerator};
 use crate::contracts::Contract;
 use crate::error::{bail, AnyContext, AnyError, AnyResult, Error};
 use crate::executor::AppResponse;
+use crate::features::{is_feature_enabled, ExperimentalFeature};
+use crate::ibc_callbacks::{IbcDestinationCallbackMsg, IbcSourceCallbackMsg};
+use crate::multisig::ensure_admin_msg_approved;
 use crate::prefixed_storage::{prefixed, prefixed_read, PrefixedStorage, ReadonlyPrefixedStorage};
-use crate::transactions::transactional;
+use crate::spans::EntryPointSpan;
+use crate::transactions::{transactional, SharedStorage};
 use cosmwasm_std::testing::mock_wasmd_attr;
 use cosmwasm_std::{
-    to_json_binary, Addr, Api, Attribute, BankMsg, Binary, BlockInfo, Checksum, Coin, ContractInfo,
-    ContractInfoResponse, CustomMsg, CustomQuery, Deps, DepsMut, Env, Event, MessageInfo, Order,
-    Querier, QuerierWrapper, Record, Reply, ReplyOn, Response, StdResult, Storage, SubMsg,
-    SubMsgResponse, SubMsgResult, TransactionInfo, WasmMsg, WasmQuery,
+    from_json, to_json_binary, to_json_vec, Addr, Api, Attribute, BankMsg, Binary, BlockInfo,
+    Checksum, Coin, ContractInfo, ContractInfoResponse, CustomMsg, CustomQuery, Deps, DepsMut,
+    Empty, Env, Event, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
+    IbcChannelOpenMsg, IbcChannelOpenResponse, IbcPacketAckMsg, IbcPacketReceiveMsg,
+    IbcPacketTimeoutMsg, IbcQuery, IbcReceiveResponse, MessageInfo, Order, PortIdResponse, Querier,
+    QuerierResult, QuerierWrapper, QueryRequest, Record, Reply, ReplyOn, Response, StdResult,
+    Storage, SubMsg, SubMsgResponse, SubMsgResult, SystemError, SystemResult, TransactionInfo,
+    WasmMsg, WasmQuery,
 };
 use cw_storage_plus::Map;
+use derivative::Derivative;
 use prost::Message;
 use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::borrow::Borrow;
+use std::backtrace::Backtrace;
+use std::cell::{Cell, RefCell};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Once};
 
 /// Contract state kept in storage, separate from the contracts themselves (contract code).
 const CONTRACTS: Map<&Addr, ContractData> = Map::new("contracts");
@@ -33,6 +45,19 @@ const NAMESPACE_WASM: &[u8] = b"wasm";
 /// [address namespace]: https://github.com/CosmWasm/wasmd/blob/96e2b91144c9a371683555f3c696f882583cc6a2/x/wasm/types/events.go#L59
 const CONTRACT_ATTR: &str = "_contract_address";
 
+/// Prefix of IBC port identifiers bound to contracts.
+pub(crate) const IBC_PORT_PREFIX: &str = "wasm.";
+
+/// Installs the panic hook capturing backtraces of panics caught in contract's entry-points.
+static PANIC_HOOK: Once = Once::new();
+
+thread_local! {
+    /// Whether panics on this thread are caught in contract's entry-points.
+    static CATCHING_PANICS: Cell<bool> = const { Cell::new(false) };
+    /// Backtrace of the last panic caught on this thread.
+    static PANIC_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
 /// A structure representing a privileged message.
 #[derive(Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct WasmSudo {
@@ -52,6 +77,27 @@ impl WasmSudo {
     }
 }
 
+/// IBC callback delivered to the contract bound to the IBC port.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IbcCallback {
+    /// Calls contract's `ibc_channel_open` entry-point.
+    ChannelOpen(IbcChannelOpenMsg),
+    /// Calls contract's `ibc_channel_connect` entry-point.
+    ChannelConnect(IbcChannelConnectMsg),
+    /// Calls contract's `ibc_channel_close` entry-point.
+    ChannelClose(IbcChannelCloseMsg),
+    /// Calls contract's `ibc_packet_receive` entry-point.
+    PacketReceive(IbcPacketReceiveMsg),
+    /// Calls contract's `ibc_packet_ack` entry-point.
+    PacketAck(IbcPacketAckMsg),
+    /// Calls contract's `ibc_packet_timeout` entry-point.
+    PacketTimeout(IbcPacketTimeoutMsg),
+    /// Calls contract's `ibc_source_callback` entry-point (ADR-8).
+    SourceCallback(IbcSourceCallbackMsg),
+    /// Calls contract's `ibc_destination_callback` entry-point (ADR-8).
+    DestinationCallback(IbcDestinationCallbackMsg),
+}
+
 /// Contract data includes information about contract,
 /// equivalent of `ContractInfo` in `wasmd` interface.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -69,6 +115,7 @@ pub struct ContractData {
 }
 
 /// Contract code base data.
+#[derive(Clone)]
 struct CodeData {
     /// Address of an account that initially stored the contract code.
     creator: Addr,
@@ -135,6 +182,52 @@ pub trait Wasm<ExecC, QueryC> {
     /// Returns a raw state dump of all key-values held by a contract with specified address.
     fn dump_wasm_raw(&self, storage: &dyn Storage, address: &Addr) -> Vec<Record>;
 
+    /// Delivers the IBC callback to the contract with specified address.
+    /// Response data holds the channel version proposed by the contract for `ChannelOpen`
+    /// callbacks and the acknowledgement written by the contract for `PacketReceive` callbacks.
+    /// Default implementation rejects all callbacks.
+    fn ibc_callback(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        contract_addr: Addr,
+        callback: IbcCallback,
+    ) -> AnyResult<AppResponse> {
+        bail!(
+            "Unexpected IBC callback {:?} for contract {}",
+            callback,
+            contract_addr
+        )
+    }
+
+    /// Returns addresses and `ContractData` of all contract instances, ordered by address.
+    /// Default implementation returns no contracts.
+    fn all_contracts(&self, _storage: &dyn Storage) -> AnyResult<Vec<(Addr, ContractData)>> {
+        Ok(vec![])
+    }
+
+    /// Returns the identifier of the IBC port bound to the contract with specified address,
+    /// or `None` when the contract does not expose IBC entry-points.
+    fn ibc_port_id(&self, _storage: &dyn Storage, _address: &Addr) -> AnyResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Returns the address of the contract bound to the IBC port with specified identifier.
+    /// This is the way channel handshakes and packets are routed to contracts.
+    fn contract_by_ibc_port_id(&self, storage: &dyn Storage, port_id: &str) -> AnyResult<Addr> {
+        if let Some(address) = port_id.strip_prefix(IBC_PORT_PREFIX) {
+            let address = Addr::unchecked(address);
+            if let Ok(Some(bound_port_id)) = self.ibc_port_id(storage, &address) {
+                if bound_port_id == port_id {
+                    return Ok(address);
+                }
+            }
+        }
+        bail!(Error::unknown_ibc_port(port_id))
+    }
+
     /// Returns the namespace of the contract storage.
     fn contract_namespace(&self, contract: &Addr) -> Vec<u8> {
         let mut name = b"contract_data/".to_vec();
@@ -170,15 +263,22 @@ pub trait Wasm<ExecC, QueryC> {
 }
 
 /// A structure representing a default wasm keeper.
+///
+/// Contract codes and generators are shared between clones of the keeper,
+/// so cloning the whole [App](crate::App) is cheap.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
 pub struct WasmKeeper<ExecC, QueryC> {
     /// Contract codes that stand for wasm code in real-life blockchain.
-    code_base: Vec<Box<dyn Contract<ExecC, QueryC>>>,
+    code_base: Vec<Arc<dyn Contract<ExecC, QueryC>>>,
     /// Code data with code base identifier and additional attributes.
     code_data: BTreeMap<u64, CodeData>,
     /// Contract's address generator.
-    address_generator: Box<dyn AddressGenerator>,
+    address_generator: Arc<dyn AddressGenerator>,
     /// Contract's code checksum generator.
-    checksum_generator: Box<dyn ChecksumGenerator>,
+    checksum_generator: Arc<dyn ChecksumGenerator>,
+    /// Flag indicating if panics in contract's entry-points are converted into errors.
+    catch_panics: bool,
     /// Just markers to make type elision fork when using it as `Wasm` trait
     _p: std::marker::PhantomData<QueryC>,
 }
@@ -189,8 +289,9 @@ impl<ExecC, QueryC> Default for WasmKeeper<ExecC, QueryC> {
         Self {
             code_base: Vec::default(),
             code_data: BTreeMap::default(),
-            address_generator: Box::new(SimpleAddressGenerator),
-            checksum_generator: Box::new(SimpleChecksumGenerator),
+            address_generator: Arc::new(SimpleAddressGenerator),
+            checksum_generator: Arc::new(SimpleChecksumGenerator),
+            catch_panics: false,
             _p: std::marker::PhantomData,
         }
     }
@@ -237,12 +338,13 @@ where
             WasmQuery::ContractInfo { contract_addr } => {
                 let addr = api.addr_validate(&contract_addr)?;
                 let contract = self.contract_data(storage, &addr)?;
+                let ibc_port = self.ibc_port_id(storage, &addr)?;
                 let res = ContractInfoResponse::new(
                     contract.code_id,
                     contract.creator,
                     contract.admin,
                     false,
-                    None,
+                    ibc_port,
                 );
                 to_json_binary(&res).map_err(Into::into)
             }
@@ -280,6 +382,82 @@ where
         self.process_response(api, router, storage, block, msg.contract_addr, res, msgs)
     }
 
+    fn ibc_callback(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        contract_addr: Addr,
+        callback: IbcCallback,
+    ) -> AnyResult<AppResponse> {
+        let addr = contract_addr.clone();
+        let (event_type, response, data) = match callback {
+            IbcCallback::ChannelOpen(msg) => {
+                let response =
+                    self.call_ibc_channel_open(addr, api, storage, router, block, msg)?;
+                let custom_event =
+                    Event::new("ibc_channel_open").add_attribute(CONTRACT_ATTR, &contract_addr);
+                return Ok(AppResponse {
+                    events: vec![custom_event],
+                    data: response.map(|response| Binary::from(response.version.into_bytes())),
+                });
+            }
+            IbcCallback::ChannelConnect(msg) => (
+                "ibc_channel_connect",
+                self.call_ibc_channel_connect(addr, api, storage, router, block, msg)?,
+                None,
+            ),
+            IbcCallback::ChannelClose(msg) => (
+                "ibc_channel_close",
+                self.call_ibc_channel_close(addr, api, storage, router, block, msg)?,
+                None,
+            ),
+            IbcCallback::PacketReceive(msg) => {
+                let response =
+                    self.call_ibc_packet_receive(addr, api, storage, router, block, msg)?;
+                let acknowledgement = response.acknowledgement.clone();
+                let response = IbcBasicResponse::new()
+                    .add_submessages(response.messages)
+                    .add_attributes(response.attributes)
+                    .add_events(response.events);
+                ("ibc_packet_receive", response, acknowledgement)
+            }
+            IbcCallback::PacketAck(msg) => (
+                "ibc_packet_ack",
+                self.call_ibc_packet_ack(addr, api, storage, router, block, msg)?,
+                None,
+            ),
+            IbcCallback::PacketTimeout(msg) => (
+                "ibc_packet_timeout",
+                self.call_ibc_packet_timeout(addr, api, storage, router, block, msg)?,
+                None,
+            ),
+            IbcCallback::SourceCallback(msg) => (
+                "ibc_source_callback",
+                self.call_ibc_source_callback(addr, api, storage, router, block, msg)?,
+                None,
+            ),
+            IbcCallback::DestinationCallback(msg) => (
+                "ibc_destination_callback",
+                self.call_ibc_destination_callback(addr, api, storage, router, block, msg)?,
+                None,
+            ),
+        };
+        let response = Self::verify_response(
+            Response::new()
+                .add_submessages(response.messages)
+                .add_attributes(response.attributes)
+                .add_events(response.events),
+        )?;
+        let custom_event = Event::new(event_type).add_attribute(CONTRACT_ATTR, &contract_addr);
+        let (res, msgs) = self.build_app_response(&contract_addr, custom_event, response);
+        let mut res =
+            self.process_response(api, router, storage, block, contract_addr, res, msgs)?;
+        res.data = data;
+        Ok(res)
+    }
+
     /// Stores the contract's code in the in-memory lookup table.
     /// Returns an identifier of the stored contract code.
     fn store_code(&mut self, creator: Addr, code: Box<dyn Contract<ExecC, QueryC>>) -> u64 {
@@ -336,13 +514,36 @@ where
         let storage = self.contract_storage(storage, address);
         storage.range(None, None, Order::Ascending).collect()
     }
+
+    /// Returns addresses and `ContractData` of all contract instances, ordered by address.
+    fn all_contracts(&self, storage: &dyn Storage) -> AnyResult<Vec<(Addr, ContractData)>> {
+        CONTRACTS
+            .range(
+                &prefixed_read(storage, NAMESPACE_WASM),
+                None,
+                None,
+                Order::Ascending,
+            )
+            .collect::<StdResult<Vec<_>>>()
+            .map_err(Into::into)
+    }
+
+    /// Returns the identifier of the IBC port bound to the contract with specified address.
+    /// Like in `wasmd`, the port identifier is the contract address prefixed with `wasm.`.
+    fn ibc_port_id(&self, storage: &dyn Storage, address: &Addr) -> AnyResult<Option<String>> {
+        let contract = self.contract_data(storage, address)?;
+        Ok(self
+            .contract_code(contract.code_id)?
+            .ibc_enabled()
+            .then(|| format!("{}{}", IBC_PORT_PREFIX, address)))
+    }
 }
 
 impl<ExecC, QueryC> WasmKeeper<ExecC, QueryC> {
     /// Returns a handler to code of the contract with specified code id.
     pub fn contract_code(&self, code_id: u64) -> AnyResult<&dyn Contract<ExecC, QueryC>> {
         let code_data = self.code_data(code_id)?;
-        Ok(self.code_base[code_data.source_id].borrow())
+        Ok(self.code_base[code_data.source_id].as_ref())
     }
 
     /// Returns code data of the contract with specified code id.
@@ -405,7 +606,7 @@ impl<ExecC, QueryC> WasmKeeper<ExecC, QueryC> {
         // calculate the checksum of the contract 'source' code based on code_id
         let checksum = self.checksum_generator.checksum(&creator, code_id);
         // store the 'source' code of the contract
-        self.code_base.push(code);
+        self.code_base.push(Arc::from(code));
         // store the additional code attributes like creator address and checksum
         self.code_data.insert(
             code_id,
@@ -481,7 +682,7 @@ where
         mut self,
         address_generator: impl AddressGenerator + 'static,
     ) -> Self {
-        self.address_generator = Box::new(address_generator);
+        self.address_generator = Arc::new(address_generator);
         self
     }
 
@@ -512,10 +713,58 @@ where
         mut self,
         checksum_generator: impl ChecksumGenerator + 'static,
     ) -> Self {
-        self.checksum_generator = Box::new(checksum_generator);
+        self.checksum_generator = Arc::new(checksum_generator);
         self
     }
 
+    /// Enables or disables converting panics in contract's entry-points into errors.
+    ///
+    /// By default, a panicking contract aborts the whole test. When enabled, the panic
+    /// is caught and reported as an error containing the panic message, the address
+    /// of the contract and the backtrace of the panic, like a real-life blockchain reports
+    /// a VM error for panicking contracts. Caught panics are not printed by the panic hook.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cw_multi_test::{AppBuilder, no_init, WasmKeeper};
+    ///
+    /// let wasm_keeper = WasmKeeper::new().with_catch_panics(true);
+    ///
+    /// let app = AppBuilder::default().with_wasm(wasm_keeper).build(no_init);
+    /// ```
+    pub fn with_catch_panics(mut self, catch_panics: bool) -> Self {
+        self.catch_panics = catch_panics;
+        self
+    }
+
+    /// Calls contract's entry-point, converting panics into errors when enabled.
+    fn call_catching_panics<T>(
+        &self,
+        address: &Addr,
+        call: impl FnOnce() -> AnyResult<T>,
+    ) -> AnyResult<T> {
+        if !self.catch_panics {
+            return call();
+        }
+        install_panic_hook();
+        let catching = CATCHING_PANICS.with(|catching| catching.replace(true));
+        let result = panic::catch_unwind(AssertUnwindSafe(call));
+        CATCHING_PANICS.with(|cell| cell.set(catching));
+        result.unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic payload".to_string());
+            let backtrace = PANIC_BACKTRACE
+                .with(|backtrace| backtrace.take())
+                .map(|backtrace| backtrace.to_string())
+                .unwrap_or_default();
+            bail!(Error::contract_panicked(address, message, backtrace))
+        })
+    }
+
     /// Executes contract's `query` entry-point.
     pub fn query_smart(
         &self,
@@ -526,14 +775,15 @@ where
         block: &BlockInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Binary> {
-        self.with_storage_readonly(
+        let span = EntryPointSpan::enter("query", &address, Some(msg.len()));
+        span.exit(self.with_storage_readonly(
             api,
             storage,
             querier,
             block,
             address,
             |handler, deps, env| handler.query(deps, env, msg),
-        )
+        ))
     }
 
     /// Returns the value stored under specified key in contracts storage.
@@ -607,6 +857,12 @@ where
         sender: Addr,
         msg: WasmMsg,
     ) -> AnyResult<AppResponse> {
+        if matches!(
+            msg,
+            WasmMsg::Migrate { .. } | WasmMsg::UpdateAdmin { .. } | WasmMsg::ClearAdmin { .. }
+        ) {
+            ensure_admin_msg_approved(storage, &sender, &msg)?;
+        }
         match msg {
             WasmMsg::Execute {
                 contract_addr,
@@ -818,6 +1074,7 @@ where
 
         // execute in cache
         let res = transactional(storage, |write_cache, _| {
+            ensure_user_msg_allowed(write_cache, &msg)?;
             router.execute(api, write_cache, block, contract.clone(), msg)
         });
 
@@ -1027,14 +1284,15 @@ where
         info: MessageInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        let span = EntryPointSpan::enter("execute", &address, Some(msg.len()));
+        Self::verify_response(span.exit(self.with_storage(
             api,
             storage,
             router,
             block,
             address,
             |contract, deps, env| contract.execute(deps, env, info, msg),
-        )?)
+        ))?)
     }
 
     /// Executes contract's `instantiate` entry-point.
@@ -1048,14 +1306,15 @@ where
         info: MessageInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        let span = EntryPointSpan::enter("instantiate", &address, Some(msg.len()));
+        Self::verify_response(span.exit(self.with_storage(
             api,
             storage,
             router,
             block,
             address,
             |contract, deps, env| contract.instantiate(deps, env, info, msg),
-        )?)
+        ))?)
     }
 
     /// Executes contract's `reply` entry-point.
@@ -1068,14 +1327,15 @@ where
         block: &BlockInfo,
         reply: Reply,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        let span = EntryPointSpan::enter("reply", &address, None);
+        Self::verify_response(span.exit(self.with_storage(
             api,
             storage,
             router,
             block,
             address,
             |contract, deps, env| contract.reply(deps, env, reply),
-        )?)
+        ))?)
     }
 
     /// Executes contract's `sudo` entry-point.
@@ -1088,14 +1348,15 @@ where
         block: &BlockInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        let span = EntryPointSpan::enter("sudo", &address, Some(msg.len()));
+        Self::verify_response(span.exit(self.with_storage(
             api,
             storage,
             router,
             block,
             address,
             |contract, deps, env| contract.sudo(deps, env, msg),
-        )?)
+        ))?)
     }
 
     /// Executes contract's `migrate` entry-point.
@@ -1108,14 +1369,175 @@ where
         block: &BlockInfo,
         msg: Vec<u8>,
     ) -> AnyResult<Response<ExecC>> {
-        Self::verify_response(self.with_storage(
+        let span = EntryPointSpan::enter("migrate", &address, Some(msg.len()));
+        Self::verify_response(span.exit(self.with_storage(
             api,
             storage,
             router,
             block,
             address,
             |contract, deps, env| contract.migrate(deps, env, msg),
-        )?)
+        ))?)
+    }
+
+    /// Executes contract's `ibc_channel_open` entry-point.
+    pub fn call_ibc_channel_open(
+        &self,
+        address: Addr,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: IbcChannelOpenMsg,
+    ) -> AnyResult<IbcChannelOpenResponse> {
+        self.with_storage(
+            api,
+            storage,
+            router,
+            block,
+            address,
+            |contract, deps, env| contract.ibc_channel_open(deps, env, msg),
+        )
+    }
+
+    /// Executes contract's `ibc_channel_connect` entry-point.
+    pub fn call_ibc_channel_connect(
+        &self,
+        address: Addr,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: IbcChannelConnectMsg,
+    ) -> AnyResult<IbcBasicResponse<ExecC>> {
+        self.with_storage(
+            api,
+            storage,
+            router,
+            block,
+            address,
+            |contract, deps, env| contract.ibc_channel_connect(deps, env, msg),
+        )
+    }
+
+    /// Executes contract's `ibc_channel_close` entry-point.
+    pub fn call_ibc_channel_close(
+        &self,
+        address: Addr,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: IbcChannelCloseMsg,
+    ) -> AnyResult<IbcBasicResponse<ExecC>> {
+        self.with_storage(
+            api,
+            storage,
+            router,
+            block,
+            address,
+            |contract, deps, env| contract.ibc_channel_close(deps, env, msg),
+        )
+    }
+
+    /// Executes contract's `ibc_packet_receive` entry-point.
+    pub fn call_ibc_packet_receive(
+        &self,
+        address: Addr,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: IbcPacketReceiveMsg,
+    ) -> AnyResult<IbcReceiveResponse<ExecC>> {
+        self.with_storage(
+            api,
+            storage,
+            router,
+            block,
+            address,
+            |contract, deps, env| contract.ibc_packet_receive(deps, env, msg),
+        )
+    }
+
+    /// Executes contract's `ibc_packet_ack` entry-point.
+    pub fn call_ibc_packet_ack(
+        &self,
+        address: Addr,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: IbcPacketAckMsg,
+    ) -> AnyResult<IbcBasicResponse<ExecC>> {
+        self.with_storage(
+            api,
+            storage,
+            router,
+            block,
+            address,
+            |contract, deps, env| contract.ibc_packet_ack(deps, env, msg),
+        )
+    }
+
+    /// Executes contract's `ibc_packet_timeout` entry-point.
+    pub fn call_ibc_packet_timeout(
+        &self,
+        address: Addr,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: IbcPacketTimeoutMsg,
+    ) -> AnyResult<IbcBasicResponse<ExecC>> {
+        self.with_storage(
+            api,
+            storage,
+            router,
+            block,
+            address,
+            |contract, deps, env| contract.ibc_packet_timeout(deps, env, msg),
+        )
+    }
+
+    /// Executes contract's `ibc_source_callback` entry-point.
+    pub fn call_ibc_source_callback(
+        &self,
+        address: Addr,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: IbcSourceCallbackMsg,
+    ) -> AnyResult<IbcBasicResponse<ExecC>> {
+        self.with_storage(
+            api,
+            storage,
+            router,
+            block,
+            address,
+            |contract, deps, env| contract.ibc_source_callback(deps, env, msg),
+        )
+    }
+
+    /// Executes contract's `ibc_destination_callback` entry-point.
+    pub fn call_ibc_destination_callback(
+        &self,
+        address: Addr,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        msg: IbcDestinationCallbackMsg,
+    ) -> AnyResult<IbcBasicResponse<ExecC>> {
+        self.with_storage(
+            api,
+            storage,
+            router,
+            block,
+            address,
+            |contract, deps, env| contract.ibc_destination_callback(deps, env, msg),
+        )
     }
 
     fn get_env<T: Into<Addr>>(&self, address: T, block: &BlockInfo) -> Env {
@@ -1142,15 +1564,16 @@ where
     {
         let contract = self.contract_data(storage, &address)?;
         let handler = self.contract_code(contract.code_id)?;
+        let querier = ContractQuerier::new(querier, self.ibc_port_id(storage, &address)?);
         let storage = self.contract_storage(storage, &address);
-        let env = self.get_env(address, block);
+        let env = self.get_env(address.clone(), block);
 
         let deps = Deps {
             storage: storage.as_ref(),
             api,
-            querier: QuerierWrapper::new(querier),
+            querier: QuerierWrapper::new(&querier),
         };
-        action(handler, deps, env)
+        self.call_catching_panics(&address, || action(handler, deps, env))
     }
 
     fn with_storage<F, T>(
@@ -1168,22 +1591,40 @@ where
     {
         let contract = self.contract_data(storage, &address)?;
         let handler = self.contract_code(contract.code_id)?;
+        let port_id = self.ibc_port_id(storage, &address)?;
 
         // We don't actually need a transaction here, as it is already embedded in a transactional.
         // execute_submsg or App.execute_multi.
         // However, we need to get write and read access to the same storage in two different objects,
         // and this is the only way I know how to do so.
         transactional(storage, |write_cache, read_store| {
+            if is_feature_enabled(read_store, ExperimentalFeature::QueryUncommittedState) {
+                // queries read the same storage the contract writes to
+                let shared_storage = SharedStorage::new(write_cache);
+                let (mut writer, reader) = (&shared_storage, &shared_storage);
+                let mut contract_storage = self.contract_storage_mut(&mut writer, &address);
+                let querier = RouterQuerier::new(router, api, &reader, block);
+                let querier = ContractQuerier::new(&querier, port_id);
+                let env = self.get_env(address.clone(), block);
+
+                let deps = DepsMut {
+                    storage: contract_storage.as_mut(),
+                    api,
+                    querier: QuerierWrapper::new(&querier),
+                };
+                return self.call_catching_panics(&address, || action(handler, deps, env));
+            }
             let mut contract_storage = self.contract_storage_mut(write_cache, &address);
             let querier = RouterQuerier::new(router, api, read_store, block);
-            let env = self.get_env(address, block);
+            let querier = ContractQuerier::new(&querier, port_id);
+            let env = self.get_env(address.clone(), block);
 
             let deps = DepsMut {
                 storage: contract_storage.as_mut(),
                 api,
                 querier: QuerierWrapper::new(&querier),
             };
-            action(handler, deps, env)
+            self.call_catching_panics(&address, || action(handler, deps, env))
         })
     }
 
@@ -1232,6 +1673,70 @@ fn instantiate_response(data: Option<Binary>, contact_address: &Addr) -> Binary
     new_data.into()
 }
 
+/// Querier passed to the contract, answering IBC queries about the contract's own port
+/// like `wasmd` does, all other queries are passed to the wrapped querier.
+struct ContractQuerier<'a> {
+    querier: &'a dyn Querier,
+    /// Identifier of the IBC port bound to the contract, if any.
+    port_id: Option<String>,
+}
+
+impl<'a> ContractQuerier<'a> {
+    fn new(querier: &'a dyn Querier, port_id: Option<String>) -> Self {
+        Self { querier, port_id }
+    }
+}
+
+impl Querier for ContractQuerier<'_> {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // see https://github.com/CosmWasm/wasmd/blob/v0.51.0/x/wasm/keeper/query_plugins.go#L251-L305
+        let Ok(QueryRequest::<Empty>::Ibc(request)) = from_json(bin_request) else {
+            return self.querier.raw_query(bin_request);
+        };
+        let port_id = self.port_id.clone();
+        let request = match request {
+            IbcQuery::PortId {} => {
+                let response = PortIdResponse::new(port_id.unwrap_or_default());
+                return SystemResult::Ok(to_json_binary(&response).into());
+            }
+            // channels of the contract's own port are returned when the port is omitted
+            IbcQuery::ListChannels { port_id: None } => IbcQuery::ListChannels { port_id },
+            IbcQuery::Channel {
+                channel_id,
+                port_id: None,
+            } => IbcQuery::Channel {
+                channel_id,
+                port_id,
+            },
+            request => request,
+        };
+        match to_json_vec(&QueryRequest::<Empty>::Ibc(request)) {
+            Ok(bin_request) => self.querier.raw_query(&bin_request),
+            Err(err) => SystemResult::Err(SystemError::InvalidRequest {
+                error: format!("Serializing query request: {}", err),
+                request: bin_request.into(),
+            }),
+        }
+    }
+}
+
+/// Installs the panic hook capturing the backtrace of panics caught in contract's entry-points,
+/// other panics are passed to the previously installed hook. The hook is installed once
+/// for the whole process, as tests running in parallel would race replacing it for each call.
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CATCHING_PANICS.with(Cell::get) {
+                let backtrace = Backtrace::force_capture();
+                PANIC_BACKTRACE.with(|cell| cell.replace(Some(backtrace)));
+            } else {
+                previous_hook(info);
+            }
+        }));
+    });
+}
+
 #[derive(Clone, PartialEq, Message)]
 struct ExecuteResponse {
     #[prost(bytes, tag = "1")]
@@ -1258,7 +1763,7 @@ mod test {
     use crate::staking::{DistributionKeeper, StakeKeeper};
     use crate::test_helpers::{caller, error, payout};
     use crate::transactions::StorageTransaction;
-    use crate::{GovFailingModule, IbcFailingModule, StargateFailing};
+    use crate::{GovFailingModule, IbcFailingModule, MintKeeper, StargateFailing};
     use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockQuerier, MockStorage};
     use cosmwasm_std::{
         coin, from_json, to_json_vec, CanonicalAddr, CodeInfoResponse, CosmosMsg, Empty, HexBinary,
@@ -1275,6 +1780,7 @@ mod test {
         IbcFailingModule,
         GovFailingModule,
         StargateFailing,
+        MintKeeper,
     >;
 
     fn wasm_keeper() -> WasmKeeper<Empty, Empty> {
@@ -1291,6 +1797,11 @@ mod test {
             ibc: IbcFailingModule::new(),
             gov: GovFailingModule::new(),
             stargate: StargateFailing,
+            mint: MintKeeper::new(),
+            tracer: Default::default(),
+            meter: Default::default(),
+            invariants: Default::default(),
+            snapshots: Default::default(),
         }
     }
 
@@ -0,0 +1,292 @@
+//! # Contract executed in a real Wasm virtual machine
+//!
+//! [WasmContract] loads a compiled `.wasm` artifact and executes it in the same virtual machine
+//! that is used by `wasmd`, so the multi-test scenarios can be run against the actual bytecode
+//! instead of native mocks. Available only when the `wasm_vm` feature is enabled.
+//!
+//! The virtual machine requires its backend (storage, API and querier) to be owned, while
+//! the [Contract] entry-points receive borrowed dependencies. That's why the contract's storage
+//! is copied into the virtual machine before each call and written back when the call succeeds,
+//! and all API calls and queries are forwarded from the virtual machine running in a separate
+//! thread to the calling thread, where they are processed using the borrowed dependencies.
+
+use crate::error::{anyhow, AnyResult};
+use crate::Contract;
+use cosmwasm_std::{
+    Api, Binary, ContractResult, CustomMsg, CustomQuery, Deps, DepsMut, Env, MessageInfo, Order,
+    Querier, Reply, Response, Storage, SystemResult,
+};
+use cosmwasm_vm::testing::MockStorage;
+use cosmwasm_vm::{
+    call_execute, call_instantiate, call_migrate, call_query, call_reply, call_sudo, Backend,
+    BackendApi, BackendError, BackendResult, GasInfo, Instance, InstanceOptions,
+    Querier as VmQuerier, Storage as VmStorage, VmResult,
+};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+
+/// Default gas limit for a single call to the contract, measured in CosmWasm gas.
+/// Equals 10M of Cosmos SDK gas with the default `wasmd` gas multiplier.
+const DEFAULT_GAS_LIMIT: u64 = 1_400_000_000_000;
+
+/// Contract loaded from the compiled `.wasm` artifact.
+///
+/// # Example
+///
+/// ```ignore
+/// use cw_multi_test::{App, WasmContract};
+///
+/// let mut app = App::default();
+/// let contract = WasmContract::from_file("artifacts/my_contract.wasm").unwrap();
+/// let code_id = app.store_code(Box::new(contract));
+/// ```
+pub struct WasmContract {
+    /// Compiled contract's bytecode.
+    code: Vec<u8>,
+    /// Gas limit for a single call to the contract.
+    gas_limit: u64,
+}
+
+impl WasmContract {
+    /// Creates a contract from the compiled bytecode.
+    pub fn new(code: impl Into<Vec<u8>>) -> Self {
+        Self {
+            code: code.into(),
+            gas_limit: DEFAULT_GAS_LIMIT,
+        }
+    }
+
+    /// Creates a contract from the compiled bytecode read from the specified file.
+    pub fn from_file(path: impl AsRef<Path>) -> AnyResult<Self> {
+        Ok(Self::new(std::fs::read(path)?))
+    }
+
+    /// Sets the gas limit for a single call to the contract, measured in CosmWasm gas.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Executes an action on the contract's instance and writes back the modified storage.
+    fn call_mut<C, Q, F>(&self, deps: DepsMut<Q>, action: F) -> AnyResult<Response<C>>
+    where
+        Q: CustomQuery,
+        F: FnOnce(&mut VmInstance) -> VmResult<ContractResult<Response<C>>> + Send,
+        C: Send,
+    {
+        let storage = load_storage(deps.storage)?;
+        let (result, storage) = self.call(deps.api, &*deps.querier, storage, action)?;
+        let response = result.into_result().map_err(|e| anyhow!(e))?;
+        save_storage(deps.storage, storage)?;
+        Ok(response)
+    }
+
+    /// Executes an action on the contract's instance running in a separate thread,
+    /// processes all API calls and queries made by the contract until the action is finished.
+    fn call<T, F>(
+        &self,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        storage: MockStorage,
+        action: F,
+    ) -> AnyResult<(T, MockStorage)>
+    where
+        F: FnOnce(&mut VmInstance) -> VmResult<T> + Send,
+        T: Send,
+    {
+        let (sender, receiver) = channel();
+        thread::scope(|scope| {
+            let handle = scope.spawn(move || -> AnyResult<(T, MockStorage)> {
+                let backend = Backend {
+                    api: VmBackendApi(sender.clone()),
+                    storage,
+                    querier: VmBackendQuerier(sender),
+                };
+                let options = InstanceOptions {
+                    gas_limit: self.gas_limit,
+                };
+                let mut instance = Instance::from_code(&self.code, backend, options, None)?;
+                let result = action(&mut instance)?;
+                let backend = instance
+                    .recycle()
+                    .ok_or_else(|| anyhow!("failed to recycle the contract instance"))?;
+                Ok((result, backend.storage))
+            });
+            // the loop finishes when the instance is dropped together with all senders
+            for request in receiver {
+                match request {
+                    BackendRequest::AddrValidate(input, reply) => {
+                        let _ = reply.send(api.addr_validate(&input).map(|_| ()));
+                    }
+                    BackendRequest::AddrCanonicalize(human, reply) => {
+                        let _ = reply.send(api.addr_canonicalize(&human).map(Vec::from));
+                    }
+                    BackendRequest::AddrHumanize(canonical, reply) => {
+                        let _ = reply.send(
+                            api.addr_humanize(&canonical.into())
+                                .map(|addr| addr.to_string()),
+                        );
+                    }
+                    BackendRequest::Query(request, reply) => {
+                        let _ = reply.send(querier.raw_query(&request));
+                    }
+                }
+            }
+            handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
+}
+
+impl<C, Q> Contract<C, Q> for WasmContract
+where
+    C: CustomMsg + DeserializeOwned + Send,
+    Q: CustomQuery,
+{
+    /// Calls `execute` entry-point exported by the contract.
+    fn execute(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        self.call_mut(deps, |instance| call_execute(instance, &env, &info, &msg))
+    }
+
+    /// Calls `instantiate` entry-point exported by the contract.
+    fn instantiate(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response<C>> {
+        self.call_mut(deps, |instance| {
+            call_instantiate(instance, &env, &info, &msg)
+        })
+    }
+
+    /// Calls `query` entry-point exported by the contract.
+    fn query(&self, deps: Deps<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        let storage = load_storage(deps.storage)?;
+        let (result, _) = self.call(deps.api, &*deps.querier, storage, |instance| {
+            call_query(instance, &env, &msg)
+        })?;
+        result.into_result().map_err(|e| anyhow!(e))
+    }
+
+    /// Calls `sudo` entry-point exported by the contract.
+    fn sudo(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.call_mut(deps, |instance| call_sudo(instance, &env, &msg))
+    }
+
+    /// Calls `reply` entry-point exported by the contract.
+    fn reply(&self, deps: DepsMut<Q>, env: Env, msg: Reply) -> AnyResult<Response<C>> {
+        self.call_mut(deps, |instance| call_reply(instance, &env, &msg))
+    }
+
+    /// Calls `migrate` entry-point exported by the contract.
+    fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: Vec<u8>) -> AnyResult<Response<C>> {
+        self.call_mut(deps, |instance| call_migrate(instance, &env, &msg))
+    }
+}
+
+/// Instance of the contract running in the virtual machine.
+type VmInstance = Instance<VmBackendApi, MockStorage, VmBackendQuerier>;
+
+/// Request sent from the virtual machine to the calling thread.
+enum BackendRequest {
+    AddrValidate(String, Sender<cosmwasm_std::StdResult<()>>),
+    AddrCanonicalize(String, Sender<cosmwasm_std::StdResult<Vec<u8>>>),
+    AddrHumanize(Vec<u8>, Sender<cosmwasm_std::StdResult<String>>),
+    Query(Vec<u8>, Sender<SystemResult<ContractResult<Binary>>>),
+}
+
+/// Sends the request to the calling thread and waits for the reply.
+fn forward<T>(
+    sender: &Sender<BackendRequest>,
+    request: impl FnOnce(Sender<T>) -> BackendRequest,
+) -> Result<T, BackendError> {
+    let (reply_sender, reply_receiver) = channel();
+    sender
+        .send(request(reply_sender))
+        .map_err(|e| BackendError::unknown(e.to_string()))?;
+    reply_receiver
+        .recv()
+        .map_err(|e| BackendError::unknown(e.to_string()))
+}
+
+/// API forwarding all calls to the calling thread.
+#[derive(Clone)]
+struct VmBackendApi(Sender<BackendRequest>);
+
+impl BackendApi for VmBackendApi {
+    fn addr_validate(&self, input: &str) -> BackendResult<()> {
+        let result = forward(&self.0, |reply| {
+            BackendRequest::AddrValidate(input.to_string(), reply)
+        })
+        .and_then(|result| result.map_err(|e| BackendError::user_err(e.to_string())));
+        (result, GasInfo::free())
+    }
+
+    fn addr_canonicalize(&self, human: &str) -> BackendResult<Vec<u8>> {
+        let result = forward(&self.0, |reply| {
+            BackendRequest::AddrCanonicalize(human.to_string(), reply)
+        })
+        .and_then(|result| result.map_err(|e| BackendError::user_err(e.to_string())));
+        (result, GasInfo::free())
+    }
+
+    fn addr_humanize(&self, canonical: &[u8]) -> BackendResult<String> {
+        let result = forward(&self.0, |reply| {
+            BackendRequest::AddrHumanize(canonical.to_vec(), reply)
+        })
+        .and_then(|result| result.map_err(|e| BackendError::user_err(e.to_string())));
+        (result, GasInfo::free())
+    }
+}
+
+/// Querier forwarding all queries to the calling thread.
+struct VmBackendQuerier(Sender<BackendRequest>);
+
+impl VmQuerier for VmBackendQuerier {
+    fn query_raw(
+        &self,
+        request: &[u8],
+        _gas_limit: u64,
+    ) -> BackendResult<SystemResult<ContractResult<Binary>>> {
+        let result = forward(&self.0, |reply| {
+            BackendRequest::Query(request.to_vec(), reply)
+        });
+        (result, GasInfo::free())
+    }
+}
+
+/// Copies the contract's storage into the storage used by the virtual machine.
+fn load_storage(storage: &dyn Storage) -> AnyResult<MockStorage> {
+    let mut vm_storage = MockStorage::new();
+    for (key, value) in storage.range(None, None, Order::Ascending) {
+        vm_storage.set(&key, &value).0?;
+    }
+    Ok(vm_storage)
+}
+
+/// Writes back the storage modified by the virtual machine into the contract's storage.
+fn save_storage(storage: &mut dyn Storage, mut vm_storage: MockStorage) -> AnyResult<()> {
+    let iterator_id = vm_storage.scan(None, None, Order::Ascending).0?;
+    let records = vm_storage.all(iterator_id).0?;
+    let keys = storage
+        .range_keys(None, None, Order::Ascending)
+        .collect::<Vec<_>>();
+    for key in keys {
+        storage.remove(&key);
+    }
+    for (key, value) in records {
+        storage.set(&key, &value);
+    }
+    Ok(())
+}
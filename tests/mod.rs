@@ -4,8 +4,14 @@ mod test_api;
 mod test_app;
 mod test_app_builder;
 mod test_contract_storage;
+mod test_contract_wrapper;
+mod test_decorators;
+mod test_mock_contract;
 mod test_module;
 mod test_prefixed_storage;
+mod test_schema_validation;
+mod test_shadow;
+mod test_spying;
 mod test_wasm;
 
 mod test_contracts {
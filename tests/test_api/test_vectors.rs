@@ -0,0 +1,80 @@
+use super::*;
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{Addr, CanonicalAddr, HexBinary};
+use cw_multi_test::{no_init, AppBuilder, MockApiVectors};
+
+const ADDR_JUNO: &str = "juno1h34lmpywh4upnjdg90cjf4j70aee6z8qqfspugamjp42e4q28kqsksmtyp";
+
+fn canonical(hex: &str) -> CanonicalAddr {
+    HexBinary::from_hex(hex).unwrap().to_vec().into()
+}
+
+#[test]
+fn test_vectors_should_be_applied() {
+    let api = MockApiVectors::new()
+        .with_address("evmos1UPPER", canonical("0a0b0c"))
+        .with_canonicalized("evmos1upper", canonical("0a0b0c"))
+        .with_canonicalize_error("evmos1bad", "decoding bech32 failed: invalid checksum")
+        .with_humanize_error(canonical("ff"), "invalid address length");
+
+    assert_eq!(
+        canonical("0a0b0c"),
+        api.addr_canonicalize("evmos1UPPER").unwrap()
+    );
+    assert_eq!(
+        canonical("0a0b0c"),
+        api.addr_canonicalize("evmos1upper").unwrap()
+    );
+    assert_eq!(
+        "evmos1UPPER",
+        api.addr_humanize(&canonical("0a0b0c")).unwrap().as_str()
+    );
+    assert_eq!(
+        "Generic error: decoding bech32 failed: invalid checksum",
+        api.addr_canonicalize("evmos1bad").unwrap_err().to_string()
+    );
+    assert_eq!(
+        "Generic error: invalid address length",
+        api.addr_humanize(&canonical("ff")).unwrap_err().to_string()
+    );
+
+    // validation requires the address to be normalized
+    assert_eq!(
+        Addr::unchecked("evmos1UPPER"),
+        api.addr_validate("evmos1UPPER").unwrap()
+    );
+    assert_eq!(
+        "Generic error: Invalid input: address not normalized",
+        api.addr_validate("evmos1upper").unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn inputs_not_covered_by_test_vectors_should_fall_back() {
+    let api = MockApiVectors::with_fallback(MockApi::default().with_prefix("juno"))
+        .with_canonicalize_error("juno1bad", "bad address");
+    assert_eq!(ADDR_JUNO, api.addr_validate(ADDR_JUNO).unwrap().as_str());
+    assert!(api.addr_validate("juno1bad").is_err());
+    assert!(MockApiVectors::new().addr_validate(ADDR_JUNO).is_err());
+}
+
+#[test]
+fn app_should_use_test_vectors() {
+    let app = AppBuilder::default()
+        .with_api(MockApiVectors::new().with_canonicalize_error("creator", "banned address"))
+        .build(no_init);
+    assert_eq!(
+        "Generic error: banned address",
+        app.api().addr_validate("creator").unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn api_vectors_should_delegate_crypto() {
+    let api = MockApiVectors::new();
+    assert_secp256k1_verify_works(&api);
+    assert_secp256k1_recover_pubkey_works(&api);
+    assert_ed25519_verify_works(&api);
+    assert_ed25519_batch_verify_works(&api);
+    assert_debug_does_not_panic(&api);
+}
@@ -0,0 +1,418 @@
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, ChannelResponse, Deps, DepsMut, Empty, Env,
+    Ibc3ChannelOpenResponse, IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg,
+    IbcChannelOpenMsg, IbcChannelOpenResponse, IbcMsg, IbcPacketAckMsg, IbcPacketReceiveMsg,
+    IbcPacketTimeoutMsg, IbcQuery, IbcReceiveResponse, IbcTimeout, IbcTimeoutBlock,
+    ListChannelsResponse, MessageInfo, PortIdResponse, QueryRequest, Response, StdError,
+};
+use cw_multi_test::{
+    App, AppBuilder, BankKeeper, Contract, ContractWrapper, DistributionKeeper, Executor,
+    FailingModule, IbcDestinationCallbackMsg, IbcLocalhost, IbcSourceCallbackMsg, StakeKeeper,
+    WasmKeeper,
+};
+use cw_storage_plus::Item;
+use serde::{Deserialize, Serialize};
+
+/// Version of channels accepted by the contract.
+const VERSION: Item<String> = Item::new("version");
+
+/// Identifier of the last connected channel.
+const CHANNEL: Item<String> = Item::new("channel");
+
+/// Everything seen by the contract.
+const STATE: Item<State> = Item::new("state");
+
+/// Channel handshake steps, packets and callbacks seen by the contract.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct State {
+    /// Handshake and closing steps, with versions and counterparty channels.
+    pub steps: Vec<String>,
+    /// Texts of received packets.
+    pub received: Vec<String>,
+    /// Acknowledgements of sent packets.
+    pub acks: Vec<String>,
+    /// Texts of timed out packets.
+    pub timeouts: Vec<String>,
+    /// Whether a channel of the contract was closed.
+    pub closed: bool,
+    /// Acknowledgements passed to the source callback.
+    pub source_acks: Vec<String>,
+    /// Number of timeouts passed to the source callback.
+    pub source_timeouts: u64,
+    /// Acknowledgements passed to the destination callback.
+    pub destination_acks: Vec<String>,
+}
+
+/// Data of packets sent by the contract, with callback metadata in the memo.
+#[derive(Serialize, Deserialize, Debug)]
+struct PacketData {
+    text: String,
+    memo: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InstantiateMsg {
+    /// Version of accepted channels, proposed when the counterparty gives none.
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecMsg {
+    /// Sends the text over the last connected channel, the packet times out 10 blocks ahead
+    /// unless the height is given, callbacks are requested for the specified contracts.
+    Send {
+        text: String,
+        timeout_height: Option<u64>,
+        src_callback: Option<String>,
+        dest_callback: Option<String>,
+    },
+    /// Closes the channel.
+    Close { channel_id: String },
+}
+
+impl ExecMsg {
+    /// Sends the text without callbacks, timing out at the specified height.
+    pub fn send(text: &str, timeout_height: u64) -> Self {
+        Self::Send {
+            text: text.to_string(),
+            timeout_height: Some(timeout_height),
+            src_callback: None,
+            dest_callback: None,
+        }
+    }
+}
+
+/// Returns [State], other queries are answered with IBC queries
+/// about the contract's own port, which is omitted.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    State {},
+    PortId {},
+    ListChannels {},
+    Channel { channel_id: String },
+}
+
+fn instantiate(
+    deps: DepsMut,
+    _: Env,
+    _: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, StdError> {
+    VERSION.save(deps.storage, &msg.version)?;
+    STATE.save(deps.storage, &State::default())?;
+    Ok(Response::default())
+}
+
+fn execute(deps: DepsMut, env: Env, _: MessageInfo, msg: ExecMsg) -> Result<Response, StdError> {
+    match msg {
+        ExecMsg::Send {
+            text,
+            timeout_height,
+            src_callback,
+            dest_callback,
+        } => {
+            let mut memo = serde_json::Map::new();
+            if let Some(address) = src_callback {
+                memo.insert(
+                    "src_callback".into(),
+                    serde_json::json!({ "address": address }),
+                );
+            }
+            if let Some(address) = dest_callback {
+                memo.insert(
+                    "dest_callback".into(),
+                    serde_json::json!({ "address": address }),
+                );
+            }
+            let data = PacketData {
+                text,
+                memo: serde_json::Value::Object(memo).to_string(),
+            };
+            let channel_id = CHANNEL.load(deps.storage)?;
+            let timeout_height = timeout_height.unwrap_or(env.block.height + 10);
+            Ok(Response::new().add_message(send_packet(channel_id, &data, timeout_height)?))
+        }
+        ExecMsg::Close { channel_id } => {
+            Ok(Response::new().add_message(IbcMsg::CloseChannel { channel_id }))
+        }
+    }
+}
+
+fn send_packet(
+    channel_id: String,
+    data: &PacketData,
+    timeout_height: u64,
+) -> Result<IbcMsg, StdError> {
+    Ok(IbcMsg::SendPacket {
+        channel_id,
+        data: to_json_binary(data)?,
+        timeout: IbcTimeout::with_block(IbcTimeoutBlock {
+            revision: 0,
+            height: timeout_height,
+        }),
+    })
+}
+
+fn query(deps: Deps, _: Env, msg: QueryMsg) -> Result<Binary, StdError> {
+    match msg {
+        QueryMsg::State {} => to_json_binary(&STATE.load(deps.storage)?),
+        QueryMsg::PortId {} => {
+            let request = QueryRequest::Ibc(IbcQuery::PortId {});
+            to_json_binary(&deps.querier.query::<PortIdResponse>(&request)?)
+        }
+        QueryMsg::ListChannels {} => {
+            let request = QueryRequest::Ibc(IbcQuery::ListChannels { port_id: None });
+            to_json_binary(&deps.querier.query::<ListChannelsResponse>(&request)?)
+        }
+        QueryMsg::Channel { channel_id } => {
+            let request = QueryRequest::Ibc(IbcQuery::Channel {
+                channel_id,
+                port_id: None,
+            });
+            to_json_binary(&deps.querier.query::<ChannelResponse>(&request)?)
+        }
+    }
+}
+
+fn update_state(deps: DepsMut, action: impl FnOnce(&mut State)) -> Result<(), StdError> {
+    STATE.update(deps.storage, |mut state| -> Result<_, StdError> {
+        action(&mut state);
+        Ok(state)
+    })?;
+    Ok(())
+}
+
+/// Proposes its own version when none is given, accepts only that version otherwise.
+fn channel_open(
+    deps: DepsMut,
+    _: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<IbcChannelOpenResponse, StdError> {
+    let (step, version) = match &msg {
+        IbcChannelOpenMsg::OpenInit { channel } => ("init", channel.version.as_str()),
+        IbcChannelOpenMsg::OpenTry {
+            counterparty_version,
+            ..
+        } => ("try", counterparty_version.as_str()),
+    };
+    let own_version = VERSION.load(deps.storage)?;
+    let step = format!("{}:{}", step, version);
+    let response = match version {
+        "" => Some(Ibc3ChannelOpenResponse {
+            version: own_version,
+        }),
+        version if version == own_version => None,
+        _ => return Err(StdError::generic_err("invalid version")),
+    };
+    update_state(deps, |state| state.steps.push(step))?;
+    Ok(response)
+}
+
+fn channel_connect(
+    deps: DepsMut,
+    _: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, StdError> {
+    let step = match &msg {
+        IbcChannelConnectMsg::OpenAck { .. } => "ack",
+        IbcChannelConnectMsg::OpenConfirm { .. } => "confirm",
+    };
+    let channel = msg.channel();
+    CHANNEL.save(deps.storage, &channel.endpoint.channel_id)?;
+    let step = format!(
+        "{}:{}:{}",
+        step, channel.version, channel.counterparty_endpoint.channel_id
+    );
+    update_state(deps, |state| state.steps.push(step))?;
+    Ok(IbcBasicResponse::new())
+}
+
+fn channel_close(
+    deps: DepsMut,
+    _: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, StdError> {
+    let step = match &msg {
+        IbcChannelCloseMsg::CloseInit { .. } => "close_init",
+        IbcChannelCloseMsg::CloseConfirm { .. } => "close_confirm",
+    };
+    let step = format!(
+        "{}:{}",
+        step,
+        msg.channel().counterparty_endpoint.channel_id
+    );
+    update_state(deps, |state| {
+        state.steps.push(step);
+        state.closed = true;
+    })?;
+    Ok(IbcBasicResponse::new())
+}
+
+/// Acknowledges the packet with its text, answering pings with pongs sent back over the channel.
+/// Fails after recording texts starting with `fail`.
+fn packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, StdError> {
+    let data: PacketData = from_json(&msg.packet.data)?;
+    let text = data.text.clone();
+    update_state(deps, |state| state.received.push(text))?;
+    if data.text.starts_with("fail") {
+        return Err(StdError::generic_err(format!(
+            "cannot receive {}",
+            data.text
+        )));
+    }
+    let response = IbcReceiveResponse::new(Binary::from(format!("ack {}", data.text).into_bytes()))
+        .add_attribute("action", "receive");
+    match data.text.strip_prefix("ping ") {
+        Some(text) => {
+            let pong = PacketData {
+                text: format!("pong {}", text),
+                memo: String::new(),
+            };
+            let channel_id = msg.packet.dest.channel_id;
+            Ok(response.add_message(send_packet(channel_id, &pong, env.block.height + 10)?))
+        }
+        None => Ok(response),
+    }
+}
+
+fn packet_ack(deps: DepsMut, _: Env, msg: IbcPacketAckMsg) -> Result<IbcBasicResponse, StdError> {
+    let ack = String::from_utf8(msg.acknowledgement.data.to_vec())?;
+    update_state(deps, |state| state.acks.push(ack))?;
+    Ok(IbcBasicResponse::new().add_attribute("action", "ack"))
+}
+
+fn packet_timeout(
+    deps: DepsMut,
+    _: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, StdError> {
+    let data: PacketData = from_json(&msg.packet.data)?;
+    update_state(deps, |state| state.timeouts.push(data.text))?;
+    Ok(IbcBasicResponse::new())
+}
+
+fn source_callback(
+    deps: DepsMut,
+    _: Env,
+    msg: IbcSourceCallbackMsg,
+) -> Result<IbcBasicResponse, StdError> {
+    match msg {
+        IbcSourceCallbackMsg::Acknowledgement(msg) => {
+            let ack = String::from_utf8(msg.acknowledgement.data.to_vec())?;
+            update_state(deps, |state| state.source_acks.push(ack))?;
+        }
+        IbcSourceCallbackMsg::Timeout(_) => {
+            update_state(deps, |state| state.source_timeouts += 1)?;
+        }
+    }
+    Ok(IbcBasicResponse::new())
+}
+
+fn destination_callback(
+    deps: DepsMut,
+    _: Env,
+    msg: IbcDestinationCallbackMsg,
+) -> Result<IbcBasicResponse, StdError> {
+    let ack = String::from_utf8(msg.ack.data.to_vec())?;
+    update_state(deps, |state| state.destination_acks.push(ack))?;
+    Ok(IbcBasicResponse::new())
+}
+
+/// Returns the IBC-enabled contract, receiving IBC callbacks.
+pub fn contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new(execute, instantiate, query)
+            .with_ibc(
+                channel_open,
+                channel_connect,
+                channel_close,
+                packet_receive,
+                packet_ack,
+                packet_timeout,
+            )
+            .with_ibc_callbacks(source_callback, destination_callback),
+    )
+}
+
+/// Returns the same contract without IBC entry points, so no port is bound to it.
+pub fn plain_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// Application relaying packets over localhost channels.
+pub type IbcApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcLocalhost,
+>;
+
+/// Returns the application relaying packets over localhost channels.
+pub fn ibc_app() -> IbcApp {
+    AppBuilder::default()
+        .with_ibc(IbcLocalhost::new())
+        .build(|_, _, _| {})
+}
+
+/// Instantiates the contract accepting channels with specified version,
+/// returns the address of the contract and the identifier of its port.
+pub fn instantiate_contract(
+    app: &mut IbcApp,
+    code_id: u64,
+    label: &str,
+    version: &str,
+) -> (Addr, String) {
+    let owner_addr = app.api().addr_make("owner");
+    let msg = InstantiateMsg {
+        version: version.to_string(),
+    };
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr, &msg, &[], label, None)
+        .unwrap();
+    let port_id = app.ibc_port_id(&contract_addr).unwrap().unwrap_or_default();
+    (contract_addr, port_id)
+}
+
+/// Prepares the chain with the IBC-enabled contract, returns the chain,
+/// the address of the contract and the identifier of its port.
+pub fn setup(version: &str) -> (IbcApp, Addr, String) {
+    let mut app = ibc_app();
+    let code_id = app.store_code(contract());
+    let (contract_addr, port_id) = instantiate_contract(&mut app, code_id, "ibc", version);
+    (app, contract_addr, port_id)
+}
+
+/// Executes the message as the owner of the contract.
+pub fn execute_contract(
+    app: &mut IbcApp,
+    contract_addr: &Addr,
+    msg: &ExecMsg,
+) -> anyhow::Result<()> {
+    let owner_addr = app.api().addr_make("owner");
+    app.execute_contract(owner_addr, contract_addr.clone(), msg, &[])?;
+    Ok(())
+}
+
+/// Returns everything seen by the contract.
+pub fn state(app: &IbcApp, contract_addr: &Addr) -> State {
+    query_contract(app, contract_addr, &QueryMsg::State {})
+}
+
+pub fn query_contract<T: serde::de::DeserializeOwned>(
+    app: &IbcApp,
+    contract_addr: &Addr,
+    msg: &QueryMsg,
+) -> T {
+    app.wrap().query_wasm_smart(contract_addr, msg).unwrap()
+}
@@ -0,0 +1,16 @@
+use cw_multi_test::{no_init, AppBuilder, CosmosSdkStargate};
+use staking::StakingApp;
+
+pub mod ibc;
+pub mod multi_send;
+pub mod staking;
+
+/// Application executing protobuf-encoded Cosmos SDK messages.
+pub type SdkApp = StakingApp<CosmosSdkStargate>;
+
+/// Returns the application executing protobuf-encoded Cosmos SDK messages.
+pub fn sdk_app() -> SdkApp {
+    AppBuilder::default()
+        .with_stargate(CosmosSdkStargate)
+        .build(no_init)
+}
@@ -0,0 +1,48 @@
+use cosmwasm_std::{Addr, AnyMsg, Coin, CosmosMsg};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ProtoIo {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(message, repeated, tag = "2")]
+    pub coins: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgMultiSend {
+    #[prost(message, repeated, tag = "1")]
+    pub inputs: Vec<ProtoIo>,
+    #[prost(message, repeated, tag = "2")]
+    pub outputs: Vec<ProtoIo>,
+}
+
+/// Returns the input or output of `MsgMultiSend`.
+pub fn proto_io(address: &Addr, coins: Vec<Coin>) -> ProtoIo {
+    ProtoIo {
+        address: address.to_string(),
+        coins: coins
+            .into_iter()
+            .map(|c| ProtoCoin {
+                denom: c.denom,
+                amount: c.amount.to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Returns the `MsgMultiSend` message sent as `CosmosMsg::Any`.
+pub fn multi_send_msg(inputs: Vec<ProtoIo>, outputs: Vec<ProtoIo>) -> CosmosMsg {
+    CosmosMsg::Any(AnyMsg {
+        type_url: "/cosmos.bank.v1beta1.MsgMultiSend".to_string(),
+        value: MsgMultiSend { inputs, outputs }.encode_to_vec().into(),
+    })
+}
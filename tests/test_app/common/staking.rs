@@ -0,0 +1,188 @@
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    coin, coins, to_json_vec, Addr, AnyMsg, ContractResult, CosmosMsg, Decimal, Empty, GrpcQuery,
+    Querier, QuerierWrapper, QueryRequest, StakingMsg, SystemResult, Validator,
+};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{
+    App, AppResponse, BankKeeper, DistributionKeeper, Executor, FailingModule, GovFailingModule,
+    IbcFailingModule, StakeKeeper, Stargate, WasmKeeper,
+};
+use prost::Message;
+
+/// Denomination of bonded tokens.
+pub const DENOM: &str = "TOKEN";
+
+/// Application with default modules and the specified Stargate handler.
+pub type StakingApp<StargateT> = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    StargateT,
+>;
+
+/// Returns the validator with specified commission percentage,
+/// which can be changed by 1% up to 100%.
+pub fn validator(validator_addr: &Addr, commission: u64) -> Validator {
+    Validator::new(
+        validator_addr.to_string(),
+        Decimal::percent(commission),
+        Decimal::percent(100),
+        Decimal::percent(1),
+    )
+}
+
+/// Sets initial balances of bonded tokens and adds validators.
+pub fn init_staking<StargateT: Stargate>(
+    app: &mut StakingApp<StargateT>,
+    balances: &[(&Addr, u128)],
+    validators: &[Validator],
+) {
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        for (addr, amount) in balances {
+            router
+                .bank
+                .init_balance(storage, addr, coins(*amount, DENOM))
+                .unwrap();
+        }
+        for validator in validators {
+            router
+                .staking
+                .add_validator(api, storage, &block, validator.clone())
+                .unwrap();
+        }
+    });
+}
+
+/// Delegates tokens of the delegator to the validator.
+pub fn delegate<StargateT: Stargate>(
+    app: &mut StakingApp<StargateT>,
+    delegator_addr: &Addr,
+    validator_addr: &Addr,
+    amount: u128,
+) {
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(amount, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+}
+
+/// Undelegates tokens of the delegator from the validator.
+pub fn undelegate<StargateT: Stargate>(
+    app: &mut StakingApp<StargateT>,
+    delegator_addr: &Addr,
+    validator_addr: &Addr,
+    amount: u128,
+) -> AppResponse {
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(amount, DENOM),
+        }
+        .into(),
+    )
+    .unwrap()
+}
+
+/// Message unjailing the validator, sent by its operator.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgUnjail {
+    #[prost(string, tag = "1")]
+    pub validator_addr: String,
+}
+
+/// Unjails the validator with protobuf-encoded `MsgUnjail`.
+pub fn unjail<StargateT: Stargate>(
+    app: &mut StakingApp<StargateT>,
+    validator_addr: &Addr,
+) -> AnyResult<AppResponse> {
+    let msg = MsgUnjail {
+        validator_addr: validator_addr.to_string(),
+    };
+    app.execute(
+        validator_addr.clone(),
+        CosmosMsg::Any(AnyMsg {
+            type_url: "/cosmos.slashing.v1beta1.MsgUnjail".to_string(),
+            value: msg.encode_to_vec().into(),
+        }),
+    )
+}
+
+/// Moves to the next block, the specified number of seconds later.
+pub fn advance_seconds<StargateT: Stargate>(app: &mut StakingApp<StargateT>, seconds: u64) {
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(seconds);
+    });
+}
+
+/// Returns the balance of bonded tokens.
+pub fn balance(querier: &dyn Querier, addr: &Addr) -> u128 {
+    QuerierWrapper::<Empty>::new(querier)
+        .query_balance(addr, DENOM)
+        .unwrap()
+        .amount
+        .u128()
+}
+
+/// Returns the amount of tokens delegated to the validator, zero when not delegated.
+pub fn delegated(querier: &dyn Querier, delegator_addr: &Addr, validator_addr: &Addr) -> u128 {
+    QuerierWrapper::<Empty>::new(querier)
+        .query_delegation(delegator_addr, validator_addr)
+        .unwrap()
+        .map_or(0, |delegation| delegation.amount.amount.u128())
+}
+
+/// Returns rewards accumulated by the delegation.
+pub fn rewards(querier: &dyn Querier, delegator_addr: &Addr, validator_addr: &Addr) -> u128 {
+    QuerierWrapper::<Empty>::new(querier)
+        .query_delegation(delegator_addr, validator_addr)
+        .unwrap()
+        .unwrap()
+        .accumulated_rewards
+        .iter()
+        .map(|c| c.amount.u128())
+        .sum()
+}
+
+/// Sends the gRPC query with protobuf-encoded request,
+/// returns the decoded response or the error message.
+pub fn try_grpc_query<T: Message + Default>(
+    querier: &dyn Querier,
+    path: &str,
+    request: impl Message,
+) -> Result<T, String> {
+    let request = QueryRequest::<Empty>::Grpc(GrpcQuery {
+        path: path.to_string(),
+        data: request.encode_to_vec().into(),
+    });
+    match querier.raw_query(&to_json_vec(&request).unwrap()) {
+        SystemResult::Ok(ContractResult::Ok(response)) => {
+            Ok(T::decode(response.as_slice()).unwrap())
+        }
+        SystemResult::Ok(ContractResult::Err(error)) => Err(error),
+        SystemResult::Err(error) => Err(error.to_string()),
+    }
+}
+
+/// Sends the gRPC query with protobuf-encoded request, returns the decoded response.
+pub fn grpc_query<T: Message + Default>(
+    querier: &dyn Querier,
+    path: &str,
+    request: impl Message,
+) -> T {
+    try_grpc_query(querier, path, request).unwrap()
+}
@@ -1,4 +1,57 @@
+mod common;
+mod test_adversary;
+mod test_all_contracts;
+mod test_balance_helpers;
+mod test_blocked_addresses;
+mod test_clone;
+mod test_community_pool;
+mod test_cron;
+mod test_delegation_queries;
+mod test_delegation_rewards;
+mod test_denom_traces;
+mod test_execute_as_module;
+mod test_experimental_features;
+mod test_f1_distribution;
+mod test_funds_matchers;
+mod test_gov;
+mod test_ibc_callbacks;
+mod test_ibc_channels;
+mod test_ibc_localhost;
+mod test_ibc_ordered_channels;
+mod test_ibc_queries;
+mod test_ibc_transfer;
+mod test_idempotency;
 mod test_instantiate2;
+mod test_invariant_checks;
+mod test_migration_matrix;
+mod test_min_self_delegation;
+mod test_mint;
+mod test_multi_send;
+mod test_multisig_admin;
+mod test_outstanding_rewards;
+mod test_param_history;
+mod test_pause;
+mod test_redelegations;
+mod test_regression_export;
+mod test_relayer;
+mod test_resource_budget;
+mod test_reward_accrual;
+mod test_rollback_blocks;
+mod test_rpc;
+mod test_send_enabled;
+mod test_send_restrictions;
+mod test_slashing;
+mod test_staking_hooks;
+mod test_staking_params;
 mod test_store_code;
 mod test_store_code_with_creator;
 mod test_store_code_with_id;
+mod test_tendermint_queries;
+mod test_tokenize_shares;
+mod test_transfer_hooks;
+mod test_unbonding;
+mod test_validator_commission;
+mod test_validator_description;
+mod test_validator_set;
+mod test_vesting;
+mod test_withdraw_address;
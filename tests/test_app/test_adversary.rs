@@ -0,0 +1,96 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, Response, StdResult,
+    SubMsg, SubMsgResult, WasmMsg,
+};
+use cw_multi_test::{Adversary, App, Contract, ContractWrapper, Executor, ResourceBudget};
+use cw_storage_plus::Item;
+
+/// Number of events and size of data received from the counterparty in the last reply.
+const LAST_REPLY: Item<(usize, usize)> = Item::new("last_reply");
+
+/// Protocol calling the counterparty passed in the message and recording its reply.
+fn protocol() -> Box<dyn Contract<Empty>> {
+    fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+    fn execute(_: DepsMut, _: Env, _: MessageInfo, counterparty: String) -> StdResult<Response> {
+        Ok(Response::new().add_submessage(SubMsg::reply_always(
+            WasmMsg::Execute {
+                contract_addr: counterparty,
+                msg: to_json_binary(&Empty {})?,
+                funds: vec![],
+            },
+            1,
+        )))
+    }
+    fn query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+        to_json_binary(&LAST_REPLY.load(deps.storage)?)
+    }
+    #[allow(deprecated)]
+    fn reply(deps: DepsMut, _: Env, msg: Reply) -> StdResult<Response> {
+        if let SubMsgResult::Ok(res) = msg.result {
+            let data_size = res.data.map_or(0, |data| data.len());
+            LAST_REPLY.save(deps.storage, &(res.events.len(), data_size))?;
+        }
+        Ok(Response::new())
+    }
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+#[test]
+fn protocol_should_survive_huge_replies() {
+    let mut app = App::default();
+    let adversary_addr = app
+        .instantiate_adversary(
+            Adversary::new()
+                .returning_data(100_000)
+                .emitting_events(2000),
+        )
+        .unwrap();
+    let code_id = app.store_code(protocol());
+    let owner_addr = app.api().addr_make("owner");
+    let protocol_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "protocol",
+            None,
+        )
+        .unwrap();
+
+    app.execute_contract(owner_addr, protocol_addr.clone(), &adversary_addr, &[])
+        .unwrap();
+
+    // the execute event of the adversary precedes emitted events,
+    // data is wrapped in the protobuf-encoded execute response
+    let last_reply: (usize, usize) = app
+        .wrap()
+        .query_wasm_smart(protocol_addr, &Empty {})
+        .unwrap();
+    assert_eq!((2001, 100_004), last_reply);
+}
+
+#[test]
+fn gas_burning_adversary_should_consume_simulated_gas() {
+    let mut app = App::default();
+    let harmless_addr = app.instantiate_adversary(Adversary::new()).unwrap();
+    let griefing_addr = app
+        .instantiate_adversary(Adversary::new().burning_gas(100))
+        .unwrap();
+    let sender_addr = app.api().addr_make("sender");
+
+    app.set_resource_budget(ResourceBudget::new());
+    app.execute_contract(sender_addr.clone(), harmless_addr, &Empty {}, &[])
+        .unwrap();
+    let harmless_gas = app.resource_usage().gas;
+
+    app.set_resource_budget(ResourceBudget::new());
+    app.execute_contract(sender_addr, griefing_addr, &Empty {}, &[])
+        .unwrap();
+    let griefing_gas = app.resource_usage().gas;
+
+    // every write of 1KiB value costs at least 2000 + 30 * 1024 gas
+    assert!(griefing_gas >= harmless_gas + 100 * (2000 + 30 * 1024));
+}
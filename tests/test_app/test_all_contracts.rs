@@ -0,0 +1,64 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::Empty;
+use cw_multi_test::{App, Executor};
+
+#[test]
+fn all_contracts_should_be_listed_with_metadata() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let admin_addr = app.api().addr_make("admin");
+    let code_id = app.store_code(counter::contract());
+
+    // no contracts instantiated yet
+    assert!(app.all_contracts().unwrap().is_empty());
+
+    let mut expected = vec![];
+    for label in ["pool-1", "vault", "pool-2"] {
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                owner_addr.clone(),
+                &Empty {},
+                &[],
+                label,
+                Some(admin_addr.to_string()),
+            )
+            .unwrap();
+        expected.push((contract_addr, label, app.block_info().height));
+        app.update_block(|block| block.height += 1);
+    }
+    expected.sort();
+
+    let contracts = app.all_contracts().unwrap();
+    assert_eq!(3, contracts.len());
+    for ((addr, data), (expected_addr, label, created)) in contracts.iter().zip(&expected) {
+        assert_eq!(expected_addr, addr);
+        assert_eq!(code_id, data.code_id);
+        assert_eq!(owner_addr, data.creator);
+        assert_eq!(Some(admin_addr.clone()), data.admin);
+        assert_eq!(*label, data.label);
+        assert_eq!(*created, data.created);
+    }
+}
+
+#[test]
+fn contracts_should_be_filtered_by_label_prefix() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(counter::contract());
+    for label in ["pool-1", "vault", "pool-2"] {
+        app.instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], label, None)
+            .unwrap();
+    }
+
+    let mut labels = app
+        .contracts_by_label_prefix("pool-")
+        .unwrap()
+        .into_iter()
+        .map(|(_, data)| data.label)
+        .collect::<Vec<_>>();
+    labels.sort();
+    assert_eq!(vec!["pool-1", "pool-2"], labels);
+    assert!(app.contracts_by_label_prefix("farm").unwrap().is_empty());
+    assert_eq!(3, app.contracts_by_label_prefix("").unwrap().len());
+}
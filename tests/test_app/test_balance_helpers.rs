@@ -0,0 +1,54 @@
+use cosmwasm_std::{coin, coins};
+use cw_multi_test::App;
+
+#[test]
+fn mint_to_should_increase_balance_and_supply() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+
+    app.mint_to(&owner_addr, coins(100, "uatom")).unwrap();
+    app.update_block(|_| {});
+    app.mint_to(&owner_addr, vec![coin(50, "uatom"), coin(10, "uosmo")])
+        .unwrap();
+
+    assert_eq!(
+        vec![coin(150, "uatom"), coin(10, "uosmo")],
+        app.wrap().query_all_balances(&owner_addr).unwrap()
+    );
+    assert_eq!(
+        coin(150, "uatom"),
+        app.wrap().query_supply("uatom").unwrap()
+    );
+
+    // invalid addresses and empty amounts are rejected
+    app.mint_to("owner", coins(1, "uatom")).unwrap_err();
+    app.mint_to(&owner_addr, vec![]).unwrap_err();
+}
+
+#[test]
+fn set_balance_should_overwrite_balance_and_adjust_supply() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let other_addr = app.api().addr_make("other");
+    app.mint_to(&owner_addr, vec![coin(100, "uatom"), coin(10, "uosmo")])
+        .unwrap();
+    app.mint_to(&other_addr, coins(30, "uatom")).unwrap();
+
+    // denominations not listed are cleared
+    app.set_balance(&owner_addr, coins(40, "uatom")).unwrap();
+    assert_eq!(
+        coins(40, "uatom"),
+        app.wrap().query_all_balances(&owner_addr).unwrap()
+    );
+    assert_eq!(coin(70, "uatom"), app.wrap().query_supply("uatom").unwrap());
+    assert_eq!(coin(0, "uosmo"), app.wrap().query_supply("uosmo").unwrap());
+
+    // all balances can be cleared
+    app.set_balance(&owner_addr, vec![]).unwrap();
+    assert!(app
+        .wrap()
+        .query_all_balances(&owner_addr)
+        .unwrap()
+        .is_empty());
+    assert_eq!(coin(30, "uatom"), app.wrap().query_supply("uatom").unwrap());
+}
@@ -0,0 +1,85 @@
+use super::common::multi_send::{multi_send_msg, proto_io};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{BankSudo, Executor};
+
+fn setup() -> (SdkApp, Addr, Addr) {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    let distribution_addr = app.module_address("distribution").unwrap();
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner_addr, coins(100, "uatom"))
+            .unwrap();
+        router
+            .bank
+            .block_address(storage, &distribution_addr)
+            .unwrap();
+    });
+    (app, owner_addr, distribution_addr)
+}
+
+#[test]
+fn blocked_addresses_should_not_receive_transfers() {
+    let (mut app, owner_addr, distribution_addr) = setup();
+    let expected_error = format!(
+        "{} is not allowed to receive funds: unauthorized",
+        distribution_addr
+    );
+
+    let err = app
+        .send_tokens(
+            owner_addr.clone(),
+            distribution_addr.clone(),
+            &coins(10, "uatom"),
+        )
+        .unwrap_err();
+    assert_eq!(expected_error, err.root_cause().to_string());
+
+    let err = app
+        .execute(
+            owner_addr.clone(),
+            multi_send_msg(
+                vec![proto_io(&owner_addr, coins(10, "uatom"))],
+                vec![proto_io(&distribution_addr, coins(10, "uatom"))],
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(expected_error, err.root_cause().to_string());
+
+    // minting to blocked address is allowed
+    app.sudo(
+        BankSudo::Mint {
+            to_address: distribution_addr.to_string(),
+            amount: coins(5, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(
+        coins(5, "uatom"),
+        app.wrap().query_all_balances(&distribution_addr).unwrap()
+    );
+}
+
+#[test]
+fn unblocked_addresses_should_receive_transfers() {
+    let (mut app, owner_addr, distribution_addr) = setup();
+    app.init_modules(|router, _, storage| {
+        assert!(router
+            .bank
+            .is_blocked_address(storage, &distribution_addr)
+            .unwrap());
+        router
+            .bank
+            .unblock_address(storage, &distribution_addr)
+            .unwrap();
+    });
+    app.send_tokens(owner_addr, distribution_addr.clone(), &coins(10, "uatom"))
+        .unwrap();
+    assert_eq!(
+        coins(10, "uatom"),
+        app.wrap().query_all_balances(&distribution_addr).unwrap()
+    );
+}
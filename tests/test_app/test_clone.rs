@@ -0,0 +1,82 @@
+use crate::test_contracts::counter;
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{coin, Addr, Empty, WasmMsg};
+use cw_multi_test::{App, AppBuilder, BankKeeper, CloneableStorage, Executor};
+
+type CloneableApp = App<BankKeeper, MockApi, CloneableStorage>;
+
+fn counter_value(app: &CloneableApp, contract_addr: &Addr) -> u64 {
+    app.wrap()
+        .query_wasm_smart::<counter::CounterResponseMsg>(
+            contract_addr,
+            &counter::CounterQueryMsg::Counter {},
+        )
+        .unwrap()
+        .value
+}
+
+#[test]
+fn cloned_app_should_branch_from_common_setup() {
+    // common setup with funded account and instantiated contract
+    let owner_addr = MockApi::default().addr_make("owner");
+    let mut app = AppBuilder::default()
+        .with_storage(CloneableStorage::default())
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &owner_addr, vec![coin(100, "ucosm")])
+                .unwrap();
+        });
+    let code_id = app.store_code(counter::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+
+    // branch the scenario
+    let mut branch = app.clone();
+    let msg = WasmMsg::ClearAdmin {
+        contract_addr: contract_addr.to_string(),
+    };
+    branch
+        .execute_contract(owner_addr.clone(), contract_addr.clone(), &msg, &[])
+        .unwrap();
+    branch
+        .send_tokens(
+            owner_addr.clone(),
+            contract_addr.clone(),
+            &[coin(40, "ucosm")],
+        )
+        .unwrap();
+    branch.update_block(|block| block.height += 10);
+
+    // the branch has its own state
+    assert_eq!(2, counter_value(&branch, &contract_addr));
+    assert_eq!(
+        60,
+        branch
+            .wrap()
+            .query_balance(&owner_addr, "ucosm")
+            .unwrap()
+            .amount
+            .u128()
+    );
+
+    // the original application is not affected
+    assert_eq!(1, counter_value(&app, &contract_addr));
+    assert_eq!(
+        100,
+        app.wrap()
+            .query_balance(&owner_addr, "ucosm")
+            .unwrap()
+            .amount
+            .u128()
+    );
+    assert_eq!(app.block_info().height + 10, branch.block_info().height);
+
+    // codes stored before cloning are shared, codes stored later are not
+    let branch_code_id = branch.store_code(counter::contract());
+    assert_eq!(code_id + 1, branch_code_id);
+    assert!(app.wrap().query_wasm_code_info(branch_code_id).is_err());
+    app.instantiate_contract(code_id, owner_addr, &Empty {}, &[], "counter", None)
+        .unwrap();
+}
@@ -0,0 +1,120 @@
+use super::common::staking::{balance, grpc_query, init_staking, DENOM};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{coin, coins, Addr, AnyMsg, Coin, CosmosMsg, DistributionMsg, Empty};
+use cw_multi_test::{DistributionSudo, Executor};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgFundCommunityPool {
+    #[prost(message, repeated, tag = "1")]
+    pub amount: Vec<ProtoCoin>,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryCommunityPoolResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub pool: Vec<ProtoCoin>,
+}
+
+fn setup() -> (SdkApp, Addr) {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    init_staking(&mut app, &[(&owner_addr, 1000)], &[]);
+    (app, owner_addr)
+}
+
+fn community_pool(app: &SdkApp) -> Vec<Coin> {
+    app.read_module(|router, _, storage| router.distribution.community_pool(storage))
+        .unwrap()
+}
+
+#[test]
+fn community_pool_should_be_funded_and_spent() {
+    let (mut app, owner_addr) = setup();
+    let grantee_addr = app.api().addr_make("grantee");
+
+    app.execute(
+        owner_addr.clone(),
+        DistributionMsg::FundCommunityPool {
+            amount: coins(300, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(coins(300, DENOM), community_pool(&app));
+    assert_eq!(700, balance(&app, &owner_addr));
+    let distribution_addr = app.module_address("distribution").unwrap();
+    assert_eq!(300, balance(&app, &distribution_addr));
+
+    app.sudo(
+        DistributionSudo::CommunityPoolSpend {
+            recipient: grantee_addr.to_string(),
+            amount: coins(100, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(coins(200, DENOM), community_pool(&app));
+    assert_eq!(100, balance(&app, &grantee_addr));
+    assert_eq!(200, balance(&app, &distribution_addr));
+
+    let err = app
+        .sudo(
+            DistributionSudo::CommunityPoolSpend {
+                recipient: grantee_addr.to_string(),
+                amount: vec![coin(300, DENOM)],
+            }
+            .into(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        "community pool does not have sufficient coins to distribute",
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn community_pool_should_be_funded_with_proto_message_and_queried() {
+    let (mut app, owner_addr) = setup();
+    let msg = MsgFundCommunityPool {
+        amount: vec![ProtoCoin {
+            denom: DENOM.to_string(),
+            amount: "250".to_string(),
+        }],
+        depositor: owner_addr.to_string(),
+    };
+    let msg = CosmosMsg::<Empty>::Any(AnyMsg {
+        type_url: "/cosmos.distribution.v1beta1.MsgFundCommunityPool".to_string(),
+        value: msg.encode_to_vec().into(),
+    });
+
+    // only the depositor can fund the pool with own coins
+    let err = app
+        .execute(app.api().addr_make("thief"), msg.clone())
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .starts_with("depositor address"));
+
+    app.execute(owner_addr, msg).unwrap();
+    let response: QueryCommunityPoolResponse =
+        grpc_query(&app, "/cosmos.distribution.v1beta1.Query/CommunityPool", ());
+    assert_eq!(
+        vec![ProtoCoin {
+            denom: DENOM.to_string(),
+            amount: "250000000000000000000".to_string(),
+        }],
+        response.pool
+    );
+}
@@ -0,0 +1,217 @@
+use cosmwasm_std::{
+    from_json, to_json_binary, to_json_vec, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo,
+    Response, StdError, StdResult,
+};
+use cw_multi_test::{
+    next_block, App, ContractWrapper, ExecutionStage, Executor, FailurePolicy, Schedule,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExecMsg {
+    /// Increments the counter and records the height of the block.
+    Tick {},
+    /// Always fails.
+    Fail {},
+    /// Writes a lot of data.
+    Heavy {},
+}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    match msg {
+        ExecMsg::Tick {} => {
+            let mut ticks: Vec<(u64, Addr)> =
+                deps.storage.get(b"ticks").map_or(Ok(vec![]), from_json)?;
+            ticks.push((env.block.height, info.sender));
+            deps.storage.set(b"ticks", &to_json_vec(&ticks)?);
+            Ok(Response::default())
+        }
+        ExecMsg::Fail {} => Err(StdError::generic_err("scheduled failure")),
+        ExecMsg::Heavy {} => {
+            for i in 0..100_u8 {
+                deps.storage.set(&[i], &[i; 100]);
+            }
+            Ok(Response::default())
+        }
+    }
+}
+
+fn query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    let ticks: Vec<(u64, Addr)> = deps.storage.get(b"ticks").map_or(Ok(vec![]), from_json)?;
+    to_json_binary(&ticks)
+}
+
+fn setup() -> (App, Addr) {
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr, &Empty {}, &[], "ticker", None)
+        .unwrap();
+    (app, contract_addr)
+}
+
+fn ticks(app: &App, contract_addr: &Addr) -> Vec<u64> {
+    let ticks: Vec<(u64, Addr)> = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &Empty {})
+        .unwrap();
+    ticks.into_iter().map(|(height, _)| height).collect()
+}
+
+fn advance(app: &mut App, blocks: u64) {
+    for _ in 0..blocks {
+        app.update_block(next_block);
+    }
+}
+
+#[test]
+fn schedules_should_be_executed_periodically() {
+    let (mut app, contract_addr) = setup();
+    let start = app.block_info().height;
+    app.add_schedule(
+        Schedule::new("begin", 2)
+            .with_msg(contract_addr.clone(), &ExecMsg::Tick {})
+            .unwrap(),
+    )
+    .unwrap();
+    advance(&mut app, 5);
+    assert_eq!(vec![start + 2, start + 4], ticks(&app, &contract_addr));
+
+    // messages are sent by the cron module
+    let ticks: Vec<(u64, Addr)> = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!(app.module_address("cron").unwrap(), ticks[0].1);
+
+    // schedules can not be duplicated
+    assert_eq!(
+        "schedule begin already exists",
+        app.add_schedule(Schedule::new("begin", 1))
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn end_blocker_schedules_should_be_executed_in_finished_block() {
+    let (mut app, contract_addr) = setup();
+    let start = app.block_info().height;
+    app.add_schedule(
+        Schedule::new("end", 2)
+            .with_msg(contract_addr.clone(), &ExecMsg::Tick {})
+            .unwrap()
+            .with_stage(ExecutionStage::EndBlocker),
+    )
+    .unwrap();
+    advance(&mut app, 5);
+    assert_eq!(vec![start + 2, start + 4], ticks(&app, &contract_addr));
+    assert_eq!(start + 5, app.block_info().height);
+}
+
+#[test]
+fn schedules_should_be_cancelled_and_rescheduled() {
+    let (mut app, contract_addr) = setup();
+    let start = app.block_info().height;
+    app.add_schedule(
+        Schedule::new("ticker", 1)
+            .with_msg(contract_addr.clone(), &ExecMsg::Tick {})
+            .unwrap(),
+    )
+    .unwrap();
+    advance(&mut app, 2);
+    app.reschedule("ticker", 3).unwrap();
+    advance(&mut app, 3);
+    assert_eq!(
+        vec![start + 1, start + 2, start + 5],
+        ticks(&app, &contract_addr)
+    );
+
+    app.remove_schedule("ticker").unwrap();
+    advance(&mut app, 3);
+    assert_eq!(3, ticks(&app, &contract_addr).len());
+    assert!(app.schedules().unwrap().is_empty());
+    assert_eq!(
+        "schedule ticker does not exist",
+        app.remove_schedule("ticker").unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn failed_schedules_should_be_reverted_and_skipped() {
+    let (mut app, contract_addr) = setup();
+    app.add_schedule(
+        Schedule::new("failing", 1)
+            .with_msg(contract_addr.clone(), &ExecMsg::Tick {})
+            .unwrap()
+            .with_msg(contract_addr.clone(), &ExecMsg::Fail {})
+            .unwrap(),
+    )
+    .unwrap();
+    advance(&mut app, 3);
+
+    // all changes of the failed schedule are reverted
+    assert!(ticks(&app, &contract_addr).is_empty());
+    let schedule = app.schedule("failing").unwrap().unwrap();
+    assert_eq!(3, schedule.failures);
+    assert!(!schedule.halted);
+    assert_eq!(
+        Some("Generic error: scheduled failure".to_string()),
+        schedule.last_error
+    );
+}
+
+#[test]
+fn failed_schedules_should_be_halted_until_rescheduled() {
+    let (mut app, contract_addr) = setup();
+    app.add_schedule(
+        Schedule::new("halting", 1)
+            .with_msg(contract_addr.clone(), &ExecMsg::Fail {})
+            .unwrap()
+            .with_failure_policy(FailurePolicy::Halt),
+    )
+    .unwrap();
+    advance(&mut app, 3);
+    let schedule = app.schedule("halting").unwrap().unwrap();
+    assert_eq!(1, schedule.failures);
+    assert!(schedule.halted);
+
+    app.reschedule("halting", 1).unwrap();
+    assert!(!app.schedule("halting").unwrap().unwrap().halted);
+    advance(&mut app, 1);
+    assert_eq!(2, app.schedule("halting").unwrap().unwrap().failures);
+}
+
+#[test]
+fn schedules_exceeding_gas_limit_should_fail() {
+    let (mut app, contract_addr) = setup();
+    app.add_schedule(
+        Schedule::new("heavy", 1)
+            .with_msg(contract_addr.clone(), &ExecMsg::Heavy {})
+            .unwrap()
+            .with_msg(contract_addr.clone(), &ExecMsg::Tick {})
+            .unwrap()
+            .with_gas_limit(50_000),
+    )
+    .unwrap();
+    app.add_schedule(
+        Schedule::new("light", 1)
+            .with_msg(contract_addr.clone(), &ExecMsg::Tick {})
+            .unwrap()
+            .with_gas_limit(50_000),
+    )
+    .unwrap();
+    advance(&mut app, 1);
+
+    // only the light schedule was executed
+    assert_eq!(1, ticks(&app, &contract_addr).len());
+    let error = app.schedule("heavy").unwrap().unwrap().last_error.unwrap();
+    assert!(error.starts_with("out of gas: used "), "{}", error);
+    assert_eq!(None, app.schedule("light").unwrap().unwrap().last_error);
+}
@@ -0,0 +1,264 @@
+use super::common::staking::{delegate, grpc_query, init_staking, validator, DENOM};
+use cosmwasm_std::{coin, Addr, StakingMsg};
+use cw_multi_test::{App, Executor};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct PageRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub offset: u64,
+    #[prost(uint64, tag = "3")]
+    pub limit: u64,
+    #[prost(bool, tag = "4")]
+    pub count_total: bool,
+    #[prost(bool, tag = "5")]
+    pub reverse: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct PageResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub next_key: Vec<u8>,
+    #[prost(uint64, tag = "2")]
+    pub total: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Delegation {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(string, tag = "3")]
+    pub shares: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct DelegationResponse {
+    #[prost(message, optional, tag = "1")]
+    pub delegation: Option<Delegation>,
+    #[prost(message, optional, tag = "2")]
+    pub balance: Option<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDelegatorDelegationsRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_addr: String,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<PageRequest>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryValidatorDelegationsRequest {
+    #[prost(string, tag = "1")]
+    pub validator_addr: String,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<PageRequest>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDelegationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub delegation_responses: Vec<DelegationResponse>,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<PageResponse>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct UnbondingDelegation {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDelegatorUnbondingDelegationsRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_addr: String,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<PageRequest>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDelegatorUnbondingDelegationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub unbonding_responses: Vec<UnbondingDelegation>,
+    #[prost(message, optional, tag = "2")]
+    pub pagination: Option<PageResponse>,
+}
+
+/// Prepares the application with three validators and three delegators,
+/// every delegator delegating 10 tokens to every validator.
+fn setup() -> (App, [Addr; 3], [Addr; 3]) {
+    let mut app = App::default();
+    let delegators = ["delegator1", "delegator2", "delegator3"].map(|d| app.api().addr_make(d));
+    let validators = ["validator1", "validator2", "validator3"].map(|v| app.api().addr_make(v));
+    let balances = delegators.iter().map(|addr| (addr, 30)).collect::<Vec<_>>();
+    let validator_set = validators
+        .iter()
+        .map(|addr| validator(addr, 0))
+        .collect::<Vec<_>>();
+    init_staking(&mut app, &balances, &validator_set);
+    for delegator_addr in &delegators {
+        for validator_addr in &validators {
+            delegate(&mut app, delegator_addr, validator_addr, 10);
+        }
+    }
+    (app, delegators, validators)
+}
+
+fn sorted(addrs: &[Addr]) -> Vec<String> {
+    let mut addrs = addrs.iter().map(Addr::to_string).collect::<Vec<_>>();
+    addrs.sort();
+    addrs
+}
+
+#[test]
+fn delegator_delegations_should_be_paginated() {
+    let (app, [delegator_addr, ..], validators) = setup();
+    let page = |key: Vec<u8>| -> QueryDelegationsResponse {
+        grpc_query(
+            &app,
+            "/cosmos.staking.v1beta1.Query/DelegatorDelegations",
+            QueryDelegatorDelegationsRequest {
+                delegator_addr: delegator_addr.to_string(),
+                pagination: Some(PageRequest {
+                    key,
+                    limit: 2,
+                    count_total: true,
+                    ..Default::default()
+                }),
+            },
+        )
+    };
+
+    let first = page(vec![]);
+    assert_eq!(2, first.delegation_responses.len());
+    let pagination = first.pagination.unwrap();
+    assert_eq!(3, pagination.total);
+    let second = page(pagination.next_key);
+    assert_eq!(1, second.delegation_responses.len());
+    assert!(second.pagination.unwrap().next_key.is_empty());
+
+    let delegations = first
+        .delegation_responses
+        .into_iter()
+        .chain(second.delegation_responses)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sorted(&validators),
+        delegations
+            .iter()
+            .map(|d| d.delegation.clone().unwrap().validator_address)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some(DelegationResponse {
+            delegation: Some(Delegation {
+                delegator_address: delegator_addr.to_string(),
+                validator_address: delegations[0].delegation.clone().unwrap().validator_address,
+                shares: "10000000000000000000".to_string(),
+            }),
+            balance: Some(ProtoCoin {
+                denom: DENOM.to_string(),
+                amount: "10".to_string(),
+            }),
+        }),
+        delegations.first().cloned()
+    );
+}
+
+#[test]
+fn validator_delegations_should_be_paginated() {
+    let (app, delegators, [validator_addr, ..]) = setup();
+    let page = |pagination: PageRequest| -> QueryDelegationsResponse {
+        grpc_query(
+            &app,
+            "/cosmos.staking.v1beta1.Query/ValidatorDelegations",
+            QueryValidatorDelegationsRequest {
+                validator_addr: validator_addr.to_string(),
+                pagination: Some(pagination),
+            },
+        )
+    };
+    let delegator_addresses = |response: &QueryDelegationsResponse| {
+        response
+            .delegation_responses
+            .iter()
+            .map(|d| d.delegation.clone().unwrap().delegator_address)
+            .collect::<Vec<_>>()
+    };
+
+    let all = page(PageRequest::default());
+    assert_eq!(sorted(&delegators), delegator_addresses(&all));
+
+    // pages in reverse order, using the offset
+    let mut expected = sorted(&delegators);
+    expected.reverse();
+    let reversed = page(PageRequest {
+        offset: 1,
+        limit: 1,
+        reverse: true,
+        ..Default::default()
+    });
+    assert_eq!(expected[1..2].to_vec(), delegator_addresses(&reversed));
+    let last = page(PageRequest {
+        key: reversed.pagination.unwrap().next_key,
+        limit: 1,
+        reverse: true,
+        ..Default::default()
+    });
+    assert_eq!(expected[2..].to_vec(), delegator_addresses(&last));
+}
+
+#[test]
+fn delegator_unbonding_delegations_should_be_paginated() {
+    let (mut app, [delegator_addr, ..], validators) = setup();
+    for validator_addr in &validators {
+        app.execute(
+            delegator_addr.clone(),
+            StakingMsg::Undelegate {
+                validator: validator_addr.to_string(),
+                amount: coin(5, DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+    }
+
+    let response: QueryDelegatorUnbondingDelegationsResponse = grpc_query(
+        &app,
+        "/cosmos.staking.v1beta1.Query/DelegatorUnbondingDelegations",
+        QueryDelegatorUnbondingDelegationsRequest {
+            delegator_addr: delegator_addr.to_string(),
+            pagination: Some(PageRequest {
+                limit: 2,
+                ..Default::default()
+            }),
+        },
+    );
+    assert_eq!(
+        sorted(&validators)[..2].to_vec(),
+        response
+            .unbonding_responses
+            .iter()
+            .map(|u| u.validator_address.clone())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        sorted(&validators)[2].as_bytes(),
+        response.pagination.unwrap().next_key
+    );
+}
@@ -0,0 +1,79 @@
+use super::common::staking::{delegate, init_staking, validator, DENOM};
+use cosmwasm_std::{Addr, DecCoin, Decimal, DelegatorReward};
+use cw_multi_test::App;
+
+const YEAR: u64 = 60 * 60 * 24 * 365;
+
+/// Prepares the application with three validators without commission,
+/// the owner delegates 1000 tokens to the first and 500 tokens to the second one.
+fn setup() -> (App, Addr, [Addr; 3]) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let validators = ["validator1", "validator2", "validator3"].map(|v| app.api().addr_make(v));
+    let validator_set = validators
+        .iter()
+        .map(|addr| validator(addr, 0))
+        .collect::<Vec<_>>();
+    init_staking(&mut app, &[(&owner_addr, 1500)], &validator_set);
+    for (validator_addr, amount) in validators.iter().zip([1000, 500]) {
+        delegate(&mut app, &owner_addr, validator_addr, amount);
+    }
+    (app, owner_addr, validators)
+}
+
+#[test]
+fn total_rewards_should_be_queried() {
+    let (mut app, owner_addr, [validator1, validator2, _]) = setup();
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+
+    let response = app
+        .wrap()
+        .query_delegation_total_rewards(&owner_addr)
+        .unwrap();
+    let mut expected = vec![
+        DelegatorReward::new(
+            validator1.to_string(),
+            vec![DecCoin::new(Decimal::percent(10000), DENOM)],
+        ),
+        DelegatorReward::new(
+            validator2.to_string(),
+            vec![DecCoin::new(Decimal::percent(5000), DENOM)],
+        ),
+    ];
+    expected.sort_by(|a, b| a.validator_address.cmp(&b.validator_address));
+    assert_eq!(expected, response.rewards);
+    assert_eq!(
+        vec![DecCoin::new(Decimal::percent(15000), DENOM)],
+        response.total
+    );
+
+    let rewards = app
+        .wrap()
+        .query_delegation_rewards(&owner_addr, &validator2)
+        .unwrap();
+    assert_eq!(vec![DecCoin::new(Decimal::percent(5000), DENOM)], rewards);
+}
+
+#[test]
+fn delegator_validators_should_be_queried() {
+    let (app, owner_addr, [validator1, validator2, _]) = setup();
+    let mut expected = vec![validator1.to_string(), validator2.to_string()];
+    expected.sort();
+    assert_eq!(
+        expected,
+        app.wrap().query_delegator_validators(&owner_addr).unwrap()
+    );
+
+    let other_addr = app.api().addr_make("other");
+    assert!(app
+        .wrap()
+        .query_delegation_total_rewards(&other_addr)
+        .unwrap()
+        .rewards
+        .is_empty());
+    assert!(app
+        .wrap()
+        .query_delegator_validators(&other_addr)
+        .unwrap()
+        .is_empty());
+}
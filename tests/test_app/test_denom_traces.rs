@@ -0,0 +1,128 @@
+use super::common::staking::{grpc_query, try_grpc_query};
+use cosmwasm_std::Querier;
+use cw_multi_test::{AppBuilder, DenomTrace, StargateDenomTraces};
+use prost::Message;
+
+/// IBC denomination of ATOM transferred over `channel-0`, like on Osmosis.
+const IBC_ATOM: &str = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoDenomTrace {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(string, tag = "2")]
+    pub base_denom: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomTraceRequest {
+    #[prost(string, tag = "1")]
+    pub hash: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomTraceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub denom_trace: Option<ProtoDenomTrace>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomTracesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub denom_traces: Vec<ProtoDenomTrace>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomHashRequest {
+    #[prost(string, tag = "1")]
+    pub trace: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDenomHashResponse {
+    #[prost(string, tag = "1")]
+    pub hash: String,
+}
+
+fn denom_trace(querier: &dyn Querier, hash: &str) -> Result<ProtoDenomTrace, String> {
+    let data = QueryDenomTraceRequest {
+        hash: hash.to_string(),
+    };
+    try_grpc_query::<QueryDenomTraceResponse>(
+        querier,
+        "/ibc.applications.transfer.v1.Query/DenomTrace",
+        data,
+    )
+    .map(|response| response.denom_trace.unwrap())
+}
+
+fn denom_hash(querier: &dyn Querier, trace: &str) -> Result<String, String> {
+    let data = QueryDenomHashRequest {
+        trace: trace.to_string(),
+    };
+    try_grpc_query::<QueryDenomHashResponse>(
+        querier,
+        "/ibc.applications.transfer.v1.Query/DenomHash",
+        data,
+    )
+    .map(|response| response.hash)
+}
+
+#[test]
+fn denom_traces_should_be_registered_and_queried() {
+    let mut app = AppBuilder::default()
+        .with_stargate(StargateDenomTraces)
+        .build(|_, _, _| {});
+
+    // register traces
+    let atom = DenomTrace::new("transfer/channel-0", "uatom");
+    assert_eq!(IBC_ATOM, atom.ibc_denom());
+    assert_eq!(IBC_ATOM, app.register_denom_trace(&atom).unwrap());
+    let osmo = DenomTrace::new("transfer/channel-1/transfer/channel-2", "uosmo");
+    let ibc_osmo = app.register_denom_trace(&osmo).unwrap();
+    assert!(ibc_osmo.starts_with("ibc/"));
+
+    // resolve traces directly in the application
+    assert_eq!(Some(atom.clone()), app.denom_trace(IBC_ATOM).unwrap());
+    assert_eq!(Some(atom), app.denom_trace(&IBC_ATOM[4..]).unwrap());
+    assert_eq!(None, app.denom_trace("ibc/0000").unwrap());
+
+    // resolve traces using queries, with or without `ibc/` prefix
+    let trace = denom_trace(&app, IBC_ATOM).unwrap();
+    assert_eq!("transfer/channel-0", trace.path);
+    assert_eq!("uatom", trace.base_denom);
+    let trace = denom_trace(&app, &ibc_osmo[4..]).unwrap();
+    assert_eq!("transfer/channel-1/transfer/channel-2", trace.path);
+    assert_eq!("uosmo", trace.base_denom);
+    let err = denom_trace(&app, "ibc/0000").unwrap_err();
+    assert!(err.contains("denomination trace not found"), "{}", err);
+
+    // list all traces
+    let response: QueryDenomTracesResponse =
+        grpc_query(&app, "/ibc.applications.transfer.v1.Query/DenomTraces", ());
+    let traces = response.denom_traces;
+    assert_eq!(2, traces.len());
+
+    // compute the hash of the registered trace
+    assert_eq!(
+        &IBC_ATOM[4..],
+        denom_hash(&app, "transfer/channel-0/uatom").unwrap()
+    );
+    let err = denom_hash(&app, "transfer/channel-9/uatom").unwrap_err();
+    assert!(err.contains("denomination trace not found"), "{}", err);
+}
+
+#[test]
+fn invalid_denom_traces_should_be_rejected() {
+    let mut app = AppBuilder::default()
+        .with_stargate(StargateDenomTraces)
+        .build(|_, _, _| {});
+    for trace in [
+        DenomTrace::new("", "uatom"),
+        DenomTrace::new("transfer", "uatom"),
+        DenomTrace::new("transfer//channel-0", "uatom"),
+        DenomTrace::new("transfer/channel-0", ""),
+    ] {
+        app.register_denom_trace(&trace).unwrap_err();
+    }
+}
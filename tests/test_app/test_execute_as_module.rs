@@ -0,0 +1,68 @@
+use cosmwasm_std::{coin, BankMsg};
+use cw_multi_test::{no_init, AppBuilder, BankSudo, MockApiBech32};
+
+#[test]
+fn module_address_should_match_cosmos_sdk() {
+    let app = AppBuilder::default()
+        .with_api(MockApiBech32::new("cosmos"))
+        .build(no_init);
+    assert_eq!(
+        "cosmos1jv65s3grqf6v6jl3dp4t6c9t9rk99cd88lyufl",
+        app.module_address("distribution").unwrap().as_str()
+    );
+}
+
+#[test]
+fn messages_should_be_sent_by_module_account() {
+    let mut app = AppBuilder::default().build(no_init);
+    let module_addr = app.module_address("distribution").unwrap();
+    let recipient_addr = app.api().addr_make("recipient");
+    app.sudo(
+        BankSudo::Mint {
+            to_address: module_addr.to_string(),
+            amount: vec![coin(100, "uatom")],
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // emulate the community pool spend
+    let responses = app
+        .execute_as_module(
+            "distribution",
+            vec![BankMsg::Send {
+                to_address: recipient_addr.to_string(),
+                amount: vec![coin(40, "uatom")],
+            }
+            .into()],
+        )
+        .unwrap();
+    assert_eq!(1, responses.len());
+    let transfer = responses[0]
+        .events
+        .iter()
+        .find(|event| event.ty == "transfer")
+        .unwrap();
+    assert!(transfer
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "sender" && attr.value == module_addr.as_str()));
+
+    let query = app.wrap();
+    assert_eq!(
+        40,
+        query
+            .query_balance(recipient_addr, "uatom")
+            .unwrap()
+            .amount
+            .u128()
+    );
+    assert_eq!(
+        60,
+        query
+            .query_balance(module_addr, "uatom")
+            .unwrap()
+            .amount
+            .u128()
+    );
+}
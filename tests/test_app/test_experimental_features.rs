@@ -0,0 +1,71 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+};
+use cw_multi_test::{App, ContractWrapper, Executor, ExperimentalFeature};
+use cw_storage_plus::Item;
+
+const COUNTER: Item<u64> = Item::new("counter");
+
+fn instantiate(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> Result<Response, StdError> {
+    COUNTER.save(deps.storage, &0)?;
+    Ok(Response::default())
+}
+
+/// Increments the counter and reports the value returned by the query sent to itself.
+fn execute(deps: DepsMut, env: Env, _: MessageInfo, _: Empty) -> Result<Response, StdError> {
+    COUNTER.update(deps.storage, |counter| -> Result<_, StdError> {
+        Ok(counter + 1)
+    })?;
+    let queried: u64 = deps
+        .querier
+        .query_wasm_smart(env.contract.address, &Empty {})?;
+    Ok(Response::new().add_attribute("queried", queried.to_string()))
+}
+
+fn query(deps: Deps, _: Env, _: Empty) -> Result<Binary, StdError> {
+    to_json_binary(&COUNTER.load(deps.storage)?)
+}
+
+fn queried_counter(app: &mut App) -> String {
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    let response = app
+        .execute_contract(owner_addr, contract_addr, &Empty {}, &[])
+        .unwrap();
+    response
+        .custom_attrs(1)
+        .iter()
+        .find(|attr| attr.key == "queried")
+        .unwrap()
+        .value
+        .clone()
+}
+
+#[test]
+fn experimental_features_should_be_disabled_by_default() {
+    let mut app = App::default();
+    assert!(!app.is_experimental_feature_enabled(ExperimentalFeature::QueryUncommittedState));
+    // the query observes the state from before the call
+    assert_eq!("0", queried_counter(&mut app));
+}
+
+#[test]
+fn queries_should_observe_uncommitted_state_when_enabled() {
+    let mut app = App::default();
+    app.enable_experimental_feature(ExperimentalFeature::QueryUncommittedState)
+        .unwrap();
+    assert!(app.is_experimental_feature_enabled(ExperimentalFeature::QueryUncommittedState));
+    assert_eq!("1", queried_counter(&mut app));
+
+    // features are set per application
+    assert!(
+        !App::default().is_experimental_feature_enabled(ExperimentalFeature::QueryUncommittedState)
+    );
+
+    app.disable_experimental_feature(ExperimentalFeature::QueryUncommittedState)
+        .unwrap();
+    assert!(!app.is_experimental_feature_enabled(ExperimentalFeature::QueryUncommittedState));
+}
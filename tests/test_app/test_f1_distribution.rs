@@ -0,0 +1,136 @@
+use super::common::staking::{delegate, init_staking, validator, DENOM};
+use cosmwasm_std::{coin, Addr, Decimal256, DistributionMsg, StakingMsg, Uint128};
+use cw_multi_test::{App, Executor, RewardAccrual, RewardDistribution, StakingSudo};
+use std::str::FromStr;
+
+/// Prepares the application with single validator without commission provisioning
+/// 10 tokens in every block, with the first delegator delegating 1 token
+/// and the second one delegating 2 tokens.
+fn setup(distribution: RewardDistribution) -> (App, Addr, Addr, Addr) {
+    let mut app = App::default();
+    let first_addr = app.api().addr_make("first");
+    let second_addr = app.api().addr_make("second");
+    let validator_addr = app.api().addr_make("validator");
+    app.init_modules(|router, _, storage| {
+        router
+            .staking
+            .setup_reward_distribution(storage, distribution)
+    })
+    .unwrap();
+    init_staking(
+        &mut app,
+        &[(&first_addr, 2), (&second_addr, 2)],
+        &[validator(&validator_addr, 0)],
+    );
+    app.sudo(
+        StakingSudo::SetRewardAccrual {
+            validator: validator_addr.to_string(),
+            accrual: Some(RewardAccrual::PerBlock(Uint128::new(10))),
+        }
+        .into(),
+    )
+    .unwrap();
+    delegate(&mut app, &first_addr, &validator_addr, 1);
+    delegate(&mut app, &second_addr, &validator_addr, 2);
+    (app, first_addr, second_addr, validator_addr)
+}
+
+/// Withdraws the first delegator's rewards in every one of four blocks,
+/// returns the withdrawn rewards of the first delegator and the rewards of the second one.
+fn withdraw_in_every_block(distribution: RewardDistribution) -> (u128, u128) {
+    let (mut app, first_addr, second_addr, validator_addr) = setup(distribution);
+    for _ in 0..4 {
+        app.update_block(|block| block.height += 1);
+        app.execute(
+            first_addr.clone(),
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: validator_addr.to_string(),
+            }
+            .into(),
+        )
+        .unwrap();
+    }
+    let withdrawn = app.wrap().query_balance(&first_addr, DENOM).unwrap().amount;
+    let rewards = app
+        .wrap()
+        .query_delegation(&second_addr, &validator_addr)
+        .unwrap()
+        .unwrap()
+        .accumulated_rewards;
+    (withdrawn.u128() - 1, rewards[0].amount.u128())
+}
+
+#[test]
+fn f1_distribution_should_truncate_withdrawn_rewards() {
+    // the remainder of 10/3 tokens per block is kept by the delegation
+    assert_eq!(
+        (13, 26),
+        withdraw_in_every_block(RewardDistribution::Linear)
+    );
+    // the remainder is not kept, like on chain, where it goes to the community pool
+    assert_eq!((12, 26), withdraw_in_every_block(RewardDistribution::F1));
+}
+
+#[test]
+fn f1_rewards_should_follow_stake_changes() {
+    let (mut app, first_addr, second_addr, validator_addr) = setup(RewardDistribution::F1);
+    app.update_block(|block| block.height += 1);
+    app.execute(
+        second_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(1, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.update_block(|block| block.height += 1);
+
+    // 10/3 + 10/2 and 20/3 + 10/2 tokens, truncated
+    let rewards = |delegator_addr: &Addr| {
+        app.wrap()
+            .query_delegation(delegator_addr, &validator_addr)
+            .unwrap()
+            .unwrap()
+            .accumulated_rewards[0]
+            .amount
+            .u128()
+    };
+    assert_eq!(8, rewards(&first_addr));
+    assert_eq!(11, rewards(&second_addr));
+
+    // validator's outstanding rewards include rewards of all periods
+    let outstanding = app
+        .read_module(|router, _, storage| {
+            router.distribution.validator_outstanding_rewards(
+                storage,
+                &app.block_info(),
+                &validator_addr,
+            )
+        })
+        .unwrap();
+    assert_eq!(
+        Decimal256::from_str("19.333333333333333333").unwrap(),
+        outstanding[0].amount
+    );
+}
+
+#[test]
+fn reward_distribution_should_be_set_before_validators_are_added() {
+    let (mut app, ..) = setup(RewardDistribution::Linear);
+    let err = app
+        .init_modules(|router, _, storage| {
+            router
+                .staking
+                .setup_reward_distribution(storage, RewardDistribution::F1)
+        })
+        .unwrap_err();
+    assert_eq!(
+        "reward distribution can be set only before any validator is added",
+        err.to_string()
+    );
+    let distribution = app
+        .read_module(|router, _, storage| router.staking.reward_distribution(storage))
+        .unwrap();
+    assert_eq!(RewardDistribution::Linear, distribution);
+}
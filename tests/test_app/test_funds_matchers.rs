@@ -0,0 +1,78 @@
+use cosmwasm_std::{coin, coins};
+use cw_multi_test::{coins_eq, App, BankSudo, Executor, FundsMatcher};
+
+#[test]
+fn funds_should_be_compared_after_normalization() {
+    coins_eq!(
+        vec![coin(5, "uosmo"), coin(0, "ujuno"), coin(100, "uatom")],
+        "100uatom,5uosmo"
+    );
+    coins_eq!(
+        vec![coin(60, "uatom"), coin(40, "uatom")],
+        coins(100, "uatom")
+    );
+    coins_eq!(Vec::new(), "");
+
+    assert!(FundsMatcher::at_least("100uosmo").matches(&coins(150, "uosmo")));
+    assert!(!FundsMatcher::at_least("100uosmo,1uatom").matches(&coins(150, "uosmo")));
+    assert!(FundsMatcher::at_most("100uosmo").matches(&coins(100, "uosmo")));
+    assert!(!FundsMatcher::at_most("100uosmo").matches(&[coin(1, "uosmo"), coin(1, "uatom")]));
+    assert!(FundsMatcher::none().matches(&coins(0, "uosmo")));
+}
+
+#[test]
+#[should_panic(expected = "expected funds exactly 100uatom, but found 5uosmo")]
+fn different_funds_should_panic() {
+    coins_eq!(coins(5, "uosmo"), "100uatom");
+}
+
+#[test]
+fn balances_and_transfers_should_match() {
+    let mut app = App::default();
+    let sender_addr = app.api().addr_make("sender");
+    let recipient_addr = app.api().addr_make("recipient");
+    app.sudo(
+        BankSudo::Mint {
+            to_address: sender_addr.to_string(),
+            amount: vec![coin(100, "uatom"), coin(500, "uosmo")],
+        }
+        .into(),
+    )
+    .unwrap();
+
+    let response = app
+        .send_tokens(
+            sender_addr.clone(),
+            recipient_addr.clone(),
+            &[coin(10, "uatom"), coin(200, "uosmo")],
+        )
+        .unwrap();
+    coins_eq!(
+        response.transferred_to(recipient_addr.as_str()),
+        "10uatom,200uosmo"
+    );
+    coins_eq!(
+        response.transferred_from(sender_addr.as_str()),
+        "10uatom,200uosmo"
+    );
+    FundsMatcher::none().assert_matches(&response.transferred_to(sender_addr.as_str()));
+
+    app.assert_balances(&sender_addr, &FundsMatcher::exactly("90uatom,300uosmo"));
+    app.assert_balances(&recipient_addr, &FundsMatcher::at_least("100uosmo"));
+}
+
+#[test]
+#[should_panic(expected = "but found 100uatom")]
+fn unmatched_balances_should_panic() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    app.sudo(
+        BankSudo::Mint {
+            to_address: owner_addr.to_string(),
+            amount: coins(100, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.assert_balances(&owner_addr, &FundsMatcher::at_least("101uatom"));
+}
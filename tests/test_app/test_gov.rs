@@ -0,0 +1,1326 @@
+use super::common::staking::{balance, grpc_query, DENOM};
+use anyhow::bail;
+use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
+use cosmwasm_std::{
+    coin, coins, to_json_binary, to_json_vec, Addr, AnyMsg, Api, Binary, BlockInfo, CosmosMsg,
+    CustomMsg, CustomQuery, Decimal, Deps, DepsMut, Empty, Env, GovMsg, MessageInfo, Response,
+    StakingMsg, StdError, StdResult, Storage, Uint128, Validator, VoteOption, WeightedVoteOption,
+};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{
+    next_block, App, AppBuilder, AppResponse, BankKeeper, BankSudo, ContractWrapper, CosmosRouter,
+    CosmosSdkStargate, DistributionKeeper, Executor, FailingModule, GovKeeper, GovParams, GovSudo,
+    IbcFailingModule, ParamChange, ProposalStatus, StakeKeeper, StakingInfo, Stargate, TallyResult,
+    WasmKeeper,
+};
+use prost::Message;
+use serde::de::DeserializeOwned;
+
+const VOTING_PERIOD: u64 = 60;
+
+const EXPEDITED_VOTING_PERIOD: u64 = 30;
+
+const MIN_DEPOSIT: u128 = 10;
+
+const MAX_DEPOSIT_PERIOD: u64 = 100;
+
+type GovApp = App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovKeeper,
+    AuthorityModule,
+>;
+
+const MSG_SET_VALUE: &str = "/test.MsgSetValue";
+
+const VALUE_KEY: &[u8] = b"authority_value";
+
+/// Custom module accepting `MsgSetValue` signed by its authority, the governance module.
+/// Cosmos SDK messages are executed by [CosmosSdkStargate].
+struct AuthorityModule {
+    authority: Addr,
+}
+
+impl Stargate for AuthorityModule {
+    fn execute_any<ExecC, QueryC>(
+        &self,
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: AnyMsg,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        if msg.type_url.starts_with("/cosmos.") {
+            return CosmosSdkStargate.execute_any(api, storage, router, block, sender, msg);
+        }
+        if msg.type_url != MSG_SET_VALUE {
+            bail!("unknown message: {}", msg.type_url);
+        }
+        if sender != self.authority {
+            bail!("unauthorized: {}", sender);
+        }
+        storage.set(VALUE_KEY, msg.value.as_slice());
+        Ok(AppResponse::default())
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Coin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgSubmitProposal {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<Any>,
+    #[prost(message, repeated, tag = "2")]
+    pub initial_deposit: Vec<Coin>,
+    #[prost(string, tag = "3")]
+    pub proposer: String,
+    #[prost(string, tag = "5")]
+    pub title: String,
+    #[prost(string, tag = "6")]
+    pub summary: String,
+    #[prost(bool, tag = "7")]
+    pub expedited: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgDeposit {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub depositor: String,
+    #[prost(message, repeated, tag = "3")]
+    pub amount: Vec<Coin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgSudoContract {
+    #[prost(string, tag = "1")]
+    pub authority: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub msg: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgExecuteContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub msg: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgMigrateContract {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub contract: String,
+    #[prost(uint64, tag = "3")]
+    pub code_id: u64,
+    #[prost(bytes = "vec", tag = "4")]
+    pub msg: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Duration {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Params {
+    #[prost(message, repeated, tag = "1")]
+    pub min_deposit: Vec<Coin>,
+    #[prost(message, optional, tag = "2")]
+    pub max_deposit_period: Option<Duration>,
+    #[prost(message, optional, tag = "3")]
+    pub voting_period: Option<Duration>,
+    #[prost(string, tag = "4")]
+    pub quorum: String,
+    #[prost(string, tag = "5")]
+    pub threshold: String,
+    #[prost(string, tag = "6")]
+    pub veto_threshold: String,
+    #[prost(string, tag = "8")]
+    pub proposal_cancel_ratio: String,
+    #[prost(message, optional, tag = "10")]
+    pub expedited_voting_period: Option<Duration>,
+    #[prost(string, tag = "11")]
+    pub expedited_threshold: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgUpdateGovParams {
+    #[prost(string, tag = "1")]
+    pub authority: String,
+    #[prost(message, optional, tag = "2")]
+    pub params: Option<Params>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct StakingParams {
+    #[prost(message, optional, tag = "1")]
+    pub unbonding_time: Option<Duration>,
+    #[prost(uint32, tag = "2")]
+    pub max_validators: u32,
+    #[prost(uint32, tag = "3")]
+    pub max_entries: u32,
+    #[prost(uint32, tag = "4")]
+    pub historical_entries: u32,
+    #[prost(string, tag = "5")]
+    pub bond_denom: String,
+    #[prost(string, tag = "6")]
+    pub min_commission_rate: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgUpdateStakingParams {
+    #[prost(string, tag = "1")]
+    pub authority: String,
+    #[prost(message, optional, tag = "2")]
+    pub params: Option<StakingParams>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct BankParams {
+    #[prost(bool, tag = "2")]
+    pub default_send_enabled: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgUpdateBankParams {
+    #[prost(string, tag = "1")]
+    pub authority: String,
+    #[prost(message, optional, tag = "2")]
+    pub params: Option<BankParams>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryParamsRequest {
+    #[prost(string, tag = "1")]
+    pub params_type: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryParamsResponse {
+    #[prost(message, optional, tag = "4")]
+    pub params: Option<Params>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoTallyResult {
+    #[prost(string, tag = "1")]
+    pub yes_count: String,
+    #[prost(string, tag = "2")]
+    pub abstain_count: String,
+    #[prost(string, tag = "3")]
+    pub no_count: String,
+    #[prost(string, tag = "4")]
+    pub no_with_veto_count: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoProposal {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(int32, tag = "3")]
+    pub status: i32,
+    #[prost(message, optional, tag = "4")]
+    pub final_tally_result: Option<ProtoTallyResult>,
+    #[prost(message, repeated, tag = "7")]
+    pub total_deposit: Vec<Coin>,
+    #[prost(string, tag = "11")]
+    pub title: String,
+    #[prost(string, tag = "13")]
+    pub proposer: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoWeightedVoteOption {
+    #[prost(int32, tag = "1")]
+    pub option: i32,
+    #[prost(string, tag = "2")]
+    pub weight: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoVote {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub voter: String,
+    #[prost(message, repeated, tag = "4")]
+    pub options: Vec<ProtoWeightedVoteOption>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryProposalRequest {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryProposalResponse {
+    #[prost(message, optional, tag = "1")]
+    pub proposal: Option<ProtoProposal>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryProposalsRequest {
+    #[prost(int32, tag = "1")]
+    pub proposal_status: i32,
+    #[prost(string, tag = "2")]
+    pub voter: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryProposalsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub proposals: Vec<ProtoProposal>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryVotesRequest {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryVotesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub votes: Vec<ProtoVote>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryTallyResultResponse {
+    #[prost(message, optional, tag = "1")]
+    pub tally: Option<ProtoTallyResult>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgCancelProposal {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+    #[prost(string, tag = "2")]
+    pub proposer: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgSubmitProposalResponse {
+    #[prost(uint64, tag = "1")]
+    pub proposal_id: u64,
+}
+
+/// Prepares the application with the first validator with 60 tokens delegated
+/// by the first delegator and the second validator with 40 tokens delegated by the second one.
+fn setup() -> (GovApp, [Addr; 2], [Addr; 2]) {
+    setup_with_params(GovParams {
+        voting_period: VOTING_PERIOD,
+        expedited_voting_period: EXPEDITED_VOTING_PERIOD,
+        ..Default::default()
+    })
+}
+
+/// Prepares the application like [setup], requiring the minimum deposit for proposals.
+fn setup_with_deposits() -> (GovApp, [Addr; 2], [Addr; 2]) {
+    setup_with_params(GovParams {
+        min_deposit: coins(MIN_DEPOSIT, DENOM),
+        max_deposit_period: MAX_DEPOSIT_PERIOD,
+        voting_period: VOTING_PERIOD,
+        expedited_voting_period: EXPEDITED_VOTING_PERIOD,
+        ..Default::default()
+    })
+}
+
+fn setup_with_params(params: GovParams) -> (GovApp, [Addr; 2], [Addr; 2]) {
+    let api = MockApi::default();
+    let delegators = ["delegator1", "delegator2"].map(|d| api.addr_make(d));
+    let validators = ["validator1", "validator2"].map(|v| api.addr_make(v));
+    // the application uses the same api, so the governance module has the same address
+    let authority = App::default().module_address("gov").unwrap();
+    let mut app = AppBuilder::default()
+        .with_gov(GovKeeper::new())
+        .with_stargate(AuthorityModule { authority })
+        .build(|router, api, storage| {
+            router.gov.setup(storage, params).unwrap();
+            for delegator_addr in &delegators {
+                router
+                    .bank
+                    .init_balance(storage, delegator_addr, coins(100, DENOM))
+                    .unwrap();
+            }
+            let block = mock_env().block;
+            for validator_addr in &validators {
+                router
+                    .staking
+                    .add_validator(
+                        api,
+                        storage,
+                        &block,
+                        Validator::new(
+                            validator_addr.to_string(),
+                            Decimal::zero(),
+                            Decimal::percent(100),
+                            Decimal::percent(1),
+                        ),
+                    )
+                    .unwrap();
+            }
+        });
+    for (delegator_addr, validator_addr, amount) in [
+        (&delegators[0], &validators[0], 60),
+        (&delegators[1], &validators[1], 40),
+    ] {
+        app.execute(
+            delegator_addr.clone(),
+            StakingMsg::Delegate {
+                validator: validator_addr.to_string(),
+                amount: coin(amount, DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+    }
+    (app, delegators, validators)
+}
+
+fn submit_proposal(app: &mut GovApp, proposer: &Addr) -> u64 {
+    submit(app, proposer, false)
+}
+
+fn submit(app: &mut GovApp, proposer: &Addr, expedited: bool) -> u64 {
+    submit_with_deposit(app, proposer, expedited, 0)
+}
+
+fn submit_with_deposit(app: &mut GovApp, proposer: &Addr, expedited: bool, deposit: u128) -> u64 {
+    submit_with_messages(app, proposer, expedited, deposit, vec![]).unwrap()
+}
+
+fn submit_with_messages(
+    app: &mut GovApp,
+    proposer: &Addr,
+    expedited: bool,
+    deposit: u128,
+    messages: Vec<Any>,
+) -> AnyResult<u64> {
+    let msg = MsgSubmitProposal {
+        messages,
+        initial_deposit: proto_coins(deposit),
+        proposer: proposer.to_string(),
+        title: "title".to_string(),
+        summary: "summary".to_string(),
+        expedited,
+    };
+    let res = app.execute(
+        proposer.clone(),
+        CosmosMsg::Any(AnyMsg {
+            type_url: "/cosmos.gov.v1.MsgSubmitProposal".to_string(),
+            value: msg.encode_to_vec().into(),
+        }),
+    )?;
+    Ok(MsgSubmitProposalResponse::decode(res.data.unwrap().as_slice())?.proposal_id)
+}
+
+fn deposit(app: &mut GovApp, depositor: &Addr, proposal_id: u64, amount: u128) {
+    let msg = MsgDeposit {
+        proposal_id,
+        depositor: depositor.to_string(),
+        amount: proto_coins(amount),
+    };
+    app.execute(
+        depositor.clone(),
+        CosmosMsg::Any(AnyMsg {
+            type_url: "/cosmos.gov.v1.MsgDeposit".to_string(),
+            value: msg.encode_to_vec().into(),
+        }),
+    )
+    .unwrap();
+}
+
+fn cancel(app: &mut GovApp, proposer: &Addr, proposal_id: u64) -> AnyResult<AppResponse> {
+    let msg = MsgCancelProposal {
+        proposal_id,
+        proposer: proposer.to_string(),
+    };
+    app.execute(
+        proposer.clone(),
+        CosmosMsg::Any(AnyMsg {
+            type_url: "/cosmos.gov.v1.MsgCancelProposal".to_string(),
+            value: msg.encode_to_vec().into(),
+        }),
+    )
+}
+
+fn proto_coins(amount: u128) -> Vec<Coin> {
+    if amount == 0 {
+        return vec![];
+    }
+    vec![Coin {
+        denom: DENOM.to_string(),
+        amount: amount.to_string(),
+    }]
+}
+
+fn vote(
+    app: &mut GovApp,
+    voter: &Addr,
+    proposal_id: u64,
+    options: &[(VoteOption, u64)],
+) -> AnyResult<AppResponse> {
+    let options = options
+        .iter()
+        .map(|(option, percent)| WeightedVoteOption {
+            option: option.clone(),
+            weight: Decimal::percent(*percent),
+        })
+        .collect();
+    app.execute(
+        voter.clone(),
+        GovMsg::VoteWeighted {
+            proposal_id,
+            options,
+        }
+        .into(),
+    )
+}
+
+/// Moves to the block after the voting period, votes are tallied at the end of the first block
+/// with the time after the end of the voting period.
+fn end_voting_period(app: &mut GovApp) {
+    pass_time(app, VOTING_PERIOD);
+}
+
+fn pass_time(app: &mut GovApp, seconds: u64) {
+    app.update_block(|block| {
+        block.time = block.time.plus_seconds(seconds);
+        block.height += 1;
+    });
+    app.update_block(next_block);
+}
+
+fn supply(app: &GovApp) -> u128 {
+    app.wrap().query_supply(DENOM).unwrap().amount.u128()
+}
+
+fn tally(yes: u128, abstain: u128, no: u128, no_with_veto: u128) -> TallyResult {
+    TallyResult {
+        yes: Uint128::new(yes),
+        abstain: Uint128::new(abstain),
+        no: Uint128::new(no),
+        no_with_veto: Uint128::new(no_with_veto),
+    }
+}
+
+#[test]
+fn weighted_votes_should_be_tallied() {
+    let (mut app, [first, second], _) = setup();
+    let proposal_id = submit_proposal(&mut app, &first);
+    assert_eq!(1, proposal_id);
+
+    let res = vote(
+        &mut app,
+        &first,
+        proposal_id,
+        &[(VoteOption::Yes, 70), (VoteOption::No, 30)],
+    )
+    .unwrap();
+    assert!(res.events.iter().any(|e| e.ty == "proposal_vote"));
+    vote(
+        &mut app,
+        &second,
+        proposal_id,
+        &[(VoteOption::Abstain, 50), (VoteOption::NoWithVeto, 50)],
+    )
+    .unwrap();
+    let stored = app
+        .read_module(|router, _, storage| router.gov.vote(storage, proposal_id, &first))
+        .unwrap()
+        .unwrap();
+    assert_eq!(2, stored.len());
+
+    // 42 of 80 non-abstaining tokens vote yes, 20 of 80 vote with veto
+    end_voting_period(&mut app);
+    let proposal = app
+        .read_module(|router, _, storage| router.gov.proposal(storage, proposal_id))
+        .unwrap();
+    assert_eq!(ProposalStatus::Passed, proposal.status);
+    assert_eq!(Some(tally(42, 20, 18, 20)), proposal.final_tally_result);
+}
+
+#[test]
+fn validator_votes_should_be_inherited_by_delegators() {
+    let (mut app, [first, second], [validator, _]) = setup();
+
+    // the first delegator inherits the validator's veto
+    let vetoed = submit_proposal(&mut app, &first);
+    vote(
+        &mut app,
+        &validator,
+        vetoed,
+        &[(VoteOption::NoWithVeto, 100)],
+    )
+    .unwrap();
+    vote(&mut app, &second, vetoed, &[(VoteOption::Yes, 100)]).unwrap();
+
+    // the first delegator overrides the validator's vote
+    let passed = submit_proposal(&mut app, &first);
+    vote(
+        &mut app,
+        &validator,
+        passed,
+        &[(VoteOption::NoWithVeto, 100)],
+    )
+    .unwrap();
+    vote(&mut app, &first, passed, &[(VoteOption::Yes, 100)]).unwrap();
+    vote(&mut app, &second, passed, &[(VoteOption::Yes, 100)]).unwrap();
+
+    end_voting_period(&mut app);
+    let proposal = |id: u64| {
+        app.read_module(|router, _, storage| router.gov.proposal(storage, id))
+            .unwrap()
+    };
+    let vetoed = proposal(vetoed);
+    assert_eq!(ProposalStatus::Rejected, vetoed.status);
+    assert_eq!(Some(tally(40, 0, 0, 60)), vetoed.final_tally_result);
+    let passed = proposal(passed);
+    assert_eq!(ProposalStatus::Passed, passed.status);
+    assert_eq!(Some(tally(100, 0, 0, 0)), passed.final_tally_result);
+}
+
+#[test]
+fn invalid_votes_should_fail() {
+    let (mut app, [first, _], _) = setup();
+    let proposal_id = submit_proposal(&mut app, &first);
+
+    let err = vote(
+        &mut app,
+        &first,
+        proposal_id,
+        &[(VoteOption::Yes, 50), (VoteOption::No, 40)],
+    )
+    .unwrap_err();
+    assert_eq!(
+        "invalid vote option: total weight must be one",
+        err.root_cause().to_string()
+    );
+    let err = vote(
+        &mut app,
+        &first,
+        proposal_id,
+        &[(VoteOption::Yes, 50), (VoteOption::Yes, 50)],
+    )
+    .unwrap_err();
+    assert_eq!(
+        "invalid vote option: duplicated option",
+        err.root_cause().to_string()
+    );
+    let err = vote(&mut app, &first, 2, &[(VoteOption::Yes, 100)]).unwrap_err();
+    assert_eq!("proposal 2 doesn't exist", err.root_cause().to_string());
+
+    // votes are not accepted after the voting period, without votes the quorum is not reached
+    end_voting_period(&mut app);
+    let err = vote(&mut app, &first, proposal_id, &[(VoteOption::Yes, 100)]).unwrap_err();
+    assert_eq!("inactive proposal 1", err.root_cause().to_string());
+    let proposal = app
+        .read_module(|router, _, storage| router.gov.proposal(storage, proposal_id))
+        .unwrap();
+    assert_eq!(ProposalStatus::Rejected, proposal.status);
+}
+
+#[test]
+fn expedited_proposals_should_fall_back_to_regular_track() {
+    let (mut app, [first, second], _) = setup();
+    let passed = submit(&mut app, &first, true);
+    let regular = submit(&mut app, &first, true);
+    let submit_time = app.block_info().time;
+    for (voter, option) in [(&first, VoteOption::Yes), (&second, VoteOption::Yes)] {
+        vote(&mut app, voter, passed, &[(option, 100)]).unwrap();
+    }
+    // 60% of yes votes is below the expedited threshold, but above the regular one
+    for (voter, option) in [(&first, VoteOption::Yes), (&second, VoteOption::No)] {
+        vote(&mut app, voter, regular, &[(option, 100)]).unwrap();
+    }
+
+    pass_time(&mut app, EXPEDITED_VOTING_PERIOD);
+    let proposal = |app: &GovApp, id: u64| {
+        app.read_module(|router, _, storage| router.gov.proposal(storage, id))
+            .unwrap()
+    };
+    assert_eq!(ProposalStatus::Passed, proposal(&app, passed).status);
+    let converted = proposal(&app, regular);
+    assert_eq!(ProposalStatus::VotingPeriod, converted.status);
+    assert!(!converted.expedited);
+    assert_eq!(
+        Some(submit_time.plus_seconds(VOTING_PERIOD)),
+        converted.voting_end_time
+    );
+
+    // votes are kept for the regular voting period
+    pass_time(&mut app, VOTING_PERIOD - EXPEDITED_VOTING_PERIOD);
+    let converted = proposal(&app, regular);
+    assert_eq!(ProposalStatus::Passed, converted.status);
+    assert_eq!(Some(tally(60, 0, 40, 0)), converted.final_tally_result);
+}
+
+#[test]
+fn deposits_should_be_refunded_when_proposal_passes() {
+    let (mut app, [first, second], _) = setup_with_deposits();
+    let proposal_id = submit_with_deposit(&mut app, &first, false, 4);
+    let proposal = |app: &GovApp| {
+        app.read_module(|router, _, storage| router.gov.proposal(storage, proposal_id))
+            .unwrap()
+    };
+    assert_eq!(ProposalStatus::DepositPeriod, proposal(&app).status);
+    let err = vote(&mut app, &first, proposal_id, &[(VoteOption::Yes, 100)]).unwrap_err();
+    assert_eq!("inactive proposal 1", err.root_cause().to_string());
+
+    // the voting period starts when deposits reach the minimum deposit
+    deposit(&mut app, &second, proposal_id, 6);
+    let proposal = proposal(&app);
+    assert_eq!(ProposalStatus::VotingPeriod, proposal.status);
+    assert_eq!(coins(MIN_DEPOSIT, DENOM), proposal.total_deposit);
+    assert_eq!(Some(app.block_info().time), proposal.voting_start_time);
+    // deposits are ordered by depositor addresses, sort them by amount for comparison
+    let mut deposits = app
+        .read_module(|router, _, storage| router.gov.deposits(storage, proposal_id))
+        .unwrap();
+    deposits.sort_by_key(|(_, amount)| amount[0].amount);
+    assert_eq!(
+        vec![
+            (first.clone(), coins(4, DENOM)),
+            (second.clone(), coins(6, DENOM))
+        ],
+        deposits
+    );
+    assert_eq!(
+        MIN_DEPOSIT,
+        balance(&app, &app.module_address("gov").unwrap())
+    );
+
+    vote(&mut app, &first, proposal_id, &[(VoteOption::Yes, 100)]).unwrap();
+    end_voting_period(&mut app);
+    assert_eq!(40, balance(&app, &first));
+    assert_eq!(60, balance(&app, &second));
+    let deposit = app
+        .read_module(|router, _, storage| router.gov.deposit(storage, proposal_id, &first))
+        .unwrap();
+    assert_eq!(None, deposit);
+}
+
+#[test]
+fn deposits_should_be_burned_on_veto_or_without_quorum() {
+    let (mut app, [first, second], _) = setup_with_deposits();
+    let vetoed = submit_with_deposit(&mut app, &first, false, MIN_DEPOSIT);
+    let ignored = submit_with_deposit(&mut app, &second, false, MIN_DEPOSIT);
+    vote(&mut app, &first, vetoed, &[(VoteOption::NoWithVeto, 100)]).unwrap();
+    assert_eq!(200, supply(&app));
+
+    end_voting_period(&mut app);
+    for id in [vetoed, ignored] {
+        let proposal = app
+            .read_module(|router, _, storage| router.gov.proposal(storage, id))
+            .unwrap();
+        assert_eq!(ProposalStatus::Rejected, proposal.status);
+    }
+    assert_eq!(30, balance(&app, &first));
+    assert_eq!(50, balance(&app, &second));
+    assert_eq!(200 - 2 * MIN_DEPOSIT, supply(&app));
+}
+
+#[test]
+fn proposals_without_minimum_deposit_should_be_dropped() {
+    let (mut app, [first, _], _) = setup_with_deposits();
+    let proposal_id = submit_with_deposit(&mut app, &first, false, 5);
+    assert_eq!(35, balance(&app, &first));
+
+    // deposits are refunded when the deposit period ends
+    pass_time(&mut app, MAX_DEPOSIT_PERIOD);
+    assert_eq!(40, balance(&app, &first));
+    let err = app
+        .read_module(|router, _, storage| router.gov.proposal(storage, proposal_id))
+        .unwrap_err();
+    assert_eq!("proposal 1 doesn't exist", err.to_string());
+}
+
+#[test]
+fn deposits_should_be_refunded_when_transfers_are_disabled() {
+    let (mut app, [first, _], _) = setup_with_deposits();
+    let proposal_id = submit_with_deposit(&mut app, &first, false, 5);
+    assert_eq!(35, balance(&app, &first));
+    app.sudo(
+        BankSudo::SetSendEnabled {
+            denom: DENOM.to_string(),
+            enabled: false,
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // refunds are paid by the governance module, transfers from module accounts are not disabled
+    pass_time(&mut app, MAX_DEPOSIT_PERIOD);
+    assert_eq!(40, balance(&app, &first));
+    app.read_module(|router, _, storage| router.gov.proposal(storage, proposal_id))
+        .unwrap_err();
+}
+
+#[test]
+fn params_should_be_queried_and_updated() {
+    let (mut app, [first, second], _) = setup();
+    let query_params = |app: &GovApp| {
+        let response: QueryParamsResponse = grpc_query(
+            app,
+            "/cosmos.gov.v1.Query/Params",
+            QueryParamsRequest::default(),
+        );
+        response.params.unwrap()
+    };
+    assert_eq!(
+        Params {
+            min_deposit: vec![],
+            max_deposit_period: Some(Duration {
+                seconds: 172800,
+                nanos: 0,
+            }),
+            voting_period: Some(Duration {
+                seconds: VOTING_PERIOD as i64,
+                nanos: 0,
+            }),
+            quorum: "0.334000000000000000".to_string(),
+            threshold: "0.500000000000000000".to_string(),
+            veto_threshold: "0.334000000000000000".to_string(),
+            proposal_cancel_ratio: "0.500000000000000000".to_string(),
+            expedited_voting_period: Some(Duration {
+                seconds: EXPEDITED_VOTING_PERIOD as i64,
+                nanos: 0,
+            }),
+            expedited_threshold: "0.667000000000000000".to_string(),
+        },
+        query_params(&app)
+    );
+
+    // raising the threshold rejects proposals which would pass with default params
+    let params = GovParams {
+        threshold: Decimal::percent(65),
+        expedited_threshold: Decimal::percent(75),
+        ..app
+            .read_module(|router, _, storage| router.gov.params(storage))
+            .unwrap()
+    };
+    app.sudo(GovSudo::UpdateParams { params }.into()).unwrap();
+    assert_eq!("0.650000000000000000", query_params(&app).threshold);
+    let height = app.block_info().height;
+    assert_eq!(
+        vec![
+            ParamChange {
+                height,
+                module: "gov".to_string(),
+                param: "threshold".to_string(),
+                value: "0.65".to_string(),
+            },
+            ParamChange {
+                height,
+                module: "gov".to_string(),
+                param: "expedited_threshold".to_string(),
+                value: "0.75".to_string(),
+            },
+        ],
+        app.param_history().unwrap()
+    );
+    let proposal_id = submit_proposal(&mut app, &first);
+    vote(&mut app, &first, proposal_id, &[(VoteOption::Yes, 100)]).unwrap();
+    vote(&mut app, &second, proposal_id, &[(VoteOption::No, 100)]).unwrap();
+    end_voting_period(&mut app);
+    let proposal = app
+        .read_module(|router, _, storage| router.gov.proposal(storage, proposal_id))
+        .unwrap();
+    assert_eq!(ProposalStatus::Rejected, proposal.status);
+
+    let err = app
+        .sudo(
+            GovSudo::UpdateParams {
+                params: GovParams {
+                    voting_period: 0,
+                    ..Default::default()
+                },
+            }
+            .into(),
+        )
+        .unwrap_err();
+    assert_eq!("voting period must be positive", err.to_string());
+}
+
+/// Records messages sent to the contract by the governance module.
+fn record_call(deps: DepsMut, call: &str) -> StdResult<Response> {
+    let mut calls = deps.storage.get(b"calls").unwrap_or_default();
+    calls.extend_from_slice(format!("{};", call).as_bytes());
+    deps.storage.set(b"calls", &calls);
+    Ok(Response::new())
+}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn execute(deps: DepsMut, _: Env, _: MessageInfo, msg: String) -> StdResult<Response> {
+    record_call(deps, &msg)
+}
+
+fn query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    let calls = deps.storage.get(b"calls").unwrap_or_default();
+    to_json_binary(&String::from_utf8(calls).unwrap())
+}
+
+/// Fails for messages other than `sudo`, to check reverting proposal messages.
+fn sudo(deps: DepsMut, _: Env, msg: String) -> StdResult<Response> {
+    if msg != "sudo" {
+        return Err(StdError::generic_err("unsupported sudo"));
+    }
+    record_call(deps, &msg)
+}
+
+fn migrate(deps: DepsMut, _: Env, msg: String) -> StdResult<Response> {
+    record_call(deps, &msg)
+}
+
+fn wasm_proposal_messages(gov: &Addr, contract: &Addr, sudo_msg: &str, code_id: u64) -> Vec<Any> {
+    let encode = |msg: &str| to_json_vec(msg).unwrap();
+    vec![
+        Any {
+            type_url: "/cosmwasm.wasm.v1.MsgExecuteContract".to_string(),
+            value: MsgExecuteContract {
+                sender: gov.to_string(),
+                contract: contract.to_string(),
+                msg: encode("execute"),
+            }
+            .encode_to_vec(),
+        },
+        Any {
+            type_url: "/cosmwasm.wasm.v1.MsgSudoContract".to_string(),
+            value: MsgSudoContract {
+                authority: gov.to_string(),
+                contract: contract.to_string(),
+                msg: encode(sudo_msg),
+            }
+            .encode_to_vec(),
+        },
+        Any {
+            type_url: "/cosmwasm.wasm.v1.MsgMigrateContract".to_string(),
+            value: MsgMigrateContract {
+                sender: gov.to_string(),
+                contract: contract.to_string(),
+                code_id,
+                msg: encode("migrate"),
+            }
+            .encode_to_vec(),
+        },
+    ]
+}
+
+#[test]
+fn passed_proposals_should_execute_wasm_messages() {
+    let (mut app, [first, second], _) = setup();
+    let gov = app.module_address("gov").unwrap();
+    let contract = ContractWrapper::new(execute, instantiate, query)
+        .with_sudo(sudo)
+        .with_migrate(migrate);
+    let code_id = app.store_code(Box::new(contract));
+    let new_code_id = app.duplicate_code(code_id).unwrap();
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            first.clone(),
+            &Empty {},
+            &[],
+            "gov",
+            Some(gov.to_string()),
+        )
+        .unwrap();
+    let calls = |app: &GovApp| -> String {
+        app.wrap()
+            .query_wasm_smart(&contract_addr, &Empty {})
+            .unwrap()
+    };
+
+    // changes of all messages are reverted when any of them fails
+    let messages = wasm_proposal_messages(&gov, &contract_addr, "fail", new_code_id);
+    let failed = submit_with_messages(&mut app, &first, false, 0, messages).unwrap();
+    let messages = wasm_proposal_messages(&gov, &contract_addr, "sudo", new_code_id);
+    let passed = submit_with_messages(&mut app, &first, false, 0, messages).unwrap();
+    for id in [failed, passed] {
+        vote(&mut app, &first, id, &[(VoteOption::Yes, 100)]).unwrap();
+        vote(&mut app, &second, id, &[(VoteOption::Yes, 100)]).unwrap();
+    }
+    end_voting_period(&mut app);
+
+    let proposal = |id: u64| {
+        app.read_module(|router, _, storage| router.gov.proposal(storage, id))
+            .unwrap()
+    };
+    let failed = proposal(failed);
+    assert_eq!(ProposalStatus::Failed, failed.status);
+    assert_eq!(
+        Some("Generic error: unsupported sudo".to_string()),
+        failed.failed_reason
+    );
+    assert_eq!(ProposalStatus::Passed, proposal(passed).status);
+    assert_eq!("execute;sudo;migrate;", calls(&app));
+    assert_eq!(
+        new_code_id,
+        app.contract_data(&contract_addr).unwrap().code_id
+    );
+
+    // proposal messages must be signed by the governance module
+    let messages = wasm_proposal_messages(&first, &contract_addr, "sudo", new_code_id);
+    let err = submit_with_messages(&mut app, &first, false, 0, messages).unwrap_err();
+    assert_eq!(
+        "expected gov account as only signer for proposal message",
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn passed_proposals_should_update_module_params() {
+    let (mut app, [first, second], _) = setup();
+    let gov = app.module_address("gov").unwrap();
+    let duration = |seconds: u64| {
+        Some(Duration {
+            seconds: seconds as i64,
+            nanos: 0,
+        })
+    };
+    // all gov params must be set in the message, the zero veto threshold is invalid
+    let gov_params = Params {
+        min_deposit: vec![],
+        max_deposit_period: duration(MAX_DEPOSIT_PERIOD),
+        voting_period: duration(2 * VOTING_PERIOD),
+        quorum: "0.5".to_string(),
+        threshold: "0.6".to_string(),
+        veto_threshold: "0".to_string(),
+        proposal_cancel_ratio: "0.5".to_string(),
+        expedited_voting_period: duration(EXPEDITED_VOTING_PERIOD),
+        expedited_threshold: "0.7".to_string(),
+    };
+    let messages = vec![
+        Any {
+            type_url: "/cosmos.staking.v1beta1.MsgUpdateParams".to_string(),
+            value: MsgUpdateStakingParams {
+                authority: gov.to_string(),
+                params: Some(StakingParams {
+                    unbonding_time: duration(3600),
+                    max_validators: 10,
+                    max_entries: 7,
+                    historical_entries: 100,
+                    bond_denom: DENOM.to_string(),
+                    min_commission_rate: Decimal::percent(5).atomics().to_string(),
+                }),
+            }
+            .encode_to_vec(),
+        },
+        Any {
+            type_url: "/cosmos.bank.v1beta1.MsgUpdateParams".to_string(),
+            value: MsgUpdateBankParams {
+                authority: gov.to_string(),
+                params: Some(BankParams {
+                    default_send_enabled: false,
+                }),
+            }
+            .encode_to_vec(),
+        },
+    ];
+    let passed = submit_with_messages(&mut app, &first, false, 0, messages).unwrap();
+    let invalid = submit_with_messages(
+        &mut app,
+        &first,
+        false,
+        0,
+        vec![Any {
+            type_url: "/cosmos.gov.v1.MsgUpdateParams".to_string(),
+            value: MsgUpdateGovParams {
+                authority: gov.to_string(),
+                params: Some(gov_params),
+            }
+            .encode_to_vec(),
+        }],
+    )
+    .unwrap();
+    for id in [passed, invalid] {
+        vote(&mut app, &first, id, &[(VoteOption::Yes, 100)]).unwrap();
+        vote(&mut app, &second, id, &[(VoteOption::Yes, 100)]).unwrap();
+    }
+    end_voting_period(&mut app);
+
+    // the governance raises the unbonding time, keeping the interest rate
+    let staking_info = app
+        .read_module(|router, _, storage| router.staking.staking_info(storage))
+        .unwrap();
+    assert_eq!(
+        StakingInfo {
+            bonded_denom: DENOM.to_string(),
+            unbonding_time: 3600,
+            max_validators: 10,
+            max_entries: 7,
+            min_commission_rate: Decimal::percent(5),
+            historical_entries: 100,
+            ..Default::default()
+        },
+        staking_info
+    );
+    let bank_params = app
+        .read_module(|router, _, storage| router.bank.params(storage))
+        .unwrap();
+    assert!(!bank_params.default_send_enabled);
+
+    // invalid gov params fail the proposal, keeping current params
+    let proposal = app
+        .read_module(|router, _, storage| router.gov.proposal(storage, invalid))
+        .unwrap();
+    assert_eq!(ProposalStatus::Failed, proposal.status);
+    assert_eq!(
+        Some("veto threshold must be positive and at most one".to_string()),
+        proposal.failed_reason
+    );
+    let params = app
+        .read_module(|router, _, storage| router.gov.params(storage))
+        .unwrap();
+    assert_eq!(VOTING_PERIOD, params.voting_period);
+}
+
+#[test]
+fn cancelled_proposals_should_be_charged_part_of_deposits() {
+    let (mut app, [first, second], [validator, _]) = setup_with_deposits();
+    let proposal_id = submit_with_deposit(&mut app, &first, false, 5);
+    deposit(&mut app, &second, proposal_id, 15);
+    vote(&mut app, &first, proposal_id, &[(VoteOption::Yes, 100)]).unwrap();
+
+    let err = cancel(&mut app, &second, proposal_id).unwrap_err();
+    assert_eq!(
+        format!("invalid proposer {}", second),
+        err.root_cause().to_string()
+    );
+
+    // half of every deposit rounded down is burned, the rest is refunded
+    let res = cancel(&mut app, &first, proposal_id).unwrap();
+    assert!(res.events.iter().any(|e| e.ty == "cancel_proposal"));
+    assert_eq!(38, balance(&app, &first));
+    assert_eq!(53, balance(&app, &second));
+    assert_eq!(200 - 9, supply(&app));
+    let err = app
+        .read_module(|router, _, storage| router.gov.proposal(storage, proposal_id))
+        .unwrap_err();
+    assert_eq!("proposal 1 doesn't exist", err.to_string());
+    let vote = app
+        .read_module(|router, _, storage| router.gov.vote(storage, proposal_id, &first))
+        .unwrap();
+    assert_eq!(None, vote);
+
+    // charged deposits are sent to the destination when set
+    let params = GovParams {
+        proposal_cancel_ratio: Decimal::percent(20),
+        proposal_cancel_dest: Some(validator.clone()),
+        ..app
+            .read_module(|router, _, storage| router.gov.params(storage))
+            .unwrap()
+    };
+    app.sudo(GovSudo::UpdateParams { params }.into()).unwrap();
+    let proposal_id = submit_with_deposit(&mut app, &first, false, MIN_DEPOSIT);
+    cancel(&mut app, &first, proposal_id).unwrap();
+    assert_eq!(36, balance(&app, &first));
+    assert_eq!(2, balance(&app, &validator));
+
+    // proposals cannot be cancelled after the voting period
+    let proposal_id = submit_with_deposit(&mut app, &first, false, MIN_DEPOSIT);
+    end_voting_period(&mut app);
+    let err = cancel(&mut app, &first, proposal_id).unwrap_err();
+    assert_eq!(
+        "proposal should be in the deposit or voting period",
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn proposals_votes_and_tally_should_be_queried() {
+    let (mut app, [first, second], _) = setup_with_deposits();
+    let voting = submit_with_deposit(&mut app, &first, false, MIN_DEPOSIT);
+    let depositing = submit_with_deposit(&mut app, &second, false, 1);
+    vote(
+        &mut app,
+        &first,
+        voting,
+        &[(VoteOption::Yes, 75), (VoteOption::No, 25)],
+    )
+    .unwrap();
+
+    let response: QueryProposalResponse = grpc_query(
+        &app,
+        "/cosmos.gov.v1.Query/Proposal",
+        QueryProposalRequest {
+            proposal_id: depositing,
+        },
+    );
+    assert_eq!(
+        Some(ProtoProposal {
+            id: depositing,
+            status: 1,
+            final_tally_result: None,
+            total_deposit: proto_coins(1),
+            title: "title".to_string(),
+            proposer: second.to_string(),
+        }),
+        response.proposal
+    );
+    let proposals = |status: i32, voter: &str| -> Vec<u64> {
+        let response: QueryProposalsResponse = grpc_query(
+            &app,
+            "/cosmos.gov.v1.Query/Proposals",
+            QueryProposalsRequest {
+                proposal_status: status,
+                voter: voter.to_string(),
+            },
+        );
+        response.proposals.into_iter().map(|p| p.id).collect()
+    };
+    assert_eq!(vec![voting, depositing], proposals(0, ""));
+    assert_eq!(vec![voting], proposals(2, ""));
+    assert_eq!(vec![voting], proposals(0, first.as_str()));
+    assert!(proposals(0, second.as_str()).is_empty());
+
+    let response: QueryVotesResponse = grpc_query(
+        &app,
+        "/cosmos.gov.v1.Query/Votes",
+        QueryVotesRequest {
+            proposal_id: voting,
+        },
+    );
+    assert_eq!(
+        vec![ProtoVote {
+            proposal_id: voting,
+            voter: first.to_string(),
+            options: vec![
+                ProtoWeightedVoteOption {
+                    option: 1,
+                    weight: "0.750000000000000000".to_string(),
+                },
+                ProtoWeightedVoteOption {
+                    option: 3,
+                    weight: "0.250000000000000000".to_string(),
+                },
+            ],
+        }],
+        response.votes
+    );
+
+    // votes are counted before the end of the voting period
+    let live_tally = |app: &GovApp| -> ProtoTallyResult {
+        let response: QueryTallyResultResponse = grpc_query(
+            app,
+            "/cosmos.gov.v1.Query/TallyResult",
+            QueryProposalRequest {
+                proposal_id: voting,
+            },
+        );
+        response.tally.unwrap()
+    };
+    let expected = ProtoTallyResult {
+        yes_count: "45".to_string(),
+        abstain_count: "0".to_string(),
+        no_count: "15".to_string(),
+        no_with_veto_count: "0".to_string(),
+    };
+    assert_eq!(expected, live_tally(&app));
+    end_voting_period(&mut app);
+    assert_eq!(expected, live_tally(&app));
+    let proposals = app
+        .read_module(|router, _, storage| router.gov.proposals(storage))
+        .unwrap();
+    assert_eq!(ProposalStatus::Passed, proposals[0].status);
+    let votes = app
+        .read_module(|router, _, storage| router.gov.votes(storage, voting))
+        .unwrap();
+    assert!(votes.is_empty());
+}
+
+#[test]
+fn passed_proposals_should_execute_any_messages_as_authority() {
+    let (mut app, [first, second], _) = setup();
+    let set_value = |value: &str| AnyMsg {
+        type_url: MSG_SET_VALUE.to_string(),
+        value: Binary::from(value.as_bytes()),
+    };
+    let stored_value = |app: &GovApp| {
+        app.read_module(|_, _, storage| storage.get(VALUE_KEY))
+            .map(|value| String::from_utf8(value).unwrap())
+    };
+
+    // only the governance module is authorized to execute the message
+    let err = app
+        .execute(first.clone(), CosmosMsg::Any(set_value("direct")))
+        .unwrap_err();
+    assert_eq!(
+        format!("unauthorized: {}", first),
+        err.root_cause().to_string()
+    );
+
+    // messages not known to the governance module are accepted when submitted,
+    // but fail the proposal when rejected by the handler
+    let messages = |msg: AnyMsg| {
+        vec![Any {
+            type_url: msg.type_url,
+            value: msg.value.to_vec(),
+        }]
+    };
+    let unknown = AnyMsg {
+        type_url: "/test.MsgUnknown".to_string(),
+        value: Binary::default(),
+    };
+    let failed = submit_with_messages(&mut app, &first, false, 0, messages(unknown)).unwrap();
+    let passed =
+        submit_with_messages(&mut app, &first, false, 0, messages(set_value("gov"))).unwrap();
+    for id in [failed, passed] {
+        vote(&mut app, &first, id, &[(VoteOption::Yes, 100)]).unwrap();
+        vote(&mut app, &second, id, &[(VoteOption::Yes, 100)]).unwrap();
+    }
+    end_voting_period(&mut app);
+
+    let proposal = |id: u64| {
+        app.read_module(|router, _, storage| router.gov.proposal(storage, id))
+            .unwrap()
+    };
+    let failed = proposal(failed);
+    assert_eq!(ProposalStatus::Failed, failed.status);
+    assert_eq!(
+        Some("unknown message: /test.MsgUnknown".to_string()),
+        failed.failed_reason
+    );
+    assert_eq!(ProposalStatus::Passed, proposal(passed).status);
+    assert_eq!(Some("gov".to_string()), stored_value(&app));
+}
@@ -0,0 +1,73 @@
+use super::common::ibc::{
+    contract, execute_contract, ibc_app, instantiate_contract, state, ExecMsg,
+};
+use cosmwasm_std::IbcOrder;
+
+const VERSION: &str = "callbacks-1";
+
+#[test]
+fn callbacks_should_be_invoked_for_packets_with_callback_memo() {
+    let mut app = ibc_app();
+    let code_id = app.store_code(contract());
+    let (sender_addr, sender_port) = instantiate_contract(&mut app, code_id, "sender", VERSION);
+    let (receiver_addr, receiver_port) =
+        instantiate_contract(&mut app, code_id, "receiver", VERSION);
+    app.open_localhost_channel(&sender_port, &receiver_port, VERSION, IbcOrder::Unordered)
+        .unwrap();
+    let height = app.block_info().height;
+
+    // both callbacks are invoked after the packet is acknowledged
+    let msg = ExecMsg::Send {
+        text: "first".to_string(),
+        timeout_height: Some(height + 10),
+        src_callback: Some(sender_addr.to_string()),
+        dest_callback: Some(receiver_addr.to_string()),
+    };
+    execute_contract(&mut app, &sender_addr, &msg).unwrap();
+    let responses = app.relay_localhost_packets().unwrap();
+    assert!(responses[0]
+        .events
+        .iter()
+        .any(|event| event.ty == "ibc_source_callback"));
+    assert!(responses[0]
+        .events
+        .iter()
+        .any(|event| event.ty == "ibc_destination_callback"));
+    assert_eq!(vec!["ack first"], state(&app, &sender_addr).source_acks);
+    assert_eq!(
+        vec!["ack first"],
+        state(&app, &receiver_addr).destination_acks
+    );
+
+    // the source callback is not invoked for contracts other than the sender
+    let msg = ExecMsg::Send {
+        text: "second".to_string(),
+        timeout_height: Some(height + 10),
+        src_callback: Some(receiver_addr.to_string()),
+        dest_callback: None,
+    };
+    execute_contract(&mut app, &sender_addr, &msg).unwrap();
+    app.relay_localhost_packets().unwrap();
+    assert_eq!(vec!["ack first"], state(&app, &sender_addr).source_acks);
+    assert!(state(&app, &receiver_addr).source_acks.is_empty());
+    assert_eq!(
+        vec!["ack first"],
+        state(&app, &receiver_addr).destination_acks
+    );
+
+    // the source callback is notified about the timeout
+    let msg = ExecMsg::Send {
+        text: "late".to_string(),
+        timeout_height: Some(height + 1),
+        src_callback: Some(sender_addr.to_string()),
+        dest_callback: Some(receiver_addr.to_string()),
+    };
+    execute_contract(&mut app, &sender_addr, &msg).unwrap();
+    app.update_block(|block| block.height += 1);
+    app.relay_localhost_packets().unwrap();
+    assert_eq!(1, state(&app, &sender_addr).source_timeouts);
+    assert_eq!(
+        vec!["ack first"],
+        state(&app, &receiver_addr).destination_acks
+    );
+}
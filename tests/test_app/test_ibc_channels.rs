@@ -0,0 +1,185 @@
+use super::common::ibc::{
+    contract, execute_contract, ibc_app, instantiate_contract, state, ExecMsg, IbcApp,
+};
+use cosmwasm_std::{Addr, IbcEndpoint, IbcOrder};
+use cw_multi_test::ChannelState;
+
+const VERSION: &str = "ics-1";
+
+#[test]
+fn channel_handshake_should_be_run_step_by_step() {
+    let mut app = ibc_app();
+    let code_id = app.store_code(contract());
+    let (first_addr, first_port) = instantiate_contract(&mut app, code_id, "first", VERSION);
+    let (second_addr, second_port) = instantiate_contract(&mut app, code_id, "second", VERSION);
+
+    // the contract proposes the version when initializing the channel
+    let channel = app
+        .channel_open_init(
+            &first_port,
+            &second_port,
+            "connection-0",
+            "",
+            IbcOrder::Unordered,
+        )
+        .unwrap();
+    assert_eq!(VERSION, channel.version);
+    assert_eq!("", channel.counterparty_endpoint.channel_id);
+    let channel_id = channel.endpoint.channel_id.clone();
+    assert_eq!(
+        ChannelState::Init,
+        app.channel_end(&channel_id).unwrap().unwrap().state
+    );
+
+    // the handshake is not continued when the counterparty rejects the version
+    let counterparty = IbcEndpoint {
+        port_id: first_port.clone(),
+        channel_id: channel_id.clone(),
+    };
+    app.channel_open_try(
+        &second_port,
+        counterparty.clone(),
+        "connection-0",
+        "ics-0",
+        IbcOrder::Unordered,
+    )
+    .unwrap_err();
+    let counterparty_channel = app
+        .channel_open_try(
+            &second_port,
+            counterparty,
+            "connection-0",
+            &channel.version,
+            IbcOrder::Unordered,
+        )
+        .unwrap();
+    let counterparty_channel_id = counterparty_channel.endpoint.channel_id;
+    assert_eq!(
+        channel_id,
+        counterparty_channel.counterparty_endpoint.channel_id
+    );
+    assert_eq!(
+        ChannelState::TryOpen,
+        app.channel_end(&counterparty_channel_id)
+            .unwrap()
+            .unwrap()
+            .state
+    );
+
+    // only the channel end in `TryOpen` state can be confirmed
+    let err = app.channel_open_confirm(&channel_id).unwrap_err();
+    assert_eq!(
+        format!(
+            "invalid channel state: channel {} is Init, expected TryOpen",
+            channel_id
+        ),
+        err.to_string()
+    );
+    app.channel_open_ack(&channel_id, &counterparty_channel_id, VERSION)
+        .unwrap();
+    app.channel_open_confirm(&counterparty_channel_id).unwrap();
+    for id in [&channel_id, &counterparty_channel_id] {
+        let channel_end = app.channel_end(id).unwrap().unwrap();
+        assert_eq!(ChannelState::Open, channel_end.state);
+        assert_eq!(VERSION, channel_end.channel.version);
+    }
+    assert_eq!(
+        vec![
+            "init:".to_string(),
+            format!("ack:{}:{}", VERSION, counterparty_channel_id),
+        ],
+        state(&app, &first_addr).steps
+    );
+    assert_eq!(
+        vec![
+            format!("try:{}", VERSION),
+            format!("confirm:{}:{}", VERSION, channel_id),
+        ],
+        state(&app, &second_addr).steps
+    );
+}
+
+#[test]
+fn channel_close_should_be_relayed_to_both_ends() {
+    let mut app = ibc_app();
+    let code_id = app.store_code(contract());
+    let (first_addr, first_port) = instantiate_contract(&mut app, code_id, "first", VERSION);
+    let (second_addr, second_port) = instantiate_contract(&mut app, code_id, "second", VERSION);
+    let (channel_id, counterparty_channel_id) = app
+        .open_localhost_channel(&first_port, &second_port, VERSION, IbcOrder::Unordered)
+        .unwrap();
+
+    // the user closes the channel, the counterparty end is closed when relayed
+    app.channel_close_init(&channel_id).unwrap();
+    assert_eq!(
+        ChannelState::Closed,
+        app.channel_end(&channel_id).unwrap().unwrap().state
+    );
+    assert_eq!(
+        ChannelState::Open,
+        app.channel_end(&counterparty_channel_id)
+            .unwrap()
+            .unwrap()
+            .state
+    );
+    let err = app.channel_close_init(&channel_id).unwrap_err();
+    assert_eq!(
+        format!(
+            "invalid channel state: channel {} is already closed",
+            channel_id
+        ),
+        err.to_string()
+    );
+    app.relay_localhost_packets().unwrap();
+    assert_eq!(
+        ChannelState::Closed,
+        app.channel_end(&counterparty_channel_id)
+            .unwrap()
+            .unwrap()
+            .state
+    );
+    assert_eq!(
+        vec![format!("close_init:{}", counterparty_channel_id)],
+        closing_steps(&app, &first_addr)
+    );
+    assert_eq!(
+        vec![format!("close_confirm:{}", channel_id)],
+        closing_steps(&app, &second_addr)
+    );
+
+    // the contract closes the channel, only the counterparty is called back, like in wasmd
+    let (channel_id, counterparty_channel_id) = app
+        .open_localhost_channel(&first_port, &second_port, VERSION, IbcOrder::Unordered)
+        .unwrap();
+    let close = ExecMsg::Close {
+        channel_id: channel_id.clone(),
+    };
+    execute_contract(&mut app, &second_addr, &close).unwrap_err();
+    execute_contract(&mut app, &first_addr, &close).unwrap();
+    assert_eq!(
+        ChannelState::Closed,
+        app.channel_end(&channel_id).unwrap().unwrap().state
+    );
+    app.relay_localhost_packets().unwrap();
+    assert_eq!(1, closing_steps(&app, &first_addr).len());
+    assert_eq!(
+        Some(&format!("close_confirm:{}", channel_id)),
+        closing_steps(&app, &second_addr).last()
+    );
+    assert_eq!(
+        ChannelState::Closed,
+        app.channel_end(&counterparty_channel_id)
+            .unwrap()
+            .unwrap()
+            .state
+    );
+}
+
+/// Returns closing steps seen by the contract.
+fn closing_steps(app: &IbcApp, contract_addr: &Addr) -> Vec<String> {
+    state(app, contract_addr)
+        .steps
+        .into_iter()
+        .filter(|step| step.starts_with("close"))
+        .collect()
+}
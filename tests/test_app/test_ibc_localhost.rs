@@ -0,0 +1,91 @@
+use super::common::ibc::{
+    contract, execute_contract, ibc_app, instantiate_contract, state, ExecMsg,
+};
+use cosmwasm_std::{
+    Binary, Empty, IbcChannel, IbcOrder, IbcQuery, ListChannelsResponse, QueryRequest,
+};
+
+const VERSION: &str = "ping-1";
+
+#[test]
+fn contracts_should_communicate_over_localhost_channel() {
+    let mut app = ibc_app();
+    let code_id = app.store_code(contract());
+    let (ping_addr, ping_port) = instantiate_contract(&mut app, code_id, "ping", VERSION);
+    let (pong_addr, pong_port) = instantiate_contract(&mut app, code_id, "pong", VERSION);
+
+    // the handshake fails when the version is rejected, no channel is opened
+    app.open_localhost_channel(&ping_port, &pong_port, "ping-0", IbcOrder::Unordered)
+        .unwrap_err();
+
+    // open the channel
+    let (ping_channel, pong_channel) = app
+        .open_localhost_channel(&ping_port, &pong_port, VERSION, IbcOrder::Unordered)
+        .unwrap();
+    assert_eq!(
+        ("channel-0", "channel-1"),
+        (ping_channel.as_str(), pong_channel.as_str())
+    );
+
+    // channels are visible to queries
+    let response: ListChannelsResponse = app
+        .wrap()
+        .query(&QueryRequest::<Empty>::Ibc(IbcQuery::ListChannels {
+            port_id: Some(pong_port.clone()),
+        }))
+        .unwrap();
+    let channels: Vec<IbcChannel> = response.channels;
+    assert_eq!(1, channels.len());
+    assert_eq!(pong_channel, channels[0].endpoint.channel_id);
+    assert_eq!(ping_port, channels[0].counterparty_endpoint.port_id);
+    assert_eq!(VERSION, channels[0].version);
+
+    // send a packet, it is delivered only when relayed
+    let height = app.block_info().height;
+    execute_contract(&mut app, &ping_addr, &ExecMsg::send("hello", height + 10)).unwrap();
+    assert!(state(&app, &pong_addr).received.is_empty());
+    let responses = app.relay_localhost_packets().unwrap();
+    assert_eq!(1, responses.len());
+    assert_eq!(Some(Binary::from(b"ack hello".to_vec())), responses[0].data);
+    assert_eq!(vec!["hello"], state(&app, &pong_addr).received);
+    assert_eq!(vec!["ack hello"], state(&app, &ping_addr).acks);
+
+    // nothing more to relay
+    assert!(app.relay_localhost_packets().unwrap().is_empty());
+
+    // packets that timed out are returned to the sender
+    execute_contract(&mut app, &pong_addr, &ExecMsg::send("late", height + 1)).unwrap();
+    app.update_block(|block| block.height += 1);
+    app.relay_localhost_packets().unwrap();
+    assert_eq!(vec!["late"], state(&app, &pong_addr).timeouts);
+    assert_eq!(vec!["hello"], state(&app, &pong_addr).received);
+    assert!(state(&app, &ping_addr).received.is_empty());
+}
+
+#[test]
+fn failed_receive_should_be_acknowledged_with_error() {
+    let mut app = ibc_app();
+    let code_id = app.store_code(contract());
+    let (ping_addr, ping_port) = instantiate_contract(&mut app, code_id, "ping", VERSION);
+    let (pong_addr, pong_port) = instantiate_contract(&mut app, code_id, "pong", VERSION);
+    app.open_localhost_channel(&ping_port, &pong_port, VERSION, IbcOrder::Unordered)
+        .unwrap();
+
+    let height = app.block_info().height;
+    execute_contract(&mut app, &ping_addr, &ExecMsg::send("fail", height + 10)).unwrap();
+    execute_contract(&mut app, &ping_addr, &ExecMsg::send("hello", height + 10)).unwrap();
+    let responses = app.relay_localhost_packets().unwrap();
+    assert_eq!(2, responses.len());
+
+    // changes of the failed receive are discarded, the error is acknowledged
+    // and later packets are still relayed
+    assert_eq!(vec!["hello"], state(&app, &pong_addr).received);
+    assert_eq!(
+        vec![
+            r#"{"error":"Generic error: cannot receive fail"}"#,
+            "ack hello"
+        ],
+        state(&app, &ping_addr).acks
+    );
+    assert!(app.relay_localhost_packets().unwrap().is_empty());
+}
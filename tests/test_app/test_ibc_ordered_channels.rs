@@ -0,0 +1,96 @@
+use super::common::ibc::{
+    contract, execute_contract, instantiate_contract, setup, state, ExecMsg, IbcApp,
+};
+use cosmwasm_std::{Addr, IbcOrder, IbcTimeout, IbcTimeoutBlock};
+use cw_multi_test::{past_timeout, ChannelState, IbcChain, Relayer};
+
+const VERSION: &str = "ordered-1";
+
+fn send(app: &mut IbcApp, contract_addr: &Addr, text: &str, timeout_height: u64) {
+    execute_contract(app, contract_addr, &ExecMsg::send(text, timeout_height)).unwrap();
+}
+
+#[test]
+fn ordered_channel_should_be_closed_on_timeout() {
+    let (mut chain, sender_addr, sender_port) = setup(VERSION);
+    let (mut counterparty, receiver_addr, receiver_port) = setup(VERSION);
+    let relayer = Relayer::new(&mut chain, &mut counterparty).unwrap();
+    let (channel_id, counterparty_channel_id) = relayer
+        .open_channel(
+            &mut chain,
+            &mut counterparty,
+            &sender_port,
+            &receiver_port,
+            VERSION,
+            IbcOrder::Ordered,
+        )
+        .unwrap();
+
+    // packets can not be received out of order
+    let height = chain.block_info().height;
+    send(&mut chain, &sender_addr, "first", height + 10);
+    send(&mut chain, &sender_addr, "second", height + 10);
+    let mut packet = IbcChain::pending_packet(&chain, relayer.connection_id())
+        .unwrap()
+        .unwrap();
+    packet.sequence = 2;
+    let err = IbcChain::receive_packet(&mut counterparty, &packet).unwrap_err();
+    assert!(err.to_string().contains("out of order"));
+    relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap();
+    assert_eq!(
+        vec!["first", "second"],
+        state(&counterparty, &receiver_addr).received
+    );
+
+    // the timeout closes both channel ends, the remaining packet is timed out on close
+    let height = chain.block_info().height;
+    send(&mut chain, &sender_addr, "late", height + 1);
+    send(&mut chain, &sender_addr, "stuck", height + 10);
+    counterparty.update_block(past_timeout(&IbcTimeout::with_block(IbcTimeoutBlock {
+        revision: 0,
+        height: height + 1,
+    })));
+    let responses = relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap();
+    assert_eq!(2, responses.len());
+    let sender_state = state(&chain, &sender_addr);
+    assert!(sender_state.received.is_empty());
+    assert_eq!(vec!["late", "stuck"], sender_state.timeouts);
+    assert!(sender_state.closed);
+    let receiver_state = state(&counterparty, &receiver_addr);
+    assert_eq!(vec!["first", "second"], receiver_state.received);
+    assert!(receiver_state.timeouts.is_empty());
+    assert!(receiver_state.closed);
+    let channel_end = chain.channel_end(&channel_id).unwrap().unwrap();
+    assert_eq!(ChannelState::Closed, channel_end.state);
+    let counterparty_channel_end = counterparty
+        .channel_end(&counterparty_channel_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(ChannelState::Closed, counterparty_channel_end.state);
+
+    // packets can not be sent over the closed channel
+    let msg = ExecMsg::send("closed", height + 10);
+    execute_contract(&mut chain, &sender_addr, &msg).unwrap_err();
+}
+
+#[test]
+fn ordered_localhost_channel_should_be_closed_on_timeout() {
+    let (mut app, sender_addr, sender_port) = setup(VERSION);
+    let code_id = app.store_code(contract());
+    let (receiver_addr, receiver_port) =
+        instantiate_contract(&mut app, code_id, "receiver", VERSION);
+    app.open_localhost_channel(&sender_port, &receiver_port, VERSION, IbcOrder::Ordered)
+        .unwrap();
+
+    let height = app.block_info().height;
+    send(&mut app, &sender_addr, "late", height + 1);
+    app.update_block(|block| block.height += 1);
+    app.relay_localhost_packets().unwrap();
+    assert_eq!(vec!["late"], state(&app, &sender_addr).timeouts);
+    assert!(state(&app, &sender_addr).closed);
+    assert!(state(&app, &receiver_addr).closed);
+}
@@ -0,0 +1,58 @@
+use super::common::ibc::{
+    contract, ibc_app, instantiate_contract, plain_contract, query_contract, QueryMsg,
+};
+use cosmwasm_std::{ChannelResponse, IbcOrder, ListChannelsResponse, PortIdResponse};
+
+const VERSION: &str = "introspect-1";
+
+#[test]
+fn contracts_should_query_channels_of_their_own_port() {
+    let mut app = ibc_app();
+    let ibc_code_id = app.store_code(contract());
+    let code_id = app.store_code(plain_contract());
+    let (first_addr, first_port) = instantiate_contract(&mut app, ibc_code_id, "first", VERSION);
+    let (second_addr, second_port) = instantiate_contract(&mut app, ibc_code_id, "second", VERSION);
+    let (plain_addr, _) = instantiate_contract(&mut app, code_id, "plain", VERSION);
+    let (first_channel, second_channel) = app
+        .open_localhost_channel(&first_port, &second_port, VERSION, IbcOrder::Unordered)
+        .unwrap();
+
+    // the port bound to the contract is returned, empty when the contract is not IBC-enabled
+    let response: PortIdResponse = query_contract(&app, &first_addr, &QueryMsg::PortId {});
+    assert_eq!(first_port, response.port_id);
+    let response: PortIdResponse = query_contract(&app, &plain_addr, &QueryMsg::PortId {});
+    assert_eq!("", response.port_id);
+
+    // only channels of the contract's own port are listed
+    let response: ListChannelsResponse =
+        query_contract(&app, &second_addr, &QueryMsg::ListChannels {});
+    assert_eq!(1, response.channels.len());
+    assert_eq!(second_channel, response.channels[0].endpoint.channel_id);
+    assert_eq!(
+        first_channel,
+        response.channels[0].counterparty_endpoint.channel_id
+    );
+    let response: ListChannelsResponse =
+        query_contract(&app, &plain_addr, &QueryMsg::ListChannels {});
+    assert!(response.channels.is_empty());
+
+    // channels of other ports are not returned
+    let channel_query = |channel_id: &str| QueryMsg::Channel {
+        channel_id: channel_id.to_string(),
+    };
+    let response: ChannelResponse =
+        query_contract(&app, &first_addr, &channel_query(&first_channel));
+    assert_eq!(VERSION, response.channel.unwrap().version);
+    let response: ChannelResponse =
+        query_contract(&app, &first_addr, &channel_query(&second_channel));
+    assert_eq!(None, response.channel);
+
+    // closed channels are not returned
+    app.channel_close_init(&first_channel).unwrap();
+    let response: ChannelResponse =
+        query_contract(&app, &first_addr, &channel_query(&first_channel));
+    assert_eq!(None, response.channel);
+    let response: ListChannelsResponse =
+        query_contract(&app, &first_addr, &QueryMsg::ListChannels {});
+    assert!(response.channels.is_empty());
+}
@@ -0,0 +1,243 @@
+use super::common::ibc::IbcApp;
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{coin, Addr, Coin, CosmosMsg, IbcMsg, IbcOrder, IbcTimeout, IbcTimeoutBlock};
+use cw_multi_test::{past_timeout, AppBuilder, DenomTrace, Executor, IbcLocalhost, Relayer};
+
+const TRANSFER_PORT: &str = "transfer";
+
+/// Prepares the chain with the user owning tokens in specified denomination,
+/// returns the chain and the address of the user.
+fn setup(user: &str, denom: &str) -> (IbcApp, Addr) {
+    let user_addr = MockApi::default().addr_make(user);
+    let app = AppBuilder::default()
+        .with_ibc(IbcLocalhost::new())
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &user_addr, vec![coin(1000, denom)])
+                .unwrap();
+        });
+    (app, user_addr)
+}
+
+fn transfer(
+    app: &mut IbcApp,
+    sender: &Addr,
+    channel_id: &str,
+    receiver: &str,
+    amount: Coin,
+) -> anyhow::Result<()> {
+    let timeout = IbcTimeout::with_block(IbcTimeoutBlock {
+        revision: 0,
+        height: app.block_info().height + 10,
+    });
+    transfer_with_timeout(app, sender, channel_id, receiver, amount, timeout)
+}
+
+fn transfer_with_timeout(
+    app: &mut IbcApp,
+    sender: &Addr,
+    channel_id: &str,
+    receiver: &str,
+    amount: Coin,
+    timeout: IbcTimeout,
+) -> anyhow::Result<()> {
+    let msg = IbcMsg::Transfer {
+        channel_id: channel_id.to_string(),
+        to_address: receiver.to_string(),
+        amount,
+        timeout,
+        memo: None,
+    };
+    app.execute(sender.clone(), CosmosMsg::Ibc(msg))?;
+    Ok(())
+}
+
+fn balance(app: &IbcApp, addr: &Addr, denom: &str) -> u128 {
+    app.wrap().query_balance(addr, denom).unwrap().amount.u128()
+}
+
+#[test]
+fn tokens_should_be_transferred_and_returned() {
+    let (mut chain, alice_addr) = setup("alice", "uatom");
+    let (mut counterparty, bob_addr) = setup("bob", "uosmo");
+    let relayer = Relayer::new(&mut chain, &mut counterparty).unwrap();
+    let (channel_id, counterparty_channel_id) = relayer
+        .open_channel(
+            &mut chain,
+            &mut counterparty,
+            TRANSFER_PORT,
+            TRANSFER_PORT,
+            "",
+            IbcOrder::Unordered,
+        )
+        .unwrap();
+    let channel_end = chain.channel_end(&channel_id).unwrap().unwrap();
+    assert_eq!("ics20-1", channel_end.channel.version);
+
+    // tokens are escrowed on the source chain, vouchers are minted on the counterparty
+    transfer(
+        &mut chain,
+        &alice_addr,
+        &channel_id,
+        bob_addr.as_str(),
+        coin(300, "uatom"),
+    )
+    .unwrap();
+    assert_eq!(700, balance(&chain, &alice_addr, "uatom"));
+    assert_eq!(
+        1000,
+        chain.wrap().query_supply("uatom").unwrap().amount.u128()
+    );
+    relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap();
+    let trace = DenomTrace::new(format!("transfer/{}", counterparty_channel_id), "uatom");
+    let voucher = trace.ibc_denom();
+    assert!(voucher.starts_with("ibc/"));
+    assert_eq!(Some(trace), counterparty.denom_trace(&voucher).unwrap());
+    assert_eq!(300, balance(&counterparty, &bob_addr, &voucher));
+
+    // returned vouchers are burned, and tokens are released from the escrow
+    transfer(
+        &mut counterparty,
+        &bob_addr,
+        &counterparty_channel_id,
+        alice_addr.as_str(),
+        coin(100, &voucher),
+    )
+    .unwrap();
+    assert_eq!(200, balance(&counterparty, &bob_addr, &voucher));
+    assert_eq!(
+        200,
+        counterparty
+            .wrap()
+            .query_supply(&voucher)
+            .unwrap()
+            .amount
+            .u128()
+    );
+    relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap();
+    assert_eq!(800, balance(&chain, &alice_addr, "uatom"));
+
+    // zero amounts can not be transferred
+    transfer(
+        &mut chain,
+        &alice_addr,
+        &channel_id,
+        bob_addr.as_str(),
+        coin(0, "uatom"),
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn tokens_should_be_refunded_on_error_acknowledgement() {
+    let (mut chain, alice_addr) = setup("alice", "uatom");
+    let (mut counterparty, _) = setup("bob", "uosmo");
+    let relayer = Relayer::new(&mut chain, &mut counterparty).unwrap();
+
+    // transfer channels must be unordered
+    relayer
+        .open_channel(
+            &mut chain,
+            &mut counterparty,
+            TRANSFER_PORT,
+            TRANSFER_PORT,
+            "ics20-1",
+            IbcOrder::Ordered,
+        )
+        .unwrap_err();
+    let (channel_id, _) = relayer
+        .open_channel(
+            &mut chain,
+            &mut counterparty,
+            TRANSFER_PORT,
+            TRANSFER_PORT,
+            "ics20-1",
+            IbcOrder::Unordered,
+        )
+        .unwrap();
+
+    // the receiver is not a valid address on the counterparty chain
+    transfer(
+        &mut chain,
+        &alice_addr,
+        &channel_id,
+        "invalid",
+        coin(300, "uatom"),
+    )
+    .unwrap();
+    assert_eq!(700, balance(&chain, &alice_addr, "uatom"));
+    let responses = relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap();
+    assert_eq!(1, responses.len());
+    let receive_event = responses[0]
+        .events
+        .iter()
+        .find(|event| event.ty == "fungible_token_packet")
+        .unwrap();
+    assert!(receive_event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "success" && attr.value == "false"));
+    assert_eq!(1000, balance(&chain, &alice_addr, "uatom"));
+}
+
+#[test]
+fn tokens_should_be_refunded_on_timeout() {
+    let (mut chain, alice_addr) = setup("alice", "uatom");
+    let (mut counterparty, bob_addr) = setup("bob", "uosmo");
+    let relayer = Relayer::new(&mut chain, &mut counterparty).unwrap();
+    let (channel_id, counterparty_channel_id) = relayer
+        .open_channel(
+            &mut chain,
+            &mut counterparty,
+            TRANSFER_PORT,
+            TRANSFER_PORT,
+            "ics20-1",
+            IbcOrder::Unordered,
+        )
+        .unwrap();
+    let voucher =
+        DenomTrace::new(format!("transfer/{}", counterparty_channel_id), "uatom").ibc_denom();
+
+    // the counterparty chain reaches the timeout before the packet is relayed
+    let timeout = IbcTimeout::with_timestamp(counterparty.block_info().time.plus_seconds(60));
+    transfer_with_timeout(
+        &mut chain,
+        &alice_addr,
+        &channel_id,
+        bob_addr.as_str(),
+        coin(300, "uatom"),
+        timeout.clone(),
+    )
+    .unwrap();
+    assert_eq!(700, balance(&chain, &alice_addr, "uatom"));
+    counterparty.update_block(past_timeout(&timeout));
+    assert_eq!(timeout.timestamp(), Some(counterparty.block_info().time));
+
+    // tokens are refunded to the sender, nothing is minted on the counterparty chain
+    let responses = relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap();
+    assert_eq!(1, responses.len());
+    let timeout_event = responses[0]
+        .events
+        .iter()
+        .find(|event| event.ty == "timeout")
+        .unwrap();
+    assert!(timeout_event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "refund_amount" && attr.value == "300"));
+    assert_eq!(1000, balance(&chain, &alice_addr, "uatom"));
+    assert_eq!(0, balance(&counterparty, &bob_addr, &voucher));
+    assert!(relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap()
+        .is_empty());
+}
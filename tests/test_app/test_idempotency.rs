@@ -0,0 +1,126 @@
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{
+    Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw_multi_test::{
+    assert_idempotent, no_init, App, AppBuilder, BankKeeper, CloneableStorage, ContractWrapper,
+    Executor, IdempotencyPolicy,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExecMsg {
+    /// Fails when already claimed.
+    Claim,
+    /// Does nothing when already settled.
+    Settle,
+    /// Increments the counter every time.
+    Increment,
+}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn execute(deps: DepsMut, _: Env, _: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    match msg {
+        ExecMsg::Claim => {
+            if deps.storage.get(b"claimed").is_some() {
+                return Err(StdError::generic_err("already claimed"));
+            }
+            deps.storage.set(b"claimed", b"yes");
+        }
+        ExecMsg::Settle => deps.storage.set(b"settled", b"yes"),
+        ExecMsg::Increment => {
+            let counter = deps.storage.get(b"counter").map_or(0, |v| v[0]);
+            deps.storage.set(b"counter", &[counter + 1]);
+        }
+    }
+    Ok(Response::default())
+}
+
+fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    Ok(Binary::default())
+}
+
+type CloneableApp = App<BankKeeper, MockApi, CloneableStorage>;
+
+fn setup() -> (CloneableApp, Addr, Addr) {
+    let mut app = AppBuilder::default()
+        .with_storage(CloneableStorage::default())
+        .build(no_init);
+    let sender_addr = app.api().addr_make("sender");
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    let contract_addr = app
+        .instantiate_contract(code_id, sender_addr.clone(), &Empty {}, &[], "idem", None)
+        .unwrap();
+    (app, sender_addr, contract_addr)
+}
+
+#[test]
+fn idempotent_messages_should_pass() {
+    let (app, sender_addr, contract_addr) = setup();
+    let state = app.dump_wasm_raw(&contract_addr);
+
+    for policy in [IdempotencyPolicy::MustFail, IdempotencyPolicy::FailOrNoOp] {
+        assert_idempotent(
+            &app,
+            sender_addr.clone(),
+            contract_addr.clone(),
+            &ExecMsg::Claim,
+            policy,
+        );
+    }
+    for policy in [IdempotencyPolicy::MustBeNoOp, IdempotencyPolicy::FailOrNoOp] {
+        assert_idempotent(
+            &app,
+            sender_addr.clone(),
+            contract_addr.clone(),
+            &ExecMsg::Settle,
+            policy,
+        );
+    }
+
+    // messages are executed on a copy of the application
+    assert_eq!(state, app.dump_wasm_raw(&contract_addr));
+}
+
+#[test]
+#[should_panic(expected = "repeated execution of Settle succeeded, but was expected to fail")]
+fn no_op_message_should_not_pass_must_fail_policy() {
+    let (app, sender_addr, contract_addr) = setup();
+    assert_idempotent(
+        &app,
+        sender_addr,
+        contract_addr,
+        &ExecMsg::Settle,
+        IdempotencyPolicy::MustFail,
+    );
+}
+
+#[test]
+#[should_panic(expected = "repeated execution of Claim failed, but was expected to be a no-op")]
+fn failing_message_should_not_pass_must_be_no_op_policy() {
+    let (app, sender_addr, contract_addr) = setup();
+    assert_idempotent(
+        &app,
+        sender_addr,
+        contract_addr,
+        &ExecMsg::Claim,
+        IdempotencyPolicy::MustBeNoOp,
+    );
+}
+
+#[test]
+#[should_panic(expected = "repeated execution of Increment changed the state at key")]
+fn state_changing_message_should_not_pass() {
+    let (app, sender_addr, contract_addr) = setup();
+    assert_idempotent(
+        &app,
+        sender_addr,
+        contract_addr,
+        &ExecMsg::Increment,
+        IdempotencyPolicy::FailOrNoOp,
+    );
+}
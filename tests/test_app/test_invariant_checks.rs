@@ -0,0 +1,181 @@
+use super::common::multi_send::{multi_send_msg, proto_io};
+use super::common::sdk_app;
+use cosmwasm_std::{
+    coin, coins, to_json_vec, Addr, Api, BankMsg, Binary, BlockInfo, CosmosMsg, CustomMsg,
+    CustomQuery, Decimal, DistributionMsg, Empty, Querier, StakingMsg, Storage, Uint128, Validator,
+};
+use cw_multi_test::error::{bail, AnyResult};
+use cw_multi_test::{AppBuilder, AppResponse, CosmosRouter, Executor, Module};
+use cw_storage_plus::Map;
+use serde::de::DeserializeOwned;
+
+/// Custom module with the accounting bug: it increases the total supply
+/// without crediting any account.
+struct LeakyMinter;
+
+impl Module for LeakyMinter {
+    type ExecT = Empty;
+    type QueryT = Empty;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _sender: Addr,
+        _msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        // the supply is written directly to the bank namespace
+        let supply = Map::<&str, Uint128>::new("supply");
+        let key = [b"\x00\x04bank".as_slice(), &supply.key("TOKEN")].concat();
+        storage.set(&key, &to_json_vec(&Uint128::new(150))?);
+        Ok(AppResponse::default())
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Empty,
+    ) -> AnyResult<AppResponse>
+    where
+        ExecC: CustomMsg + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        bail!("unsupported sudo message")
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        _request: Empty,
+    ) -> AnyResult<Binary> {
+        bail!("unsupported query")
+    }
+}
+
+#[test]
+fn invariants_should_hold_for_bank_and_staking_operations() {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    let recipient_addr = app.api().addr_make("recipient");
+    let validator_addr = app.api().addr_make("validator");
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner_addr, coins(1000, "TOKEN"))
+            .unwrap();
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(100),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+    });
+    app.enable_invariant_checks();
+
+    app.send_tokens(
+        owner_addr.clone(),
+        recipient_addr.clone(),
+        &coins(100, "TOKEN"),
+    )
+    .unwrap();
+    app.execute(
+        owner_addr.clone(),
+        multi_send_msg(
+            vec![proto_io(&owner_addr, coins(20, "TOKEN"))],
+            vec![proto_io(&recipient_addr, coins(20, "TOKEN"))],
+        ),
+    )
+    .unwrap();
+    app.execute(
+        recipient_addr.clone(),
+        BankMsg::Burn {
+            amount: coins(50, "TOKEN"),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(500, "TOKEN"),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(60 * 60 * 24 * 365));
+    app.execute(
+        owner_addr.clone(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(500, "TOKEN"),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(60));
+    app.update_block(|_| {});
+
+    // failed messages are not checked
+    app.send_tokens(owner_addr, recipient_addr, &coins(10_000, "TOKEN"))
+        .unwrap_err();
+}
+
+#[test]
+#[should_panic(
+    expected = "bank invariant violated after processing Custom(Empty)\n  TOKEN: sum of balances 100 != total supply 150 (-50)"
+)]
+fn violated_invariant_should_abort_the_test() {
+    let mut app = AppBuilder::new_custom()
+        .with_custom(LeakyMinter)
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked("owner"), coins(100, "TOKEN"))
+                .unwrap();
+        });
+    app.enable_invariant_checks();
+    app.execute(Addr::unchecked("owner"), CosmosMsg::Custom(Empty {}))
+        .unwrap();
+}
+
+#[test]
+fn invariants_should_not_be_checked_when_disabled() {
+    let mut app = AppBuilder::new_custom()
+        .with_custom(LeakyMinter)
+        .build(|_, _, _| {});
+    app.enable_invariant_checks();
+    app.disable_invariant_checks();
+    app.execute(Addr::unchecked("owner"), CosmosMsg::Custom(Empty {}))
+        .unwrap();
+}
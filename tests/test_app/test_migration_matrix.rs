@@ -0,0 +1,140 @@
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{
+    no_init, App, AppBuilder, BankKeeper, CloneableStorage, ContractWrapper, Executor,
+    MigrationMatrix,
+};
+use cw_storage_plus::Item;
+
+/// Counter stored by version 1.0.0.
+const COUNTER_V1: Item<u64> = Item::new("counter");
+
+/// Counter stored by versions 2.0.0 and 3.0.0.
+const COUNTER_V2: Item<u64> = Item::new("count");
+
+fn instantiate_v1(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    COUNTER_V1.save(deps.storage, &0)?;
+    Ok(Response::default())
+}
+
+fn execute_v1(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    COUNTER_V1.update(deps.storage, |counter| -> StdResult<_> { Ok(counter + 1) })?;
+    Ok(Response::default())
+}
+
+fn query_v1(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    to_json_binary(&COUNTER_V1.load(deps.storage)?)
+}
+
+fn instantiate_v2(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    COUNTER_V2.save(deps.storage, &0)?;
+    Ok(Response::default())
+}
+
+fn execute_v2(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    COUNTER_V2.update(deps.storage, |counter| -> StdResult<_> { Ok(counter + 1) })?;
+    Ok(Response::default())
+}
+
+fn query_v2(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    to_json_binary(&COUNTER_V2.load(deps.storage)?)
+}
+
+fn migrate_v2(deps: DepsMut, _: Env, _: Empty) -> StdResult<Response> {
+    // migrating from version 2.0.0 keeps the counter in place
+    if let Some(counter) = COUNTER_V1.may_load(deps.storage)? {
+        COUNTER_V1.remove(deps.storage);
+        COUNTER_V2.save(deps.storage, &counter)?;
+    }
+    Ok(Response::default())
+}
+
+/// Broken migration, forgetting the counter stored by version 1.0.0.
+fn migrate_v3(deps: DepsMut, _: Env, _: Empty) -> StdResult<Response> {
+    if COUNTER_V2.may_load(deps.storage)?.is_none() {
+        COUNTER_V2.save(deps.storage, &0)?;
+    }
+    Ok(Response::default())
+}
+
+type CloneableApp = App<BankKeeper, MockApi, CloneableStorage>;
+
+fn matrix(admin: Addr) -> MigrationMatrix<CloneableApp> {
+    MigrationMatrix::new("counter", admin.clone(), &Empty {}, &Empty {})
+        .unwrap()
+        .with_scenario(move |app: &mut CloneableApp, contract_addr| {
+            for _ in 0..3 {
+                app.execute_contract(admin.clone(), contract_addr.clone(), &Empty {}, &[])?;
+            }
+            Ok(())
+        })
+        .with_invariant(|app: &CloneableApp, contract_addr| {
+            let counter: u64 = app.wrap().query_wasm_smart(contract_addr, &Empty {})?;
+            if counter != 3 {
+                anyhow::bail!("expected counter 3, found {}", counter);
+            }
+            Ok(())
+        })
+}
+
+#[test]
+fn compatible_versions_should_pass_migration_matrix() {
+    let mut app = AppBuilder::default()
+        .with_storage(CloneableStorage::default())
+        .build(no_init);
+    let admin_addr = app.api().addr_make("admin");
+    let v1 = ContractWrapper::new(execute_v1, instantiate_v1, query_v1).with_migrate(migrate_v2);
+    let v2 = ContractWrapper::new(execute_v2, instantiate_v2, query_v2).with_migrate(migrate_v2);
+    let v1_code_id = app
+        .store_code_version("counter", "1.0.0", Box::new(v1))
+        .unwrap();
+    let v2_code_id = app
+        .store_code_version("counter", "2.0.0", Box::new(v2))
+        .unwrap();
+    assert_eq!(
+        vec![
+            ("1.0.0".to_string(), v1_code_id),
+            ("2.0.0".to_string(), v2_code_id)
+        ],
+        app.code_versions("counter").unwrap()
+    );
+
+    // versions are registered only once
+    let v2 = ContractWrapper::new(execute_v2, instantiate_v2, query_v2);
+    assert_eq!(
+        "version 2.0.0 of contract counter is already registered",
+        app.store_code_version("counter", "2.0.0", Box::new(v2))
+            .unwrap_err()
+            .to_string()
+    );
+
+    app.run_migration_matrix(&matrix(admin_addr)).unwrap();
+
+    // the matrix is run on copies of the application
+    assert!(app.all_contracts().unwrap().is_empty());
+}
+
+#[test]
+fn incompatible_versions_should_fail_migration_matrix() {
+    let mut app = AppBuilder::default()
+        .with_storage(CloneableStorage::default())
+        .build(no_init);
+    let admin_addr = app.api().addr_make("admin");
+    let v1 = ContractWrapper::new(execute_v1, instantiate_v1, query_v1);
+    let v2 = ContractWrapper::new(execute_v2, instantiate_v2, query_v2).with_migrate(migrate_v2);
+    let v3 = ContractWrapper::new(execute_v2, instantiate_v2, query_v2).with_migrate(migrate_v3);
+    app.store_code_version("counter", "1.0.0", Box::new(v1))
+        .unwrap();
+    app.store_code_version("counter", "2.0.0", Box::new(v2))
+        .unwrap();
+    app.store_code_version("counter", "3.0.0", Box::new(v3))
+        .unwrap();
+    assert_eq!(
+        "migration of contract counter from version 1.0.0 to 3.0.0 failed: after migration: expected counter 3, found 0",
+        app.run_migration_matrix(&matrix(admin_addr))
+            .unwrap_err()
+            .to_string()
+    );
+}
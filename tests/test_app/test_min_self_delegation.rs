@@ -0,0 +1,155 @@
+use super::common::staking::{init_staking, undelegate, unjail, DENOM};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{coin, Addr, AnyMsg, CosmosMsg, Decimal, Event, StakingMsg, Uint128};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{AppResponse, Executor, StakingSudo};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct CommissionRates {
+    #[prost(string, tag = "1")]
+    pub rate: String,
+    #[prost(string, tag = "2")]
+    pub max_rate: String,
+    #[prost(string, tag = "3")]
+    pub max_change_rate: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgCreateValidator {
+    #[prost(message, optional, tag = "2")]
+    pub commission: Option<CommissionRates>,
+    #[prost(string, tag = "3")]
+    pub min_self_delegation: String,
+    #[prost(string, tag = "4")]
+    pub delegator_address: String,
+    #[prost(string, tag = "5")]
+    pub validator_address: String,
+    #[prost(message, optional, tag = "7")]
+    pub value: Option<ProtoCoin>,
+}
+
+fn create_validator(
+    app: &mut SdkApp,
+    validator_addr: &Addr,
+    amount: u128,
+    min_self_delegation: u128,
+) -> AnyResult<AppResponse> {
+    let msg = MsgCreateValidator {
+        commission: Some(CommissionRates {
+            rate: Decimal::percent(10).atomics().to_string(),
+            max_rate: Decimal::percent(20).atomics().to_string(),
+            max_change_rate: Decimal::percent(5).atomics().to_string(),
+        }),
+        min_self_delegation: min_self_delegation.to_string(),
+        delegator_address: validator_addr.to_string(),
+        validator_address: validator_addr.to_string(),
+        value: Some(ProtoCoin {
+            denom: DENOM.to_string(),
+            amount: amount.to_string(),
+        }),
+    };
+    app.execute(
+        validator_addr.clone(),
+        CosmosMsg::Any(AnyMsg {
+            type_url: "/cosmos.staking.v1beta1.MsgCreateValidator".to_string(),
+            value: msg.encode_to_vec().into(),
+        }),
+    )
+}
+
+fn is_jailed(app: &SdkApp, validator_addr: &Addr) -> bool {
+    app.read_module(|router, _, storage| router.staking.jailed_until(storage, validator_addr))
+        .unwrap()
+        .is_some()
+}
+
+/// Prepares the application with the funded validator's operator.
+fn setup() -> (SdkApp, Addr) {
+    let mut app = sdk_app();
+    let validator_addr = app.api().addr_make("validator");
+    init_staking(&mut app, &[(&validator_addr, 1000)], &[]);
+    (app, validator_addr)
+}
+
+#[test]
+fn validator_below_min_self_delegation_should_be_jailed() {
+    let (mut app, validator_addr) = setup();
+
+    let err = create_validator(&mut app, &validator_addr, 50, 100).unwrap_err();
+    assert_eq!(
+        "validator's self delegation must be greater than their minimum self delegation",
+        err.root_cause().to_string()
+    );
+    create_validator(&mut app, &validator_addr, 150, 100).unwrap();
+    let min_self_delegation = app
+        .read_module(|router, _, storage| {
+            router.staking.min_self_delegation(storage, &validator_addr)
+        })
+        .unwrap();
+    assert_eq!(Uint128::new(100), min_self_delegation);
+
+    // the validator stays bonded while its self-delegation meets the minimum
+    undelegate(&mut app, &validator_addr, &validator_addr, 50);
+    assert!(!is_jailed(&app, &validator_addr));
+
+    let res = undelegate(&mut app, &validator_addr, &validator_addr, 1);
+    assert!(res.has_event(
+        &Event::new("jail")
+            .add_attribute("validator", &validator_addr)
+            .add_attribute("reason", "min_self_delegation")
+    ));
+    assert!(is_jailed(&app, &validator_addr));
+    assert!(app.wrap().query_all_validators().unwrap().is_empty());
+
+    // the validator can be unjailed after its self-delegation is restored
+    let err = unjail(&mut app, &validator_addr).unwrap_err();
+    assert_eq!(
+        "validator's self delegation less than minimum; cannot be unjailed",
+        err.root_cause().to_string()
+    );
+    app.execute(
+        validator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(1, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    unjail(&mut app, &validator_addr).unwrap();
+    assert_eq!(1, app.wrap().query_all_validators().unwrap().len());
+
+    // raising the minimum above the self-delegation jails the validator immediately
+    app.sudo(
+        StakingSudo::SetMinSelfDelegation {
+            validator: validator_addr.to_string(),
+            min_self_delegation: Uint128::new(200),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert!(is_jailed(&app, &validator_addr));
+}
+
+#[test]
+fn fully_undelegated_validator_should_be_jailed() {
+    let (mut app, validator_addr) = setup();
+    create_validator(&mut app, &validator_addr, 100, 0).unwrap();
+
+    let res = undelegate(&mut app, &validator_addr, &validator_addr, 100);
+    assert!(res.has_event(
+        &Event::new("jail")
+            .add_attribute("validator", &validator_addr)
+            .add_attribute("reason", "no_delegations")
+    ));
+    assert!(is_jailed(&app, &validator_addr));
+}
@@ -0,0 +1,268 @@
+use super::common::staking::{
+    balance, delegate, grpc_query, init_staking, rewards, try_grpc_query, validator, DENOM,
+};
+use cosmwasm_std::{coins, Addr, BankMsg, Decimal, DistributionMsg, Uint128};
+use cw_multi_test::{
+    next_block, no_init, App, AppBuilder, Executor, MintFailingModule, MintParams, MintSudo,
+    Minter, StakingInfo,
+};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoMintParams {
+    #[prost(string, tag = "1")]
+    pub mint_denom: String,
+    #[prost(string, tag = "2")]
+    pub inflation_rate_change: String,
+    #[prost(string, tag = "3")]
+    pub inflation_max: String,
+    #[prost(string, tag = "4")]
+    pub inflation_min: String,
+    #[prost(string, tag = "5")]
+    pub goal_bonded: String,
+    #[prost(uint64, tag = "6")]
+    pub blocks_per_year: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryParamsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub params: Option<ProtoMintParams>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryInflationResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub inflation: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryAnnualProvisionsResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub annual_provisions: Vec<u8>,
+}
+
+/// Prepares the application with the owner of 10000 tokens and validators with given commission,
+/// without rewards accrued with the interest rate, so all rewards come from allocated tokens.
+fn setup(params: MintParams, validators: &[(&str, u64)]) -> (App, Addr, Vec<Addr>) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    app.init_modules(|router, _, storage| {
+        router
+            .staking
+            .setup(
+                storage,
+                StakingInfo {
+                    apr: Decimal::zero(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        router.mint.setup(storage, params).unwrap();
+    });
+    let validators = validators
+        .iter()
+        .map(|(name, commission)| (app.api().addr_make(name), *commission))
+        .collect::<Vec<_>>();
+    let validator_set = validators
+        .iter()
+        .map(|(validator_addr, commission)| validator(validator_addr, *commission))
+        .collect::<Vec<_>>();
+    init_staking(&mut app, &[(&owner_addr, 10000)], &validator_set);
+    let validators = validators.into_iter().map(|(addr, _)| addr).collect();
+    (app, owner_addr, validators)
+}
+
+fn minter(app: &App) -> Minter {
+    app.read_module(|router, _, storage| router.mint.minter(storage))
+        .unwrap()
+}
+
+#[test]
+fn minted_tokens_should_be_allocated_as_rewards() {
+    let params = MintParams {
+        inflation_min: Decimal::percent(10),
+        inflation_max: Decimal::percent(10),
+        blocks_per_year: 10,
+        ..Default::default()
+    };
+    let (mut app, owner_addr, validators) = setup(params, &[("validator", 10)]);
+    let validator_addr = &validators[0];
+    delegate(&mut app, &owner_addr, validator_addr, 5000);
+    app.update_block(next_block);
+
+    // 10% of 10000 tokens per year of 10 blocks
+    assert_eq!(
+        Minter {
+            inflation: Decimal::percent(10),
+            annual_provisions: Decimal::from_ratio(1000u128, 1u128),
+        },
+        minter(&app)
+    );
+    assert_eq!(
+        Uint128::new(10100),
+        app.wrap().query_supply(DENOM).unwrap().amount
+    );
+
+    // minted tokens are moved from the fee collector to the distribution module,
+    // 2% goes to the community pool, 10% of the rest is the validator's commission
+    let fee_collector = app.module_address("fee_collector").unwrap();
+    assert_eq!(0, balance(&app, &fee_collector));
+    let community_pool = app
+        .read_module(|router, _, storage| router.distribution.community_pool(storage))
+        .unwrap();
+    assert_eq!(coins(2, DENOM), community_pool);
+    assert_eq!(88, rewards(&app, &owner_addr, validator_addr));
+
+    // withdrawn rewards are paid from the distribution module, without minting new tokens
+    app.execute(
+        owner_addr.clone(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(5088, balance(&app, &owner_addr));
+    assert_eq!(
+        Uint128::new(10100),
+        app.wrap().query_supply(DENOM).unwrap().amount
+    );
+}
+
+#[test]
+fn inflation_should_move_towards_goal_bonded() {
+    let params = MintParams {
+        goal_bonded: Decimal::one(),
+        blocks_per_year: 10,
+        ..Default::default()
+    };
+    let (mut app, owner_addr, validators) = setup(params, &[("validator", 0)]);
+    delegate(&mut app, &owner_addr, &validators[0], 5000);
+    app.update_block(next_block);
+
+    // half of the goal is bonded, so the inflation rises by half of the rate change per year
+    assert_eq!(
+        Minter {
+            inflation: Decimal::bps(1365),
+            annual_provisions: Decimal::from_ratio(1365u128, 1u128),
+        },
+        minter(&app)
+    );
+    for _ in 0..20 {
+        app.update_block(next_block);
+    }
+    let response: QueryInflationResponse =
+        grpc_query(&app, "/cosmos.mint.v1beta1.Query/Inflation", ());
+    assert_eq!(
+        Decimal::percent(20).atomics().to_string().into_bytes(),
+        response.inflation
+    );
+
+    let err = app
+        .sudo(
+            MintSudo::UpdateParams {
+                params: MintParams {
+                    community_tax: Decimal::percent(50),
+                    proposer_reward: Decimal::percent(60),
+                    ..Default::default()
+                },
+            }
+            .into(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        "community tax and proposer reward cannot exceed one",
+        err.to_string()
+    );
+}
+
+#[test]
+fn collected_fees_should_be_rewarded_to_block_proposers() {
+    // no tokens are minted, all collected fees are rewarded to the proposer
+    let params = MintParams {
+        inflation_min: Decimal::zero(),
+        inflation_max: Decimal::zero(),
+        community_tax: Decimal::zero(),
+        proposer_reward: Decimal::one(),
+        ..Default::default()
+    };
+    let (mut app, owner_addr, validators) = setup(params, &[("first", 0), ("second", 0)]);
+    delegate(&mut app, &owner_addr, &validators[0], 100);
+    delegate(&mut app, &owner_addr, &validators[1], 300);
+
+    // validators propose blocks proportionally to their stake
+    let fee_collector = app.module_address("fee_collector").unwrap();
+    for _ in 0..4 {
+        app.execute(
+            owner_addr.clone(),
+            BankMsg::Send {
+                to_address: fee_collector.to_string(),
+                amount: coins(100, DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+        app.update_block(next_block);
+    }
+    assert_eq!(100, rewards(&app, &owner_addr, &validators[0]));
+    assert_eq!(300, rewards(&app, &owner_addr, &validators[1]));
+}
+
+#[test]
+fn mint_state_should_be_queried_with_grpc() {
+    let params = MintParams {
+        inflation_min: Decimal::percent(10),
+        inflation_max: Decimal::percent(10),
+        blocks_per_year: 10,
+        ..Default::default()
+    };
+    let (mut app, _, _) = setup(params, &[("validator", 0)]);
+    app.update_block(next_block);
+
+    let response: QueryParamsResponse = grpc_query(&app, "/cosmos.mint.v1beta1.Query/Params", ());
+    assert_eq!(
+        Some(ProtoMintParams {
+            mint_denom: DENOM.to_string(),
+            inflation_rate_change: Decimal::percent(13).atomics().to_string(),
+            inflation_max: Decimal::percent(10).atomics().to_string(),
+            inflation_min: Decimal::percent(10).atomics().to_string(),
+            goal_bonded: Decimal::percent(67).atomics().to_string(),
+            blocks_per_year: 10,
+        }),
+        response.params
+    );
+    let response: QueryAnnualProvisionsResponse =
+        grpc_query(&app, "/cosmos.mint.v1beta1.Query/AnnualProvisions", ());
+    assert_eq!(
+        minter(&app)
+            .annual_provisions
+            .atomics()
+            .to_string()
+            .into_bytes(),
+        response.annual_provisions
+    );
+}
+
+#[test]
+fn custom_mint_module_should_be_used_by_app() {
+    let mut app = AppBuilder::default()
+        .with_mint(MintFailingModule::new())
+        .build(no_init);
+
+    // privileged actions and queries are handled by the mint module of the app
+    let err = app
+        .sudo(
+            MintSudo::UpdateParams {
+                params: MintParams::default(),
+            }
+            .into(),
+        )
+        .unwrap_err();
+    assert!(err.to_string().starts_with("Unexpected sudo msg"));
+    let err =
+        try_grpc_query::<QueryInflationResponse>(&app, "/cosmos.mint.v1beta1.Query/Inflation", ())
+            .unwrap_err();
+    assert!(err.contains("Unexpected custom query"));
+    app.update_block(next_block);
+}
@@ -0,0 +1,179 @@
+use super::common::multi_send::{multi_send_msg, proto_io};
+use super::common::staking::{balance, DENOM};
+use super::common::SdkApp;
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{coin, coins, Addr, Event};
+use cw_multi_test::{App, AppBuilder, CosmosSdkStargate, Executor, PausableModule, PauseSudo};
+
+fn setup() -> (SdkApp, Addr, Addr, Addr) {
+    let api = MockApi::default();
+    let alice = api.addr_make("alice");
+    let bob = api.addr_make("bob");
+    let carol = api.addr_make("carol");
+    let app = AppBuilder::default()
+        .with_stargate(CosmosSdkStargate)
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &alice, coins(100, DENOM))
+                .unwrap();
+            router
+                .bank
+                .init_balance(storage, &bob, vec![coin(50, DENOM), coin(10, "other")])
+                .unwrap();
+        });
+    (app, alice, bob, carol)
+}
+
+#[test]
+fn multi_send_with_multiple_outputs_should_work() {
+    let (mut app, _, bob, carol) = setup();
+
+    let res = app
+        .execute(
+            bob.clone(),
+            multi_send_msg(
+                vec![proto_io(&bob, vec![coin(30, DENOM), coin(10, "other")])],
+                vec![
+                    proto_io(&carol, vec![coin(20, DENOM), coin(10, "other")]),
+                    proto_io(&bob, coins(10, DENOM)),
+                ],
+            ),
+        )
+        .unwrap();
+
+    assert_eq!(30, balance(&app, &bob));
+    assert_eq!(20, balance(&app, &carol));
+    assert_eq!(
+        10,
+        app.wrap()
+            .query_balance(&carol, "other")
+            .unwrap()
+            .amount
+            .u128()
+    );
+    assert!(res.has_event(
+        &Event::new("coin_spent")
+            .add_attribute("spender", bob.as_str())
+            .add_attribute("amount", "30TOKEN,10other")
+    ));
+    assert!(res.has_event(
+        &Event::new("coin_received")
+            .add_attribute("receiver", carol.as_str())
+            .add_attribute("amount", "20TOKEN,10other")
+    ));
+}
+
+#[test]
+fn multi_send_should_check_inputs_and_outputs() {
+    let (mut app, alice, bob, carol) = setup();
+
+    let err = app
+        .execute(
+            alice.clone(),
+            multi_send_msg(
+                vec![proto_io(&alice, coins(70, DENOM))],
+                vec![proto_io(&carol, coins(60, DENOM))],
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(
+        "sum of inputs 70TOKEN does not match sum of outputs 60TOKEN",
+        err.to_string()
+    );
+
+    let err = app
+        .execute(
+            alice.clone(),
+            multi_send_msg(vec![], vec![proto_io(&carol, coins(60, DENOM))]),
+        )
+        .unwrap_err();
+    assert_eq!("no inputs to send transaction", err.to_string());
+
+    // insufficient funds, nothing is transferred
+    app.execute(
+        bob.clone(),
+        multi_send_msg(
+            vec![proto_io(&bob, coins(60, DENOM))],
+            vec![proto_io(&carol, coins(60, DENOM))],
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(100, balance(&app, &alice));
+    assert_eq!(50, balance(&app, &bob));
+    assert_eq!(0, balance(&app, &carol));
+}
+
+#[test]
+fn multi_send_messages_should_be_routed_to_bank() {
+    let (mut app, alice, bob, carol) = setup();
+
+    let res = app
+        .execute(
+            alice.clone(),
+            multi_send_msg(
+                vec![proto_io(&alice, coins(90, DENOM))],
+                vec![
+                    proto_io(&bob, coins(30, DENOM)),
+                    proto_io(&carol, coins(60, DENOM)),
+                ],
+            ),
+        )
+        .unwrap();
+    assert_eq!(10, balance(&app, &alice));
+    assert_eq!(80, balance(&app, &bob));
+    assert_eq!(60, balance(&app, &carol));
+    assert!(res.has_event(
+        &Event::new("transfer")
+            .add_attribute("recipient", carol.as_str())
+            .add_attribute("sender", alice.as_str())
+            .add_attribute("amount", "60TOKEN")
+    ));
+
+    // sender must own all inputs
+    let err = app
+        .execute(
+            alice.clone(),
+            multi_send_msg(
+                vec![
+                    proto_io(&alice, coins(10, DENOM)),
+                    proto_io(&bob, coins(10, DENOM)),
+                ],
+                vec![proto_io(&carol, coins(20, DENOM))],
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(
+        format!("input address {bob} does not match the signer {alice}"),
+        err.to_string()
+    );
+
+    // paused bank module rejects transfers
+    app.sudo(PauseSudo::Pause(PausableModule::Bank).into())
+        .unwrap();
+    let err = app
+        .execute(
+            alice.clone(),
+            multi_send_msg(
+                vec![proto_io(&alice, coins(10, DENOM))],
+                vec![proto_io(&carol, coins(10, DENOM))],
+            ),
+        )
+        .unwrap_err();
+    assert_eq!("send transactions are disabled", err.to_string());
+}
+
+#[test]
+fn multi_send_messages_should_be_rejected_without_handler() {
+    let mut app = App::default();
+    let alice = app.api().addr_make("alice");
+    let carol = app.api().addr_make("carol");
+    app.execute(
+        alice.clone(),
+        multi_send_msg(
+            vec![proto_io(&alice, coins(10, DENOM))],
+            vec![proto_io(&carol, coins(10, DENOM))],
+        ),
+    )
+    .unwrap_err();
+}
@@ -0,0 +1,147 @@
+use crate::test_contracts;
+use cosmwasm_std::{Addr, Empty, WasmMsg};
+use cw_multi_test::error::Error;
+use cw_multi_test::{App, Executor};
+
+/// Instantiates the counter contract administered by a multisig admin with 2 of 3 members.
+fn setup() -> (App, Addr, Vec<Addr>, Addr) {
+    let mut app = App::default();
+    let multisig_addr = app.api().addr_make("multisig");
+    let members = vec![
+        app.api().addr_make("alice"),
+        app.api().addr_make("bob"),
+        app.api().addr_make("carol"),
+    ];
+    app.register_multisig_admin(&multisig_addr, members.clone(), 2)
+        .unwrap();
+
+    let code_id = app.store_code(test_contracts::counter::contract());
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            members[0].clone(),
+            &Empty {},
+            &[],
+            "counter",
+            Some(multisig_addr.to_string()),
+        )
+        .unwrap();
+    (app, multisig_addr, members, contract_addr)
+}
+
+#[test]
+fn admin_operation_requires_threshold_approvals() {
+    let (mut app, multisig_addr, members, contract_addr) = setup();
+    let new_admin_addr = app.api().addr_make("new_admin");
+    let msg = WasmMsg::UpdateAdmin {
+        contract_addr: contract_addr.to_string(),
+        admin: new_admin_addr.to_string(),
+    };
+
+    // operation approved by single member can not be executed
+    app.approve_admin_msg(&multisig_addr, &members[0], &msg)
+        .unwrap();
+    let err = app
+        .execute(multisig_addr.clone(), msg.clone().into())
+        .unwrap_err();
+    assert_eq!(
+        &Error::insufficient_approvals(1, 2),
+        err.root_cause().downcast_ref::<Error>().unwrap()
+    );
+
+    // approving twice by the same member has no effect
+    app.approve_admin_msg(&multisig_addr, &members[0], &msg)
+        .unwrap();
+    assert_eq!(
+        vec![members[0].clone()],
+        app.admin_msg_approvals(&multisig_addr, &msg).unwrap()
+    );
+
+    // operation approved by two members is executed and consumes approvals
+    app.approve_admin_msg(&multisig_addr, &members[2], &msg)
+        .unwrap();
+    app.execute(multisig_addr.clone(), msg.clone().into())
+        .unwrap();
+    assert_eq!(
+        Some(new_admin_addr),
+        app.contract_data(&contract_addr).unwrap().admin
+    );
+    assert!(app
+        .admin_msg_approvals(&multisig_addr, &msg)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn approvals_are_bound_to_the_operation() {
+    let (mut app, multisig_addr, members, contract_addr) = setup();
+    let approved_msg = WasmMsg::UpdateAdmin {
+        contract_addr: contract_addr.to_string(),
+        admin: app.api().addr_make("new_admin").to_string(),
+    };
+    app.approve_admin_msg(&multisig_addr, &members[0], &approved_msg)
+        .unwrap();
+    app.approve_admin_msg(&multisig_addr, &members[1], &approved_msg)
+        .unwrap();
+
+    // clearing the admin was not approved
+    let msg = WasmMsg::ClearAdmin {
+        contract_addr: contract_addr.to_string(),
+    };
+    let err = app.execute(multisig_addr.clone(), msg.into()).unwrap_err();
+    assert_eq!(
+        &Error::insufficient_approvals(0, 2),
+        err.root_cause().downcast_ref::<Error>().unwrap()
+    );
+    assert_eq!(
+        Some(multisig_addr),
+        app.contract_data(&contract_addr).unwrap().admin
+    );
+}
+
+#[test]
+fn only_members_can_approve() {
+    let (mut app, multisig_addr, _, contract_addr) = setup();
+    let msg = WasmMsg::ClearAdmin {
+        contract_addr: contract_addr.to_string(),
+    };
+
+    let stranger_addr = app.api().addr_make("stranger");
+    assert_eq!(
+        Error::not_multisig_member(&stranger_addr),
+        app.approve_admin_msg(&multisig_addr, &stranger_addr, &msg)
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    );
+    assert_eq!(
+        Error::not_multisig_admin(&stranger_addr),
+        app.approve_admin_msg(&stranger_addr, &multisig_addr, &msg)
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    );
+}
+
+#[test]
+fn threshold_must_be_reachable() {
+    let mut app = App::default();
+    let multisig_addr = app.api().addr_make("multisig");
+    let alice_addr = app.api().addr_make("alice");
+
+    let members = vec![alice_addr.clone(), alice_addr];
+    assert_eq!(
+        Error::invalid_multisig_threshold(2, 1),
+        app.register_multisig_admin(&multisig_addr, members.clone(), 2)
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    );
+    assert_eq!(
+        Error::invalid_multisig_threshold(0, 1),
+        app.register_multisig_admin(&multisig_addr, members, 0)
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    );
+}
@@ -0,0 +1,126 @@
+use super::common::staking::{delegate, grpc_query, init_staking, validator, DENOM};
+use cosmwasm_std::{coin, Addr, Decimal, DistributionMsg, Uint128};
+use cw_multi_test::{App, Executor, StakingSudo};
+use prost::Message;
+
+const YEAR: u64 = 60 * 60 * 24 * 365;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoDecCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ValidatorOutstandingRewards {
+    #[prost(message, repeated, tag = "1")]
+    pub rewards: Vec<ProtoDecCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryValidatorOutstandingRewardsRequest {
+    #[prost(string, tag = "1")]
+    pub validator_address: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryValidatorOutstandingRewardsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub rewards: Option<ValidatorOutstandingRewards>,
+}
+
+/// Prepares the application with single validator charging 10% commission
+/// and three delegators, each delegating 333 tokens.
+fn setup() -> (App, [Addr; 3], Addr) {
+    let mut app = App::default();
+    let delegators = ["delegator1", "delegator2", "delegator3"].map(|d| app.api().addr_make(d));
+    let validator_addr = app.api().addr_make("validator");
+    let balances = delegators
+        .iter()
+        .map(|addr| (addr, 333))
+        .collect::<Vec<_>>();
+    init_staking(&mut app, &balances, &[validator(&validator_addr, 10)]);
+    for delegator_addr in &delegators {
+        delegate(&mut app, delegator_addr, &validator_addr, 333);
+    }
+    (app, delegators, validator_addr)
+}
+
+fn outstanding_rewards(app: &App, validator_addr: &Addr) -> Decimal {
+    let response: QueryValidatorOutstandingRewardsResponse = grpc_query(
+        app,
+        "/cosmos.distribution.v1beta1.Query/ValidatorOutstandingRewards",
+        QueryValidatorOutstandingRewardsRequest {
+            validator_address: validator_addr.to_string(),
+        },
+    );
+    response
+        .rewards
+        .unwrap()
+        .rewards
+        .iter()
+        .map(|c| Decimal::from_atomics(c.amount.parse::<Uint128>().unwrap(), 18).unwrap())
+        .sum()
+}
+
+#[test]
+fn outstanding_rewards_should_include_rewards_and_commission() {
+    let (mut app, [delegator1, ..], validator_addr) = setup();
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+
+    // 10% of 999 tokens, 10% of that is the commission
+    assert_eq!(
+        Decimal::from_ratio(999u128, 10u128),
+        outstanding_rewards(&app, &validator_addr)
+    );
+
+    // withdrawn rewards are not outstanding anymore
+    app.execute(
+        delegator1.clone(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    let balance = app.wrap().query_balance(&delegator1, DENOM).unwrap();
+    assert_eq!(coin(29, DENOM), balance);
+    assert_eq!(
+        Decimal::from_ratio(709u128, 10u128),
+        outstanding_rewards(&app, &validator_addr)
+    );
+}
+
+#[test]
+fn rewards_should_not_exceed_outstanding_rewards_after_slashing() {
+    let (mut app, delegators, validator_addr) = setup();
+    app.sudo(
+        StakingSudo::Slash {
+            validator: validator_addr.to_string(),
+            percentage: Decimal::percent(50),
+        }
+        .into(),
+    )
+    .unwrap();
+    // validator's stake is 499 tokens, delegators' stakes are 166.5 tokens
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+    let expected = Decimal::from_ratio(499u128, 10u128);
+    assert_eq!(expected, outstanding_rewards(&app, &validator_addr));
+
+    // distributing rewards to delegators does not create new tokens
+    app.execute(
+        delegators[0].clone(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    let withdrawn = app.wrap().query_balance(&delegators[0], DENOM).unwrap();
+    assert_eq!(coin(14, DENOM), withdrawn);
+    let outstanding = outstanding_rewards(&app, &validator_addr);
+    assert!(outstanding <= expected - Decimal::from_ratio(14u128, 1u128));
+    assert!(outstanding > Decimal::from_ratio(358u128, 10u128));
+}
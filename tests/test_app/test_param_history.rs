@@ -0,0 +1,56 @@
+use cosmwasm_std::Decimal;
+use cw_multi_test::{
+    next_block, App, ExperimentalFeature, ParamChange, PausableModule, PauseSudo, StakingInfo,
+    StakingSudo,
+};
+
+fn change(height: u64, module: &str, param: &str, value: &str) -> ParamChange {
+    ParamChange {
+        height,
+        module: module.to_string(),
+        param: param.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[test]
+fn parameter_changes_should_be_recorded_with_heights() {
+    let mut app = App::default();
+    let start = app.block_info().height;
+    assert!(app.param_history().unwrap().is_empty());
+
+    app.enable_experimental_feature(ExperimentalFeature::QueryUncommittedState)
+        .unwrap();
+    app.update_block(next_block);
+    app.sudo(
+        StakingSudo::UpdateParams {
+            params: StakingInfo {
+                unbonding_time: 120,
+                apr: Decimal::percent(5),
+                ..StakingInfo::default()
+            },
+        }
+        .into(),
+    )
+    .unwrap();
+    app.update_block(next_block);
+    app.sudo(PauseSudo::Pause(PausableModule::Bank).into())
+        .unwrap();
+    app.update_block(next_block);
+    app.sudo(PauseSudo::Resume(PausableModule::Bank).into())
+        .unwrap();
+    app.disable_experimental_feature(ExperimentalFeature::QueryUncommittedState)
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            change(start, "features", "query_uncommitted_state", "true"),
+            change(start + 1, "staking", "unbonding_time", "120"),
+            change(start + 1, "staking", "apr", "0.05"),
+            change(start + 2, "bank", "paused", "true"),
+            change(start + 3, "bank", "paused", "false"),
+            change(start + 3, "features", "query_uncommitted_state", "false"),
+        ],
+        app.param_history().unwrap()
+    );
+}
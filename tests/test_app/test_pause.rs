@@ -0,0 +1,243 @@
+use cosmwasm_std::{
+    coin, coins, BankMsg, Decimal, IbcMsg, IbcTimeout, StakingMsg, Timestamp, Validator,
+};
+use cw_multi_test::{
+    next_block, no_init, App, AppBuilder, BankSudo, Executor, IbcAcceptingModule, PausableModule,
+    PauseSudo,
+};
+
+#[test]
+fn paused_bank_rejects_transfers() {
+    // prepare the application
+    let mut app = App::default();
+
+    // prepare user addresses
+    let sender_addr = app.api().addr_make("sender");
+    let recipient_addr = app.api().addr_make("recipient");
+
+    // fund the sender
+    app.sudo(
+        BankSudo::Mint {
+            to_address: sender_addr.to_string(),
+            amount: coins(100, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // pause the bank module
+    app.sudo(PauseSudo::Pause(PausableModule::Bank).into())
+        .unwrap();
+
+    // sending tokens should fail with the same error as on a real chain
+    assert_eq!(
+        "send transactions are disabled",
+        app.send_tokens(
+            sender_addr.clone(),
+            recipient_addr.clone(),
+            &coins(10, "uatom")
+        )
+        .unwrap_err()
+        .to_string()
+    );
+
+    // burning tokens is also rejected
+    assert_eq!(
+        "tx type not allowed: bank module is paused",
+        app.execute(
+            sender_addr.clone(),
+            BankMsg::Burn {
+                amount: coins(10, "uatom")
+            }
+            .into()
+        )
+        .unwrap_err()
+        .to_string()
+    );
+
+    // queries are still processed
+    assert_eq!(
+        coin(100, "uatom"),
+        app.wrap().query_balance(&sender_addr, "uatom").unwrap()
+    );
+
+    // resume the bank module
+    app.sudo(PauseSudo::Resume(PausableModule::Bank).into())
+        .unwrap();
+
+    // sending tokens works again
+    app.send_tokens(sender_addr, recipient_addr.clone(), &coins(10, "uatom"))
+        .unwrap();
+    assert_eq!(
+        coin(10, "uatom"),
+        app.wrap().query_balance(recipient_addr, "uatom").unwrap()
+    );
+}
+
+#[test]
+fn paused_ibc_rejects_transfers() {
+    // prepare the application with accepting IBC module
+    let mut app = AppBuilder::default()
+        .with_ibc(IbcAcceptingModule::new())
+        .build(no_init);
+
+    // prepare user addresses
+    let sender_addr = app.api().addr_make("sender");
+
+    let transfer_msg = IbcMsg::Transfer {
+        channel_id: "channel-0".to_string(),
+        to_address: "cosmos1recipient".to_string(),
+        amount: coin(10, "uatom"),
+        timeout: IbcTimeout::with_timestamp(Timestamp::from_seconds(1000)),
+        memo: None,
+    };
+
+    // transfers are accepted until the IBC module is paused
+    app.execute(sender_addr.clone(), transfer_msg.clone().into())
+        .unwrap();
+
+    // pause the IBC module
+    app.sudo(PauseSudo::Pause(PausableModule::Ibc).into())
+        .unwrap();
+
+    assert_eq!(
+        "fungible token transfers from this chain are disabled",
+        app.execute(sender_addr.clone(), transfer_msg.into())
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(
+        "tx type not allowed: ibc module is paused",
+        app.execute(
+            sender_addr,
+            IbcMsg::CloseChannel {
+                channel_id: "channel-0".to_string()
+            }
+            .into()
+        )
+        .unwrap_err()
+        .to_string()
+    );
+}
+
+#[test]
+fn pausing_one_module_does_not_affect_others() {
+    // prepare the application
+    let mut app = App::default();
+
+    // prepare user addresses
+    let sender_addr = app.api().addr_make("sender");
+    let validator_addr = app.api().addr_make("validator");
+
+    // pause the staking module
+    app.sudo(PauseSudo::Pause(PausableModule::Staking).into())
+        .unwrap();
+
+    assert_eq!(
+        "tx type not allowed: staking module is paused",
+        app.execute(
+            sender_addr.clone(),
+            StakingMsg::Delegate {
+                validator: validator_addr.to_string(),
+                amount: coin(10, "TOKEN"),
+            }
+            .into()
+        )
+        .unwrap_err()
+        .to_string()
+    );
+
+    // bank module is not paused, so minted tokens can be burned
+    app.sudo(
+        BankSudo::Mint {
+            to_address: sender_addr.to_string(),
+            amount: coins(100, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.execute(
+        sender_addr,
+        BankMsg::Burn {
+            amount: coins(10, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn paused_bank_does_not_block_unbonding_payouts() {
+    // prepare the application
+    let mut app = App::default();
+
+    // prepare user addresses
+    let delegator_addr = app.api().addr_make("delegator");
+    let validator_addr = app.api().addr_make("validator");
+
+    // fund the delegator and add a validator
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &delegator_addr, coins(100, "TOKEN"))
+            .unwrap();
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(100),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+    });
+
+    // delegate and undelegate tokens
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, "TOKEN"),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, "TOKEN"),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // pause the bank module
+    app.sudo(PauseSudo::Pause(PausableModule::Bank).into())
+        .unwrap();
+
+    // unbonded tokens are released by the staking module, even when transfers are disabled
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(60);
+    });
+    app.update_block(next_block);
+    assert_eq!(
+        coin(100, "TOKEN"),
+        app.wrap().query_balance(&delegator_addr, "TOKEN").unwrap()
+    );
+
+    // the delegator can not transfer the released tokens
+    assert_eq!(
+        "send transactions are disabled",
+        app.send_tokens(delegator_addr, validator_addr, &coins(10, "TOKEN"))
+            .unwrap_err()
+            .to_string()
+    );
+}
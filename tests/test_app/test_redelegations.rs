@@ -0,0 +1,310 @@
+use super::common::staking::{
+    advance_seconds, delegate, init_staking, try_grpc_query, validator, DENOM,
+};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{coin, Addr, AnyMsg, CosmosMsg, Empty, StakingMsg};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{AppResponse, Executor, RedelegationEntry};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoTimestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgBeginRedelegate {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_src_address: String,
+    #[prost(string, tag = "3")]
+    pub validator_dst_address: String,
+    #[prost(message, optional, tag = "4")]
+    pub amount: Option<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoRedelegationEntry {
+    #[prost(int64, tag = "1")]
+    pub creation_height: i64,
+    #[prost(message, optional, tag = "2")]
+    pub completion_time: Option<ProtoTimestamp>,
+    #[prost(string, tag = "3")]
+    pub initial_balance: String,
+    #[prost(string, tag = "4")]
+    pub shares_dst: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoRedelegation {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_src_address: String,
+    #[prost(string, tag = "3")]
+    pub validator_dst_address: String,
+    #[prost(message, repeated, tag = "4")]
+    pub entries: Vec<ProtoRedelegationEntry>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RedelegationEntryResponse {
+    #[prost(message, optional, tag = "1")]
+    pub redelegation_entry: Option<ProtoRedelegationEntry>,
+    #[prost(string, tag = "4")]
+    pub balance: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RedelegationResponse {
+    #[prost(message, optional, tag = "1")]
+    pub redelegation: Option<ProtoRedelegation>,
+    #[prost(message, repeated, tag = "2")]
+    pub entries: Vec<RedelegationEntryResponse>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryRedelegationsRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_addr: String,
+    #[prost(string, tag = "2")]
+    pub src_validator_addr: String,
+    #[prost(string, tag = "3")]
+    pub dst_validator_addr: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryRedelegationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub redelegation_responses: Vec<RedelegationResponse>,
+}
+
+/// Prepares the application with three validators and the owner delegating
+/// 100 tokens to the first one.
+fn setup() -> (SdkApp, Addr, [Addr; 3]) {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    let validators = ["validator1", "validator2", "validator3"].map(|v| app.api().addr_make(v));
+    let validator_set = validators
+        .iter()
+        .map(|addr| validator(addr, 10))
+        .collect::<Vec<_>>();
+    init_staking(&mut app, &[(&owner_addr, 100)], &validator_set);
+    delegate(&mut app, &owner_addr, &validators[0], 100);
+    (app, owner_addr, validators)
+}
+
+fn redelegate(
+    app: &mut SdkApp,
+    owner_addr: &Addr,
+    src_validator: &Addr,
+    dst_validator: &Addr,
+    amount: u128,
+) -> AnyResult<AppResponse> {
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Redelegate {
+            src_validator: src_validator.to_string(),
+            dst_validator: dst_validator.to_string(),
+            amount: coin(amount, DENOM),
+        }
+        .into(),
+    )
+}
+
+fn redelegation_entries(app: &SdkApp, addr: &Addr) -> Vec<RedelegationEntry> {
+    app.read_module(|router, _, storage| router.staking.redelegation_entries(storage, addr))
+        .unwrap()
+}
+
+fn query_redelegations(
+    app: &SdkApp,
+    request: QueryRedelegationsRequest,
+) -> Result<QueryRedelegationsResponse, String> {
+    try_grpc_query(app, "/cosmos.staking.v1beta1.Query/Redelegations", request)
+}
+
+#[test]
+fn redelegations_should_complete_after_unbonding_time() {
+    let (mut app, owner_addr, [validator1, validator2, validator3]) = setup();
+    let block = app.block_info();
+
+    let res = redelegate(&mut app, &owner_addr, &validator1, &validator2, 40).unwrap();
+    let event = res.events.iter().find(|e| e.ty == "redelegate").unwrap();
+    assert!(event
+        .attributes
+        .iter()
+        .any(|a| a.key == "completion_time" && a.value == "2019-10-23T02:24:39Z"));
+    assert_eq!(
+        vec![RedelegationEntry {
+            delegator: owner_addr.clone(),
+            src_validator: validator1.clone(),
+            dst_validator: validator2.clone(),
+            creation_height: block.height,
+            completion_time: block.time.plus_seconds(60),
+            balance: coin(40, DENOM),
+        }],
+        redelegation_entries(&app, &owner_addr)
+    );
+
+    // redelegated tokens are bonded to the destination validator immediately
+    let delegation = app
+        .wrap()
+        .query_delegation(&owner_addr, &validator2)
+        .unwrap()
+        .unwrap();
+    assert_eq!(coin(40, DENOM), delegation.amount);
+
+    // redelegated tokens can not be redelegated again until the redelegation completes
+    let err = redelegate(&mut app, &owner_addr, &validator2, &validator3, 10).unwrap_err();
+    assert_eq!(
+        "redelegation to this validator already in progress; first redelegation to this validator must complete before next redelegation",
+        err.root_cause().to_string()
+    );
+
+    advance_seconds(&mut app, 60);
+    advance_seconds(&mut app, 1);
+    assert!(redelegation_entries(&app, &owner_addr).is_empty());
+    redelegate(&mut app, &owner_addr, &validator2, &validator3, 10).unwrap();
+}
+
+#[test]
+fn redelegation_entries_should_be_limited() {
+    let (mut app, owner_addr, [validator1, validator2, _]) = setup();
+
+    for _ in 0..7 {
+        redelegate(&mut app, &owner_addr, &validator1, &validator2, 1).unwrap();
+        advance_seconds(&mut app, 1);
+    }
+    let err = redelegate(&mut app, &owner_addr, &validator1, &validator2, 1).unwrap_err();
+    assert_eq!(
+        "too many redelegation entries for (delegator, src-validator, dst-validator) tuple",
+        err.root_cause().to_string()
+    );
+
+    let err = redelegate(&mut app, &owner_addr, &validator1, &validator1, 1).unwrap_err();
+    assert_eq!(
+        "cannot redelegate to the same validator",
+        err.root_cause().to_string()
+    );
+
+    // the oldest entry completes, so the next redelegation is accepted
+    advance_seconds(&mut app, 53);
+    advance_seconds(&mut app, 1);
+    assert_eq!(6, redelegation_entries(&app, &owner_addr).len());
+    redelegate(&mut app, &owner_addr, &validator1, &validator2, 1).unwrap();
+}
+
+#[test]
+fn begin_redelegate_should_be_executed() {
+    let (mut app, owner_addr, [validator1, validator2, _]) = setup();
+    let msg = MsgBeginRedelegate {
+        delegator_address: owner_addr.to_string(),
+        validator_src_address: validator1.to_string(),
+        validator_dst_address: validator2.to_string(),
+        amount: Some(ProtoCoin {
+            denom: DENOM.to_string(),
+            amount: "30".to_string(),
+        }),
+    };
+    let msg = CosmosMsg::<Empty>::Any(AnyMsg {
+        type_url: "/cosmos.staking.v1beta1.MsgBeginRedelegate".to_string(),
+        value: msg.encode_to_vec().into(),
+    });
+
+    // only the delegator can redelegate its tokens
+    let err = app
+        .execute(app.api().addr_make("thief"), msg.clone())
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .starts_with("delegator address"));
+
+    app.execute(owner_addr.clone(), msg).unwrap();
+    let entries = redelegation_entries(&app, &owner_addr);
+    assert_eq!(1, entries.len());
+    assert_eq!(coin(30, DENOM), entries[0].balance);
+}
+
+#[test]
+fn redelegations_should_be_queried() {
+    let (mut app, owner_addr, [validator1, validator2, validator3]) = setup();
+    let block = app.block_info();
+    redelegate(&mut app, &owner_addr, &validator1, &validator2, 10).unwrap();
+    redelegate(&mut app, &owner_addr, &validator1, &validator3, 20).unwrap();
+
+    let entry = |amount: &str| RedelegationEntryResponse {
+        redelegation_entry: Some(ProtoRedelegationEntry {
+            creation_height: block.height as i64,
+            completion_time: Some(ProtoTimestamp {
+                seconds: block.time.plus_seconds(60).seconds() as i64,
+                nanos: block.time.subsec_nanos() as i32,
+            }),
+            initial_balance: amount.to_string(),
+            shares_dst: format!("{}000000000000000000", amount),
+        }),
+        balance: amount.to_string(),
+    };
+    let response = |dst_validator: &Addr, amount: &str| RedelegationResponse {
+        redelegation: Some(ProtoRedelegation {
+            delegator_address: owner_addr.to_string(),
+            validator_src_address: validator1.to_string(),
+            validator_dst_address: dst_validator.to_string(),
+            entries: entry(amount).redelegation_entry.into_iter().collect(),
+        }),
+        entries: vec![entry(amount)],
+    };
+
+    let mut expected = vec![response(&validator2, "10"), response(&validator3, "20")];
+    expected.sort_by(|a, b| {
+        let dst = |r: &RedelegationResponse| r.redelegation.clone().unwrap().validator_dst_address;
+        dst(a).cmp(&dst(b))
+    });
+    let all = query_redelegations(
+        &app,
+        QueryRedelegationsRequest {
+            delegator_addr: owner_addr.to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(expected, all.redelegation_responses);
+
+    let single = query_redelegations(
+        &app,
+        QueryRedelegationsRequest {
+            delegator_addr: owner_addr.to_string(),
+            src_validator_addr: validator1.to_string(),
+            dst_validator_addr: validator3.to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        vec![response(&validator3, "20")],
+        single.redelegation_responses
+    );
+
+    let err = query_redelegations(
+        &app,
+        QueryRedelegationsRequest {
+            delegator_addr: owner_addr.to_string(),
+            src_validator_addr: validator2.to_string(),
+            dst_validator_addr: validator3.to_string(),
+        },
+    )
+    .unwrap_err();
+    assert!(err.contains("redelegation not found"));
+}
@@ -0,0 +1,65 @@
+use cosmwasm_std::{coins, BankMsg, Empty, Response};
+use cw_multi_test::{App, Executor, MockContract};
+
+fn setup() -> (App, u64) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner_addr, coins(100, "uatom"))
+            .unwrap();
+    });
+    let code_id = app.store_code(Box::new(
+        MockContract::new().on_execute(&Empty {}, Response::new()),
+    ));
+    (app, code_id)
+}
+
+#[test]
+fn recorded_scenario_should_be_exported_as_rust_code() {
+    let (mut app, code_id) = setup();
+    let owner_addr = app.api().addr_make("owner");
+    let recipient_addr = app.api().addr_make("recipient");
+    app.enable_tracing();
+
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "mock", None)
+        .unwrap();
+    app.execute_contract(
+        owner_addr.clone(),
+        contract_addr.clone(),
+        &Empty {},
+        &coins(10, "uatom"),
+    )
+    .unwrap();
+    app.execute(
+        owner_addr.clone(),
+        BankMsg::Send {
+            to_address: recipient_addr.to_string(),
+            amount: coins(1000, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap_err();
+
+    let code = app.export_regression_test("replay_transfers").unwrap();
+
+    assert!(code.starts_with(
+        "use cosmwasm_std::{coin, from_json, Addr, CosmosMsg};\nuse cw_multi_test::{App, Executor};"
+    ));
+    assert!(code.contains("pub fn replay_transfers(app: &mut App) {"));
+    assert!(code.contains(&format!(
+        "    let addr_0 = Addr::unchecked(\"{}\");",
+        owner_addr
+    )));
+    assert!(code.contains("    // step 2: execute {}\n"));
+    assert!(code.contains("    let err = app.execute(addr_0.clone(), msg).unwrap_err();"));
+    assert!(code.contains(
+        "    assert_eq!(\"Cannot Sub with given operands\", err.root_cause().to_string());"
+    ));
+    assert!(code.contains(
+        "    assert_eq!(app.wrap().query_all_balances(&addr_0).unwrap(), vec![coin(90, \"uatom\")]);"
+    ));
+    assert!(code.ends_with("}"));
+}
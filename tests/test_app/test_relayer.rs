@@ -0,0 +1,138 @@
+use super::common::ibc::{execute_contract, setup, state, ExecMsg};
+use cosmwasm_std::{Binary, IbcOrder};
+use cw_multi_test::Relayer;
+
+const VERSION: &str = "ping-1";
+
+#[test]
+fn packets_should_be_relayed_between_chains() {
+    let (mut chain, controller_addr, controller_port) = setup(VERSION);
+    let (mut counterparty, host_addr, host_port) = setup(VERSION);
+    let relayer = Relayer::new(&mut chain, &mut counterparty).unwrap();
+    assert_eq!(
+        ("07-tendermint-0", "connection-0"),
+        (relayer.client_id(), relayer.connection_id())
+    );
+
+    let (channel_id, counterparty_channel_id) = relayer
+        .open_channel(
+            &mut chain,
+            &mut counterparty,
+            &controller_port,
+            &host_port,
+            VERSION,
+            IbcOrder::Unordered,
+        )
+        .unwrap();
+    let channel_end = chain.channel_end(&channel_id).unwrap().unwrap();
+    assert_eq!(relayer.connection_id(), channel_end.channel.connection_id);
+    assert_eq!(
+        counterparty_channel_id,
+        channel_end.channel.counterparty_endpoint.channel_id
+    );
+    let counterparty_channel_end = counterparty
+        .channel_end(&counterparty_channel_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        relayer.counterparty_connection_id(),
+        counterparty_channel_end.channel.connection_id
+    );
+
+    // the ping is received by the host, which answers with the pong
+    let ping = ExecMsg::Send {
+        text: "ping hello".to_string(),
+        timeout_height: None,
+        src_callback: None,
+        dest_callback: None,
+    };
+    execute_contract(&mut chain, &controller_addr, &ping).unwrap();
+
+    // packets sent over the connection are not relayed by the localhost client
+    assert!(chain.relay_localhost_packets().unwrap().is_empty());
+    assert!(state(&counterparty, &host_addr).received.is_empty());
+
+    let responses = relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap();
+    assert_eq!(2, responses.len());
+    assert_eq!(
+        Some(Binary::from(b"ack ping hello".to_vec())),
+        responses[0].data
+    );
+    let host_state = state(&counterparty, &host_addr);
+    assert_eq!(vec!["ping hello"], host_state.received);
+    assert_eq!(vec!["ack pong hello"], host_state.acks);
+    let controller_state = state(&chain, &controller_addr);
+    assert_eq!(vec!["pong hello"], controller_state.received);
+    assert_eq!(vec!["ack ping hello"], controller_state.acks);
+
+    // nothing more to relay
+    assert!(relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn failed_receive_should_not_block_later_packets() {
+    let (mut chain, controller_addr, controller_port) = setup(VERSION);
+    let (mut counterparty, host_addr, host_port) = setup(VERSION);
+    let relayer = Relayer::new(&mut chain, &mut counterparty).unwrap();
+    relayer
+        .open_channel(
+            &mut chain,
+            &mut counterparty,
+            &controller_port,
+            &host_port,
+            VERSION,
+            IbcOrder::Unordered,
+        )
+        .unwrap();
+
+    // the host fails to receive the first packet
+    let height = counterparty.block_info().height;
+    execute_contract(
+        &mut chain,
+        &controller_addr,
+        &ExecMsg::send("fail", height + 10),
+    )
+    .unwrap();
+    execute_contract(
+        &mut chain,
+        &controller_addr,
+        &ExecMsg::send("hello", height + 10),
+    )
+    .unwrap();
+    execute_contract(
+        &mut counterparty,
+        &host_addr,
+        &ExecMsg::send("hi", height + 10),
+    )
+    .unwrap();
+
+    let responses = relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap();
+    assert_eq!(3, responses.len());
+
+    // changes of the failed receive are discarded, the error is acknowledged to the controller
+    let host_state = state(&counterparty, &host_addr);
+    assert_eq!(vec!["hello"], host_state.received);
+    assert_eq!(vec!["ack hi"], host_state.acks);
+    let controller_state = state(&chain, &controller_addr);
+    assert_eq!(vec!["hi"], controller_state.received);
+    assert_eq!(
+        vec![
+            r#"{"error":"Generic error: cannot receive fail"}"#,
+            "ack hello"
+        ],
+        controller_state.acks
+    );
+
+    // nothing more to relay
+    assert!(relayer
+        .relay_packets(&mut chain, &mut counterparty)
+        .unwrap()
+        .is_empty());
+}
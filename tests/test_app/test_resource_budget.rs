@@ -0,0 +1,185 @@
+use cosmwasm_std::{
+    coins, to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdError, StdResult, WasmMsg,
+};
+use cw_multi_test::{
+    next_block, App, BankSudo, ContractWrapper, Executor, ResourceBudget, ResourceUsage,
+};
+
+fn funded_app() -> (App, Addr, Addr) {
+    let mut app = App::default();
+    let sender_addr = app.api().addr_make("sender");
+    let recipient_addr = app.api().addr_make("recipient");
+    app.sudo(
+        BankSudo::Mint {
+            to_address: sender_addr.to_string(),
+            amount: coins(1000, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap();
+    (app, sender_addr, recipient_addr)
+}
+
+#[test]
+fn resources_are_counted_only_when_budget_is_set() {
+    let (mut app, sender_addr, recipient_addr) = funded_app();
+
+    // no budget, no usage
+    app.send_tokens(
+        sender_addr.clone(),
+        recipient_addr.clone(),
+        &coins(1, "uatom"),
+    )
+    .unwrap();
+    app.update_block(next_block);
+    assert_eq!(ResourceUsage::default(), app.resource_usage());
+
+    // resources are counted since the budget was set
+    app.set_resource_budget(ResourceBudget::new());
+    let send = CosmosMsg::from(BankMsg::Send {
+        to_address: recipient_addr.to_string(),
+        amount: coins(1, "uatom"),
+    });
+    app.execute_multi(sender_addr, vec![send.clone(), send])
+        .unwrap();
+    app.update_block(next_block);
+    let usage = app.resource_usage();
+    assert_eq!(2, usage.msgs);
+    assert_eq!(1, usage.blocks);
+    assert!(usage.gas > 0);
+
+    // clearing the budget stops counting
+    app.clear_resource_budget();
+    assert_eq!(ResourceUsage::default(), app.resource_usage());
+}
+
+#[test]
+#[should_panic(expected = "resource budget exceeded: too many messages")]
+fn exceeding_messages_budget_should_panic() {
+    let (mut app, sender_addr, recipient_addr) = funded_app();
+    app.set_resource_budget(ResourceBudget::new().with_max_msgs(3));
+    for _ in 0..4 {
+        app.send_tokens(
+            sender_addr.clone(),
+            recipient_addr.clone(),
+            &coins(1, "uatom"),
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+#[should_panic(expected = "resource budget exceeded: out of gas")]
+fn exceeding_gas_budget_should_panic() {
+    let (mut app, sender_addr, recipient_addr) = funded_app();
+    app.set_resource_budget(ResourceBudget::new().with_max_gas(10_000));
+    loop {
+        app.send_tokens(
+            sender_addr.clone(),
+            recipient_addr.clone(),
+            &coins(1, "uatom"),
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+#[should_panic(expected = "resource budget exceeded: too many blocks")]
+fn exceeding_blocks_budget_should_panic() {
+    let mut app = App::default();
+    app.set_resource_budget(ResourceBudget::new().with_max_blocks(10));
+    loop {
+        app.update_block(next_block);
+    }
+}
+
+/// Contract writing to its storage and forwarding the call to the contract
+/// set in the instantiation message, if any.
+mod forwarder {
+    use super::*;
+
+    pub fn instantiate(
+        deps: DepsMut,
+        _: Env,
+        _: MessageInfo,
+        target: Option<String>,
+    ) -> StdResult<Response> {
+        if let Some(target) = target {
+            deps.storage.set(b"target", target.as_bytes());
+        }
+        Ok(Response::default())
+    }
+
+    pub fn execute(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        deps.storage.set(b"counter", &[1; 100]);
+        let response = Response::default();
+        Ok(match deps.storage.get(b"target") {
+            Some(target) => response.add_message(WasmMsg::Execute {
+                contract_addr: String::from_utf8(target)?,
+                msg: to_json_binary(&Empty {})?,
+                funds: vec![],
+            }),
+            None => response,
+        })
+    }
+
+    pub fn query(_: Deps, _: Env, _: Empty) -> Result<Binary, StdError> {
+        to_json_binary(&Empty {})
+    }
+}
+
+#[test]
+fn gas_free_contracts_should_not_be_charged() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(Box::new(ContractWrapper::new(
+        forwarder::execute,
+        forwarder::instantiate,
+        forwarder::query,
+    )));
+    let target_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &None::<String>,
+            &[],
+            "target",
+            None,
+        )
+        .unwrap();
+    let system_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Some(target_addr.to_string()),
+            &[],
+            "system",
+            None,
+        )
+        .unwrap();
+    app.set_resource_budget(ResourceBudget::new());
+
+    // gas used by the system contract and the contract it calls
+    app.execute_contract(owner_addr.clone(), system_addr.clone(), &Empty {}, &[])
+        .unwrap();
+    assert!(app.resource_usage().gas > 0);
+
+    // gas-free system contract is not charged, also for calling other contracts
+    app.add_gas_free_contract(system_addr.clone());
+    assert_eq!(vec![system_addr.clone()], app.gas_free_contracts());
+    app.set_resource_budget(ResourceBudget::new());
+    app.execute_contract(owner_addr.clone(), system_addr.clone(), &Empty {}, &[])
+        .unwrap();
+    let usage = app.resource_usage();
+    assert_eq!(0, usage.gas);
+    assert_eq!(2, usage.msgs);
+
+    // contract under test is charged when called directly
+    app.execute_contract(owner_addr.clone(), target_addr.clone(), &Empty {}, &[])
+        .unwrap();
+    assert!(app.resource_usage().gas > 0);
+
+    app.remove_gas_free_contract(&system_addr);
+    assert!(app.gas_free_contracts().is_empty());
+}
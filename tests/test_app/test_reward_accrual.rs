@@ -0,0 +1,86 @@
+use super::common::staking::{delegate, init_staking, rewards, validator, DENOM};
+use cosmwasm_std::{coin, coins, Addr, Decimal, StakingMsg, Uint128};
+use cw_multi_test::{App, Executor, RewardAccrual, StakingSudo};
+
+const YEAR: u64 = 60 * 60 * 24 * 365;
+
+/// Prepares the application with two validators without commission,
+/// the owner delegates 1000 tokens to each of them.
+fn setup() -> (App, Addr, [Addr; 2]) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let validators = ["validator1", "validator2"].map(|v| app.api().addr_make(v));
+    let validator_set = validators
+        .iter()
+        .map(|addr| validator(addr, 0))
+        .collect::<Vec<_>>();
+    init_staking(&mut app, &[(&owner_addr, 2000)], &validator_set);
+    for validator_addr in &validators {
+        delegate(&mut app, &owner_addr, validator_addr, 1000);
+    }
+    (app, owner_addr, validators)
+}
+
+fn set_accrual(app: &mut App, validator_addr: &Addr, accrual: Option<RewardAccrual>) {
+    app.sudo(
+        StakingSudo::SetRewardAccrual {
+            validator: validator_addr.to_string(),
+            accrual,
+        }
+        .into(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn validators_should_accrue_rewards_at_own_rates() {
+    let (mut app, owner_addr, [validator1, validator2]) = setup();
+    set_accrual(
+        &mut app,
+        &validator1,
+        Some(RewardAccrual::Apr(Decimal::percent(20))),
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+    assert_eq!(200, rewards(&app, &owner_addr, &validator1));
+    // other validators accrue rewards at the rate from staking parameters
+    assert_eq!(100, rewards(&app, &owner_addr, &validator2));
+
+    // accrued rewards are kept when the rate is restored
+    set_accrual(&mut app, &validator1, None);
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+    assert_eq!(300, rewards(&app, &owner_addr, &validator1));
+}
+
+#[test]
+fn per_block_provision_should_be_split_between_delegators() {
+    let (mut app, owner_addr, [validator1, _]) = setup();
+    let other_addr = app.api().addr_make("other");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &other_addr, coins(3000, DENOM))
+    })
+    .unwrap();
+    app.execute(
+        other_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator1.to_string(),
+            amount: coin(3000, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    set_accrual(
+        &mut app,
+        &validator1,
+        Some(RewardAccrual::PerBlock(Uint128::new(100))),
+    );
+
+    // provision does not depend on the time between blocks
+    for _ in 0..10 {
+        app.update_block(|block| block.height += 1);
+    }
+    assert_eq!(250, rewards(&app, &owner_addr, &validator1));
+    assert_eq!(750, rewards(&app, &other_addr, &validator1));
+}
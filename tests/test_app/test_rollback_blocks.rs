@@ -0,0 +1,72 @@
+use cosmwasm_std::coins;
+use cw_multi_test::{next_block, App, BankSudo, Executor};
+
+#[test]
+fn rolled_back_blocks_should_rewind_state_and_height() {
+    let mut app = App::default();
+    let sender_addr = app.api().addr_make("sender");
+    let recipient_addr = app.api().addr_make("recipient");
+    app.sudo(
+        BankSudo::Mint {
+            to_address: sender_addr.to_string(),
+            amount: coins(100, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap();
+    let balance = |app: &App| {
+        app.wrap()
+            .query_balance(&recipient_addr, "uatom")
+            .unwrap()
+            .amount
+            .u128()
+    };
+
+    // snapshots are not recorded by default
+    app.update_block(next_block);
+    assert_eq!(
+        "block snapshots are not enabled",
+        app.rollback_blocks(1).unwrap_err().to_string()
+    );
+
+    // send tokens in every block
+    app.enable_block_snapshots(3);
+    let start_height = app.block_info().height;
+    for _ in 0..4 {
+        app.send_tokens(
+            sender_addr.clone(),
+            recipient_addr.clone(),
+            &coins(10, "uatom"),
+        )
+        .unwrap();
+        app.update_block(next_block);
+    }
+    app.send_tokens(
+        sender_addr.clone(),
+        recipient_addr.clone(),
+        &coins(10, "uatom"),
+    )
+    .unwrap();
+    assert_eq!(50, balance(&app));
+    assert_eq!(start_height + 4, app.block_info().height);
+    assert_eq!(3, app.block_snapshots_count());
+
+    // only recorded blocks can be rolled back
+    assert_eq!(
+        "cannot rollback 4 blocks, 3 block snapshots available",
+        app.rollback_blocks(4).unwrap_err().to_string()
+    );
+
+    // rewind the last two blocks, discarding the transfer from the current block
+    app.rollback_blocks(2).unwrap();
+    assert_eq!(start_height + 2, app.block_info().height);
+    assert_eq!(30, balance(&app));
+    assert_eq!(1, app.block_snapshots_count());
+
+    // the chain continues from the restored block
+    app.update_block(next_block);
+    assert_eq!(start_height + 3, app.block_info().height);
+    app.rollback_blocks(2).unwrap();
+    assert_eq!(start_height + 1, app.block_info().height);
+    assert_eq!(20, balance(&app));
+}
@@ -0,0 +1,161 @@
+#![cfg(feature = "rpc")]
+
+use crate::test_contracts;
+use cosmwasm_std::{coins, to_json_binary, Addr, Empty};
+use cw_multi_test::{App, BankSudo, Executor, RpcFacade, RpcLimits};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+fn setup() -> (App, Addr, Addr) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    app.sudo(
+        BankSudo::Mint {
+            to_address: owner_addr.to_string(),
+            amount: coins(100, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap();
+    let code_id = app.store_code(test_contracts::counter::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    (app, owner_addr, contract_addr)
+}
+
+#[test]
+fn smart_query_should_work() {
+    let (app, _, contract_addr) = setup();
+    let rpc = RpcFacade::new(&app);
+
+    let query_data = to_json_binary(&test_contracts::counter::CounterQueryMsg::Counter {})
+        .unwrap()
+        .to_base64()
+        .replace('=', "%3D");
+    let response = rpc.handle(
+        "GET",
+        &format!(
+            "/cosmwasm/wasm/v1/contract/{}/smart/{}",
+            contract_addr, query_data
+        ),
+    );
+    assert_eq!(200, response.status);
+    assert_eq!(r#"{"data":{"value":1}}"#, response.body);
+
+    // invalid query is reported as an error
+    let query_data = to_json_binary(&Empty {}).unwrap().to_base64();
+    let response = rpc.handle(
+        "GET",
+        &format!(
+            "/cosmwasm/wasm/v1/contract/{}/smart/{}",
+            contract_addr, query_data
+        ),
+    );
+    assert_eq!(500, response.status);
+    assert!(response.body.starts_with(r#"{"code":2,"message":"#));
+}
+
+#[test]
+fn bank_queries_should_work() {
+    let (app, owner_addr, _) = setup();
+    let rpc = RpcFacade::new(&app);
+
+    let response = rpc.handle(
+        "GET",
+        &format!("/cosmos/bank/v1beta1/balances/{}", owner_addr),
+    );
+    assert_eq!(200, response.status);
+    assert_eq!(
+        r#"{"balances":[{"denom":"uatom","amount":"100"}],"pagination":{"next_key":null,"total":"1"}}"#,
+        response.body
+    );
+
+    let response = rpc.handle(
+        "GET",
+        &format!(
+            "/cosmos/bank/v1beta1/balances/{}/by_denom?denom=uatom",
+            owner_addr
+        ),
+    );
+    assert_eq!(200, response.status);
+    assert_eq!(
+        r#"{"balance":{"denom":"uatom","amount":"100"}}"#,
+        response.body
+    );
+
+    let response = rpc.handle(
+        "GET",
+        &format!("/cosmos/bank/v1beta1/balances/{}/by_denom", owner_addr),
+    );
+    assert_eq!(400, response.status);
+}
+
+#[test]
+fn unsupported_requests_should_fail() {
+    let (app, owner_addr, _) = setup();
+    let rpc = RpcFacade::new(&app);
+
+    assert_eq!(
+        404,
+        rpc.handle("GET", "/cosmos/staking/v1beta1/params").status
+    );
+    assert_eq!(
+        405,
+        rpc.handle(
+            "POST",
+            &format!("/cosmos/bank/v1beta1/balances/{}", owner_addr)
+        )
+        .status
+    );
+
+    // response exceeding the limit is rejected
+    let rpc = rpc.with_limits(RpcLimits {
+        max_response_size: 16,
+        ..Default::default()
+    });
+    let response = rpc.handle(
+        "GET",
+        &format!("/cosmos/bank/v1beta1/balances/{}", owner_addr),
+    );
+    assert_eq!(500, response.status);
+}
+
+#[test]
+fn serving_over_http_should_work() {
+    let (app, owner_addr, _) = setup();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let client = std::thread::spawn(move || {
+        let send = |request: String| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let balances = send(format!(
+            "GET /cosmos/bank/v1beta1/balances/{}/by_denom?denom=uatom HTTP/1.1\r\nHost: localhost\r\n\r\n",
+            owner_addr
+        ));
+        let oversized = send(format!(
+            "GET / HTTP/1.1\r\nHost: localhost\r\nX-Padding: {}\r\n\r\n",
+            "x".repeat(400)
+        ));
+        (balances, oversized)
+    });
+
+    RpcFacade::new(&app)
+        .with_limits(RpcLimits {
+            max_request_size: 256,
+            ..Default::default()
+        })
+        .serve(&listener, 2)
+        .unwrap();
+
+    let (balances, oversized) = client.join().unwrap();
+    assert!(balances.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(balances.ends_with(r#"{"balance":{"denom":"uatom","amount":"100"}}"#));
+    assert!(oversized.starts_with("HTTP/1.1 431 "));
+}
@@ -0,0 +1,256 @@
+use super::common::staking::grpc_query;
+use cosmwasm_std::{coin, coins, Addr, BankMsg, Decimal, Empty, StakingMsg, Validator};
+use cw_multi_test::{next_block, App, BankParams, BankSudo, Executor, MockContract, SendEnabled};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoSendEnabled {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(bool, tag = "2")]
+    pub enabled: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoParams {
+    #[prost(message, repeated, tag = "1")]
+    pub send_enabled: Vec<ProtoSendEnabled>,
+    #[prost(bool, tag = "2")]
+    pub default_send_enabled: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryParamsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub params: Option<ProtoParams>,
+}
+
+fn set_send_enabled(app: &mut App, denom: &str, enabled: bool) {
+    app.sudo(
+        BankSudo::SetSendEnabled {
+            denom: denom.to_string(),
+            enabled,
+        }
+        .into(),
+    )
+    .unwrap();
+}
+
+fn is_send_enabled(app: &App, denom: &str) -> bool {
+    app.read_module(|router, _, storage| router.bank.is_send_enabled(storage, denom))
+        .unwrap()
+}
+
+fn setup() -> (App, Addr, Addr) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let recipient_addr = app.api().addr_make("recipient");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &owner_addr,
+                vec![coin(100, "uatom"), coin(100, "ulocked")],
+            )
+            .unwrap();
+    });
+    (app, owner_addr, recipient_addr)
+}
+
+#[test]
+fn transfers_of_disabled_denominations_should_fail() {
+    let (mut app, owner_addr, recipient_addr) = setup();
+    assert!(is_send_enabled(&app, "ulocked"));
+    set_send_enabled(&mut app, "ulocked", false);
+    assert!(!is_send_enabled(&app, "ulocked"));
+    assert!(is_send_enabled(&app, "uatom"));
+
+    let err = app
+        .send_tokens(
+            owner_addr.clone(),
+            recipient_addr.clone(),
+            &[coin(10, "uatom"), coin(10, "ulocked")],
+        )
+        .unwrap_err();
+    assert_eq!(
+        "ulocked transfers are currently disabled: send transactions are disabled",
+        err.root_cause().to_string()
+    );
+    app.send_tokens(
+        owner_addr.clone(),
+        recipient_addr.clone(),
+        &coins(10, "uatom"),
+    )
+    .unwrap();
+
+    // funds attached to contract calls are transfers too
+    let code_id = app.store_code(Box::new(MockContract::new()));
+    let err = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &coins(10, "ulocked"),
+            "mock",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        "ulocked transfers are currently disabled: send transactions are disabled",
+        err.root_cause().to_string()
+    );
+
+    // disabled denomination can still be burned
+    app.execute(
+        owner_addr.clone(),
+        BankMsg::Burn {
+            amount: coins(10, "ulocked"),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // transfers can be enabled again
+    set_send_enabled(&mut app, "ulocked", true);
+    app.send_tokens(owner_addr, recipient_addr.clone(), &coins(10, "ulocked"))
+        .unwrap();
+    assert_eq!(
+        vec![coin(10, "uatom"), coin(10, "ulocked")],
+        app.wrap().query_all_balances(recipient_addr).unwrap()
+    );
+
+    let history = app.param_history().unwrap();
+    assert_eq!(
+        vec!["false", "true"],
+        history
+            .iter()
+            .filter(|change| change.module == "bank" && change.param == "send_enabled/ulocked")
+            .map(|change| change.value.as_str())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn bank_params_should_be_updated_and_queried() {
+    let (mut app, owner_addr, recipient_addr) = setup();
+    assert_eq!(
+        BankParams::default(),
+        app.read_module(|router, _, storage| router.bank.params(storage))
+            .unwrap()
+    );
+    set_send_enabled(&mut app, "ulocked", false);
+
+    // transfers are disabled by default, only listed denominations can be transferred
+    app.sudo(
+        BankSudo::SetParams {
+            params: BankParams {
+                send_enabled: vec![SendEnabled {
+                    denom: "uatom".to_string(),
+                    enabled: true,
+                }],
+                default_send_enabled: false,
+            },
+        }
+        .into(),
+    )
+    .unwrap();
+    app.send_tokens(
+        owner_addr.clone(),
+        recipient_addr.clone(),
+        &coins(10, "uatom"),
+    )
+    .unwrap();
+    app.send_tokens(owner_addr, recipient_addr, &coins(10, "ulocked"))
+        .unwrap_err();
+    assert!(!is_send_enabled(&app, "uosmo"));
+
+    // parameters are answered to the gRPC query
+    let response: QueryParamsResponse = grpc_query(&app, "/cosmos.bank.v1beta1.Query/Params", ());
+    assert_eq!(
+        ProtoParams {
+            send_enabled: vec![ProtoSendEnabled {
+                denom: "uatom".to_string(),
+                enabled: true,
+            }],
+            default_send_enabled: false,
+        },
+        response.params.unwrap()
+    );
+
+    // changes of effective flags are recorded
+    let history = app.param_history().unwrap();
+    assert_eq!(
+        vec![
+            ("send_enabled/ulocked", "false"),
+            ("default_send_enabled", "false"),
+        ],
+        history
+            .iter()
+            .filter(|change| change.module == "bank")
+            .map(|change| (change.param.as_str(), change.value.as_str()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn unbonded_tokens_should_be_released_when_transfers_are_disabled() {
+    let mut app = App::default();
+    let delegator_addr = app.api().addr_make("delegator");
+    let validator_addr = app.api().addr_make("validator");
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &delegator_addr, coins(100, "TOKEN"))
+            .unwrap();
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(100),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+    });
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, "TOKEN"),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.execute(
+        delegator_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(100, "TOKEN"),
+        }
+        .into(),
+    )
+    .unwrap();
+    set_send_enabled(&mut app, "TOKEN", false);
+
+    // unbonded tokens are paid out by the staking module, not sent by the user
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(60);
+    });
+    app.update_block(next_block);
+    assert_eq!(
+        coin(100, "TOKEN"),
+        app.wrap().query_balance(&delegator_addr, "TOKEN").unwrap()
+    );
+
+    // the delegator can not transfer the released tokens
+    app.send_tokens(delegator_addr, validator_addr, &coins(10, "TOKEN"))
+        .unwrap_err();
+}
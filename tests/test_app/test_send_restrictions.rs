@@ -0,0 +1,172 @@
+use super::common::multi_send::{multi_send_msg, proto_io};
+use super::common::SdkApp;
+use cosmwasm_std::{coins, Addr, Coin};
+use cw_multi_test::error::{bail, AnyResult};
+use cw_multi_test::{AppBuilder, BankKeeper, CosmosSdkStargate, Executor, IntoAddr};
+
+/// Prepares the application with funded owner and the bank keeper
+/// with specified send restrictions.
+fn setup(bank: BankKeeper) -> (SdkApp, Addr) {
+    let owner_addr = "owner".into_addr();
+    let app = AppBuilder::default()
+        .with_bank(bank)
+        .with_stargate(CosmosSdkStargate)
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &owner_addr, coins(100, "uatom"))
+                .unwrap();
+        });
+    (app, owner_addr)
+}
+
+/// Rejects transfers to the `sanctioned` account.
+fn reject_sanctioned(_from: &Addr, to: &Addr, _amount: &[Coin]) -> AnyResult<Addr> {
+    if *to == "sanctioned".into_addr() {
+        bail!("recipient {} is sanctioned", to);
+    }
+    Ok(to.clone())
+}
+
+/// Redirects all transfers to the `escrow` account.
+fn redirect_to_escrow(_from: &Addr, _to: &Addr, _amount: &[Coin]) -> AnyResult<Addr> {
+    Ok("escrow".into_addr())
+}
+
+#[test]
+fn send_restriction_should_reject_transfers() {
+    let (mut app, owner_addr) = setup(BankKeeper::new().with_send_restriction(reject_sanctioned));
+
+    app.send_tokens(owner_addr.clone(), "alice".into_addr(), &coins(10, "uatom"))
+        .unwrap();
+    let err = app
+        .send_tokens(
+            owner_addr.clone(),
+            "sanctioned".into_addr(),
+            &coins(10, "uatom"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        format!("recipient {} is sanctioned", "sanctioned".into_addr()),
+        err.root_cause().to_string()
+    );
+    let err = app
+        .execute(
+            owner_addr.clone(),
+            multi_send_msg(
+                vec![proto_io(&owner_addr, coins(20, "uatom"))],
+                vec![
+                    proto_io(&"alice".into_addr(), coins(10, "uatom")),
+                    proto_io(&"sanctioned".into_addr(), coins(10, "uatom")),
+                ],
+            ),
+        )
+        .unwrap_err();
+    assert!(err.root_cause().to_string().ends_with("is sanctioned"));
+
+    let balance = app.wrap().query_all_balances(&owner_addr).unwrap();
+    assert_eq!(coins(90, "uatom"), balance);
+    let balance = app
+        .wrap()
+        .query_all_balances("sanctioned".into_addr())
+        .unwrap();
+    assert!(balance.is_empty());
+}
+
+#[test]
+fn send_restriction_should_redirect_transfers() {
+    let (mut app, owner_addr) = setup(BankKeeper::new().with_send_restriction(redirect_to_escrow));
+    let escrow_addr = "escrow".into_addr();
+
+    let res = app
+        .send_tokens(owner_addr.clone(), "alice".into_addr(), &coins(10, "uatom"))
+        .unwrap();
+    let transfer = res.events.iter().find(|e| e.ty == "transfer").unwrap();
+    assert!(transfer
+        .attributes
+        .iter()
+        .any(|a| a.key == "recipient" && a.value == escrow_addr.as_str()));
+    app.execute(
+        owner_addr.clone(),
+        multi_send_msg(
+            vec![proto_io(&owner_addr, coins(30, "uatom"))],
+            vec![
+                proto_io(&"bob".into_addr(), coins(10, "uatom")),
+                proto_io(&"carol".into_addr(), coins(20, "uatom")),
+            ],
+        ),
+    )
+    .unwrap();
+
+    let balance = app.wrap().query_all_balances(&escrow_addr).unwrap();
+    assert_eq!(coins(40, "uatom"), balance);
+    for name in ["alice", "bob", "carol"] {
+        let balance = app.wrap().query_all_balances(name.into_addr()).unwrap();
+        assert!(balance.is_empty());
+    }
+}
+
+#[test]
+fn send_restrictions_should_be_applied_in_order() {
+    // transfers are redirected to the escrow first, so the sanctioned account is never checked
+    let (mut app, owner_addr) = setup(BankKeeper::new().with_send_restriction(reject_sanctioned));
+    app.init_modules(|router, _, _| router.bank.prepend_send_restriction(redirect_to_escrow));
+    app.send_tokens(
+        owner_addr.clone(),
+        "sanctioned".into_addr(),
+        &coins(10, "uatom"),
+    )
+    .unwrap();
+
+    // the restriction rejecting transfers to escrow is applied after redirecting
+    app.init_modules(|router, _, _| {
+        router
+            .bank
+            .append_send_restriction(|_: &Addr, to: &Addr, _: &[Coin]| -> AnyResult<Addr> {
+                bail!("transfers to {} are frozen", to)
+            })
+    });
+    let err = app
+        .send_tokens(owner_addr.clone(), "alice".into_addr(), &coins(10, "uatom"))
+        .unwrap_err();
+    assert_eq!(
+        format!("transfers to {} are frozen", "escrow".into_addr()),
+        err.root_cause().to_string()
+    );
+
+    app.init_modules(|router, _, _| router.bank.clear_send_restrictions());
+    app.send_tokens(owner_addr, "alice".into_addr(), &coins(10, "uatom"))
+        .unwrap();
+    let balance = app.wrap().query_all_balances("escrow".into_addr()).unwrap();
+    assert_eq!(coins(10, "uatom"), balance);
+    let balance = app.wrap().query_all_balances("alice".into_addr()).unwrap();
+    assert_eq!(coins(10, "uatom"), balance);
+}
+
+#[test]
+fn multi_send_with_several_senders_should_be_rejected() {
+    let (mut app, owner_addr) = setup(BankKeeper::new().with_send_restriction(reject_sanctioned));
+    app.send_tokens(owner_addr.clone(), "alice".into_addr(), &coins(10, "uatom"))
+        .unwrap();
+
+    let err = app
+        .execute(
+            owner_addr.clone(),
+            multi_send_msg(
+                vec![
+                    proto_io(&owner_addr, coins(10, "uatom")),
+                    proto_io(&"alice".into_addr(), coins(10, "uatom")),
+                ],
+                vec![proto_io(&"bob".into_addr(), coins(20, "uatom"))],
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(
+        format!(
+            "input address {} does not match the signer {}",
+            "alice".into_addr(),
+            owner_addr
+        ),
+        err.root_cause().to_string()
+    );
+}
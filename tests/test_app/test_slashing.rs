@@ -0,0 +1,149 @@
+use super::common::staking::{
+    delegate, delegated, init_staking, rewards, unjail, validator, MsgUnjail, DENOM,
+};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{coin, Addr, AnyMsg, CosmosMsg, Decimal, StakingMsg};
+use cw_multi_test::{Executor, SlashingParams, StakingSudo};
+use prost::Message;
+
+const YEAR: u64 = 60 * 60 * 24 * 365;
+
+/// Prepares the application with single validator without commission
+/// and the owner delegating 1000 tokens, rewards are 10% per year.
+fn setup() -> (SdkApp, Addr, Addr) {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    init_staking(
+        &mut app,
+        &[(&owner_addr, 1000)],
+        &[validator(&validator_addr, 0)],
+    );
+    delegate(&mut app, &owner_addr, &validator_addr, 1000);
+    (app, owner_addr, validator_addr)
+}
+
+#[test]
+fn slashing_should_affect_delegations_and_unbondings() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(200, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    app.slash_validator(&validator_addr, Decimal::percent(10))
+        .unwrap();
+    assert_eq!(720, delegated(&app, &owner_addr, &validator_addr));
+    let entries = app
+        .read_module(|router, _, storage| router.staking.unbonding_entries(storage, &owner_addr))
+        .unwrap();
+    assert_eq!(coin(180, DENOM), entries[0].balance);
+}
+
+#[test]
+fn jailed_validator_should_not_earn_rewards() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    let jailed_until = app.block_info().time.plus_seconds(YEAR);
+    app.jail_validator(&validator_addr, jailed_until).unwrap();
+
+    // jailed validator is not bonded, but delegations are kept
+    assert!(app.wrap().query_all_validators().unwrap().is_empty());
+    assert_eq!(1000, delegated(&app, &owner_addr, &validator_addr));
+
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR / 2));
+    let err = unjail(&mut app, &validator_addr).unwrap_err();
+    assert_eq!(
+        "validator still jailed; cannot be unjailed",
+        err.root_cause().to_string()
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR / 2));
+    assert_eq!(0, rewards(&app, &owner_addr, &validator_addr));
+    unjail(&mut app, &validator_addr).unwrap();
+    assert_eq!(1, app.wrap().query_all_validators().unwrap().len());
+    let err = unjail(&mut app, &validator_addr).unwrap_err();
+    assert_eq!(
+        "validator not jailed; cannot be unjailed",
+        err.root_cause().to_string()
+    );
+
+    // rewards are earned again after unjailing
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+    assert_eq!(100, rewards(&app, &owner_addr, &validator_addr));
+}
+
+#[test]
+fn downtime_should_be_slashed_and_jailed() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    app.init_modules(|router, _, storage| {
+        router.staking.setup_slashing(
+            storage,
+            SlashingParams {
+                downtime_jail_duration: 60,
+                slash_fraction_downtime: Decimal::percent(2),
+                ..SlashingParams::default()
+            },
+        )
+    })
+    .unwrap();
+
+    app.sudo(
+        StakingSudo::SlashForDowntime {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(980, delegated(&app, &owner_addr, &validator_addr));
+    let jailed_until = app
+        .read_module(|router, _, storage| router.staking.jailed_until(storage, &validator_addr))
+        .unwrap();
+    assert_eq!(Some(app.block_info().time.plus_seconds(60)), jailed_until);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(60));
+    unjail(&mut app, &validator_addr).unwrap();
+}
+
+#[test]
+fn double_signing_validator_should_be_jailed_forever() {
+    let (mut app, owner_addr, validator_addr) = setup();
+
+    app.sudo(
+        StakingSudo::SlashForDoubleSign {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(950, delegated(&app, &owner_addr, &validator_addr));
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100 * YEAR));
+    let err = unjail(&mut app, &validator_addr).unwrap_err();
+    assert_eq!(
+        "validator still jailed; cannot be unjailed",
+        err.root_cause().to_string()
+    );
+
+    // only the operator can unjail the validator
+    let msg = MsgUnjail {
+        validator_addr: validator_addr.to_string(),
+    };
+    let err = app
+        .execute(
+            owner_addr,
+            CosmosMsg::Any(AnyMsg {
+                type_url: "/cosmos.slashing.v1beta1.MsgUnjail".to_string(),
+                value: msg.encode_to_vec().into(),
+            }),
+        )
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .starts_with("validator address"));
+}
@@ -0,0 +1,174 @@
+use super::common::staking::{init_staking, validator, DENOM};
+use cosmwasm_std::{coin, Addr, Decimal, Empty, Event, Response, StakingMsg, StdError, Validator};
+use cw_multi_test::{App, ContractWrapper, Executor, StakingHookMsg, StakingSudo};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type Hooks = Rc<RefCell<Vec<(StakingHookMsg, Option<u128>)>>>;
+
+/// Prepares the application with single validator, funded owner and the hook contract
+/// recording all notified staking events, together with the owner's delegation
+/// visible to the contract when notified. Slashing more than a half of the stake
+/// is rejected by the hook.
+fn setup() -> (App, Addr, Addr, Addr, Hooks) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    let hooks: Hooks = Rc::new(RefCell::new(vec![]));
+    let contract = ContractWrapper::new_closure(
+        |_, _, _, _: Empty| -> Result<Response, StdError> { Ok(Response::default()) },
+        |_, _, _, _: Empty| -> Result<Response, StdError> { Ok(Response::default()) },
+        |_, _, _: Empty| -> Result<_, StdError> { Ok(Default::default()) },
+    )
+    .with_sudo_closure({
+        let hooks = hooks.clone();
+        let owner_addr = owner_addr.clone();
+        let validator_addr = validator_addr.clone();
+        move |deps, _, msg: StakingHookMsg| -> Result<Response, StdError> {
+            if let StakingHookMsg::BeforeValidatorSlashed { fraction, .. } = &msg {
+                if *fraction > Decimal::percent(50) {
+                    return Err(StdError::generic_err("slashing is too severe"));
+                }
+            }
+            let delegated = deps
+                .querier
+                .query_delegation(&owner_addr, &validator_addr)?
+                .map(|delegation| delegation.amount.amount.u128());
+            hooks.borrow_mut().push((msg, delegated));
+            Ok(Response::new().add_event(Event::new("hook")))
+        }
+    });
+    let code_id = app.store_code(Box::new(contract));
+    let hook_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "hook", None)
+        .unwrap();
+    init_staking(
+        &mut app,
+        &[(&owner_addr, 1100)],
+        &[validator(&validator_addr, 0)],
+    );
+    app.init_modules(|router, _, storage| router.staking.add_staking_hook(storage, &hook_addr))
+        .unwrap();
+    (app, owner_addr, validator_addr, hook_addr, hooks)
+}
+
+#[test]
+fn staking_hooks_should_be_called_for_delegation_changes() {
+    let (mut app, owner_addr, validator_addr, hook_addr, hooks) = setup();
+
+    let res = app
+        .execute(
+            owner_addr.clone(),
+            StakingMsg::Delegate {
+                validator: validator_addr.to_string(),
+                amount: coin(1000, DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+    assert!(res.has_event(&Event::new("sudo").add_attribute("_contract_address", &hook_addr)));
+    for amount in [400, 600] {
+        app.execute(
+            owner_addr.clone(),
+            StakingMsg::Undelegate {
+                validator: validator_addr.to_string(),
+                amount: coin(amount, DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+    }
+
+    // the delegation is still visible to the contract notified about its removal
+    let modified = StakingHookMsg::AfterDelegationModified {
+        delegator: owner_addr.to_string(),
+        validator: validator_addr.to_string(),
+    };
+    let removed = StakingHookMsg::BeforeDelegationRemoved {
+        delegator: owner_addr.to_string(),
+        validator: validator_addr.to_string(),
+    };
+    assert_eq!(
+        vec![
+            (modified.clone(), Some(1000)),
+            (modified, Some(600)),
+            (removed, Some(600)),
+        ],
+        *hooks.borrow()
+    );
+
+    // removed hook is not called anymore
+    app.init_modules(|router, _, storage| {
+        router
+            .staking
+            .remove_staking_hook(storage, &hook_addr)
+            .unwrap();
+        assert!(router.staking.staking_hooks(storage).unwrap().is_empty());
+    });
+    app.execute(
+        owner_addr,
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(10, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(3, hooks.borrow().len());
+}
+
+#[test]
+fn staking_hooks_should_be_called_for_validator_changes() {
+    let (mut app, _, validator_addr, _, hooks) = setup();
+    let new_validator_addr = app.api().addr_make("new_validator");
+
+    app.sudo(
+        StakingSudo::AddValidator {
+            validator: Validator::new(
+                new_validator_addr.to_string(),
+                Decimal::zero(),
+                Decimal::percent(100),
+                Decimal::percent(1),
+            ),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.slash_validator(&validator_addr, Decimal::percent(10))
+        .unwrap();
+    app.sudo(
+        StakingSudo::RemoveValidator {
+            validator: new_validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(
+        vec![
+            StakingHookMsg::AfterValidatorCreated {
+                validator: new_validator_addr.to_string(),
+            },
+            StakingHookMsg::BeforeValidatorSlashed {
+                validator: validator_addr.to_string(),
+                fraction: Decimal::percent(10),
+            },
+            StakingHookMsg::AfterValidatorRemoved {
+                validator: new_validator_addr.to_string(),
+            },
+        ],
+        hooks
+            .borrow()
+            .iter()
+            .map(|(msg, _)| msg.clone())
+            .collect::<Vec<_>>()
+    );
+
+    // slashing is reverted when the hook fails
+    let err = app
+        .slash_validator(&validator_addr, Decimal::percent(60))
+        .unwrap_err();
+    assert_eq!(
+        "Generic error: slashing is too severe",
+        err.root_cause().to_string()
+    );
+}
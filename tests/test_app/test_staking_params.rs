@@ -0,0 +1,143 @@
+use super::common::staking::{delegate, grpc_query, init_staking, validator, DENOM};
+use cosmwasm_std::{coin, Addr, Decimal, StakingMsg};
+use cw_multi_test::{App, Executor, StakingInfo, StakingSudo};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct Duration {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Params {
+    #[prost(message, optional, tag = "1")]
+    pub unbonding_time: Option<Duration>,
+    #[prost(uint32, tag = "2")]
+    pub max_validators: u32,
+    #[prost(uint32, tag = "3")]
+    pub max_entries: u32,
+    #[prost(uint32, tag = "4")]
+    pub historical_entries: u32,
+    #[prost(string, tag = "5")]
+    pub bond_denom: String,
+    #[prost(string, tag = "6")]
+    pub min_commission_rate: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryParamsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub params: Option<Params>,
+}
+
+/// Prepares the application with two validators, the owner delegates
+/// 100 tokens to the first and 200 tokens to the second one.
+fn setup() -> (App, Addr, [Addr; 2]) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let validators = ["validator1", "validator2"].map(|v| app.api().addr_make(v));
+    let validator_set = validators
+        .iter()
+        .map(|addr| validator(addr, 10))
+        .collect::<Vec<_>>();
+    init_staking(&mut app, &[(&owner_addr, 300)], &validator_set);
+    for (validator_addr, amount) in validators.iter().zip([100, 200]) {
+        delegate(&mut app, &owner_addr, validator_addr, amount);
+    }
+    (app, owner_addr, validators)
+}
+
+fn update_params(app: &mut App, params: StakingInfo) {
+    app.sudo(StakingSudo::UpdateParams { params }.into())
+        .unwrap();
+}
+
+#[test]
+fn staking_params_should_be_queried() {
+    let (mut app, _, _) = setup();
+    update_params(
+        &mut app,
+        StakingInfo {
+            unbonding_time: 120,
+            min_commission_rate: Decimal::percent(5),
+            ..StakingInfo::default()
+        },
+    );
+
+    let response: QueryParamsResponse =
+        grpc_query(&app, "/cosmos.staking.v1beta1.Query/Params", ());
+    assert_eq!(
+        Some(Params {
+            unbonding_time: Some(Duration {
+                seconds: 120,
+                nanos: 0
+            }),
+            max_validators: 100,
+            max_entries: 7,
+            historical_entries: 10_000,
+            bond_denom: DENOM.to_string(),
+            min_commission_rate: "50000000000000000".to_string(),
+        }),
+        response.params
+    );
+}
+
+#[test]
+fn changed_params_should_be_applied() {
+    let (mut app, owner_addr, [validator1, validator2]) = setup();
+    update_params(
+        &mut app,
+        StakingInfo {
+            max_validators: 1,
+            max_entries: 2,
+            min_commission_rate: Decimal::percent(5),
+            ..StakingInfo::default()
+        },
+    );
+
+    // only the validator with the highest stake is bonded
+    assert_eq!(
+        vec![validator(&validator2, 10)],
+        app.wrap().query_all_validators().unwrap()
+    );
+
+    // the number of pending unbondings is limited
+    let undelegate = |app: &mut App| {
+        app.execute(
+            owner_addr.clone(),
+            StakingMsg::Undelegate {
+                validator: validator1.to_string(),
+                amount: coin(10, DENOM),
+            }
+            .into(),
+        )
+    };
+    undelegate(&mut app).unwrap();
+    undelegate(&mut app).unwrap();
+    let err = undelegate(&mut app).unwrap_err();
+    assert_eq!(
+        "too many unbonding delegation entries for (delegator, validator) tuple",
+        err.root_cause().to_string()
+    );
+
+    // the commission of new validators is limited
+    let err = app
+        .sudo(
+            StakingSudo::AddValidator {
+                validator: validator(&app.api().addr_make("validator3"), 1),
+            }
+            .into(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        "commission cannot be less than min rate",
+        err.root_cause().to_string()
+    );
+    let params = app
+        .read_module(|router, _, storage| router.staking.staking_info(storage))
+        .unwrap();
+    assert_eq!(Decimal::percent(5), params.min_commission_rate);
+}
@@ -0,0 +1,270 @@
+use super::common::staking::{grpc_query, try_grpc_query, DENOM};
+use cosmwasm_std::{coin, Decimal, Querier, StakingMsg, Validator};
+use cw_multi_test::{
+    App, AppBuilder, BankSudo, Executor, StakingInfo, StakingSudo, StargateTendermint,
+};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct Timestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Header {
+    #[prost(string, tag = "2")]
+    pub chain_id: String,
+    #[prost(int64, tag = "3")]
+    pub height: i64,
+    #[prost(message, optional, tag = "4")]
+    pub time: Option<Timestamp>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Block {
+    #[prost(message, optional, tag = "1")]
+    pub header: Option<Header>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct GetLatestBlockResponse {
+    #[prost(message, optional, tag = "2")]
+    pub block: Option<Block>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct TmValidator {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(int64, tag = "3")]
+    pub voting_power: i64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct GetLatestValidatorSetResponse {
+    #[prost(int64, tag = "1")]
+    pub block_height: i64,
+    #[prost(message, repeated, tag = "2")]
+    pub validators: Vec<TmValidator>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryHistoricalInfoRequest {
+    #[prost(int64, tag = "1")]
+    pub height: i64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct StakingValidator {
+    #[prost(string, tag = "1")]
+    pub operator_address: String,
+    #[prost(int32, tag = "4")]
+    pub status: i32,
+    #[prost(string, tag = "5")]
+    pub tokens: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct HistoricalInfo {
+    #[prost(message, optional, tag = "1")]
+    pub header: Option<Header>,
+    #[prost(message, repeated, tag = "2")]
+    pub valset: Vec<StakingValidator>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryHistoricalInfoResponse {
+    #[prost(message, optional, tag = "1")]
+    pub hist: Option<HistoricalInfo>,
+}
+
+fn historical_info(querier: &dyn Querier, height: i64) -> HistoricalInfo {
+    grpc_query::<QueryHistoricalInfoResponse>(
+        querier,
+        "/cosmos.staking.v1beta1.Query/HistoricalInfo",
+        QueryHistoricalInfoRequest { height },
+    )
+    .hist
+    .unwrap()
+}
+
+#[test]
+fn tendermint_queries_should_work() {
+    let mut app = AppBuilder::default()
+        .with_stargate(StargateTendermint)
+        .build(|_, _, _| {});
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(100),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+    });
+    app.sudo(
+        BankSudo::Mint {
+            to_address: owner_addr.to_string(),
+            amount: vec![coin(5_000_000, DENOM)],
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // latest block reflects the simulated block
+    let latest: GetLatestBlockResponse = grpc_query(
+        &app,
+        "/cosmos.base.tendermint.v1beta1.Service/GetLatestBlock",
+        (),
+    );
+    let header = latest.block.unwrap().header.unwrap();
+    assert_eq!(block.chain_id, header.chain_id);
+    assert_eq!(block.height as i64, header.height);
+    assert_eq!(block.time.seconds() as i64, header.time.unwrap().seconds);
+
+    // validators without bonded tokens are not in the validator set
+    let set: GetLatestValidatorSetResponse = grpc_query(
+        &app,
+        "/cosmos.base.tendermint.v1beta1.Service/GetLatestValidatorSet",
+        (),
+    );
+    assert!(set.validators.is_empty());
+
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(3_000_000, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    let set: GetLatestValidatorSetResponse = grpc_query(
+        &app,
+        "/cosmos.base.tendermint.v1beta1.Service/GetLatestValidatorSet",
+        (),
+    );
+    assert_eq!(block.height as i64, set.block_height);
+    assert_eq!(
+        vec![TmValidator {
+            address: validator_addr.to_string(),
+            voting_power: 3,
+        }],
+        set.validators
+    );
+
+    // the historical info of the left block is preserved
+    app.update_block(|block| block.height += 1);
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(2_000_000, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    let past = historical_info(&app, block.height as i64);
+    assert_eq!(block.height as i64, past.header.unwrap().height);
+    assert_eq!(
+        vec![StakingValidator {
+            operator_address: validator_addr.to_string(),
+            status: 3,
+            tokens: "3000000".to_string(),
+        }],
+        past.valset
+    );
+    let current = historical_info(&app, block.height as i64 + 1);
+    assert_eq!("5000000", current.valset[0].tokens);
+
+    // unknown heights are rejected
+    let err = try_grpc_query::<QueryHistoricalInfoResponse>(
+        &app,
+        "/cosmos.staking.v1beta1.Query/HistoricalInfo",
+        QueryHistoricalInfoRequest { height: 100 },
+    )
+    .unwrap_err();
+    assert!(err.contains("historical info for height 100 not found"));
+}
+
+#[test]
+fn historical_info_should_be_answered_by_staking_module() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::percent(10),
+                    Decimal::percent(100),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &owner_addr, vec![coin(1_000_000, DENOM)])
+            .unwrap();
+    });
+    app.sudo(
+        StakingSudo::UpdateParams {
+            params: StakingInfo {
+                historical_entries: 2,
+                ..StakingInfo::default()
+            },
+        }
+        .into(),
+    )
+    .unwrap();
+    app.execute(
+        owner_addr,
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(1_000_000, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    for _ in 0..3 {
+        app.update_block(|block| block.height += 1);
+    }
+
+    let past = historical_info(&app, block.height as i64 + 1);
+    assert_eq!(
+        vec![StakingValidator {
+            operator_address: validator_addr.to_string(),
+            status: 3,
+            tokens: "1000000".to_string(),
+        }],
+        past.valset
+    );
+
+    // only the configured number of recent entries is kept
+    let err = try_grpc_query::<QueryHistoricalInfoResponse>(
+        &app,
+        "/cosmos.staking.v1beta1.Query/HistoricalInfo",
+        QueryHistoricalInfoRequest {
+            height: block.height as i64,
+        },
+    )
+    .unwrap_err();
+    assert!(err.contains("not found"));
+}
@@ -0,0 +1,233 @@
+use super::common::staking::{delegate, delegated, init_staking, validator, DENOM};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{coin, coins, Addr, AnyMsg, CosmosMsg, Decimal, StdError};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{AppResponse, Executor, TokenizeShareRecord};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgTokenizeShares {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(message, optional, tag = "3")]
+    pub amount: Option<ProtoCoin>,
+    #[prost(string, tag = "4")]
+    pub tokenized_share_owner: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgRedeemTokensForShares {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(message, optional, tag = "2")]
+    pub amount: Option<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgTransferTokenizeShareRecord {
+    #[prost(uint64, tag = "1")]
+    pub tokenize_share_record_id: u64,
+    #[prost(string, tag = "2")]
+    pub sender: String,
+    #[prost(string, tag = "3")]
+    pub new_owner: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgTokenizeSharesResponse {
+    #[prost(message, optional, tag = "1")]
+    pub amount: Option<ProtoCoin>,
+}
+
+/// Prepares the application with single validator and the owner delegating 1000 tokens.
+fn setup() -> (SdkApp, Addr, Addr) {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    init_staking(
+        &mut app,
+        &[(&owner_addr, 1000)],
+        &[validator(&validator_addr, 0)],
+    );
+    delegate(&mut app, &owner_addr, &validator_addr, 1000);
+    (app, owner_addr, validator_addr)
+}
+
+fn execute_any(
+    app: &mut SdkApp,
+    sender: &Addr,
+    type_url: &str,
+    value: Vec<u8>,
+) -> AnyResult<AppResponse> {
+    app.execute(
+        sender.clone(),
+        CosmosMsg::Any(AnyMsg {
+            type_url: type_url.to_string(),
+            value: value.into(),
+        }),
+    )
+}
+
+fn tokenize(
+    app: &mut SdkApp,
+    delegator: &Addr,
+    validator: &Addr,
+    amount: u128,
+    owner: &Addr,
+) -> AnyResult<AppResponse> {
+    let msg = MsgTokenizeShares {
+        delegator_address: delegator.to_string(),
+        validator_address: validator.to_string(),
+        amount: Some(ProtoCoin {
+            denom: DENOM.to_string(),
+            amount: amount.to_string(),
+        }),
+        tokenized_share_owner: owner.to_string(),
+    };
+    execute_any(
+        app,
+        delegator,
+        "/cosmos.staking.v1beta1.MsgTokenizeShares",
+        msg.encode_to_vec(),
+    )
+}
+
+fn redeem(app: &mut SdkApp, delegator: &Addr, denom: &str, amount: u128) -> AnyResult<AppResponse> {
+    let msg = MsgRedeemTokensForShares {
+        delegator_address: delegator.to_string(),
+        amount: Some(ProtoCoin {
+            denom: denom.to_string(),
+            amount: amount.to_string(),
+        }),
+    };
+    execute_any(
+        app,
+        delegator,
+        "/gaia.liquid.v1beta1.MsgRedeemTokensForShares",
+        msg.encode_to_vec(),
+    )
+}
+
+fn records(app: &SdkApp) -> Vec<TokenizeShareRecord> {
+    app.read_module(|router, _, storage| router.staking.tokenize_share_records(storage))
+        .unwrap()
+}
+
+#[test]
+fn shares_should_be_tokenized_and_redeemed() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    let holder_addr = app.api().addr_make("holder");
+
+    let res = tokenize(&mut app, &owner_addr, &validator_addr, 400, &owner_addr).unwrap();
+    let records = records(&app);
+    assert_eq!(1, records.len());
+    let record = records[0].clone();
+    assert_eq!(owner_addr, record.owner);
+    let denom = record.share_denom();
+    assert_eq!(format!("{}/1", validator_addr), denom);
+    let response = MsgTokenizeSharesResponse::decode(res.data.unwrap().as_slice()).unwrap();
+    assert_eq!(
+        Some(ProtoCoin {
+            denom: denom.clone(),
+            amount: "400".to_string(),
+        }),
+        response.amount
+    );
+
+    // tokenized stake is delegated by the record's module account
+    assert_eq!(600, delegated(&app, &owner_addr, &validator_addr));
+    assert_eq!(
+        400,
+        delegated(&app, &record.module_account, &validator_addr)
+    );
+    let balance = app.wrap().query_balance(&owner_addr, &denom).unwrap();
+    assert_eq!(coin(400, &denom), balance);
+
+    // share tokens are transferable and can be redeemed by any holder
+    app.send_tokens(owner_addr.clone(), holder_addr.clone(), &coins(100, &denom))
+        .unwrap();
+    redeem(&mut app, &holder_addr, &denom, 100).unwrap();
+    assert_eq!(100, delegated(&app, &holder_addr, &validator_addr));
+    assert_eq!(
+        300,
+        delegated(&app, &record.module_account, &validator_addr)
+    );
+
+    let err = redeem(&mut app, &holder_addr, &denom, 100).unwrap_err();
+    assert!(err
+        .chain()
+        .any(|cause| matches!(cause.downcast_ref(), Some(StdError::Overflow { .. }))));
+
+    // the record is removed when all shares are redeemed
+    redeem(&mut app, &owner_addr, &denom, 300).unwrap();
+    assert_eq!(900, delegated(&app, &owner_addr, &validator_addr));
+    assert!(self::records(&app).is_empty());
+    let err = redeem(&mut app, &owner_addr, &denom, 1).unwrap_err();
+    assert_eq!(
+        "tokenize share record not exists",
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn redeemed_shares_should_reflect_slashing() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    tokenize(&mut app, &owner_addr, &validator_addr, 500, &owner_addr).unwrap();
+    let denom = records(&app)[0].share_denom();
+
+    app.slash_validator(&validator_addr, Decimal::percent(10))
+        .unwrap();
+    redeem(&mut app, &owner_addr, &denom, 500).unwrap();
+    assert_eq!(900, delegated(&app, &owner_addr, &validator_addr));
+}
+
+#[test]
+fn tokenize_share_record_should_be_transferred_by_owner() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    let new_owner_addr = app.api().addr_make("new_owner");
+    tokenize(&mut app, &owner_addr, &validator_addr, 100, &owner_addr).unwrap();
+
+    // only delegated stake can be tokenized
+    let err = tokenize(&mut app, &new_owner_addr, &validator_addr, 100, &owner_addr).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .starts_with("no delegation for (address, validator) tuple"));
+
+    let transfer = |sender: &Addr| MsgTransferTokenizeShareRecord {
+        tokenize_share_record_id: 1,
+        sender: sender.to_string(),
+        new_owner: new_owner_addr.to_string(),
+    };
+    let type_url = "/cosmos.staking.v1beta1.MsgTransferTokenizeShareRecord";
+    let err = execute_any(
+        &mut app,
+        &new_owner_addr,
+        type_url,
+        transfer(&new_owner_addr).encode_to_vec(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        "not tokenize share record owner",
+        err.root_cause().to_string()
+    );
+
+    execute_any(
+        &mut app,
+        &owner_addr,
+        type_url,
+        transfer(&owner_addr).encode_to_vec(),
+    )
+    .unwrap();
+    assert_eq!(new_owner_addr, records(&app)[0].owner);
+}
@@ -0,0 +1,123 @@
+use super::common::multi_send::{multi_send_msg, proto_io};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{coins, Addr, Empty, Event, Response, StdError};
+use cw_multi_test::{ContractWrapper, Executor, IntoAddr, TransferHookMsg};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Prepares the application with funded owner and the hook contract recording
+/// all notified transfers, transfers to `blocked` account are rejected by the hook.
+fn setup() -> (SdkApp, Addr, Addr, Rc<RefCell<Vec<TransferHookMsg>>>) {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    let blocked_addr = app.api().addr_make("blocked");
+    let transfers = Rc::new(RefCell::new(vec![]));
+    let contract = ContractWrapper::new_closure(
+        |_, _, _, _: Empty| -> Result<Response, StdError> { Ok(Response::default()) },
+        |_, _, _, _: Empty| -> Result<Response, StdError> { Ok(Response::default()) },
+        |_, _, _: Empty| -> Result<_, StdError> { Ok(Default::default()) },
+    )
+    .with_sudo_closure({
+        let transfers = transfers.clone();
+        move |_, _, msg: TransferHookMsg| -> Result<Response, StdError> {
+            let TransferHookMsg::BankTransfer { to, .. } = &msg;
+            if blocked_addr.as_str() == to {
+                return Err(StdError::generic_err("recipient is not approved"));
+            }
+            transfers.borrow_mut().push(msg);
+            Ok(Response::new().add_event(Event::new("hook")))
+        }
+    });
+    let code_id = app.store_code(Box::new(contract));
+    let hook_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "hook", None)
+        .unwrap();
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner_addr, coins(100, "uatom"))
+            .unwrap();
+        router
+            .bank
+            .add_transfer_hook(storage, &owner_addr, &hook_addr)
+            .unwrap();
+    });
+    (app, owner_addr, hook_addr, transfers)
+}
+
+#[test]
+fn transfer_hooks_should_be_called_for_watched_addresses() {
+    let (mut app, owner_addr, hook_addr, transfers) = setup();
+
+    let res = app
+        .send_tokens(owner_addr.clone(), "alice".into_addr(), &coins(10, "uatom"))
+        .unwrap();
+    assert!(res.has_event(&Event::new("sudo").add_attribute("_contract_address", &hook_addr)));
+    app.execute(
+        owner_addr.clone(),
+        multi_send_msg(
+            vec![proto_io(&owner_addr, coins(30, "uatom"))],
+            vec![
+                proto_io(&"bob".into_addr(), coins(10, "uatom")),
+                proto_io(&"carol".into_addr(), coins(20, "uatom")),
+            ],
+        ),
+    )
+    .unwrap();
+
+    // transfers of not watched addresses are not reported
+    app.send_tokens("alice".into_addr(), "bob".into_addr(), &coins(5, "uatom"))
+        .unwrap();
+
+    let transfer = |to: &str, amount: u128| TransferHookMsg::BankTransfer {
+        from: owner_addr.to_string(),
+        to: to.into_addr().to_string(),
+        amount: coins(amount, "uatom"),
+    };
+    assert_eq!(
+        vec![
+            transfer("alice", 10),
+            transfer("bob", 10),
+            transfer("carol", 20)
+        ],
+        *transfers.borrow()
+    );
+
+    // removed hook is not called anymore
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .remove_transfer_hook(storage, &owner_addr, &hook_addr)
+            .unwrap();
+        assert!(router
+            .bank
+            .transfer_hooks(storage, &owner_addr)
+            .unwrap()
+            .is_empty());
+    });
+    app.send_tokens(owner_addr.clone(), "alice".into_addr(), &coins(10, "uatom"))
+        .unwrap();
+    assert_eq!(3, transfers.borrow().len());
+}
+
+#[test]
+fn failing_transfer_hook_should_revert_transfer() {
+    let (mut app, owner_addr, _, transfers) = setup();
+
+    let err = app
+        .send_tokens(
+            owner_addr.clone(),
+            "blocked".into_addr(),
+            &coins(10, "uatom"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        "Generic error: recipient is not approved",
+        err.root_cause().to_string()
+    );
+    assert!(transfers.borrow().is_empty());
+    assert_eq!(
+        coins(100, "uatom"),
+        app.wrap().query_all_balances(&owner_addr).unwrap()
+    );
+}
@@ -0,0 +1,237 @@
+use super::common::staking::{
+    advance_seconds, balance, delegate, grpc_query, init_staking, try_grpc_query, undelegate,
+    validator, DENOM,
+};
+use cosmwasm_std::{coin, Addr, Decimal, StakingMsg};
+use cw_multi_test::{App, Executor, StakingInfo, StakingSudo, UnbondingEntry};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoTimestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct UnbondingDelegationEntry {
+    #[prost(int64, tag = "1")]
+    pub creation_height: i64,
+    #[prost(message, optional, tag = "2")]
+    pub completion_time: Option<ProtoTimestamp>,
+    #[prost(string, tag = "3")]
+    pub initial_balance: String,
+    #[prost(string, tag = "4")]
+    pub balance: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct UnbondingDelegation {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(message, repeated, tag = "3")]
+    pub entries: Vec<UnbondingDelegationEntry>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDelegatorUnbondingDelegationsRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_addr: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDelegatorUnbondingDelegationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub unbonding_responses: Vec<UnbondingDelegation>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryUnbondingDelegationRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_addr: String,
+    #[prost(string, tag = "2")]
+    pub validator_addr: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryUnbondingDelegationResponse {
+    #[prost(message, optional, tag = "1")]
+    pub unbond: Option<UnbondingDelegation>,
+}
+
+/// Prepares the application with single validator and the owner delegating 100 tokens.
+fn setup() -> (App, Addr, Addr) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    init_staking(
+        &mut app,
+        &[(&owner_addr, 100)],
+        &[validator(&validator_addr, 10)],
+    );
+    delegate(&mut app, &owner_addr, &validator_addr, 100);
+    (app, owner_addr, validator_addr)
+}
+
+fn unbonding_entries(app: &App, addr: &Addr) -> Vec<UnbondingEntry> {
+    app.read_module(|router, _, storage| router.staking.unbonding_entries(storage, addr))
+        .unwrap()
+}
+
+#[test]
+fn undelegated_tokens_should_be_released_after_unbonding_time() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    let block = app.block_info();
+
+    let res = app
+        .execute(
+            owner_addr.clone(),
+            StakingMsg::Undelegate {
+                validator: validator_addr.to_string(),
+                amount: coin(40, DENOM),
+            }
+            .into(),
+        )
+        .unwrap();
+    let completion_time = block.time.plus_seconds(60);
+    let unbond = res.events.iter().find(|e| e.ty == "unbond").unwrap();
+    assert!(unbond
+        .attributes
+        .iter()
+        .any(|a| a.key == "completion_time" && a.value == "2019-10-23T02:24:39Z"));
+    assert_eq!(
+        vec![UnbondingEntry {
+            delegator: owner_addr.clone(),
+            validator: validator_addr.clone(),
+            creation_height: block.height,
+            completion_time,
+            initial_balance: coin(40, DENOM),
+            balance: coin(40, DENOM),
+        }],
+        unbonding_entries(&app, &owner_addr)
+    );
+
+    // tokens are still unbonding one second before the completion time
+    advance_seconds(&mut app, 59);
+    advance_seconds(&mut app, 1);
+    assert_eq!(0, balance(&app, &owner_addr));
+    assert_eq!(1, unbonding_entries(&app, &owner_addr).len());
+
+    // tokens are released at the end of the block reaching the completion time
+    advance_seconds(&mut app, 1);
+    assert_eq!(40, balance(&app, &owner_addr));
+    assert!(unbonding_entries(&app, &owner_addr).is_empty());
+}
+
+#[test]
+fn unbondings_should_be_released_when_unbonding_time_is_shortened() {
+    let (mut app, owner_addr, validator_addr) = setup();
+
+    undelegate(&mut app, &owner_addr, &validator_addr, 10);
+    app.sudo(
+        StakingSudo::UpdateParams {
+            params: StakingInfo {
+                unbonding_time: 10,
+                ..StakingInfo::default()
+            },
+        }
+        .into(),
+    )
+    .unwrap();
+    undelegate(&mut app, &owner_addr, &validator_addr, 20);
+    let completion_times = unbonding_entries(&app, &owner_addr)
+        .into_iter()
+        .map(|entry| entry.completion_time)
+        .collect::<Vec<_>>();
+    let block = app.block_info();
+    assert_eq!(
+        vec![block.time.plus_seconds(10), block.time.plus_seconds(60)],
+        completion_times
+    );
+
+    // the second unbonding is released first, though it was queued last
+    advance_seconds(&mut app, 10);
+    advance_seconds(&mut app, 1);
+    assert_eq!(20, balance(&app, &owner_addr));
+    advance_seconds(&mut app, 50);
+    advance_seconds(&mut app, 1);
+    assert_eq!(30, balance(&app, &owner_addr));
+}
+
+#[test]
+fn unbonding_delegations_should_be_queried() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    let block = app.block_info();
+    undelegate(&mut app, &owner_addr, &validator_addr, 10);
+    app.sudo(
+        StakingSudo::Slash {
+            validator: validator_addr.to_string(),
+            percentage: Decimal::percent(50),
+        }
+        .into(),
+    )
+    .unwrap();
+    advance_seconds(&mut app, 5);
+    undelegate(&mut app, &owner_addr, &validator_addr, 20);
+
+    let expected = UnbondingDelegation {
+        delegator_address: owner_addr.to_string(),
+        validator_address: validator_addr.to_string(),
+        entries: vec![
+            UnbondingDelegationEntry {
+                creation_height: block.height as i64,
+                completion_time: Some(ProtoTimestamp {
+                    seconds: block.time.plus_seconds(60).seconds() as i64,
+                    nanos: block.time.subsec_nanos() as i32,
+                }),
+                initial_balance: "10".to_string(),
+                balance: "5".to_string(),
+            },
+            UnbondingDelegationEntry {
+                creation_height: block.height as i64 + 1,
+                completion_time: Some(ProtoTimestamp {
+                    seconds: block.time.plus_seconds(65).seconds() as i64,
+                    nanos: block.time.subsec_nanos() as i32,
+                }),
+                initial_balance: "20".to_string(),
+                balance: "20".to_string(),
+            },
+        ],
+    };
+
+    let request = QueryDelegatorUnbondingDelegationsRequest {
+        delegator_addr: owner_addr.to_string(),
+    };
+    let response: QueryDelegatorUnbondingDelegationsResponse = grpc_query(
+        &app,
+        "/cosmos.staking.v1beta1.Query/DelegatorUnbondingDelegations",
+        request,
+    );
+    assert_eq!(vec![expected.clone()], response.unbonding_responses);
+
+    let request = QueryUnbondingDelegationRequest {
+        delegator_addr: owner_addr.to_string(),
+        validator_addr: validator_addr.to_string(),
+    };
+    let response: QueryUnbondingDelegationResponse = grpc_query(
+        &app,
+        "/cosmos.staking.v1beta1.Query/UnbondingDelegation",
+        request,
+    );
+    assert_eq!(Some(expected), response.unbond);
+
+    let request = QueryUnbondingDelegationRequest {
+        delegator_addr: validator_addr.to_string(),
+        validator_addr: validator_addr.to_string(),
+    };
+    let err = try_grpc_query::<QueryUnbondingDelegationResponse>(
+        &app,
+        "/cosmos.staking.v1beta1.Query/UnbondingDelegation",
+        request,
+    )
+    .unwrap_err();
+    assert!(err.contains("unbonding delegation with delegator"));
+}
@@ -0,0 +1,161 @@
+use super::common::staking::{balance, delegate, grpc_query, init_staking, validator, DENOM};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{Addr, AnyMsg, CosmosMsg, DecCoin, Decimal, DistributionMsg};
+use cw_multi_test::Executor;
+use prost::Message;
+
+const YEAR: u64 = 60 * 60 * 24 * 365;
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgWithdrawValidatorCommission {
+    #[prost(string, tag = "1")]
+    pub validator_address: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoDecCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ValidatorAccumulatedCommission {
+    #[prost(message, repeated, tag = "1")]
+    pub commission: Vec<ProtoDecCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryValidatorCommissionRequest {
+    #[prost(string, tag = "1")]
+    pub validator_address: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryValidatorCommissionResponse {
+    #[prost(message, optional, tag = "1")]
+    pub commission: Option<ValidatorAccumulatedCommission>,
+}
+
+/// Prepares the application with single validator charging 10% commission
+/// and the owner delegating 1000 tokens, rewards are 10% per year.
+fn setup() -> (SdkApp, Addr, Addr) {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    init_staking(
+        &mut app,
+        &[(&owner_addr, 1000)],
+        &[validator(&validator_addr, 10)],
+    );
+    delegate(&mut app, &owner_addr, &validator_addr, 1000);
+    (app, owner_addr, validator_addr)
+}
+
+fn withdraw_commission_msg(validator_addr: &Addr) -> CosmosMsg {
+    let msg = MsgWithdrawValidatorCommission {
+        validator_address: validator_addr.to_string(),
+    };
+    CosmosMsg::Any(AnyMsg {
+        type_url: "/cosmos.distribution.v1beta1.MsgWithdrawValidatorCommission".to_string(),
+        value: msg.encode_to_vec().into(),
+    })
+}
+
+#[test]
+fn commission_should_be_accrued_separately_from_rewards() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+
+    let block = app.block_info();
+    let commission = app
+        .read_module(|router, _, storage| {
+            router
+                .distribution
+                .validator_commission(storage, &block, &validator_addr)
+        })
+        .unwrap();
+    assert_eq!(
+        vec![DecCoin::new(Decimal::percent(1000), DENOM)],
+        commission
+    );
+
+    // delegator receives rewards without the commission
+    app.execute(
+        owner_addr.clone(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(90, balance(&app, &owner_addr));
+
+    let response: QueryValidatorCommissionResponse = grpc_query(
+        &app,
+        "/cosmos.distribution.v1beta1.Query/ValidatorCommission",
+        QueryValidatorCommissionRequest {
+            validator_address: validator_addr.to_string(),
+        },
+    );
+    assert_eq!(
+        Some(ValidatorAccumulatedCommission {
+            commission: vec![ProtoDecCoin {
+                denom: DENOM.to_string(),
+                amount: "10000000000000000000".to_string(),
+            }],
+        }),
+        response.commission
+    );
+}
+
+#[test]
+fn commission_should_be_withdrawn_by_validator_operator() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+
+    // only the operator can withdraw the commission
+    let err = app
+        .execute(owner_addr, withdraw_commission_msg(&validator_addr))
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .starts_with("validator address"));
+
+    let res = app
+        .execute(
+            validator_addr.clone(),
+            withdraw_commission_msg(&validator_addr),
+        )
+        .unwrap();
+    let event = res
+        .events
+        .iter()
+        .find(|e| e.ty == "withdraw_commission")
+        .unwrap();
+    assert_eq!("10TOKEN", event.attributes[0].value);
+    assert_eq!(10, balance(&app, &validator_addr));
+
+    // withdrawn commission is not withdrawn again
+    let err = app
+        .execute(
+            validator_addr.clone(),
+            withdraw_commission_msg(&validator_addr),
+        )
+        .unwrap_err();
+    assert_eq!(
+        "no validator commission to withdraw",
+        err.root_cause().to_string()
+    );
+    let block = app.block_info();
+    let commission = app
+        .read_module(|router, _, storage| {
+            router
+                .distribution
+                .validator_commission(storage, &block, &validator_addr)
+        })
+        .unwrap();
+    assert!(commission.is_empty());
+}
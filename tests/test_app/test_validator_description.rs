@@ -0,0 +1,276 @@
+use super::common::staking::{delegate, grpc_query, init_staking};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{Addr, AnyMsg, CosmosMsg, Decimal, Validator};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{AppResponse, Executor, ValidatorDescription};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct Description {
+    #[prost(string, tag = "1")]
+    pub moniker: String,
+    #[prost(string, tag = "2")]
+    pub identity: String,
+    #[prost(string, tag = "3")]
+    pub website: String,
+    #[prost(string, tag = "4")]
+    pub security_contact: String,
+    #[prost(string, tag = "5")]
+    pub details: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgEditValidator {
+    #[prost(message, optional, tag = "1")]
+    pub description: Option<Description>,
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(string, tag = "3")]
+    pub commission_rate: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct CommissionRates {
+    #[prost(string, tag = "1")]
+    pub rate: String,
+    #[prost(string, tag = "2")]
+    pub max_rate: String,
+    #[prost(string, tag = "3")]
+    pub max_change_rate: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Timestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Commission {
+    #[prost(message, optional, tag = "1")]
+    pub commission_rates: Option<CommissionRates>,
+    #[prost(message, optional, tag = "2")]
+    pub update_time: Option<Timestamp>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoValidator {
+    #[prost(string, tag = "1")]
+    pub operator_address: String,
+    #[prost(bool, tag = "3")]
+    pub jailed: bool,
+    #[prost(int32, tag = "4")]
+    pub status: i32,
+    #[prost(string, tag = "5")]
+    pub tokens: String,
+    #[prost(string, tag = "6")]
+    pub delegator_shares: String,
+    #[prost(message, optional, tag = "7")]
+    pub description: Option<Description>,
+    #[prost(message, optional, tag = "10")]
+    pub commission: Option<Commission>,
+    #[prost(string, tag = "11")]
+    pub min_self_delegation: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryValidatorRequest {
+    #[prost(string, tag = "1")]
+    pub validator_addr: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryValidatorResponse {
+    #[prost(message, optional, tag = "1")]
+    pub validator: Option<ProtoValidator>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryValidatorsRequest {
+    #[prost(string, tag = "1")]
+    pub status: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryValidatorsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub validators: Vec<ProtoValidator>,
+}
+
+/// Prepares the application with two validators, the first one with 100 tokens
+/// delegated by the owner.
+fn setup() -> (SdkApp, Addr, Addr) {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    let first_addr = app.api().addr_make("first");
+    let second_addr = app.api().addr_make("second");
+    let validators = [&first_addr, &second_addr].map(|validator_addr| {
+        Validator::new(
+            validator_addr.to_string(),
+            Decimal::percent(10),
+            Decimal::percent(20),
+            Decimal::percent(5),
+        )
+    });
+    init_staking(&mut app, &[(&owner_addr, 100)], &validators);
+    delegate(&mut app, &owner_addr, &first_addr, 100);
+    (app, first_addr, second_addr)
+}
+
+fn edit_description(
+    app: &mut SdkApp,
+    validator_addr: &Addr,
+    description: Description,
+) -> AnyResult<AppResponse> {
+    let msg = MsgEditValidator {
+        description: Some(description),
+        validator_address: validator_addr.to_string(),
+        commission_rate: String::new(),
+    };
+    app.execute(
+        validator_addr.clone(),
+        CosmosMsg::Any(AnyMsg {
+            type_url: "/cosmos.staking.v1beta1.MsgEditValidator".to_string(),
+            value: msg.encode_to_vec().into(),
+        }),
+    )
+}
+
+#[test]
+fn validator_description_should_be_edited() {
+    let (mut app, validator_addr, _) = setup();
+    let do_not_modify = "[do-not-modify]".to_string();
+
+    edit_description(
+        &mut app,
+        &validator_addr,
+        Description {
+            moniker: "validator".to_string(),
+            website: "https://validator.example".to_string(),
+            details: "the best validator".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    edit_description(
+        &mut app,
+        &validator_addr,
+        Description {
+            moniker: do_not_modify.clone(),
+            identity: do_not_modify.clone(),
+            website: do_not_modify.clone(),
+            security_contact: "security@validator.example".to_string(),
+            details: do_not_modify.clone(),
+        },
+    )
+    .unwrap();
+    let description = app
+        .read_module(|router, _, storage| {
+            router
+                .staking
+                .validator_description(storage, &validator_addr)
+        })
+        .unwrap();
+    assert_eq!(
+        ValidatorDescription {
+            moniker: "validator".to_string(),
+            identity: String::new(),
+            website: "https://validator.example".to_string(),
+            security_contact: "security@validator.example".to_string(),
+            details: "the best validator".to_string(),
+        },
+        description
+    );
+
+    let err = edit_description(
+        &mut app,
+        &validator_addr,
+        Description {
+            moniker: "m".repeat(71),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        "invalid moniker length; got: 71, max: 70",
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn validators_should_be_queried_with_description_and_commission() {
+    let (mut app, first_addr, second_addr) = setup();
+    edit_description(
+        &mut app,
+        &first_addr,
+        Description {
+            moniker: "first".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let created_at = app.block_info().time;
+
+    let response: QueryValidatorResponse = grpc_query(
+        &app,
+        "/cosmos.staking.v1beta1.Query/Validator",
+        QueryValidatorRequest {
+            validator_addr: first_addr.to_string(),
+        },
+    );
+    assert_eq!(
+        Some(ProtoValidator {
+            operator_address: first_addr.to_string(),
+            jailed: false,
+            status: 3,
+            tokens: "100".to_string(),
+            delegator_shares: "100000000000000000000".to_string(),
+            description: Some(Description {
+                moniker: "first".to_string(),
+                ..Default::default()
+            }),
+            commission: Some(Commission {
+                commission_rates: Some(CommissionRates {
+                    rate: Decimal::percent(10).atomics().to_string(),
+                    max_rate: Decimal::percent(20).atomics().to_string(),
+                    max_change_rate: Decimal::percent(5).atomics().to_string(),
+                }),
+                update_time: Some(Timestamp {
+                    seconds: created_at.seconds() as i64,
+                    nanos: created_at.subsec_nanos() as i32,
+                }),
+            }),
+            min_self_delegation: "0".to_string(),
+        }),
+        response.validator
+    );
+
+    // jailed validators are not bonded
+    let jailed_until = app.block_info().time.plus_hours(1);
+    app.jail_validator(&second_addr, jailed_until).unwrap();
+    let validators = |status: &str| -> Vec<(String, bool)> {
+        let response: QueryValidatorsResponse = grpc_query(
+            &app,
+            "/cosmos.staking.v1beta1.Query/Validators",
+            QueryValidatorsRequest {
+                status: status.to_string(),
+            },
+        );
+        response
+            .validators
+            .into_iter()
+            .map(|v| (v.operator_address, v.jailed))
+            .collect()
+    };
+    assert_eq!(
+        vec![(first_addr.to_string(), false)],
+        validators("BOND_STATUS_BONDED")
+    );
+    assert_eq!(
+        vec![(second_addr.to_string(), true)],
+        validators("BOND_STATUS_UNBONDED")
+    );
+    assert_eq!(2, validators("").len());
+}
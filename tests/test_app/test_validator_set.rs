@@ -0,0 +1,248 @@
+use super::common::staking::{balance, DENOM};
+use super::common::{sdk_app, SdkApp};
+use cosmwasm_std::{coin, coins, Addr, AnyMsg, CosmosMsg, Decimal, StakingMsg, Validator};
+use cw_multi_test::error::AnyResult;
+use cw_multi_test::{AppResponse, Executor, StakingSudo};
+use prost::Message;
+
+const YEAR: u64 = 60 * 60 * 24 * 365;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct CommissionRates {
+    #[prost(string, tag = "1")]
+    pub rate: String,
+    #[prost(string, tag = "2")]
+    pub max_rate: String,
+    #[prost(string, tag = "3")]
+    pub max_change_rate: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgCreateValidator {
+    #[prost(message, optional, tag = "2")]
+    pub commission: Option<CommissionRates>,
+    #[prost(string, tag = "3")]
+    pub min_self_delegation: String,
+    #[prost(string, tag = "4")]
+    pub delegator_address: String,
+    #[prost(string, tag = "5")]
+    pub validator_address: String,
+    #[prost(message, optional, tag = "7")]
+    pub value: Option<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct MsgEditValidator {
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    #[prost(string, tag = "3")]
+    pub commission_rate: String,
+}
+
+/// Encodes the decimal like `LegacyDec`.
+fn legacy_dec(value: Decimal) -> String {
+    value.atomics().to_string()
+}
+
+fn create_validator(
+    app: &mut SdkApp,
+    validator_addr: &Addr,
+    amount: u128,
+) -> AnyResult<AppResponse> {
+    let msg = MsgCreateValidator {
+        commission: Some(CommissionRates {
+            rate: legacy_dec(Decimal::percent(10)),
+            max_rate: legacy_dec(Decimal::percent(20)),
+            max_change_rate: legacy_dec(Decimal::percent(5)),
+        }),
+        min_self_delegation: "1".to_string(),
+        delegator_address: validator_addr.to_string(),
+        validator_address: validator_addr.to_string(),
+        value: Some(ProtoCoin {
+            denom: DENOM.to_string(),
+            amount: amount.to_string(),
+        }),
+    };
+    app.execute(
+        validator_addr.clone(),
+        CosmosMsg::Any(AnyMsg {
+            type_url: "/cosmos.staking.v1beta1.MsgCreateValidator".to_string(),
+            value: msg.encode_to_vec().into(),
+        }),
+    )
+}
+
+fn edit_validator(
+    app: &mut SdkApp,
+    validator_addr: &Addr,
+    commission: Decimal,
+) -> AnyResult<AppResponse> {
+    let msg = MsgEditValidator {
+        validator_address: validator_addr.to_string(),
+        commission_rate: legacy_dec(commission),
+    };
+    app.execute(
+        validator_addr.clone(),
+        CosmosMsg::Any(AnyMsg {
+            type_url: "/cosmos.staking.v1beta1.MsgEditValidator".to_string(),
+            value: msg.encode_to_vec().into(),
+        }),
+    )
+}
+
+#[test]
+fn validator_should_be_created_and_edited() {
+    let mut app = sdk_app();
+    let validator_addr = app.api().addr_make("validator");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &validator_addr, coins(100, DENOM))
+    })
+    .unwrap();
+
+    let res = create_validator(&mut app, &validator_addr, 60).unwrap();
+    assert!(res.events.iter().any(|e| e.ty == "create_validator"));
+    assert_eq!(
+        vec![Validator::new(
+            validator_addr.to_string(),
+            Decimal::percent(10),
+            Decimal::percent(20),
+            Decimal::percent(5),
+        )],
+        app.wrap().query_all_validators().unwrap()
+    );
+    // self-delegation is bonded
+    assert_eq!(40, balance(&app, &validator_addr));
+    let delegation = app
+        .wrap()
+        .query_delegation(&validator_addr, &validator_addr)
+        .unwrap()
+        .unwrap();
+    assert_eq!(coin(60, DENOM), delegation.amount);
+
+    let err = create_validator(&mut app, &validator_addr, 10).unwrap_err();
+    assert!(err.root_cause().to_string().contains("already exists"));
+
+    // commission can be changed once in 24 hours, counting from the validator's creation
+    let err = edit_validator(&mut app, &validator_addr, Decimal::percent(15)).unwrap_err();
+    assert_eq!(
+        "commission cannot be changed more than once in 24h",
+        err.root_cause().to_string()
+    );
+    app.update_block(|block| block.time = block.time.plus_hours(24));
+    edit_validator(&mut app, &validator_addr, Decimal::percent(15)).unwrap();
+    let err = edit_validator(&mut app, &validator_addr, Decimal::percent(16)).unwrap_err();
+    assert_eq!(
+        "commission cannot be changed more than once in 24h",
+        err.root_cause().to_string()
+    );
+    app.update_block(|block| block.time = block.time.plus_hours(24));
+    let validator = app
+        .wrap()
+        .query_validator(validator_addr.to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(Decimal::percent(15), validator.commission);
+
+    let err = edit_validator(&mut app, &validator_addr, Decimal::percent(25)).unwrap_err();
+    assert_eq!(
+        "commission cannot be more than the max rate",
+        err.root_cause().to_string()
+    );
+    let err = edit_validator(&mut app, &validator_addr, Decimal::percent(5)).unwrap_err();
+    assert_eq!(
+        "commission cannot be changed more than max change rate",
+        err.root_cause().to_string()
+    );
+
+    // only the operator can edit the validator
+    let msg = MsgEditValidator {
+        validator_address: validator_addr.to_string(),
+        commission_rate: legacy_dec(Decimal::percent(12)),
+    };
+    let err = app
+        .execute(
+            app.api().addr_make("thief"),
+            CosmosMsg::Any(AnyMsg {
+                type_url: "/cosmos.staking.v1beta1.MsgEditValidator".to_string(),
+                value: msg.encode_to_vec().into(),
+            }),
+        )
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .starts_with("validator address"));
+}
+
+#[test]
+fn removed_validator_delegations_should_be_unbonded() {
+    let mut app = sdk_app();
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner_addr, coins(1000, DENOM))
+    })
+    .unwrap();
+    app.sudo(
+        StakingSudo::AddValidator {
+            validator: Validator::new(
+                validator_addr.to_string(),
+                Decimal::percent(10),
+                Decimal::percent(100),
+                Decimal::percent(1),
+            ),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(1000, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+
+    let res = app
+        .sudo(
+            StakingSudo::RemoveValidator {
+                validator: validator_addr.to_string(),
+            }
+            .into(),
+        )
+        .unwrap();
+    assert!(res.events.iter().any(|e| e.ty == "unbond"));
+    assert!(app.wrap().query_all_validators().unwrap().is_empty());
+    assert!(app
+        .wrap()
+        .query_all_delegations(&owner_addr)
+        .unwrap()
+        .is_empty());
+    // rewards and commission are withdrawn
+    assert_eq!(90, balance(&app, &owner_addr));
+    assert_eq!(10, balance(&app, &validator_addr));
+
+    // delegated tokens are released after the unbonding time
+    let entries = app
+        .read_module(|router, _, storage| router.staking.unbonding_entries(storage, &owner_addr))
+        .unwrap();
+    assert_eq!(coin(1000, DENOM), entries[0].balance);
+    app.update_block(|block| block.time = block.time.plus_seconds(60));
+    app.update_block(|block| block.time = block.time.plus_seconds(1));
+    assert_eq!(1090, balance(&app, &owner_addr));
+}
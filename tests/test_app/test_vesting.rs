@@ -0,0 +1,370 @@
+use super::common::staking::{advance_seconds, grpc_query, init_staking, validator, DENOM};
+use cosmwasm_std::{coin, coins, Addr, BankMsg, Coin, Decimal, StakingMsg};
+use cw_multi_test::{App, BankSudo, Executor, StakingSudo, VestingAccount, VestingPeriod};
+use prost::Message;
+
+#[derive(Clone, PartialEq, Message)]
+struct ProtoCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QuerySpendableBalancesRequest {
+    #[prost(string, tag = "1")]
+    pub address: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QuerySpendableBalancesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub balances: Vec<ProtoCoin>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QuerySpendableBalanceByDenomRequest {
+    #[prost(string, tag = "1")]
+    pub address: String,
+    #[prost(string, tag = "2")]
+    pub denom: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QuerySpendableBalanceByDenomResponse {
+    #[prost(message, optional, tag = "1")]
+    pub balance: Option<ProtoCoin>,
+}
+
+/// Prepares the application with single validator and a vesting account
+/// holding 1000 tokens vesting linearly during 100 seconds.
+fn setup() -> (App, Addr, Addr) {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    init_staking(&mut app, &[], &[validator(&validator_addr, 10)]);
+    let block = app.block_info();
+    app.sudo(
+        BankSudo::CreateVestingAccount {
+            to_address: owner_addr.to_string(),
+            amount: coins(1000, DENOM),
+            start_time: block.time,
+            end_time: block.time.plus_seconds(100),
+        }
+        .into(),
+    )
+    .unwrap();
+    (app, owner_addr, validator_addr)
+}
+
+fn vesting_account(app: &App, addr: &Addr) -> VestingAccount {
+    app.read_module(|router, _, storage| router.bank.vesting_account(storage, addr))
+        .unwrap()
+        .unwrap()
+}
+
+fn spendable_balance(app: &App, addr: &Addr) -> Vec<Coin> {
+    let block = app.block_info();
+    app.read_module(|router, _, storage| router.bank.spendable_balance(storage, &block, addr))
+        .unwrap()
+}
+
+fn send(app: &mut App, from: &Addr, amount: u128) -> String {
+    app.send_tokens(
+        from.clone(),
+        Addr::unchecked("recipient"),
+        &coins(amount, DENOM),
+    )
+    .map(|_| "ok".to_string())
+    .unwrap_or_else(|e| e.to_string())
+}
+
+#[test]
+fn vesting_tokens_are_locked() {
+    let (mut app, owner_addr, _) = setup();
+
+    // nothing is vested yet
+    assert_eq!(
+        "spendable balance 0TOKEN is smaller than 1TOKEN: insufficient funds",
+        send(&mut app, &owner_addr, 1)
+    );
+
+    // burning vesting tokens is not allowed either
+    app.execute(
+        owner_addr.clone(),
+        BankMsg::Burn {
+            amount: coins(1, DENOM),
+        }
+        .into(),
+    )
+    .unwrap_err();
+
+    // a quarter is vested after 25 seconds
+    advance_seconds(&mut app, 25);
+    assert_eq!(
+        "spendable balance 250TOKEN is smaller than 251TOKEN: insufficient funds",
+        send(&mut app, &owner_addr, 251)
+    );
+    assert_eq!("ok", send(&mut app, &owner_addr, 250));
+
+    // everything is vested at the end time
+    advance_seconds(&mut app, 75);
+    assert_eq!("ok", send(&mut app, &owner_addr, 750));
+}
+
+#[test]
+fn delegations_are_tracked() {
+    let (mut app, owner_addr, validator_addr) = setup();
+
+    // vested tokens are delegated after vesting ones
+    advance_seconds(&mut app, 20);
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(900, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    let account = vesting_account(&app, &owner_addr);
+    assert_eq!(coins(800, DENOM), account.delegated_vesting);
+    assert_eq!(coins(100, DENOM), account.delegated_free);
+
+    // delegated vesting tokens are not locked, so the remaining vested tokens can be sent
+    assert!(account.locked_coins(app.block_info().time).is_empty());
+    assert_eq!("ok", send(&mut app, &owner_addr, 100));
+
+    // undelegated tokens are tracked as delegated free tokens first
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(300, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    advance_seconds(&mut app, 60);
+    // the unbonding completes at the end of the block
+    app.update_block(|block| block.height += 1);
+    let account = vesting_account(&app, &owner_addr);
+    assert_eq!(coins(600, DENOM), account.delegated_vesting);
+    assert!(account.delegated_free.is_empty());
+
+    // still vesting tokens are covered by the delegated vesting tokens
+    let time = app.block_info().time;
+    assert_eq!(coins(200, DENOM), account.vesting_coins(time));
+    assert!(account.locked_coins(time).is_empty());
+}
+
+#[test]
+fn slashed_vesting_delegations_are_not_tracked() {
+    let (mut app, owner_addr, validator_addr) = setup();
+
+    // delegate all vesting tokens
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(1000, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    let account = vesting_account(&app, &owner_addr);
+    assert_eq!(coins(1000, DENOM), account.delegated_vesting);
+
+    // slash the validator by half
+    app.sudo(
+        StakingSudo::Slash {
+            validator: validator_addr.to_string(),
+            percentage: Decimal::percent(50),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // undelegate the remaining stake and wait for unbonding
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Undelegate {
+            validator: validator_addr.to_string(),
+            amount: coin(500, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+    advance_seconds(&mut app, 60);
+    // the unbonding completes at the end of the block
+    app.update_block(|block| block.height += 1);
+    assert_eq!(
+        coin(500, DENOM),
+        app.wrap().query_balance(&owner_addr, DENOM).unwrap()
+    );
+
+    // slashed tokens stay tracked as delegated vesting, like in Cosmos SDK
+    let account = vesting_account(&app, &owner_addr);
+    assert_eq!(coins(500, DENOM), account.delegated_vesting);
+    assert!(account.delegated_free.is_empty());
+
+    // 400 tokens are still vesting, but they are covered by the delegated vesting tokens
+    let time = app.block_info().time;
+    assert_eq!(coins(400, DENOM), account.vesting_coins(time));
+    assert!(account.locked_coins(time).is_empty());
+    assert_eq!("ok", send(&mut app, &owner_addr, 500));
+}
+
+#[test]
+fn delayed_vesting_tokens_are_locked_until_end_time() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let end_time = app.block_info().time.plus_seconds(100);
+    app.sudo(
+        BankSudo::CreateDelayedVestingAccount {
+            to_address: owner_addr.to_string(),
+            amount: coins(1000, DENOM),
+            end_time,
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // nothing is spendable before the end time
+    advance_seconds(&mut app, 99);
+    assert!(spendable_balance(&app, &owner_addr).is_empty());
+    assert_eq!(
+        "spendable balance 0TOKEN is smaller than 1TOKEN: insufficient funds",
+        send(&mut app, &owner_addr, 1)
+    );
+
+    // everything is vested at once
+    advance_seconds(&mut app, 1);
+    assert_eq!(coins(1000, DENOM), spendable_balance(&app, &owner_addr));
+    assert_eq!("ok", send(&mut app, &owner_addr, 1000));
+}
+
+#[test]
+fn periodic_vesting_tokens_are_released_after_each_period() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let start_time = app.block_info().time;
+    app.sudo(
+        BankSudo::CreatePeriodicVestingAccount {
+            to_address: owner_addr.to_string(),
+            start_time,
+            periods: vec![
+                VestingPeriod {
+                    length: 10,
+                    amount: coins(100, DENOM),
+                },
+                VestingPeriod {
+                    length: 20,
+                    amount: vec![coin(200, DENOM), coin(50, "OTHER")],
+                },
+            ],
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // the account is funded with the sum of all periods
+    let account = vesting_account(&app, &owner_addr);
+    assert_eq!(
+        vec![coin(50, "OTHER"), coin(300, DENOM)],
+        account.original_vesting
+    );
+    assert_eq!(start_time.plus_seconds(30), account.end_time);
+
+    // tokens are locked until the end of the first period
+    advance_seconds(&mut app, 9);
+    assert!(spendable_balance(&app, &owner_addr).is_empty());
+    advance_seconds(&mut app, 1);
+    assert_eq!(coins(100, DENOM), spendable_balance(&app, &owner_addr));
+    assert_eq!(
+        "spendable balance 100TOKEN is smaller than 101TOKEN: insufficient funds",
+        send(&mut app, &owner_addr, 101)
+    );
+    assert_eq!("ok", send(&mut app, &owner_addr, 100));
+
+    // the second period releases the rest
+    advance_seconds(&mut app, 20);
+    assert_eq!(
+        vec![coin(50, "OTHER"), coin(200, DENOM)],
+        spendable_balance(&app, &owner_addr)
+    );
+}
+
+#[test]
+fn spendable_balance_excludes_locked_tokens() {
+    let (mut app, owner_addr, _) = setup();
+    app.sudo(
+        BankSudo::Mint {
+            to_address: owner_addr.to_string(),
+            amount: coins(50, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // tokens received outside of vesting are spendable
+    assert_eq!(coins(50, DENOM), spendable_balance(&app, &owner_addr));
+    advance_seconds(&mut app, 50);
+    assert_eq!(coins(550, DENOM), spendable_balance(&app, &owner_addr));
+    assert_eq!(
+        coin(1050, DENOM),
+        app.wrap().query_balance(&owner_addr, DENOM).unwrap()
+    );
+}
+
+#[test]
+fn spendable_balances_should_be_queried() {
+    let (mut app, owner_addr, validator_addr) = setup();
+    app.mint_to(&owner_addr, coins(10, "uosmo")).unwrap();
+    advance_seconds(&mut app, 30);
+
+    // delegated vesting tokens are not locked anymore
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(200, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // 700 tokens are vesting, 200 of them are delegated, 800 tokens remain in the balance
+    let response: QuerySpendableBalancesResponse = grpc_query(
+        &app,
+        "/cosmos.bank.v1beta1.Query/SpendableBalances",
+        QuerySpendableBalancesRequest {
+            address: owner_addr.to_string(),
+        },
+    );
+    assert_eq!(
+        vec![
+            ProtoCoin {
+                denom: DENOM.to_string(),
+                amount: "300".to_string(),
+            },
+            ProtoCoin {
+                denom: "uosmo".to_string(),
+                amount: "10".to_string(),
+            },
+        ],
+        response.balances
+    );
+
+    let response: QuerySpendableBalanceByDenomResponse = grpc_query(
+        &app,
+        "/cosmos.bank.v1beta1.Query/SpendableBalanceByDenom",
+        QuerySpendableBalanceByDenomRequest {
+            address: owner_addr.to_string(),
+            denom: DENOM.to_string(),
+        },
+    );
+    assert_eq!("300", response.balance.unwrap().amount);
+    assert_eq!("ok", send(&mut app, &owner_addr, 300));
+}
@@ -0,0 +1,106 @@
+use super::common::staking::{balance, grpc_query, DENOM};
+use cosmwasm_std::{coin, coins, Addr, Decimal, DistributionMsg, StakingMsg, Validator};
+use cw_multi_test::{App, Executor};
+use prost::Message;
+
+const YEAR: u64 = 60 * 60 * 24 * 365;
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDelegatorWithdrawAddressRequest {
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct QueryDelegatorWithdrawAddressResponse {
+    #[prost(string, tag = "1")]
+    pub withdraw_address: String,
+}
+
+fn query_withdraw_address(app: &App, delegator_addr: &Addr) -> String {
+    let response: QueryDelegatorWithdrawAddressResponse = grpc_query(
+        app,
+        "/cosmos.distribution.v1beta1.Query/DelegatorWithdrawAddress",
+        QueryDelegatorWithdrawAddressRequest {
+            delegator_address: delegator_addr.to_string(),
+        },
+    );
+    response.withdraw_address
+}
+
+#[test]
+fn rewards_should_be_withdrawn_to_withdraw_address() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let validator_addr = app.api().addr_make("validator");
+    let compounder_addr = app.api().addr_make("compounder");
+    let block = app.block_info();
+    app.init_modules(|router, api, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner_addr, coins(1000, DENOM))
+            .unwrap();
+        router
+            .staking
+            .add_validator(
+                api,
+                storage,
+                &block,
+                Validator::new(
+                    validator_addr.to_string(),
+                    Decimal::zero(),
+                    Decimal::percent(100),
+                    Decimal::percent(1),
+                ),
+            )
+            .unwrap();
+    });
+    app.execute(
+        owner_addr.clone(),
+        StakingMsg::Delegate {
+            validator: validator_addr.to_string(),
+            amount: coin(1000, DENOM),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // rewards are withdrawn to the delegator by default
+    assert_eq!(
+        owner_addr,
+        app.wrap()
+            .query_delegator_withdraw_address(&owner_addr)
+            .unwrap()
+    );
+
+    app.execute(
+        owner_addr.clone(),
+        DistributionMsg::SetWithdrawAddress {
+            address: compounder_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(
+        compounder_addr,
+        app.wrap()
+            .query_delegator_withdraw_address(&owner_addr)
+            .unwrap()
+    );
+    assert_eq!(
+        compounder_addr.to_string(),
+        query_withdraw_address(&app, &owner_addr)
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(YEAR));
+    app.execute(
+        owner_addr.clone(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: validator_addr.to_string(),
+        }
+        .into(),
+    )
+    .unwrap();
+    assert_eq!(0, balance(&app, &owner_addr));
+    assert_eq!(100, balance(&app, &compounder_addr));
+}
@@ -0,0 +1,71 @@
+use cosmwasm_std::testing::MockApi;
+use cosmwasm_std::{coin, Decimal, Validator};
+use cw_multi_test::{AppBuilder, StakingInfo};
+
+#[test]
+fn building_app_with_validators_should_work() {
+    // prepare operator addresses
+    let api = MockApi::default();
+    let first_addr = api.addr_make("first");
+    let second_addr = api.addr_make("second");
+
+    // build the application with initial validator set
+    let app = AppBuilder::default()
+        .with_validators([
+            (first_addr.clone(), 10, Decimal::percent(5)),
+            (second_addr.clone(), 20, Decimal::percent(10)),
+        ])
+        .build(|router, _, storage| {
+            router
+                .staking
+                .setup(
+                    storage,
+                    StakingInfo {
+                        bonded_denom: "ustake".to_string(),
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        });
+
+    // validators should be created with the requested commission rates
+    assert_eq!(
+        vec![
+            Validator::new(
+                first_addr.to_string(),
+                Decimal::percent(5),
+                Decimal::one(),
+                Decimal::percent(1),
+            ),
+            Validator::new(
+                second_addr.to_string(),
+                Decimal::percent(10),
+                Decimal::one(),
+                Decimal::percent(1),
+            ),
+        ],
+        app.wrap().query_all_validators().unwrap()
+    );
+
+    // operators should self-delegate tokens of the bonded denomination matching the voting power
+    for (operator_addr, tokens) in [(first_addr, 10_000_000), (second_addr, 20_000_000)] {
+        let delegation = app
+            .wrap()
+            .query_delegation(&operator_addr, &operator_addr)
+            .unwrap()
+            .unwrap();
+        assert_eq!(coin(tokens, "ustake"), delegation.amount);
+    }
+}
+
+#[test]
+#[should_panic(expected = "failed to create validator")]
+fn building_app_with_duplicated_validators_should_panic() {
+    let validator_addr = MockApi::default().addr_make("validator");
+    AppBuilder::default()
+        .with_validators([
+            (validator_addr.clone(), 1, Decimal::percent(5)),
+            (validator_addr, 1, Decimal::percent(5)),
+        ])
+        .build(|_, _, _| {});
+}
@@ -0,0 +1,8 @@
+mod test_async_entry_points;
+mod test_closures;
+mod test_codec;
+mod test_dispatch_contract_macro;
+mod test_instance_context;
+mod test_msg_translator;
+mod test_strict_decoding;
+mod test_without_query;
@@ -0,0 +1,93 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use cw_storage_plus::Item;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+const VALUE: Item<String> = Item::new("value");
+
+/// External fixture resolving the value on another thread, like an HTTP mock would do.
+struct Fixture {
+    state: Arc<Mutex<(Option<String>, Option<Waker>)>>,
+}
+
+impl Fixture {
+    fn fetch(value: &str) -> Self {
+        let state = Arc::new(Mutex::new((None, None::<Waker>)));
+        let shared = Arc::clone(&state);
+        let value = value.to_string();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            let mut state = shared.lock().unwrap();
+            state.0 = Some(value);
+            if let Some(waker) = state.1.take() {
+                waker.wake();
+            }
+        });
+        Self { state }
+    }
+}
+
+impl Future for Fixture {
+    type Output = String;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<String> {
+        let mut state = self.state.lock().unwrap();
+        match state.0.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+async fn instantiate(deps: DepsMut<'_>, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    VALUE.save(deps.storage, &Fixture::fetch("initial").await)?;
+    Ok(Response::default())
+}
+
+async fn execute(deps: DepsMut<'_>, _: Env, _: MessageInfo, msg: String) -> StdResult<Response> {
+    let value = Fixture::fetch(&msg).await;
+    VALUE.save(deps.storage, &value)?;
+    Ok(Response::new().add_attribute("value", value))
+}
+
+async fn query(deps: Deps<'_>, _: Env, _: Empty) -> StdResult<Binary> {
+    let value = VALUE.load(deps.storage)?;
+    to_json_binary(&format!("{} {}", value, Fixture::fetch("queried").await))
+}
+
+#[test]
+fn async_entry_points_should_work() {
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(ContractWrapper::new_async(
+        execute,
+        instantiate,
+        query,
+    )));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "async", None)
+        .unwrap();
+    let value: String = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!("initial queried", value);
+
+    app.execute_contract(owner_addr, contract_addr.clone(), &"updated", &[])
+        .unwrap();
+    let value: String = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!("updated queried", value);
+}
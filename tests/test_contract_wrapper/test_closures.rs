@@ -0,0 +1,152 @@
+use cosmwasm_std::{to_json_binary, BankMsg, Binary, Empty, Reply, Response, StdError, SubMsg};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[test]
+fn closures_should_share_state_with_test() {
+    // state shared between the test and the mocked contract
+    let executions = Rc::new(RefCell::new(Vec::<String>::new()));
+    let instantiated = Rc::new(RefCell::new(false));
+    let queried = Rc::new(RefCell::new(0_u64));
+
+    // prepare the contract using capturing closures
+    let contract = ContractWrapper::new_closure(
+        {
+            let executions = executions.clone();
+            move |_, _, info, msg: String| -> Result<Response, StdError> {
+                executions
+                    .borrow_mut()
+                    .push(format!("{}:{}", info.sender, msg));
+                Ok(Response::default())
+            }
+        },
+        {
+            let instantiated = instantiated.clone();
+            move |_, _, _, _: Empty| -> Result<Response, StdError> {
+                *instantiated.borrow_mut() = true;
+                Ok(Response::default())
+            }
+        },
+        {
+            let queried = queried.clone();
+            move |_, _, _: Empty| {
+                *queried.borrow_mut() += 1;
+                to_json_binary(&*queried.borrow())
+            }
+        },
+    );
+
+    // prepare the application
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(contract));
+    let owner_addr = app.api().addr_make("owner");
+
+    // instantiate the contract
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "closures",
+            None,
+        )
+        .unwrap();
+    assert!(*instantiated.borrow());
+
+    // execute the contract
+    app.execute_contract(owner_addr.clone(), contract_addr.clone(), &"first", &[])
+        .unwrap();
+    app.execute_contract(owner_addr.clone(), contract_addr.clone(), &"second", &[])
+        .unwrap();
+    assert_eq!(
+        vec![
+            format!("{}:first", owner_addr),
+            format!("{}:second", owner_addr)
+        ],
+        *executions.borrow()
+    );
+
+    // query the contract
+    let count: u64 = app
+        .wrap()
+        .query_wasm_smart(contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!(1, count);
+    assert_eq!(1, *queried.borrow());
+}
+
+#[test]
+fn optional_entry_points_should_accept_closures() {
+    // state shared between the test and the mocked contract
+    let replies = Rc::new(RefCell::new(Vec::<Reply>::new()));
+    let sudo_calls = Rc::new(RefCell::new(Vec::<String>::new()));
+    let migrations = Rc::new(RefCell::new(0_u32));
+
+    // prepare the contract, execute always fails in submessage and expects the reply
+    let contract = ContractWrapper::new_closure(
+        |_, _, _, _: Empty| -> Result<Response, StdError> {
+            Ok(Response::new()
+                .add_submessage(SubMsg::reply_always(BankMsg::Burn { amount: vec![] }, 7)))
+        },
+        |_, _, _, _: Empty| -> Result<Response, StdError> { Ok(Response::default()) },
+        |_, _, _: Empty| -> Result<Binary, StdError> { Ok(Binary::default()) },
+    )
+    .with_reply_closure({
+        let replies = replies.clone();
+        move |_, _, reply| -> Result<Response, StdError> {
+            replies.borrow_mut().push(reply);
+            Ok(Response::default())
+        }
+    })
+    .with_sudo_closure({
+        let sudo_calls = sudo_calls.clone();
+        move |_, _, msg: String| -> Result<Response, StdError> {
+            sudo_calls.borrow_mut().push(msg);
+            Ok(Response::default())
+        }
+    })
+    .with_migrate_closure({
+        let migrations = migrations.clone();
+        move |_, _, _: Empty| -> Result<Response, StdError> {
+            *migrations.borrow_mut() += 1;
+            Ok(Response::default())
+        }
+    });
+
+    // prepare the application
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(contract));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "closures",
+            Some(owner_addr.to_string()),
+        )
+        .unwrap();
+
+    // replies are collected by the test
+    app.execute_contract(owner_addr.clone(), contract_addr.clone(), &Empty {}, &[])
+        .unwrap();
+    assert_eq!(1, replies.borrow().len());
+    assert_eq!(7, replies.borrow()[0].id);
+    assert!(replies.borrow()[0]
+        .result
+        .clone()
+        .unwrap_err()
+        .starts_with("Cannot transfer empty coins amount"));
+
+    // sudo messages are collected by the test
+    app.wasm_sudo(contract_addr.clone(), &"privileged").unwrap();
+    assert_eq!(vec!["privileged".to_string()], *sudo_calls.borrow());
+
+    // migrations are counted by the test
+    app.migrate_contract(owner_addr, contract_addr, &Empty {}, code_id)
+        .unwrap();
+    assert_eq!(1, *migrations.borrow());
+}
@@ -0,0 +1,95 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+    WasmMsg,
+};
+use cw_multi_test::error::{bail, AnyResult};
+use cw_multi_test::{App, ContractWrapper, Executor, MessageCodec};
+use cw_storage_plus::Item;
+use serde::{Deserialize, Serialize};
+
+const TOTAL: Item<u64> = Item::new("total");
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExecMsg {
+    Add { value: u64 },
+    Reset {},
+}
+
+/// Compact binary codec: the first byte selects the message, optionally followed
+/// by the big-endian value. JSON messages (starting with `{`) are passed unchanged.
+struct CompactCodec;
+
+impl MessageCodec for CompactCodec {
+    fn to_json(&self, msg: Vec<u8>) -> AnyResult<Vec<u8>> {
+        match msg.split_first() {
+            Some((b'{', _)) => Ok(msg),
+            Some((1, value)) => {
+                let value = u64::from_be_bytes(value.try_into()?);
+                Ok(format!(r#"{{"add":{{"value":{}}}}}"#, value).into_bytes())
+            }
+            Some((2, [])) => Ok(br#"{"reset":{}}"#.to_vec()),
+            _ => bail!("invalid compact message"),
+        }
+    }
+}
+
+fn instantiate(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    TOTAL.save(deps.storage, &0)?;
+    Ok(Response::default())
+}
+
+fn execute(deps: DepsMut, _: Env, _: MessageInfo, msg: ExecMsg) -> Result<Response, StdError> {
+    TOTAL.update(deps.storage, |total| -> StdResult<_> {
+        Ok(match msg {
+            ExecMsg::Add { value } => total + value,
+            ExecMsg::Reset {} => 0,
+        })
+    })?;
+    Ok(Response::default())
+}
+
+fn query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    to_json_binary(&TOTAL.load(deps.storage)?)
+}
+
+#[test]
+fn messages_should_be_decoded_using_codec() {
+    let mut app = App::default();
+    let contract = ContractWrapper::new(execute, instantiate, query).with_codec(CompactCodec);
+    let code_id = app.store_code(Box::new(contract));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "compact", None)
+        .unwrap();
+
+    let execute_raw = |app: &mut App, msg: Vec<u8>| {
+        app.execute(
+            owner_addr.clone(),
+            WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: msg.into(),
+                funds: vec![],
+            }
+            .into(),
+        )
+    };
+    let total = |app: &App| -> u64 {
+        app.wrap()
+            .query_wasm_smart(&contract_addr, &Empty {})
+            .unwrap()
+    };
+
+    // binary messages are decoded by the codec
+    let mut add = vec![1];
+    add.extend_from_slice(&5_u64.to_be_bytes());
+    execute_raw(&mut app, add.clone()).unwrap();
+    execute_raw(&mut app, add).unwrap();
+    assert_eq!(10, total(&app));
+    execute_raw(&mut app, vec![2]).unwrap();
+    assert_eq!(0, total(&app));
+
+    // codec errors are reported
+    let err = execute_raw(&mut app, vec![3]).unwrap_err();
+    assert_eq!("invalid compact message", err.root_cause().to_string());
+}
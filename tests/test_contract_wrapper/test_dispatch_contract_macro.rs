@@ -0,0 +1,191 @@
+use cw_multi_test::{dispatch_contract, App, Executor};
+
+/// Counter contract shaped like the code generated by the Sylvia framework.
+mod counter {
+    use cosmwasm_std::{
+        to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    };
+    use cw_storage_plus::Item;
+
+    pub struct CounterContract {
+        pub(crate) count: Item<u64>,
+    }
+
+    impl CounterContract {
+        pub const fn new() -> Self {
+            Self {
+                count: Item::new("count"),
+            }
+        }
+    }
+
+    pub mod sv {
+        use super::*;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub struct InstantiateMsg {
+            pub count: u64,
+        }
+
+        impl InstantiateMsg {
+            pub fn dispatch(
+                self,
+                contract: &CounterContract,
+                (deps, _, _): (DepsMut, Env, MessageInfo),
+            ) -> StdResult<Response> {
+                contract.count.save(deps.storage, &self.count)?;
+                Ok(Response::default())
+            }
+        }
+
+        #[derive(Debug, Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub enum ContractExecMsg {
+            Increment {},
+        }
+
+        impl ContractExecMsg {
+            pub fn dispatch(
+                self,
+                contract: &CounterContract,
+                (deps, _, _): (DepsMut, Env, MessageInfo),
+            ) -> StdResult<Response> {
+                contract
+                    .count
+                    .update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+                Ok(Response::default())
+            }
+        }
+
+        #[derive(Debug, Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub enum ContractQueryMsg {
+            Count {},
+        }
+
+        impl ContractQueryMsg {
+            pub fn dispatch(
+                self,
+                contract: &CounterContract,
+                (deps, _): (Deps, Env),
+            ) -> StdResult<Binary> {
+                to_json_binary(&contract.count.load(deps.storage)?)
+            }
+        }
+
+        #[derive(Debug, Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub enum ContractSudoMsg {
+            Reset {},
+        }
+
+        impl ContractSudoMsg {
+            pub fn dispatch(
+                self,
+                contract: &CounterContract,
+                (deps, _): (DepsMut, Env),
+            ) -> StdResult<Response> {
+                contract.count.save(deps.storage, &0)?;
+                Ok(Response::default())
+            }
+        }
+
+        #[derive(Debug, Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        pub struct MigrateMsg {
+            pub count: u64,
+        }
+
+        impl MigrateMsg {
+            pub fn dispatch(
+                self,
+                contract: &CounterContract,
+                (deps, _): (DepsMut, Env),
+            ) -> StdResult<Response> {
+                contract.count.save(deps.storage, &self.count)?;
+                Ok(Response::default())
+            }
+        }
+    }
+}
+
+use counter::sv::{ContractExecMsg, ContractQueryMsg, ContractSudoMsg, InstantiateMsg, MigrateMsg};
+
+fn count(app: &App, contract_addr: &cosmwasm_std::Addr) -> u64 {
+    app.wrap()
+        .query_wasm_smart(contract_addr, &ContractQueryMsg::Count {})
+        .unwrap()
+}
+
+#[test]
+fn messages_should_be_dispatched_to_contract() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(Box::new(dispatch_contract!(
+        counter::CounterContract::new(),
+        counter::sv with sudo, migrate
+    )));
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &InstantiateMsg { count: 10 },
+            &[],
+            "counter",
+            Some(owner_addr.to_string()),
+        )
+        .unwrap();
+    assert_eq!(10, count(&app, &contract_addr));
+
+    app.execute_contract(
+        owner_addr.clone(),
+        contract_addr.clone(),
+        &ContractExecMsg::Increment {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(11, count(&app, &contract_addr));
+
+    app.wasm_sudo(contract_addr.clone(), &ContractSudoMsg::Reset {})
+        .unwrap();
+    assert_eq!(0, count(&app, &contract_addr));
+
+    app.migrate_contract(
+        owner_addr,
+        contract_addr.clone(),
+        &MigrateMsg { count: 5 },
+        code_id,
+    )
+    .unwrap();
+    assert_eq!(5, count(&app, &contract_addr));
+}
+
+#[test]
+fn optional_entry_points_should_not_be_dispatched_by_default() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(Box::new(dispatch_contract!(
+        counter::CounterContract::new(),
+        counter::sv
+    )));
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr,
+            &InstantiateMsg { count: 1 },
+            &[],
+            "counter",
+            None,
+        )
+        .unwrap();
+
+    let err = app
+        .wasm_sudo(contract_addr, &ContractSudoMsg::Reset {})
+        .unwrap_err();
+    assert_eq!(
+        "sudo is not implemented for contract",
+        err.root_cause().to_string()
+    );
+}
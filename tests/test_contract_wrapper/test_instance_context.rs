@@ -0,0 +1,57 @@
+use cosmwasm_std::{to_json_binary, Addr, Empty, Response, StdError};
+use cw_multi_test::{App, ContractWrapper, Executor, InstanceContext};
+
+/// Oracle returning the price set in the context of its instance,
+/// executing the oracle doubles the price.
+fn oracle(
+    prices: &InstanceContext<u128>,
+) -> ContractWrapper<Empty, Empty, Empty, StdError, StdError, StdError> {
+    let execute_prices = prices.clone();
+    let query_prices = prices.clone();
+    ContractWrapper::new_closure(
+        move |_, env, _, _: Empty| {
+            execute_prices.update(&env.contract.address, |price| *price *= 2);
+            Ok(Response::default())
+        },
+        |_, _, _, _: Empty| Ok(Response::default()),
+        move |_, env, _: Empty| to_json_binary(&query_prices.of(&env)?),
+    )
+}
+
+fn price(app: &App, oracle_addr: &Addr) -> Result<u128, StdError> {
+    app.wrap().query_wasm_smart(oracle_addr, &Empty {})
+}
+
+#[test]
+fn instances_should_use_their_own_context() {
+    let prices = InstanceContext::new();
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(oracle(&prices)));
+    let owner_addr = app.api().addr_make("owner");
+    let mut instantiate = |label: &str| {
+        app.instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], label, None)
+            .unwrap()
+    };
+    let oracle_a = instantiate("oracle A");
+    let oracle_b = instantiate("oracle B");
+    let oracle_c = instantiate("oracle C");
+    prices.set(oracle_a.clone(), 10);
+    prices.set(oracle_b.clone(), 20);
+
+    assert_eq!(10, price(&app, &oracle_a).unwrap());
+    assert_eq!(20, price(&app, &oracle_b).unwrap());
+    assert!(price(&app, &oracle_c)
+        .unwrap_err()
+        .to_string()
+        .contains(&format!("context of contract {} not found", oracle_c)));
+
+    // closures can update the context of the called instance only
+    app.execute_contract(owner_addr, oracle_b.clone(), &Empty {}, &[])
+        .unwrap();
+    assert_eq!(Some(10), prices.get(&oracle_a));
+    assert_eq!(Some(40), prices.get(&oracle_b));
+
+    // removed context is no longer available
+    assert_eq!(Some(10), prices.remove(&oracle_a));
+    assert!(price(&app, &oracle_a).is_err());
+}
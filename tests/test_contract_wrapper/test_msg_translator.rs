@@ -0,0 +1,98 @@
+use cosmwasm_std::{
+    AnyMsg, Binary, CosmosMsg, CustomMsg, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError,
+};
+use cw_multi_test::custom_handler::CachingCustomHandler;
+use cw_multi_test::{default_msg_translator, no_init, AppBuilder, ContractWrapper, Executor};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+enum ChainMsg {
+    Ping,
+}
+
+impl CustomMsg for ChainMsg {}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> Result<Response, StdError> {
+    Ok(Response::default())
+}
+
+/// Returns the message passed in the execute message.
+fn execute(_: DepsMut, _: Env, _: MessageInfo, msg: CosmosMsg) -> Result<Response, StdError> {
+    Ok(Response::new().add_message(msg))
+}
+
+fn query(_: Deps, _: Env, _: Empty) -> Result<Binary, StdError> {
+    Ok(Binary::default())
+}
+
+fn ping_translator(msg: CosmosMsg<Empty>) -> CosmosMsg<ChainMsg> {
+    match msg {
+        CosmosMsg::Custom(Empty {}) => CosmosMsg::Custom(ChainMsg::Ping),
+        other => default_msg_translator(other),
+    }
+}
+
+#[test]
+fn custom_messages_should_be_translated() {
+    let custom_handler = CachingCustomHandler::<ChainMsg, Empty>::new();
+    let custom_handler_state = custom_handler.state();
+    let mut app = AppBuilder::new_custom()
+        .with_custom(custom_handler)
+        .build(no_init);
+    let owner_addr = app.api().addr_make("owner");
+
+    // the translator is applied, even when set after entry-points were populated
+    let contract =
+        ContractWrapper::<_, _, _, _, _, _, ChainMsg>::new_with_empty(execute, instantiate, query)
+            .with_msg_translator(ping_translator);
+    let code_id = app.store_code(Box::new(contract));
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "empty", None)
+        .unwrap();
+
+    app.execute_contract(
+        owner_addr,
+        contract_addr,
+        &CosmosMsg::<Empty>::Custom(Empty {}),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        vec![ChainMsg::Ping],
+        custom_handler_state.execs().to_owned()
+    );
+}
+
+#[test]
+fn any_messages_should_be_passed_through() {
+    let mut app = AppBuilder::new_custom()
+        .with_custom(CachingCustomHandler::<ChainMsg, Empty>::new())
+        .build(no_init);
+    let owner_addr = app.api().addr_make("owner");
+
+    let contract =
+        ContractWrapper::<_, _, _, _, _, _, ChainMsg>::new_with_empty(execute, instantiate, query);
+    let code_id = app.store_code(Box::new(contract));
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "empty", None)
+        .unwrap();
+
+    // the message reaches the default stargate module, that rejects all messages
+    let err = app
+        .execute_contract(
+            owner_addr,
+            contract_addr,
+            &CosmosMsg::<Empty>::Any(AnyMsg {
+                type_url: "/cosmos.authz.v1beta1.MsgExec".to_string(),
+                value: Binary::default(),
+            }),
+            &[],
+        )
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("/cosmos.authz.v1beta1.MsgExec"));
+}
@@ -0,0 +1,85 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, WasmMsg,
+};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExecMsg {
+    Transfer { recipient: String, amount: u64 },
+    Configure(Config),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Config {
+    limit: Option<u64>,
+}
+
+fn execute(_: DepsMut, _: Env, _: MessageInfo, _: ExecMsg) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    to_json_binary(&Empty {})
+}
+
+/// Sends the raw JSON message to the contract, returns the error message if any.
+fn execute_raw(strict: bool, msg: &str) -> Result<(), String> {
+    let mut contract = ContractWrapper::new(execute, instantiate, query);
+    if strict {
+        contract = contract.with_strict_decoding();
+    }
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(contract));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "strict", None)
+        .unwrap();
+    let msg = WasmMsg::Execute {
+        contract_addr: contract_addr.to_string(),
+        msg: Binary::from(msg.as_bytes()),
+        funds: vec![],
+    };
+    app.execute(owner_addr, msg.into())
+        .map(|_| ())
+        .map_err(|err| err.root_cause().to_string())
+}
+
+#[test]
+fn unknown_fields_should_be_accepted_by_default() {
+    execute_raw(
+        false,
+        r#"{"transfer":{"recipient":"alice","amount":10,"memo":"hi"}}"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn known_fields_should_be_accepted_in_strict_mode() {
+    execute_raw(true, r#"{"transfer":{"recipient":"alice","amount":10}}"#).unwrap();
+    execute_raw(true, r#"{"configure":{"limit":null}}"#).unwrap();
+    execute_raw(true, r#"{"configure":{}}"#).unwrap();
+}
+
+#[test]
+fn unknown_fields_should_be_rejected_in_strict_mode() {
+    let err = execute_raw(
+        true,
+        r#"{"transfer":{"recipient":"alice","amount":10,"memo":"hi"}}"#,
+    )
+    .unwrap_err();
+    assert!(err.ends_with("unknown field `memo`"), "{}", err);
+
+    // unknown fields are detected in nested structures too
+    let err = execute_raw(true, r#"{"configure":{"limit":5,"max":{"a":1}}}"#).unwrap_err();
+    assert!(err.ends_with("unknown field `max`"), "{}", err);
+
+    // other decoding errors are reported as usual
+    let err = execute_raw(true, r#"{"transfer":{"recipient":"alice"}}"#).unwrap_err();
+    assert!(err.contains("missing field `amount`"), "{}", err);
+}
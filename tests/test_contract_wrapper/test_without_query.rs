@@ -0,0 +1,43 @@
+use cosmwasm_std::{DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult};
+use cw_multi_test::{App, ContractWrapper, Executor};
+
+fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("action", "execute"))
+}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+#[test]
+fn contract_without_query_should_work() {
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(ContractWrapper::new_without_query(
+        execute,
+        instantiate,
+    )));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "no-query",
+            None,
+        )
+        .unwrap();
+
+    // executing the contract works as usual
+    app.execute_contract(owner_addr, contract_addr.clone(), &Empty {}, &[])
+        .unwrap();
+
+    // querying the contract fails with a clear error
+    let err: StdError = app
+        .wrap()
+        .query_wasm_smart::<Empty>(&contract_addr, &Empty {})
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("query is not implemented for contract"));
+}
@@ -0,0 +1,145 @@
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult,
+};
+use cw_multi_test::error::bail;
+use cw_multi_test::{next_block, App, Contract, ContractDecorators, ContractWrapper, Executor};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExecMsg {
+    Add { value: u64 },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum QueryMsg {
+    Total {},
+    Double {},
+}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+fn load_total(deps: Deps) -> u64 {
+    deps.storage
+        .get(b"total")
+        .map_or(0, |v| u64::from_be_bytes(v.try_into().unwrap()))
+}
+
+fn execute(deps: DepsMut, _: Env, _: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    let ExecMsg::Add { value } = msg;
+    let total = load_total(deps.as_ref()) + value;
+    deps.storage.set(b"total", &total.to_be_bytes());
+    Ok(Response::new().add_attribute("total", total.to_string()))
+}
+
+fn query(deps: Deps, _: Env, msg: QueryMsg) -> Result<Binary, StdError> {
+    match msg {
+        QueryMsg::Total {} => to_json_binary(&load_total(deps)),
+        QueryMsg::Double {} => to_json_binary(&(2 * load_total(deps))),
+    }
+}
+
+fn adder() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn setup(contract: Box<dyn Contract<Empty>>) -> (App, Addr, Addr) {
+    let mut app = App::default();
+    let code_id = app.store_code(contract);
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "adder", None)
+        .unwrap();
+    (app, owner_addr, contract_addr)
+}
+
+fn total(app: &App, contract_addr: &Addr) -> u64 {
+    app.wrap()
+        .query_wasm_smart(contract_addr, &QueryMsg::Total {})
+        .unwrap()
+}
+
+fn add(app: &mut App, owner_addr: &Addr, contract_addr: &Addr, value: u64) {
+    app.execute_contract(
+        owner_addr.clone(),
+        contract_addr.clone(),
+        &ExecMsg::Add { value },
+        &[],
+    )
+    .unwrap();
+}
+
+#[test]
+fn execute_override_should_replace_or_augment_matching_messages() {
+    let contract = adder()
+        .with_execute_override(&json!({ "add": { "value": 13 } }), |_, _, _, _, _| {
+            bail!("unlucky number")
+        })
+        .with_execute_override(
+            &json!({ "add": { "value": 2 } }),
+            |contract, mut deps, env, info, msg| {
+                // call the original contract twice
+                contract.execute(deps.branch(), env.clone(), info.clone(), msg.clone())?;
+                contract.execute(deps, env, info, msg)
+            },
+        );
+    let (mut app, owner_addr, contract_addr) = setup(contract);
+
+    add(&mut app, &owner_addr, &contract_addr, 5);
+    assert_eq!(5, total(&app, &contract_addr));
+    let err = app
+        .execute_contract(
+            owner_addr.clone(),
+            contract_addr.clone(),
+            &ExecMsg::Add { value: 13 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!("unlucky number", err.root_cause().to_string());
+    add(&mut app, &owner_addr, &contract_addr, 2);
+    assert_eq!(9, total(&app, &contract_addr));
+}
+
+#[test]
+fn query_override_should_replace_matching_queries() {
+    let contract = adder().with_query_override(&QueryMsg::Total {}, |_, _, _, _| {
+        Ok(to_json_binary(&100_u64)?)
+    });
+    let (mut app, owner_addr, contract_addr) = setup(contract);
+    add(&mut app, &owner_addr, &contract_addr, 5);
+    assert_eq!(100, total(&app, &contract_addr));
+    let double: u64 = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &QueryMsg::Double {})
+        .unwrap();
+    assert_eq!(10, double);
+}
+
+#[test]
+fn delaying_should_process_messages_later() {
+    let (mut app, owner_addr, contract_addr) = setup(adder().delaying(2));
+
+    add(&mut app, &owner_addr, &contract_addr, 1);
+    add(&mut app, &owner_addr, &contract_addr, 2);
+    assert_eq!(0, total(&app, &contract_addr));
+
+    // delayed messages are not due yet
+    app.update_block(next_block);
+    add(&mut app, &owner_addr, &contract_addr, 4);
+    assert_eq!(0, total(&app, &contract_addr));
+
+    // first two messages are due
+    app.update_block(next_block);
+    add(&mut app, &owner_addr, &contract_addr, 8);
+    assert_eq!(3, total(&app, &contract_addr));
+
+    // third message is due
+    app.update_block(next_block);
+    add(&mut app, &owner_addr, &contract_addr, 16);
+    assert_eq!(7, total(&app, &contract_addr));
+}
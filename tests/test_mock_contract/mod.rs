@@ -0,0 +1 @@
+mod test_canned_responses;
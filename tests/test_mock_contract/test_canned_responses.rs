@@ -0,0 +1,113 @@
+use cosmwasm_std::{Addr, Decimal, Empty, Event, Response};
+use cw_multi_test::{App, Executor, MockContract};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum OracleQueryMsg {
+    Price { denom: String },
+    Config {},
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum OracleExecuteMsg {
+    UpdatePrice { denom: String, price: Decimal },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct PriceResponse {
+    price: Decimal,
+}
+
+fn price(denom: &str) -> OracleQueryMsg {
+    OracleQueryMsg::Price {
+        denom: denom.to_string(),
+    }
+}
+
+fn setup(contract: MockContract) -> (App, Addr, Addr) {
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(contract));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "oracle", None)
+        .unwrap();
+    (app, owner_addr, contract_addr)
+}
+
+#[test]
+fn queries_should_return_canned_responses() {
+    let oracle = MockContract::new()
+        .on_query(
+            &price("uatom"),
+            &PriceResponse {
+                price: Decimal::percent(950),
+            },
+        )
+        .on_query_error(&any_price_pattern(), "unknown denom")
+        .on_query(&OracleQueryMsg::Config {}, &Empty {});
+    let (app, _, oracle_addr) = setup(oracle);
+
+    // the most specific pattern matches first
+    let response: PriceResponse = app
+        .wrap()
+        .query_wasm_smart(&oracle_addr, &price("uatom"))
+        .unwrap();
+    assert_eq!(Decimal::percent(950), response.price);
+
+    // the pattern without arguments matches any message of the same kind
+    let err = app
+        .wrap()
+        .query_wasm_smart::<PriceResponse>(&oracle_addr, &price("uosmo"))
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown denom"), "{}", err);
+
+    app.wrap()
+        .query_wasm_smart::<Empty>(&oracle_addr, &OracleQueryMsg::Config {})
+        .unwrap();
+}
+
+/// Pattern matching the `price` query for any denomination.
+fn any_price_pattern() -> impl Serialize {
+    #[derive(Serialize)]
+    struct AnyPrice {
+        price: Empty,
+    }
+    AnyPrice { price: Empty {} }
+}
+
+#[test]
+fn executions_should_return_canned_responses() {
+    let update = OracleExecuteMsg::UpdatePrice {
+        denom: "uatom".to_string(),
+        price: Decimal::one(),
+    };
+    let oracle = MockContract::new().on_execute(
+        &update,
+        Response::new().add_event(Event::new("price_updated").add_attribute("denom", "uatom")),
+    );
+    let (mut app, owner_addr, oracle_addr) = setup(oracle);
+
+    let response = app
+        .execute_contract(owner_addr.clone(), oracle_addr.clone(), &update, &[])
+        .unwrap();
+    assert!(response.has_event(&Event::new("wasm-price_updated").add_attribute("denom", "uatom")));
+
+    // messages not matching any pattern are rejected
+    let err = app
+        .execute_contract(
+            owner_addr,
+            oracle_addr,
+            &OracleExecuteMsg::UpdatePrice {
+                denom: "uosmo".to_string(),
+                price: Decimal::one(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .starts_with("no mock response for execute message"));
+}
@@ -0,0 +1 @@
+mod test_validated_messages;
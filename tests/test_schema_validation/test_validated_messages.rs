@@ -0,0 +1,146 @@
+use cosmwasm_std::{Addr, Response, Uint128};
+use cw_multi_test::{App, Executor, MockContract, SchemaValidated};
+use schemars::{schema_for, JsonSchema};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+#[allow(dead_code)]
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ExecuteMsg {
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+        memo: Option<String>,
+    },
+    Batch {
+        amounts: Vec<Uint128>,
+    },
+    Pause {},
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct InstantiateMsg {
+    admin: String,
+    limit: u32,
+}
+
+fn setup() -> (App, Addr, Addr) {
+    let contract = SchemaValidated::new(Box::new(
+        MockContract::new()
+            .on_instantiate(
+                &json!({ "admin": "admin", "limit": 10 }),
+                Response::default(),
+            )
+            .on_execute(
+                &json!({ "transfer": { "recipient": "bob", "amount": "100", "memo": null } }),
+                Response::default(),
+            ),
+    ))
+    .with_instantiate_schema(schema_for!(InstantiateMsg))
+    .with_execute_schema(schema_for!(ExecuteMsg));
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(contract));
+    let owner_addr = app.api().addr_make("owner");
+    let instantiate_msg = InstantiateMsg {
+        admin: "admin".to_string(),
+        limit: 10,
+    };
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &instantiate_msg,
+            &[],
+            "validated",
+            None,
+        )
+        .unwrap();
+    (app, owner_addr, contract_addr)
+}
+
+fn execute_err(msg: Value) -> String {
+    let (mut app, owner_addr, contract_addr) = setup();
+    app.execute_contract(owner_addr, contract_addr, &msg, &[])
+        .unwrap_err()
+        .root_cause()
+        .to_string()
+}
+
+#[test]
+fn valid_messages_should_be_dispatched() {
+    let (mut app, owner_addr, contract_addr) = setup();
+    let msg = ExecuteMsg::Transfer {
+        recipient: "bob".to_string(),
+        amount: Uint128::new(100),
+        memo: None,
+    };
+    app.execute_contract(owner_addr, contract_addr, &msg, &[])
+        .unwrap();
+}
+
+#[test]
+fn invalid_field_types_should_be_reported() {
+    assert_eq!(
+        "invalid execute message: field `transfer.amount` expected string, got number",
+        execute_err(json!({ "transfer": { "recipient": "bob", "amount": 100 } }))
+    );
+    assert_eq!(
+        "invalid execute message: field `transfer.memo` expected string or null, got boolean",
+        execute_err(json!({ "transfer": { "recipient": "bob", "amount": "1", "memo": true } }))
+    );
+    assert_eq!(
+        "invalid execute message: field `batch.amounts.1` expected string, got number",
+        execute_err(json!({ "batch": { "amounts": ["1", 2] } }))
+    );
+}
+
+#[test]
+fn missing_and_unknown_fields_should_be_reported() {
+    assert_eq!(
+        "invalid execute message: missing field `transfer.recipient`",
+        execute_err(json!({ "transfer": { "amount": "100" } }))
+    );
+    assert_eq!(
+        "invalid execute message: message does not match any variant, expected one of `transfer`, `batch`, `pause`",
+        execute_err(json!({ "withdraw": {} }))
+    );
+
+    let (mut app, owner_addr, contract_addr) = setup();
+    let code_id = app.contract_data(&contract_addr).unwrap().code_id;
+    let err = app
+        .instantiate_contract(
+            code_id,
+            owner_addr,
+            &json!({ "admin": "admin", "limit": 10, "extra": 1 }),
+            &[],
+            "validated",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        "invalid instantiate message: unknown field `extra`",
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn numeric_limits_should_be_reported() {
+    let (mut app, owner_addr, contract_addr) = setup();
+    let code_id = app.contract_data(&contract_addr).unwrap().code_id;
+    let err = app
+        .instantiate_contract(
+            code_id,
+            owner_addr,
+            &json!({ "admin": "admin", "limit": -1 }),
+            &[],
+            "validated",
+            None,
+        )
+        .unwrap_err();
+    assert_eq!(
+        "invalid instantiate message: field `limit` expected at least 0, got -1",
+        err.root_cause().to_string()
+    );
+}
@@ -0,0 +1,118 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw_multi_test::{App, ContractWrapper, Executor, Shadowed};
+use cw_storage_plus::Item;
+use serde::{Deserialize, Serialize};
+
+const COUNTER: Item<u64> = Item::new("counter");
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExecMsg {
+    Add(u64),
+}
+
+fn instantiate(deps: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+    COUNTER.save(deps.storage, &0)?;
+    Ok(Response::default())
+}
+
+fn execute_v1(deps: DepsMut, _: Env, _: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    let ExecMsg::Add(value) = msg;
+    let counter = COUNTER.load(deps.storage)? + value;
+    COUNTER.save(deps.storage, &counter)?;
+    Ok(Response::new().add_attribute("counter", counter.to_string()))
+}
+
+/// Refactored version, saturating the counter at 10 instead of failing on overflow.
+fn execute_v2(deps: DepsMut, _: Env, _: MessageInfo, msg: ExecMsg) -> StdResult<Response> {
+    let ExecMsg::Add(value) = msg;
+    let counter = (COUNTER.load(deps.storage)? + value).min(10);
+    COUNTER.save(deps.storage, &counter)?;
+    Ok(Response::new().add_attribute("counter", counter.to_string()))
+}
+
+fn query(deps: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+    to_json_binary(&COUNTER.load(deps.storage)?)
+}
+
+fn failing_execute(_: DepsMut, _: Env, _: MessageInfo, _: ExecMsg) -> StdResult<Response> {
+    Err(StdError::generic_err("not yet implemented"))
+}
+
+#[test]
+fn divergences_should_be_reported() {
+    let shadowed = Shadowed::new(
+        Box::new(ContractWrapper::new(execute_v1, instantiate, query)),
+        Box::new(ContractWrapper::new(execute_v2, instantiate, query)),
+    );
+    let log = shadowed.log();
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(shadowed));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+
+    // both implementations behave the same
+    app.execute_contract(
+        owner_addr.clone(),
+        contract_addr.clone(),
+        &ExecMsg::Add(5),
+        &[],
+    )
+    .unwrap();
+    let counter: u64 = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!(5, counter);
+    log.assert_no_divergences();
+
+    // implementations diverge, the result of the primary contract is used
+    app.execute_contract(owner_addr, contract_addr.clone(), &ExecMsg::Add(10), &[])
+        .unwrap();
+    let counter: u64 = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert_eq!(15, counter);
+    let divergences = log.divergences();
+    assert_eq!(1, divergences.len());
+    assert_eq!("execute", divergences[0].entry_point);
+    assert_eq!(contract_addr, divergences[0].contract_addr);
+    assert_eq!(2, divergences[0].differences.len());
+    assert!(divergences[0].differences[0].starts_with("responses differ"));
+    assert_eq!(
+        "storage differs at key `counter`: primary 15, shadow 10",
+        divergences[0].differences[1]
+    );
+
+    // the shadow contract is queried against the same state
+    log.clear();
+    let _: u64 = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &Empty {})
+        .unwrap();
+    assert!(log.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "only shadow failed: Generic error: not yet implemented")]
+fn failing_shadow_should_be_reported() {
+    let shadowed = Shadowed::new(
+        Box::new(ContractWrapper::new(execute_v1, instantiate, query)),
+        Box::new(ContractWrapper::new(failing_execute, instantiate, query)),
+    );
+    let log = shadowed.log();
+    let mut app = App::default();
+    let code_id = app.store_code(Box::new(shadowed));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "counter", None)
+        .unwrap();
+    app.execute_contract(owner_addr, contract_addr, &ExecMsg::Add(1), &[])
+        .unwrap();
+    log.assert_no_divergences();
+}
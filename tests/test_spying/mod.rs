@@ -0,0 +1 @@
+mod test_spied_calls;
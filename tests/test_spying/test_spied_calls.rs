@@ -0,0 +1,94 @@
+use cosmwasm_std::{
+    coin, to_json_binary, to_json_vec, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo,
+    Response, StdError, WasmMsg,
+};
+use cw_multi_test::{App, BankSudo, ContractWrapper, Executor, MockContract, Spying};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum OracleMsg {
+    SetPrice { price: u64 },
+}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> Result<Response, StdError> {
+    Ok(Response::default())
+}
+
+/// Forwards the price and all received funds to the oracle.
+fn execute(_: DepsMut, _: Env, info: MessageInfo, oracle: String) -> Result<Response, StdError> {
+    Ok(Response::new().add_message(WasmMsg::Execute {
+        contract_addr: oracle,
+        msg: to_json_binary(&OracleMsg::SetPrice { price: 42 })?,
+        funds: info.funds,
+    }))
+}
+
+fn query(_: Deps, _: Env, _: Empty) -> Result<Binary, StdError> {
+    Ok(Binary::default())
+}
+
+#[test]
+fn spied_calls_should_be_recorded() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    app.sudo(
+        BankSudo::Mint {
+            to_address: owner_addr.to_string(),
+            amount: vec![coin(100, "uatom")],
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // wrap the oracle into the spying contract
+    let oracle = Spying::new(Box::new(
+        MockContract::new()
+            .on_execute(&OracleMsg::SetPrice { price: 42 }, Response::default())
+            .on_query(&Empty {}, &42_u64),
+    ));
+    let oracle_log = oracle.log();
+    let oracle_code_id = app.store_code(Box::new(oracle));
+    let proxy_code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    let instantiate = |app: &mut App, code_id: u64, label: &str| -> Addr {
+        app.instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], label, None)
+            .unwrap()
+    };
+    let oracle_addr = instantiate(&mut app, oracle_code_id, "oracle");
+    let proxy_addr = instantiate(&mut app, proxy_code_id, "proxy");
+
+    // instantiation is recorded
+    let calls = oracle_log.calls();
+    assert_eq!(1, calls.len());
+    assert_eq!("instantiate", calls[0].entry_point);
+    assert_eq!(Some(owner_addr.clone()), calls[0].sender);
+    oracle_log.clear();
+
+    // execute the proxy, that forwards the message to the oracle
+    app.execute_contract(
+        owner_addr,
+        proxy_addr.clone(),
+        &oracle_addr,
+        &[coin(10, "uatom")],
+    )
+    .unwrap();
+    let _: u64 = app
+        .wrap()
+        .query_wasm_smart(&oracle_addr, &Empty {})
+        .unwrap();
+
+    let calls = oracle_log.calls_to("execute");
+    assert_eq!(1, calls.len());
+    assert_eq!(oracle_addr, calls[0].contract_addr);
+    assert_eq!(Some(proxy_addr), calls[0].sender);
+    assert_eq!(vec![coin(10, "uatom")], calls[0].funds);
+    assert_eq!(
+        to_json_vec(&OracleMsg::SetPrice { price: 42 }).unwrap(),
+        calls[0].msg.to_vec()
+    );
+
+    let calls = oracle_log.calls_to("query");
+    assert_eq!(1, calls.len());
+    assert_eq!(None, calls[0].sender);
+    assert_eq!(2, oracle_log.calls().len());
+}
@@ -1,2 +1,7 @@
+mod test_catch_panics;
+mod test_error_payload;
+mod test_ibc_port;
+mod test_tracing_spans;
+mod test_wasm_contract;
 mod test_with_addr_gen;
 mod test_with_checksum_gen;
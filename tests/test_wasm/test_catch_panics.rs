@@ -0,0 +1,76 @@
+use cosmwasm_std::{Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError};
+use cw_multi_test::error::Error;
+use cw_multi_test::{no_init, App, AppBuilder, ContractWrapper, Executor, WasmKeeper};
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> Result<Response, StdError> {
+    Ok(Response::default())
+}
+
+fn execute(_: DepsMut, _: Env, _: MessageInfo, amount: u64) -> Result<Response, StdError> {
+    panic!("attempt to spend {} tokens", amount)
+}
+
+fn query(_: Deps, _: Env, _: Empty) -> Result<Binary, StdError> {
+    panic!("query is not implemented")
+}
+
+fn setup(app: &mut App) -> (Addr, Addr) {
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    let owner_addr = app.api().addr_make("owner");
+    let contract_addr = app
+        .instantiate_contract(
+            code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "panicking",
+            None,
+        )
+        .unwrap();
+    (owner_addr, contract_addr)
+}
+
+#[test]
+fn panics_should_be_converted_into_errors() {
+    let mut app = AppBuilder::default()
+        .with_wasm(WasmKeeper::new().with_catch_panics(true))
+        .build(no_init);
+    let (owner_addr, contract_addr) = setup(&mut app);
+
+    // panic in execute entry-point is reported as an error
+    let err = app
+        .execute_contract(owner_addr, contract_addr.clone(), &10_u64, &[])
+        .unwrap_err();
+    let Some(Error::ContractPanicked(address, message, backtrace)) =
+        err.root_cause().downcast_ref::<Error>()
+    else {
+        panic!("unexpected error: {:?}", err);
+    };
+    assert_eq!(contract_addr.as_str(), address);
+    assert_eq!("attempt to spend 10 tokens", message);
+
+    // the backtrace points at the panicking entry-point
+    assert!(
+        backtrace.contains("test_catch_panics::execute"),
+        "{}",
+        backtrace
+    );
+
+    // panic in query entry-point is reported as an error
+    let err = app
+        .wrap()
+        .query_wasm_smart::<Empty>(contract_addr.as_str(), &Empty {})
+        .unwrap_err();
+    assert!(err.to_string().contains(&format!(
+        "contract {} panicked: query is not implemented",
+        contract_addr
+    )));
+}
+
+#[test]
+#[should_panic(expected = "attempt to spend 10 tokens")]
+fn panics_should_not_be_caught_by_default() {
+    let mut app = App::default();
+    let (owner_addr, contract_addr) = setup(&mut app);
+    let _ = app.execute_contract(owner_addr, contract_addr, &10_u64, &[]);
+}
@@ -0,0 +1,131 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, WasmMsg,
+};
+use cw_multi_test::error::{JsonError, JsonErrorExt};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Shortfall {
+    denom: String,
+    missing: u128,
+}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> Result<Response, StdError> {
+    Ok(Response::default())
+}
+
+fn query(_: Deps, _: Env, _: Empty) -> Result<Binary, StdError> {
+    Ok(Binary::default())
+}
+
+/// Always fails with the error carrying the payload.
+fn failing_execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> Result<Response, JsonError> {
+    Err(JsonError::new(
+        "insufficient funds",
+        &Shortfall {
+            denom: "uatom".to_string(),
+            missing: 100,
+        },
+    ))
+}
+
+/// Forwards the execution to the contract specified in the message.
+fn proxy_execute(_: DepsMut, _: Env, _: MessageInfo, target: String) -> Result<Response, StdError> {
+    Ok(Response::new().add_message(WasmMsg::Execute {
+        contract_addr: target,
+        msg: to_json_binary(&Empty {})?,
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn error_payload_should_be_retrievable() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(Box::new(ContractWrapper::new(
+        failing_execute,
+        instantiate,
+        query,
+    )));
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "failing", None)
+        .unwrap();
+
+    let err = app
+        .execute_contract(owner_addr, contract_addr, &Empty {}, &[])
+        .unwrap_err();
+    assert_eq!("insufficient funds", err.root_cause().to_string());
+    assert_eq!(
+        Shortfall {
+            denom: "uatom".to_string(),
+            missing: 100
+        },
+        err.json::<Shortfall>().unwrap()
+    );
+}
+
+#[test]
+fn error_payload_should_survive_nested_calls() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let failing_code_id = app.store_code(Box::new(ContractWrapper::new(
+        failing_execute,
+        instantiate,
+        query,
+    )));
+    let proxy_code_id = app.store_code(Box::new(ContractWrapper::new(
+        proxy_execute,
+        instantiate,
+        query,
+    )));
+    let failing_addr = app
+        .instantiate_contract(
+            failing_code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "failing",
+            None,
+        )
+        .unwrap();
+    let proxy_addr = app
+        .instantiate_contract(
+            proxy_code_id,
+            owner_addr.clone(),
+            &Empty {},
+            &[],
+            "proxy",
+            None,
+        )
+        .unwrap();
+
+    let err = app
+        .execute_contract(owner_addr, proxy_addr, &failing_addr.to_string(), &[])
+        .unwrap_err();
+    assert_eq!(100, err.json::<Shortfall>().unwrap().missing);
+}
+
+#[test]
+fn errors_without_payload_should_be_reported() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+    let code_id = app.store_code(Box::new(ContractWrapper::new(
+        proxy_execute,
+        instantiate,
+        query,
+    )));
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "proxy", None)
+        .unwrap();
+
+    // the target contract does not exist
+    let err = app
+        .execute_contract(owner_addr, contract_addr, &"unknown", &[])
+        .unwrap_err();
+    assert!(err
+        .json::<Shortfall>()
+        .unwrap_err()
+        .to_string()
+        .starts_with("error has no JSON payload"));
+}
@@ -0,0 +1,122 @@
+use crate::test_contracts;
+use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, Response};
+use cw_multi_test::error::{AnyResult, Error};
+use cw_multi_test::{App, Contract, Executor};
+
+/// Contract exposing IBC entry-points, delegates all calls to the counter contract.
+struct IbcCounter(Box<dyn Contract<Empty>>);
+
+impl Contract<Empty> for IbcCounter {
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response> {
+        self.0.execute(deps, env, info, msg)
+    }
+
+    fn instantiate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Vec<u8>,
+    ) -> AnyResult<Response> {
+        self.0.instantiate(deps, env, info, msg)
+    }
+
+    fn query(&self, deps: Deps, env: Env, msg: Vec<u8>) -> AnyResult<Binary> {
+        self.0.query(deps, env, msg)
+    }
+
+    fn sudo(&self, deps: DepsMut, env: Env, msg: Vec<u8>) -> AnyResult<Response> {
+        self.0.sudo(deps, env, msg)
+    }
+
+    fn reply(&self, deps: DepsMut, env: Env, msg: Reply) -> AnyResult<Response> {
+        self.0.reply(deps, env, msg)
+    }
+
+    fn migrate(&self, deps: DepsMut, env: Env, msg: Vec<u8>) -> AnyResult<Response> {
+        self.0.migrate(deps, env, msg)
+    }
+
+    fn ibc_enabled(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn ibc_enabled_contract_should_have_port_bound() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+
+    let code_id = app.store_code(Box::new(IbcCounter(test_contracts::counter::contract())));
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr, &Empty {}, &[], "ibc-counter", None)
+        .unwrap();
+
+    let port_id = format!("wasm.{}", contract_addr);
+
+    // port identifier should be reported in contract info
+    let contract_info = app
+        .wrap()
+        .query_wasm_contract_info(contract_addr.clone())
+        .unwrap();
+    assert_eq!(Some(port_id.clone()), contract_info.ibc_port);
+
+    // port identifier should be bound to the contract
+    assert_eq!(
+        Some(port_id.clone()),
+        app.ibc_port_id(&contract_addr).unwrap()
+    );
+    assert_eq!(
+        contract_addr,
+        app.contract_by_ibc_port_id(&port_id).unwrap()
+    );
+}
+
+#[test]
+fn regular_contract_should_have_no_port_bound() {
+    let mut app = App::default();
+    let owner_addr = app.api().addr_make("owner");
+
+    let code_id = app.store_code(test_contracts::counter::contract());
+    let contract_addr = app
+        .instantiate_contract(code_id, owner_addr, &Empty {}, &[], "counter", None)
+        .unwrap();
+
+    let contract_info = app
+        .wrap()
+        .query_wasm_contract_info(contract_addr.clone())
+        .unwrap();
+    assert_eq!(None, contract_info.ibc_port);
+    assert_eq!(None, app.ibc_port_id(&contract_addr).unwrap());
+
+    // contract without IBC entry-points can not be resolved by port identifier
+    let port_id = format!("wasm.{}", contract_addr);
+    assert_eq!(
+        Error::unknown_ibc_port(&port_id),
+        app.contract_by_ibc_port_id(&port_id)
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    );
+    let port_id = format!("wasm.{}", app.api().addr_make("unknown"));
+    assert_eq!(
+        Error::unknown_ibc_port(&port_id),
+        app.contract_by_ibc_port_id(&port_id)
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    );
+    assert_eq!(
+        Error::unknown_ibc_port("transfer"),
+        app.contract_by_ibc_port_id("transfer")
+            .unwrap_err()
+            .downcast()
+            .unwrap()
+    );
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "tracing")]
+
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+};
+use cw_multi_test::{App, ContractWrapper, Executor};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Spans recorded by the subscriber, as lists of field values.
+type Spans = Arc<Mutex<Vec<Vec<(String, String)>>>>;
+
+/// Minimal subscriber recording all spans with their fields.
+struct RecordingSubscriber(Spans);
+
+struct FieldsVisitor<'a>(&'a mut Vec<(String, String)>);
+
+impl Visit for FieldsVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .push((field.name().to_string(), format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name().to_string(), value.to_string()));
+    }
+}
+
+impl Subscriber for RecordingSubscriber {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.0.lock().unwrap();
+        let mut fields = vec![("name".to_string(), span.metadata().name().to_string())];
+        span.record(&mut FieldsVisitor(&mut fields));
+        spans.push(fields);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.0.lock().unwrap();
+        let fields = &mut spans[span.into_u64() as usize - 1];
+        values.record(&mut FieldsVisitor(fields));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> Result<Response, StdError> {
+    Ok(Response::default())
+}
+
+fn execute(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> Result<Response, StdError> {
+    Err(StdError::generic_err("not allowed"))
+}
+
+fn query(_: Deps, _: Env, _: Empty) -> Result<Binary, StdError> {
+    to_json_binary(&Empty {})
+}
+
+fn field<'a>(span: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    span.iter()
+        .find(|(field, _)| field == name)
+        .map(|(_, value)| value.as_str())
+}
+
+#[test]
+fn entry_point_calls_should_be_traced() {
+    let spans = Spans::default();
+    let subscriber = RecordingSubscriber(Arc::clone(&spans));
+    let contract_addr = tracing::subscriber::with_default(subscriber, || {
+        let mut app = App::default();
+        let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+        let owner_addr = app.api().addr_make("owner");
+        let contract_addr = app
+            .instantiate_contract(code_id, owner_addr.clone(), &Empty {}, &[], "traced", None)
+            .unwrap();
+        app.execute_contract(owner_addr, contract_addr.clone(), &Empty {}, &[])
+            .unwrap_err();
+        let _: Empty = app
+            .wrap()
+            .query_wasm_smart(&contract_addr, &Empty {})
+            .unwrap();
+        contract_addr
+    });
+
+    let spans = spans.lock().unwrap();
+    let spans = spans
+        .iter()
+        .filter(|span| field(span, "name") == Some("entry_point"))
+        .collect::<Vec<_>>();
+    let entry_points = spans
+        .iter()
+        .map(|span| field(span, "entry_point").unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["instantiate", "execute", "query"], entry_points);
+    for span in &spans {
+        assert_eq!(Some(contract_addr.as_str()), field(span, "contract"));
+        assert_eq!(Some("2"), field(span, "msg_size"));
+    }
+    assert_eq!(Some("ok"), field(spans[0], "result"));
+    assert_eq!(
+        Some("Generic error: not allowed"),
+        field(spans[1], "result")
+    );
+}
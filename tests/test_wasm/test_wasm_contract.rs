@@ -0,0 +1,112 @@
+#![cfg(feature = "wasm_vm")]
+
+use cosmwasm_std::{coins, Addr};
+use cw_multi_test::{App, BankSudo, Executor, WasmContract};
+use serde::{Deserialize, Serialize};
+
+/// Compiled `hackatom` contract from CosmWasm test data.
+const HACKATOM: &str = "tests/testdata/hackatom.wasm";
+
+#[derive(Serialize)]
+struct InstantiateMsg {
+    verifier: String,
+    beneficiary: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ExecuteMsg {
+    Release {},
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum QueryMsg {
+    Verifier {},
+}
+
+#[derive(Deserialize)]
+struct VerifierResponse {
+    verifier: String,
+}
+
+fn instantiate_hackatom(app: &mut App, verifier: &Addr, beneficiary: &Addr) -> Addr {
+    let code_id = app.store_code(Box::new(WasmContract::from_file(HACKATOM).unwrap()));
+    app.instantiate_contract(
+        code_id,
+        verifier.clone(),
+        &InstantiateMsg {
+            verifier: verifier.to_string(),
+            beneficiary: beneficiary.to_string(),
+        },
+        &[],
+        "hackatom",
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn compiled_contract_should_work() {
+    let mut app = App::default();
+    let verifier_addr = app.api().addr_make("verifier");
+    let beneficiary_addr = app.api().addr_make("beneficiary");
+
+    let contract_addr = instantiate_hackatom(&mut app, &verifier_addr, &beneficiary_addr);
+    app.sudo(
+        BankSudo::Mint {
+            to_address: contract_addr.to_string(),
+            amount: coins(100, "uatom"),
+        }
+        .into(),
+    )
+    .unwrap();
+
+    // state written during instantiation should be visible in queries
+    let response: VerifierResponse = app
+        .wrap()
+        .query_wasm_smart(&contract_addr, &QueryMsg::Verifier {})
+        .unwrap();
+    assert_eq!(verifier_addr.as_str(), response.verifier);
+
+    // releasing funds queries the contract's balance and sends it to the beneficiary
+    app.execute_contract(
+        verifier_addr,
+        contract_addr.clone(),
+        &ExecuteMsg::Release {},
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        coins(100, "uatom"),
+        app.wrap().query_all_balances(&beneficiary_addr).unwrap()
+    );
+    assert!(app
+        .wrap()
+        .query_all_balances(&contract_addr)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn contract_errors_should_be_reported() {
+    let mut app = App::default();
+    let verifier_addr = app.api().addr_make("verifier");
+    let beneficiary_addr = app.api().addr_make("beneficiary");
+
+    let contract_addr = instantiate_hackatom(&mut app, &verifier_addr, &beneficiary_addr);
+
+    // only verifier can release funds
+    let err = app
+        .execute_contract(
+            beneficiary_addr,
+            contract_addr.clone(),
+            &ExecuteMsg::Release {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!("Unauthorized", err.root_cause().to_string());
+
+    // state written by the contract should be kept in the contract's storage
+    assert!(!app.dump_wasm_raw(&contract_addr).is_empty());
+}